@@ -0,0 +1,115 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, FnArg, GenericArgument, ItemFn, Pat, PathArguments, ReturnType, Signature, Type};
+
+/// Declares a Tauri command whose calls are timed: wraps the body in a `CommandSpan` from
+/// `utils::command_timing`, recording the duration, the result and the size of its text and list
+/// parameters. Parameter contents are never recorded. Only async commands are supported.
+#[proc_macro_attribute]
+pub fn command(_attributes: TokenStream, item: TokenStream) -> TokenStream {
+    let function = parse_macro_input!(item as ItemFn);
+
+    if function.sig.asyncness.is_none() {
+        return syn::Error::new_spanned(&function.sig, "timed commands must be async")
+            .to_compile_error()
+            .into();
+    }
+
+    let ItemFn { attrs, vis, sig, block } = function;
+    let name = sig.ident.to_string();
+    let output = match &sig.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => quote!(#ty),
+    };
+
+    let sizes = sizes(&sig);
+
+    quote!(
+        #(#attrs)*
+        #[::tauri::command]
+        #vis #sig {
+            let span = crate::utils::command_timing::CommandSpan::enter(#name, ::std::vec![#(#sizes),*]);
+            #[allow(clippy::let_unit_value)]
+            let result: #output = async move #block.await;
+            span.exit(&result);
+            result
+        }
+    ).into()
+}
+
+/// The name and length of every text and list parameter, the only things the span gets of them.
+fn sizes(sig: &Signature) -> Vec<TokenStream2> {
+    sig.inputs.iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(typed) => Some(typed),
+            FnArg::Receiver(_) => None,
+        })
+        .filter_map(|typed| match (&*typed.pat, measured(&typed.ty)) {
+            (Pat::Ident(pat), Some(optional)) => {
+                let ident = &pat.ident;
+                let label = ident.to_string();
+                Some(match optional {
+                    false => quote!((#label, ::std::option::Option::Some(#ident.len()))),
+                    true => quote!((#label, #ident.as_ref().map(|value| value.len()))),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether a parameter has a length worth recording: `Some(false)` for strings and lists,
+/// `Some(true)` when they are optional.
+fn measured(ty: &Type) -> Option<bool> {
+    let Type::Path(path) = ty else { return None };
+    let segment = path.path.segments.last()?;
+
+    match segment.ident.to_string().as_str() {
+        "String" | "Vec" => Some(false),
+        "Option" => {
+            let PathArguments::AngleBracketed(arguments) = &segment.arguments else { return None };
+            match arguments.args.first()? {
+                GenericArgument::Type(inner) if measured(inner) == Some(false) => Some(true),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+    use super::*;
+
+    #[test]
+    fn passes_only_the_lengths_of_the_parameters() {
+        let sig: Signature = parse_quote!(
+            async fn sign_in(username: String, password: Option<String>, tags: Vec<String>, attempt: i64, app_handle: AppHandle) -> Result<(), String>
+        );
+
+        let sizes: Vec<String> = sizes(&sig).iter().map(ToString::to_string).collect();
+        assert_eq!(sizes, [
+            quote!(("username", ::std::option::Option::Some(username.len()))).to_string(),
+            quote!(("password", password.as_ref().map(|value| value.len()))).to_string(),
+            quote!(("tags", ::std::option::Option::Some(tags.len()))).to_string(),
+        ]);
+    }
+
+    #[test]
+    fn measured_parameters() {
+        let cases: [(Type, Option<bool>); 6] = [
+            (parse_quote!(String), Some(false)),
+            (parse_quote!(Vec<FeedCookie>), Some(false)),
+            (parse_quote!(Option<String>), Some(true)),
+            (parse_quote!(Option<i64>), None),
+            (parse_quote!(Option<Option<String>>), None),
+            (parse_quote!(AppHandle), None),
+        ];
+
+        for (ty, expected) in cases {
+            assert_eq!(measured(&ty), expected, "{}", quote!(#ty));
+        }
+    }
+}
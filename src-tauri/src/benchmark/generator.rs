@@ -0,0 +1,178 @@
+use std::ops::Range;
+use std::path::Path;
+use sqlx::{Pool, Sqlite};
+use sqlx::query::Query;
+use sqlx::sqlite::{SqliteArguments, SqlitePoolOptions};
+use crate::benchmark::random::SeededRandom;
+use crate::database::{connect_options, migrator, READ_CONNECTIONS};
+use crate::enums::benchmark_profile::BenchmarkProfile;
+use crate::utils::dedup::dedup_key;
+
+pub const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+/// Distinct words of the generated text.
+pub const VOCABULARY_SIZE: u64 = 5_000;
+/// Articles older than this are read, like after catching up on everything.
+const READ_AFTER_DAYS: i64 = 14;
+const QUEUED_ARTICLES: usize = 30;
+/// Articles inserted per transaction.
+const BATCH_SIZE: usize = 1_000;
+const SYLLABLES: [&str; 16] = ["ka", "lo", "mi", "ne", "ru", "sa", "ti", "vo", "ba", "de", "fi", "go", "hu", "ja", "pe", "zu"];
+
+/// Creates a database at `path` with every migration applied, like at startup with the real one.
+pub async fn create_database(path: &Path) -> Result<Pool<Sqlite>, String> {
+    let options = connect_options(path, false)
+        .create_if_missing(true);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options).await
+        .map_err(|e| format!("Error creating the benchmark database: {}", e))?;
+
+    migrator::migrator().await?
+        .run(&pool).await
+        .map_err(|e| format!("Error applying the migrations: {}", e))?;
+
+    Ok(pool)
+}
+
+/// Read-only connections to the database at `path`, like the ones of the query commands.
+pub async fn open_read_pool(path: &Path) -> Result<Pool<Sqlite>, String> {
+    SqlitePoolOptions::new()
+        .max_connections(READ_CONNECTIONS)
+        .connect_with(connect_options(path, true)).await
+        .map_err(|e| format!("Error opening the benchmark database: {}", e))
+}
+
+/// Fills the database with the feeds, articles, read state and reading queue of `profile`.
+/// The same seed generates the same rows, dated relative to `now`.
+pub async fn generate(profile: BenchmarkProfile, seed: u32, now: i64, pool: &Pool<Sqlite>) -> Result<(), String> {
+    let mut random = SeededRandom::new(seed);
+
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+    for feed in 0..profile.feeds() {
+        let priority = match random.below(10) {
+            0 => "high",
+            1 => "low",
+            _ => "normal",
+        };
+
+        sqlx::query(include_str!("../database/queries/insert_feed.sql"))
+            .bind(feed_identifier(feed))
+            .bind(title(&mut random, 1, 3))
+            .bind(format!("https://feed-{}.example.com/rss", feed))
+            .bind(if random.chance(0.3) { "atom" } else { "rss" })
+            .bind(random.between(15, 240) as i64)
+            .bind(priority)
+            .execute(&mut *transaction).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+    }
+    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))?;
+
+    let history_ms = profile.history_days() as i64 * DAY_MS;
+    for batch_start in (0..profile.articles()).step_by(BATCH_SIZE) {
+        let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+        for identifier in batch_start..(batch_start + BATCH_SIZE).min(profile.articles()) {
+            let feed = random.skewed(profile.feeds() as u64) as usize;
+            let date = now - random.below(history_ms as u64) as i64;
+
+            article_query(&mut random, feed, identifier as i64, date)
+                .execute(&mut *transaction).await
+                .map_err(|e| format!("Error executing query: {}", e))?;
+        }
+        transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))?;
+    }
+
+    sqlx::query(include_str!("../database/queries/mark_articles_read_before.sql"))
+        .bind(now - READ_AFTER_DAYS * DAY_MS)
+        .execute(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    for _ in 0..QUEUED_ARTICLES {
+        sqlx::query(include_str!("../database/queries/queue_article.sql"))
+            .bind(random.below(profile.articles() as u64).to_string())
+            .execute(pool).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// The insert sync runs for a fetched article, with generated text.
+pub fn article_query(random: &mut SeededRandom, feed: usize, identifier: i64, date: i64) -> Query<'static, Sqlite, SqliteArguments<'static>> {
+    let title = title(random, 4, 12);
+    let link = format!("https://feed-{}.example.com/articles/{}", feed, identifier);
+    let key = dedup_key(Some(&link), &title);
+    let words = 30 + (random.exponential(250.0) as usize).min(6_000);
+
+    sqlx::query(include_str!("../database/queries/insert_article.sql"))
+        .bind(identifier)
+        .bind(feed_identifier(feed))
+        .bind(title)
+        .bind(content(random, words))
+        .bind(date)
+        .bind(random.chance(0.6).then(|| format!("https://feed-{}.example.com/images/{}.jpg", feed, identifier)))
+        .bind(random.chance(0.1).then(|| format!("{}/comments", link)))
+        .bind(link)
+        .bind(0)
+        .bind(key)
+        .bind("[]")
+        .bind("{}")
+}
+
+/// An RSS document of `feed` with the `items` of the run `tag`, newest first. An item keeps its GUID and link
+/// across documents, its text being generated again as if the feed had edited it.
+pub fn feed_document(random: &mut SeededRandom, feed: usize, tag: &str, items: Range<usize>, now: i64) -> String {
+    let mut document = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\"><channel><title>{}</title><link>https://feed-{}.example.com/</link>",
+        title(random, 1, 3), feed,
+    );
+    for item in items.rev() {
+        let words = 30 + (random.exponential(250.0) as usize).min(6_000);
+        let date = chrono::DateTime::from_timestamp_millis(now - item as i64 * 60_000).unwrap_or_default();
+        document.push_str(&format!(
+            "<item><guid>{tag}-{feed}-{item}</guid><title>{}</title><link>https://feed-{feed}.example.com/{tag}/{item}</link><pubDate>{}</pubDate><description><![CDATA[{}]]></description></item>",
+            title(random, 4, 12), date.to_rfc2822(), content(random, words),
+        ));
+    }
+    document.push_str("</channel></rss>");
+    document
+}
+
+pub fn feed_identifier(feed: usize) -> String {
+    format!("benchmark-feed-{}", feed)
+}
+
+/// A word of the vocabulary, every index giving a different one.
+pub fn word(index: u64) -> String {
+    let mut word = String::new();
+    // Starting past the single syllable ones, which would be too short to be words
+    let mut rest = index + SYLLABLES.len() as u64;
+    while rest > 0 {
+        word.push_str(SYLLABLES[(rest % SYLLABLES.len() as u64) as usize]);
+        rest /= SYLLABLES.len() as u64;
+    }
+    word
+}
+
+fn title(random: &mut SeededRandom, min_words: u64, max_words: u64) -> String {
+    let words = random.between(min_words, max_words);
+    (0..words).map(|_| word(random.zipf(VOCABULARY_SIZE))).collect::<Vec<_>>().join(" ")
+}
+
+/// HTML paragraphs of words picked as often as in real text.
+fn content(random: &mut SeededRandom, words: usize) -> String {
+    let mut content = String::with_capacity(words * 8);
+    let mut remaining = words;
+    while remaining > 0 {
+        let paragraph = (random.between(40, 120) as usize).min(remaining);
+        content.push_str("<p>");
+        for position in 0..paragraph {
+            if position > 0 {
+                content.push(' ');
+            }
+            content.push_str(&word(random.zipf(VOCABULARY_SIZE)));
+        }
+        content.push_str(".</p>");
+        remaining -= paragraph;
+    }
+    content
+}
@@ -0,0 +1,3 @@
+pub mod random;
+pub mod generator;
+pub mod suites;
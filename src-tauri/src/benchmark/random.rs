@@ -0,0 +1,49 @@
+/// SplitMix64, so a seed generates the same profile on every platform and version.
+pub struct SeededRandom(u64);
+
+impl SeededRandom {
+    pub fn new(seed: u32) -> Self {
+        SeededRandom(seed as u64)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut value = self.0;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^ (value >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in `[0, bound)`.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        (self.unit() * bound as f64) as u64
+    }
+
+    pub fn between(&mut self, min: u64, max: u64) -> u64 {
+        min + self.below(max - min + 1)
+    }
+
+    pub fn chance(&mut self, probability: f64) -> bool {
+        self.unit() < probability
+    }
+
+    /// In `[0, bound)`, low values more likely but none dominating, like the article counts of feeds.
+    pub fn skewed(&mut self, bound: u64) -> u64 {
+        (self.unit() * self.unit() * bound as f64) as u64
+    }
+
+    /// In `[0, bound)` following Zipf's law, like how often words are used.
+    pub fn zipf(&mut self, bound: u64) -> u64 {
+        ((bound as f64).powf(self.unit()) as u64 - 1).min(bound - 1)
+    }
+
+    /// Mostly small with a long tail, like article lengths.
+    pub fn exponential(&mut self, mean: f64) -> f64 {
+        -(1.0 - self.unit()).ln() * mean
+    }
+}
@@ -0,0 +1,410 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use sqlx::query::Query;
+use sqlx::sqlite::SqliteArguments;
+use sqlx::{Pool, Sqlite};
+use crate::benchmark::generator::{article_query, feed_document, feed_identifier, word, DAY_MS, VOCABULARY_SIZE};
+use crate::benchmark::random::SeededRandom;
+use crate::commands::articles::load_articles;
+use crate::commands::dedup::known_articles;
+use crate::commands::fetcher::{prepare_article, SYNC_BATCH_ARTICLES};
+use crate::commands::search::{search_page, translate, DEFAULT_SEARCH_LIMIT};
+use crate::commands::timeline::{load_jump, load_timeline};
+use crate::database::article_writer::{complete_feed, insert_article};
+use crate::enums::feed_write::FeedWrite;
+use crate::parsers::feed_content::parse_feed;
+use crate::enums::article_sort::ArticleSort;
+use crate::enums::benchmark_profile::BenchmarkProfile;
+use crate::enums::benchmark_suite::BenchmarkSuite;
+use crate::enums::page_direction::PageDirection;
+use crate::enums::read_state::ReadState;
+use crate::structs::article_cursor::ArticleCursor;
+use crate::structs::article_filter::ArticleFilter;
+use crate::structs::article::Article;
+use crate::structs::benchmark_result::BenchmarkResult;
+use crate::structs::feed_completion::FeedCompletion;
+use crate::structs::feed_validators::FeedValidators;
+use crate::structs::sync_writer::SyncWriter;
+use crate::structs::write_gate::WriteGate;
+use crate::utils::dedup::dedup_key;
+use crate::utils::title::display_title;
+
+/// Runs of each query, enough for stable percentiles.
+const REPEATS: usize = 10;
+const PAGE_SIZE: i64 = 100;
+/// Pages walked per sort, about what scrolling through a busy week loads.
+const PAGES: usize = 20;
+const SYNC_BATCHES: usize = 10;
+/// Articles per simulated fetch, half of them already stored as in most real fetches.
+const SYNC_BATCH_SIZE: usize = 50;
+/// Read articles older than this are what a purge would delete.
+const PURGE_AFTER_DAYS: i64 = 30;
+/// Dates the All Articles view jumps to, spread over the history.
+const TIMELINE_JUMPS: u64 = 8;
+/// Pages scrolled each way from a jump.
+const TIMELINE_PAGES: usize = 5;
+/// Articles stored by the sync the timeline is read during, about a full pass after a day offline.
+const CONTENTION_ARTICLES: usize = 5_000;
+const SYNC_PASSES: usize = 5;
+const PASS_FEEDS: usize = 40;
+/// Items of each fetched document, half of them delivered by the previous pass already.
+const DOCUMENT_ITEMS: usize = 50;
+/// Feeds synced at the same time, the default sync concurrency.
+const PASS_WORKERS: usize = 4;
+
+/// Fetched documents, feed identifier first, left for the workers of a pass.
+type DocumentQueue = Arc<Mutex<VecDeque<(String, String)>>>;
+
+pub async fn run(suite: BenchmarkSuite, profile: BenchmarkProfile, random: &mut SeededRandom, now: i64, pool: &Pool<Sqlite>, read_pool: &Pool<Sqlite>) -> Result<BenchmarkResult, String> {
+    match suite {
+        BenchmarkSuite::Pagination => pagination(pool).await,
+        BenchmarkSuite::UnreadCounts => unread_counts(pool).await,
+        BenchmarkSuite::Search => search(pool).await,
+        BenchmarkSuite::SyncUpsert => sync_upsert(profile, random, now, pool).await,
+        BenchmarkSuite::PurgePreview => purge_preview(now, pool).await,
+        BenchmarkSuite::Timeline => timeline(profile, now, pool).await,
+        BenchmarkSuite::ReadsDuringSync => reads_during_sync(profile, random, now, pool, read_pool).await,
+        BenchmarkSuite::SyncSequential | BenchmarkSuite::SyncPipelined => sync_pass(suite, profile, random, now, pool).await,
+    }
+}
+
+/// Jumps to dates across the history then scrolls both ways, in both sorts and with only the unread articles,
+/// most of which are recent. Each jump and page is a sample.
+async fn timeline(profile: BenchmarkProfile, now: i64, pool: &Pool<Sqlite>) -> Result<BenchmarkResult, String> {
+    let history_ms = profile.history_days() as i64 * DAY_MS;
+    let filters = [
+        (ArticleSort::Date, None),
+        (ArticleSort::FirstSeen, None),
+        (ArticleSort::Date, Some(ReadState::Unread)),
+        (ArticleSort::FirstSeen, Some(ReadState::Unread)),
+    ];
+    let mut samples = vec![];
+    let mut rows = 0;
+
+    for (sort, read_state) in filters {
+        let filter = ArticleFilter { sort, read_state, ..ArticleFilter::default() };
+        for jump in 0..=TIMELINE_JUMPS {
+            let date = now - history_ms * jump as i64 / TIMELINE_JUMPS as i64;
+
+            let started = Instant::now();
+            let anchor = load_jump(&filter, date, pool).await?;
+            samples.push(started.elapsed());
+
+            for direction in [PageDirection::Older, PageDirection::Newer] {
+                // Nothing is newer than the top
+                if direction == PageDirection::Newer && anchor.is_none() {
+                    continue;
+                }
+
+                let mut cursor = anchor.clone();
+                for _ in 0..TIMELINE_PAGES {
+                    let started = Instant::now();
+                    let page = load_timeline(&filter, cursor.as_ref(), direction, PAGE_SIZE, pool).await?;
+                    samples.push(started.elapsed());
+
+                    rows += page.articles.len() as i64;
+                    cursor = match direction {
+                        PageDirection::Older => page.older_cursor,
+                        PageDirection::Newer => page.newer_cursor,
+                    };
+                    if cursor.is_none() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(BenchmarkResult::new(BenchmarkSuite::Timeline, rows, samples).with_target(profile.timeline_target()))
+}
+
+/// The timeline in every sort, then the biggest feed, each page being a sample.
+async fn pagination(pool: &Pool<Sqlite>) -> Result<BenchmarkResult, String> {
+    let walks = [
+        (None, ArticleSort::Date),
+        (None, ArticleSort::Score),
+        (None, ArticleSort::FirstSeen),
+        (Some(feed_identifier(0)), ArticleSort::Date),
+    ];
+    let mut samples = vec![];
+    let mut rows = 0;
+
+    for (feed_identifier, sort) in walks {
+        let mut cursor: Option<ArticleCursor> = None;
+        for _ in 0..PAGES {
+            let filter = ArticleFilter {
+                feed_identifier: feed_identifier.clone(),
+                sort,
+                cursor: cursor.take(),
+                ..ArticleFilter::default()
+            };
+
+            let started = Instant::now();
+            let page = load_articles(filter, PAGE_SIZE, pool).await?;
+            samples.push(started.elapsed());
+
+            rows += page.articles.len() as i64;
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+    }
+
+    Ok(BenchmarkResult::new(BenchmarkSuite::Pagination, rows, samples))
+}
+
+/// The statement the feed list runs from the frontend.
+async fn unread_counts(pool: &Pool<Sqlite>) -> Result<BenchmarkResult, String> {
+    let mut samples = vec![];
+    let mut rows = 0;
+
+    for _ in 0..REPEATS {
+        let started = Instant::now();
+        let counts = sqlx::query_as::<_, (String, i64)>(include_str!("../database/queries/count_unread_articles.sql"))
+            .fetch_all(pool).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+        samples.push(started.elapsed());
+
+        rows += counts.len() as i64;
+    }
+
+    Ok(BenchmarkResult::new(BenchmarkSuite::UnreadCounts, rows, samples))
+}
+
+async fn search(pool: &Pool<Sqlite>) -> Result<BenchmarkResult, String> {
+    let queries = [
+        word(0),
+        word(VOCABULARY_SIZE / 2),
+        format!("{} {}", word(1), word(2)),
+        format!("\"{} {}\"", word(0), word(1)),
+        format!("{} OR {}", word(100), word(200)),
+        format!("{} -{}", word(0), word(1)),
+        format!("title:{} is:unread", word(3)),
+    ];
+    let mut samples = vec![];
+    let mut rows = 0;
+
+    for query in queries.iter() {
+        for _ in 0..REPEATS {
+            let (_, filters) = translate(query)?;
+
+            let started = Instant::now();
+            let page = search_page(filters, None, DEFAULT_SEARCH_LIMIT, pool).await?;
+            samples.push(started.elapsed());
+
+            rows += page.articles.len() as i64;
+        }
+    }
+
+    Ok(BenchmarkResult::new(BenchmarkSuite::Search, rows, samples))
+}
+
+/// The statements sync runs for a fetch, from loading the known articles of the feed to inserting each one.
+/// The text is generated beforehand so only the database is timed.
+async fn sync_upsert(profile: BenchmarkProfile, random: &mut SeededRandom, now: i64, pool: &Pool<Sqlite>) -> Result<BenchmarkResult, String> {
+    let mut samples = vec![];
+    let mut rows = 0;
+
+    for batch in 0..SYNC_BATCHES {
+        let feed = random.skewed(profile.feeds() as u64) as usize;
+        let known: Vec<i64> = known_articles(&feed_identifier(feed), pool).await?.iter()
+            .filter_map(|article| article.identifier.parse().ok())
+            .take(SYNC_BATCH_SIZE / 2)
+            .collect();
+        let first_new = (profile.articles() + batch * SYNC_BATCH_SIZE) as i64;
+        let identifiers = known.iter().copied().chain(first_new..).take(SYNC_BATCH_SIZE);
+        let queries: Vec<_> = identifiers
+            .map(|identifier| {
+                let date = now - random.below(DAY_MS as u64) as i64;
+                article_query(random, feed, identifier, date)
+            })
+            .collect();
+
+        let started = Instant::now();
+        known_articles(&feed_identifier(feed), pool).await?;
+        for query in queries {
+            match query.execute(pool).await {
+                Ok(_) => rows += 1,
+                Err(e) => match e.as_database_error() {
+                    Some(database_error) => match database_error.code().unwrap_or_default().to_string().as_str() {
+                        "2067" | "1555" => {}
+                        _ => return Err(format!("Error executing query: {}", e)),
+                    },
+                    None => return Err(format!("Error executing query: {}", e)),
+                },
+            }
+        }
+        samples.push(started.elapsed());
+    }
+
+    Ok(BenchmarkResult::new(BenchmarkSuite::SyncUpsert, rows, samples))
+}
+
+async fn purge_preview(now: i64, pool: &Pool<Sqlite>) -> Result<BenchmarkResult, String> {
+    let mut samples = vec![];
+    let mut rows = 0;
+
+    for _ in 0..REPEATS {
+        let started = Instant::now();
+        let purgeable = sqlx::query_as::<_, (String, i64, i64)>(include_str!("../database/queries/count_purgeable_articles.sql"))
+            .bind(now - PURGE_AFTER_DAYS * DAY_MS)
+            .fetch_all(pool).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+        samples.push(started.elapsed());
+
+        rows += purgeable.len() as i64;
+    }
+
+    Ok(BenchmarkResult::new(BenchmarkSuite::PurgePreview, rows, samples))
+}
+
+/// The first page of the timeline, read from the read-only connections over and over while articles are
+/// stored through the writing one in transactions of about a feed. Each read is a sample, checked against
+/// the target of the profile.
+async fn reads_during_sync(profile: BenchmarkProfile, random: &mut SeededRandom, now: i64, pool: &Pool<Sqlite>, read_pool: &Pool<Sqlite>) -> Result<BenchmarkResult, String> {
+    // Past the ones the sync upsert suite may have added
+    let first_new = (profile.articles() * 2) as i64;
+    let queries: Vec<_> = (first_new..first_new + CONTENTION_ARTICLES as i64)
+        .map(|identifier| {
+            let feed = random.skewed(profile.feeds() as u64) as usize;
+            let date = now - random.below(DAY_MS as u64) as i64;
+            article_query(random, feed, identifier, date)
+        })
+        .collect();
+
+    let writing = Arc::new(AtomicBool::new(true));
+    let writer = {
+        let pool = pool.clone();
+        let writing = writing.clone();
+        tauri::async_runtime::spawn(async move {
+            let result = write_in_batches(queries, &pool).await;
+            writing.store(false, Ordering::SeqCst);
+            result
+        })
+    };
+
+    let mut samples = vec![];
+    while writing.load(Ordering::SeqCst) {
+        let started = Instant::now();
+        load_articles(ArticleFilter::default(), PAGE_SIZE, read_pool).await?;
+        samples.push(started.elapsed());
+    }
+    let rows = writer.await.map_err(|e| e.to_string())??;
+
+    Ok(BenchmarkResult::new(BenchmarkSuite::ReadsDuringSync, rows, samples).with_target(profile.read_target_during_sync()))
+}
+
+async fn write_in_batches(queries: Vec<Query<'static, Sqlite, SqliteArguments<'static>>>, pool: &Pool<Sqlite>) -> Result<i64, String> {
+    let mut rows = 0;
+    let mut queries = queries.into_iter().peekable();
+
+    while queries.peek().is_some() {
+        let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+        for query in queries.by_ref().take(SYNC_BATCH_ARTICLES) {
+            query.execute(&mut *transaction).await.map_err(|e| format!("Error executing query: {}", e))?;
+            rows += 1;
+        }
+        transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))?;
+        tokio::task::yield_now().await;
+    }
+
+    Ok(rows)
+}
+
+/// Full passes over generated documents of the same feeds, `PASS_WORKERS` feeds at once. Each document is
+/// parsed, its articles prepared and stored, so the parsing the writer overlaps with is timed too. Each pass
+/// is a sample, rows being the articles stored, inserted or updated.
+async fn sync_pass(suite: BenchmarkSuite, profile: BenchmarkProfile, random: &mut SeededRandom, now: i64, pool: &Pool<Sqlite>) -> Result<BenchmarkResult, String> {
+    let tag = match suite {
+        BenchmarkSuite::SyncPipelined => "pipelined",
+        _ => "sequential",
+    };
+    let feeds = PASS_FEEDS.min(profile.feeds());
+    let mut samples = vec![];
+    let mut rows = 0;
+
+    for pass in 0..SYNC_PASSES {
+        let first_item = pass * DOCUMENT_ITEMS / 2;
+        let documents: VecDeque<(String, String)> = (0..feeds)
+            .map(|feed| (feed_identifier(feed), feed_document(random, feed, tag, first_item..first_item + DOCUMENT_ITEMS, now)))
+            .collect();
+        let documents = Arc::new(Mutex::new(documents));
+
+        let started = Instant::now();
+        let writer = (suite == BenchmarkSuite::SyncPipelined).then(|| SyncWriter::start(pool.clone(), WriteGate::default()));
+        let workers: Vec<_> = (0..PASS_WORKERS)
+            .map(|_| tauri::async_runtime::spawn(store_documents(documents.clone(), writer.clone(), pool.clone())))
+            .collect();
+        drop(writer);
+        for worker in workers {
+            rows += worker.await.map_err(|e| e.to_string())??;
+        }
+        samples.push(started.elapsed());
+    }
+
+    Ok(BenchmarkResult::new(suite, rows, samples))
+}
+
+/// Parses and stores documents until none is left, through `writer` when there is one. Without, the feed
+/// is updated first and its articles stored in transactions of `SYNC_BATCH_ARTICLES`.
+async fn store_documents(documents: DocumentQueue, writer: Option<SyncWriter>, pool: Pool<Sqlite>) -> Result<i64, String> {
+    let mut rows = 0;
+
+    loop {
+        let Some((feed_identifier, document)) = documents.lock().unwrap().pop_front() else { return Ok(rows) };
+        let (feed, _, _) = parse_feed(&document)?;
+        let articles = feed.entries.into_iter().map(|entry| {
+            let mut article = Article::from(entry);
+            article.title = display_title(&article.original_title, &[]);
+            let key = dedup_key(article.link.as_deref(), &article.original_title);
+            prepare_article(&feed_identifier, article, key, &[], &[])
+        });
+        let completion = FeedCompletion {
+            feed_type: "rss",
+            image_url: None,
+            validators: FeedValidators::default(),
+            session_working: false,
+            fetched: vec![],
+            previous: None,
+        };
+
+        match &writer {
+            Some(writer) => {
+                let (writes, written) = writer.feed(&feed_identifier);
+                let mut batch = Vec::with_capacity(SYNC_BATCH_ARTICLES);
+                for article in articles {
+                    batch.push(article);
+                    rows += 1;
+                    if batch.len() == SYNC_BATCH_ARTICLES && writes.send(FeedWrite::Articles(std::mem::take(&mut batch))).await.is_err() {
+                        break;
+                    }
+                }
+                if !batch.is_empty() {
+                    let _ = writes.send(FeedWrite::Articles(batch)).await;
+                }
+                let _ = writes.send(FeedWrite::Finish(completion)).await;
+                drop(writes);
+                written.await.map_err(|_| String::from("Error storing feed: the writer stopped"))??;
+            }
+            None => {
+                let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+                complete_feed(&feed_identifier, completion, &mut transaction).await?;
+                transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))?;
+
+                let mut articles = articles.peekable();
+                while articles.peek().is_some() {
+                    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+                    for article in articles.by_ref().take(SYNC_BATCH_ARTICLES) {
+                        insert_article(&feed_identifier, article, &mut transaction).await?;
+                        rows += 1;
+                    }
+                    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))?;
+                    tokio::task::yield_now().await;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,350 @@
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::commands::reading_queue::{notify_if_changed, remove_from_queue};
+use crate::database::change_journal::emit_article_change;
+use crate::database::local_metrics;
+use crate::enums::local_metric::LocalMetric;
+use crate::structs::article_content::ArticleContent;
+use crate::structs::article_filter::ArticleFilter;
+use crate::structs::article_page::ArticlePage;
+use crate::structs::feed_article_count::FeedArticleCount;
+use crate::structs::field_update::FieldUpdate;
+use crate::structs::preferences::Preferences;
+use crate::structs::read_pool::ReadPool;
+use crate::structs::smart_counts::SmartCounts;
+use crate::structs::stored_article::StoredArticle;
+use crate::structs::write_gate::WriteGate;
+use crate::utils::pagination::{page_limit, MAX_PAGE_SIZE};
+
+/// Articles published this long before being first seen come from the archive of a newly added feed.
+pub const BACKFILLED_AGE_MS: i64 = 7 * 24 * 60 * 60 * 1000;
+
+#[alduin_macros::command]
+pub async fn get_articles(filter: ArticleFilter, app_handle: AppHandle) -> Result<ArticlePage, String> {
+    let limit = page_limit("get_articles", filter.limit, MAX_PAGE_SIZE);
+
+    let pool = app_handle.state::<ReadPool>();
+    load_articles(filter, limit, &pool).await
+}
+
+/// A page of the articles matching `filter`, `limit` being already capped.
+pub async fn load_articles(filter: ArticleFilter, limit: i64, pool: &Pool<Sqlite>) -> Result<ArticlePage, String> {
+    let articles = sqlx::query_as::<_, StoredArticle>(include_str!("../database/queries/get_articles.sql"))
+        .bind(filter.feed_identifier)
+        .bind(filter.min_score)
+        .bind(filter.sort.as_str())
+        .bind(filter.cursor.as_ref().map(|cursor| cursor.sort_key(filter.sort)))
+        .bind(filter.cursor.as_ref().map(|cursor| cursor.date))
+        .bind(filter.cursor.map(|cursor| cursor.identifier))
+        .bind(limit)
+        .bind(BACKFILLED_AGE_MS)
+        .bind(filter.read_state.map(|state| state.as_str()))
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(ArticlePage::new(articles, limit))
+}
+
+/// Counts the articles updated since they were last read, in all and per feed.
+#[alduin_macros::command]
+pub async fn get_smart_counts(app_handle: AppHandle) -> Result<SmartCounts, String> {
+    let pool = app_handle.state::<ReadPool>();
+    count_smart(&pool).await
+}
+
+pub async fn count_smart(pool: &Pool<Sqlite>) -> Result<SmartCounts, String> {
+    let updated_since_read_by_feed = sqlx::query_as::<_, FeedArticleCount>(include_str!("../database/queries/count_updated_since_read.sql"))
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(SmartCounts {
+        updated_since_read: updated_since_read_by_feed.iter().map(|feed| feed.count).sum(),
+        updated_since_read_by_feed,
+    })
+}
+
+/// Returns the content of an article, or its first chunk when it is longer than the `contentChunkSize` preference.
+/// Sending a few MB in one message makes the webview hang.
+#[alduin_macros::command]
+pub async fn get_article_content(article_id: String, app_handle: AppHandle) -> Result<ArticleContent, String> {
+    let pool = app_handle.state::<ReadPool>();
+    load_content_chunk(&article_id, 0, Preferences::load(&app_handle).content_chunk_size, &pool).await
+        .map(|(_, content)| content)
+}
+
+/// Returns the chunk a token from `get_article_content` points to. When the content was replaced meanwhile,
+/// fails with `Conflict:` followed by the first chunk of the new content, the reader having to start over.
+#[alduin_macros::command]
+pub async fn get_article_content_chunk(article_id: String, token: String, app_handle: AppHandle) -> Result<ArticleContent, String> {
+    let (version, offset) = token.split_once(':')
+        .and_then(|(version, offset)| Some((version.parse::<i64>().ok()?, offset.parse::<i64>().ok()?)))
+        .ok_or_else(|| format!("Invalid continuation token: {}", token))?;
+
+    let pool = app_handle.state::<ReadPool>();
+    let chunk_size = Preferences::load(&app_handle).content_chunk_size;
+    let (current_version, content) = load_content_chunk(&article_id, offset, chunk_size, &pool).await?;
+    if current_version == version {
+        return Ok(content);
+    }
+
+    let (_, restart) = load_content_chunk(&article_id, 0, chunk_size, &pool).await?;
+    let restart = serde_json::to_string(&restart).map_err(|e| format!("Error serializing content: {}", e))?;
+    Err(format!("Conflict: {}", restart))
+}
+
+/// Returns the version of the article along with the chunk, the token pointing to the next one.
+async fn load_content_chunk(article_id: &str, offset: i64, chunk_size: i64, pool: &Pool<Sqlite>) -> Result<(i64, ArticleContent), String> {
+    let chunk_size = chunk_size.max(1);
+    let (version, total_size, chunk) = sqlx::query_as::<_, (i64, i64, String)>(include_str!("../database/queries/get_article_content_chunk.sql"))
+        .bind(article_id)
+        .bind(offset)
+        .bind(chunk_size)
+        .fetch_optional(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .ok_or_else(|| format!("Article {} not found", article_id))?;
+
+    let next_offset = offset + chunk_size;
+    let next_token = (next_offset < total_size).then(|| format!("{}:{}", version, next_offset));
+
+    Ok((version, ArticleContent { chunk, total_size, next_token }))
+}
+
+/// Only touches the read state, so it never overwrites another field edited meanwhile from another window.
+#[alduin_macros::command]
+pub async fn set_article_read(article_id: String, read: bool, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    if let Some((version, feed_identifier, score)) = mark_read(&article_id, read, &pool).await? {
+        if read {
+            local_metrics::record(&app_handle, LocalMetric::ArticlesRead, 1).await;
+            if Preferences::load(&app_handle).dequeue_read {
+                notify_if_changed(remove_from_queue(&article_id, &pool).await?, &app_handle).await?;
+            }
+        }
+        let update = FieldUpdate { identifier: article_id, fields: vec!["read"], version };
+        emit_article_change(&app_handle, "article-updated", update, |filter| filter.may_show(&feed_identifier, Some(score))).await?;
+    }
+
+    Ok(())
+}
+
+/// Returns the new version, the feed and the score of the article, None when it doesn't exist.
+pub async fn mark_read(article_id: &str, read: bool, pool: &Pool<Sqlite>) -> Result<Option<(i64, String, i64)>, String> {
+    sqlx::query_as::<_, (i64, String, i64)>(include_str!("../database/queries/set_article_read.sql"))
+        .bind(read)
+        .bind(article_id)
+        .fetch_optional(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet, HashSet};
+    use std::time::Duration;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::database::article_writer::insert_article;
+    use crate::database::migrator::migrator;
+    use crate::enums::article_sort::ArticleSort;
+    use crate::enums::read_state::ReadState;
+    use crate::structs::article::Article;
+    use crate::structs::article_cursor::ArticleCursor;
+    use crate::structs::prepared_article::PreparedArticle;
+    use super::*;
+
+    const ARTICLES: u64 = 120;
+    const PAGE_SIZE: i64 = 7;
+
+    /// xorshift64, so every failing seed can be replayed.
+    struct Random(u64);
+
+    impl Random {
+        fn below(&mut self, bound: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % bound
+        }
+    }
+
+    /// Articles sharing dates, scores and first seen times, so the pages end in the middle of ties.
+    async fn fixture(random: &mut Random) -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval) VALUES ('feed', 'feed', '', 60)")
+            .execute(&pool).await.unwrap();
+
+        for article in 0..ARTICLES {
+            let first_seen = (random.below(4) > 0).then(|| random.below(10) as i64);
+            sqlx::query("INSERT INTO articles (identifier, feed_identifier, title, content, date, score, first_seen) VALUES (?, 'feed', '', '', ?, ?, ?)")
+                .bind(format!("{:03}", article))
+                .bind(random.below(15) as i64)
+                .bind(random.below(5) as i64 - 2)
+                .bind(first_seen)
+                .execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    /// Ordered, for the deletions of a seed to be the same on every run.
+    async fn identifiers(pool: &Pool<Sqlite>) -> BTreeSet<String> {
+        sqlx::query_scalar("SELECT identifier FROM articles").fetch_all(pool).await.unwrap().into_iter().collect()
+    }
+
+    #[tokio::test]
+    async fn pages_through_random_deletions() {
+        for seed in 1..=20u64 {
+            for sort in [ArticleSort::Date, ArticleSort::Score, ArticleSort::FirstSeen] {
+                let mut random = Random(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+                let pool = fixture(&mut random).await;
+                let mut survivors = identifiers(&pool).await;
+                let mut returned = HashSet::new();
+                let mut cursor: Option<ArticleCursor> = None;
+
+                loop {
+                    let filter = ArticleFilter { sort, cursor: cursor.take(), ..ArticleFilter::default() };
+                    let page = load_articles(filter, PAGE_SIZE, &pool).await.unwrap();
+                    for article in page.articles.iter() {
+                        assert!(returned.insert(article.identifier.clone()), "seed {} {:?}: {} returned twice", seed, sort, article.identifier);
+                    }
+
+                    // Pruned, merged or removed with their feed while the list was open, the anchor of the cursor included
+                    let mut deleted: Vec<String> = survivors.iter()
+                        .filter(|_| random.below(8) == 0)
+                        .cloned()
+                        .collect();
+                    if let Some(next) = page.next_cursor.as_ref().filter(|_| random.below(3) == 0) {
+                        deleted.push(next.identifier.clone());
+                    }
+                    for identifier in deleted {
+                        sqlx::query("DELETE FROM articles WHERE identifier = ?").bind(&identifier).execute(&pool).await.unwrap();
+                        survivors.remove(&identifier);
+                    }
+
+                    match page.next_cursor {
+                        Some(next) => cursor = Some(next),
+                        None => break,
+                    }
+                }
+
+                let skipped: Vec<&String> = survivors.iter().filter(|identifier| !returned.contains(*identifier)).collect();
+                assert!(skipped.is_empty(), "seed {} {:?}: skipped {:?}", seed, sort, skipped);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn ends_on_a_short_page() {
+        let pool = fixture(&mut Random(7)).await;
+
+        let page = load_articles(ArticleFilter::default(), ARTICLES as i64, &pool).await.unwrap();
+        assert_eq!(page.articles.len() as u64, ARTICLES);
+        assert!(page.next_cursor.is_some());
+
+        let filter = ArticleFilter { cursor: page.next_cursor, ..ArticleFilter::default() };
+        let page = load_articles(filter, ARTICLES as i64, &pool).await.unwrap();
+        assert!(page.articles.is_empty());
+        assert!(page.next_cursor.is_none());
+    }
+
+    /// Stores the article as a sync fetching it with this content would.
+    async fn sync(feed_identifier: &str, identifier: u32, content: &str, pool: &Pool<Sqlite>) {
+        let article = Article {
+            id: identifier.to_string(),
+            title: String::from("title"),
+            original_title: String::from("title"),
+            content: content.to_string(),
+            date: 0,
+            read: false,
+            image: None,
+            comments_url: None,
+            link: None,
+            language: None,
+            enclosures: vec![],
+            post_processors: vec![],
+            extras: BTreeMap::new(),
+        };
+        let prepared = PreparedArticle { identifier, article, score: 0, dedup_key: None, links: vec![] };
+        insert_article(feed_identifier, prepared, &mut pool.acquire().await.unwrap()).await.unwrap();
+        // The times are in ms, so every step comes strictly after the previous one
+        tokio::time::sleep(Duration::from_millis(3)).await;
+    }
+
+    async fn read(identifier: u32, read: bool, pool: &Pool<Sqlite>) {
+        assert!(mark_read(&identifier.to_string(), read, pool).await.unwrap().is_some());
+        tokio::time::sleep(Duration::from_millis(3)).await;
+    }
+
+    async fn shown(read_state: ReadState, pool: &Pool<Sqlite>) -> Vec<String> {
+        let filter = ArticleFilter { read_state: Some(read_state), ..ArticleFilter::default() };
+        load_articles(filter, MAX_PAGE_SIZE, pool).await.unwrap()
+            .articles.into_iter()
+            .map(|article| article.identifier)
+            .collect()
+    }
+
+    async fn history(identifier: u32, pool: &Pool<Sqlite>) -> (Option<i64>, Option<i64>) {
+        sqlx::query_as("SELECT read_at, content_updated_at FROM articles WHERE identifier = ?")
+            .bind(identifier.to_string())
+            .fetch_one(pool).await.unwrap()
+    }
+
+    /// The unread, read and updated since read articles, and the updated count of each feed.
+    async fn states(pool: &Pool<Sqlite>) -> [Vec<String>; 4] {
+        let counts = count_smart(pool).await.unwrap();
+        let mut by_feed: Vec<String> = counts.updated_since_read_by_feed.iter().map(|feed| format!("{}: {}", feed.feed_identifier, feed.count)).collect();
+        by_feed.sort();
+        assert_eq!(counts.updated_since_read, counts.updated_since_read_by_feed.iter().map(|feed| feed.count).sum::<i64>());
+        [shown(ReadState::Unread, pool).await, shown(ReadState::Read, pool).await, shown(ReadState::UpdatedSinceRead, pool).await, by_feed]
+    }
+
+    #[tokio::test]
+    async fn follows_the_read_history_through_updates() {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval, reread_on_update) VALUES ('reread', 'reread', '', 60, 1), ('plain', 'plain', '', 60, 0)")
+            .execute(&pool).await.unwrap();
+        let strings = |identifiers: &[&str]| identifiers.iter().map(|identifier| identifier.to_string()).collect::<Vec<String>>();
+
+        sync("reread", 2, "first", &pool).await;
+        sync("plain", 1, "first", &pool).await;
+        assert_eq!(states(&pool).await, [strings(&["2", "1"]), vec![], vec![], vec![]]);
+
+        read(2, true, &pool).await;
+        read(1, true, &pool).await;
+        let (read_at, _) = history(2, &pool).await;
+        assert_eq!(states(&pool).await, [vec![], strings(&["2", "1"]), vec![], vec![]]);
+
+        // The feed asking for it gets the article read again, the other one only flags it
+        sync("reread", 2, "second", &pool).await;
+        sync("plain", 1, "second", &pool).await;
+        assert_eq!(states(&pool).await, [strings(&["2"]), strings(&["1"]), strings(&["2", "1"]), strings(&["plain: 1", "reread: 1"])]);
+        let (kept_read_at, first_update) = history(2, &pool).await;
+        assert_eq!(kept_read_at, read_at);
+
+        // A second update moves the update time only, an unchanged fetch nothing
+        sync("reread", 2, "third", &pool).await;
+        let (kept_read_at, second_update) = history(2, &pool).await;
+        assert_eq!(kept_read_at, read_at);
+        assert!(second_update > first_update);
+        sync("reread", 2, "third", &pool).await;
+        assert_eq!(history(2, &pool).await, (read_at, second_update));
+
+        // Marked unread again, it still was updated since read
+        let plain_history = history(1, &pool).await;
+        read(1, false, &pool).await;
+        assert_eq!(history(1, &pool).await, plain_history);
+        assert_eq!(states(&pool).await, [strings(&["2", "1"]), vec![], strings(&["2", "1"]), strings(&["plain: 1", "reread: 1"])]);
+
+        // Opened again
+        read(2, true, &pool).await;
+        let (reread_at, _) = history(2, &pool).await;
+        assert!(reread_at > second_update);
+        assert_eq!(states(&pool).await, [strings(&["1"]), strings(&["2"]), strings(&["1"]), strings(&["plain: 1"])]);
+
+        // And updated once more
+        sync("reread", 2, "fourth", &pool).await;
+        assert_eq!(history(2, &pool).await.0, reread_at);
+        assert_eq!(states(&pool).await, [strings(&["2", "1"]), vec![], strings(&["2", "1"]), strings(&["plain: 1", "reread: 1"])]);
+    }
+}
@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+use tauri::AppHandle;
+use tauri_plugin_autostart::ManagerExt;
+use crate::structs::autostart_registration::AutostartRegistration;
+use crate::structs::autostart_status::AutostartStatus;
+use crate::structs::preferences::Preferences;
+use crate::utils::autostart::{is_stale, launch_args, load_registration, save_registration};
+
+#[alduin_macros::command]
+pub async fn get_autostart_status(app_handle: AppHandle) -> Result<AutostartStatus, String> {
+    let enabled = app_handle.autolaunch().is_enabled().map_err(|e| format!("Error reading autostart: {}", e))?;
+    let expected = expected_registration(&app_handle)?;
+    let registered = read_registration(&app_handle);
+
+    Ok(AutostartStatus {
+        enabled,
+        path_matches: registered.as_ref().is_some_and(|registered| registered.path == expected.path),
+        args_match: registered.as_ref().is_some_and(|registered| registered.args == expected.args),
+        derived_path: expected.path,
+    })
+}
+
+/// Registers the login item again when it was made for another executable or other arguments,
+/// e.g. after an update moved the install. Left alone when autostart is disabled.
+pub fn repair_autostart(app_handle: &AppHandle) -> Result<(), String> {
+    let manager = app_handle.autolaunch();
+    let enabled = manager.is_enabled().map_err(|e| format!("Error reading autostart: {}", e))?;
+    if !enabled || !Preferences::load(app_handle).auto_start {
+        return Ok(());
+    }
+
+    let expected = expected_registration(app_handle)?;
+    if !is_stale(read_registration(app_handle).as_ref(), &expected) {
+        return Ok(());
+    }
+
+    eprintln!("Autostart registration is stale, registering it again");
+    manager.disable().map_err(|e| format!("Error disabling autostart: {}", e))?;
+    manager.enable().map_err(|e| format!("Error enabling autostart: {}", e))?;
+
+    let Some(path) = registration_path(app_handle) else { return Ok(()) };
+    save_registration(&path, &expected)
+}
+
+fn expected_registration(app_handle: &AppHandle) -> Result<AutostartRegistration, String> {
+    let path = std::env::current_exe().map_err(|e| format!("Error reading executable path: {}", e))?;
+
+    Ok(AutostartRegistration {
+        path: path.to_string_lossy().to_string(),
+        args: launch_args(&Preferences::load(app_handle)),
+    })
+}
+
+fn read_registration(app_handle: &AppHandle) -> Option<AutostartRegistration> {
+    load_registration(&registration_path(app_handle)?)
+}
+
+fn registration_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    app_handle.path_resolver().app_data_dir().map(|dir| dir.join("autostart.json"))
+}
@@ -0,0 +1,46 @@
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::scheduler::availability::{load_fetches, load_unavailable_window};
+use crate::structs::feed_health::FeedHealth;
+use crate::structs::write_gate::WriteGate;
+
+#[alduin_macros::command]
+pub async fn get_feed_health(feed_id: String, app_handle: AppHandle) -> Result<FeedHealth, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let fetches = load_fetches(&feed_id, &*pool).await?;
+    let failures = fetches.iter().filter(|fetch| !fetch.success).count();
+
+    Ok(FeedHealth {
+        successes: fetches.len() - failures,
+        failures,
+        unavailable_window: load_unavailable_window(&feed_id, &pool).await?,
+    })
+}
+
+/// Forgets the fetch history of a feed, so it is synced at any time of day again. The history and the
+/// window found in it go together, a feed never keeps a window without the fetches it was found in.
+#[alduin_macros::command]
+pub async fn clear_feed_unavailability(feed_id: String, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    clear_unavailability(&feed_id, &pool).await
+}
+
+pub async fn clear_unavailability(feed_id: &str, pool: &Pool<Sqlite>) -> Result<(), String> {
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+
+    sqlx::query(include_str!("../database/queries/clear_feed_fetches.sql"))
+        .bind(feed_id)
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    sqlx::query(include_str!("../database/queries/set_unavailable_window.sql"))
+        .bind(None::<u32>)
+        .bind(None::<u32>)
+        .bind(feed_id)
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))
+}
@@ -0,0 +1,166 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+use crate::benchmark::generator::{create_database, generate, open_read_pool};
+use crate::benchmark::random::SeededRandom;
+use crate::benchmark::suites;
+use crate::enums::benchmark_profile::BenchmarkProfile;
+use crate::enums::benchmark_suite::BenchmarkSuite;
+use crate::structs::benchmark_report::BenchmarkReport;
+use crate::utils::command_timing::milliseconds;
+
+const ENABLE_ARG: &str = "--enable-benchmarks";
+const DEFAULT_SEED: u32 = 42;
+
+/// Benchmarks share their temporary database, so they run one at a time.
+static RUNNING: Mutex<()> = Mutex::const_new(());
+
+/// Generates a synthetic `profile` in a temporary database and times the key queries against it, every suite
+/// when none are given. The report is also written to the `benchmarks` data folder to compare versions.
+/// Only available in debug builds or when started with `--enable-benchmarks`.
+#[alduin_macros::command]
+pub async fn run_benchmark(profile: BenchmarkProfile, suites: Option<Vec<BenchmarkSuite>>, seed: Option<u32>, app_handle: AppHandle) -> Result<BenchmarkReport, String> {
+    if !cfg!(debug_assertions) && !std::env::args().any(|arg| arg == ENABLE_ARG) {
+        return Err(format!("Benchmarks are only available in debug builds or with {}", ENABLE_ARG));
+    }
+    let _running = RUNNING.try_lock().map_err(|_| String::from("A benchmark is already running"))?;
+
+    let mut suites = suites.unwrap_or_else(|| BenchmarkSuite::ALL.to_vec());
+    suites.sort();
+    suites.dedup();
+
+    let path = std::env::temp_dir().join("alduin-benchmark.db");
+    remove_database(&path);
+    let result = benchmark(profile, &suites, seed.unwrap_or(DEFAULT_SEED), &path).await;
+    remove_database(&path);
+
+    let mut report = result?;
+    report.app_version = app_handle.package_info().version.to_string();
+    report.report_path = match write_report(&report, &app_handle) {
+        Ok(path) => Some(path.to_string_lossy().to_string()),
+        Err(e) => {
+            eprintln!("Error writing the benchmark report: {}", e);
+            None
+        }
+    };
+
+    Ok(report)
+}
+
+async fn benchmark(profile: BenchmarkProfile, suites: &[BenchmarkSuite], seed: u32, path: &Path) -> Result<BenchmarkReport, String> {
+    let pool = create_database(path).await?;
+    let now = chrono::Utc::now().timestamp_millis();
+
+    let started = Instant::now();
+    generate(profile, seed, now, &pool).await?;
+    let generation = started.elapsed();
+
+    sqlx::query(include_str!("../database/queries/wal_checkpoint.sql"))
+        .execute(&pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    let database_bytes = fs::metadata(path).map(|metadata| metadata.len()).unwrap_or_default();
+    let read_pool = open_read_pool(path).await?;
+
+    // Its own stream, so running fewer suites doesn't change what the others see
+    let mut random = SeededRandom::new(seed.wrapping_add(1));
+    let mut results = vec![];
+    for suite in suites {
+        results.push(suites::run(*suite, profile, &mut random, now, &pool, &read_pool).await?);
+    }
+    read_pool.close().await;
+    pool.close().await;
+
+    Ok(BenchmarkReport {
+        app_version: String::new(),
+        profile,
+        seed,
+        feeds: profile.feeds(),
+        articles: profile.articles(),
+        database_bytes,
+        generation_ms: milliseconds(generation),
+        ran_at: now,
+        results,
+        report_path: None,
+    })
+}
+
+/// Writes the report as `<version>-<profile>-<seed>-<time>.json`, to compare runs of several versions.
+fn write_report(report: &BenchmarkReport, app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let directory = app_handle.path_resolver().app_data_dir()
+        .ok_or_else(|| String::from("No app data directory"))?
+        .join("benchmarks");
+    fs::create_dir_all(&directory).map_err(|e| format!("Error creating {:?}: {}", directory, e))?;
+
+    let path = directory.join(format!("{}-{}-{}-{}.json", report.app_version, report.profile.as_str(), report.seed, report.ran_at));
+    let json = serde_json::to_vec_pretty(report).map_err(|e| format!("Error serializing the report: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Error writing {:?}: {}", path, e))?;
+
+    Ok(path)
+}
+
+fn remove_database(path: &Path) {
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use crate::commands::articles::load_articles;
+    use crate::structs::article_filter::ArticleFilter;
+    use super::*;
+
+    /// The p95 of loading the first page of the timeline with nothing else running.
+    async fn idle_p95(path: &Path) -> Duration {
+        let read_pool = open_read_pool(path).await.unwrap();
+        let mut samples = vec![];
+        for _ in 0..40 {
+            let started = Instant::now();
+            load_articles(ArticleFilter::default(), 100, &read_pool).await.unwrap();
+            samples.push(started.elapsed());
+        }
+        read_pool.close().await;
+
+        samples.sort();
+        samples[samples.len() * 95 / 100]
+    }
+
+    #[tokio::test]
+    async fn reads_stay_fast_during_a_heavy_sync() {
+        let path = std::env::temp_dir().join(format!("alduin-reads-during-sync-{}.db", std::process::id()));
+        remove_database(&path);
+        let report = benchmark(BenchmarkProfile::Small, &[BenchmarkSuite::ReadsDuringSync], DEFAULT_SEED, &path).await;
+        let idle_p95 = idle_p95(&path).await;
+        remove_database(&path);
+
+        let result = &report.unwrap().results[0];
+        assert_eq!(result.rows, 5_000);
+        // Read all along the sync, not once before or after it
+        assert!(result.samples >= 20, "{:?}", result);
+        // Waiting on the writer or a checkpoint would take several reads' time
+        assert!(result.p95_ms < 3.0 * milliseconds(idle_p95), "{:?}, idle p95 {:?}", result, idle_p95);
+        // The targets are set for optimized builds
+        if !cfg!(debug_assertions) {
+            assert_eq!(result.within_target, Some(true), "{:?}", result);
+        }
+    }
+
+    #[tokio::test]
+    async fn pages_the_large_timeline_within_target() {
+        let path = std::env::temp_dir().join(format!("alduin-timeline-{}.db", std::process::id()));
+        remove_database(&path);
+        // Generating the large profile takes minutes unoptimized, where the target isn't checked anyway
+        let profile = if cfg!(debug_assertions) { BenchmarkProfile::Small } else { BenchmarkProfile::Large };
+        let report = benchmark(profile, &[BenchmarkSuite::Timeline], DEFAULT_SEED, &path).await;
+        remove_database(&path);
+
+        let result = &report.unwrap().results[0];
+        assert!(result.rows > 0, "{:?}", result);
+        if !cfg!(debug_assertions) {
+            assert_eq!(result.within_target, Some(true), "{:?}", result);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use tokio::sync::mpsc::{self, UnboundedSender};
+use url::Url;
+use crate::commands::links::discover_feeds;
+use crate::parsers::bookmarks::parse_bookmarks;
+use crate::structs::bookmark::Bookmark;
+use crate::structs::bookmark_folder::BookmarkFolder;
+use crate::structs::bookmark_import_options::BookmarkImportOptions;
+use crate::structs::bookmarks_discovery::BookmarksDiscovery;
+use crate::structs::bookmarks_inspection::BookmarksInspection;
+use crate::structs::discovered_feed::DiscoveredFeed;
+use crate::structs::discovered_folder::DiscoveredFolder;
+use crate::structs::discovery_progress::DiscoveryProgress;
+use crate::structs::exported_feed::ExportedFeed;
+use crate::structs::feed_candidate::FeedCandidate;
+use crate::structs::undiscovered_site::UndiscoveredSite;
+use crate::utils::input_limits::{read_file, MAX_BOOKMARKS_BYTES};
+use crate::utils::url::canonical_url;
+
+/// Sites fetched at the same time. The sites of a host are fetched one after the other.
+const DISCOVERY_CONCURRENCY: usize = 4;
+/// Sites fetched at most by a discovery, whatever its options ask for.
+const MAX_DISCOVERY_REQUESTS: usize = 200;
+/// Between two sites of the same host.
+const HOST_DELAY: Duration = Duration::from_secs(1);
+
+static DISCOVERY_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// The URLs of the sites left to fetch, host by host, with their index among all the sites.
+type HostQueue = Arc<Mutex<VecDeque<Vec<(usize, String)>>>>;
+
+/// Lists the folders of a browser bookmarks export, parents of the folders holding bookmarks included,
+/// for `import_bookmarks_html` to be limited to some of them.
+#[alduin_macros::command]
+pub async fn inspect_bookmarks_html(path: String) -> Result<BookmarksInspection, String> {
+    let bookmarks = read_bookmarks(&path)?;
+    let mut folders: Vec<BookmarkFolder> = vec![];
+    let mut ignored = 0;
+
+    for bookmark in &bookmarks {
+        if !is_web_url(&bookmark.url) {
+            ignored += 1;
+            continue;
+        }
+
+        for depth in 1..=bookmark.folder.len() {
+            let path = &bookmark.folder[..depth];
+            if !folders.iter().any(|folder| folder.path == path) {
+                folders.push(BookmarkFolder { path: path.to_vec(), bookmarks: 0 });
+            }
+        }
+        match folders.iter_mut().find(|folder| folder.path == bookmark.folder) {
+            Some(folder) => folder.bookmarks += 1,
+            None => folders.push(BookmarkFolder { path: bookmark.folder.clone(), bookmarks: 1 }),
+        }
+    }
+
+    Ok(BookmarksInspection { folders, bookmarks: bookmarks.len() - ignored, ignored })
+}
+
+/// Looks for the feeds of the bookmarked sites, emitting `bookmarks-discovery-progress` after each site.
+/// Nothing is subscribed to: the user picks among the feeds found, which `import_feeds` then imports.
+/// Bookmarks of feeds or sites already subscribed to are left out before fetching anything, and at most
+/// `MAX_DISCOVERY_REQUESTS` sites are fetched. Once cancelled, returns what was found so far.
+#[alduin_macros::command]
+pub async fn import_bookmarks_html(path: String, options: BookmarkImportOptions, app_handle: AppHandle) -> Result<BookmarksDiscovery, String> {
+    DISCOVERY_CANCELLED.store(false, Ordering::SeqCst);
+
+    let bookmarks = read_bookmarks(&path)?;
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let subscribed: HashSet<String> = sqlx::query_as::<_, ExportedFeed>(include_str!("../database/queries/export_feeds.sql"))
+        .fetch_all(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .iter()
+        .flat_map(|feed| [Some(feed.url.as_str()), feed.html_url.as_deref()])
+        .flatten()
+        .filter_map(canonical_url)
+        .collect();
+
+    let mut discovery = BookmarksDiscovery::default();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut sites: Vec<Bookmark> = vec![];
+    for bookmark in bookmarks {
+        let selected = options.folders.is_empty()
+            || options.folders.iter().any(|folder| bookmark.folder.starts_with(folder));
+        if !selected || !is_web_url(&bookmark.url) {
+            continue;
+        }
+
+        let Some(canonical) = canonical_url(&bookmark.url) else { continue };
+        if subscribed.contains(&canonical) {
+            discovery.already_subscribed.push(bookmark);
+        } else if seen.insert(canonical) {
+            sites.push(bookmark);
+        }
+    }
+
+    let limit = options.max_requests.unwrap_or(MAX_DISCOVERY_REQUESTS).min(MAX_DISCOVERY_REQUESTS);
+    let over_limit = sites.split_off(limit.min(sites.len()));
+
+    // Each host is handed to a single worker, which fetches its sites in turn
+    let mut hosts: Vec<Vec<(usize, String)>> = vec![];
+    let mut host_indexes: HashMap<String, usize> = HashMap::new();
+    for (index, site) in sites.iter().enumerate() {
+        let host = Url::parse(&site.url).ok().and_then(|url| url.host_str().map(str::to_string)).unwrap_or_default();
+        let host_index = *host_indexes.entry(host).or_insert_with(|| {
+            hosts.push(vec![]);
+            hosts.len() - 1
+        });
+        hosts[host_index].push((index, site.url.clone()));
+    }
+
+    let workers = DISCOVERY_CONCURRENCY.min(hosts.len());
+    let hosts = Arc::new(Mutex::new(VecDeque::from(hosts)));
+    let (sender, mut results) = mpsc::unbounded_channel();
+    for _ in 0..workers {
+        tauri::async_runtime::spawn(discovery_worker(hosts.clone(), sender.clone(), app_handle.clone()));
+    }
+    drop(sender);
+
+    let mut found: Vec<Option<Result<Vec<FeedCandidate>, String>>> = sites.iter().map(|_| None).collect();
+    while let Some((index, result)) = results.recv().await {
+        found[index] = Some(result);
+        discovery.requests += 1;
+
+        app_handle.emit_all("bookmarks-discovery-progress", DiscoveryProgress { sites_done: discovery.requests, sites_total: sites.len() })
+            .map_err(|e| format!("Error emitting progress: {}", e))?;
+    }
+    discovery.cancelled = DISCOVERY_CANCELLED.load(Ordering::SeqCst);
+
+    // A feed advertised by several sites is offered once, in the folder of the first one
+    let mut feed_urls: HashSet<String> = HashSet::new();
+    for (site, result) in sites.into_iter().zip(found) {
+        let candidates = match result {
+            None => {
+                discovery.not_checked.push(site);
+                continue;
+            }
+            Some(Err(error)) => {
+                discovery.without_feed.push(UndiscoveredSite { title: site.title, url: site.url, folder: site.folder, error: Some(error) });
+                continue;
+            }
+            Some(Ok(candidates)) if candidates.is_empty() => {
+                discovery.without_feed.push(UndiscoveredSite { title: site.title, url: site.url, folder: site.folder, error: None });
+                continue;
+            }
+            Some(Ok(candidates)) => candidates,
+        };
+
+        let new_candidates: Vec<FeedCandidate> = candidates.into_iter()
+            .filter(|candidate| candidate.existing_feed.is_none())
+            .collect();
+        if new_candidates.is_empty() {
+            discovery.already_subscribed.push(site);
+            continue;
+        }
+
+        let folder_index = match discovery.folders.iter().position(|folder| folder.folder == site.folder) {
+            Some(index) => index,
+            None => {
+                discovery.folders.push(DiscoveredFolder { folder: site.folder.clone(), feeds: vec![] });
+                discovery.folders.len() - 1
+            }
+        };
+        for candidate in new_candidates {
+            if !feed_urls.insert(canonical_url(&candidate.url).unwrap_or_else(|| candidate.url.clone())) {
+                continue;
+            }
+            discovery.folders[folder_index].feeds.push(DiscoveredFeed {
+                url: candidate.url,
+                title: candidate.title.unwrap_or_else(|| site.title.clone()),
+                feed_type: candidate.feed_type,
+                site_url: site.url.clone(),
+            });
+        }
+    }
+    discovery.folders.retain(|folder| !folder.feeds.is_empty());
+    discovery.not_checked.extend(over_limit);
+
+    Ok(discovery)
+}
+
+/// Stops the running discovery once the sites being fetched are.
+#[alduin_macros::command]
+pub async fn cancel_bookmarks_discovery() -> Result<(), String> {
+    DISCOVERY_CANCELLED.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Fetches the sites of one host after the other until none is left or the discovery is cancelled.
+async fn discovery_worker(hosts: HostQueue, results: UnboundedSender<(usize, Result<Vec<FeedCandidate>, String>)>, app_handle: AppHandle) {
+    loop {
+        let Some(sites) = hosts.lock().unwrap().pop_front() else { return };
+
+        for (position, (index, url)) in sites.into_iter().enumerate() {
+            if position > 0 {
+                tokio::time::sleep(HOST_DELAY).await;
+            }
+            if DISCOVERY_CANCELLED.load(Ordering::SeqCst) {
+                return;
+            }
+
+            let result = discover_feeds(url, &app_handle).await;
+            if results.send((index, result)).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn read_bookmarks(path: &str) -> Result<Vec<Bookmark>, String> {
+    let content = read_file(path, MAX_BOOKMARKS_BYTES)?;
+    let html = String::from_utf8_lossy(&content);
+    if !html.to_lowercase().contains("<dl") {
+        return Err(String::from("This file is not a bookmarks export"));
+    }
+
+    parse_bookmarks(&html)
+}
+
+fn is_web_url(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+}
@@ -0,0 +1,114 @@
+use sqlx::{Pool, Sqlite, SqliteConnection};
+use tauri::{AppHandle, Manager};
+use crate::secrets;
+use crate::structs::certificate_info::CertificateInfo;
+use crate::structs::feed_certificates::FeedCertificates;
+use crate::structs::feed_tls::FeedTls;
+use crate::structs::write_gate::WriteGate;
+use crate::utils::certificates::{read_ca_certificate, read_client_certificate};
+
+/// Replaces the client certificate presented to the host of the feed, a PKCS#12 file read again on each sync.
+/// An empty path removes it. Returns what the file holds once it could be read with the passphrase. A feed
+/// keeps a row of certificates only while it has one of them.
+#[alduin_macros::command]
+pub async fn set_feed_client_certificate(feed_id: String, path: Option<String>, passphrase: Option<String>, app_handle: AppHandle) -> Result<Option<CertificateInfo>, String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let path = path.map(|path| path.trim().to_string()).filter(|path| !path.is_empty());
+    let passphrase = path.as_ref().and(passphrase);
+    let info = match &path {
+        Some(path) => Some(read_client_certificate(path, passphrase.as_deref().unwrap_or_default())?.1),
+        None => None,
+    };
+
+    // Stored first, so the row never refers to a secret that isn't there
+    let passphrase_secret = match passphrase {
+        Some(passphrase) => Some(secrets::store(passphrase).await?),
+        None => None,
+    };
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let saved = save_client_certificate(&feed_id, path.as_deref(), passphrase_secret.as_deref(), &pool).await;
+    secrets::forget_if_unsaved(passphrase_secret, saved).await?;
+    if let Err(e) = secrets::forget_orphaned(&pool).await {
+        eprintln!("Error forgetting the replaced secrets: {}", e);
+    }
+    Ok(info)
+}
+
+/// Replaces the CA trusted for the host of the feed on top of the system ones, for self-signed setups.
+/// An empty path removes it.
+#[alduin_macros::command]
+pub async fn set_feed_ca_certificate(feed_id: String, path: Option<String>, app_handle: AppHandle) -> Result<Option<CertificateInfo>, String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let path = path.map(|path| path.trim().to_string()).filter(|path| !path.is_empty());
+    let info = match &path {
+        Some(path) => Some(read_ca_certificate(path)?.1),
+        None => None,
+    };
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    save_ca_certificate(&feed_id, path.as_deref(), &pool).await?;
+    Ok(info)
+}
+
+/// Reads the certificate files of the feed, failing when one of them can't be read anymore.
+pub async fn load_feed_tls(feed_id: &str, pool: &Pool<Sqlite>) -> Result<Option<FeedTls>, String> {
+    let certificates = sqlx::query_as::<_, FeedCertificates>(include_str!("../database/queries/get_feed_certificates.sql"))
+        .bind(feed_id)
+        .fetch_optional(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    let Some(certificates) = certificates else { return Ok(None) };
+
+    let passphrase = match certificates.client_certificate_secret {
+        Some(reference) => secrets::load(reference).await?,
+        None => None,
+    };
+    let identity = match &certificates.client_certificate_path {
+        Some(path) => Some(read_client_certificate(path, passphrase.as_deref().unwrap_or_default())?),
+        None => None,
+    };
+    let ca = match &certificates.ca_certificate_path {
+        Some(path) => Some(read_ca_certificate(path)?),
+        None => None,
+    };
+
+    Ok(Some(FeedTls { identity, ca }))
+}
+
+pub async fn save_client_certificate(feed_id: &str, path: Option<&str>, passphrase_secret: Option<&str>, pool: &Pool<Sqlite>) -> Result<(), String> {
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+
+    sqlx::query(include_str!("../database/queries/upsert_feed_client_certificate.sql"))
+        .bind(feed_id)
+        .bind(path)
+        .bind(passphrase_secret)
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    delete_if_empty(feed_id, &mut transaction).await?;
+    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))
+}
+
+pub async fn save_ca_certificate(feed_id: &str, path: Option<&str>, pool: &Pool<Sqlite>) -> Result<(), String> {
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+
+    sqlx::query(include_str!("../database/queries/upsert_feed_ca_certificate.sql"))
+        .bind(feed_id)
+        .bind(path)
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    delete_if_empty(feed_id, &mut transaction).await?;
+    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))
+}
+
+async fn delete_if_empty(feed_id: &str, connection: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(include_str!("../database/queries/delete_empty_feed_certificates.sql"))
+        .bind(feed_id)
+        .execute(connection).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(())
+}
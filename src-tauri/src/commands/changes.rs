@@ -0,0 +1,21 @@
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager, Window};
+use crate::database::change_journal;
+use crate::structs::missed_changes::MissedChanges;
+use crate::structs::write_gate::WriteGate;
+
+/// Records that the window applied every change up to `watermark`.
+#[alduin_macros::command]
+pub async fn ack_changes(watermark: i64, window: Window, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    change_journal::acknowledge(window.label(), watermark, &pool).await
+}
+
+/// What the window missed, for the frontend to pull once it listens to the events, e.g. after a reload.
+#[alduin_macros::command]
+pub async fn get_missed_changes(window: Window, app_handle: AppHandle) -> Result<MissedChanges, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    change_journal::missed_changes(window.label(), &pool).await
+}
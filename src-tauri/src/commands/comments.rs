@@ -0,0 +1,137 @@
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use xxhash_rust::xxh32::xxh32;
+use crate::commands::fetcher::fetch_feed;
+use crate::enums::address_family::AddressFamily;
+use crate::parsers::feed_content::parse_feed;
+use crate::structs::comment::Comment;
+use crate::structs::read_pool::ReadPool;
+use crate::structs::write_gate::WriteGate;
+
+#[alduin_macros::command]
+pub async fn watch_comments(article_id: String, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+
+    let comments_url = sqlx::query_scalar::<_, Option<String>>(include_str!("../database/queries/get_comments_url.sql"))
+        .bind(&article_id)
+        .fetch_optional(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .flatten();
+
+    let url = comments_url.ok_or_else(|| String::from("This article has no comments feed"))?;
+
+    sqlx::query(include_str!("../database/queries/watch_comments.sql"))
+        .bind(&article_id)
+        .bind(&url)
+        .execute(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    // Fetch right away so the first comments show up without waiting for the next sync
+    sync_comments(&article_id, url, app_handle.clone()).await
+}
+
+/// Stops watching the comments of an article and deletes the ones fetched, both or neither.
+#[alduin_macros::command]
+pub async fn unwatch_comments(article_id: String, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    unwatch(&article_id, &pool).await
+}
+
+pub async fn unwatch(article_id: &str, pool: &Pool<Sqlite>) -> Result<(), String> {
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+
+    sqlx::query(include_str!("../database/queries/unwatch_comments.sql"))
+        .bind(article_id)
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    sqlx::query(include_str!("../database/queries/delete_article_comments.sql"))
+        .bind(article_id)
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))
+}
+
+#[alduin_macros::command]
+pub async fn get_article_comments(article_id: String, app_handle: AppHandle) -> Result<Vec<Comment>, String> {
+    let pool = app_handle.state::<ReadPool>();
+
+    sqlx::query_as::<_, Comment>(include_str!("../database/queries/get_article_comments.sql"))
+        .bind(&article_id)
+        .fetch_all(&**pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// Syncs every watched comment feed, dropping the ones without new comments for 30 days first.
+/// Failures are only logged, comments must never make a regular sync fail. Skipped while writes are frozen.
+pub async fn sync_watched_comments(app_handle: AppHandle) {
+    let Ok(writing) = app_handle.state::<WriteGate>().enter() else { return };
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+
+    if let Err(e) = sqlx::query(include_str!("../database/queries/expire_watched_comments.sql"))
+        .execute(&*pool).await {
+        eprintln!("Error expiring watched comments: {}", e);
+    }
+    // Not held while fetching, each feed entering the gate again for its comments
+    drop(writing);
+
+    let watched: Vec<(String, String)> = match sqlx::query_as(include_str!("../database/queries/get_watched_comments.sql"))
+        .fetch_all(&*pool).await {
+        Ok(watched) => watched,
+        Err(e) => {
+            eprintln!("Error loading watched comments: {}", e);
+            return;
+        }
+    };
+
+    for (article_id, url) in watched {
+        if let Err(e) = sync_comments(&article_id, url, app_handle.clone()).await {
+            eprintln!("Error syncing comments of article {}: {}", article_id, e);
+        }
+    }
+}
+
+async fn sync_comments(article_id: &str, url: String, app_handle: AppHandle) -> Result<(), String> {
+    let content = fetch_feed(url, AddressFamily::Auto, app_handle.clone()).await?;
+    let (feed, _, _) = parse_feed(&content)?;
+
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let mut inserted = false;
+
+    for entry in feed.entries {
+        let comment = Comment::from_entry(article_id, entry);
+
+        let insert_comment_query = sqlx::query(include_str!("../database/queries/insert_comment.sql"))
+            .bind(xxh32(&[article_id.as_bytes(), comment.identifier.as_bytes()].concat(), 42))
+            .bind(comment.article_identifier)
+            .bind(comment.author)
+            .bind(comment.content)
+            .bind(comment.date);
+
+        match insert_comment_query.execute(&*pool).await {
+            Ok(_) => inserted = true,
+            Err(e) => match e.as_database_error() {
+                Some(database_error) => match database_error.code().unwrap_or_default().to_string().as_str() {
+                    "2067" | "1555" => {}
+                    _ => return Err(format!("Error executing query: {}", e)),
+                },
+                None => return Err(format!("Error executing query: {}", e)),
+            },
+        }
+    }
+
+    sqlx::query(include_str!("../database/queries/touch_watched_comments.sql"))
+        .bind(inserted)
+        .bind(article_id)
+        .execute(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use sqlx::{Pool, Sqlite, SqliteExecutor};
+use sqlx::types::Json;
+use tauri::{AppHandle, Manager};
+use url::Url;
+use crate::enums::session_state::SessionState;
+use crate::structs::feed_cookie::FeedCookie;
+use crate::structs::feed_session::FeedSession;
+use crate::structs::write_gate::WriteGate;
+use crate::secrets;
+use crate::utils::cookies::{parse_cookie_export, validate_cookie};
+
+/// Replaces the cookies sent with the feed, an empty list removing them. Their values are kept in the keychain.
+#[alduin_macros::command]
+pub async fn update_feed_cookies(feed_id: String, cookies: Vec<FeedCookie>, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    cookies.iter().try_for_each(validate_cookie)?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    if cookies.is_empty() {
+        sqlx::query(include_str!("../database/queries/delete_feed_session.sql"))
+            .bind(&feed_id)
+            .execute(&*pool).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+    } else {
+        // Stored first, so the row never refers to a secret that isn't there
+        let cookies_secret = secrets::store_json(&cookies).await?;
+        let names: Vec<&String> = cookies.iter().map(|cookie| &cookie.name).collect();
+        let saved = sqlx::query(include_str!("../database/queries/upsert_feed_session.sql"))
+            .bind(&feed_id)
+            .bind(Json(names))
+            .bind(&cookies_secret)
+            .execute(&*pool).await
+            .map(|_| ())
+            .map_err(|e| format!("Error executing query: {}", e));
+        secrets::forget_if_unsaved(Some(cookies_secret), saved).await?;
+    }
+
+    // The secrets replaced are left in the keychain when it can't be reached, and removed by a later write
+    if let Err(e) = secrets::forget_orphaned(&pool).await {
+        eprintln!("Error forgetting the replaced cookies: {}", e);
+    }
+    Ok(())
+}
+
+/// Extracts the cookies for the host of the feed from a pasted "Copy as cURL" command or cookies.txt export.
+/// Nothing is saved, the result is meant for `update_feed_cookies`.
+#[alduin_macros::command]
+pub async fn parse_feed_cookies(feed_id: String, input: String, app_handle: AppHandle) -> Result<Vec<FeedCookie>, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let url = sqlx::query_scalar::<_, String>(include_str!("../database/queries/get_feed_url.sql"))
+        .bind(&feed_id)
+        .fetch_optional(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .ok_or_else(|| format!("Feed {} not found", feed_id))?;
+
+    let host = Url::parse(&url).ok()
+        .and_then(|url| url.host_str().map(str::to_lowercase))
+        .ok_or_else(|| format!("Invalid feed URL: {}", url))?;
+
+    let cookies = parse_cookie_export(&input, &host, chrono::Utc::now().timestamp())?;
+    cookies.iter().try_for_each(validate_cookie)?;
+
+    Ok(cookies)
+}
+
+/// With the cookies read from the keychain, none when they can't be found there anymore.
+pub async fn load_feed_session(feed_id: &str, pool: &Pool<Sqlite>) -> Result<Option<FeedSession>, String> {
+    let row: Option<(String, SessionState)> = sqlx::query_as(include_str!("../database/queries/get_feed_session.sql"))
+        .bind(feed_id)
+        .fetch_optional(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    let Some((cookies_secret, state)) = row else { return Ok(None) };
+
+    let cookies = secrets::load_json(cookies_secret).await?.unwrap_or_default();
+    Ok(Some(FeedSession { cookies, state }))
+}
+
+pub async fn set_session_state<'e, E: SqliteExecutor<'e>>(feed_id: &str, state: SessionState, executor: E) -> Result<(), String> {
+    sqlx::query(include_str!("../database/queries/set_feed_session_state.sql"))
+        .bind(state)
+        .bind(feed_id)
+        .execute(executor).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(())
+}
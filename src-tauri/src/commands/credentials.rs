@@ -0,0 +1,135 @@
+use std::collections::BTreeMap;
+use reqwest::header::{HeaderName, HeaderValue, ACCEPT, CONNECTION, CONTENT_LENGTH, COOKIE, HOST, IF_MODIFIED_SINCE, IF_NONE_MATCH, TRANSFER_ENCODING};
+use sqlx::{Pool, Sqlite, SqliteConnection};
+use sqlx::types::Json;
+use tauri::{AppHandle, Manager};
+use crate::structs::feed_credentials::FeedCredentials;
+use crate::secrets;
+use crate::structs::write_gate::WriteGate;
+
+/// Set by the sync itself, or by the cookies of the feed.
+const RESERVED_HEADERS: [HeaderName; 8] = [HOST, ACCEPT, COOKIE, IF_NONE_MATCH, IF_MODIFIED_SINCE, CONNECTION, CONTENT_LENGTH, TRANSFER_ENCODING];
+
+/// Replaces the Basic auth sent with the feed, an empty username removing it. A feed keeps a row of
+/// credentials only while it has a username or headers.
+#[alduin_macros::command]
+pub async fn set_feed_credentials(feed_id: String, username: Option<String>, password: Option<String>, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let username = username.filter(|username| !username.is_empty());
+    if username.as_deref().is_some_and(|username| username.contains(':') || username.chars().any(char::is_control)) {
+        return Err(String::from("Invalid username: no colons or control characters"));
+    }
+    let password = username.as_ref().and(password);
+    // Stored first, so the row never refers to a secret that isn't there
+    let password_secret = match password {
+        Some(password) => Some(secrets::store(password).await?),
+        None => None,
+    };
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let saved = save_basic_auth(&feed_id, username.as_deref(), password_secret.as_deref(), &pool).await;
+    secrets::forget_if_unsaved(password_secret, saved).await?;
+    forget_replaced(&pool).await;
+    Ok(())
+}
+
+/// Replaces the extra headers sent with the feed, an empty map removing them. Same row as the Basic auth.
+#[alduin_macros::command]
+pub async fn set_feed_headers(feed_id: String, headers: BTreeMap<String, String>, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let mut normalized = BTreeMap::new();
+    for (name, value) in headers {
+        let name = validate_header(&name, &value)?;
+        normalized.insert(name.to_string(), value.trim().to_string());
+    }
+
+    let headers_secret = if normalized.is_empty() {
+        None
+    } else {
+        Some(secrets::store_json(&normalized).await?)
+    };
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let names: Vec<&String> = normalized.keys().collect();
+    let saved = save_headers(&feed_id, &names, headers_secret.as_deref(), &pool).await;
+    secrets::forget_if_unsaved(headers_secret, saved).await?;
+    forget_replaced(&pool).await;
+    Ok(())
+}
+
+/// With the password and the values of the headers read from the keychain.
+pub async fn load_feed_credentials(feed_id: &str, pool: &Pool<Sqlite>) -> Result<Option<FeedCredentials>, String> {
+    let row: Option<(Option<String>, Option<String>, Option<String>)> = sqlx::query_as(include_str!("../database/queries/get_feed_credentials.sql"))
+        .bind(feed_id)
+        .fetch_optional(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    let Some((username, password_secret, headers_secret)) = row else { return Ok(None) };
+
+    let password = match password_secret {
+        Some(reference) => secrets::load(reference).await?,
+        None => None,
+    };
+    let headers = match headers_secret {
+        Some(reference) => secrets::load_json(reference).await?.unwrap_or_default(),
+        None => BTreeMap::new(),
+    };
+
+    Ok(Some(FeedCredentials { username, password, headers }))
+}
+
+pub async fn save_basic_auth(feed_id: &str, username: Option<&str>, password_secret: Option<&str>, pool: &Pool<Sqlite>) -> Result<(), String> {
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+
+    sqlx::query(include_str!("../database/queries/upsert_feed_basic_auth.sql"))
+        .bind(feed_id)
+        .bind(username)
+        .bind(password_secret)
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    delete_if_empty(feed_id, &mut transaction).await?;
+    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))
+}
+
+pub async fn save_headers(feed_id: &str, names: &[&String], headers_secret: Option<&str>, pool: &Pool<Sqlite>) -> Result<(), String> {
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+
+    sqlx::query(include_str!("../database/queries/upsert_feed_headers.sql"))
+        .bind(feed_id)
+        .bind(Json(names))
+        .bind(headers_secret)
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    delete_if_empty(feed_id, &mut transaction).await?;
+    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))
+}
+
+/// The secrets replaced are left in the keychain when it can't be reached, and removed by a later write.
+async fn forget_replaced(pool: &Pool<Sqlite>) {
+    if let Err(e) = secrets::forget_orphaned(pool).await {
+        eprintln!("Error forgetting the replaced secrets: {}", e);
+    }
+}
+
+async fn delete_if_empty(feed_id: &str, connection: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(include_str!("../database/queries/delete_empty_feed_credentials.sql"))
+        .bind(feed_id)
+        .execute(connection).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(())
+}
+
+/// The header name, lowercased. The value isn't part of the error, it may be a secret.
+fn validate_header(name: &str, value: &str) -> Result<HeaderName, String> {
+    let header_name = HeaderName::try_from(name.trim()).map_err(|_| format!("Invalid header name: {}", name))?;
+    if RESERVED_HEADERS.contains(&header_name) {
+        return Err(format!("The {} header can't be replaced", header_name));
+    }
+    HeaderValue::try_from(value.trim()).map_err(|_| format!("Invalid value for the {} header", header_name))?;
+
+    Ok(header_name)
+}
@@ -0,0 +1,326 @@
+use std::collections::HashSet;
+use sqlx::{Pool, Sqlite, SqliteConnection};
+use tauri::{AppHandle, Manager};
+use crate::commands::fetcher::article_identifier;
+use crate::enums::dedup_strategy::DedupStrategy;
+use crate::structs::article::Article;
+use crate::structs::dedup_candidate::DedupCandidate;
+use crate::structs::feed_dedup::FeedDedup;
+use crate::utils::dedup::{dedup_key, has_unstable_guids};
+use crate::structs::write_gate::WriteGate;
+
+#[alduin_macros::command]
+pub async fn get_dedup_strategy(feed_id: String, app_handle: AppHandle) -> Result<FeedDedup, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    get_feed_dedup(&feed_id, &pool).await
+}
+
+/// Forces the dedup strategy of a feed, or gives the choice back to the automatic detection when `None`.
+#[alduin_macros::command]
+pub async fn set_dedup_strategy(feed_id: String, strategy: Option<DedupStrategy>, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+
+    match strategy {
+        Some(DedupStrategy::LinkTitle) => switch_to_link_title(&feed_id, true, &pool).await,
+        Some(DedupStrategy::Guid) => set_feed_dedup(&feed_id, DedupStrategy::Guid, true, &pool).await,
+        None => set_feed_dedup(&feed_id, DedupStrategy::Guid, false, &pool).await,
+    }
+}
+
+pub async fn get_feed_dedup(feed_id: &str, pool: &Pool<Sqlite>) -> Result<FeedDedup, String> {
+    Ok(sqlx::query_as::<_, FeedDedup>(include_str!("../database/queries/get_feed_dedup.sql"))
+        .bind(feed_id)
+        .fetch_optional(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .unwrap_or_default())
+}
+
+pub async fn known_articles(feed_id: &str, pool: &Pool<Sqlite>) -> Result<Vec<DedupCandidate>, String> {
+    sqlx::query_as::<_, DedupCandidate>(include_str!("../database/queries/get_feed_dedup_keys.sql"))
+        .bind(feed_id)
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// Of the articles deleted by a prune, which link and title dedup keeps skipping.
+pub async fn pruned_dedup_keys(feed_id: &str, pool: &Pool<Sqlite>) -> Result<Vec<u32>, String> {
+    sqlx::query_scalar::<_, u32>(include_str!("../database/queries/get_pruned_dedup_keys.sql"))
+        .bind(feed_id)
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// Drops the articles of a fetch already stored under another GUID. A feed following the automatic
+/// detection is switched to link and title dedup first when most of them are, or when it changed format.
+/// Returns the articles left with their dedup key, and whether the feed was switched.
+pub async fn dedup_fetch(feed_id: &str, articles: Vec<Article>, format_change: bool, pool: &Pool<Sqlite>) -> Result<(Vec<(Article, Option<u32>)>, bool), String> {
+    let mut dedup = get_feed_dedup(feed_id, pool).await?;
+    let known = known_articles(feed_id, pool).await?;
+    // The stored keys of the articles not backfilled yet were computed before titles were cleaned
+    let mut known_keys: HashSet<u32> = known.iter()
+        .flat_map(|article| [article.dedup_key, dedup_key(article.link.as_deref(), &article.title)])
+        .flatten()
+        .collect();
+    known_keys.extend(pruned_dedup_keys(feed_id, pool).await?);
+
+    let mut switched = false;
+    if dedup.strategy == DedupStrategy::Guid && !dedup.locked {
+        let known_identifiers: HashSet<&str> = known.iter().map(|article| article.identifier.as_str()).collect();
+        let rotated = articles.iter()
+            .filter(|article| {
+                let identifier = article_identifier(feed_id, &article.id).to_string();
+                !known_identifiers.contains(identifier.as_str())
+                    && dedup_key(article.link.as_deref(), &article.original_title).is_some_and(|key| known_keys.contains(&key))
+            })
+            .count();
+
+        if format_change || has_unstable_guids(rotated, articles.len()) {
+            switch_to_link_title(feed_id, false, pool).await?;
+            dedup.strategy = DedupStrategy::LinkTitle;
+            switched = true;
+        }
+    }
+
+    let articles = articles.into_iter()
+        .filter_map(|article| {
+            let key = dedup_key(article.link.as_deref(), &article.original_title);
+            // insert() also catches the same article being sent twice in one fetch
+            if dedup.strategy == DedupStrategy::LinkTitle && key.is_some_and(|key| !known_keys.insert(key)) {
+                return None;
+            }
+            Some((article, key))
+        })
+        .collect();
+    Ok((articles, switched))
+}
+
+/// Switches a feed to link and title dedup and merges the duplicates it already created,
+/// keeping the first copy of each article and marking it read when any copy was read.
+pub async fn switch_to_link_title(feed_id: &str, locked: bool, pool: &Pool<Sqlite>) -> Result<(), String> {
+    let articles = known_articles(feed_id, pool).await?;
+
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+
+    // Articles inserted before the key existed
+    for article in articles.iter().filter(|article| article.dedup_key.is_none()) {
+        if let Some(key) = dedup_key(article.link.as_deref(), &article.title) {
+            sqlx::query(include_str!("../database/queries/set_article_dedup_key.sql"))
+                .bind(key)
+                .bind(article.rowid)
+                .execute(&mut *transaction).await
+                .map_err(|e| format!("Error executing query: {}", e))?;
+        }
+    }
+
+    merge_duplicates(feed_id, &mut transaction).await?;
+
+    sqlx::query(include_str!("../database/queries/set_feed_dedup.sql"))
+        .bind(DedupStrategy::LinkTitle)
+        .bind(locked)
+        .bind(feed_id)
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))
+}
+
+/// Keeps the first copy of the articles of the feed sharing a dedup key, read when any copy was read.
+pub async fn merge_duplicates(feed_id: &str, connection: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(include_str!("../database/queries/merge_duplicate_read_state.sql"))
+        .bind(feed_id)
+        .execute(&mut *connection).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    sqlx::query(include_str!("../database/queries/delete_duplicate_articles.sql"))
+        .bind(feed_id)
+        .execute(&mut *connection).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(())
+}
+
+async fn set_feed_dedup(feed_id: &str, strategy: DedupStrategy, locked: bool, pool: &Pool<Sqlite>) -> Result<(), String> {
+    sqlx::query(include_str!("../database/queries/set_feed_dedup.sql"))
+        .bind(strategy)
+        .bind(locked)
+        .bind(feed_id)
+        .execute(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::commands::fetcher::prepare_article;
+    use crate::database::article_writer::insert_article;
+    use crate::database::migrator::migrator;
+    use crate::enums::feed_type::FeedType;
+    use crate::utils::dedup::changed_format;
+    use crate::utils::title::display_title;
+    use super::*;
+
+    const FEED: &str = "daily";
+
+    async fn fixture() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval) VALUES (?, ?, 'https://daily.example/feed', 60)")
+            .bind(FEED).bind(FEED)
+            .execute(&pool).await.unwrap();
+        pool
+    }
+
+    /// The items of the feed on `day` as (slug, title), each under a new GUID that day.
+    fn rotated_fetch(items: &[(&str, &str)], day: u32) -> Vec<Article> {
+        let items: String = items.iter()
+            .map(|(slug, title)| format!(
+                "<item><guid isPermaLink=\"false\">{slug}-{day}</guid><link>https://daily.example/{slug}</link><title>{title}</title><description>{title}</description></item>",
+            ))
+            .collect();
+        let document = format!("<rss version=\"2.0\"><channel><title>Daily</title><link>https://daily.example/</link><description>Daily</description>{}</channel></rss>", items);
+
+        feed_rs::parser::parse(document.as_bytes()).unwrap().entries.into_iter()
+            .map(|entry| {
+                let mut article = Article::from(entry);
+                article.title = display_title(&article.original_title, &[]);
+                article
+            })
+            .collect()
+    }
+
+    /// The items as (slug, title) in one of the formats the feed is negotiated in, each with its GUID convention.
+    fn negotiated_fetch(format: FeedType, items: &[(&str, &str)]) -> (&'static str, Vec<Article>) {
+        let document = match format {
+            FeedType::RSS => format!(
+                "<rss version=\"2.0\"><channel><title>Daily</title><link>https://daily.example/</link><description>Daily</description>{}</channel></rss>",
+                items.iter().map(|(slug, title)| format!("<item><guid>https://daily.example/?p={slug}</guid><link>https://daily.example/{slug}</link><title>{title}</title><description>{title}</description></item>")).collect::<String>(),
+            ),
+            FeedType::Atom => format!(
+                "<feed xmlns=\"http://www.w3.org/2005/Atom\"><title>Daily</title><id>urn:daily</id><updated>2026-01-01T00:00:00Z</updated>{}</feed>",
+                items.iter().map(|(slug, title)| format!("<entry><id>tag:daily.example,2026:{slug}</id><link href=\"https://daily.example/{slug}\"/><title>{title}</title><updated>2026-01-01T00:00:00Z</updated><summary>{title}</summary></entry>")).collect::<String>(),
+            ),
+            FeedType::JSON => format!(
+                "{{\"version\": \"https://jsonfeed.org/version/1.1\", \"title\": \"Daily\", \"items\": [{}]}}",
+                items.iter().map(|(slug, title)| format!("{{\"id\": \"{slug}\", \"url\": \"https://daily.example/{slug}\", \"title\": \"{title}\", \"content_text\": \"{title}\"}}")).collect::<Vec<String>>().join(", "),
+            ),
+        };
+
+        let feed = feed_rs::parser::parse(document.as_bytes()).unwrap();
+        let articles = feed.entries.into_iter()
+            .map(|entry| {
+                let mut article = Article::from(entry);
+                article.title = display_title(&article.original_title, &[]);
+                article
+            })
+            .collect();
+        (FeedType::from(feed.feed_type).as_str(), articles)
+    }
+
+    /// Stores a fetch like a sync does, returning whether the feed was switched to link and title dedup.
+    async fn ingest(articles: Vec<Article>, format_change: bool, pool: &Pool<Sqlite>) -> bool {
+        let (articles, switched) = dedup_fetch(FEED, articles, format_change, pool).await.unwrap();
+        let mut connection = pool.acquire().await.unwrap();
+        for (article, key) in articles {
+            insert_article(FEED, prepare_article(FEED, article, key, &[], &[]), &mut connection).await.unwrap();
+        }
+        switched
+    }
+
+    /// Every stored article as (link, read).
+    async fn stored(pool: &Pool<Sqlite>) -> Vec<(String, bool)> {
+        sqlx::query_as("SELECT link, read FROM articles WHERE feed_identifier = ? ORDER BY link, rowid")
+            .bind(FEED)
+            .fetch_all(pool).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn replays_three_days_of_rotated_guids() {
+        let pool = fixture().await;
+
+        // Two items say too little about the GUIDs, the second day is stored twice
+        assert!(!ingest(rotated_fetch(&[("a", "Alpha"), ("b", "Beta")], 1), false, &pool).await);
+        sqlx::query("UPDATE articles SET read = 1 WHERE link = 'https://daily.example/a'").execute(&pool).await.unwrap();
+        assert!(!ingest(rotated_fetch(&[("a", "Alpha"), ("b", "Beta")], 2), false, &pool).await);
+        assert_eq!(stored(&pool).await.len(), 4);
+
+        // Most of the third day is known by link and title, which merges the copies of the second
+        assert!(ingest(rotated_fetch(&[("a", "Alpha"), ("b", "Beta"), ("c", "Gamma")], 3), false, &pool).await);
+        assert_eq!(stored(&pool).await, [
+            (String::from("https://daily.example/a"), true),
+            (String::from("https://daily.example/b"), false),
+            (String::from("https://daily.example/c"), false),
+        ]);
+        assert_eq!(get_feed_dedup(FEED, &pool).await.unwrap().strategy, DedupStrategy::LinkTitle);
+
+        // Switched, the next days only add the items that are new
+        assert!(!ingest(rotated_fetch(&[("a", "Alpha"), ("c", "Gamma"), ("d", "Delta")], 4), false, &pool).await);
+        let links: Vec<String> = stored(&pool).await.into_iter().map(|(link, _)| link).collect();
+        assert_eq!(links, ["https://daily.example/a", "https://daily.example/b", "https://daily.example/c", "https://daily.example/d"]);
+    }
+
+    #[tokio::test]
+    async fn keeps_the_strategy_chosen_by_the_user() {
+        let pool = fixture().await;
+        sqlx::query("UPDATE feeds SET dedup_strategy = 'guid', dedup_strategy_locked = 1").execute(&pool).await.unwrap();
+
+        let items = [("a", "Alpha"), ("b", "Beta"), ("c", "Gamma")];
+        for day in 1..=3 {
+            assert!(!ingest(rotated_fetch(&items, day), false, &pool).await);
+        }
+
+        assert_eq!(stored(&pool).await.len(), 9);
+    }
+
+    #[tokio::test]
+    async fn replays_alternating_formats() {
+        let pool = fixture().await;
+        let two = [("a", "Alpha"), ("b", "Beta")];
+        let three = [("a", "Alpha"), ("b", "Beta"), ("c", "Gamma")];
+        // As (format, items, articles stored after the fetch)
+        let fetches = [
+            (FeedType::Atom, &two[..], 2),
+            (FeedType::JSON, &two[..], 2),
+            (FeedType::Atom, &three[..], 3),
+            (FeedType::RSS, &three[..], 3),
+            (FeedType::JSON, &three[..], 3),
+            (FeedType::Atom, &two[..], 3),
+        ];
+
+        let mut previous: Option<&str> = None;
+        for (sync, (format, items, expected)) in fetches.into_iter().enumerate() {
+            let (feed_type, articles) = negotiated_fetch(format, items);
+            let format_change = changed_format(previous, feed_type, sync == 0);
+            assert_eq!(format_change.is_some(), sync > 0, "{}", feed_type);
+
+            // A format change is enough, even below the items needed to tell rotating GUIDs
+            assert_eq!(ingest(articles, format_change.is_some(), &pool).await, sync == 1);
+            previous = Some(feed_type);
+
+            assert_eq!(stored(&pool).await.len(), expected, "after the {} fetch", feed_type);
+        }
+
+        // Articles keep the identifier of the format they were first seen in
+        let identifiers: Vec<String> = sqlx::query_scalar("SELECT identifier FROM articles WHERE link = 'https://daily.example/a'")
+            .fetch_all(&pool).await.unwrap();
+        assert_eq!(identifiers, [article_identifier(FEED, "tag:daily.example,2026:a").to_string()]);
+    }
+
+    #[test]
+    fn only_changed_formats_count() {
+        let cases = [
+            (None, "rss", true, None),
+            (None, "rss", false, None),
+            (Some("atom"), "rss", true, None),
+            (Some("atom"), "atom", false, None),
+            (Some("atom"), "json", false, Some("atom")),
+        ];
+
+        for (previous, current, first_sync, expected) in cases {
+            assert_eq!(changed_format(previous, current, first_sync).as_deref(), expected, "{:?} {}", previous, current);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::commands::fetcher::fetch_feed;
+use crate::commands::webview::load_webview_capabilities;
+use crate::enums::address_family::AddressFamily;
+use crate::enums::enclosure_role::EnclosureRole;
+use crate::parsers::transcript::{detect_format, parse_transcript};
+use crate::structs::enclosure::Enclosure;
+use crate::structs::read_pool::ReadPool;
+use crate::structs::transcript::Transcript;
+use crate::structs::write_gate::WriteGate;
+use crate::utils::capabilities::is_playable;
+
+/// Media enclosures are annotated with whether the webview can play them, when it has been probed.
+#[alduin_macros::command]
+pub async fn get_article_enclosures(article_id: String, app_handle: AppHandle) -> Result<Vec<Enclosure>, String> {
+    let pool = app_handle.state::<ReadPool>();
+    let mut enclosures = load_enclosures(&article_id, &pool).await?;
+
+    if let Some(capabilities) = load_webview_capabilities(&app_handle).await? {
+        for enclosure in enclosures.iter_mut().filter(|enclosure| enclosure.role == EnclosureRole::Media) {
+            enclosure.playable = is_playable(&capabilities, enclosure.mime_type.as_deref(), &enclosure.url);
+        }
+    }
+
+    Ok(enclosures)
+}
+
+/// Returns the transcript of an article, downloading it on first access. When several are attached,
+/// the timed formats are preferred. The download is not cached while writes are frozen.
+#[alduin_macros::command]
+pub async fn get_article_transcript(article_id: String, app_handle: AppHandle) -> Result<Option<Transcript>, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+
+    let cached = sqlx::query_as::<_, Transcript>(include_str!("../database/queries/get_article_transcript.sql"))
+        .bind(&article_id)
+        .fetch_optional(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    if cached.is_some() {
+        return Ok(cached);
+    }
+
+    let transcript_enclosure = load_enclosures(&article_id, &pool).await?.into_iter()
+        .filter(|enclosure| enclosure.role == EnclosureRole::Transcript)
+        .min_by_key(|enclosure| detect_format(enclosure.mime_type.as_deref(), &enclosure.url));
+    let Some(enclosure) = transcript_enclosure else { return Ok(None) };
+
+    let content = fetch_feed(enclosure.url.clone(), AddressFamily::Auto, app_handle.clone()).await?;
+    let transcript = parse_transcript(&content, enclosure.mime_type.as_deref(), &enclosure.url)?;
+
+    if let Ok(_writing) = app_handle.state::<WriteGate>().enter() {
+        sqlx::query(include_str!("../database/queries/insert_article_transcript.sql"))
+            .bind(&article_id)
+            .bind(transcript.format)
+            .bind(&transcript.mime_type)
+            .bind(&transcript.segments)
+            .bind(&transcript.raw)
+            .execute(&*pool).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+    }
+
+    Ok(Some(transcript))
+}
+
+async fn load_enclosures(article_id: &str, pool: &Pool<Sqlite>) -> Result<Vec<Enclosure>, String> {
+    sqlx::query_as::<_, Enclosure>(include_str!("../database/queries/get_article_enclosures.sql"))
+        .bind(article_id)
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
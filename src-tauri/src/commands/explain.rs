@@ -0,0 +1,418 @@
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::commands::articles::BACKFILLED_AGE_MS;
+use crate::commands::scoring::{load_feed_folders, load_scoring_rules};
+use crate::enums::article_sort::ArticleSort;
+use crate::enums::dedup_strategy::DedupStrategy;
+use crate::enums::explanation_kind::ExplanationKind;
+use crate::enums::language::Language;
+use crate::enums::read_state::ReadState;
+use crate::enums::timestamp_style::TimestampStyle;
+use crate::scoring::evaluator::CompiledRule;
+use crate::structs::article_cursor::ArticleCursor;
+use crate::structs::article_filter::ArticleFilter;
+use crate::structs::explained_article::ExplainedArticle;
+use crate::structs::explanation_factor::ExplanationFactor;
+use crate::structs::read_pool::ReadPool;
+use crate::structs::scoring_subject::ScoringSubject;
+use crate::structs::stored_article::StoredArticle;
+use crate::utils::time_format::format_timestamp;
+
+/// Copies of the article listed at most, the others being counted.
+const MAX_DEDUP_GROUP: i64 = 10;
+
+/// Why the article is shown, hidden or ordered as it is in the view of `context_filter`, the timeline
+/// when left out: the filters of the view, its score and the scoring rules adding to it, how it is told
+/// apart from copies of itself, its read state and its position under the sort of the view.
+/// The rules are evaluated again against the stored article, the stored score telling what they gave last.
+#[alduin_macros::command]
+pub async fn explain_article(article_id: String, context_filter: Option<ArticleFilter>, app_handle: AppHandle) -> Result<Vec<ExplanationFactor>, String> {
+    let pool = app_handle.state::<ReadPool>();
+    explain(&article_id, context_filter.unwrap_or_default(), &pool).await
+}
+
+pub async fn explain(article_id: &str, filter: ArticleFilter, pool: &Pool<Sqlite>) -> Result<Vec<ExplanationFactor>, String> {
+    let explained = sqlx::query_as::<_, ExplainedArticle>(include_str!("../database/queries/get_explained_article.sql"))
+        .bind(article_id)
+        .bind(BACKFILLED_AGE_MS)
+        .fetch_optional(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .ok_or_else(|| format!("Unknown article: {}", article_id))?;
+    let article = &explained.article;
+
+    let mut factors = vec![];
+    let feed_name = explained.feed_name.clone().unwrap_or_else(|| article.feed_identifier.clone());
+    let feed_factor = ExplanationFactor::new(ExplanationKind::Feed, format!("From {}", feed_name));
+    factors.push(match explained.feed_archived {
+        Some(true) => feed_factor.with_details(vec![String::from("The feed is archived, it isn't synced anymore")]),
+        Some(false) => feed_factor,
+        None => feed_factor.with_details(vec![String::from("The feed was removed since")]),
+    });
+
+    factors.extend(filter_factors(article, &filter));
+    factors.extend(score_factors(&explained, pool).await?);
+    factors.push(dedup_factor(&explained, pool).await?);
+    factors.push(read_state_factor(article));
+    factors.extend(explained.post_processors.iter().map(|name| {
+        ExplanationFactor::new(ExplanationKind::PostProcessor, format!("Processed by {}", name))
+            .with_details(vec![String::from("It changed the content as synced, before the scoring rules were applied")])
+    }));
+
+    if !factors.iter().any(|factor| factor.hides) {
+        factors.push(position_factor(article, &filter, pool).await?);
+    }
+
+    Ok(factors)
+}
+
+/// The filters of the view, each one either keeping or hiding the article.
+fn filter_factors(article: &StoredArticle, filter: &ArticleFilter) -> Vec<ExplanationFactor> {
+    let mut factors = vec![];
+
+    if let Some(feed_identifier) = &filter.feed_identifier {
+        let kept = *feed_identifier == article.feed_identifier;
+        let label = if kept { "In the feed shown" } else { "Not in the feed shown" };
+        factors.push(ExplanationFactor::new(ExplanationKind::Filter, String::from(label)).with_hides(!kept));
+    }
+
+    if let Some(min_score) = filter.min_score {
+        let kept = article.score >= min_score;
+        let label = if kept {
+            format!("Score {} reaches the minimum of {}", article.score, min_score)
+        } else {
+            format!("Score {} is below the minimum of {}", article.score, min_score)
+        };
+        factors.push(ExplanationFactor::new(ExplanationKind::Filter, label).with_hides(!kept));
+    }
+
+    if let Some(read_state) = filter.read_state {
+        let (kept, label) = match read_state {
+            ReadState::Unread => (!article.read || article.reread_requested, "Only the unread articles are shown"),
+            ReadState::Read => (article.read && !article.reread_requested, "Only the read articles are shown"),
+            ReadState::UpdatedSinceRead => (article.updated_since_read, "Only the articles updated since read are shown"),
+        };
+        factors.push(ExplanationFactor::new(ExplanationKind::Filter, String::from(label)).with_hides(!kept));
+    }
+
+    factors
+}
+
+/// The score, then every rule with how each of its conditions held. Rules are listed even when they
+/// don't match, a rule that can't compile anymore being reported rather than failing the explanation.
+async fn score_factors(explained: &ExplainedArticle, pool: &Pool<Sqlite>) -> Result<Vec<ExplanationFactor>, String> {
+    let article = &explained.article;
+    let folders = load_feed_folders(Some(&article.feed_identifier), pool).await?
+        .remove(&article.feed_identifier)
+        .unwrap_or_default();
+    let subject = ScoringSubject {
+        feed_identifier: &article.feed_identifier,
+        folders: &folders,
+        language: explained.language.as_deref(),
+        title: &article.title,
+        content: &article.content,
+    };
+    let mut rule_factors = vec![];
+    let mut score = 0;
+
+    for rule in load_scoring_rules(pool).await? {
+        let compiled = match CompiledRule::compile(&rule) {
+            Ok(compiled) => compiled,
+            Err(e) => {
+                rule_factors.push(ExplanationFactor::new(ExplanationKind::ScoringRule, format!("Rule {} is ignored", rule.identifier))
+                    .with_details(vec![e])
+                    .with_applies(false));
+                continue;
+            }
+        };
+
+        let outcomes = compiled.explain(&subject);
+        let matched = outcomes.iter().all(|outcome| outcome.matched);
+        if matched {
+            score += compiled.weight();
+        }
+        let label = match matched {
+            true => format!("Rule {} adds {:+}", rule.identifier, compiled.weight()),
+            false => format!("Rule {} doesn't match, it would add {:+}", rule.identifier, compiled.weight()),
+        };
+        let details = outcomes.into_iter()
+            .map(|outcome| format!("{}: {}", if outcome.matched { "Holds" } else { "Doesn't hold" }, outcome.description))
+            .collect();
+        rule_factors.push(ExplanationFactor::new(ExplanationKind::ScoringRule, label).with_details(details).with_applies(matched));
+    }
+
+    let details = if score == article.score {
+        vec![]
+    } else {
+        // Rules are applied at ingest and to every article once changed, this is the time in between
+        vec![format!("The current rules give {}, the articles are being scored again", score)]
+    };
+    let mut factors = vec![ExplanationFactor::new(ExplanationKind::Score, format!("Score {}", article.score)).with_details(details)];
+    factors.extend(rule_factors);
+    Ok(factors)
+}
+
+/// How syncs recognize the article, and the other articles of the feed with its link and title.
+async fn dedup_factor(explained: &ExplainedArticle, pool: &Pool<Sqlite>) -> Result<ExplanationFactor, String> {
+    let article = &explained.article;
+    let strategy = explained.dedup_strategy.unwrap_or_default();
+    let Some(dedup_key) = explained.dedup_key else {
+        return Ok(ExplanationFactor::new(ExplanationKind::Dedup, String::from("Recognized by its GUID"))
+            .with_details(vec![String::from("It has no usable link, so it can't be matched with copies by link and title")]));
+    };
+
+    let copies = sqlx::query_as::<_, (String, String)>(include_str!("../database/queries/get_dedup_group.sql"))
+        .bind(&article.feed_identifier)
+        .bind(dedup_key)
+        .bind(&article.identifier)
+        .bind(MAX_DEDUP_GROUP)
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    let pruned = sqlx::query_scalar::<_, i64>(include_str!("../database/queries/count_pruned_dedup_key.sql"))
+        .bind(&article.feed_identifier)
+        .bind(dedup_key)
+        .fetch_one(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    let mut details = vec![];
+    match strategy {
+        DedupStrategy::Guid => details.push(String::from("The feed keeps stable GUIDs, copies with another GUID are stored apart")),
+        DedupStrategy::LinkTitle => details.push(String::from("The feed rotates its GUIDs, later copies with the same link and title are skipped")),
+    }
+    if !copies.is_empty() {
+        details.push(match strategy {
+            DedupStrategy::Guid => format!("{} other article(s) of the feed share its link and title", copies.len()),
+            DedupStrategy::LinkTitle => format!("{} other article(s) of the feed share its link and title, stored before the feed was matched this way", copies.len()),
+        });
+        details.extend(copies.into_iter().map(|(identifier, title)| format!("{} ({})", title, identifier)));
+    }
+    if pruned > 0 {
+        details.push(format!("{} pruned article(s) shared its link and title, their copies are skipped too", pruned));
+    }
+
+    let label = match strategy {
+        DedupStrategy::Guid => "Recognized by its GUID",
+        DedupStrategy::LinkTitle => "Recognized by its link and title",
+    };
+    Ok(ExplanationFactor::new(ExplanationKind::Dedup, String::from(label)).with_details(details))
+}
+
+fn read_state_factor(article: &StoredArticle) -> ExplanationFactor {
+    let label = match (article.read, article.reread_requested) {
+        (_, true) => "Unread again, updated since read in a feed asking to reread updates",
+        (true, false) => "Read",
+        (false, false) => "Unread",
+    };
+
+    let mut details = vec![];
+    if let Some(read_at) = article.read_at {
+        details.push(format!("Last read {}", local_time(read_at)));
+    }
+    if article.updated_since_read {
+        details.push(format!("Updated by a sync {}", local_time(article.content_updated_at.unwrap_or_default())));
+    }
+    ExplanationFactor::new(ExplanationKind::ReadState, String::from(label)).with_details(details)
+}
+
+/// The rank among the articles of the view, with what the sort compares.
+async fn position_factor(article: &StoredArticle, filter: &ArticleFilter, pool: &Pool<Sqlite>) -> Result<ExplanationFactor, String> {
+    let cursor = ArticleCursor::after(article);
+    let count = |cursor: Option<&ArticleCursor>| {
+        sqlx::query_scalar::<_, i64>(include_str!("../database/queries/count_articles_ahead.sql"))
+            .bind(&filter.feed_identifier)
+            .bind(filter.min_score)
+            .bind(filter.sort.as_str())
+            .bind(cursor.map(|cursor| cursor.sort_key(filter.sort)))
+            .bind(cursor.map(|cursor| cursor.date))
+            .bind(cursor.map(|cursor| cursor.identifier.clone()))
+            .bind(filter.read_state.map(|state| state.as_str()))
+            .fetch_one(pool)
+    };
+    let ahead = count(Some(&cursor)).await.map_err(|e| format!("Error executing query: {}", e))?;
+    let total = count(None).await.map_err(|e| format!("Error executing query: {}", e))?;
+
+    let mut details = vec![match filter.sort {
+        ArticleSort::Date => format!("Sorted by publication date, newest first. Published {}", local_time(article.date)),
+        ArticleSort::Score => format!("Sorted by score, highest first, then by publication date. Score {}, published {}", article.score, local_time(article.date)),
+        ArticleSort::FirstSeen => format!("Sorted by when Alduin first stored them, newest first. First seen {}", local_time(article.first_seen.unwrap_or(article.date))),
+    }];
+    if article.backfilled {
+        details.push(match filter.sort {
+            ArticleSort::FirstSeen => String::from("Published long before it was first seen, from the archive of a newly added feed, so placed by when it was first seen"),
+            _ => String::from("Published long before it was first seen, from the archive of a newly added feed"),
+        });
+    }
+
+    Ok(ExplanationFactor::new(ExplanationKind::Position, format!("Position {} of {}", ahead + 1, total)).with_details(details))
+}
+
+/// In English like the rest of the explanations.
+fn local_time(timestamp_ms: i64) -> String {
+    format_timestamp(timestamp_ms, TimestampStyle::DateTime, Language::English)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::commands::articles::{load_articles, mark_read};
+    use crate::commands::fetcher::prepare_article;
+    use crate::database::article_writer::insert_article;
+    use crate::database::migrator::migrator;
+    use crate::scoring::evaluator::compile_rules;
+    use crate::structs::article::Article;
+    use crate::utils::dedup::dedup_key;
+    use super::*;
+
+    const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+    /// Feed, GUID, title, content, language and link, then the rules expected to match.
+    type Engineered = (&'static str, &'static str, &'static str, &'static str, Option<&'static str>, Option<&'static str>, &'static [&'static str]);
+
+    const ARTICLES: [Engineered; 6] = [
+        ("blog", "1", "Rust 2.0 released", "A new edition of the language", Some("en-GB"), Some("https://blog.example/rust"), &["keyword", "language", "nested-folder"]),
+        ("blog", "2", "Gardening", "Too short", Some("fr"), Some("https://blog.example/garden"), &[]),
+        ("blog", "3", "Weekly notes", "Three words here", None, None, &["nested-folder"]),
+        ("news", "4", "Release of the week", "Anything", Some("en"), Some("https://news.example/release"), &["feed-regex", "language"]),
+        // Copies of the same story under rotated GUIDs
+        ("news", "5", "Rust news", "The same story", None, Some("https://news.example/story"), &["keyword"]),
+        ("news", "6", "Rust news", "The same story again", None, Some("http://www.news.example/story/"), &["keyword"]),
+    ];
+
+    /// Rules and folders deciding the scores, then the articles ingested like a sync does.
+    async fn fixture() -> (Pool<Sqlite>, BTreeMap<String, (i64, &'static [&'static str])>) {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        sqlx::query("
+            INSERT INTO folders (identifier, name, parent_identifier) VALUES ('reading', 'Reading', NULL), ('tech', 'Tech', 'reading');
+            INSERT INTO feeds (identifier, display_name, url, interval, folder_identifier) VALUES ('blog', 'Blog', '', 60, 'tech');
+            INSERT INTO feeds (identifier, display_name, url, interval, dedup_strategy) VALUES ('news', 'News', '', 60, 'link_title');
+            INSERT INTO scoring_rules (identifier, conditions, weight) VALUES
+                ('keyword', '[{\"type\":\"keyword\",\"value\":\"rust\"}]', 5),
+                ('nested-folder', '[{\"type\":\"folder\",\"value\":\"reading\"},{\"type\":\"minWords\",\"value\":3}]', 2),
+                ('language', '[{\"type\":\"language\",\"value\":\"en\"}]', -1),
+                ('feed-regex', '[{\"type\":\"feed\",\"value\":\"news\"},{\"type\":\"regex\",\"value\":\"(?i)^release\"}]', 10);
+        ").execute(&pool).await.unwrap();
+
+        let rules = compile_rules(&load_scoring_rules(&pool).await.unwrap()).unwrap();
+        let folders = load_feed_folders(None, &pool).await.unwrap();
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut ingested = BTreeMap::new();
+        for (index, (feed, guid, title, content, language, link, matching)) in ARTICLES.into_iter().enumerate() {
+            let article = Article {
+                id: guid.to_string(),
+                title: title.to_string(),
+                original_title: title.to_string(),
+                content: content.to_string(),
+                date: now - index as i64 * DAY_MS,
+                read: false,
+                image: None,
+                comments_url: None,
+                link: link.map(str::to_string),
+                language: language.map(str::to_string),
+                enclosures: vec![],
+                post_processors: vec![],
+                extras: BTreeMap::new(),
+            };
+            let prepared = prepare_article(feed, article, dedup_key(link, title), &rules, folders.get(feed).map(Vec::as_slice).unwrap_or_default());
+            ingested.insert(prepared.identifier.to_string(), (prepared.score, matching));
+            insert_article(feed, prepared, &mut pool.acquire().await.unwrap()).await.unwrap();
+        }
+        (pool, ingested)
+    }
+
+    fn labels(factors: &[ExplanationFactor], kind: &str) -> Vec<String> {
+        factors.iter()
+            .filter(|factor| format!("{:?}", factor.kind) == kind)
+            .map(|factor| factor.label.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn matches_the_ingest_time_decisions() {
+        let (pool, ingested) = fixture().await;
+
+        for (identifier, (score, matching)) in ingested.iter() {
+            let factors = explain(identifier, ArticleFilter::default(), &pool).await.unwrap();
+
+            let score_factor = factors.iter().find(|factor| matches!(factor.kind, ExplanationKind::Score)).unwrap();
+            assert_eq!(score_factor.label, format!("Score {}", score), "{}", identifier);
+            // The rules gave the same score at ingest
+            assert!(score_factor.details.is_empty(), "{}: {:?}", identifier, score_factor);
+
+            let rules: Vec<&ExplanationFactor> = factors.iter().filter(|factor| matches!(factor.kind, ExplanationKind::ScoringRule)).collect();
+            assert_eq!(rules.len(), 4, "{}", identifier);
+            let applying: Vec<&str> = rules.iter()
+                .filter(|rule| rule.applies)
+                .map(|rule| rule.label.split(' ').nth(1).unwrap())
+                .collect();
+            let mut expected = matching.to_vec();
+            expected.sort();
+            let mut applying_sorted = applying.clone();
+            applying_sorted.sort();
+            assert_eq!(applying_sorted, expected, "{}", identifier);
+            // Every condition of a matching rule holds, a rule not matching has one that doesn't
+            for rule in rules.iter() {
+                let holding = rule.details.iter().filter(|detail| detail.starts_with("Holds")).count();
+                assert_eq!(holding == rule.details.len(), rule.applies, "{}: {:?}", identifier, rule);
+            }
+            assert!(!factors.iter().any(|factor| factor.hides), "{}", identifier);
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_what_hides_and_orders_the_article() {
+        let (pool, ingested) = fixture().await;
+        let identifier = |guid: &str| {
+            let feed = ARTICLES.iter().find(|article| article.1 == guid).unwrap().0;
+            crate::commands::fetcher::article_identifier(feed, guid).to_string()
+        };
+        let rust = identifier("1");
+
+        // Where the list itself puts it
+        let page = load_articles(ArticleFilter::default(), 50, &pool).await.unwrap();
+        let position = page.articles.iter().position(|article| article.identifier == rust).unwrap();
+        let factors = explain(&rust, ArticleFilter::default(), &pool).await.unwrap();
+        assert_eq!(labels(&factors, "Position"), [format!("Position {} of {}", position + 1, ingested.len())]);
+        assert_eq!(labels(&factors, "Feed"), ["From Blog"]);
+
+        let hidden = [
+            ArticleFilter { feed_identifier: Some(String::from("news")), ..ArticleFilter::default() },
+            ArticleFilter { min_score: Some(7), ..ArticleFilter::default() },
+            ArticleFilter { read_state: Some(ReadState::Read), ..ArticleFilter::default() },
+        ];
+        for filter in hidden {
+            let factors = explain(&rust, filter, &pool).await.unwrap();
+            assert_eq!(factors.iter().filter(|factor| factor.hides).count(), 1, "{:?}", factors);
+            assert!(labels(&factors, "Position").is_empty(), "{:?}", factors);
+        }
+
+        let by_score = ArticleFilter { sort: ArticleSort::Score, min_score: Some(6), ..ArticleFilter::default() };
+        let factors = explain(&rust, by_score, &pool).await.unwrap();
+        assert_eq!(labels(&factors, "Filter"), ["Score 6 reaches the minimum of 6"]);
+        // Only the release of the news feed scores higher
+        assert_eq!(labels(&factors, "Position"), ["Position 2 of 2"]);
+
+        mark_read(&rust, true, &pool).await.unwrap();
+        let factors = explain(&rust, ArticleFilter::default(), &pool).await.unwrap();
+        assert_eq!(labels(&factors, "ReadState"), ["Read"]);
+    }
+
+    #[tokio::test]
+    async fn lists_the_copies_of_the_article() {
+        let (pool, _) = fixture().await;
+        let identifier = |feed: &str, guid: &str| crate::commands::fetcher::article_identifier(feed, guid).to_string();
+
+        let factors = explain(&identifier("news", "5"), ArticleFilter::default(), &pool).await.unwrap();
+        let dedup = factors.iter().find(|factor| matches!(factor.kind, ExplanationKind::Dedup)).unwrap();
+        assert_eq!(dedup.label, "Recognized by its link and title");
+        assert!(dedup.details.iter().any(|detail| detail.starts_with("1 other article(s) of the feed share its link and title")), "{:?}", dedup);
+        assert!(dedup.details.contains(&format!("Rust news ({})", identifier("news", "6"))), "{:?}", dedup);
+
+        let factors = explain(&identifier("blog", "3"), ArticleFilter::default(), &pool).await.unwrap();
+        let dedup = factors.iter().find(|factor| matches!(factor.kind, ExplanationKind::Dedup)).unwrap();
+        assert_eq!(dedup.label, "Recognized by its GUID");
+        assert_eq!(dedup.details, ["It has no usable link, so it can't be matched with copies by link and title"]);
+
+        assert_eq!(explain("missing", ArticleFilter::default(), &pool).await.unwrap_err(), "Unknown article: missing");
+    }
+}
@@ -0,0 +1,367 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+use crate::commands::opml::load_folders;
+use crate::commands::scoring::load_scoring_rules;
+use crate::structs::export_manifest::ExportManifest;
+use crate::structs::export_progress::ExportProgress;
+use crate::structs::exported_archive::ExportedArchive;
+use crate::structs::exported_article::ExportedArticle;
+use crate::structs::exported_feed::ExportedFeed;
+use crate::structs::feed_change::FeedChange;
+use crate::structs::local_metric_day::LocalMetricDay;
+use crate::structs::opml_export_options::OpmlExportOptions;
+use crate::structs::queued_article::QueuedArticle;
+use crate::structs::write_gate::WriteGate;
+use crate::utils::export_path::{default_file_name, io_path, resolve_export_path};
+use crate::utils::opml::feeds_opml;
+use crate::utils::time_format::today;
+
+/// Articles per file, so neither the export nor a later import has to hold them all in memory.
+const ARTICLES_PER_FILE: i64 = 1000;
+
+static EXPORT_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Writes everything Alduin stores into one zip archive: the feeds as OPML, the articles, the scoring rules,
+/// the reading queue, the feed change history, the local metrics and the preferences, along with a manifest.
+/// Feed cookies are left out. The archive goes to `target_path`, or the default export folder, under another
+/// name if taken unless `overwrite`, and only replaces an existing file once complete.
+#[alduin_macros::command]
+pub async fn export_everything(target_path: Option<String>, overwrite: Option<bool>, app_handle: AppHandle) -> Result<ExportedArchive, String> {
+    EXPORT_CANCELLED.store(false, Ordering::SeqCst);
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let default_name = default_file_name("alduin-export", "zip", today());
+    let target_path = resolve_export_path(target_path.as_deref(), &default_export_directory(&pool).await?, &default_name, overwrite.unwrap_or(false))?;
+
+    let mut partial_path = target_path.clone().into_os_string();
+    partial_path.push(".partial");
+    let partial_path = PathBuf::from(partial_path);
+    let result = write_export(&partial_path, &app_handle).await;
+    match result {
+        Ok(manifest) => {
+            fs::rename(io_path(&partial_path), io_path(&target_path)).map_err(|e| format!("Error moving export to {}: {}", target_path.display(), e))?;
+            Ok(ExportedArchive { path: target_path.display().to_string(), manifest })
+        }
+        Err(e) => {
+            let _ = fs::remove_file(io_path(&partial_path));
+            Err(e)
+        }
+    }
+}
+
+/// Stops the running export after the current file, removing the partial archive.
+#[alduin_macros::command]
+pub async fn cancel_export() -> Result<(), String> {
+    EXPORT_CANCELLED.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// The folder exports go to when given no path: the one chosen, the downloads folder otherwise.
+#[alduin_macros::command]
+pub async fn get_default_export_directory(app_handle: AppHandle) -> Result<String, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    Ok(default_export_directory(&pool).await?.display().to_string())
+}
+
+/// Sets the folder exports go to when given no path, creating it, none going back to the downloads folder.
+/// Returns the folder now used.
+#[alduin_macros::command]
+pub async fn set_default_export_directory(path: Option<String>, app_handle: AppHandle) -> Result<String, String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let path = path.map(|path| path.trim().to_string()).filter(|path| !path.is_empty());
+    if let Some(path) = &path {
+        let directory = Path::new(path);
+        if !directory.is_absolute() {
+            return Err(format!("{} is not an absolute path", path));
+        }
+        fs::create_dir_all(io_path(directory)).map_err(|e| format!("Error creating the folder {}: {}", path, e))?;
+    }
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    sqlx::query(include_str!("../database/queries/set_default_export_directory.sql"))
+        .bind(&path)
+        .execute(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(default_export_directory(&pool).await?.display().to_string())
+}
+
+pub async fn default_export_directory(pool: &Pool<Sqlite>) -> Result<PathBuf, String> {
+    let directory = sqlx::query_scalar::<_, Option<String>>(include_str!("../database/queries/get_default_export_directory.sql"))
+        .fetch_optional(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .flatten();
+
+    directory.map(PathBuf::from)
+        .or_else(tauri::api::path::download_dir)
+        .or_else(tauri::api::path::document_dir)
+        .or_else(tauri::api::path::home_dir)
+        .ok_or_else(|| String::from("No folder to export to, choose one"))
+}
+
+async fn write_export(path: &Path, app_handle: &AppHandle) -> Result<ExportManifest, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    // Copied as is, the frontend store being the source of truth for the preferences
+    let preferences = app_handle.path_resolver().app_data_dir()
+        .and_then(|dir| fs::read(dir.join("preferences.dat")).ok())
+        .unwrap_or_else(|| b"{}".to_vec());
+
+    write_archive(path, &pool, &app_handle.package_info().version.to_string(), &preferences, |progress| {
+        app_handle.emit_all("export-progress", progress).map_err(|e| format!("Error emitting progress: {}", e))
+    }).await
+}
+
+/// Writes the archive of `export_everything`, calling `progress` after each file of articles.
+async fn write_archive<F>(path: &Path, pool: &Pool<Sqlite>, app_version: &str, preferences: &[u8], progress: F) -> Result<ExportManifest, String>
+where
+    F: Fn(ExportProgress) -> Result<(), String>,
+{
+    let file = File::create(io_path(path)).map_err(|e| format!("Error creating {}: {}", path.display(), e))?;
+    let mut archive = ZipWriter::new(file);
+    let mut files = vec![];
+
+    let exported_at = chrono::Utc::now();
+    let feeds = sqlx::query_as::<_, ExportedFeed>(include_str!("../database/queries/export_feeds.sql"))
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    let folders = load_folders(pool).await?;
+    add_file(&mut archive, &mut files, "feeds.opml", feeds_opml(&feeds, &folders, &OpmlExportOptions::default(), &exported_at.to_rfc2822()).as_bytes())?;
+
+    let articles_total = sqlx::query_scalar::<_, i64>(include_str!("../database/queries/count_articles.sql"))
+        .fetch_one(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    let mut articles_done = 0;
+    let mut last_rowid = 0;
+    let mut page_number = 0;
+    loop {
+        if EXPORT_CANCELLED.load(Ordering::SeqCst) {
+            return Err(String::from("Export cancelled"));
+        }
+
+        let page = sqlx::query_as::<_, ExportedArticle>(include_str!("../database/queries/export_articles_page.sql"))
+            .bind(last_rowid)
+            .bind(ARTICLES_PER_FILE)
+            .fetch_all(pool).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+        let Some(last) = page.last() else { break };
+        last_rowid = last.rowid;
+        articles_done += page.len() as i64;
+        page_number += 1;
+
+        add_json(&mut archive, &mut files, &format!("articles/{:05}.json", page_number), &page)?;
+
+        progress(ExportProgress { articles_done, articles_total })?;
+    }
+
+    add_json(&mut archive, &mut files, "scoring_rules.json", &load_scoring_rules(pool).await?)?;
+
+    let queue = sqlx::query_as::<_, QueuedArticle>(include_str!("../database/queries/export_reading_queue.sql"))
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    add_json(&mut archive, &mut files, "reading_queue.json", &queue)?;
+
+    let mut changes = BTreeMap::new();
+    for feed in feeds.iter() {
+        let feed_changes = sqlx::query_as::<_, FeedChange>(include_str!("../database/queries/get_feed_changes.sql"))
+            .bind(&feed.identifier)
+            .fetch_all(pool).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+        changes.insert(feed.identifier.clone(), feed_changes);
+    }
+    add_json(&mut archive, &mut files, "feed_changes.json", &changes)?;
+
+    let metrics = sqlx::query_as::<_, LocalMetricDay>(include_str!("../database/queries/get_local_metrics.sql"))
+        .bind("0000-01-01")
+        .bind("9999-12-31")
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    add_json(&mut archive, &mut files, "local_metrics.json", &metrics)?;
+
+    add_file(&mut archive, &mut files, "preferences.json", preferences)?;
+
+    let manifest = ExportManifest {
+        app_version: app_version.to_string(),
+        schema_version: sqlx::query_scalar::<_, i64>(include_str!("../database/queries/get_schema_version.sql"))
+            .fetch_one(pool).await
+            .map_err(|e| format!("Error executing query: {}", e))?,
+        exported_at: exported_at.timestamp_millis(),
+        feeds: feeds.len(),
+        articles: articles_done,
+        files,
+    };
+    archive.start_file("manifest.json", SimpleFileOptions::default()).map_err(|e| format!("Error writing manifest.json: {}", e))?;
+    serde_json::to_writer_pretty(&mut archive, &manifest).map_err(|e| format!("Error writing manifest.json: {}", e))?;
+
+    archive.finish().map_err(|e| format!("Error writing export: {}", e))?;
+    Ok(manifest)
+}
+
+fn add_json<T: Serialize + ?Sized>(archive: &mut ZipWriter<File>, files: &mut Vec<String>, name: &str, value: &T) -> Result<(), String> {
+    archive.start_file(name, SimpleFileOptions::default()).map_err(|e| format!("Error writing {}: {}", name, e))?;
+    serde_json::to_writer(&mut *archive, value).map_err(|e| format!("Error writing {}: {}", name, e))?;
+    files.push(name.to_string());
+    Ok(())
+}
+
+fn add_file(archive: &mut ZipWriter<File>, files: &mut Vec<String>, name: &str, content: &[u8]) -> Result<(), String> {
+    archive.start_file(name, SimpleFileOptions::default()).map_err(|e| format!("Error writing {}: {}", name, e))?;
+    archive.write_all(content).map_err(|e| format!("Error writing {}: {}", name, e))?;
+    files.push(name.to_string());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use std::sync::Mutex;
+    use serde_json::Value;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use zip::ZipArchive;
+    use crate::database::migrator::migrator;
+    use crate::structs::opml_outline::OpmlOutline;
+    use crate::utils::opml::parse_opml;
+    use super::*;
+
+    const ARTICLES: i64 = 2 * ARTICLES_PER_FILE + 7;
+    const PREFERENCES: &[u8] = br#"{"version": 3, "localMetrics": true, "showWindowOn": "cursor"}"#;
+
+    /// A profile using every part of the archive, with the text the formats have to escape.
+    async fn fixture() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+
+        let statements = [
+            "INSERT INTO folders (identifier, name, parent_identifier) VALUES ('news', 'News & \"Views\"', NULL), ('local', 'Local <city>', 'news')",
+            "INSERT INTO feeds (identifier, display_name, url, interval, html_url, folder_identifier, priority, archived, dedup_strategy, dedup_strategy_locked,
+                                address_family, user_agent, reread_on_update, retention_days, retention_max_articles, title_prefixes)
+             VALUES ('a', 'Démo “feed” & <co>', 'https://a.example/feed?x=1&y=2', 15, 'https://a.example/', 'local', 'high', 0, 'link_title', 1,
+                     'ipv4', 'browser', 1, 0, 200, '[\"[Sponsored] \", \"AD: \"]'),
+                    ('b', 'Plain', 'https://b.example/feed', 60, NULL, NULL, 'normal', 1, 'guid', 0, 'auto', 'honest', 0, NULL, NULL, '[]')",
+            "INSERT INTO scoring_rules (identifier, conditions, weight) VALUES ('rule', '[{\"type\": \"keyword\", \"value\": \"rust\"}, {\"type\": \"minWords\", \"value\": 300}]', 5)",
+            "INSERT INTO feed_changes (feed_identifier, field, old_value, new_value, source, changed_at) VALUES
+                ('a', 'displayName', 'Demo', 'Démo “feed” & <co>', 'user', 2), ('a', 'interval', '60', '15', 'user', 1), ('b', 'type', NULL, 'rss', 'sync', 3)",
+            "INSERT INTO local_metrics (day, metric, value) VALUES ('2026-03-29', 'searches', 4), ('2026-03-30', 'articles_read', 12)",
+        ];
+        for statement in statements {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        sqlx::query("
+            WITH RECURSIVE numbers(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM numbers WHERE n < ?)
+            INSERT INTO articles (identifier, feed_identifier, title, content, date, read, image_url, comments_url, link, score, first_seen)
+            SELECT n, CASE WHEN n % 3 = 0 THEN 'b' ELSE 'a' END, 'Title ' || n || ' “quoted” \\ 🦀', '<p>' || n || ' &amp; \"more\"</p>' || char(10),
+                   n * 1000, n % 2, CASE WHEN n % 5 = 0 THEN 'https://a.example/' || n || '.png' END, NULL, 'https://a.example/' || n, n % 7 - 3, n * 1000 + 1
+            FROM numbers
+        ")
+            .bind(ARTICLES)
+            .execute(&pool).await.unwrap();
+        for (article, position) in [("3", 1.0), ("1", 1.5), ("2000", 3.0)] {
+            sqlx::query("INSERT INTO reading_queue (article_identifier, position, added_at) VALUES (?, ?, 7)")
+                .bind(article).bind(position)
+                .execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    fn read_file(archive: &mut ZipArchive<File>, name: &str) -> String {
+        let mut content = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut content).unwrap();
+        content
+    }
+
+    fn read_json(archive: &mut ZipArchive<File>, name: &str) -> Value {
+        serde_json::from_str(&read_file(archive, name)).unwrap()
+    }
+
+    async fn stored<T: Serialize + for<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> + Send + Unpin>(query: &str, pool: &Pool<Sqlite>) -> Value {
+        serde_json::to_value(sqlx::query_as::<_, T>(query).fetch_all(pool).await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn exports_everything_without_loss() {
+        let pool = fixture().await;
+        let path = std::env::temp_dir().join(format!("alduin-export-{}.zip", std::process::id()));
+        let reported = Mutex::new(vec![]);
+
+        let manifest = write_archive(&path, &pool, "1.2.3", PREFERENCES, |progress| {
+            reported.lock().unwrap().push((progress.articles_done, progress.articles_total));
+            Ok(())
+        }).await.unwrap();
+        let mut archive = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+
+        // Every file is listed, the manifest aside
+        let mut names: Vec<&str> = archive.file_names().filter(|name| *name != "manifest.json").collect();
+        names.sort();
+        let mut listed = manifest.files.clone();
+        listed.sort();
+        assert_eq!(names, listed);
+        assert_eq!(read_json(&mut archive, "manifest.json"), serde_json::to_value(&manifest).unwrap());
+        assert_eq!((manifest.app_version.as_str(), manifest.feeds, manifest.articles), ("1.2.3", 2, ARTICLES));
+        assert_eq!(*reported.lock().unwrap(), [(ARTICLES_PER_FILE, ARTICLES), (2 * ARTICLES_PER_FILE, ARTICLES), (ARTICLES, ARTICLES)]);
+
+        let mut articles = vec![];
+        for page in 1..=3 {
+            let Value::Array(page) = read_json(&mut archive, &format!("articles/{:05}.json", page)) else { panic!("articles/{:05}.json", page) };
+            articles.extend(page);
+        }
+        assert_eq!(Value::Array(articles), stored::<ExportedArticle>("SELECT rowid, * FROM articles ORDER BY rowid", &pool).await);
+
+        assert_eq!(read_json(&mut archive, "scoring_rules.json"), serde_json::to_value(load_scoring_rules(&pool).await.unwrap()).unwrap());
+        assert_eq!(read_json(&mut archive, "reading_queue.json"), stored::<QueuedArticle>("SELECT * FROM reading_queue ORDER BY position", &pool).await);
+        assert_eq!(read_json(&mut archive, "local_metrics.json"), stored::<LocalMetricDay>("SELECT day, metric, value FROM local_metrics ORDER BY day", &pool).await);
+        let changes = read_json(&mut archive, "feed_changes.json");
+        for feed in ["a", "b"] {
+            let query = format!("SELECT field, old_value, new_value, source, changed_at FROM feed_changes WHERE feed_identifier = '{}' ORDER BY changed_at DESC", feed);
+            assert_eq!(changes[feed], stored::<FeedChange>(&query, &pool).await);
+        }
+        assert_eq!(read_file(&mut archive, "preferences.json").as_bytes(), PREFERENCES);
+
+        // The feeds read back from the OPML with their folders and settings
+        let outlines = parse_opml(read_file(&mut archive, "feeds.opml").as_bytes()).unwrap();
+        let feeds = sqlx::query_as::<_, ExportedFeed>(include_str!("../database/queries/export_feeds.sql")).fetch_all(&pool).await.unwrap();
+        let feed_outlines: Vec<&OpmlOutline> = outlines.iter().filter(|outline| outline.xml_url.is_some()).collect();
+        assert_eq!(feed_outlines.len(), feeds.len());
+        for feed in feeds.iter() {
+            let outline = feed_outlines.iter().find(|outline| outline.xml_url.as_deref() == Some(feed.url.as_str())).unwrap();
+            let settings = outline.settings.clone().unwrap();
+            let value = |value: Value| value.as_str().map(String::from);
+            assert_eq!(outline.title, feed.display_name);
+            assert_eq!(outline.html_url, feed.html_url);
+            assert_eq!(settings.interval, Some(feed.interval));
+            assert_eq!(settings.priority.map(|priority| priority.as_str()), Some(feed.priority.as_str()));
+            assert_eq!(settings.archived, Some(feed.archived));
+            assert_eq!(value(serde_json::to_value(settings.dedup_strategy).unwrap()), Some(feed.dedup_strategy.clone()));
+            assert_eq!(settings.dedup_strategy_locked, Some(feed.dedup_strategy_locked));
+            assert_eq!(settings.address_family.map(|family| family.as_str()), Some(feed.address_family.as_str()));
+            assert_eq!(settings.user_agent.map(|user_agent| user_agent.as_str()), Some(feed.user_agent.as_str()));
+            assert_eq!(settings.reread_on_update, Some(feed.reread_on_update));
+            assert_eq!(settings.retention_days, feed.retention_days);
+            assert_eq!(settings.retention_max_articles, feed.retention_max_articles);
+            assert_eq!(settings.title_prefixes.unwrap_or_default(), serde_json::from_str::<Vec<String>>(&feed.title_prefixes).unwrap());
+        }
+        let nested = feed_outlines.iter().find(|outline| outline.xml_url.as_deref() == Some("https://a.example/feed?x=1&y=2")).unwrap();
+        assert_eq!(nested.folder, ["News & \"Views\"", "Local <city>"]);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn stops_when_the_progress_fails() {
+        let pool = fixture().await;
+        let path = std::env::temp_dir().join(format!("alduin-export-stopped-{}.zip", std::process::id()));
+
+        let result = write_archive(&path, &pool, "1.2.3", b"{}", |_| Err(String::from("Window closed"))).await;
+        assert_eq!(result.unwrap_err(), "Window closed");
+
+        fs::remove_file(path).unwrap();
+    }
+}
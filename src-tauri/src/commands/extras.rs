@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+use sqlx::{Pool, Sqlite};
+use sqlx::types::Json;
+use tauri::{AppHandle, Manager};
+use crate::database::change_journal::emit_change;
+use crate::structs::article_details::ArticleDetails;
+use crate::structs::article_extra::ArticleExtra;
+use crate::structs::feed_extras::FeedExtras;
+use crate::structs::field_update::FieldUpdate;
+use crate::structs::read_pool::ReadPool;
+use crate::structs::write_gate::WriteGate;
+
+#[alduin_macros::command]
+pub async fn get_feed_extras(feed_id: String, app_handle: AppHandle) -> Result<FeedExtras, String> {
+    let pool = app_handle.state::<ReadPool>();
+    load_feed_extras(&feed_id, &pool).await
+}
+
+/// Sets the extra namespaces read for a feed, only affecting articles synced afterwards, and the extras shown.
+#[alduin_macros::command]
+pub async fn set_feed_extras(feed_id: String, extras: FeedExtras, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    if let Some(namespace) = extras.namespaces.iter()
+        .find(|namespace| namespace.is_empty() || !namespace.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')) {
+        return Err(format!("Invalid namespace prefix: {}", namespace));
+    }
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let version = sqlx::query_scalar::<_, i64>(include_str!("../database/queries/set_feed_extras.sql"))
+        .bind(Json(&extras))
+        .bind(&feed_id)
+        .fetch_optional(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    if let Some(version) = version {
+        emit_change(&app_handle, "feed-updated", FieldUpdate { identifier: feed_id, fields: vec!["extras"], version }).await?;
+    }
+
+    Ok(())
+}
+
+#[alduin_macros::command]
+pub async fn get_article_details(article_id: String, app_handle: AppHandle) -> Result<ArticleDetails, String> {
+    let pool = app_handle.state::<ReadPool>();
+
+    let (extras, feed_extras) = sqlx::query_as::<_, (Json<BTreeMap<String, Vec<ArticleExtra>>>, Json<FeedExtras>)>(include_str!("../database/queries/get_article_details.sql"))
+        .bind(&article_id)
+        .fetch_optional(&**pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .ok_or_else(|| format!("Unknown article: {}", article_id))?;
+
+    Ok(ArticleDetails {
+        displayed_extras: feed_extras.0.displayed.into_iter()
+            .filter(|key| extras.0.contains_key(key))
+            .collect(),
+        extras: extras.0,
+    })
+}
+
+pub async fn load_feed_extras(feed_id: &str, pool: &Pool<Sqlite>) -> Result<FeedExtras, String> {
+    Ok(sqlx::query_scalar::<_, Json<FeedExtras>>(include_str!("../database/queries/get_feed_extras.sql"))
+        .bind(feed_id)
+        .fetch_optional(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .map(|extras| extras.0)
+        .unwrap_or_default())
+}
@@ -0,0 +1,50 @@
+use sqlx::{Pool, Sqlite, SqliteConnection};
+use tauri::{AppHandle, Manager};
+use crate::enums::feed_change_source::FeedChangeSource;
+use crate::structs::feed_change::FeedChange;
+use crate::structs::read_pool::ReadPool;
+
+#[alduin_macros::command]
+pub async fn get_feed_changes(feed_id: String, app_handle: AppHandle) -> Result<Vec<FeedChange>, String> {
+    let pool = app_handle.state::<ReadPool>();
+
+    sqlx::query_as::<_, FeedChange>(include_str!("../database/queries/get_feed_changes.sql"))
+        .bind(&feed_id)
+        .fetch_all(&**pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// Records a change of a feed field, nothing when the value is the same. To be called with the transaction
+/// making the change, so the history never shows a change that was rolled back or misses one.
+pub async fn record_feed_change(
+    connection: &mut SqliteConnection,
+    feed_id: &str,
+    field: &str,
+    old_value: Option<String>,
+    new_value: Option<String>,
+    source: FeedChangeSource,
+) -> Result<(), String> {
+    if old_value == new_value {
+        return Ok(());
+    }
+
+    sqlx::query(include_str!("../database/queries/insert_feed_change.sql"))
+        .bind(feed_id)
+        .bind(field)
+        .bind(old_value)
+        .bind(new_value)
+        .bind(source)
+        .execute(connection).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(())
+}
+
+/// Drops changes older than a year.
+pub async fn expire_feed_changes(pool: &Pool<Sqlite>) -> Result<(), String> {
+    sqlx::query(include_str!("../database/queries/expire_feed_changes.sql"))
+        .execute(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(())
+}
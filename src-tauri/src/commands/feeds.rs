@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use sqlx::{Pool, Sqlite, SqliteConnection, SqliteExecutor};
+use sqlx::types::Json;
+use tauri::{AppHandle, Manager};
+use crate::commands::feed_changes::record_feed_change;
+use crate::commands::fetcher::sync;
+use crate::database::change_journal::emit_change;
+use crate::structs::sync_request::SyncRequest;
+use crate::enums::feed_change_source::FeedChangeSource;
+use crate::structs::duplicate_feeds::DuplicateFeeds;
+use crate::structs::editable_feed::EditableFeed;
+use crate::structs::feed_changes::FeedChanges;
+use crate::structs::field_update::FieldUpdate;
+use crate::structs::write_gate::WriteGate;
+use crate::utils::title::display_title;
+use crate::utils::url::canonical_url;
+use crate::secrets;
+
+/// Returns the identifier of an existing feed pointing to the same resource as `url`,
+/// `www.` and letter case of the host aside.
+#[alduin_macros::command]
+pub async fn find_duplicate_feed(url: String, app_handle: AppHandle) -> Result<Option<String>, String> {
+    let Some(canonical) = canonical_url(&url) else { return Ok(None) };
+    let pool = app_handle.state::<Pool<Sqlite>>();
+
+    let feeds = get_feed_urls(&pool).await?;
+
+    Ok(feeds.into_iter()
+        .find(|(_, feed_url)| canonical_url(feed_url).as_deref() == Some(canonical.as_str()))
+        .map(|(identifier, _)| identifier))
+}
+
+#[alduin_macros::command]
+pub async fn find_duplicate_feeds(app_handle: AppHandle) -> Result<Vec<DuplicateFeeds>, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    duplicate_feeds(&pool).await
+}
+
+#[alduin_macros::command]
+pub async fn get_editable_feed(feed_id: String, app_handle: AppHandle) -> Result<Option<EditableFeed>, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    load_editable_feed(&feed_id, &*pool).await
+}
+
+/// Updates only the given fields of a feed and returns its new version. With `expected_version`, fails with a
+/// `Conflict` error holding the current feed as JSON when another window edited it since it was loaded.
+#[alduin_macros::command]
+pub async fn update_feed(feed_id: String, changes: FeedChanges, expected_version: Option<i64>, app_handle: AppHandle) -> Result<i64, String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let (version, fields) = apply_feed_changes(&feed_id, changes, expected_version, &pool).await?;
+
+    emit_change(&app_handle, "feed-updated", FieldUpdate { identifier: feed_id, fields, version }).await?;
+
+    Ok(version)
+}
+
+/// Writes the changes of `update_feed` and records them, returning the new version and the changed fields.
+pub async fn apply_feed_changes(feed_id: &str, changes: FeedChanges, expected_version: Option<i64>, pool: &Pool<Sqlite>) -> Result<(i64, Vec<&'static str>), String> {
+    let fields: Vec<&'static str> = [
+        ("displayName", changes.display_name.is_some()),
+        ("url", changes.url.is_some()),
+        ("interval", changes.interval.is_some()),
+        ("priority", changes.priority.is_some()),
+        ("archived", changes.archived.is_some()),
+        ("addressFamily", changes.address_family.is_some()),
+        ("rereadOnUpdate", changes.reread_on_update.is_some()),
+        ("retentionDays", changes.retention_days.is_some()),
+        ("retentionMaxArticles", changes.retention_max_articles.is_some()),
+        ("titlePrefixes", changes.title_prefixes.is_some()),
+        ("userAgent", changes.user_agent.is_some()),
+    ].into_iter().filter(|(_, changed)| *changed).map(|(field, _)| field).collect();
+
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+
+    let current = load_editable_feed(feed_id, &mut *transaction).await?
+        .ok_or_else(|| String::from("This feed does not exist"))?;
+
+    let version = sqlx::query_scalar::<_, i64>(include_str!("../database/queries/update_feed.sql"))
+        .bind(&changes.display_name)
+        .bind(&changes.url)
+        .bind(changes.interval)
+        .bind(feed_id)
+        .bind(expected_version)
+        .bind(changes.priority)
+        .bind(changes.archived)
+        .bind(changes.address_family)
+        .bind(changes.reread_on_update)
+        .bind(changes.retention_days)
+        .bind(changes.retention_max_articles)
+        .bind(changes.title_prefixes.as_ref().map(Json))
+        .bind(changes.user_agent)
+        .fetch_optional(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    let Some(version) = version else {
+        let current = serde_json::to_string(&current).map_err(|e| format!("Error serializing feed: {}", e))?;
+        return Err(format!("Conflict: {}", current));
+    };
+
+    let changed_fields = [
+        ("displayName", Some(current.display_name), changes.display_name),
+        ("url", Some(current.url), changes.url),
+        ("interval", Some(current.interval.to_string()), changes.interval.map(|interval| interval.to_string())),
+        ("priority", Some(current.priority.as_str().to_string()), changes.priority.map(|priority| priority.as_str().to_string())),
+        ("archived", Some(current.archived.to_string()), changes.archived.map(|archived| archived.to_string())),
+        ("addressFamily", Some(current.address_family.as_str().to_string()), changes.address_family.map(|family| family.as_str().to_string())),
+        ("rereadOnUpdate", Some(current.reread_on_update.to_string()), changes.reread_on_update.map(|reread| reread.to_string())),
+        ("retentionDays", Some(retention_value(current.retention_days)), changes.retention_days.map(|days| retention_value(Some(days)))),
+        ("retentionMaxArticles", Some(retention_value(current.retention_max_articles)), changes.retention_max_articles.map(|articles| retention_value(Some(articles)))),
+        ("titlePrefixes", Some(current.title_prefixes.join("\n")), changes.title_prefixes.as_ref().map(|prefixes| prefixes.join("\n"))),
+        ("userAgent", Some(current.user_agent.as_str().to_string()), changes.user_agent.map(|user_agent| user_agent.as_str().to_string())),
+    ];
+    for (field, old_value, new_value) in changed_fields {
+        if new_value.is_some() {
+            record_feed_change(&mut transaction, feed_id, field, old_value, new_value, FeedChangeSource::User).await?;
+        }
+    }
+
+    if let Some(prefixes) = changes.title_prefixes.as_ref().filter(|prefixes| **prefixes != *current.title_prefixes) {
+        retitle_articles(feed_id, prefixes, &mut transaction).await?;
+    }
+
+    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))?;
+
+    Ok((version, fields))
+}
+
+/// Syncs a feed one last time so its history is complete, then archives it.
+#[alduin_macros::command]
+pub async fn archive_feed(feed_id: String, app_handle: AppHandle) -> Result<i64, String> {
+    // The sync and the update enter the gate themselves, a fetch holding back a freeze
+    app_handle.state::<WriteGate>().check()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let feed = load_editable_feed(&feed_id, &*pool).await?
+        .ok_or_else(|| String::from("This feed does not exist"))?;
+
+    if !feed.archived {
+        sync(SyncRequest { identifier: feed_id.clone(), url: feed.url }, app_handle.clone()).await?;
+    }
+
+    let changes = FeedChanges { archived: Some(true), ..FeedChanges::default() };
+    update_feed(feed_id, changes, None, app_handle).await
+}
+
+/// Deletes a feed and its articles. Archived feeds are kept unless `force` is set, they were archived to be kept.
+#[alduin_macros::command]
+pub async fn remove_feed(feed_id: String, force: bool, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let feed = load_editable_feed(&feed_id, &*pool).await?;
+
+    if feed.is_some_and(|feed| feed.archived) && !force {
+        return Err(String::from("FeedArchived: archived feeds can only be removed with force"));
+    }
+
+    sqlx::query(include_str!("../database/queries/delete_feed.sql"))
+        .bind(&feed_id)
+        .execute(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    // Its credentials went with it, their secrets are left in the keychain until the next startup otherwise
+    if let Err(e) = secrets::forget_orphaned(&pool).await {
+        eprintln!("Error forgetting the secrets of the feed: {}", e);
+    }
+
+    Ok(())
+}
+
+pub async fn duplicate_feeds(pool: &Pool<Sqlite>) -> Result<Vec<DuplicateFeeds>, String> {
+    let mut groups: Vec<DuplicateFeeds> = vec![];
+    let mut group_indexes: HashMap<String, usize> = HashMap::new();
+
+    for (identifier, url) in get_feed_urls(pool).await? {
+        let Some(canonical) = canonical_url(&url) else { continue };
+
+        match group_indexes.get(&canonical) {
+            Some(&index) => groups[index].identifiers.push(identifier),
+            None => {
+                group_indexes.insert(canonical.clone(), groups.len());
+                groups.push(DuplicateFeeds { canonical_url: canonical, identifiers: vec![identifier] });
+            }
+        }
+    }
+
+    Ok(groups.into_iter().filter(|group| group.identifiers.len() > 1).collect())
+}
+
+/// Returns the other feeds subscribed to the same resource as the given one under another host variant.
+pub async fn sibling_feeds(identifier: &str, url: &str, pool: &Pool<Sqlite>) -> Result<Vec<String>, String> {
+    let Some(canonical) = canonical_url(url) else { return Ok(vec![]) };
+
+    Ok(get_feed_urls(pool).await?.into_iter()
+        .filter(|(feed_identifier, feed_url)| {
+            feed_identifier != identifier && canonical_url(feed_url).as_deref() == Some(canonical.as_str())
+        })
+        .map(|(feed_identifier, _)| feed_identifier)
+        .collect())
+}
+
+async fn get_feed_urls(pool: &Pool<Sqlite>) -> Result<Vec<(String, String)>, String> {
+    sqlx::query_as(include_str!("../database/queries/get_feed_urls.sql"))
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+async fn load_editable_feed<'e, E: SqliteExecutor<'e>>(feed_id: &str, executor: E) -> Result<Option<EditableFeed>, String> {
+    sqlx::query_as::<_, EditableFeed>(include_str!("../database/queries/get_editable_feed.sql"))
+        .bind(feed_id)
+        .fetch_optional(executor).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+pub async fn load_title_prefixes<'e, E: SqliteExecutor<'e>>(feed_id: &str, executor: E) -> Result<Vec<String>, String> {
+    sqlx::query_scalar::<_, Json<Vec<String>>>(include_str!("../database/queries/get_feed_title_prefixes.sql"))
+        .bind(feed_id)
+        .fetch_optional(executor).await
+        .map(|prefixes| prefixes.map(|prefixes| prefixes.0).unwrap_or_default())
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// Cleans the stored titles of a feed again with its new prefixes, only writing the ones that change.
+async fn retitle_articles(feed_id: &str, prefixes: &[String], connection: &mut SqliteConnection) -> Result<(), String> {
+    let articles: Vec<(i64, String, String)> = sqlx::query_as(include_str!("../database/queries/get_feed_titles.sql"))
+        .bind(feed_id)
+        .fetch_all(&mut *connection).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    for (rowid, title, original_title) in articles {
+        let retitled = display_title(&original_title, prefixes);
+        if retitled != title {
+            sqlx::query(include_str!("../database/queries/set_article_title.sql"))
+                .bind(retitled)
+                .bind(original_title)
+                .bind(rowid)
+                .execute(&mut *connection).await
+                .map_err(|e| format!("Error executing query: {}", e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A retention override as recorded in the feed changes, "default" when following the preferences.
+fn retention_value(value: Option<i64>) -> String {
+    match value {
+        Some(value) if value >= 0 => value.to_string(),
+        _ => String::from("default"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::database::migrator::migrator;
+    use super::*;
+
+    const EDITS: i64 = 20;
+
+    async fn fixture() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval) VALUES ('feed', 'Feed', 'https://a.example/feed', 60)")
+            .execute(&pool).await.unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn keeps_the_fields_of_interleaved_updates() {
+        let pool = fixture().await;
+
+        let renames = tokio::spawn({
+            let pool = pool.clone();
+            async move {
+                for edit in 1..=EDITS {
+                    let changes = FeedChanges { display_name: Some(format!("Feed {}", edit)), ..FeedChanges::default() };
+                    let (_, fields) = apply_feed_changes("feed", changes, None, &pool).await.unwrap();
+                    assert_eq!(fields, ["displayName"]);
+                    tokio::task::yield_now().await;
+                }
+            }
+        });
+        let intervals = tokio::spawn({
+            let pool = pool.clone();
+            async move {
+                for edit in 1..=EDITS {
+                    let changes = FeedChanges { interval: Some(edit), ..FeedChanges::default() };
+                    let (_, fields) = apply_feed_changes("feed", changes, None, &pool).await.unwrap();
+                    assert_eq!(fields, ["interval"]);
+                    tokio::task::yield_now().await;
+                }
+            }
+        });
+        renames.await.unwrap();
+        intervals.await.unwrap();
+
+        let feed = load_editable_feed("feed", &pool).await.unwrap().unwrap();
+        assert_eq!(feed.display_name, format!("Feed {}", EDITS));
+        assert_eq!(feed.interval, EDITS);
+        assert_eq!(feed.url, "https://a.example/feed");
+        assert_eq!(feed.version, 2 * EDITS);
+
+        let recorded: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM feed_changes WHERE feed_identifier = 'feed'")
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(recorded, 2 * EDITS);
+    }
+
+    #[tokio::test]
+    async fn conflicts_with_an_outdated_version() {
+        let pool = fixture().await;
+
+        let rename = |name: &str| FeedChanges { display_name: Some(name.to_string()), ..FeedChanges::default() };
+        let (version, _) = apply_feed_changes("feed", rename("First"), Some(0), &pool).await.unwrap();
+        assert_eq!(version, 1);
+
+        // Edited in another window since version 0 was loaded
+        let error = apply_feed_changes("feed", rename("Second"), Some(0), &pool).await.unwrap_err();
+        let current: serde_json::Value = serde_json::from_str(error.strip_prefix("Conflict: ").unwrap()).unwrap();
+        assert_eq!(current["displayName"], "First");
+        assert_eq!(current["version"], 1);
+
+        let (version, _) = apply_feed_changes("feed", rename("Second"), Some(1), &pool).await.unwrap();
+        assert_eq!(version, 2);
+        assert_eq!(load_editable_feed("feed", &pool).await.unwrap().unwrap().display_name, "Second");
+    }
+
+    #[tokio::test]
+    async fn leaves_the_version_to_the_user_edits() {
+        let pool = fixture().await;
+
+        // Sync bookkeeping
+        sqlx::query(include_str!("../database/queries/sync.sql"))
+            .bind("rss").bind(None::<String>).bind("feed").bind(None::<String>).bind(None::<String>)
+            .execute(&pool).await.unwrap();
+
+        assert_eq!(load_editable_feed("feed", &pool).await.unwrap().unwrap().version, 0);
+    }
+}
@@ -1,103 +1,903 @@
 use reqwest::redirect::Policy;
+use encoding_rs::{Encoding, UTF_8};
+use reqwest::header::{ACCEPT, CONTENT_TYPE, COOKIE, LOCATION};
+use reqwest::{Response, StatusCode};
 use crate::structs::sync_request::SyncRequest;
 use crate::structs::sync_response::SyncResponse;
-use feed_rs::parser;
 use reqwest::Client;
 use sqlx::{Pool, Sqlite};
 use tauri::{AppHandle, Manager};
 use crate::enums::feed_type::FeedType;
+use crate::enums::background_job::BackgroundJob;
+use crate::structs::activity_monitor::ActivityMonitor;
 use crate::structs::article::Article;
 use crate::structs::image::Image;
+use crate::parsers::item_extensions::parse_item_extensions;
+use crate::parsers::outbound_links::outbound_links;
+use crate::parsers::feed_content::parse_feed;
+use crate::commands::comments::sync_watched_comments;
+use crate::commands::feeds::{duplicate_feeds, load_title_prefixes, sibling_feeds};
+use crate::commands::scoring::{load_feed_folders, load_scoring_rules};
+use crate::commands::post_processors::load_pipeline;
+use crate::commands::extras::load_feed_extras;
+use crate::commands::feed_changes::expire_feed_changes;
+use crate::commands::cookies::{load_feed_session, set_session_state};
+use crate::commands::credentials::load_feed_credentials;
+use crate::commands::certificates::load_feed_tls;
+use crate::enums::session_state::SessionState;
+use crate::structs::feed_cookie::FeedCookie;
+use crate::structs::feed_credentials::FeedCredentials;
+use crate::structs::feed_tls::FeedTls;
+use crate::utils::certificates::{certificate_error, expired_error};
+use crate::utils::cookies::{cookie_header, is_login_page};
+use crate::utils::dedup::changed_format;
+use crate::utils::geo_block::geo_block_error;
+use crate::utils::input_limits::{too_large, MAX_RESPONSE_BYTES};
+use url::Url;
+use crate::commands::dedup::dedup_fetch;
+use crate::commands::retention::prune_when_idle;
+use crate::utils::title::display_title;
+use crate::scoring::evaluator::{compile_rules, score, CompiledRule};
+use crate::structs::scoring_subject::ScoringSubject;
+use crate::utils::url::canonical_url;
+use std::collections::{HashSet, VecDeque};
+use crate::scheduler::queue;
+use crate::scheduler::availability::scheduled_sync;
+use crate::database::{health, local_metrics};
+use crate::database::change_journal::emit_article_change;
+use crate::enums::local_metric::LocalMetric;
+use crate::scheduler::limiter::{acquire_sync_permit, FullPass};
+use crate::structs::preferences::Preferences;
+use crate::structs::sync_progress::SyncProgress;
+use crate::structs::write_gate::WriteGate;
+use crate::enums::address_family::AddressFamily;
+use crate::enums::user_agent::UserAgent;
+use crate::structs::feed_network::FeedNetwork;
+use crate::structs::family_resolver::FamilyResolver;
+use crate::structs::fetch_debug::FetchDebug;
+use crate::structs::system_resolver::SystemResolver;
+use crate::enums::feed_fetch::FeedFetch;
+use crate::structs::feed_validators::FeedValidators;
+use crate::structs::sync_outcome::SyncOutcome;
+use crate::structs::sync_writer::SyncWriter;
+use crate::structs::prepared_article::PreparedArticle;
+use crate::structs::feed_completion::FeedCompletion;
+use crate::enums::feed_write::FeedWrite;
+use crate::structs::sync_failure::SyncFailure;
+use crate::structs::sync_report::SyncReport;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{self, UnboundedSender};
 use xxhash_rust::xxh32::xxh32;
 
-#[tauri::command]
-pub async fn sync(sync_request: SyncRequest, app_handle: AppHandle) -> Result<(), String> {
+/// Some endpoints negotiate the format, asking explicitly keeps them from serving whatever a cache holds.
+const FEED_ACCEPT: &str = "application/atom+xml, application/rss+xml;q=0.9, application/feed+json;q=0.8, application/json;q=0.7, application/xml;q=0.6, text/xml;q=0.6, */*;q=0.1";
+
+/// A fetch taking longer is abandoned, so a dead host only fails its own feed.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Articles sent to the writer at once, the next batch being prepared while it stores this one.
+pub const SYNC_BATCH_ARTICLES: usize = 50;
+
+/// Fetches the feed conditionally once it has validators. When the server answers that it didn't change,
+/// only the time of the sync is updated.
+#[alduin_macros::command]
+pub async fn sync(sync_request: SyncRequest, app_handle: AppHandle) -> Result<SyncOutcome, String> {
+    let writer = SyncWriter::start(app_handle.state::<Pool<Sqlite>>().inner().clone(), app_handle.state::<WriteGate>().inner().clone());
+    sync_feed(sync_request, &writer, &app_handle).await
+}
+
+/// Same as `sync`, the articles being stored by the writer of the run. The feed is stored in a single
+/// transaction, its validators and metadata with its articles, so a failing sync leaves it as it was.
+pub async fn sync_feed(sync_request: SyncRequest, writer: &SyncWriter, app_handle: &AppHandle) -> Result<SyncOutcome, String> {
     let SyncRequest { identifier, url } = sync_request;
 
-    let content = fetch_feed(url, app_handle.clone()).await?;
-
-    let response = match parser::parse(content.as_bytes()) {
-        Ok(feed) => Ok(SyncResponse {
-            identifier,
-            feed_type: FeedType::from(feed.feed_type),
-            articles: feed.entries.into_iter().map(Article::from).collect(),
-            image: match feed.logo {
-                Some(l) => Some(Image::from(l)),
-                None => match feed.icon {
-                    Some(i) => Some(Image::from(i)),
-                    None => None,
+    let pool = app_handle.state::<Pool<Sqlite>>();
+
+    // The same feed subscribed under another host variant carries the same items with different
+    // GUIDs, so links already delivered by those feeds are skipped
+    let mut sibling_links: HashSet<String> = HashSet::new();
+    for sibling in sibling_feeds(&identifier, &url, &pool).await? {
+        let links: Vec<String> = sqlx::query_scalar(include_str!("../database/queries/get_feed_links.sql"))
+            .bind(sibling)
+            .fetch_all(&*pool).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+        sibling_links.extend(links.iter().filter_map(|link| canonical_url(link)));
+    }
+
+    let pipeline = load_pipeline(&identifier, &pool).await?;
+    let title_prefixes = load_title_prefixes(&identifier, &*pool).await?;
+    let feed_extras = load_feed_extras(&identifier, &pool).await?;
+
+    let _permit = acquire_sync_permit(Preferences::load(app_handle).sync_concurrency()).await?;
+
+    let network = load_feed_network(&identifier, &pool).await?;
+    let validators = load_feed_validators(&identifier, &pool).await?;
+    let session = load_feed_session(&identifier, &pool).await?;
+    let credentials = load_feed_credentials(&identifier, &pool).await?;
+    let tls = load_feed_tls(&identifier, &pool).await?;
+    let fetch = if session.is_none() && credentials.is_none() && tls.is_none() {
+        fetch_modified(url, &validators, network, app_handle).await?
+    } else {
+        let cookies = session.as_ref().map(|session| session.cookies.as_slice()).unwrap_or_default();
+        match fetch_with_secrets(&url, cookies, credentials.as_ref(), tls.as_ref(), &validators, network, app_handle).await? {
+            Some(fetch) => fetch,
+            None => return Err(match &session {
+                Some(session) => reject_session(&identifier, session.state, app_handle).await,
+                None => format!("AuthenticationFailed: the site rejected the credentials of feed {}", identifier),
+            }),
+        }
+    };
+
+    let (content, validators) = match fetch {
+        FeedFetch::Modified { content, validators } => (content, validators),
+        FeedFetch::NotModified(repeated) => {
+            let _writing = app_handle.state::<WriteGate>().enter()?;
+
+            if session.is_some_and(|session| session.state != SessionState::Working) {
+                set_session_state(&identifier, SessionState::Working, &*pool).await?;
+            }
+            // The validators are only replaced by the ones a 304 repeats, never cleared
+            sqlx::query(include_str!("../database/queries/sync_not_modified.sql"))
+                .bind(repeated.etag)
+                .bind(repeated.last_modified)
+                .bind(&identifier)
+                .execute(&*pool).await
+                .map_err(|e| format!("Error executing query: {}", e))?;
+
+            local_metrics::record(app_handle, LocalMetric::SyncsRun, 1).await;
+            return Ok(SyncOutcome { not_modified: true, ..SyncOutcome::default() });
+        }
+    };
+
+    let (feed, content, parse_warnings) = parse_feed(&content)?;
+    let feed_language = feed.language;
+    let mut response = SyncResponse {
+        identifier,
+        feed_type: FeedType::from(feed.feed_type),
+        articles: feed.entries.into_iter()
+            .map(|mut entry| {
+                let applied = pipeline.apply_entry(&mut entry);
+                let mut article = Article::from(entry);
+                article.post_processors = applied;
+                article.title = display_title(&article.original_title, &title_prefixes);
+                article.language = article.language.or_else(|| feed_language.clone());
+                article
+            })
+            .collect(),
+        image: feed.logo.or(feed.icon).map(Image::from),
+    };
+
+    // JSON feeds have no XML extensions, and a count mismatch means we can't pair items safely
+    if !matches!(response.feed_type, FeedType::JSON) {
+        let extensions = parse_item_extensions(content.as_bytes(), &feed_extras.namespaces);
+        if extensions.len() == response.articles.len() {
+            for (article, extension) in response.articles.iter_mut().zip(extensions) {
+                if extension.comments_url.is_some() {
+                    article.comments_url = extension.comments_url;
+                }
+                if !extension.enclosures.is_empty() {
+                    article.enclosures = extension.enclosures;
                 }
+                article.extras = extension.extras;
             }
-        }),
-        Err(e) => Err(format!("Error parsing feed: {}", e)),
-    }.unwrap();
+        }
+    }
 
-    let image_url = match response.image {
-        Some(image) => Some(image.uri),
-        None => None,
+    let image_url = response.image.map(|image| image.uri);
+
+    // Held until the writer stored the feed, which enters the gate for its own transaction too
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let feed_type = response.feed_type.as_str();
+
+    let (old_type, old_image_url, last_updated): (Option<String>, Option<String>, Option<i64>) =
+        sqlx::query_as(include_str!("../database/queries/get_feed_metadata.sql"))
+            .bind(&response.identifier)
+            .fetch_optional(&*pool).await
+            .map_err(|e| format!("Error executing query: {}", e))?
+            .unwrap_or_default();
+    let first_sync = last_updated.unwrap_or_default() == 0;
+
+    let format_change = changed_format(old_type.as_deref(), feed_type, first_sync);
+
+    let scoring_rules = compile_rules(&load_scoring_rules(&pool).await?)?;
+    let folders = load_feed_folders(Some(&response.identifier), &pool).await?
+        .remove(&response.identifier)
+        .unwrap_or_default();
+
+    let mut warnings = parse_warnings;
+    if let Some(old_type) = &format_change {
+        warnings.push(format!("Served as {} instead of {}", feed_type, old_type));
+    }
+
+    let fetched = response.articles.iter()
+        .map(|article| article_identifier(&response.identifier, &article.id).to_string())
+        .collect();
+    let (articles, switched) = dedup_fetch(&response.identifier, response.articles, format_change.is_some(), &pool).await?;
+    if switched {
+        app_handle.emit_all("dedup-strategy-changed", response.identifier.clone())
+            .map_err(|e| format!("Error emitting dedup strategy change: {}", e))?;
+    }
+
+    let completion = FeedCompletion {
+        feed_type,
+        image_url,
+        validators,
+        session_working: session.is_some_and(|session| session.state != SessionState::Working),
+        fetched,
+        // The first sync only discovers the metadata, it doesn't change it
+        previous: (!first_sync).then_some((old_type, old_image_url)),
+    };
+
+    // The writer stores each batch while the next one is prepared. Returning before `Finish` is sent
+    // drops `writes`, which rolls the feed back.
+    let (writes, written) = writer.feed(&response.identifier);
+    let mut batch = Vec::with_capacity(SYNC_BATCH_ARTICLES);
+    for (article, article_dedup_key) in articles {
+        let is_sibling_duplicate = article.link.as_deref()
+            .and_then(canonical_url)
+            .is_some_and(|link| sibling_links.contains(&link));
+        if is_sibling_duplicate {
+            continue;
+        }
+
+        batch.push(prepare_article(&response.identifier, article, article_dedup_key, &scoring_rules, &folders));
+        // A closed channel means the writer failed, which `written` tells below
+        if batch.len() == SYNC_BATCH_ARTICLES && writes.send(FeedWrite::Articles(std::mem::take(&mut batch))).await.is_err() {
+            break;
+        }
+    }
+
+    app_handle.state::<WriteGate>().check()?;
+    if !batch.is_empty() {
+        let _ = writes.send(FeedWrite::Articles(batch)).await;
+    }
+    let _ = writes.send(FeedWrite::Finish(completion)).await;
+    drop(writes);
+
+    let ingested = written.await
+        .map_err(|_| String::from("Error syncing feed: the writer of the run stopped"))??;
+
+    local_metrics::record(app_handle, LocalMetric::SyncsRun, 1).await;
+    local_metrics::record(app_handle, LocalMetric::ArticlesIngested, ingested).await;
+
+    Ok(SyncOutcome { not_modified: false, new_articles: ingested, warnings })
+}
+
+/// Scores the article and extracts its links, leaving only the writes to the writer.
+pub fn prepare_article(feed_identifier: &str, article: Article, dedup_key: Option<u32>, scoring_rules: &[CompiledRule], folders: &[String]) -> PreparedArticle {
+    let subject = ScoringSubject {
+        feed_identifier,
+        folders,
+        language: article.language.as_deref(),
+        title: &article.title,
+        content: &article.content,
     };
+    PreparedArticle {
+        identifier: article_identifier(feed_identifier, &article.id),
+        score: score(scoring_rules, &subject),
+        dedup_key,
+        links: outbound_links(&article.content, article.link.as_deref()),
+        article,
+    }
+}
+
+pub fn article_identifier(feed_identifier: &str, guid: &str) -> u32 {
+    xxh32(&[feed_identifier.as_bytes(), guid.as_bytes()].concat(), 42)
+}
+
+/// Syncs the feeds `sync_concurrency` at a time, each one succeeding or failing on its own, and emits
+/// `sync-progress` as each completes. Freezing writes stops the run with the remaining feeds still queued,
+/// so the next run resumes from there, and returns the report of the feeds synced until then.
+#[alduin_macros::command]
+pub async fn sync_all(sync_request: Vec<SyncRequest>, app_handle: AppHandle) -> Result<SyncReport, String> {
+    let _full_pass = FullPass::start();
 
     let pool = app_handle.state::<Pool<Sqlite>>();
-    let update_feed_query = sqlx::query(include_str!("../database/queries/sync.sql"))
-        .bind(match response.feed_type {
-            FeedType::Atom => "atom",
-            FeedType::RSS => "rss",
-            FeedType::JSON => "json",
-        })
-        .bind(image_url)
-        .bind(response.identifier.clone());
-
-    update_feed_query.execute(&*pool).await.expect("Error executing query");
-
-    for article in response.articles {
-        let image_url = match article.image {
-            Some(image) => Some(image.uri),
-            None => None,
+    let writing = app_handle.state::<WriteGate>().enter()?;
+    let requests = queue::resume_or_start(sync_request, &pool).await?;
+    drop(writing);
+
+    let total = requests.len();
+    let started = Instant::now();
+
+    let workers = Preferences::load(&app_handle).sync_concurrency().min(total);
+    let requests = Arc::new(Mutex::new(VecDeque::from(requests)));
+    let (sender, mut results) = mpsc::unbounded_channel();
+    // One writer for the whole run, the workers parsing the next feeds while it stores the previous ones
+    let writer = SyncWriter::start(pool.inner().clone(), app_handle.state::<WriteGate>().inner().clone());
+    for _ in 0..workers {
+        tauri::async_runtime::spawn(sync_worker(requests.clone(), sender.clone(), writer.clone(), app_handle.clone()));
+    }
+    drop(sender);
+    drop(writer);
+
+    let mut report = SyncReport::default();
+    let mut current = 0;
+    while let Some((identifier, result)) = results.recv().await {
+        // Dequeued even on failure, otherwise a broken feed would block every resumed run. Left queued
+        // when writes were frozen meanwhile, the next run syncing it again
+        if let Ok(_writing) = app_handle.state::<WriteGate>().enter() {
+            queue::complete(&identifier, &pool).await?;
+        }
+
+        current += 1;
+        let eta_ms = started.elapsed().as_millis() as u64 / current as u64 * (total - current) as u64;
+        let (outcome, error) = match result {
+            Ok(outcome) => {
+                report.new_articles += outcome.new_articles;
+                if outcome.not_modified {
+                    report.not_modified.push(identifier.clone());
+                } else {
+                    report.updated.push(identifier.clone());
+                }
+                (outcome, None)
+            }
+            Err(error) => {
+                report.failed.push(SyncFailure { identifier: identifier.clone(), error: error.clone() });
+                (SyncOutcome::default(), Some(error))
+            }
         };
+        let SyncOutcome { not_modified, warnings, .. } = outcome;
+        let changed = !not_modified && error.is_none();
+        let progress = SyncProgress { identifier: identifier.clone(), current, total, eta_ms, not_modified, warnings, error };
+        emit_article_change(&app_handle, "sync-progress", progress, |filter| changed && filter.may_show(&identifier, None)).await?;
+    }
+
+    // The workers stopped as writes were frozen, the rest of the run writing too
+    let Ok(writing) = app_handle.state::<WriteGate>().enter() else {
+        return Ok(report);
+    };
+    if let Err(e) = expire_feed_changes(&pool).await {
+        eprintln!("Error expiring feed changes: {}", e);
+    }
+    drop(writing);
+
+    // Watched comment feeds come last so they never delay the feeds shown in the sidebar
+    sync_watched_comments(app_handle.clone()).await;
+    // Once the user is idle, a single prune for the syncs finishing meanwhile
+    if Preferences::load(&app_handle).prune_after_sync && app_handle.state::<ActivityMonitor>().defer(BackgroundJob::Prune) {
+        tauri::async_runtime::spawn(prune_when_idle(app_handle.clone()));
+    }
+    // Done writing, unless writes were frozen meanwhile
+    if app_handle.state::<WriteGate>().check().is_ok() {
+        health::checkpoint(&pool).await;
+    }
+
+    // Lets the frontend suggest merging feeds subscribed under several host variants
+    let duplicates = duplicate_feeds(&pool).await?;
+    if !duplicates.is_empty() {
+        app_handle.emit_all("duplicate-feeds-detected", duplicates)
+            .map_err(|e| format!("Error emitting duplicates: {}", e))?;
+    }
+
+    Ok(report)
+}
+
+/// Syncs the queued feeds one after the other until none is left or writes are frozen.
+async fn sync_worker(requests: Arc<Mutex<VecDeque<SyncRequest>>>, results: UnboundedSender<(String, Result<SyncOutcome, String>)>, writer: SyncWriter, app_handle: AppHandle) {
+    while app_handle.state::<WriteGate>().check().is_ok() {
+        let Some(request) = requests.lock().unwrap().pop_front() else { return };
+        let identifier = request.identifier.clone();
+
+        // Run apart so a panicking sync only fails its own feed
+        let result = tauri::async_runtime::spawn(scheduled_sync(request, writer.clone(), app_handle.clone())).await
+            .unwrap_or_else(|e| Err(format!("Error syncing feed: {}", e)));
+
+        if results.send((identifier, result)).is_err() {
+            return;
+        }
+    }
+}
+
+/// Fetches from the addresses of `family`, Auto following the preferences. Feeds fetched for something else
+/// than a sync, like comments, pass Auto and send the user agent of Alduin.
+pub async fn fetch_feed(feed_link: String, family: AddressFamily, app_handle: AppHandle) -> Result<String, String> {
+    let network = FeedNetwork { address_family: family, user_agent: UserAgent::Honest };
+    match fetch_modified(feed_link, &FeedValidators::default(), network, &app_handle).await? {
+        FeedFetch::Modified { content, .. } => Ok(content),
+        FeedFetch::NotModified(_) => Err(String::from("Error fetching feed: not modified, though it wasn't asked")),
+    }
+}
+
+/// Same as `fetch_feed`, conditional on `validators`.
+async fn fetch_modified(feed_link: String, validators: &FeedValidators, network: FeedNetwork, app_handle: &AppHandle) -> Result<FeedFetch, String> {
+    let family = network.address_family.or(Preferences::load(app_handle).address_family);
+    let (client, resolver) = feed_client(family, network.user_agent, Policy::limited(2), None, app_handle)?;
+
+    let response = validators.apply(client.get(feed_link).header(ACCEPT, FEED_ACCEPT)).send().await
+        .map_err(|e| fetch_error(&e, &resolver, None))?;
+    let status = response.status();
+    let received = FeedValidators::from_headers(response.headers());
+    if status == StatusCode::NOT_MODIFIED {
+        return Ok(FeedFetch::NotModified(received));
+    }
 
-        let insert_article_query = sqlx::query(include_str!("../database/queries/insert_article.sql"))
-            .bind(xxh32(&[response.identifier.as_bytes(), article.id.as_bytes()].concat(), 42))
-            .bind(response.identifier.clone())
-            .bind(article.title)
-            .bind(article.content)
-            .bind(article.date)
-            .bind(image_url);
+    let text = read_text(response).await?;
 
-        match insert_article_query.execute(&*pool).await {
-            Ok(_) => Ok(()),
-            // cast Error is a SqliteError
-            Err(e) => match e.as_database_error() {
-                // code() is a Cow<str>, so we need to match on &str
-                Some(database_error) => match database_error.code().unwrap_or_default().to_string().as_str() {
-                    "2067" => Ok(()),
-                    "1555" => Ok(()),
-                    _ => Err(format!("Error executing query: {}", e)),
-                },
-                None => Err(format!("Error executing query: {}", e)),
-            },
-        }?;
+    if let Some(error) = geo_block_error(status, &text) {
+        return Err(error);
+    }
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        return Err(format!("AuthenticationFailed: the site asks for credentials ({})", status));
     }
 
-    Ok(())
+    Ok(FeedFetch::Modified { content: text, validators: received })
 }
 
-#[tauri::command]
-pub async fn sync_all(sync_request: Vec<SyncRequest>, app_handle: AppHandle) -> Result<(), String> {
-    for request in sync_request {
-        sync(request, app_handle.clone()).await?;
+/// Reads the body as text like `Response::text`, refusing it once past `MAX_RESPONSE_BYTES` rather than
+/// after holding all of it.
+async fn read_text(mut response: Response) -> Result<String, String> {
+    if response.content_length().is_some_and(|length| length > MAX_RESPONSE_BYTES as u64) {
+        return Err(too_large("The response", MAX_RESPONSE_BYTES));
+    }
+    let encoding = response.headers().get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(|content_type| content_type.split(';').find_map(|parameter| parameter.trim().strip_prefix("charset=")))
+        .and_then(|charset| Encoding::for_label(charset.trim_matches('"').as_bytes()))
+        .unwrap_or(UTF_8);
+
+    let mut body = vec![];
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("Error reading response text: {}", e))? {
+        if body.len() + chunk.len() > MAX_RESPONSE_BYTES {
+            return Err(too_large("The response", MAX_RESPONSE_BYTES));
+        }
+        body.extend_from_slice(&chunk);
     }
-    Ok(())
+
+    let (text, _, _) = encoding.decode(&body);
+    Ok(text.into_owned())
+}
+
+/// Fetches a feed the way a sync does, with its certificates but without its session cookies or credentials,
+/// and tells which addresses it was fetched from.
+#[alduin_macros::command]
+pub async fn debug_fetch(feed_id: String, app_handle: AppHandle) -> Result<FetchDebug, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let url = sqlx::query_scalar::<_, String>(include_str!("../database/queries/get_feed_url.sql"))
+        .bind(&feed_id)
+        .fetch_optional(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .ok_or_else(|| String::from("This feed does not exist"))?;
+
+    let network = load_feed_network(&feed_id, &pool).await?;
+    let family = network.address_family.or(Preferences::load(&app_handle).address_family);
+    let tls = load_feed_tls(&feed_id, &pool).await?;
+    let redirect = match &tls {
+        Some(_) => same_origin_policy(Url::parse(&url).map_err(|e| format!("Invalid feed URL: {}", e))?),
+        None => Policy::limited(2),
+    };
+    let (client, resolver) = feed_client(family, network.user_agent, redirect, tls.as_ref(), &app_handle)?;
+
+    let started = Instant::now();
+    let result = client.get(&url).header(ACCEPT, FEED_ACCEPT).send().await;
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+
+    let (connected_address, status, error) = match &result {
+        Ok(response) => (response.remote_addr().map(|address| address.to_string()), Some(response.status().as_u16()), None),
+        Err(e) => (None, e.status().map(|status| status.as_u16()), Some(tls.as_ref().and_then(expired_error).unwrap_or_else(|| fetch_error(e, &resolver, tls.as_ref())))),
+    };
+    let resolved = resolver.resolved();
+
+    Ok(FetchDebug {
+        url,
+        address_family: family,
+        user_agent: network.user_agent,
+        resolved_addresses: resolved.iter().map(|address| address.ip().to_string()).collect(),
+        tried_addresses: family.filter(resolved).iter().map(|address| address.ip().to_string()).collect(),
+        connected_address,
+        status,
+        elapsed_ms,
+        error,
+        client_certificate: tls.as_ref().and_then(FeedTls::client_certificate).cloned(),
+        ca_certificate: tls.as_ref().and_then(FeedTls::ca_certificate).cloned(),
+    })
+}
+
+async fn load_feed_validators(feed_id: &str, pool: &Pool<Sqlite>) -> Result<FeedValidators, String> {
+    Ok(sqlx::query_as::<_, FeedValidators>(include_str!("../database/queries/get_feed_validators.sql"))
+        .bind(feed_id)
+        .fetch_optional(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .unwrap_or_default())
+}
+
+async fn load_feed_network(feed_id: &str, pool: &Pool<Sqlite>) -> Result<FeedNetwork, String> {
+    Ok(sqlx::query_as::<_, FeedNetwork>(include_str!("../database/queries/get_feed_network.sql"))
+        .bind(feed_id)
+        .fetch_optional(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .unwrap_or_default())
 }
 
-async fn fetch_feed(feed_link: String, app_handle: AppHandle) -> Result<String, String> {
+/// The client resolves hosts to the addresses of `family` only, its resolver keeping what it resolved.
+/// A client given the certificates of a feed must only fetch from the origin of the feed.
+fn feed_client(family: AddressFamily, user_agent: UserAgent, redirect: Policy, tls: Option<&FeedTls>, app_handle: &AppHandle) -> Result<(Client, Arc<FamilyResolver>), String> {
     let version = app_handle.package_info().version.to_string();
-    let client = Client::builder()
-        .user_agent(format!("Alduin v{}", version))
-        .redirect(Policy::limited(2))
-        .build().expect("Error building client");
+    build_client(family, user_agent.header(&version), redirect, tls)
+}
+
+fn build_client(family: AddressFamily, user_agent: String, redirect: Policy, tls: Option<&FeedTls>) -> Result<(Client, Arc<FamilyResolver>), String> {
+    let resolver = Arc::new(FamilyResolver::new(family, Arc::new(SystemResolver)));
+    let mut builder = Client::builder()
+        .user_agent(user_agent)
+        .redirect(redirect)
+        .timeout(FETCH_TIMEOUT)
+        .dns_resolver(resolver.clone());
+    if let Some(tls) = tls {
+        builder = tls.apply(builder);
+    }
+    let client = builder.build().map_err(|e| format!("Error building client: {}", e))?;
+
+    Ok((client, resolver))
+}
+
+/// Same limit as `Policy::limited(2)`, stopping at the first redirect to another origin.
+fn same_origin_policy(feed_url: Url) -> Policy {
+    Policy::custom(move |attempt| {
+        if attempt.url().origin() != feed_url.origin() {
+            attempt.stop()
+        } else if attempt.previous().len() > 2 {
+            attempt.error("too many redirects")
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
+/// A host publishing an IPv6 address it doesn't answer on times out whenever its IPv6 addresses come first,
+/// which the `Ipv6Unreachable` error suggests avoiding with the IPv4 only setting of the feed.
+fn fetch_error(e: &reqwest::Error, resolver: &FamilyResolver, tls: Option<&FeedTls>) -> String {
+    if let Some(error) = certificate_error(e, tls) {
+        return error;
+    }
+
+    let resolved = resolver.resolved();
+    let ipv6_first = resolved.first().is_some_and(|address| address.is_ipv6())
+        && resolved.iter().any(|address| address.is_ipv4());
+
+    if resolver.family() == AddressFamily::Auto && ipv6_first && (e.is_timeout() || e.is_connect()) {
+        return format!("Ipv6Unreachable: {}. The host may not answer on IPv6, try IPv4 only in the settings of the feed", e);
+    }
+
+    format!("Error fetching feed: {}", e)
+}
+
+/// Same as `fetch_feed` with the session cookies, credentials and certificates of the feed, following redirects
+/// by hand so they are only ever sent to the origin of the feed. Other hosts are fetched by a client of their
+/// own, which never presents the client certificate. Returns None when the site rejects them.
+async fn fetch_with_secrets(feed_link: &str, cookies: &[FeedCookie], credentials: Option<&FeedCredentials>, tls: Option<&FeedTls>, validators: &FeedValidators, network: FeedNetwork, app_handle: &AppHandle) -> Result<Option<FeedFetch>, String> {
+    if let Some(error) = tls.and_then(expired_error) {
+        return Err(error);
+    }
+
+    let family = network.address_family.or(Preferences::load(app_handle).address_family);
+    let client = feed_client(family, network.user_agent, Policy::none(), None, app_handle)?;
+    let origin_client = match tls {
+        Some(tls) => Some(feed_client(family, network.user_agent, Policy::none(), Some(tls), app_handle)?),
+        None => None,
+    };
+
+    let feed_url = Url::parse(feed_link).map_err(|e| format!("Invalid feed URL: {}", e))?;
+    follow_from_origin(feed_url, cookies, credentials, tls, validators, &client, origin_client.as_ref()).await
+}
+
+/// The redirects of `fetch_with_secrets`, `origin_client` fetching from the origin of the feed when it has
+/// certificates and `client` from anywhere else.
+async fn follow_from_origin(feed_url: Url, cookies: &[FeedCookie], credentials: Option<&FeedCredentials>, tls: Option<&FeedTls>, validators: &FeedValidators, client: &(Client, Arc<FamilyResolver>), origin_client: Option<&(Client, Arc<FamilyResolver>)>) -> Result<Option<FeedFetch>, String> {
+    let mut url = feed_url.clone();
+
+    // Same redirect limit as fetch_feed
+    for _ in 0..=2 {
+        let same_origin = url.origin() == feed_url.origin();
+        let ((client, resolver), tls) = match origin_client {
+            Some(origin_client) if same_origin => (origin_client, tls),
+            _ => (client, None),
+        };
 
-    let response = client.get(feed_link).send().await.expect("Error fetching feed");
-    let text = response.text().await.expect("Error reading response text");
+        let mut request = validators.apply(client.get(url.clone()).header(ACCEPT, FEED_ACCEPT));
+        if same_origin {
+            if !cookies.is_empty() {
+                request = request.header(COOKIE, cookie_header(cookies));
+            }
+            if let Some(credentials) = credentials {
+                request = credentials.apply(request);
+            }
+        }
+
+        let response = request.send().await.map_err(|e| fetch_error(&e, resolver, tls))?;
+        let status = response.status();
+        let received = FeedValidators::from_headers(response.headers());
+
+        // A 3xx as well, but not a redirect
+        if status == StatusCode::NOT_MODIFIED {
+            return Ok(Some(FeedFetch::NotModified(received)));
+        }
+
+        if status.is_redirection() {
+            let location = response.headers().get(LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .ok_or_else(|| String::from("Error fetching feed: redirect without a location"))?;
+            url = url.join(location).map_err(|e| format!("Error fetching feed: {}", e))?;
+            continue;
+        }
+
+        let text = read_text(response).await?;
+
+        // Checked first, a country block isn't the fault of the session or credentials
+        if let Some(error) = geo_block_error(status, &text) {
+            return Err(error);
+        }
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return Ok(None);
+        }
+
+        // Only sessions end up on a login page, a rejected Basic auth is a 401
+        return Ok((cookies.is_empty() || !is_login_page(&text)).then_some(FeedFetch::Modified { content: text, validators: received }));
+    }
+
+    Err(String::from("Error fetching feed: too many redirects"))
+}
+
+/// A session rejected after having worked has expired, which is notified once with `feed-session-expired`.
+async fn reject_session(identifier: &str, state: SessionState, app_handle: &AppHandle) -> String {
+    if state == SessionState::New {
+        return String::from("Error fetching feed: the site rejected the cookies");
+    }
+
+    let writing = (state == SessionState::Working).then(|| app_handle.state::<WriteGate>().enter().ok()).flatten();
+    if writing.is_some() {
+        let pool = app_handle.state::<Pool<Sqlite>>();
+        if let Err(e) = set_session_state(identifier, SessionState::Expired, &*pool).await {
+            eprintln!("Error expiring session of feed {}: {}", identifier, e);
+        }
+        if let Err(e) = app_handle.emit_all("feed-session-expired", identifier.to_string()) {
+            eprintln!("Error emitting session expiry: {}", e);
+        }
+    }
+
+    format!("SessionExpired: {}", identifier)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::net::SocketAddr;
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio_rustls::rustls::crypto::ring::default_provider;
+    use tokio_rustls::rustls::pki_types::pem::PemObject;
+    use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+    use tokio_rustls::rustls::server::WebPkiClientVerifier;
+    use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+    use tokio_rustls::TlsAcceptor;
+    use crate::utils::certificates::{read_ca_certificate, read_client_certificate};
+    use super::*;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tls/");
+    const FEED: &str = "<rss version=\"2.0\"><channel><title>Homelab</title></channel></rss>";
+
+    /// Each connection to a test server, as whether it presented a client certificate and the head of its
+    /// request, None when the handshake failed.
+    type Connections = Arc<Mutex<Vec<(bool, Option<String>)>>>;
+
+    fn fixture(name: &str) -> String {
+        format!("{}{}", FIXTURES, name)
+    }
+
+    fn ok(body: &str) -> String {
+        format!("HTTP/1.1 200 OK\r\ncontent-type: application/rss+xml\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}", body.len(), body)
+    }
 
-    Ok(text)
+    fn redirect(location: &str) -> String {
+        format!("HTTP/1.1 302 Found\r\nlocation: {}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n", location)
+    }
+
+    /// A server of the test CA asking for a client certificate of it, `require_certificate` refusing the
+    /// connections without one.
+    fn server_config(require_certificate: bool) -> Arc<ServerConfig> {
+        let provider = Arc::new(default_provider());
+        let mut roots = RootCertStore::empty();
+        roots.add(CertificateDer::from_pem_file(fixture("ca.pem")).unwrap()).unwrap();
+        let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider.clone());
+        let verifier = if require_certificate { verifier } else { verifier.allow_unauthenticated() };
+
+        let certificates = CertificateDer::pem_file_iter(fixture("server.pem")).unwrap()
+            .collect::<Result<Vec<_>, _>>().unwrap();
+        let key = PrivateKeyDer::from_pem_file(fixture("server.key")).unwrap();
+        let config = ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions().unwrap()
+            .with_client_cert_verifier(verifier.build().unwrap())
+            .with_single_cert(certificates, key).unwrap();
+        Arc::new(config)
+    }
+
+    /// Answers the requests for the paths of `routes` on 127.0.0.1, over TLS with `tls`, and records the
+    /// connections. Other paths are a 404.
+    async fn serve(tls: Option<Arc<ServerConfig>>, routes: Vec<(&'static str, String)>) -> (SocketAddr, Connections) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let connections = Connections::default();
+
+        let recorded = connections.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (routes, connections) = (routes.clone(), recorded.clone());
+                let Some(config) = tls.clone() else {
+                    tokio::spawn(answer(stream, false, routes, connections));
+                    continue;
+                };
+                tokio::spawn(async move {
+                    match TlsAcceptor::from(config).accept(stream).await {
+                        Ok(stream) => {
+                            let presented = stream.get_ref().1.peer_certificates().is_some();
+                            answer(stream, presented, routes, connections).await;
+                        },
+                        Err(_) => connections.lock().unwrap().push((false, None)),
+                    }
+                });
+            }
+        });
+
+        (address, connections)
+    }
+
+    async fn answer<S: AsyncRead + AsyncWrite + Unpin>(mut stream: S, presented: bool, routes: Vec<(&'static str, String)>, connections: Connections) {
+        let mut head = vec![];
+        let mut buffer = [0; 1024];
+        while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+            match stream.read(&mut buffer).await {
+                Ok(0) | Err(_) => return,
+                Ok(read) => head.extend_from_slice(&buffer[..read]),
+            }
+        }
+        let head = String::from_utf8_lossy(&head).to_lowercase();
+        let path = head.split_whitespace().nth(1).unwrap_or_default().to_string();
+        connections.lock().unwrap().push((presented, Some(head)));
+
+        let response = routes.into_iter()
+            .find_map(|(route, response)| (route == path).then_some(response))
+            .unwrap_or_else(|| String::from("HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"));
+        let _ = stream.write_all(response.as_bytes()).await;
+        let _ = stream.shutdown().await;
+    }
+
+    fn feed_tls(client_certificate: Option<&str>, ca: &str) -> FeedTls {
+        FeedTls {
+            identity: client_certificate.map(|name| read_client_certificate(&fixture(name), "alduin").unwrap()),
+            ca: Some(read_ca_certificate(&fixture(ca)).unwrap()),
+        }
+    }
+
+    /// Fetches like `fetch_with_secrets` with `tls` and nothing else.
+    async fn fetch(url: String, tls: &FeedTls) -> Result<Option<FeedFetch>, String> {
+        let client = build_client(AddressFamily::Auto, String::from("Alduin/test"), Policy::none(), None).unwrap();
+        let origin_client = build_client(AddressFamily::Auto, String::from("Alduin/test"), Policy::none(), Some(tls)).unwrap();
+
+        follow_from_origin(Url::parse(&url).unwrap(), &[], None, Some(tls), &FeedValidators::default(), &client, Some(&origin_client)).await
+    }
+
+    fn content(fetch: Result<Option<FeedFetch>, String>) -> String {
+        match fetch {
+            Ok(Some(FeedFetch::Modified { content, .. })) => content,
+            Ok(Some(FeedFetch::NotModified(_))) => String::from("not modified"),
+            Ok(None) => String::from("rejected"),
+            Err(e) => e,
+        }
+    }
+
+    #[tokio::test]
+    async fn presents_the_client_certificate_to_the_origin() {
+        let routes = vec![("/feed", redirect("/moved")), ("/moved", ok(FEED))];
+        let (origin, connections) = serve(Some(server_config(true)), routes).await;
+
+        let fetched = fetch(format!("https://{}/feed", origin), &feed_tls(Some("client.p12"), "ca.pem")).await;
+
+        assert_eq!(content(fetched), FEED);
+        let presented: Vec<bool> = connections.lock().unwrap().iter().map(|(presented, _)| *presented).collect();
+        assert_eq!(presented, [true, true]);
+    }
+
+    #[tokio::test]
+    async fn certificate_errors() {
+        let (origin, _) = serve(Some(server_config(true)), vec![("/feed", ok(FEED))]).await;
+        let cases = [
+            (None, "ca.pem", "ClientCertificateRejected: the site asks for a client certificate"),
+            (Some("client.p12"), "other-ca.pem", "ServerCertificateUntrusted: the certificate of the site is signed neither by O=Alduin Tests, CN=Other Test CA"),
+        ];
+
+        for (client_certificate, ca, expected) in cases {
+            let fetched = content(fetch(format!("https://{}/feed", origin), &feed_tls(client_certificate, ca)).await);
+            assert!(fetched.starts_with(expected), "{:?}: {}", client_certificate, fetched);
+        }
+    }
+
+    #[tokio::test]
+    async fn never_presents_the_client_certificate_to_other_origins() {
+        let (other, other_connections) = serve(Some(server_config(false)), vec![("/feed", ok(FEED))]).await;
+        let routes = vec![("/feed", redirect(&format!("https://{}/feed", other)))];
+        let (origin, connections) = serve(Some(server_config(true)), routes).await;
+
+        let fetched = content(fetch(format!("https://{}/feed", origin), &feed_tls(Some("client.p12"), "ca.pem")).await);
+
+        // The other origin gets neither the certificate nor the CA trusted for the feed
+        assert!(fetched.starts_with("ServerCertificateUntrusted: the certificate of the site isn't signed by a trusted authority"), "{}", fetched);
+        // Recorded once the server read the alert of the client
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(connections.lock().unwrap().len(), 1);
+        let other_connections = other_connections.lock().unwrap();
+        assert!(!other_connections.is_empty());
+        assert!(other_connections.iter().all(|(presented, _)| !presented));
+    }
+
+    #[tokio::test]
+    async fn never_sends_the_session_or_credentials_to_other_origins() {
+        let (other, other_connections) = serve(None, vec![("/feed", ok(FEED))]).await;
+        let routes = vec![("/feed", redirect(&format!("http://{}/feed", other)))];
+        let (origin, connections) = serve(None, routes).await;
+        let cookies = [FeedCookie { name: String::from("session"), value: String::from("s3cr3t") }];
+        let credentials = FeedCredentials {
+            username: Some(String::from("reader")),
+            password: Some(String::from("hunter2")),
+            headers: BTreeMap::from([(String::from("x-token"), String::from("t0k3n"))]),
+        };
+        let client = build_client(AddressFamily::Auto, String::from("Alduin/test"), Policy::none(), None).unwrap();
+
+        let fetched = follow_from_origin(Url::parse(&format!("http://{}/feed", origin)).unwrap(), &cookies, Some(&credentials), None, &FeedValidators::default(), &client, None).await;
+
+        assert_eq!(content(fetched), FEED);
+        let connections = connections.lock().unwrap();
+        let head = connections[0].1.as_deref().unwrap();
+        assert!(head.contains("cookie: session=s3cr3t") && head.contains("authorization: basic") && head.contains("x-token: t0k3n"), "{}", head);
+        let other_connections = other_connections.lock().unwrap();
+        let head = other_connections[0].1.as_deref().unwrap();
+        assert!(!head.contains("cookie:") && !head.contains("authorization:") && !head.contains("x-token:"), "{}", head);
+    }
+
+    /// Publishes a dead IPv6 address first, then the IPv4 one of the test server.
+    struct BrokenIpv6Resolver;
+
+    impl reqwest::dns::Resolve for BrokenIpv6Resolver {
+        fn resolve(&self, _: hyper::client::connect::dns::Name) -> reqwest::dns::Resolving {
+            let addresses: Vec<SocketAddr> = vec!["[2001:db8::1]:0".parse().unwrap(), "127.0.0.1:0".parse().unwrap()];
+            Box::pin(async move { Ok(Box::new(addresses.into_iter()) as reqwest::dns::Addrs) })
+        }
+    }
+
+    async fn fetch_over(family: AddressFamily, port: u16) -> Result<u16, String> {
+        let resolver = Arc::new(FamilyResolver::new(family, Arc::new(BrokenIpv6Resolver)));
+        let client = Client::builder()
+            .dns_resolver(resolver.clone())
+            .connect_timeout(Duration::from_millis(500))
+            .build().unwrap();
+
+        client.get(format!("http://feeds.example:{}/feed.xml", port)).send().await
+            .map(|response| response.status().as_u16())
+            .map_err(|e| fetch_error(&e, &resolver, None))
+    }
+
+    #[tokio::test]
+    async fn suggests_ipv4_when_the_ipv6_addresses_come_first() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request).await;
+                let _ = stream.write_all(ok(FEED).as_bytes()).await;
+            }
+        });
+
+        assert_eq!(fetch_over(AddressFamily::Ipv4, port).await, Ok(200));
+        let error = fetch_over(AddressFamily::Ipv6, port).await.unwrap_err();
+        assert!(error.starts_with("Error fetching feed"), "{}", error);
+
+        // A port nothing answers on, the IPv6 address having been tried first
+        let closed = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+        let error = fetch_over(AddressFamily::Auto, closed).await.unwrap_err();
+        assert!(error.starts_with("Ipv6Unreachable"), "{}", error);
+        let error = fetch_over(AddressFamily::Ipv4, closed).await.unwrap_err();
+        assert!(error.starts_with("Error fetching feed"), "{}", error);
+    }
 }
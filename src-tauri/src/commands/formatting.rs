@@ -0,0 +1,10 @@
+use tauri::AppHandle;
+use crate::enums::timestamp_style::TimestampStyle;
+use crate::structs::preferences::Preferences;
+use crate::utils::time_format;
+
+/// Formats a timestamp in milliseconds like the backend does in its messages, for both to agree.
+#[alduin_macros::command]
+pub async fn format_timestamp(timestamp: i64, style: TimestampStyle, app_handle: AppHandle) -> Result<String, String> {
+    Ok(time_format::format_timestamp(timestamp, style, Preferences::load(&app_handle).language))
+}
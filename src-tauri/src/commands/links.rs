@@ -0,0 +1,87 @@
+use tauri::{AppHandle, Manager};
+use url::Url;
+use crate::commands::feeds::find_duplicate_feed;
+use crate::commands::fetcher::fetch_feed;
+use crate::enums::address_family::AddressFamily;
+use crate::enums::feed_type::FeedType;
+use crate::enums::link_action::LinkAction;
+use crate::enums::link_action_result::LinkActionResult;
+use crate::parsers::feed_content::parse_feed;
+use crate::parsers::feed_links::feed_links;
+use crate::structs::article_cursor::ArticleCursor;
+use crate::structs::article_page::ArticlePage;
+use crate::structs::feed_candidate::FeedCandidate;
+use crate::structs::read_pool::ReadPool;
+use crate::structs::stored_article::StoredArticle;
+use crate::utils::title::clean_title;
+use crate::utils::url::{clean_url, link_key};
+
+const LINKING_ARTICLES_PAGE_SIZE: i64 = 50;
+
+/// Runs an action on a link right-clicked in an article, so the context menu only needs this command.
+#[alduin_macros::command]
+pub async fn link_action(url: String, action: LinkAction, app_handle: AppHandle) -> Result<LinkActionResult, String> {
+    match action {
+        LinkAction::Subscribe => Ok(LinkActionResult::FeedCandidates { candidates: discover_feeds(url, &app_handle).await? }),
+        LinkAction::CopyClean => clean_url(&url)
+            .map(|url| LinkActionResult::CleanUrl { url })
+            .ok_or_else(|| format!("Invalid URL: {}", url)),
+    }
+}
+
+/// Lists the articles linking to a page, most recent first. A bare domain like `example.com`
+/// matches every page of the domain and of its subdomains instead.
+#[alduin_macros::command]
+pub async fn find_articles_linking_to(url_or_domain: String, cursor: Option<ArticleCursor>, app_handle: AppHandle) -> Result<ArticlePage, String> {
+    let (host, path) = link_target(&url_or_domain)
+        .ok_or_else(|| format!("Invalid URL or domain: {}", url_or_domain))?;
+
+    let pool = app_handle.state::<ReadPool>();
+    let articles = sqlx::query_as::<_, StoredArticle>(include_str!("../database/queries/find_articles_linking_to.sql"))
+        .bind(host)
+        .bind(path)
+        .bind(cursor.as_ref().map(|cursor| cursor.date))
+        .bind(cursor.map(|cursor| cursor.identifier))
+        .bind(LINKING_ARTICLES_PAGE_SIZE)
+        .fetch_all(&**pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(ArticlePage::new(articles, LINKING_ARTICLES_PAGE_SIZE))
+}
+
+/// Returns the host to look for, with the path unless the whole domain is wanted.
+fn link_target(url_or_domain: &str) -> Option<(String, Option<String>)> {
+    let input = url_or_domain.trim();
+    let has_scheme = input.contains("://");
+    let url = Url::parse(&if has_scheme { input.to_string() } else { format!("https://{}", input) }).ok()?;
+    let (host, path) = link_key(&url)?;
+
+    let whole_domain = !has_scheme && !input.trim_end_matches('/').contains('/');
+    Some((host, (!whole_domain).then_some(path)))
+}
+
+pub async fn discover_feeds(url: String, app_handle: &AppHandle) -> Result<Vec<FeedCandidate>, String> {
+    let base = Url::parse(url.trim()).map_err(|e| format!("Invalid URL: {}", e))?;
+    if !matches!(base.scheme(), "http" | "https") {
+        return Err(format!("Unsupported URL scheme: {}", base.scheme()));
+    }
+
+    let content = fetch_feed(base.to_string(), AddressFamily::Auto, app_handle.clone()).await?;
+
+    // The link may point to a feed rather than to a page advertising some
+    let mut candidates = match parse_feed(&content) {
+        Ok((feed, _, _)) => vec![FeedCandidate {
+            url: base.to_string(),
+            title: feed.title.map(|title| clean_title(&title.content)),
+            feed_type: Some(FeedType::from(feed.feed_type)),
+            existing_feed: None,
+        }],
+        Err(_) => feed_links(&content, &base),
+    };
+
+    for candidate in candidates.iter_mut() {
+        candidate.existing_feed = find_duplicate_feed(candidate.url.clone(), app_handle.clone()).await?;
+    }
+
+    Ok(candidates)
+}
@@ -0,0 +1,110 @@
+use sqlx::types::Json;
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager, Window, WindowBuilder, WindowUrl, Wry};
+use crate::structs::list_filter::ListFilter;
+use crate::structs::list_window::ListWindow;
+use crate::structs::list_windows::ListWindows;
+use crate::structs::read_pool::ReadPool;
+use crate::structs::write_gate::WriteGate;
+use crate::utils::ulid::ulid;
+
+pub const LIST_WINDOW_PREFIX: &str = "list-";
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Opens a window listing the articles of `filter`, next to the main window and independent of what it
+/// shows. The filter is kept in the session state of the window, which the next session reopens it from.
+/// Returns the label of the window.
+#[alduin_macros::command]
+pub async fn open_list_window(filter: ListFilter, title: String, app_handle: AppHandle) -> Result<String, String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let title: String = title.trim().chars().take(MAX_TITLE_CHARS).collect();
+    let title = if title.is_empty() { String::from("Articles") } else { title };
+    let list_window = ListWindow { label: format!("{}{}", LIST_WINDOW_PREFIX, ulid()), title, filter: Json(filter) };
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    sqlx::query(include_str!("../database/queries/save_list_window.sql"))
+        .bind(&list_window.label)
+        .bind(&list_window.title)
+        .bind(&list_window.filter)
+        .execute(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    create_list_window(&app_handle, &list_window)?;
+    Ok(list_window.label)
+}
+
+/// The title and filter of the calling window, none for the windows that aren't list windows.
+#[alduin_macros::command]
+pub async fn get_list_window(window: Window, app_handle: AppHandle) -> Result<Option<ListWindow>, String> {
+    let pool = app_handle.state::<ReadPool>();
+
+    sqlx::query_as::<_, ListWindow>(include_str!("../database/queries/get_list_window.sql"))
+        .bind(window.label())
+        .fetch_optional(&**pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// Reopens the list windows left open by the last session, once, when the main window is first shown.
+pub async fn restore_list_windows(app_handle: AppHandle) {
+    let Some(pool) = app_handle.try_state::<ReadPool>() else { return };
+    if !app_handle.state::<ListWindows>().start_restore() {
+        return;
+    }
+
+    let list_windows = match sqlx::query_as::<_, ListWindow>(include_str!("../database/queries/get_list_windows.sql"))
+        .fetch_all(&**pool).await {
+        Ok(list_windows) => list_windows,
+        Err(e) => return eprintln!("Error loading the list windows: {}", e),
+    };
+    for list_window in list_windows {
+        if let Err(e) = create_list_window(&app_handle, &list_window) {
+            eprintln!("{}", e);
+        }
+    }
+}
+
+/// Forgets a list window closed by the user, so the next session doesn't reopen it. The ones still open
+/// when the app quits are kept, their windows being destroyed without being asked to close.
+pub async fn forget_list_window(label: String, app_handle: AppHandle) {
+    if !app_handle.state::<ListWindows>().unsubscribe(&label) {
+        return;
+    }
+    let _writing = match app_handle.state::<WriteGate>().enter() {
+        Ok(writing) => writing,
+        Err(e) => return eprintln!("Error forgetting list window {}: {}", label, e),
+    };
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    if let Err(e) = sqlx::query(include_str!("../database/queries/delete_list_window.sql"))
+        .bind(&label)
+        .execute(&*pool).await {
+        eprintln!("Error forgetting list window {}: {}", label, e);
+    }
+}
+
+pub fn is_list_window(label: &str) -> bool {
+    label.starts_with(LIST_WINDOW_PREFIX)
+}
+
+/// Registers its filter before creating it, so it gets the events about its articles from the start.
+fn create_list_window(app_handle: &AppHandle<Wry>, list_window: &ListWindow) -> Result<(), String> {
+    if let Some(window) = app_handle.get_window(&list_window.label) {
+        return window.set_focus().map_err(|e| format!("Error focusing list window {}: {}", list_window.label, e));
+    }
+
+    let list_windows = app_handle.state::<ListWindows>();
+    list_windows.subscribe(&list_window.label, list_window.filter.0.clone());
+    let created = WindowBuilder::new(app_handle, list_window.label.clone(), WindowUrl::App("index.html".into()))
+        .title(format!("{} - Alduin", list_window.title))
+        .inner_size(420.0, 640.0)
+        .min_inner_size(200.0, 200.0)
+        .decorations(false)
+        .build();
+
+    if let Err(e) = created {
+        list_windows.unsubscribe(&list_window.label);
+        return Err(format!("Error creating list window {}: {}", list_window.label, e));
+    }
+    Ok(())
+}
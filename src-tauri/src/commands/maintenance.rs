@@ -0,0 +1,135 @@
+use std::time::Duration;
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::database::{backfill, health};
+use crate::enums::schema_failure::SchemaFailure;
+use crate::structs::command_timing::CommandTiming;
+use crate::structs::pool_health::PoolHealth;
+use crate::structs::preferences::Preferences;
+use crate::structs::preferences_upgrade::PreferencesUpgrade;
+use crate::structs::resource_stats::ResourceStats;
+use crate::structs::schema_check::SchemaCheck;
+use crate::structs::schema_info::SchemaInfo;
+use crate::structs::version_mismatch::VersionMismatch;
+use crate::utils::command_timing::command_timings;
+use crate::utils::memory::resident_memory_bytes;
+use crate::structs::write_freeze_payload::WriteFreezePayload;
+use crate::structs::write_gate::WriteGate;
+
+const DEFAULT_MAX_FREEZE_MINUTES: u64 = 10;
+/// How long a freeze waits for the writes already running, a sync storing a large feed taking a few seconds.
+const FREEZE_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Stops every backend write, waits for the ones running to commit or roll back, then checkpoints the WAL
+/// so the database file is consistent on disk. Writes thaw automatically after `max_minutes` so a crashed
+/// backup tool can't freeze the app forever.
+#[alduin_macros::command]
+pub async fn freeze_writes(reason: String, max_minutes: Option<u64>, app_handle: AppHandle) -> Result<(), String> {
+    let gate = app_handle.state::<WriteGate>();
+    let generation = gate.freeze(reason.clone());
+
+    if tokio::time::timeout(FREEZE_DRAIN_TIMEOUT, gate.wait_for_writers()).await.is_err() {
+        gate.thaw_generation(generation);
+        return Err(format!("Error freezing writes: some were still running after {} s", FREEZE_DRAIN_TIMEOUT.as_secs()));
+    }
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    if let Err(e) = sqlx::query(include_str!("../database/queries/wal_checkpoint.sql")).execute(&*pool).await {
+        gate.thaw_generation(generation);
+        return Err(format!("Error checkpointing database: {}", e));
+    }
+
+    app_handle.emit_all("writes-frozen", WriteFreezePayload { frozen: true, reason: Some(reason) })
+        .map_err(|e| format!("Error emitting freeze: {}", e))?;
+
+    let max_minutes = max_minutes.unwrap_or(DEFAULT_MAX_FREEZE_MINUTES);
+    let handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(max_minutes * 60)).await;
+        if handle.state::<WriteGate>().thaw_generation(generation) {
+            eprintln!("Writes automatically thawed after {} minutes", max_minutes);
+            if let Err(e) = handle.emit_all("writes-frozen", WriteFreezePayload { frozen: false, reason: None }) {
+                eprintln!("Error emitting thaw: {}", e);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[alduin_macros::command]
+pub async fn thaw_writes(app_handle: AppHandle) -> Result<(), String> {
+    if app_handle.state::<WriteGate>().thaw() {
+        app_handle.emit_all("writes-frozen", WriteFreezePayload { frozen: false, reason: None })
+            .map_err(|e| format!("Error emitting thaw: {}", e))?;
+    }
+
+    Ok(())
+}
+
+#[alduin_macros::command]
+pub async fn get_pool_health(app_handle: AppHandle) -> Result<PoolHealth, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+
+    Ok(PoolHealth {
+        size: pool.size(),
+        idle: pool.num_idle(),
+        acquire_timeouts: health::acquire_timeouts(),
+        file_replacements: health::file_replacements(),
+    })
+}
+
+/// Shows whether low resource mode is active and what it changes, with the memory used by the backend.
+#[alduin_macros::command]
+pub async fn get_resource_stats(app_handle: AppHandle) -> Result<ResourceStats, String> {
+    let preferences = Preferences::load(&app_handle);
+
+    Ok(ResourceStats {
+        low_resource_mode: preferences.low_resource_mode,
+        sync_concurrency: preferences.sync_concurrency(),
+        recompute_batch_size: preferences.recompute_batch_size(),
+        resident_memory_bytes: resident_memory_bytes(),
+    })
+}
+
+/// How long each command took since startup, to back "the app feels slow" reports with numbers.
+#[alduin_macros::command]
+pub async fn get_command_timings() -> Result<Vec<CommandTiming>, String> {
+    Ok(command_timings())
+}
+
+/// Reports the schema version, and the progress of the backfills still catching up with it.
+#[alduin_macros::command]
+pub async fn get_schema_info(app_handle: AppHandle) -> Result<SchemaInfo, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+
+    let version = sqlx::query_scalar::<_, i64>(include_str!("../database/queries/get_schema_version.sql"))
+        .fetch_one(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(SchemaInfo {
+        version,
+        backfills: backfill::backfill_progress(&pool).await?,
+    })
+}
+
+/// Set when this version of the app is too old for the database, which is then opened read-only.
+/// Also emitted at startup as `startup:version-mismatch`.
+#[alduin_macros::command]
+pub async fn get_version_mismatch(app_handle: AppHandle) -> Result<Option<VersionMismatch>, String> {
+    Ok(app_handle.state::<SchemaCheck>().mismatch.clone())
+}
+
+/// How the preferences file was upgraded at startup, which preferences were reset and whether it can be
+/// saved. Also emitted at startup as `startup:preferences-reset` when preferences were lost or can't be saved.
+#[alduin_macros::command]
+pub async fn get_preferences_upgrade(app_handle: AppHandle) -> Result<PreferencesUpgrade, String> {
+    Ok(app_handle.state::<PreferencesUpgrade>().inner().clone())
+}
+
+/// Set when the migrations failed or left the database on a schema other than the expected one, the
+/// database being then opened read-only. Also emitted at startup as `startup:schema-failure`.
+#[alduin_macros::command]
+pub async fn get_schema_failure(app_handle: AppHandle) -> Result<Option<SchemaFailure>, String> {
+    Ok(app_handle.state::<SchemaCheck>().failure.clone())
+}
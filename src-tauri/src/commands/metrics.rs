@@ -0,0 +1,85 @@
+use std::fs;
+use chrono::NaiveDate;
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::commands::export::default_export_directory;
+use crate::database::local_metrics;
+use crate::enums::export_format::ExportFormat;
+use crate::enums::language::Language;
+use crate::structs::local_metric_day::LocalMetricDay;
+use crate::structs::preferences::Preferences;
+use crate::structs::write_gate::WriteGate;
+use crate::utils::export_path::{default_file_name, io_path, resolve_export_path};
+use crate::utils::time_format::{today, week_start};
+
+/// Returns the daily metrics between two dates formatted as YYYY-MM-DD, both included, or with `by_week`
+/// their sums per week, dated by its first day in the language of the preferences.
+#[alduin_macros::command]
+pub async fn get_local_metrics(from: String, to: String, by_week: Option<bool>, app_handle: AppHandle) -> Result<Vec<LocalMetricDay>, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let metrics = load_local_metrics(&from, &to, &pool).await?;
+
+    Ok(if by_week.unwrap_or(false) { sum_weeks(metrics, Preferences::load(&app_handle).language) } else { metrics })
+}
+
+/// Writes the daily metrics between two dates to `path`, or the default export folder, under another name
+/// if taken unless `overwrite`. Returns the path written.
+#[alduin_macros::command]
+pub async fn export_local_metrics(path: Option<String>, format: ExportFormat, from: String, to: String, overwrite: Option<bool>, app_handle: AppHandle) -> Result<String, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let metrics = load_local_metrics(&from, &to, &pool).await?;
+
+    let content = match format {
+        ExportFormat::Csv => std::iter::once(String::from("day,metric,value"))
+            .chain(metrics.iter().map(|row| format!("{},{},{}", row.day, row.metric.as_str(), row.value)))
+            .map(|line| line + "\n")
+            .collect(),
+        ExportFormat::Json => serde_json::to_string_pretty(&metrics)
+            .map_err(|e| format!("Error serializing metrics: {}", e))?,
+    };
+
+    let extension = match format {
+        ExportFormat::Csv => "csv",
+        ExportFormat::Json => "json",
+    };
+    let default_name = default_file_name("alduin-metrics", extension, today());
+    let path = resolve_export_path(path.as_deref(), &default_export_directory(&pool).await?, &default_name, overwrite.unwrap_or(false))?;
+    fs::write(io_path(&path), content).map_err(|e| format!("Error writing {}: {}", path.display(), e))?;
+    Ok(path.display().to_string())
+}
+
+/// Called by the frontend when the metrics get disabled.
+#[alduin_macros::command]
+pub async fn clear_local_metrics(app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    local_metrics::clear(&pool).await
+}
+
+async fn load_local_metrics(from: &str, to: &str, pool: &Pool<Sqlite>) -> Result<Vec<LocalMetricDay>, String> {
+    for day in [from, to] {
+        NaiveDate::parse_from_str(day, "%Y-%m-%d").map_err(|e| format!("Invalid date {}: {}", day, e))?;
+    }
+
+    sqlx::query_as::<_, LocalMetricDay>(include_str!("../database/queries/get_local_metrics.sql"))
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// The days come sorted, so those of a week follow each other.
+fn sum_weeks(days: Vec<LocalMetricDay>, language: Language) -> Vec<LocalMetricDay> {
+    let mut weeks: Vec<LocalMetricDay> = vec![];
+    for day in days {
+        let Ok(date) = NaiveDate::parse_from_str(&day.day, "%Y-%m-%d") else { continue };
+        let week = week_start(date, language).format("%Y-%m-%d").to_string();
+
+        match weeks.iter_mut().rev().take_while(|row| row.day == week).find(|row| row.metric.as_str() == day.metric.as_str()) {
+            Some(row) => row.value += day.value,
+            None => weeks.push(LocalMetricDay { day: week, ..day }),
+        }
+    }
+    weeks
+}
@@ -1,2 +1,40 @@
 pub mod fetcher;
 pub mod splashscreen;
+pub mod comments;
+pub mod feeds;
+pub mod scoring;
+pub mod articles;
+pub mod session;
+pub mod dedup;
+pub mod maintenance;
+pub mod post_processors;
+pub mod feed_changes;
+pub mod enclosures;
+pub mod autostart;
+pub mod search;
+pub mod tray;
+pub mod cookies;
+pub mod links;
+pub mod webview;
+pub mod metrics;
+pub mod reading_queue;
+pub mod export;
+pub mod availability;
+pub mod extras;
+pub mod changes;
+pub mod benchmark;
+pub mod scheduler;
+pub mod state_exchange;
+pub mod opml;
+pub mod bookmarks;
+pub mod credentials;
+pub mod certificates;
+pub mod retention;
+pub mod quick_add;
+pub mod explain;
+pub mod self_test;
+pub mod list_windows;
+pub mod overrides;
+pub mod formatting;
+pub mod notifications;
+pub mod timeline;
@@ -0,0 +1,14 @@
+use tauri::{AppHandle, Manager};
+use crate::database::notification_ledger::recent_notifications;
+use crate::structs::notified_article::NotifiedArticle;
+use crate::structs::read_pool::ReadPool;
+use crate::utils::pagination::page_limit;
+
+const DEFAULT_HISTORY_LIMIT: i64 = 50;
+
+/// The articles last notified, most recent first, for the notifications missed while away.
+#[alduin_macros::command]
+pub async fn get_recent_notifications(limit: Option<i64>, app_handle: AppHandle) -> Result<Vec<NotifiedArticle>, String> {
+    let pool = app_handle.state::<ReadPool>();
+    recent_notifications(page_limit("get_recent_notifications", limit, DEFAULT_HISTORY_LIMIT), &pool).await
+}
@@ -0,0 +1,251 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use sqlx::{Pool, Sqlite, SqliteConnection};
+use sqlx::types::Json;
+use tauri::{AppHandle, Manager};
+use url::Url;
+use crate::commands::export::default_export_directory;
+use crate::database::change_journal::emit_change;
+use crate::structs::exported_feed::ExportedFeed;
+use crate::structs::folder::Folder;
+use crate::structs::opml_export_options::OpmlExportOptions;
+use crate::structs::opml_failure::OpmlFailure;
+use crate::structs::opml_feed_settings::OpmlFeedSettings;
+use crate::structs::opml_import::OpmlImport;
+use crate::structs::opml_outline::OpmlOutline;
+use crate::structs::write_gate::WriteGate;
+use crate::utils::export_path::{default_file_name, io_path, resolve_export_path};
+use crate::utils::input_limits::{read_file, MAX_OPML_BYTES};
+use crate::utils::opml::{feeds_opml, parse_opml};
+use crate::utils::time_format::today;
+use crate::utils::ulid::ulid;
+use crate::utils::url::canonical_url;
+
+/// Interval of the imported feeds, the default of the add feed form.
+const IMPORTED_FEED_INTERVAL: i64 = 5;
+
+/// Subscribes to the feeds of an OPML file, creating its folders. Feeds already subscribed to, under the same
+/// URL or a variant of it, are skipped. The settings of the `alduin:` attributes are applied to the new feeds. The frontend refreshes its feeds on `feeds-imported`, so the next
+/// `sync_all` includes them.
+#[alduin_macros::command]
+pub async fn import_opml(path: String, app_handle: AppHandle) -> Result<OpmlImport, String> {
+    app_handle.state::<WriteGate>().check()?;
+
+    let content = read_file(&path, MAX_OPML_BYTES)?;
+    import_outlines(parse_opml(&content)?, &app_handle).await
+}
+
+/// Same as `import_opml` for feeds picked by the user, like the ones `import_bookmarks_html` found.
+#[alduin_macros::command]
+pub async fn import_feeds(feeds: Vec<OpmlOutline>, app_handle: AppHandle) -> Result<OpmlImport, String> {
+    app_handle.state::<WriteGate>().check()?;
+
+    import_outlines(feeds, &app_handle).await
+}
+
+pub async fn import_outlines(outlines: Vec<OpmlOutline>, app_handle: &AppHandle) -> Result<OpmlImport, String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let mut known_urls: HashSet<String> = sqlx::query_as::<_, ExportedFeed>(include_str!("../database/queries/export_feeds.sql"))
+        .fetch_all(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .iter()
+        .filter_map(|feed| canonical_url(&feed.url))
+        .collect();
+
+    let mut import = OpmlImport::default();
+    let mut folders: HashMap<Vec<String>, String> = HashMap::new();
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+
+    for outline in outlines {
+        let Some(xml_url) = outline.xml_url else {
+            let mut path = outline.folder;
+            path.push(outline.title);
+            folder_identifier(&path, &mut folders, &mut import, &mut transaction).await?;
+            continue;
+        };
+
+        let canonical = match Url::parse(&xml_url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => canonical_url(url.as_str()),
+            _ => None,
+        };
+        let Some(canonical) = canonical else {
+            import.failed += 1;
+            import.failures.push(OpmlFailure { title: outline.title, xml_url, error: String::from("Not an HTTP or HTTPS URL") });
+            continue;
+        };
+        if known_urls.contains(&canonical) {
+            import.skipped += 1;
+            continue;
+        }
+
+        let folder = folder_identifier(&outline.folder, &mut folders, &mut import, &mut transaction).await?;
+        let identifier = ulid();
+        let inserted = sqlx::query(include_str!("../database/queries/import_feed.sql"))
+            .bind(&identifier)
+            .bind(&outline.title)
+            .bind(&xml_url)
+            .bind(IMPORTED_FEED_INTERVAL)
+            .bind(folder)
+            .bind(&outline.html_url)
+            .execute(&mut *transaction).await;
+        let inserted = match (inserted, &outline.settings) {
+            (Ok(_), Some(settings)) => apply_settings(&identifier, settings, &mut transaction).await,
+            (inserted, _) => inserted.map(|_| ()),
+        };
+
+        match inserted {
+            Ok(_) => {
+                import.added += 1;
+                known_urls.insert(canonical);
+            }
+            Err(e) => {
+                import.failed += 1;
+                import.failures.push(OpmlFailure { title: outline.title, xml_url, error: format!("Error executing query: {}", e) });
+            }
+        }
+    }
+
+    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))?;
+
+    emit_change(app_handle, "feeds-imported", import.clone()).await?;
+
+    Ok(import)
+}
+
+/// Writes the subscriptions as OPML 2.0, the feeds, folders and settings picked by `options`, everything when
+/// None, to `path` or the default export folder, under another name if taken unless `overwrite`. Returns the
+/// path written.
+#[alduin_macros::command]
+pub async fn export_opml(path: Option<String>, overwrite: Option<bool>, options: Option<OpmlExportOptions>, app_handle: AppHandle) -> Result<String, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let feeds = sqlx::query_as::<_, ExportedFeed>(include_str!("../database/queries/export_feeds.sql"))
+        .fetch_all(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    let folders = load_folders(&pool).await?;
+    let content = feeds_opml(&feeds, &folders, &options.unwrap_or_default(), &chrono::Utc::now().to_rfc2822());
+
+    let default_name = default_file_name("alduin-feeds", "opml", today());
+    let path = resolve_export_path(path.as_deref(), &default_export_directory(&pool).await?, &default_name, overwrite.unwrap_or(false))?;
+    fs::write(io_path(&path), content).map_err(|e| format!("Error writing {}: {}", path.display(), e))?;
+    Ok(path.display().to_string())
+}
+
+pub async fn load_folders(pool: &Pool<Sqlite>) -> Result<Vec<Folder>, String> {
+    sqlx::query_as::<_, Folder>(include_str!("../database/queries/get_folders.sql"))
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+async fn apply_settings(identifier: &str, settings: &OpmlFeedSettings, connection: &mut SqliteConnection) -> Result<(), sqlx::Error> {
+    sqlx::query(include_str!("../database/queries/import_feed_settings.sql"))
+        .bind(identifier)
+        .bind(settings.interval)
+        .bind(settings.priority)
+        .bind(settings.archived)
+        .bind(settings.dedup_strategy)
+        .bind(settings.dedup_strategy_locked)
+        .bind(settings.address_family)
+        .bind(settings.user_agent)
+        .bind(settings.reread_on_update)
+        .bind(settings.retention_days)
+        .bind(settings.retention_max_articles)
+        .bind(settings.title_prefixes.as_ref().map(Json))
+        .execute(connection).await
+        .map(|_| ())
+}
+
+/// The folder at `path`, reusing the existing folders and creating the missing ones. None for the root.
+async fn folder_identifier(path: &[String], folders: &mut HashMap<Vec<String>, String>, import: &mut OpmlImport, connection: &mut SqliteConnection) -> Result<Option<String>, String> {
+    let mut parent: Option<String> = None;
+
+    for depth in 1..=path.len() {
+        if let Some(identifier) = folders.get(&path[..depth]) {
+            parent = Some(identifier.clone());
+            continue;
+        }
+
+        let name = &path[depth - 1];
+        let existing = sqlx::query_scalar::<_, String>(include_str!("../database/queries/find_folder.sql"))
+            .bind(name)
+            .bind(&parent)
+            .fetch_optional(&mut *connection).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+
+        let identifier = match existing {
+            Some(identifier) => identifier,
+            None => {
+                let identifier = ulid();
+                sqlx::query(include_str!("../database/queries/insert_folder.sql"))
+                    .bind(&identifier)
+                    .bind(name)
+                    .bind(&parent)
+                    .execute(&mut *connection).await
+                    .map_err(|e| format!("Error executing query: {}", e))?;
+                import.folders_added += 1;
+                identifier
+            }
+        };
+
+        folders.insert(path[..depth].to_vec(), identifier.clone());
+        parent = Some(identifier);
+    }
+
+    Ok(parent)
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::database::migrator::migrator;
+    use super::*;
+
+    async fn database() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        pool
+    }
+
+    /// The exported feeds without their identifier, which an import doesn't keep
+    async fn exported(pool: &Pool<Sqlite>) -> Vec<serde_json::Value> {
+        sqlx::query_as::<_, ExportedFeed>(include_str!("../database/queries/export_feeds.sql"))
+            .fetch_all(pool).await.unwrap()
+            .iter()
+            .map(|feed| {
+                let mut feed = serde_json::to_value(feed).unwrap();
+                feed.as_object_mut().unwrap().remove("identifier");
+                feed
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn export_then_import_keeps_the_settings() {
+        let source = database().await;
+        sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval, html_url) VALUES ('tuned', 'Tuned', 'https://example.com/tuned.xml', 5, 'https://example.com'), ('plain', 'Plain', 'https://example.com/plain.xml', 5, NULL)")
+            .execute(&source).await.unwrap();
+        sqlx::query("UPDATE feeds SET interval = 240, priority = 'low', archived = 1, dedup_strategy = 'link_title', dedup_strategy_locked = 1, address_family = 'ipv4', user_agent = 'browser', reread_on_update = 1, retention_days = 0, retention_max_articles = 50, title_prefixes = '[\"Ad: \"]' WHERE identifier = 'tuned'")
+            .execute(&source).await.unwrap();
+        let feeds = sqlx::query_as::<_, ExportedFeed>(include_str!("../database/queries/export_feeds.sql"))
+            .fetch_all(&source).await.unwrap();
+
+        let opml = feeds_opml(&feeds, &[], &OpmlExportOptions::default(), "now");
+        let imported = database().await;
+        let mut connection = imported.acquire().await.unwrap();
+        for outline in parse_opml(opml.as_bytes()).unwrap() {
+            let identifier = ulid();
+            sqlx::query(include_str!("../database/queries/import_feed.sql"))
+                .bind(&identifier)
+                .bind(&outline.title)
+                .bind(&outline.xml_url)
+                .bind(IMPORTED_FEED_INTERVAL)
+                .bind(None::<String>)
+                .bind(&outline.html_url)
+                .execute(&mut *connection).await.unwrap();
+            apply_settings(&identifier, outline.settings.as_ref().unwrap(), &mut connection).await.unwrap();
+        }
+        drop(connection);
+
+        assert_eq!(exported(&imported).await, exported(&source).await);
+    }
+}
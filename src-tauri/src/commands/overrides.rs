@@ -0,0 +1,115 @@
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::structs::feed_network_settings::FeedNetworkSettings;
+use crate::structs::feed_overrides::FeedOverrides;
+use crate::structs::read_pool::ReadPool;
+
+/// Every feed fetched with network settings of its own, whatever they are, to review them in one place.
+/// The values of headers, cookies, credentials and passphrases are masked, only their names are shown.
+#[alduin_macros::command]
+pub async fn get_override_audit(app_handle: AppHandle) -> Result<Vec<FeedOverrides>, String> {
+    let pool = app_handle.state::<ReadPool>();
+    override_audit(&pool).await
+}
+
+pub async fn override_audit(pool: &Pool<Sqlite>) -> Result<Vec<FeedOverrides>, String> {
+    let settings = sqlx::query_as::<_, FeedNetworkSettings>(include_str!("../database/queries/get_network_settings.sql"))
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(settings.into_iter()
+        .map(|settings| FeedOverrides { overrides: settings.overrides(), feed_identifier: settings.feed_identifier, display_name: settings.display_name })
+        .filter(|feed| !feed.overrides.is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::database::migrator::migrator;
+    use crate::enums::override_kind::OverrideKind;
+    use super::*;
+
+    /// Columns of the network tables an override can't be read from
+    const BOOKKEEPING_COLUMNS: [&str; 3] = ["feed_identifier", "updated_at", "state"];
+
+    /// A feed for each kind of override, set as their commands do, the secrets being keychain references.
+    const OVERRIDES: [(&str, &str); 7] = [
+        ("user_agent", "UPDATE feeds SET user_agent = 'browser' WHERE identifier = ?1"),
+        ("address_family", "UPDATE feeds SET address_family = 'ipv6' WHERE identifier = ?1"),
+        ("headers", include_str!("../database/queries/upsert_feed_headers.sql")),
+        ("basic_auth", "INSERT INTO feed_credentials (feed_identifier, username, password_secret, updated_at) VALUES (?1, 'reader', ?3, 0)"),
+        ("cookies", "INSERT INTO feed_sessions (feed_identifier, cookie_names, cookies_secret, updated_at) VALUES (?1, ?2, ?3, 0)"),
+        ("client_certificate", include_str!("../database/queries/upsert_feed_client_certificate.sql")),
+        ("ca_certificate", include_str!("../database/queries/upsert_feed_ca_certificate.sql")),
+    ];
+
+    async fn pool() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn audits_every_kind_of_override() {
+        let pool = pool().await;
+        sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval) VALUES ('default', 'default', '', 60)")
+            .execute(&pool).await.unwrap();
+        for (name, query) in OVERRIDES {
+            sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval) VALUES (?, ?, '', 60)")
+                .bind(name).bind(name)
+                .execute(&pool).await.unwrap();
+            let path_or_names = if name.ends_with("certificate") { "/certificates/feed.pem" } else { r#"["Authorization"]"# };
+            sqlx::query(query)
+                .bind(name).bind(path_or_names).bind(format!("secret-of-{}", name))
+                .execute(&pool).await.unwrap();
+        }
+
+        let audit = override_audit(&pool).await.unwrap();
+        let audited: Vec<(&str, Vec<OverrideKind>)> = audit.iter()
+            .map(|feed| (feed.feed_identifier.as_str(), feed.overrides.iter().map(|item| item.kind).collect()))
+            .collect();
+        // Named after their kind, sorted by name
+        let mut expected: Vec<(&str, Vec<OverrideKind>)> = OverrideKind::ALL.iter().zip(OVERRIDES)
+            .map(|(kind, (name, _))| {
+                assert_eq!(serde_json::to_value(kind).unwrap(), name);
+                (name, vec![*kind])
+            })
+            .collect();
+        expected.sort_by_key(|(name, _)| *name);
+        assert_eq!(OVERRIDES.len(), OverrideKind::ALL.len());
+        assert_eq!(audited, expected);
+        assert!(!serde_json::to_string(&audit).unwrap().contains("secret-of-"));
+        let details: Vec<(&str, &str)> = audit.iter().map(|feed| (feed.feed_identifier.as_str(), feed.overrides[0].detail.as_str())).collect();
+        assert_eq!(details, [
+            ("address_family", "ipv6 only"),
+            ("basic_auth", "username and password ********"),
+            ("ca_certificate", "/certificates/feed.pem"),
+            ("client_certificate", "/certificates/feed.pem, passphrase ********"),
+            ("cookies", "Authorization=********"),
+            ("headers", "Authorization: ********"),
+            ("user_agent", "browser"),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn reads_every_network_setting_of_the_feeds() {
+        let pool = pool().await;
+        let query = include_str!("../database/queries/get_network_settings.sql");
+
+        for table in ["feed_credentials", "feed_sessions", "feed_certificates"] {
+            let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?)")
+                .bind(table)
+                .fetch_all(&pool).await.unwrap();
+            assert!(!columns.is_empty(), "{}", table);
+
+            for column in columns {
+                // The values in the keychain are masked, their names read instead
+                if BOOKKEEPING_COLUMNS.contains(&column.as_str()) || (column.ends_with("_secret") && column != "client_certificate_secret") {
+                    continue;
+                }
+                assert!(query.contains(&format!("{}.{}", table, column)), "{}.{} isn't audited", table, column);
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use sqlx::{Pool, Sqlite};
+use sqlx::types::Json;
+use tauri::{AppHandle, Manager};
+use crate::database::change_journal::emit_change;
+use crate::post_processors::{registry, Pipeline};
+use crate::structs::field_update::FieldUpdate;
+use crate::structs::post_processor_setting::PostProcessorSetting;
+use crate::structs::write_gate::WriteGate;
+
+#[alduin_macros::command]
+pub async fn get_post_processors(feed_id: String, app_handle: AppHandle) -> Result<Vec<PostProcessorSetting>, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let flags = get_feed_flags(&feed_id, &pool).await?;
+
+    Ok(registry().iter()
+        .map(|processor| PostProcessorSetting {
+            name: processor.name().to_string(),
+            enabled: flags.get(processor.name()).copied().unwrap_or(true),
+        })
+        .collect())
+}
+
+/// Enables or disables one post-processor for a feed, only affecting articles synced afterwards.
+/// The flag is set in place so toggles from several windows never overwrite each other.
+#[alduin_macros::command]
+pub async fn set_post_processor(feed_id: String, name: String, enabled: bool, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    if !registry().iter().any(|processor| processor.name() == name) {
+        return Err(format!("Unknown post-processor: {}", name));
+    }
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let version = sqlx::query_scalar::<_, i64>(include_str!("../database/queries/set_feed_post_processors.sql"))
+        .bind(&name)
+        .bind(if enabled { "true" } else { "false" })
+        .bind(&feed_id)
+        .fetch_optional(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    if let Some(version) = version {
+        emit_change(&app_handle, "feed-updated", FieldUpdate { identifier: feed_id, fields: vec!["postProcessors"], version }).await?;
+    }
+
+    Ok(())
+}
+
+/// Lists the post-processors that changed the content of an article, for debugging odd renderings.
+#[alduin_macros::command]
+pub async fn get_article_post_processors(article_id: String, app_handle: AppHandle) -> Result<Vec<String>, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+
+    Ok(sqlx::query_scalar::<_, Json<Vec<String>>>(include_str!("../database/queries/get_article_post_processors.sql"))
+        .bind(&article_id)
+        .fetch_optional(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .map(|applied| applied.0)
+        .unwrap_or_default())
+}
+
+pub async fn load_pipeline(feed_id: &str, pool: &Pool<Sqlite>) -> Result<Pipeline, String> {
+    Ok(Pipeline::for_flags(&get_feed_flags(feed_id, pool).await?))
+}
+
+async fn get_feed_flags(feed_id: &str, pool: &Pool<Sqlite>) -> Result<HashMap<String, bool>, String> {
+    Ok(sqlx::query_scalar::<_, Json<HashMap<String, bool>>>(include_str!("../database/queries/get_feed_post_processors.sql"))
+        .bind(feed_id)
+        .fetch_optional(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .map(|flags| flags.0)
+        .unwrap_or_default())
+}
@@ -0,0 +1,80 @@
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, ClipboardManager, Manager};
+use crate::commands::links::discover_feeds;
+use crate::commands::opml::import_outlines;
+use crate::commands::splashscreen::show_main_window;
+use crate::structs::add_feed_request::AddFeedRequest;
+use crate::structs::opml_outline::OpmlOutline;
+use crate::structs::quick_add::QuickAdd;
+use crate::structs::write_gate::WriteGate;
+use crate::utils::notification::notify;
+use crate::utils::url::web_url;
+
+/// Subscribes to the feed at the URL in the clipboard, for the tray, and tells how it went with a notification.
+/// The clipboard is only read then. A URL offering several feeds opens the add feed dialog with them instead,
+/// through `take_add_feed_request`.
+pub async fn add_feed_from_clipboard(app_handle: AppHandle) {
+    let quick_add = app_handle.state::<QuickAdd>();
+    if !quick_add.start() {
+        return;
+    }
+
+    match add_from_clipboard(&app_handle).await {
+        Ok(Some(message)) | Err(message) => notify(&app_handle, &message),
+        Ok(None) => {}
+    }
+    quick_add.finish();
+}
+
+/// The add feed dialog the tray asked for, once. The main window asks on `tray:add-feed-requested` and when it
+/// loads, in case it wasn't listening yet.
+#[alduin_macros::command]
+pub async fn take_add_feed_request(app_handle: AppHandle) -> Result<Option<AddFeedRequest>, String> {
+    Ok(app_handle.state::<QuickAdd>().take())
+}
+
+/// Returns the message to notify, none when the dialog was opened instead.
+async fn add_from_clipboard(app_handle: &AppHandle) -> Result<Option<String>, String> {
+    // The menu item is disabled until then, a click may race it
+    if app_handle.try_state::<Pool<Sqlite>>().is_none() {
+        return Err(String::from("Alduin is still starting, try again in a moment"));
+    }
+    app_handle.state::<WriteGate>().check()?;
+
+    let text = app_handle.clipboard_manager().read_text()
+        .map_err(|e| format!("Error reading the clipboard: {}", e))?
+        .unwrap_or_default();
+    let url = web_url(&text).ok_or_else(|| String::from("The clipboard holds no web address to add a feed from"))?;
+
+    let mut candidates = discover_feeds(url.clone(), app_handle).await
+        .map_err(|e| format!("No feed found at {}: {}", url, e))?;
+    if candidates.len() > 1 {
+        app_handle.state::<QuickAdd>().request(AddFeedRequest { url, candidates });
+        show_main_window(app_handle)?;
+        app_handle.emit_all("tray:add-feed-requested", ())
+            .map_err(|e| format!("Error emitting add feed request: {}", e))?;
+        return Ok(None);
+    }
+
+    let Some(candidate) = candidates.pop() else {
+        return Err(format!("No feed found at {}", url));
+    };
+    let title = candidate.title.filter(|title| !title.is_empty()).unwrap_or_else(|| candidate.url.clone());
+    if candidate.existing_feed.is_some() {
+        return Ok(Some(format!("Already subscribed to {}", title)));
+    }
+
+    let outline = OpmlOutline {
+        title: title.clone(),
+        html_url: (candidate.url != url).then_some(url),
+        xml_url: Some(candidate.url),
+        folder: vec![],
+        settings: None,
+    };
+    let import = import_outlines(vec![outline], app_handle).await?;
+    match import.failures.into_iter().next() {
+        Some(failure) => Err(format!("Error subscribing to {}: {}", title, failure.error)),
+        None if import.added == 0 => Ok(Some(format!("Already subscribed to {}", title))),
+        None => Ok(Some(format!("Subscribed to {}", title))),
+    }
+}
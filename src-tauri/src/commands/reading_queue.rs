@@ -0,0 +1,246 @@
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::database::change_journal::emit_change;
+use crate::structs::read_pool::ReadPool;
+use crate::structs::stored_article::StoredArticle;
+use crate::structs::write_gate::WriteGate;
+
+/// Appends an article to the reading queue, an article already queued keeping its place.
+#[alduin_macros::command]
+pub async fn queue_article(article_id: String, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let added = add_to_queue(&article_id, &pool).await?;
+    notify_if_changed(added, &app_handle).await
+}
+
+#[alduin_macros::command]
+pub async fn dequeue_article(article_id: String, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let removed = remove_from_queue(&article_id, &pool).await?;
+    notify_if_changed(removed, &app_handle).await
+}
+
+/// Moves a queued article right after another one, or first when `after` is None.
+#[alduin_macros::command]
+pub async fn reorder_queue(article_id: String, after: Option<String>, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let moved = move_in_queue(&article_id, after.as_deref(), &pool).await?;
+    notify_if_changed(moved, &app_handle).await
+}
+
+#[alduin_macros::command]
+pub async fn get_queue(app_handle: AppHandle) -> Result<Vec<StoredArticle>, String> {
+    let pool = app_handle.state::<ReadPool>();
+    load_queue(&pool).await
+}
+
+/// Removes the first article of the queue and returns it. Two windows popping at the same time
+/// never get the same article, as it is removed and returned by a single statement.
+#[alduin_macros::command]
+pub async fn pop_next_in_queue(app_handle: AppHandle) -> Result<Option<StoredArticle>, String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let Some(article_id) = pop_queue(&pool).await? else { return Ok(None) };
+
+    notify_if_changed(1, &app_handle).await?;
+
+    sqlx::query_as::<_, StoredArticle>(include_str!("../database/queries/get_stored_article.sql"))
+        .bind(&article_id)
+        .fetch_optional(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// Returns how many articles were added, zero when already queued.
+pub async fn add_to_queue(article_id: &str, pool: &Pool<Sqlite>) -> Result<u64, String> {
+    sqlx::query(include_str!("../database/queries/queue_article.sql"))
+        .bind(article_id)
+        .execute(pool).await
+        .map(|result| result.rows_affected())
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+pub async fn move_in_queue(article_id: &str, after: Option<&str>, pool: &Pool<Sqlite>) -> Result<u64, String> {
+    let moved = sqlx::query(include_str!("../database/queries/reorder_queue.sql"))
+        .bind(article_id)
+        .bind(after)
+        .execute(pool).await
+        .map(|result| result.rows_affected())
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    if moved == 0 {
+        return Err(format!("Article {} or {} is not queued", article_id, after.unwrap_or_default()));
+    }
+    Ok(moved)
+}
+
+pub async fn load_queue(pool: &Pool<Sqlite>) -> Result<Vec<StoredArticle>, String> {
+    sqlx::query_as::<_, StoredArticle>(include_str!("../database/queries/get_reading_queue.sql"))
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// Removes the first article of the queue in one statement, returning its identifier.
+pub async fn pop_queue(pool: &Pool<Sqlite>) -> Result<Option<String>, String> {
+    sqlx::query_scalar::<_, String>(include_str!("../database/queries/pop_reading_queue.sql"))
+        .fetch_optional(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// Returns how many articles were removed, zero or one.
+pub async fn remove_from_queue(article_id: &str, pool: &Pool<Sqlite>) -> Result<u64, String> {
+    sqlx::query(include_str!("../database/queries/dequeue_article.sql"))
+        .bind(article_id)
+        .execute(pool).await
+        .map(|result| result.rows_affected())
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// Lets every window refresh its queue.
+pub async fn notify_if_changed(changed: u64, app_handle: &AppHandle) -> Result<(), String> {
+    if changed > 0 {
+        emit_change(app_handle, "reading-queue-changed", ()).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::str::FromStr;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use crate::database::migrator::migrator;
+    use super::*;
+
+    const ARTICLES: u64 = 40;
+
+    /// xorshift64, so every failing seed can be replayed.
+    struct Random(u64);
+
+    impl Random {
+        fn below(&mut self, bound: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % bound
+        }
+    }
+
+    async fn with_articles(pool: &Pool<Sqlite>) {
+        migrator().await.unwrap().run(pool).await.unwrap();
+        sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval) VALUES ('feed', 'feed', '', 60)")
+            .execute(pool).await.unwrap();
+        for article in 0..ARTICLES {
+            sqlx::query("INSERT INTO articles (identifier, feed_identifier, title, content, date) VALUES (?, 'feed', '', '', 0)")
+                .bind(article.to_string())
+                .execute(pool).await.unwrap();
+        }
+    }
+
+    async fn queue(pool: &Pool<Sqlite>) -> Vec<String> {
+        load_queue(pool).await.unwrap().into_iter().map(|article| article.identifier).collect()
+    }
+
+    #[tokio::test]
+    async fn follows_a_list_model() {
+        for seed in 1..=10u64 {
+            let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+            with_articles(&pool).await;
+            let mut random = Random(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+            let mut model: Vec<String> = vec![];
+
+            for step in 0..300 {
+                let article = random.below(ARTICLES).to_string();
+                match random.below(6) {
+                    0 | 1 => {
+                        let added = add_to_queue(&article, &pool).await.unwrap();
+                        assert_eq!(added == 1, !model.contains(&article));
+                        if added == 1 {
+                            model.push(article);
+                        }
+                    }
+                    2 => {
+                        let removed = remove_from_queue(&article, &pool).await.unwrap();
+                        assert_eq!(removed == 1, model.contains(&article));
+                        model.retain(|queued| *queued != article);
+                    }
+                    3 => {
+                        let popped = pop_queue(&pool).await.unwrap();
+                        assert_eq!(popped.as_ref(), model.first());
+                        if popped.is_some() {
+                            model.remove(0);
+                        }
+                    }
+                    _ => {
+                        // First a seventh of the time, after another queued article otherwise
+                        let after = (random.below(7) > 0 && !model.is_empty())
+                            .then(|| model[random.below(model.len() as u64) as usize].clone());
+                        let moved = move_in_queue(&article, after.as_deref(), &pool).await;
+                        let valid = model.contains(&article) && after.as_ref() != Some(&article);
+                        assert_eq!(moved.is_ok(), valid, "seed {} step {}", seed, step);
+                        if valid {
+                            model.retain(|queued| *queued != article);
+                            let index = after.map(|after| model.iter().position(|queued| *queued == after).unwrap() + 1).unwrap_or(0);
+                            model.insert(index, article);
+                        }
+                    }
+                }
+
+                assert_eq!(queue(&pool).await, model, "seed {} step {}", seed, step);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_pops_never_share_an_article() {
+        let path = std::env::temp_dir().join(format!("alduin-reading-queue-{}.db", std::process::id()));
+        let options = SqliteConnectOptions::from_str(&path.to_string_lossy()).unwrap().create_if_missing(true);
+        let pool = SqlitePoolOptions::new().max_connections(4).connect_with(options).await.unwrap();
+        with_articles(&pool).await;
+        for article in 0..ARTICLES {
+            add_to_queue(&article.to_string(), &pool).await.unwrap();
+        }
+
+        // Windows popping while the queue is reordered under them
+        let poppers: Vec<_> = (0..4)
+            .map(|_| tokio::spawn({
+                let pool = pool.clone();
+                async move {
+                    let mut popped = vec![];
+                    while let Some(article) = pop_queue(&pool).await.unwrap() {
+                        popped.push(article);
+                    }
+                    popped
+                }
+            }))
+            .collect();
+        let reorderer = tokio::spawn({
+            let pool = pool.clone();
+            async move {
+                for article in (0..ARTICLES).rev() {
+                    // Gone when already popped
+                    let _ = move_in_queue(&article.to_string(), None, &pool).await;
+                }
+            }
+        });
+
+        let mut popped = vec![];
+        for popper in poppers {
+            popped.extend(popper.await.unwrap());
+        }
+        reorderer.await.unwrap();
+        pool.close().await;
+        std::fs::remove_file(&path).unwrap();
+
+        let distinct: HashSet<&String> = popped.iter().collect();
+        assert_eq!(popped.len() as u64, ARTICLES);
+        assert_eq!(distinct.len() as u64, ARTICLES);
+    }
+}
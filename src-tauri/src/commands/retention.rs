@@ -0,0 +1,333 @@
+use std::time::Duration;
+use sqlx::{Pool, Sqlite};
+use sqlx::types::Json;
+use tauri::{AppHandle, Manager};
+use crate::database::change_journal::emit_change;
+use crate::database::health;
+use crate::enums::background_job::BackgroundJob;
+use crate::scheduler::idle::wait_for_idle;
+use crate::structs::preferences::Preferences;
+use crate::structs::feed_purge_preview::FeedPurgePreview;
+use crate::structs::feed_storage_stats::FeedStorageStats;
+use crate::structs::prune_report::PruneReport;
+use crate::structs::purge_preview::PurgePreview;
+use crate::structs::read_pool::ReadPool;
+use crate::structs::storage_policy::StoragePolicy;
+use crate::structs::storage_projection::StorageProjection;
+use crate::structs::write_gate::WriteGate;
+use crate::utils::storage_projection::{self, MEASURED_DAYS};
+
+/// Articles deleted per transaction, so a sync waiting to write never waits long.
+const PRUNE_BATCH_ARTICLES: usize = 500;
+/// Between two transactions, for the waiting writers to get the lock before the next one takes it.
+const PRUNE_BATCH_PAUSE: Duration = Duration::from_millis(20);
+/// `PRAGMA auto_vacuum` of a database freeing its pages with `PRAGMA incremental_vacuum`.
+const AUTO_VACUUM_INCREMENTAL: i64 = 2;
+
+/// Deletes the articles past the retention of their feed, then gives the freed space back to the disk.
+/// Databases created without incremental vacuum are converted by a full `VACUUM` the first time, which
+/// holds the database for as long as it takes to copy it and reindex the articles for search.
+#[alduin_macros::command]
+pub async fn prune_articles(app_handle: AppHandle) -> Result<PruneReport, String> {
+    prune(&app_handle, true).await
+}
+
+/// The prune after a sync, waiting for the user to be idle. Skipped when it was turned off meanwhile.
+pub async fn prune_when_idle(app_handle: AppHandle) {
+    wait_for_idle(BackgroundJob::Prune, &app_handle).await;
+    if !Preferences::load(&app_handle).prune_after_sync {
+        return;
+    }
+
+    if let Err(e) = prune(&app_handle, false).await {
+        eprintln!("Error pruning articles: {}", e);
+    }
+}
+
+/// Deletes the articles older than the retention days of their feed, or past its most recent retention
+/// articles. Articles in the reading queue and the articles of archived feeds are never deleted. The
+/// deleted articles are remembered until they leave the feed, so it doesn't deliver them again. Without `full_vacuum`, a
+/// database not converted to incremental vacuum yet only reuses the freed pages for its next writes.
+pub async fn prune(app_handle: &AppHandle, full_vacuum: bool) -> Result<PruneReport, String> {
+    app_handle.state::<WriteGate>().check()?;
+
+    let preferences = Preferences::load(app_handle);
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let bytes_before = health::database_bytes();
+
+    let candidates = prune_candidates(preferences.retention_days, preferences.retention_max_articles, &pool).await?;
+
+    let mut articles_deleted = 0;
+    for (index, batch) in candidates.chunks(PRUNE_BATCH_ARTICLES).enumerate() {
+        if index > 0 {
+            tokio::time::sleep(PRUNE_BATCH_PAUSE).await;
+        }
+        let _writing = app_handle.state::<WriteGate>().enter()?;
+        articles_deleted += delete_batch(batch, &pool).await?;
+    }
+
+    let vacuumed = match app_handle.state::<WriteGate>().enter() {
+        Ok(_writing) if articles_deleted > 0 || full_vacuum => vacuum(&pool, full_vacuum).await,
+        _ => false,
+    };
+
+    let bytes_after = health::database_bytes();
+    let report = PruneReport {
+        articles_deleted,
+        bytes_before,
+        bytes_after,
+        bytes_reclaimed: bytes_before.saturating_sub(bytes_after),
+        vacuumed,
+    };
+    if articles_deleted > 0 {
+        emit_change(app_handle, "articles-pruned", report.clone()).await?;
+    }
+
+    Ok(report)
+}
+
+/// Projects the database over the next `horizon_days` with a retention and filter not saved yet, for the
+/// retention preferences. The first month is the effect of pruning with them right away.
+#[alduin_macros::command]
+pub async fn project_storage(policy: StoragePolicy, horizon_days: u32, app_handle: AppHandle) -> Result<StorageProjection, String> {
+    let pool = app_handle.state::<ReadPool>();
+    let now = chrono::Utc::now().timestamp_millis();
+
+    let candidates = prune_candidates(policy.retention_days, policy.retention_max_articles, &pool).await?;
+    let stats = sqlx::query_as::<_, FeedStorageStats>(include_str!("../database/queries/get_storage_stats.sql"))
+        .bind(Json(&candidates))
+        .bind(retention_limit(policy.retention_days))
+        .bind(retention_limit(policy.retention_max_articles))
+        .bind(policy.min_score)
+        .bind(now - MEASURED_DAYS as i64 * 24 * 60 * 60 * 1000)
+        .fetch_all(&**pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(storage_projection::project(&stats, health::database_bytes(), now, horizon_days))
+}
+
+/// What a prune with a retention not saved yet would delete, per feed, without deleting anything. The
+/// articles are the ones `prune` lists, so the numbers are those of a prune run right away.
+#[alduin_macros::command]
+pub async fn preview_purge(policy: StoragePolicy, app_handle: AppHandle) -> Result<PurgePreview, String> {
+    let pool = app_handle.state::<ReadPool>();
+    load_purge_preview(&policy, &pool).await
+}
+
+pub async fn load_purge_preview(policy: &StoragePolicy, pool: &Pool<Sqlite>) -> Result<PurgePreview, String> {
+    let candidates = prune_candidates(policy.retention_days, policy.retention_max_articles, pool).await?;
+    if candidates.is_empty() {
+        return Ok(PurgePreview::default());
+    }
+
+    let feeds = sqlx::query_as::<_, FeedPurgePreview>(include_str!("../database/queries/get_purge_preview.sql"))
+        .bind(Json(&candidates))
+        .bind(policy.min_score)
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(PurgePreview {
+        articles: feeds.iter().map(|feed| feed.articles).sum(),
+        unread: feeds.iter().map(|feed| feed.unread).sum(),
+        bytes: feeds.iter().map(|feed| feed.bytes).sum(),
+        feeds,
+    })
+}
+
+/// The articles a prune with this retention would delete, the feeds with their own keeping it.
+async fn prune_candidates(retention_days: u32, retention_max_articles: u32, pool: &Pool<Sqlite>) -> Result<Vec<String>, String> {
+    sqlx::query_scalar::<_, String>(include_str!("../database/queries/get_prune_candidates.sql"))
+        .bind(retention_limit(retention_days))
+        .bind(retention_limit(retention_max_articles))
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// Deletes `batch` in a transaction, remembering the articles, and returns how many were deleted.
+async fn delete_batch(batch: &[String], pool: &Pool<Sqlite>) -> Result<u64, String> {
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+    sqlx::query(include_str!("../database/queries/insert_pruned_articles.sql"))
+        .bind(Json(batch))
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    let deleted = sqlx::query(include_str!("../database/queries/delete_pruned_articles.sql"))
+        .bind(Json(batch))
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))?;
+
+    Ok(deleted.rows_affected())
+}
+
+/// 0 turns a retention limit off.
+fn retention_limit(limit: u32) -> Option<u32> {
+    Some(limit).filter(|limit| *limit > 0)
+}
+
+/// Frees the pages left empty and refreshes the query planner statistics. Failures are only logged,
+/// the articles are deleted either way.
+async fn vacuum(pool: &Pool<Sqlite>, full_vacuum: bool) -> bool {
+    let auto_vacuum = sqlx::query_scalar::<_, i64>(include_str!("../database/queries/get_auto_vacuum.sql"))
+        .fetch_one(pool).await;
+
+    // Unlike the incremental one, a full VACUUM can renumber the rowids of the articles, which have no
+    // INTEGER PRIMARY KEY
+    let (vacuum_query, renumbers) = match auto_vacuum {
+        Ok(AUTO_VACUUM_INCREMENTAL) => (Some(include_str!("../database/queries/incremental_vacuum.sql")), false),
+        Ok(_) if full_vacuum => (Some(include_str!("../database/queries/vacuum_incremental.sql")), true),
+        Ok(_) => (None, false),
+        Err(e) => {
+            eprintln!("Error reading auto vacuum: {}", e);
+            (None, false)
+        }
+    };
+
+    // Before, as starting over is right whether the VACUUM succeeds or not
+    if renumbers {
+        if let Err(e) = sqlx::query(include_str!("../database/queries/reset_backfill_progress.sql")).execute(pool).await {
+            eprintln!("Error resetting the backfills: {}", e);
+        }
+    }
+
+    let mut vacuumed = false;
+    if let Some(vacuum_query) = vacuum_query {
+        match sqlx::query(vacuum_query).execute(pool).await {
+            Ok(_) => vacuumed = true,
+            Err(e) => eprintln!("Error vacuuming the database: {}", e),
+        }
+    }
+
+    // The search index points at the articles by rowid
+    if renumbers && vacuumed {
+        if let Err(e) = sqlx::query(include_str!("../database/queries/rebuild_article_search.sql")).execute(pool).await {
+            eprintln!("Error rebuilding the search index: {}", e);
+        }
+    }
+
+    if let Err(e) = sqlx::query(include_str!("../database/queries/optimize.sql")).execute(pool).await {
+        eprintln!("Error optimizing the database: {}", e);
+    }
+    // The freed pages only leave the file once the log is copied back and truncated
+    if let Err(e) = sqlx::query(include_str!("../database/queries/wal_checkpoint.sql")).execute(pool).await {
+        eprintln!("Error checkpointing the database: {}", e);
+    }
+
+    vacuumed
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::database::migrator::migrator;
+    use super::*;
+
+    const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+    /// Feeds as (identifier, archived, retention days, retention articles) and their articles as (feed,
+    /// identifier, days old, read, score, queued)
+    async fn fixture() -> Pool<Sqlite> {
+        let feeds = [
+            ("preferences", false, None, None),
+            ("own", false, Some(0), Some(1)),
+            ("archived", true, None, None),
+        ];
+        let articles = [
+            ("preferences", "p-old-read", 90, true, 0, false),
+            ("preferences", "p-old-unread", 60, false, 0, false),
+            ("preferences", "p-old-low", 45, false, -10, false),
+            ("preferences", "p-old-queued", 90, false, 0, true),
+            ("preferences", "p-recent", 1, false, 0, false),
+            ("own", "o-newest", 1, false, 0, false),
+            ("own", "o-newer", 2, true, 0, false),
+            ("own", "o-new", 3, false, 0, false),
+            ("archived", "a-old", 90, false, 0, false),
+        ];
+
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+
+        let now = chrono::Utc::now().timestamp_millis();
+        for (identifier, archived, days, max_articles) in feeds {
+            sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval, archived, retention_days, retention_max_articles) VALUES (?, ?, '', 60, ?, ?, ?)")
+                .bind(identifier).bind(identifier).bind(archived).bind(days).bind(max_articles)
+                .execute(&pool).await.unwrap();
+        }
+        for (feed, identifier, days_old, read, score, queued) in articles {
+            sqlx::query("INSERT INTO articles (identifier, feed_identifier, title, content, date, first_seen, read, score) VALUES (?, ?, ?, 'content', ?, ?, ?, ?)")
+                .bind(identifier).bind(feed).bind(identifier).bind(now - days_old * DAY_MS).bind(now - days_old * DAY_MS).bind(read).bind(score)
+                .execute(&pool).await.unwrap();
+            if queued {
+                sqlx::query("INSERT INTO reading_queue (article_identifier, position, added_at) VALUES (?, 1, ?)")
+                    .bind(identifier).bind(now)
+                    .execute(&pool).await.unwrap();
+            }
+        }
+
+        pool
+    }
+
+    async fn article_counts(pool: &Pool<Sqlite>) -> Vec<(String, i64)> {
+        sqlx::query_as("SELECT feed_identifier, COUNT(*) FROM articles GROUP BY feed_identifier ORDER BY feed_identifier")
+            .fetch_all(pool).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn preview_matches_the_prune() {
+        let pool = fixture().await;
+        let policy = StoragePolicy { retention_days: 30, retention_max_articles: 0, min_score: Some(0) };
+
+        let preview = load_purge_preview(&policy, &pool).await.unwrap();
+
+        let feeds: Vec<(&str, i64, i64)> = preview.feeds.iter()
+            .map(|feed| (feed.feed_identifier.as_str(), feed.articles, feed.unread))
+            .collect();
+        assert_eq!(feeds, vec![("preferences", 3, 1), ("own", 2, 1)]);
+        assert_eq!((preview.articles, preview.unread), (5, 2));
+        assert_eq!(preview.bytes, ["p-old-read", "p-old-unread", "p-old-low", "o-newer", "o-new"].iter().map(|title| title.len() as i64 + 7).sum::<i64>());
+
+        let before = article_counts(&pool).await;
+        let candidates = prune_candidates(policy.retention_days, policy.retention_max_articles, &pool).await.unwrap();
+        let deleted = delete_batch(&candidates, &pool).await.unwrap();
+        let after = article_counts(&pool).await;
+
+        assert_eq!(deleted as i64, preview.articles);
+        for ((feed, count_before), (_, count_after)) in before.iter().zip(after.iter()) {
+            let previewed = preview.feeds.iter().find(|preview| &preview.feed_identifier == feed).map_or(0, |preview| preview.articles);
+            assert_eq!(count_before - count_after, previewed, "{}", feed);
+        }
+
+        let preview = load_purge_preview(&policy, &pool).await.unwrap();
+        assert!(preview.feeds.is_empty());
+    }
+
+    #[tokio::test]
+    async fn full_vacuum_keeps_search_and_backfills_in_step() {
+        let pool = fixture().await;
+        sqlx::query("DELETE FROM articles WHERE identifier IN ('p-old-read', 'p-old-unread', 'o-new')").execute(&pool).await.unwrap();
+        sqlx::query("UPDATE backfills SET last_rowid = 5, done = name = 'article_links'").execute(&pool).await.unwrap();
+        // What a VACUUM renumbering the articles leaves, SQLite keeping them in this case
+        sqlx::query("UPDATE articles SET rowid = 100 WHERE identifier = 'o-newest'").execute(&pool).await.unwrap();
+
+        assert!(vacuum(&pool, true).await);
+
+        let auto_vacuum: i64 = sqlx::query_scalar(include_str!("../database/queries/get_auto_vacuum.sql")).fetch_one(&pool).await.unwrap();
+        assert_eq!(auto_vacuum, AUTO_VACUUM_INCREMENTAL);
+        sqlx::query("INSERT INTO article_search(article_search, rank) VALUES ('integrity-check', 1)").execute(&pool).await.unwrap();
+        let found: Vec<String> = sqlx::query_scalar("SELECT articles.identifier FROM article_search JOIN articles ON articles.rowid = article_search.rowid WHERE article_search MATCH 'newest OR queued' ORDER BY articles.identifier")
+            .fetch_all(&pool).await.unwrap();
+        assert_eq!(found, vec!["o-newest", "p-old-queued"]);
+        let progress: Vec<(String, i64)> = sqlx::query_as("SELECT name, last_rowid FROM backfills ORDER BY name").fetch_all(&pool).await.unwrap();
+        assert!(progress.iter().all(|(name, last_rowid)| *last_rowid == if name == "article_links" { 5 } else { 0 }), "{:?}", progress);
+    }
+
+    #[tokio::test]
+    async fn nothing_to_preview_without_retention() {
+        let pool = fixture().await;
+
+        let preview = load_purge_preview(&StoragePolicy::default(), &pool).await.unwrap();
+
+        // Only the feed with its own retention
+        assert_eq!(preview.articles, 2);
+        assert_eq!(preview.feeds.len(), 1);
+    }
+}
@@ -0,0 +1,46 @@
+use tauri::{AppHandle, Manager};
+use crate::scheduler::background::background_sync_status;
+use crate::scheduler::idle::record_activity;
+use crate::scheduler::power::scheduler_state;
+use crate::structs::background_sync::BackgroundSync;
+use crate::structs::background_sync_status::BackgroundSyncStatus;
+use crate::structs::scheduler_state::SchedulerState;
+
+/// The power status and the policy applied because of it, also sent as `scheduler-policy-changed`, and
+/// whether the user is idle with the jobs waiting for it.
+#[alduin_macros::command]
+pub async fn get_scheduler_state(app_handle: AppHandle) -> Result<SchedulerState, String> {
+    Ok(scheduler_state(&app_handle))
+}
+
+/// The heartbeat of the frontend, sent while the user scrolls, clicks or types.
+#[alduin_macros::command]
+pub async fn report_activity(app_handle: AppHandle) -> Result<(), String> {
+    record_activity(&app_handle);
+    Ok(())
+}
+
+#[alduin_macros::command]
+pub async fn get_background_sync(app_handle: AppHandle) -> Result<BackgroundSyncStatus, String> {
+    Ok(background_sync_status(&app_handle))
+}
+
+/// Only applies the interval until the next launch, the frontend saves it as `backgroundSyncMinutes`.
+#[alduin_macros::command]
+pub async fn set_sync_interval(minutes: u32, app_handle: AppHandle) -> Result<BackgroundSyncStatus, String> {
+    app_handle.state::<BackgroundSync>().set_minutes(minutes as u64);
+    Ok(background_sync_status(&app_handle))
+}
+
+/// Until resumed or the app restarts. A run in progress still finishes.
+#[alduin_macros::command]
+pub async fn pause_background_sync(app_handle: AppHandle) -> Result<BackgroundSyncStatus, String> {
+    app_handle.state::<BackgroundSync>().set_paused(true);
+    Ok(background_sync_status(&app_handle))
+}
+
+#[alduin_macros::command]
+pub async fn resume_background_sync(app_handle: AppHandle) -> Result<BackgroundSyncStatus, String> {
+    app_handle.state::<BackgroundSync>().set_paused(false);
+    Ok(background_sync_status(&app_handle))
+}
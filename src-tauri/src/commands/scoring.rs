@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::database::change_journal::emit_change;
+use crate::scoring::evaluator::{compile_rules, score, CompiledRule};
+use crate::structs::scorable_article::ScorableArticle;
+use crate::structs::scoring_preview::ScoringPreview;
+use crate::structs::preferences::Preferences;
+use crate::structs::scoring_rule::ScoringRule;
+use crate::structs::write_gate::WriteGate;
+
+const MAX_PREVIEW_LIMIT: i64 = 500;
+
+/// Bumped on every rule change so a recomputation started with outdated rules stops early.
+static RULES_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[alduin_macros::command]
+pub async fn get_scoring_rules(app_handle: AppHandle) -> Result<Vec<ScoringRule>, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    load_scoring_rules(&pool).await
+}
+
+#[alduin_macros::command]
+pub async fn save_scoring_rule(rule: ScoringRule, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    CompiledRule::compile(&rule)?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    sqlx::query(include_str!("../database/queries/upsert_scoring_rule.sql"))
+        .bind(&rule.identifier)
+        .bind(&rule.conditions)
+        .bind(rule.weight)
+        .execute(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    spawn_recompute(app_handle.clone());
+    Ok(())
+}
+
+#[alduin_macros::command]
+pub async fn delete_scoring_rule(identifier: String, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    sqlx::query(include_str!("../database/queries/delete_scoring_rule.sql"))
+        .bind(&identifier)
+        .execute(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    spawn_recompute(app_handle.clone());
+    Ok(())
+}
+
+/// Shows how a rule would change the score of the most recent articles, without saving it. Editing a saved
+/// rule replaces its weight on the articles it matched, which lists the ones it would stop matching too.
+#[alduin_macros::command]
+pub async fn preview_scoring(rule: ScoringRule, limit: i64, app_handle: AppHandle) -> Result<Vec<ScoringPreview>, String> {
+    let compiled = CompiledRule::compile(&rule)?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let stored = sqlx::query_as::<_, ScoringRule>(include_str!("../database/queries/get_scoring_rule.sql"))
+        .bind(&rule.identifier)
+        .fetch_optional(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    // Saved rules were compiled before being saved, this only fails if the stored conditions were edited outside the app
+    let stored = stored.and_then(|stored| CompiledRule::compile(&stored).ok());
+
+    let articles = sqlx::query_as::<_, ScorableArticle>(include_str!("../database/queries/get_recent_articles.sql"))
+        .bind(limit.clamp(1, MAX_PREVIEW_LIMIT))
+        .fetch_all(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    let folders = load_feed_folders(None, &pool).await?;
+
+    Ok(articles.into_iter()
+        .filter_map(|article| {
+            let subject = article.subject(&folders);
+            let matches = |rule: &CompiledRule| rule.matches(&subject);
+            let old = stored.as_ref().filter(|stored| matches(stored)).map(CompiledRule::weight);
+            let new = matches(&compiled).then(|| compiled.weight());
+            if old.is_none() && new.is_none() {
+                return None;
+            }
+
+            Some(ScoringPreview {
+                new_score: article.score - old.unwrap_or_default() + new.unwrap_or_default(),
+                current_score: article.score,
+                identifier: article.identifier,
+                feed_identifier: article.feed_identifier,
+                title: article.title,
+            })
+        })
+        .collect())
+}
+
+pub async fn load_scoring_rules(pool: &Pool<Sqlite>) -> Result<Vec<ScoringRule>, String> {
+    sqlx::query_as::<_, ScoringRule>(include_str!("../database/queries/get_scoring_rules.sql"))
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// The folders of the feeds for the folder conditions, the folder of each feed then its ancestors. Feeds
+/// outside of folders are left out. Only `feed_identifier` is loaded when given.
+pub async fn load_feed_folders(feed_identifier: Option<&str>, pool: &Pool<Sqlite>) -> Result<HashMap<String, Vec<String>>, String> {
+    let rows: Vec<(String, String)> = sqlx::query_as(include_str!("../database/queries/get_feed_folders.sql"))
+        .bind(feed_identifier)
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    let mut folders: HashMap<String, Vec<String>> = HashMap::new();
+    for (feed_identifier, folder_identifier) in rows {
+        folders.entry(feed_identifier).or_default().push(folder_identifier);
+    }
+    Ok(folders)
+}
+
+fn spawn_recompute(app_handle: AppHandle) {
+    let generation = RULES_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    tauri::async_runtime::spawn(async move {
+        match recompute_scores(generation, &app_handle).await {
+            Ok(true) => {
+                if let Err(e) = emit_change(&app_handle, "scores-updated", ()).await {
+                    eprintln!("Error emitting scores update: {}", e);
+                }
+            }
+            Ok(false) => {}
+            Err(e) => eprintln!("Error recomputing scores: {}", e),
+        }
+    });
+}
+
+/// Rescores every article in small transactions, waiting out freezes. Returns false when newer rules
+/// superseded this run.
+async fn recompute_scores(generation: u64, app_handle: &AppHandle) -> Result<bool, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let rules = compile_rules(&load_scoring_rules(&pool).await?)?;
+    let folders = load_feed_folders(None, &pool).await?;
+    let mut last_rowid = 0;
+
+    loop {
+        // A freeze pauses the run, the batches already committed being kept
+        let _writing = app_handle.state::<WriteGate>().enter_when_thawed().await?;
+        if RULES_GENERATION.load(Ordering::SeqCst) != generation {
+            return Ok(false);
+        }
+
+        let batch = sqlx::query_as::<_, ScorableArticle>(include_str!("../database/queries/get_scoring_batch.sql"))
+            .bind(last_rowid)
+            .bind(Preferences::load(app_handle).recompute_batch_size())
+            .fetch_all(&*pool).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+
+        let Some(last) = batch.last() else { return Ok(true) };
+        last_rowid = last.rowid;
+
+        let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+        for article in batch.iter() {
+            let new_score = score(&rules, &article.subject(&folders));
+            if new_score != article.score {
+                sqlx::query(include_str!("../database/queries/update_article_score.sql"))
+                    .bind(new_score)
+                    .bind(&article.identifier)
+                    .execute(&mut *transaction).await
+                    .map_err(|e| format!("Error executing query: {}", e))?;
+            }
+        }
+        transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::database::migrator::migrator;
+    use super::*;
+
+    #[tokio::test]
+    async fn loads_the_folders_nearest_first() {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        sqlx::query("INSERT INTO folders (identifier, name, parent_identifier) VALUES ('tech', 'Tech', NULL), ('rust', 'Rust', 'tech'), ('news', 'News', NULL)")
+            .execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval, folder_identifier) VALUES
+            ('nested', 'nested', '', 60, 'rust'), ('root', 'root', '', 60, 'news'), ('loose', 'loose', '', 60, NULL)")
+            .execute(&pool).await.unwrap();
+
+        let folders = load_feed_folders(None, &pool).await.unwrap();
+        assert_eq!(folders, HashMap::from([
+            (String::from("nested"), vec![String::from("rust"), String::from("tech")]),
+            (String::from("root"), vec![String::from("news")]),
+        ]));
+
+        let folders = load_feed_folders(Some("nested"), &pool).await.unwrap();
+        assert_eq!(folders.keys().collect::<Vec<_>>(), ["nested"]);
+        assert!(load_feed_folders(Some("loose"), &pool).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,64 @@
+use sqlx::{Pool, Sqlite};
+use sqlx::types::Json;
+use tauri::{AppHandle, Manager};
+use crate::database::local_metrics;
+use crate::enums::local_metric::LocalMetric;
+use crate::search::filters::compile_filters;
+use crate::search::query::parse_query;
+use crate::structs::article_cursor::ArticleCursor;
+use crate::structs::article_page::ArticlePage;
+use crate::structs::read_pool::ReadPool;
+use crate::structs::search_error::SearchError;
+use crate::structs::search_filters::SearchFilters;
+use crate::structs::search_query::SearchQuery;
+use crate::structs::stored_article::StoredArticle;
+use crate::utils::pagination::page_limit;
+
+pub const DEFAULT_SEARCH_LIMIT: i64 = 100;
+
+#[alduin_macros::command]
+pub async fn search_articles(query: String, limit: Option<i64>, cursor: Option<ArticleCursor>, app_handle: AppHandle) -> Result<ArticlePage, String> {
+    let (_, filters) = translate(&query)?;
+    let limit = page_limit("search_articles", limit, DEFAULT_SEARCH_LIMIT);
+    if cursor.is_none() {
+        local_metrics::record(&app_handle, LocalMetric::Searches, 1).await;
+    }
+
+    let pool = app_handle.state::<ReadPool>();
+    search_page(filters, cursor, limit, &pool).await
+}
+
+/// A page of the articles matching compiled search filters.
+pub async fn search_page(filters: SearchFilters, cursor: Option<ArticleCursor>, limit: i64, pool: &Pool<Sqlite>) -> Result<ArticlePage, String> {
+    let articles = sqlx::query_as::<_, StoredArticle>(include_str!("../database/queries/search_articles.sql"))
+        .bind(filters.text)
+        .bind(filters.feeds.map(Json))
+        .bind((!filters.excluded_feeds.is_empty()).then_some(Json(filters.excluded_feeds)))
+        .bind(filters.read)
+        .bind(filters.before)
+        .bind(filters.after)
+        .bind(cursor.as_ref().map(|cursor| cursor.date))
+        .bind(cursor.map(|cursor| cursor.identifier))
+        .bind(limit)
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(ArticlePage::new(articles, limit))
+}
+
+/// Shows how a query is understood, so the search field can flag mistakes while typing.
+#[alduin_macros::command]
+pub async fn explain_search(query: String) -> Result<SearchQuery, String> {
+    translate(&query).map(|(parsed, _)| parsed)
+}
+
+pub fn translate(query: &str) -> Result<(SearchQuery, SearchFilters), String> {
+    parse_query(query)
+        .and_then(|parsed| compile_filters(query, &parsed).map(|filters| (parsed, filters)))
+        .map_err(invalid_query)
+}
+
+/// The frontend parses the error to highlight the offending part of the query.
+fn invalid_query(error: SearchError) -> String {
+    format!("InvalidQuery: {}", serde_json::to_string(&error).unwrap_or_default())
+}
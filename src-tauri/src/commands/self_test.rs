@@ -0,0 +1,442 @@
+use std::fs::{self, OpenOptions};
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Config, Manager};
+use crate::commands::overrides::override_audit;
+use crate::database::{connect_options, latest_migration, pool_options};
+use crate::enums::check_status::CheckStatus;
+use crate::enums::tray_status::TrayStatus;
+use crate::scheduler::background::background_sync_status;
+use crate::structs::background_sync_status::BackgroundSyncStatus;
+use crate::structs::schema_check::SchemaCheck;
+use crate::structs::self_test_check::SelfTestCheck;
+use crate::structs::self_test_environment::SelfTestEnvironment;
+use crate::structs::self_test_report::SelfTestReport;
+use crate::structs::tray_monitor::TrayMonitor;
+use crate::structs::write_gate::WriteGate;
+use crate::utils::disk::free_space;
+use crate::utils::notification::{notify, probe_notification_daemon};
+use crate::utils::tray::probe_tray_host;
+
+pub const SELF_TEST_ARG: &str = "--self-test";
+/// Answers any request with an empty 204, made for connectivity checks.
+const PROBE_URL: &str = "https://www.gstatic.com/generate_204";
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(4);
+const LOW_DISK_SPACE: u64 = 500 * 1024 * 1024;
+const CRITICAL_DISK_SPACE: u64 = 50 * 1024 * 1024;
+
+/// Quick checks of what support usually asks about first, each passing, warning or failing with a
+/// one-line detail. Nothing is changed: the write check takes the write lock and only writes to a temporary
+/// table, rolled back. All the checks run at once and stop after a few seconds.
+#[alduin_macros::command]
+pub async fn run_self_test(app_handle: AppHandle) -> Result<SelfTestReport, String> {
+    Ok(run_checks(app_environment(&app_handle)).await)
+}
+
+/// Runs the self-test for the tray and notifies the verdict, the report being emitted as `self-test-finished`.
+pub async fn run_self_test_from_tray(app_handle: AppHandle) {
+    let report = run_checks(app_environment(&app_handle)).await;
+
+    notify(&app_handle, &report.summary());
+    if let Err(e) = app_handle.emit_all("self-test-finished", &report) {
+        eprintln!("Error emitting self-test report: {}", e);
+    }
+}
+
+/// For `--self-test`: opens the database without the app, prints the checks and returns the exit code.
+/// The scheduler then can't be checked, and the migrations may be waiting for the next launch.
+pub async fn run_self_test_from_cli(config: &Config) -> i32 {
+    let config_dir = tauri::api::path::app_config_dir(config);
+    let database_file = config_dir.as_ref().map(|dir| dir.join("alduin.db"));
+    let pool = match &database_file {
+        Some(file) if file.exists() => pool_options()
+            .max_connections(2)
+            .connect_with(connect_options(file, false)).await
+            .map_err(|e| format!("Error opening {}: {}", file.display(), e)),
+        Some(file) => Err(format!("No database at {}, Alduin never started", file.display())),
+        None => Err(String::from("No configuration folder on this system")),
+    };
+
+    let environment = SelfTestEnvironment {
+        pool,
+        read_only: None,
+        migrated: false,
+        data_dirs: distinct_dirs(config_dir, tauri::api::path::app_data_dir(config)),
+        tray: TrayStatus::Unknown,
+        scheduler: None,
+    };
+    let report = run_checks(environment).await;
+
+    for check in &report.checks {
+        println!("{:<4}  {:<14}  {} ({} ms)", check.status.as_str().to_uppercase(), check.name, check.detail, check.duration_ms);
+    }
+    println!("{} in {} ms", report.verdict.as_str().to_uppercase(), report.duration_ms);
+    report.verdict.exit_code()
+}
+
+fn app_environment(app_handle: &AppHandle) -> SelfTestEnvironment {
+    let resolver = app_handle.path_resolver();
+    let read_only = match &app_handle.state::<SchemaCheck>().mismatch {
+        Some(mismatch) => Some(format!("The database was upgraded by a newer version of Alduin than {}", mismatch.app_version)),
+        None => app_handle.state::<WriteGate>().check().err(),
+    };
+
+    SelfTestEnvironment {
+        pool: app_handle.try_state::<Pool<Sqlite>>()
+            .map(|pool| pool.inner().clone())
+            .ok_or_else(|| String::from("The database isn't open yet, Alduin is still starting")),
+        read_only,
+        migrated: true,
+        data_dirs: distinct_dirs(resolver.app_config_dir(), resolver.app_data_dir()),
+        tray: app_handle.state::<TrayMonitor>().status(),
+        scheduler: Some(background_sync_status(app_handle)),
+    }
+}
+
+/// The configuration and data folders are the same one on Windows and macOS.
+fn distinct_dirs(config_dir: Option<PathBuf>, data_dir: Option<PathBuf>) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = config_dir.into_iter().chain(data_dir).collect();
+    dirs.dedup();
+    dirs
+}
+
+async fn run_checks(environment: SelfTestEnvironment) -> SelfTestReport {
+    let started = Instant::now();
+    let pool = environment.pool.clone();
+    let read_only = environment.read_only.clone();
+    let migrated = environment.migrated;
+    let data_dirs = environment.data_dirs.clone();
+    let disk_dirs = environment.data_dirs.clone();
+
+    let checks = vec![
+        spawn_check("database-read", CHECK_TIMEOUT, { let pool = pool.clone(); async move { check_read(pool?).await } }),
+        spawn_check("database-write", CHECK_TIMEOUT, { let pool = pool.clone(); async move { check_write(pool?, read_only).await } }),
+        spawn_check("migrations", CHECK_TIMEOUT, { let pool = pool.clone(); async move { check_migrations(pool?, migrated).await } }),
+        spawn_check("search", CHECK_TIMEOUT, { let pool = pool.clone(); async move { check_search(pool?).await } }),
+        spawn_check("network-overrides", CHECK_TIMEOUT, { let pool = pool.clone(); async move { check_overrides(pool?).await } }),
+        spawn_check("consistency", CHECK_TIMEOUT, async move { check_consistency(pool?).await }),
+        spawn_check("network", NETWORK_TIMEOUT, check_network()),
+        spawn_check("data-folders", CHECK_TIMEOUT, async move { check_data_dirs(&data_dirs) }),
+        spawn_check("disk-space", CHECK_TIMEOUT, blocking(move || check_disk_space(&disk_dirs))),
+        spawn_check("tray", CHECK_TIMEOUT, blocking(move || check_tray(environment.tray))),
+        spawn_check("notifications", CHECK_TIMEOUT, blocking(check_notifications)),
+        spawn_check("scheduler", CHECK_TIMEOUT, async move { check_scheduler(environment.scheduler) }),
+    ];
+
+    let mut results = vec![];
+    for check in checks {
+        results.push(check.await);
+    }
+    SelfTestReport::new(results, elapsed_ms(started))
+}
+
+/// Starts the check right away, the returned future giving its outcome once done or timed out. A check
+/// erring, like one needing the database when it isn't open, fails.
+fn spawn_check<F>(name: &'static str, timeout: Duration, check: F) -> Pin<Box<dyn Future<Output = SelfTestCheck> + Send>>
+where
+    F: Future<Output = Result<(CheckStatus, String), String>> + Send + 'static,
+{
+    let started = Instant::now();
+    let handle = tauri::async_runtime::spawn(tokio::time::timeout(timeout, check));
+
+    Box::pin(async move {
+        let (status, detail) = match handle.await {
+            Ok(Ok(Ok(outcome))) => outcome,
+            Ok(Ok(Err(e))) => (CheckStatus::Fail, e),
+            Ok(Err(_)) => (CheckStatus::Fail, format!("No answer after {} s", timeout.as_secs())),
+            Err(e) => (CheckStatus::Fail, format!("The check stopped: {}", e)),
+        };
+        SelfTestCheck { name, status, detail, duration_ms: elapsed_ms(started) }
+    })
+}
+
+/// For the checks asking other programs, off the async runtime.
+async fn blocking<F>(check: F) -> Result<(CheckStatus, String), String>
+where
+    F: FnOnce() -> Result<(CheckStatus, String), String> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(check).await
+        .map_err(|e| format!("The check stopped: {}", e))?
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    started.elapsed().as_millis() as u64
+}
+
+async fn check_read(pool: Pool<Sqlite>) -> Result<(CheckStatus, String), String> {
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+    let feeds = sqlx::query_scalar::<_, i64>(include_str!("../database/queries/self_test_read.sql"))
+        .fetch_one(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    transaction.rollback().await.map_err(|e| format!("Error ending transaction: {}", e))?;
+
+    Ok((CheckStatus::Pass, format!("Read {} feeds", feeds)))
+}
+
+/// Takes the write lock like a sync would, writing only to a temporary table of the connection.
+async fn check_write(pool: Pool<Sqlite>, read_only: Option<String>) -> Result<(CheckStatus, String), String> {
+    if let Some(reason) = read_only {
+        return Ok((CheckStatus::Warn, format!("Writes are refused: {}", reason)));
+    }
+
+    let mut transaction = match pool.begin_with("BEGIN IMMEDIATE").await {
+        Ok(transaction) => transaction,
+        Err(e) if e.as_database_error().is_some_and(|error| error.message().contains("locked")) => {
+            return Ok((CheckStatus::Warn, String::from("The database is busy, a sync may be writing")));
+        }
+        Err(e) => return Err(format!("Error starting write transaction: {}", e)),
+    };
+    sqlx::query(include_str!("../database/queries/create_self_test_probe.sql"))
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    sqlx::query(include_str!("../database/queries/insert_self_test_probe.sql"))
+        .bind(chrono::Utc::now().timestamp_millis())
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    transaction.rollback().await.map_err(|e| format!("Error rolling back: {}", e))?;
+
+    Ok((CheckStatus::Pass, String::from("Took the write lock and wrote to a temporary table")))
+}
+
+async fn check_migrations(pool: Pool<Sqlite>, migrated: bool) -> Result<(CheckStatus, String), String> {
+    let version = sqlx::query_scalar::<_, i64>(include_str!("../database/queries/get_schema_version.sql"))
+        .fetch_one(&pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    let latest = latest_migration();
+
+    Ok(match version {
+        _ if version == latest => (CheckStatus::Pass, format!("Schema {} is current", version)),
+        _ if version > latest => (CheckStatus::Warn, format!("Schema {} is newer than the {} of this version", version, latest)),
+        _ if migrated => (CheckStatus::Fail, format!("Schema {} wasn't migrated to {}", version, latest)),
+        _ => (CheckStatus::Warn, format!("Schema {}, migrated to {} at the next launch", version, latest)),
+    })
+}
+
+async fn check_search(pool: Pool<Sqlite>) -> Result<(CheckStatus, String), String> {
+    sqlx::query_scalar::<_, i64>(include_str!("../database/queries/self_test_search.sql"))
+        .bind("alduin")
+        .fetch_one(&pool).await
+        .map_err(|e| format!("The search index doesn't answer: {}", e))?;
+
+    Ok((CheckStatus::Pass, String::from("The search index answers")))
+}
+
+/// Names the feeds fetched with network settings of their own and which ones, for support to tell them
+/// apart from the defaults. Values are left out, the audit masks them.
+async fn check_overrides(pool: Pool<Sqlite>) -> Result<(CheckStatus, String), String> {
+    let audit = override_audit(&pool).await?;
+    if audit.is_empty() {
+        return Ok((CheckStatus::Pass, String::from("Every feed uses the network defaults")));
+    }
+
+    let feeds: Vec<String> = audit.iter()
+        .map(|feed| {
+            let kinds: Vec<&str> = feed.overrides.iter().map(|network_override| network_override.kind.label()).collect();
+            format!("{} ({})", feed.display_name, kinds.join(", "))
+        })
+        .collect();
+    Ok((CheckStatus::Pass, format!("{} feeds with their own network settings: {}", audit.len(), feeds.join("; "))))
+}
+
+/// Looks for what a write stopped between its statements, by a crash or the app quitting, used to leave
+/// behind. Nothing is repaired, the rows are harmless and the next edit of the feed removes them.
+async fn check_consistency(pool: Pool<Sqlite>) -> Result<(CheckStatus, String), String> {
+    let (credentials, certificates, windows) = sqlx::query_as::<_, (i64, i64, i64)>(include_str!("../database/queries/self_test_consistency.sql"))
+        .fetch_one(&pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    let found: Vec<String> = [
+        (credentials, "empty credentials"),
+        (certificates, "empty certificates"),
+        (windows, "downtimes without fetch history"),
+    ].into_iter().filter(|(count, _)| *count > 0).map(|(count, what)| format!("{} {}", count, what)).collect();
+
+    Ok(if found.is_empty() {
+        (CheckStatus::Pass, String::from("No interrupted write left anything behind"))
+    } else {
+        (CheckStatus::Warn, format!("Left by interrupted writes: {}", found.join(", ")))
+    })
+}
+
+async fn check_network() -> Result<(CheckStatus, String), String> {
+    let client = reqwest::Client::builder()
+        .timeout(NETWORK_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Error building client: {}", e))?;
+    let response = client.get(PROBE_URL).send().await
+        .map_err(|e| format!("Error reaching {}: {}", PROBE_URL, e))?;
+
+    // A portal or a proxy answering in its place still tells the network is up
+    Ok(match response.status().as_u16() {
+        204 => (CheckStatus::Pass, format!("Reached {}", PROBE_URL)),
+        status => (CheckStatus::Warn, format!("{} answered {} instead of 204, a captive portal or a proxy may be in the way", PROBE_URL, status)),
+    })
+}
+
+/// Writes then removes a file in each folder, without creating the missing ones.
+fn check_data_dirs(dirs: &[PathBuf]) -> Result<(CheckStatus, String), String> {
+    if dirs.is_empty() {
+        return Err(String::from("No data folder on this system"));
+    }
+
+    for dir in dirs {
+        if !dir.exists() {
+            return Ok((CheckStatus::Warn, format!("{} doesn't exist yet", dir.display())));
+        }
+        let probe = dir.join(format!(".alduin-self-test-{}", std::process::id()));
+        let written = OpenOptions::new().write(true).create_new(true).open(&probe)
+            .and_then(|mut file| file.write_all(b"alduin"));
+        let _ = fs::remove_file(&probe);
+        written.map_err(|e| format!("{} isn't writable: {}", dir.display(), e))?;
+    }
+
+    Ok((CheckStatus::Pass, format!("{} writable", dirs.iter().map(|dir| dir.display().to_string()).collect::<Vec<_>>().join(", "))))
+}
+
+fn check_disk_space(dirs: &[PathBuf]) -> Result<(CheckStatus, String), String> {
+    let dir = dirs.iter().find(|dir| dir.exists()).map(PathBuf::as_path).unwrap_or(Path::new("."));
+    let available = free_space(dir).ok_or_else(|| format!("Error reading the free space of {}", dir.display()))?;
+    let detail = format!("{} MB free on the disk of {}", available / 1024 / 1024, dir.display());
+
+    Ok(match available {
+        _ if available < CRITICAL_DISK_SPACE => (CheckStatus::Fail, detail),
+        _ if available < LOW_DISK_SPACE => (CheckStatus::Warn, detail),
+        _ => (CheckStatus::Pass, detail),
+    })
+}
+
+fn check_tray(status: TrayStatus) -> Result<(CheckStatus, String), String> {
+    let status = match status {
+        TrayStatus::Unknown => probe_tray_host(),
+        status => status,
+    };
+
+    Ok(match status {
+        TrayStatus::Available => (CheckStatus::Pass, String::from("A tray host shows the icon")),
+        TrayStatus::Unavailable => (CheckStatus::Warn, String::from("No tray host, a hidden window can't be brought back")),
+        TrayStatus::Unknown => (CheckStatus::Warn, String::from("The tray host couldn't be probed")),
+    })
+}
+
+fn check_notifications() -> Result<(CheckStatus, String), String> {
+    Ok(match probe_notification_daemon() {
+        Some(true) => (CheckStatus::Pass, String::from("Notifications can be shown")),
+        Some(false) => (CheckStatus::Warn, String::from("No notification daemon, notifications won't show")),
+        None => (CheckStatus::Warn, String::from("The notification daemon couldn't be probed")),
+    })
+}
+
+fn check_scheduler(status: Option<BackgroundSyncStatus>) -> Result<(CheckStatus, String), String> {
+    let Some(status) = status else {
+        return Ok((CheckStatus::Skip, String::from("Only checked in the running app")));
+    };
+
+    Ok(match (status.alive, status.paused, status.running) {
+        (false, _, _) => (CheckStatus::Fail, String::from("The background sync stopped")),
+        (true, true, _) => (CheckStatus::Warn, String::from("The background sync is paused")),
+        (true, false, true) => (CheckStatus::Pass, String::from("Syncing now")),
+        (true, false, false) => (CheckStatus::Pass, format!("Syncing every {} minutes", status.minutes)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::benchmark::random::SeededRandom;
+    use crate::commands::availability::clear_unavailability;
+    use crate::commands::certificates::{save_ca_certificate, save_client_certificate};
+    use crate::commands::comments::unwatch;
+    use crate::commands::credentials::{save_basic_auth, save_headers};
+    use crate::database::migrator::migrator;
+    use crate::scheduler::availability::record_fetch;
+    use crate::utils::cancellation::{cancel_after, count_pending};
+    use super::*;
+
+    const RESET: &str = "DELETE FROM feed_credentials; DELETE FROM feed_certificates; DELETE FROM watched_comments;
+        DELETE FROM article_comments; DELETE FROM feed_fetches; DELETE FROM forgotten_secrets;
+        UPDATE feeds SET unavailable_start = NULL, unavailable_minutes = NULL;";
+    /// What the writes below change, each only ever seen as before or after it
+    const STATE: &str = "SELECT json_array(
+        (SELECT json_group_array(json_array(username, password_secret, header_names, headers_secret)) FROM feed_credentials),
+        (SELECT json_group_array(json_array(client_certificate_path, client_certificate_secret, ca_certificate_path)) FROM feed_certificates),
+        (SELECT json_group_array(url) FROM watched_comments),
+        (SELECT json_group_array(identifier) FROM article_comments),
+        (SELECT json_group_array(json_array(local_date, local_minute, success)) FROM feed_fetches),
+        (SELECT json_group_array(json_array(unavailable_start, unavailable_minutes)) FROM feeds))";
+
+    /// The writes of several statements, from a state they change with each of them.
+    const WRITES: [(&str, &str); 7] = [
+        // Removing the username deletes the row left without credentials
+        ("set_feed_credentials", "INSERT INTO feed_credentials (feed_identifier, username, password_secret, updated_at) VALUES ('feed', 'reader', 'secret', 0)"),
+        ("set_feed_headers", "INSERT INTO feed_credentials (feed_identifier, header_names, headers_secret, updated_at) VALUES ('feed', '[\"X-Token\"]', 'secret', 0)"),
+        ("set_feed_client_certificate", "INSERT INTO feed_certificates (feed_identifier, client_certificate_path, client_certificate_secret, updated_at) VALUES ('feed', 'client.p12', 'secret', 0)"),
+        ("set_feed_ca_certificate", "INSERT INTO feed_certificates (feed_identifier, ca_certificate_path, updated_at) VALUES ('feed', 'ca.pem', 0)"),
+        ("unwatch_comments", "INSERT INTO watched_comments (article_identifier, url, last_activity) VALUES ('article', 'https://blog.example/comments', 0);
+            INSERT INTO article_comments (identifier, article_identifier, content, date) VALUES ('1', 'article', '', 0), ('2', 'article', '', 0)"),
+        ("clear_feed_unavailability", "INSERT INTO feed_fetches (feed_identifier, fetched_at, local_date, local_minute, success) VALUES ('feed', 0, '2024-01-01', 200, 0);
+            UPDATE feeds SET unavailable_start = 180, unavailable_minutes = 60"),
+        // A success clears the window its single failure can't explain
+        ("scheduled fetch", "INSERT INTO feed_fetches (feed_identifier, fetched_at, local_date, local_minute, success) VALUES ('feed', 0, '2024-01-01', 200, 0);
+            UPDATE feeds SET unavailable_start = 180, unavailable_minutes = 60"),
+    ];
+
+    fn write<'a>(name: &str, pool: &'a Pool<Sqlite>) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+        match name {
+            "set_feed_credentials" => Box::pin(save_basic_auth("feed", None, None, pool)),
+            "set_feed_headers" => Box::pin(save_headers("feed", &[], None, pool)),
+            "set_feed_client_certificate" => Box::pin(save_client_certificate("feed", None, None, pool)),
+            "set_feed_ca_certificate" => Box::pin(save_ca_certificate("feed", None, pool)),
+            "unwatch_comments" => Box::pin(unwatch("article", pool)),
+            "clear_feed_unavailability" => Box::pin(clear_unavailability("feed", pool)),
+            "scheduled fetch" => Box::pin(record_fetch("feed", "2024-01-02", 600, true, pool)),
+            _ => unreachable!(),
+        }
+    }
+
+    async fn state(name: &str, setup: &str, pool: &Pool<Sqlite>) -> String {
+        sqlx::raw_sql(RESET).execute(pool).await.unwrap();
+        sqlx::raw_sql(setup).execute(pool).await.unwrap();
+        assert_eq!(check_consistency(pool.clone()).await.unwrap().0, CheckStatus::Pass, "{}", name);
+        sqlx::query_scalar(STATE).fetch_one(pool).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn writes_cancelled_at_any_await_point_stay_whole() {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        sqlx::raw_sql("INSERT INTO feeds (identifier, display_name, url, interval) VALUES ('feed', 'feed', '', 60);
+            INSERT INTO articles (identifier, feed_identifier, title, content, date) VALUES ('article', 'feed', '', '', 0)")
+            .execute(&pool).await.unwrap();
+        let mut random = SeededRandom::new(269);
+
+        for (name, setup) in WRITES {
+            let before = state(name, setup, &pool).await;
+            let (points, written) = count_pending(write(name, &pool)).await;
+            written.unwrap();
+            let after: String = sqlx::query_scalar(STATE).fetch_one(&pool).await.unwrap();
+            assert_ne!(before, after, "{}", name);
+
+            // Each await point in turn, then as many at random, the worker answering some faster than others
+            let cancellations = (0..=points).chain((0..=points).map(|_| random.below(points + 1)));
+            for polls in cancellations {
+                assert_eq!(state(name, setup, &pool).await, before, "{}", name);
+                let finished = cancel_after(polls, write(name, &pool)).await;
+                // A transaction dropped is rolled back when its connection is next used
+                let state: String = sqlx::query_scalar(STATE).fetch_one(&pool).await.unwrap();
+
+                assert!(state == before || state == after, "{} cancelled after {} polls left {}", name, polls, state);
+                match finished {
+                    Some(Ok(())) => assert_eq!(state, after, "{} after {} polls", name, polls),
+                    Some(Err(e)) => panic!("{} after {} polls: {}", name, polls, e),
+                    None => (),
+                }
+                assert_eq!(check_consistency(pool.clone()).await.unwrap(), (CheckStatus::Pass, String::from("No interrupted write left anything behind")), "{} after {} polls", name, polls);
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager, Window};
+use crate::structs::session_state::SessionState;
+use crate::structs::write_gate::WriteGate;
+
+/// Saves where the user is in the calling window, each window having its own entry.
+#[alduin_macros::command]
+pub async fn save_session_state(state: SessionState, window: Window, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+
+    sqlx::query(include_str!("../database/queries/save_session_state.sql"))
+        .bind(window.label())
+        .bind(state.feed_identifier)
+        .bind(state.article_identifier)
+        .bind(state.scroll_anchor)
+        .bind(state.reader_scroll.map(|scroll| scroll.clamp(0.0, 1.0)))
+        .execute(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(())
+}
+
+#[alduin_macros::command]
+pub async fn get_session_state(window: Window, app_handle: AppHandle) -> Result<Option<SessionState>, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+
+    sqlx::query_as::<_, SessionState>(include_str!("../database/queries/get_session_state.sql"))
+        .bind(window.label())
+        .fetch_optional(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// Forgets the session of every window, used when the user chooses to start fresh.
+#[alduin_macros::command]
+pub async fn clear_session_state(app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+
+    sqlx::query(include_str!("../database/queries/clear_session_state.sql"))
+        .execute(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(())
+}
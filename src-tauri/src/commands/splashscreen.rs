@@ -1,13 +1,177 @@
-use tauri::{Manager, Window};
+use tauri::{AppHandle, Manager, Window, WindowBuilder, Wry};
+use crate::commands::list_windows::restore_list_windows;
+use crate::enums::window_status::WindowStatus;
+use crate::structs::main_window::MainWindow;
+use crate::utils::window_placement::move_to_preferred_monitor;
 
-#[tauri::command]
-pub async fn close_splashscreen(window: Window) {
-    if let Some(splashscreen) = window.get_window("splashscreen") {
-        splashscreen.close().unwrap();
+const MAIN_WINDOW: &str = "main";
+const SPLASHSCREEN: &str = "splashscreen";
+
+/// Closes the splashscreen, doing nothing once it is closed.
+#[alduin_macros::command]
+pub async fn close_splashscreen(app_handle: AppHandle) -> Result<WindowStatus, String> {
+    let main_window = app_handle.state::<MainWindow>();
+    let _lock = main_window.lock();
+
+    close_splashscreen_window(&app_handle, &main_window)
+}
+
+/// Shows the main window, doing nothing when it is already shown. Creates it from the configuration when
+/// it doesn't exist, and closes the splashscreen if `close_splashscreen` wasn't called yet.
+#[alduin_macros::command]
+pub async fn open_main_window(app_handle: AppHandle) -> Result<WindowStatus, String> {
+    show_main_window(&app_handle)
+}
+
+/// Same as `open_main_window`, for the tray. The first time, the list windows of the last session are reopened too.
+pub fn show_main_window(app_handle: &AppHandle<Wry>) -> Result<WindowStatus, String> {
+    let main_window = app_handle.state::<MainWindow>();
+    let _lock = main_window.lock();
+
+    let window = app_handle.get_window(MAIN_WINDOW);
+    let visible = window.as_ref().map(|window| window.is_visible().unwrap_or(false));
+    match window {
+        Some(_) if visible == Some(true) => {}
+        Some(window) => show(app_handle, &window)?,
+        None => show(app_handle, &create_main_window(app_handle)?)?,
+    }
+    let status = open_status(visible, &main_window);
+    close_splashscreen_window(app_handle, &main_window)?;
+    tauri::async_runtime::spawn(restore_list_windows(app_handle.clone()));
+
+    Ok(status)
+}
+
+/// What showing a main window that was `visible`, hidden or missing did, once it is shown.
+fn open_status(visible: Option<bool>, main_window: &MainWindow) -> WindowStatus {
+    match visible {
+        Some(true) => WindowStatus::AlreadyOpen,
+        Some(false) if main_window.take_recreated() => WindowStatus::Recreated,
+        Some(false) => WindowStatus::Opened,
+        None => {
+            main_window.take_recreated();
+            WindowStatus::Recreated
+        }
+    }
+}
+
+/// Creates the main window again after it went away without being asked to close. The new window
+/// keeps the label of the lost one, so `get_session_state` gives it back its session, and stays
+/// hidden until its frontend loaded and called `open_main_window`.
+pub fn recreate_main_window(app_handle: &AppHandle<Wry>) {
+    let main_window = app_handle.state::<MainWindow>();
+    let _lock = main_window.lock();
+
+    let created = match app_handle.get_window(MAIN_WINDOW) {
+        Some(_) => true,
+        None => match create_main_window(app_handle) {
+            Ok(_) => true,
+            Err(e) => {
+                eprintln!("{}", e);
+                false
+            }
+        },
+    };
+    main_window.finish_recreating(created);
+}
+
+fn create_main_window(app_handle: &AppHandle<Wry>) -> Result<Window<Wry>, String> {
+    let config = app_handle.config().tauri.windows.iter()
+        .find(|window| window.label == MAIN_WINDOW)
+        .cloned()
+        .ok_or_else(|| String::from("The main window is missing from the configuration"))?;
+
+    WindowBuilder::from_config(app_handle, config).build()
+        .map_err(|e| format!("Error creating the main window: {}", e))
+}
+
+fn show(app_handle: &AppHandle<Wry>, window: &Window<Wry>) -> Result<(), String> {
+    if let Err(e) = move_to_preferred_monitor(app_handle, window) {
+        eprintln!("Error moving the window to the preferred monitor: {}", e);
     }
+    window.show().map_err(|e| format!("Error showing the main window: {}", e))
 }
 
-#[tauri::command]
-pub async fn open_main_window(window: Window) {
-    window.get_window("main").unwrap().show().unwrap();
+fn close_splashscreen_window(app_handle: &AppHandle<Wry>, main_window: &MainWindow) -> Result<WindowStatus, String> {
+    let Some(splashscreen) = app_handle.get_window(SPLASHSCREEN) else { return Ok(WindowStatus::AlreadyClosed) };
+    // Closing is asynchronous, the window is still there when called again right after
+    if !main_window.close_splashscreen() {
+        return Ok(WindowStatus::AlreadyClosed);
+    }
+
+    splashscreen.close().map_err(|e| format!("Error closing the splashscreen: {}", e))?;
+    Ok(WindowStatus::Closed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use super::*;
+
+    #[test]
+    fn opening_twice_does_nothing() {
+        let main_window = MainWindow::default();
+
+        // The main window is created hidden from the configuration, then shown by the frontend
+        assert_eq!(open_status(Some(false), &main_window), WindowStatus::Opened);
+        assert_eq!(open_status(Some(true), &main_window), WindowStatus::AlreadyOpen);
+        // Hidden to the tray, then shown from it
+        assert_eq!(open_status(Some(false), &main_window), WindowStatus::Opened);
+    }
+
+    #[test]
+    fn closes_the_splashscreen_once() {
+        let main_window = Arc::new(MainWindow::default());
+
+        // The frontend and open_main_window racing, while the first close is still in flight
+        let closes: Vec<bool> = (0..8)
+            .map(|_| {
+                let main_window = main_window.clone();
+                std::thread::spawn(move || {
+                    let _lock = main_window.lock();
+                    main_window.close_splashscreen()
+                })
+            })
+            .map(|thread| thread.join().unwrap())
+            .collect();
+        assert_eq!(closes.iter().filter(|closed| **closed).count(), 1);
+        assert!(!main_window.close_splashscreen());
+    }
+
+    #[test]
+    fn recreates_a_destroyed_main_window() {
+        let main_window = MainWindow::default();
+        assert_eq!(open_status(Some(false), &main_window), WindowStatus::Opened);
+
+        // The webview process crashed, the app waiting for the new window instead of exiting
+        assert!(main_window.destroyed());
+        assert!(main_window.is_recreating());
+        main_window.finish_recreating(true);
+        assert!(!main_window.is_recreating());
+
+        // Reported once, when its frontend first shows it
+        assert_eq!(open_status(Some(false), &main_window), WindowStatus::Recreated);
+        assert_eq!(open_status(Some(true), &main_window), WindowStatus::AlreadyOpen);
+        assert_eq!(open_status(Some(false), &main_window), WindowStatus::Opened);
+
+        // Failing to create it, the next open creates it
+        assert!(main_window.destroyed());
+        main_window.finish_recreating(false);
+        assert_eq!(open_status(None, &main_window), WindowStatus::Recreated);
+        assert_eq!(open_status(Some(true), &main_window), WindowStatus::AlreadyOpen);
+    }
+
+    #[test]
+    fn closing_on_request_isnt_recreated() {
+        let main_window = MainWindow::default();
+
+        main_window.expect_close();
+        assert!(!main_window.destroyed());
+        assert!(!main_window.is_recreating());
+        // Opened from the tray later
+        assert_eq!(open_status(None, &main_window), WindowStatus::Recreated);
+
+        // The request is used up by the window it was for
+        assert!(main_window.destroyed());
+    }
 }
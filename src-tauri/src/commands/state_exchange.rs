@@ -0,0 +1,12 @@
+use tauri::{AppHandle, Manager};
+use crate::database::state_exchange::exchange_now;
+use crate::structs::exchange_report::ExchangeReport;
+use crate::structs::write_gate::WriteGate;
+
+/// Exchanges the read state and the subscriptions with the other devices now, rather than at the next interval.
+#[alduin_macros::command]
+pub async fn exchange_state(app_handle: AppHandle) -> Result<ExchangeReport, String> {
+    app_handle.state::<WriteGate>().check()?;
+
+    exchange_now(&app_handle).await
+}
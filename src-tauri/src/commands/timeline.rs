@@ -0,0 +1,283 @@
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::commands::articles::BACKFILLED_AGE_MS;
+use crate::enums::article_sort::ArticleSort;
+use crate::enums::page_direction::PageDirection;
+use crate::structs::article_cursor::ArticleCursor;
+use crate::structs::article_filter::ArticleFilter;
+use crate::structs::read_pool::ReadPool;
+use crate::structs::stored_article::StoredArticle;
+use crate::structs::timeline_page::TimelinePage;
+use crate::utils::pagination::{page_limit, MAX_PAGE_SIZE};
+
+/// A page of the All Articles view before or after `cursor`, from the newest article without one when
+/// paging older and from the oldest when paging newer. `filter.cursor` is left out for `cursor`.
+///
+/// Unlike `get_articles`, each page is a range of an index also holding the columns of the filters, so
+/// it takes as long anywhere in the history, even when the filter leaves out most of the articles.
+#[alduin_macros::command]
+pub async fn get_timeline(filter: ArticleFilter, cursor: Option<ArticleCursor>, direction: PageDirection, app_handle: AppHandle) -> Result<TimelinePage, String> {
+    let limit = page_limit("get_timeline", filter.limit, MAX_PAGE_SIZE);
+
+    let pool = app_handle.state::<ReadPool>();
+    load_timeline(&filter, cursor.as_ref(), direction, limit, &pool).await
+}
+
+/// The cursor an older page starts from to begin with the article closest to `date`, in milliseconds, among
+/// the matching ones. None when that is the newest article, the page then starting from the top.
+#[alduin_macros::command]
+pub async fn jump_to_date(filter: ArticleFilter, date: i64, app_handle: AppHandle) -> Result<Option<ArticleCursor>, String> {
+    let pool = app_handle.state::<ReadPool>();
+    load_jump(&filter, date, &pool).await
+}
+
+/// `limit` being already capped.
+pub async fn load_timeline(filter: &ArticleFilter, cursor: Option<&ArticleCursor>, direction: PageDirection, limit: i64, pool: &Pool<Sqlite>) -> Result<TimelinePage, String> {
+    // One more tells whether the page reaches the end, rather than a full page leaving an empty one after it
+    let mut articles = fetch_page(filter, cursor, direction, limit + 1, pool).await?;
+    let more = articles.len() as i64 > limit;
+    articles.truncate(limit as usize);
+
+    // Loaded from the cursor outwards, the newer ones come oldest first
+    if direction == PageDirection::Newer {
+        articles.reverse();
+    }
+
+    let (older_cursor, newer_cursor) = match direction {
+        PageDirection::Older => (
+            more.then(|| articles.last().map(ArticleCursor::after)).flatten(),
+            cursor.is_some().then(|| articles.first().map(ArticleCursor::after)).flatten(),
+        ),
+        PageDirection::Newer => (
+            cursor.is_some().then(|| articles.last().map(ArticleCursor::after)).flatten(),
+            more.then(|| articles.first().map(ArticleCursor::after)).flatten(),
+        ),
+    };
+
+    Ok(TimelinePage { articles, limit, older_cursor, newer_cursor })
+}
+
+/// Compares the last article at or before `date` with the first one after it, each found in the index
+/// from a position between them.
+pub async fn load_jump(filter: &ArticleFilter, date: i64, pool: &Pool<Sqlite>) -> Result<Option<ArticleCursor>, String> {
+    let boundary = boundary_cursor(filter.sort, date.saturating_add(1));
+    let before = fetch_page(filter, Some(&boundary), PageDirection::Older, 1, pool).await?;
+    let after = fetch_page(filter, Some(&boundary), PageDirection::Newer, 2, pool).await?;
+
+    let closest_is_before = match (before.first(), after.first()) {
+        (Some(before), Some(after)) => date.saturating_sub(position(before, filter.sort)) <= position(after, filter.sort).saturating_sub(date),
+        (before, _) => before.is_some(),
+    };
+
+    // The article right after the closest one bounds the page starting with it
+    let newer_neighbour = if closest_is_before { after.first() } else { after.get(1) };
+    Ok(newer_neighbour.map(ArticleCursor::after))
+}
+
+async fn fetch_page(filter: &ArticleFilter, cursor: Option<&ArticleCursor>, direction: PageDirection, limit: i64, pool: &Pool<Sqlite>) -> Result<Vec<StoredArticle>, String> {
+    let query = match (filter.sort, direction) {
+        (ArticleSort::Date, PageDirection::Older) => include_str!("../database/queries/get_timeline_older.sql"),
+        (ArticleSort::Date, PageDirection::Newer) => include_str!("../database/queries/get_timeline_newer.sql"),
+        (ArticleSort::FirstSeen, PageDirection::Older) => include_str!("../database/queries/get_first_seen_timeline_older.sql"),
+        (ArticleSort::FirstSeen, PageDirection::Newer) => include_str!("../database/queries/get_first_seen_timeline_newer.sql"),
+        (ArticleSort::Score, _) => return Err(String::from("The timeline is sorted by date or first seen, not by score")),
+    };
+
+    // Starting from the very end rather than from no cursor keeps the range searchable
+    let edge = match direction {
+        PageDirection::Older => i64::MAX,
+        PageDirection::Newer => i64::MIN,
+    };
+    let (sort_key, date, identifier) = cursor
+        .map(|cursor| (cursor.sort_key(filter.sort), cursor.date, cursor.identifier.as_str()))
+        .unwrap_or((edge, edge, ""));
+
+    let statement = sqlx::query_as::<_, StoredArticle>(query)
+        .bind(filter.feed_identifier.as_deref())
+        .bind(filter.min_score)
+        .bind(date)
+        .bind(identifier)
+        .bind(limit)
+        .bind(BACKFILLED_AGE_MS)
+        .bind(filter.read_state.map(|state| state.as_str()));
+    let statement = match filter.sort {
+        ArticleSort::FirstSeen => statement.bind(sort_key),
+        ArticleSort::Date | ArticleSort::Score => statement,
+    };
+
+    statement.fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// A position in between the articles before `time` and those from it on, no identifier sorting before "".
+fn boundary_cursor(sort: ArticleSort, time: i64) -> ArticleCursor {
+    match sort {
+        ArticleSort::FirstSeen => ArticleCursor { date: i64::MIN, identifier: String::new(), score: None, first_seen: Some(time) },
+        ArticleSort::Date | ArticleSort::Score => ArticleCursor { date: time, identifier: String::new(), score: None, first_seen: None },
+    }
+}
+
+/// The time an article is sorted by in the timeline.
+fn position(article: &StoredArticle, sort: ArticleSort) -> i64 {
+    match sort {
+        ArticleSort::FirstSeen => article.first_seen.unwrap_or(article.date),
+        ArticleSort::Date | ArticleSort::Score => article.date,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::benchmark::random::SeededRandom;
+    use crate::database::migrator::migrator;
+    use crate::enums::read_state::ReadState;
+    use super::*;
+
+    const ARTICLES: u64 = 400;
+    const HOUR_MS: i64 = 60 * 60 * 1000;
+
+    /// The articles as (identifier, feed, date, first seen, read), dates often shared and some never seen
+    /// by a sync, from before the first one was recorded.
+    async fn fixture() -> (Pool<Sqlite>, Vec<(String, &'static str, i64, Option<i64>, bool)>) {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval) VALUES ('a', 'a', '', 60), ('b', 'b', '', 60)")
+            .execute(&pool).await.unwrap();
+
+        let mut random = SeededRandom::new(273);
+        let mut articles = vec![];
+        for index in 0..ARTICLES {
+            let feed = if random.chance(0.3) { "b" } else { "a" };
+            let date = random.below(200) as i64 * HOUR_MS;
+            let first_seen = random.chance(0.8).then(|| date + random.below(400) as i64 * HOUR_MS);
+            // Most are read, as in a timeline caught up with
+            let read = random.chance(0.9);
+            let identifier = format!("{:04}", random.below(10_000) * 1_000 + index);
+            sqlx::query("INSERT INTO articles (identifier, feed_identifier, title, content, date, first_seen, read) VALUES (?, ?, '', '', ?, ?, ?)")
+                .bind(&identifier).bind(feed).bind(date).bind(first_seen).bind(read)
+                .execute(&pool).await.unwrap();
+            articles.push((identifier, feed, date, first_seen, read));
+        }
+        (pool, articles)
+    }
+
+    fn filters() -> Vec<ArticleFilter> {
+        let mut filters = vec![];
+        for sort in [ArticleSort::Date, ArticleSort::FirstSeen] {
+            for (feed_identifier, read_state) in [(None, None), (None, Some(ReadState::Unread)), (None, Some(ReadState::Read)), (Some("b"), None), (Some("b"), Some(ReadState::Unread))] {
+                filters.push(ArticleFilter { sort, read_state, feed_identifier: feed_identifier.map(String::from), ..ArticleFilter::default() });
+            }
+        }
+        filters
+    }
+
+    /// The matching articles newest first, with the time they're sorted by.
+    fn expected(articles: &[(String, &str, i64, Option<i64>, bool)], filter: &ArticleFilter) -> Vec<(i64, String)> {
+        let mut matching: Vec<(i64, i64, String)> = articles.iter()
+            .filter(|(_, feed, _, _, _)| filter.feed_identifier.as_deref().is_none_or(|identifier| identifier == *feed))
+            .filter(|(_, _, _, _, read)| match filter.read_state {
+                Some(ReadState::Unread) => !read,
+                Some(ReadState::Read) => *read,
+                Some(ReadState::UpdatedSinceRead) => false,
+                None => true,
+            })
+            .map(|(identifier, _, date, first_seen, _)| match filter.sort {
+                ArticleSort::FirstSeen => (first_seen.unwrap_or(*date), *date, identifier.clone()),
+                ArticleSort::Date | ArticleSort::Score => (*date, 0, identifier.clone()),
+            })
+            .collect();
+        matching.sort();
+        matching.reverse();
+        matching.into_iter().map(|(position, _, identifier)| (position, identifier)).collect()
+    }
+
+    fn identifiers(page: &TimelinePage) -> Vec<String> {
+        page.articles.iter().map(|article| article.identifier.clone()).collect()
+    }
+
+    #[tokio::test]
+    async fn pages_through_the_whole_timeline_both_ways() {
+        let (pool, articles) = fixture().await;
+
+        for filter in filters() {
+            let expected: Vec<String> = expected(&articles, &filter).into_iter().map(|(_, identifier)| identifier).collect();
+            assert!(!expected.is_empty(), "{:?}", filter);
+
+            for limit in [1, 7, 50, 1_000] {
+                let case = (&filter, limit);
+                let mut older_pages = vec![];
+                let mut cursor = None;
+                loop {
+                    let page = load_timeline(&filter, cursor.as_ref(), PageDirection::Older, limit, &pool).await.unwrap();
+                    assert_eq!(page.newer_cursor.is_none(), older_pages.is_empty(), "{:?}", case);
+                    cursor = page.older_cursor.clone();
+                    older_pages.push(page);
+                    if cursor.is_none() {
+                        break;
+                    }
+                }
+                let walked: Vec<String> = older_pages.iter().flat_map(identifiers).collect();
+                assert_eq!(walked, expected, "{:?}", case);
+
+                // Back up from each page lands on the one before it
+                for pages in older_pages.windows(2) {
+                    let newer = load_timeline(&filter, pages[1].newer_cursor.as_ref(), PageDirection::Newer, limit, &pool).await.unwrap();
+                    assert_eq!(identifiers(&newer), identifiers(&pages[0]), "{:?}", case);
+                }
+
+                let mut newer_pages = vec![];
+                let mut cursor = None;
+                loop {
+                    let page = load_timeline(&filter, cursor.as_ref(), PageDirection::Newer, limit, &pool).await.unwrap();
+                    assert_eq!(page.older_cursor.is_none(), newer_pages.is_empty(), "{:?}", case);
+                    cursor = page.newer_cursor.clone();
+                    newer_pages.push(page);
+                    if cursor.is_none() {
+                        break;
+                    }
+                }
+                let walked: Vec<String> = newer_pages.iter().rev().flat_map(identifiers).collect();
+                assert_eq!(walked, expected, "{:?}", case);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn jumps_to_the_closest_article() {
+        let (pool, articles) = fixture().await;
+        let mut random = SeededRandom::new(1);
+
+        for filter in filters() {
+            let expected = expected(&articles, &filter);
+            // Before the oldest, after the newest, right on articles and in between
+            let mut dates = vec![-100 * HOUR_MS, 1_000 * HOUR_MS];
+            dates.extend((0..10).map(|_| expected[random.below(expected.len() as u64) as usize].0));
+            dates.extend((0..30).map(|_| random.below(600 * 60) as i64 * 60 * 1000));
+
+            for date in dates {
+                // The last at or before the date, or the first after it when closer
+                let before = expected.iter().position(|(position, _)| *position <= date);
+                let after = before.map_or(expected.len(), |index| index).checked_sub(1);
+                let closest = match (before, after) {
+                    (Some(before), Some(after)) if expected[after].0 - date < date - expected[before].0 => after,
+                    (Some(before), _) => before,
+                    (None, after) => after.unwrap(),
+                };
+
+                let cursor = load_jump(&filter, date, &pool).await.unwrap();
+                assert_eq!(cursor.is_none(), closest == 0, "{:?}", (&filter, date));
+                let page = load_timeline(&filter, cursor.as_ref(), PageDirection::Older, 3, &pool).await.unwrap();
+                assert_eq!(page.articles[0].identifier, expected[closest].1, "{:?}", (&filter, date));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn refuses_to_sort_by_score() {
+        let (pool, _) = fixture().await;
+        let filter = ArticleFilter { sort: ArticleSort::Score, ..ArticleFilter::default() };
+
+        assert!(load_timeline(&filter, None, PageDirection::Older, 10, &pool).await.is_err());
+        assert!(load_jump(&filter, 0, &pool).await.is_err());
+    }
+}
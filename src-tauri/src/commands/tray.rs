@@ -0,0 +1,62 @@
+use std::time::Duration;
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::enums::tray_status::TrayStatus;
+use crate::scheduler::power::power_policy;
+use crate::structs::preferences::Preferences;
+use crate::structs::tray_monitor::TrayMonitor;
+use crate::structs::tray_report::TrayReport;
+use crate::utils::tray::{close_to_tray, probe_tray_host, status_event, tray_menu};
+
+const TRAY_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+#[alduin_macros::command]
+pub async fn get_tray_status(app_handle: AppHandle) -> Result<TrayReport, String> {
+    Ok(tray_report(&app_handle))
+}
+
+/// Hides the main window to the tray, or quits when the tray can't bring it back.
+#[alduin_macros::command]
+pub async fn close_main_window(app_handle: AppHandle) -> Result<(), String> {
+    let window = app_handle.get_window("main").ok_or_else(|| String::from("Main window not found"))?;
+
+    if tray_report(&app_handle).close_to_tray {
+        window.hide().map_err(|e| format!("Error hiding window: {}", e))
+    } else {
+        window.close().map_err(|e| format!("Error closing window: {}", e))
+    }
+}
+
+/// Probes the tray host right away then periodically, as it may only show up once the login session is fully started.
+/// Emits `tray:unavailable` and `tray:available` on changes.
+pub async fn watch_tray(app_handle: AppHandle) {
+    loop {
+        let status = tauri::async_runtime::spawn_blocking(probe_tray_host).await.unwrap_or_default();
+
+        if let Some(previous) = app_handle.state::<TrayMonitor>().update(status) {
+            if previous == TrayStatus::Unavailable && status == TrayStatus::Available {
+                // So the new host gets the menu
+                if let Err(e) = app_handle.tray_handle().set_menu(tray_menu(&power_policy(&app_handle), app_handle.try_state::<Pool<Sqlite>>().is_some())) {
+                    eprintln!("Error registering tray menu: {}", e);
+                }
+            }
+
+            if let Some(event) = status_event(status) {
+                if let Err(e) = app_handle.emit_all(event, tray_report(&app_handle)) {
+                    eprintln!("Error emitting tray status: {}", e);
+                }
+            }
+        }
+
+        tokio::time::sleep(TRAY_PROBE_INTERVAL).await;
+    }
+}
+
+fn tray_report(app_handle: &AppHandle) -> TrayReport {
+    let status = app_handle.state::<TrayMonitor>().status();
+
+    TrayReport {
+        status,
+        close_to_tray: close_to_tray(Preferences::load(app_handle).close_behavior, status),
+    }
+}
@@ -0,0 +1,40 @@
+use sqlx::{Pool, Sqlite};
+use sqlx::types::Json;
+use tauri::{AppHandle, Manager};
+use crate::structs::webview_capabilities::WebviewCapabilities;
+use crate::structs::write_gate::WriteGate;
+
+/// Whether the frontend still has to report what the webview can decode, which happens once per app version.
+#[alduin_macros::command]
+pub async fn needs_webview_probe(app_handle: AppHandle) -> Result<bool, String> {
+    Ok(load_webview_capabilities(&app_handle).await?.is_none())
+}
+
+#[alduin_macros::command]
+pub async fn report_webview_capabilities(capabilities: WebviewCapabilities, app_handle: AppHandle) -> Result<(), String> {
+    let _writing = app_handle.state::<WriteGate>().enter()?;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    sqlx::query(include_str!("../database/queries/save_webview_capabilities.sql"))
+        .bind(app_handle.package_info().version.to_string())
+        .bind(Json(capabilities.image_types.0))
+        .bind(Json(capabilities.audio_types.0))
+        .execute(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(())
+}
+
+#[alduin_macros::command]
+pub async fn get_webview_capabilities(app_handle: AppHandle) -> Result<Option<WebviewCapabilities>, String> {
+    load_webview_capabilities(&app_handle).await
+}
+
+/// The capabilities probed by the running version, None until the probe is done.
+pub async fn load_webview_capabilities(app_handle: &AppHandle) -> Result<Option<WebviewCapabilities>, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    sqlx::query_as::<_, WebviewCapabilities>(include_str!("../database/queries/get_webview_capabilities.sql"))
+        .bind(app_handle.package_info().version.to_string())
+        .fetch_optional(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
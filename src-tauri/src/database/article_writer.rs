@@ -0,0 +1,209 @@
+use std::time::Duration;
+use sqlx::{Pool, Sqlite, SqliteConnection};
+use sqlx::types::Json;
+use tokio::sync::mpsc::{Receiver, UnboundedReceiver};
+use crate::commands::cookies::set_session_state;
+use crate::commands::feed_changes::record_feed_change;
+use crate::enums::feed_change_source::FeedChangeSource;
+use crate::enums::feed_write::FeedWrite;
+use crate::enums::session_state::SessionState;
+use crate::structs::feed_completion::FeedCompletion;
+use crate::structs::feed_stream::FeedStream;
+use crate::structs::prepared_article::PreparedArticle;
+use crate::structs::write_gate::WriteGate;
+
+/// Pruned articles that left the feed are remembered this long still, for the feeds bringing back items
+/// they dropped for a while.
+const PRUNED_ARTICLES_GRACE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Writes the feeds of a sync run as their streams arrive, until every `SyncWriter` handle is dropped.
+pub async fn run(mut streams: UnboundedReceiver<FeedStream>, pool: Pool<Sqlite>, gate: WriteGate) {
+    while let Some(FeedStream { feed_identifier, writes, done }) = streams.recv().await {
+        let result = write_feed(&feed_identifier, writes, &pool, &gate).await;
+        // The sync may have been dropped meanwhile, its articles being stored anyway
+        let _ = done.send(result);
+    }
+}
+
+/// Stores the articles of a feed as they arrive and commits them with its completion, so a sync failing
+/// halfway leaves nothing from this fetch behind. Returns how many articles were new.
+///
+/// A freeze waits for the feed being written, which is rolled back rather than committed when writes were
+/// frozen meanwhile, the freeze then not waiting for the rest of its articles.
+async fn write_feed(feed_identifier: &str, mut writes: Receiver<FeedWrite>, pool: &Pool<Sqlite>, gate: &WriteGate) -> Result<i64, String> {
+    let _writing = gate.enter()?;
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+    let mut ingested = 0;
+
+    while let Some(write) = writes.recv().await {
+        match write {
+            FeedWrite::Articles(articles) => {
+                gate.check()?;
+                for article in articles {
+                    ingested += insert_article(feed_identifier, article, &mut transaction).await? as i64;
+                }
+            },
+            FeedWrite::Finish(completion) => {
+                gate.check()?;
+                complete_feed(feed_identifier, completion, &mut transaction).await?;
+                transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))?;
+                return Ok(ingested);
+            }
+        }
+    }
+
+    Err(String::from("Error syncing feed: it stopped before its last articles, none were stored"))
+}
+
+/// Inserts the article, or updates its content when it is already stored. Returns whether it was new.
+pub async fn insert_article(feed_identifier: &str, prepared: PreparedArticle, connection: &mut SqliteConnection) -> Result<bool, String> {
+    let PreparedArticle { identifier, article, score, dedup_key, links } = prepared;
+
+    let inserted = sqlx::query(include_str!("queries/insert_article.sql"))
+        .bind(identifier)
+        .bind(feed_identifier)
+        .bind(&article.title)
+        .bind(&article.content)
+        .bind(article.date)
+        .bind(article.image.map(|image| image.uri))
+        .bind(article.comments_url)
+        .bind(article.link)
+        .bind(score)
+        .bind(dedup_key)
+        .bind(Json(article.post_processors))
+        .bind(Json(article.extras))
+        .bind(&article.original_title)
+        .bind(&article.language)
+        .execute(&mut *connection).await;
+
+    let inserted = match inserted {
+        // Ignored for the articles pruned before, see `commands::retention`
+        Ok(result) => Ok(result.rows_affected() > 0),
+        // cast Error is a SqliteError
+        Err(e) => match e.as_database_error() {
+            // code() is a Cow<str>, so we need to match on &str
+            Some(database_error) => match database_error.code().unwrap_or_default().to_string().as_str() {
+                "2067" => Ok(false),
+                "1555" => Ok(false),
+                _ => Err(format!("Error executing query: {}", e)),
+            },
+            None => Err(format!("Error executing query: {}", e)),
+        },
+    }?;
+
+    // Already stored, the feed may have edited it since
+    if !inserted {
+        sqlx::query(include_str!("queries/update_article_content.sql"))
+            .bind(identifier)
+            .bind(&article.title)
+            .bind(&article.content)
+            .bind(&article.original_title)
+            .execute(&mut *connection).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+    }
+
+    for enclosure in article.enclosures {
+        sqlx::query(include_str!("queries/insert_enclosure.sql"))
+            .bind(identifier)
+            .bind(enclosure.url)
+            .bind(enclosure.mime_type)
+            .bind(enclosure.role)
+            .bind(enclosure.language)
+            .execute(&mut *connection).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+    }
+
+    for (host, path) in links {
+        sqlx::query(include_str!("queries/insert_article_link.sql"))
+            .bind(identifier)
+            .bind(host)
+            .bind(path)
+            .execute(&mut *connection).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+    }
+
+    Ok(inserted)
+}
+
+pub async fn complete_feed(feed_identifier: &str, completion: FeedCompletion, connection: &mut SqliteConnection) -> Result<(), String> {
+    let FeedCompletion { feed_type, image_url, validators, session_working, fetched, previous } = completion;
+
+    if session_working {
+        set_session_state(feed_identifier, SessionState::Working, &mut *connection).await?;
+    }
+
+    sqlx::query(include_str!("queries/sync.sql"))
+        .bind(feed_type)
+        .bind(&image_url)
+        .bind(feed_identifier)
+        .bind(validators.etag)
+        .bind(validators.last_modified)
+        .execute(&mut *connection).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    // An empty fetch is more likely a broken feed than every article leaving it
+    if !fetched.is_empty() {
+        sqlx::query(include_str!("queries/delete_departed_pruned_articles.sql"))
+            .bind(feed_identifier)
+            .bind(Json(fetched))
+            .bind(PRUNED_ARTICLES_GRACE.as_millis() as i64)
+            .execute(&mut *connection).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+    }
+
+    if let Some((old_type, old_image_url)) = previous {
+        record_feed_change(connection, feed_identifier, "type", old_type, Some(feed_type.to_string()), FeedChangeSource::Sync).await?;
+        record_feed_change(connection, feed_identifier, "imageUrl", old_image_url, image_url, FeedChangeSource::Sync).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::database::migrator::migrator;
+    use crate::structs::feed_validators::FeedValidators;
+    use super::*;
+
+    const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+    fn completion(fetched: &[&str]) -> FeedCompletion {
+        FeedCompletion {
+            feed_type: "rss",
+            image_url: None,
+            validators: FeedValidators::default(),
+            session_working: false,
+            fetched: fetched.iter().map(|identifier| identifier.to_string()).collect(),
+            previous: None,
+        }
+    }
+
+    async fn pruned(connection: &mut SqliteConnection) -> Vec<String> {
+        sqlx::query_scalar("SELECT identifier FROM pruned_articles ORDER BY identifier").fetch_all(connection).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn forgets_the_pruned_articles_that_left_the_feed() {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval) VALUES ('feed', 'feed', '', 60), ('other', 'other', '', 60)")
+            .execute(&pool).await.unwrap();
+        let now = chrono::Utc::now().timestamp_millis();
+        let tombstones = [("1", "feed", 60), ("2", "feed", 60), ("3", "feed", 1), ("4", "other", 60)];
+        for (identifier, feed, days_ago) in tombstones {
+            sqlx::query("INSERT INTO pruned_articles (identifier, feed_identifier, pruned_at) VALUES (?, ?, ?)")
+                .bind(identifier).bind(feed).bind(now - days_ago * DAY_MS)
+                .execute(&pool).await.unwrap();
+        }
+        let mut connection = pool.acquire().await.unwrap();
+
+        // Nothing fetched, the feed may be broken
+        complete_feed("feed", completion(&[]), &mut connection).await.unwrap();
+        assert_eq!(pruned(&mut connection).await, ["1", "2", "3", "4"]);
+
+        complete_feed("feed", completion(&["1", "5"]), &mut connection).await.unwrap();
+        // 1 is still in the feed and 3 left it lately, the ones of the other feed aren't this sync's
+        assert_eq!(pruned(&mut connection).await, ["1", "3", "4"]);
+    }
+}
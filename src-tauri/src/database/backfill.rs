@@ -0,0 +1,345 @@
+//! Data changes too heavy for a migration, which would lock the database for minutes on big profiles.
+//!
+//! The migration only makes the instant schema change, like adding a nullable column or a table,
+//! and registers its backfill with a row in `backfills`. The backfill then runs after startup in
+//! small transactions, each one saving its progress along with its batch.
+//! Until it is done, queries must accept rows that aren't backfilled yet. For instance the dedup code
+//! computes a missing dedup key on the fly rather than relying on the column.
+//!
+//! Progress is saved as a rowid, which the full `VACUUM` of a first prune can change, so the prune starts
+//! the backfills not done over. Each batch has to skip the rows it already handled, or redo them harmlessly.
+
+use std::time::Duration;
+use chrono::Utc;
+use sqlx::{Pool, Sqlite, SqliteConnection};
+use sqlx::types::Json;
+use tauri::{AppHandle, Manager};
+use crate::commands::dedup::merge_duplicates;
+use crate::enums::background_job::BackgroundJob;
+use crate::enums::dedup_strategy::DedupStrategy;
+use crate::parsers::outbound_links::outbound_links;
+use crate::scheduler::power::power_policy;
+use crate::structs::activity_monitor::ActivityMonitor;
+use crate::structs::backfill_progress::BackfillProgress;
+use crate::structs::write_gate::WriteGate;
+use crate::utils::dedup::dedup_key;
+use crate::utils::title::display_title;
+
+const BATCH_SIZE: i64 = 500;
+/// Leaves room for the commands between two batches.
+const BATCH_PAUSE: Duration = Duration::from_millis(50);
+/// Lets the frontend load the database first, which applies the migrations registering backfills.
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+const PAUSED_RETRY: Duration = Duration::from_secs(60);
+
+/// Rowid, link and title of an article, with the title prefixes of its feed
+type TitleRow = (i64, Option<String>, String, Json<Vec<String>>);
+
+#[derive(Clone, Copy)]
+enum Backfill {
+    DedupKeys,
+    Links,
+    Titles,
+}
+
+impl Backfill {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "article_dedup_keys" => Some(Backfill::DedupKeys),
+            "article_links" => Some(Backfill::Links),
+            "article_titles" => Some(Backfill::Titles),
+            _ => None,
+        }
+    }
+
+    /// Processes the rows after `last_rowid` and returns the rowid of the last one, None once no row is left.
+    async fn run_batch(&self, last_rowid: i64, connection: &mut SqliteConnection) -> Result<Option<i64>, String> {
+        match self {
+            Backfill::DedupKeys => {
+                let rows: Vec<(i64, Option<String>, String)> = sqlx::query_as(include_str!("queries/get_dedup_key_backfill_batch.sql"))
+                    .bind(last_rowid)
+                    .bind(BATCH_SIZE)
+                    .fetch_all(&mut *connection).await
+                    .map_err(|e| format!("Error executing query: {}", e))?;
+
+                // Articles without a usable link keep no key, the rowid still moves past them
+                for (rowid, link, title) in rows.iter() {
+                    if let Some(key) = dedup_key(link.as_deref(), title) {
+                        sqlx::query(include_str!("queries/set_article_dedup_key.sql"))
+                            .bind(key)
+                            .bind(rowid)
+                            .execute(&mut *connection).await
+                            .map_err(|e| format!("Error executing query: {}", e))?;
+                    }
+                }
+
+                Ok(rows.last().map(|(rowid, _, _)| *rowid))
+            }
+            Backfill::Links => {
+                let rows: Vec<(i64, String, String, Option<String>)> = sqlx::query_as(include_str!("queries/get_link_backfill_batch.sql"))
+                    .bind(last_rowid)
+                    .bind(BATCH_SIZE)
+                    .fetch_all(&mut *connection).await
+                    .map_err(|e| format!("Error executing query: {}", e))?;
+
+                for (_, identifier, content, link) in rows.iter() {
+                    for (host, path) in outbound_links(content, link.as_deref()) {
+                        sqlx::query(include_str!("queries/insert_article_link.sql"))
+                            .bind(identifier)
+                            .bind(host)
+                            .bind(path)
+                            .execute(&mut *connection).await
+                            .map_err(|e| format!("Error executing query: {}", e))?;
+                    }
+                }
+
+                Ok(rows.last().map(|(rowid, _, _, _)| *rowid))
+            }
+            Backfill::Titles => {
+                let rows: Vec<TitleRow> = sqlx::query_as(include_str!("queries/get_title_backfill_batch.sql"))
+                    .bind(last_rowid)
+                    .bind(BATCH_SIZE)
+                    .fetch_all(&mut *connection).await
+                    .map_err(|e| format!("Error executing query: {}", e))?;
+
+                // The title column still holds the original title, moved to original_title
+                for (rowid, link, title, prefixes) in rows.iter() {
+                    let key = dedup_key(link.as_deref(), title);
+                    let cleaned = display_title(title, prefixes);
+                    let query = if cleaned == *title {
+                        // Leaves the search index alone
+                        sqlx::query(include_str!("queries/set_article_original_title.sql"))
+                    } else {
+                        sqlx::query(include_str!("queries/set_backfilled_title.sql")).bind(cleaned)
+                    };
+                    query.bind(key)
+                        .bind(rowid)
+                        .execute(&mut *connection).await
+                        .map_err(|e| format!("Error executing query: {}", e))?;
+                }
+
+                Ok(rows.last().map(|(rowid, _, _, _)| *rowid))
+            }
+        }
+    }
+
+    /// Runs in the transaction of the last batch.
+    async fn finish(&self, connection: &mut SqliteConnection) -> Result<(), String> {
+        match self {
+            Backfill::DedupKeys | Backfill::Links => Ok(()),
+            // Copies of an article stored under differently encoded titles now share their key, and
+            // are merged the way switching the feed to link and title dedup merged the others
+            Backfill::Titles => {
+                let feeds: Vec<String> = sqlx::query_scalar(include_str!("queries/get_link_title_feeds.sql"))
+                    .bind(DedupStrategy::LinkTitle)
+                    .fetch_all(&mut *connection).await
+                    .map_err(|e| format!("Error executing query: {}", e))?;
+
+                for feed in feeds {
+                    merge_duplicates(&feed, &mut *connection).await?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Runs the next batch and saves the progress in the same transaction, so a run killed midway resumes
+    /// right after the last batch committed. Returns whether the backfill is done.
+    async fn advance(&self, name: &str, connection: &mut SqliteConnection) -> Result<bool, String> {
+        // Read again each time, a full VACUUM in between resetting it
+        let last_rowid: i64 = sqlx::query_scalar(include_str!("queries/get_backfill_progress.sql"))
+            .bind(name)
+            .fetch_one(&mut *connection).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+        let next = self.run_batch(last_rowid, &mut *connection).await?;
+        if next.is_none() {
+            self.finish(&mut *connection).await?;
+        }
+
+        sqlx::query(include_str!("queries/save_backfill_progress.sql"))
+            .bind(next.unwrap_or(last_rowid))
+            .bind(next.is_none())
+            .bind(name)
+            .execute(&mut *connection).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+
+        Ok(next.is_none())
+    }
+
+    async fn remaining(&self, last_rowid: i64, pool: &Pool<Sqlite>) -> Result<i64, String> {
+        let query = match self {
+            Backfill::DedupKeys => include_str!("queries/count_dedup_key_backfill.sql"),
+            Backfill::Links => include_str!("queries/count_link_backfill.sql"),
+            Backfill::Titles => include_str!("queries/count_title_backfill.sql"),
+        };
+
+        sqlx::query_scalar(query)
+            .bind(last_rowid)
+            .fetch_one(pool).await
+            .map_err(|e| format!("Error executing query: {}", e))
+    }
+}
+
+/// Runs the pending backfills one after the other, resuming from their saved progress.
+pub async fn run_backfills(app_handle: AppHandle) {
+    tokio::time::sleep(STARTUP_DELAY).await;
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let pending: Vec<(String, i64, bool)> = match sqlx::query_as(include_str!("queries/get_backfills.sql"))
+        .fetch_all(&*pool).await {
+        Ok(backfills) => backfills,
+        Err(e) => {
+            eprintln!("Error loading backfills: {}", e);
+            return;
+        }
+    };
+
+    for (name, _, _) in pending.into_iter().filter(|(_, _, done)| !done) {
+        let Some(backfill) = Backfill::from_name(&name) else {
+            eprintln!("Unknown backfill {}", name);
+            continue;
+        };
+
+        if let Err(e) = run_backfill(backfill, &name, &app_handle).await {
+            eprintln!("Error running backfill {}: {}", name, e);
+        }
+    }
+}
+
+async fn run_backfill(backfill: Backfill, name: &str, app_handle: &AppHandle) -> Result<(), String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+
+    loop {
+        // Waits for a thaw, to be plugged in or for the user to be idle, rather than giving up until the next
+        // launch. The user coming back pauses it after the batch running, committed with its progress
+        let writing = loop {
+            match app_handle.state::<WriteGate>().enter() {
+                Ok(writing) if power_policy(app_handle).idle_maintenance
+                    && app_handle.state::<ActivityMonitor>().may_run(BackgroundJob::Backfill, Utc::now().timestamp_millis()) => break writing,
+                _ => tokio::time::sleep(PAUSED_RETRY).await,
+            }
+        };
+
+        let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+        let done = backfill.advance(name, &mut transaction).await?;
+        transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))?;
+        drop(writing);
+
+        if done {
+            return Ok(());
+        }
+        tokio::time::sleep(BATCH_PAUSE).await;
+    }
+}
+
+pub async fn backfill_progress(pool: &Pool<Sqlite>) -> Result<Vec<BackfillProgress>, String> {
+    let backfills: Vec<(String, i64, bool)> = sqlx::query_as(include_str!("queries/get_backfills.sql"))
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    let mut progress = vec![];
+    for (name, last_rowid, done) in backfills {
+        let remaining = match (done, Backfill::from_name(&name)) {
+            (false, Some(backfill)) => Some(backfill.remaining(last_rowid, pool).await?),
+            _ => None,
+        };
+        progress.push(BackfillProgress { name, done, remaining });
+    }
+
+    Ok(progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::database::migrator::migrator;
+    use super::*;
+
+    const ARTICLES: i64 = 2 * BATCH_SIZE + 20;
+
+    /// Articles stored before the dedup key existed, every tenth one without a link.
+    async fn fixture() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval) VALUES ('feed', 'feed', '', 60)")
+            .execute(&pool).await.unwrap();
+        sqlx::query("
+            WITH RECURSIVE numbers(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM numbers WHERE n < ?)
+            INSERT INTO articles (identifier, feed_identifier, title, original_title, content, date, link)
+            SELECT n, 'feed', 'Article ' || n, 'Article ' || n, '', n, CASE WHEN n % 10 = 0 THEN NULL ELSE 'https://a.example/' || n END
+            FROM numbers
+        ")
+            .bind(ARTICLES)
+            .execute(&pool).await.unwrap();
+        sqlx::query("UPDATE articles SET dedup_key = NULL").execute(&pool).await.unwrap();
+        sqlx::query("UPDATE backfills SET last_rowid = 0, done = 0 WHERE name = 'article_dedup_keys'").execute(&pool).await.unwrap();
+        pool
+    }
+
+    async fn progress(pool: &Pool<Sqlite>) -> BackfillProgress {
+        backfill_progress(pool).await.unwrap().into_iter()
+            .find(|progress| progress.name == "article_dedup_keys")
+            .unwrap()
+    }
+
+    async fn keyed(pool: &Pool<Sqlite>) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM articles WHERE dedup_key IS NOT NULL").fetch_one(pool).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn registers_the_backfills_with_their_migration() {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+
+        let backfills = backfill_progress(&pool).await.unwrap();
+        for progress in backfills.iter() {
+            assert!(Backfill::from_name(&progress.name).is_some(), "{}", progress.name);
+            // Nothing to backfill in a new profile
+            assert_eq!(progress.remaining, (!progress.done).then_some(0), "{}", progress.name);
+        }
+        assert!(backfills.iter().any(|progress| progress.name == "article_dedup_keys"));
+    }
+
+    #[tokio::test]
+    async fn resumes_after_the_last_committed_batch() {
+        let pool = fixture().await;
+        let linked = ARTICLES - ARTICLES / 10;
+        assert_eq!(progress(&pool).await.remaining, Some(ARTICLES));
+
+        let mut transaction = pool.begin().await.unwrap();
+        assert!(!Backfill::DedupKeys.advance("article_dedup_keys", &mut transaction).await.unwrap());
+        transaction.commit().await.unwrap();
+        let first_batch = keyed(&pool).await;
+        assert_eq!(first_batch, BATCH_SIZE - BATCH_SIZE / 10);
+
+        // Killed in the middle of the second batch
+        let mut transaction = pool.begin().await.unwrap();
+        assert!(!Backfill::DedupKeys.advance("article_dedup_keys", &mut transaction).await.unwrap());
+        drop(transaction);
+        assert_eq!(keyed(&pool).await, first_batch);
+        assert_eq!(progress(&pool).await.remaining, Some(ARTICLES - BATCH_SIZE));
+
+        // The next launch goes on from the first batch
+        let mut batches = 0;
+        loop {
+            let mut transaction = pool.begin().await.unwrap();
+            let done = Backfill::DedupKeys.advance("article_dedup_keys", &mut transaction).await.unwrap();
+            transaction.commit().await.unwrap();
+            batches += 1;
+            if done {
+                break;
+            }
+        }
+
+        // The second batch, the rest, and the empty one telling it is done
+        assert_eq!(batches, 3);
+        assert_eq!(keyed(&pool).await, linked);
+        let progress = progress(&pool).await;
+        assert!(progress.done);
+        assert_eq!(progress.remaining, None);
+
+        let mismatched: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM articles WHERE link IS NOT NULL AND dedup_key IS NULL")
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(mismatched, 0);
+    }
+}
@@ -0,0 +1,324 @@
+//! Lets a window catch up with the changes whose events it missed, e.g. while its webview was reloading.
+//!
+//! Triggers journal every change to the articles, the feeds and the reading queue, frontend writes
+//! included. Events about changed data are sent with `emit_change`, carrying the watermark of the last
+//! change, which the window acknowledges once it applied them. When a window is focused, the changes after
+//! its acknowledged watermark are pushed to it as `changes-missed`. Events about articles skip the list
+//! windows whose filter can't show them, see `emit_article_change`. Too many changes, or changes already
+//! pruned from the journal, are replaced by a full refresh of the window.
+
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager, Window};
+use crate::structs::journal_change::JournalChange;
+use crate::structs::journaled::Journaled;
+use crate::structs::list_filter::ListFilter;
+use crate::structs::list_windows::ListWindows;
+use crate::structs::missed_changes::MissedChanges;
+use crate::structs::write_gate::WriteGate;
+
+const RETENTION_MS: i64 = 24 * 60 * 60 * 1000;
+/// Beyond it reloading everything is cheaper than applying the changes one by one
+const MAX_MISSED_CHANGES: i64 = 500;
+
+/// Sends an event about changed data to every window, with the watermark to acknowledge.
+pub async fn emit_change<S: Serialize + Clone>(app_handle: &AppHandle, event: &str, payload: S) -> Result<(), String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let watermark = watermark(&pool).await?;
+
+    app_handle.emit_all(event, Journaled { payload, watermark, list_windows: None })
+        .map_err(|e| format!("Error emitting {}: {}", event, e))
+}
+
+/// Like `emit_change` for changed articles, the list windows only getting the event when their filter
+/// passes `shows`, so a sync doesn't wake every one of them. The others catch up once focused.
+pub async fn emit_article_change<S: Serialize + Clone>(app_handle: &AppHandle, event: &str, payload: S, shows: impl Fn(&ListFilter) -> bool) -> Result<(), String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let watermark = watermark(&pool).await?;
+    let list_windows = app_handle.state::<ListWindows>();
+    let showing = list_windows.showing(shows);
+
+    let journaled = Journaled { payload, watermark, list_windows: Some(showing.clone()) };
+    for (label, window) in app_handle.windows() {
+        if list_windows.contains(&label) && !showing.contains(&label) {
+            continue;
+        }
+        if let Err(e) = window.emit(event, journaled.clone()) {
+            eprintln!("Error emitting {} to window {}: {}", event, label, e);
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn watermark(pool: &Pool<Sqlite>) -> Result<i64, String> {
+    sqlx::query_scalar::<_, i64>(include_str!("queries/get_change_watermark.sql"))
+        .fetch_one(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+pub async fn acknowledge(window_label: &str, watermark: i64, pool: &Pool<Sqlite>) -> Result<(), String> {
+    sqlx::query(include_str!("queries/ack_changes.sql"))
+        .bind(window_label)
+        .bind(watermark)
+        .execute(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(())
+}
+
+/// A window that never acknowledged anything, e.g. one just created, gets a full refresh.
+pub async fn missed_changes(window_label: &str, pool: &Pool<Sqlite>) -> Result<MissedChanges, String> {
+    let watermark = watermark(pool).await?;
+    let acknowledged = sqlx::query_scalar::<_, i64>(include_str!("queries/get_change_ack.sql"))
+        .bind(window_label)
+        .fetch_optional(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    let Some(from) = acknowledged else {
+        return Ok(MissedChanges { from: 0, watermark, full_refresh: true, changes: vec![] });
+    };
+    if from >= watermark {
+        return Ok(MissedChanges { from, watermark, full_refresh: false, changes: vec![] });
+    }
+
+    let oldest = sqlx::query_scalar::<_, Option<i64>>(include_str!("queries/get_oldest_change.sql"))
+        .fetch_one(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    if oldest.is_none_or(|oldest| oldest > from + 1) || watermark - from > MAX_MISSED_CHANGES {
+        return Ok(MissedChanges { from, watermark, full_refresh: true, changes: vec![] });
+    }
+
+    let changes = sqlx::query_as::<_, JournalChange>(include_str!("queries/get_changes_since.sql"))
+        .bind(from)
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(MissedChanges { from, watermark, full_refresh: false, changes })
+}
+
+/// Pushes the missed changes to a window being focused. Skipped until the database is set up.
+pub async fn catch_up(window: Window) {
+    let Some(pool) = window.try_state::<Pool<Sqlite>>() else { return };
+
+    match missed_changes(window.label(), &pool).await {
+        Ok(missed) if missed.full_refresh || !missed.changes.is_empty() => {
+            if let Err(e) = window.emit("changes-missed", missed) {
+                eprintln!("Error emitting missed changes: {}", e);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Error loading the changes missed by window {}: {}", window.label(), e),
+    }
+}
+
+/// Drops the changes older than a day. Run at startup.
+pub async fn tidy(app_handle: AppHandle) {
+    let Ok(_writing) = app_handle.state::<WriteGate>().enter() else { return };
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    if let Err(e) = sqlx::query(include_str!("queries/prune_change_journal.sql"))
+        .bind(RETENTION_MS)
+        .execute(&*pool).await {
+        eprintln!("Error pruning the change journal: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::database::migrator::migrator;
+    use crate::enums::change_entity::ChangeEntity;
+    use super::*;
+
+    /// xorshift64, so every failing seed can be replayed.
+    struct Random(u64);
+
+    impl Random {
+        fn below(&mut self, bound: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % bound
+        }
+    }
+
+    async fn fixture() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval) VALUES ('feed', 'feed', '', 60)")
+            .execute(&pool).await.unwrap();
+        pool
+    }
+
+    type Shown = BTreeMap<String, (String, bool)>;
+
+    async fn article(identifier: &str, pool: &Pool<Sqlite>) -> Option<(String, bool)> {
+        sqlx::query_as("SELECT title, read FROM articles WHERE identifier = ?")
+            .bind(identifier)
+            .fetch_optional(pool).await.unwrap()
+    }
+
+    async fn articles(pool: &Pool<Sqlite>) -> Shown {
+        sqlx::query_as::<_, (String, String, bool)>("SELECT identifier, title, read FROM articles")
+            .fetch_all(pool).await.unwrap()
+            .into_iter()
+            .map(|(identifier, title, read)| (identifier, (title, read)))
+            .collect()
+    }
+
+    /// A window showing the articles, which loses them and the events sent meanwhile when its webview reloads.
+    struct SimulatedWindow {
+        shown: Shown,
+        listening: bool,
+    }
+
+    impl SimulatedWindow {
+        /// Like `ChangeManager`, a journaled event reloads the articles before acknowledging.
+        async fn receive(&mut self, watermark: i64, pool: &Pool<Sqlite>) {
+            if self.listening {
+                self.shown = articles(pool).await;
+                acknowledge("main", watermark, pool).await.unwrap();
+            }
+        }
+
+        /// Only the rows the journal names are reloaded, so every missed change has to be in it.
+        async fn catch_up(&mut self, pool: &Pool<Sqlite>) {
+            let missed = missed_changes("main", pool).await.unwrap();
+            if missed.full_refresh {
+                self.shown = articles(pool).await;
+            }
+            for change in missed.changes.iter().filter(|change| change.entity == ChangeEntity::Article) {
+                match article(&change.identifier, pool).await {
+                    Some(row) => self.shown.insert(change.identifier.clone(), row),
+                    None => self.shown.remove(&change.identifier),
+                };
+            }
+            acknowledge("main", missed.watermark, pool).await.unwrap();
+        }
+
+        /// Recreated under the same label: it loads what it shows, then pulls what it missed meanwhile.
+        async fn recreate(&mut self, pool: &Pool<Sqlite>) {
+            self.shown = articles(pool).await;
+            self.listening = true;
+            self.catch_up(pool).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn windows_recreated_mid_sync_converge() {
+        for seed in 1..=20u64 {
+            let pool = fixture().await;
+            let mut random = Random(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+            let mut window = SimulatedWindow { shown: Shown::new(), listening: false };
+            window.recreate(&pool).await;
+            let mut next = 0;
+
+            for _ in 0..150 {
+                let identifiers: Vec<String> = articles(&pool).await.into_keys().collect();
+                match random.below(10) {
+                    0..=3 => {
+                        next += 1;
+                        sqlx::query("INSERT INTO articles (identifier, feed_identifier, title, content, date) VALUES (?, 'feed', 'synced', '', 0)")
+                            .bind(format!("a{}", next))
+                            .execute(&pool).await.unwrap();
+                    }
+                    4 | 5 if !identifiers.is_empty() => {
+                        let identifier = &identifiers[random.below(identifiers.len() as u64) as usize];
+                        sqlx::query("UPDATE articles SET title = ?, read = NOT read WHERE identifier = ?")
+                            .bind(format!("edited {}", seed))
+                            .bind(identifier)
+                            .execute(&pool).await.unwrap();
+                    }
+                    6 if !identifiers.is_empty() => {
+                        let identifier = &identifiers[random.below(identifiers.len() as u64) as usize];
+                        sqlx::query("DELETE FROM articles WHERE identifier = ?").bind(identifier).execute(&pool).await.unwrap();
+                    }
+                    // The webview reloads, or the window is closed until a later recreation
+                    7 if window.listening => {
+                        window.listening = false;
+                        window.shown.clear();
+                    }
+                    8 if !window.listening => window.recreate(&pool).await,
+                    8 => window.catch_up(&pool).await,
+                    _ => {}
+                }
+                // Most writes send their event, lost while the window doesn't listen
+                if random.below(3) > 0 {
+                    let watermark = watermark(&pool).await.unwrap();
+                    window.receive(watermark, &pool).await;
+                }
+            }
+
+            if !window.listening {
+                window.recreate(&pool).await;
+            }
+            // Focused at last
+            window.catch_up(&pool).await;
+            assert_eq!(window.shown, articles(&pool).await, "seed {}", seed);
+            let missed = missed_changes("main", &pool).await.unwrap();
+            assert!(!missed.full_refresh && missed.changes.is_empty(), "seed {}", seed);
+        }
+    }
+
+    #[tokio::test]
+    async fn lists_the_changes_after_the_acknowledged_watermark() {
+        let pool = fixture().await;
+        sqlx::query("INSERT INTO articles (identifier, feed_identifier, title, content, date) VALUES ('1', 'feed', '', '', 0), ('2', 'feed', '', '', 0)")
+            .execute(&pool).await.unwrap();
+
+        let fresh = missed_changes("main", &pool).await.unwrap();
+        assert!(fresh.full_refresh);
+        acknowledge("main", fresh.watermark, &pool).await.unwrap();
+
+        sqlx::query("UPDATE articles SET read = 1 WHERE identifier = '1'").execute(&pool).await.unwrap();
+        sqlx::query("UPDATE feeds SET display_name = 'renamed'").execute(&pool).await.unwrap();
+        sqlx::query("UPDATE articles SET title = 'again' WHERE identifier = '1'").execute(&pool).await.unwrap();
+        // Not shown, the backfills would flood the journal
+        sqlx::query("UPDATE articles SET dedup_key = 'key' WHERE identifier = '2'").execute(&pool).await.unwrap();
+
+        let missed = missed_changes("main", &pool).await.unwrap();
+        assert!(!missed.full_refresh);
+        assert_eq!(missed.from, fresh.watermark);
+        let changes: Vec<(ChangeEntity, &str)> = missed.changes.iter().map(|change| (change.entity, change.identifier.as_str())).collect();
+        assert_eq!(changes, [(ChangeEntity::Feed, "feed"), (ChangeEntity::Article, "1")]);
+
+        // A late event doesn't move the watermark back
+        acknowledge("main", missed.watermark, &pool).await.unwrap();
+        acknowledge("main", fresh.watermark, &pool).await.unwrap();
+        let caught_up = missed_changes("main", &pool).await.unwrap();
+        assert_eq!((caught_up.from, caught_up.changes.len()), (missed.watermark, 0));
+    }
+
+    #[tokio::test]
+    async fn refreshes_everything_rather_than_too_many_or_pruned_changes() {
+        let pool = fixture().await;
+        acknowledge("main", watermark(&pool).await.unwrap(), &pool).await.unwrap();
+        sqlx::query("
+            WITH RECURSIVE numbers(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM numbers WHERE n < ?)
+            INSERT INTO articles (identifier, feed_identifier, title, content, date) SELECT n, 'feed', '', '', 0 FROM numbers
+        ").bind(MAX_MISSED_CHANGES + 1).execute(&pool).await.unwrap();
+
+        let flood = missed_changes("main", &pool).await.unwrap();
+        assert!(flood.full_refresh && flood.changes.is_empty());
+        acknowledge("main", flood.watermark, &pool).await.unwrap();
+
+        sqlx::query("UPDATE articles SET read = 1 WHERE identifier = '1'").execute(&pool).await.unwrap();
+        acknowledge("other", flood.watermark + 1, &pool).await.unwrap();
+        sqlx::query("UPDATE articles SET read = 1 WHERE identifier = '2'").execute(&pool).await.unwrap();
+        sqlx::query("UPDATE change_journal SET changed_at = changed_at - ? WHERE watermark <= ?")
+            .bind(RETENTION_MS + 1)
+            .bind(flood.watermark + 1)
+            .execute(&pool).await.unwrap();
+        sqlx::query(include_str!("queries/prune_change_journal.sql")).bind(RETENTION_MS).execute(&pool).await.unwrap();
+
+        // The first change main missed was pruned, the one other missed is still there
+        assert!(missed_changes("main", &pool).await.unwrap().full_refresh);
+        let other = missed_changes("other", &pool).await.unwrap();
+        assert!(!other.full_refresh);
+        assert_eq!(other.changes.iter().map(|change| change.identifier.as_str()).collect::<Vec<_>>(), ["2"]);
+        // The watermark survives the pruning
+        assert_eq!(other.watermark, flood.watermark + 2);
+    }
+}
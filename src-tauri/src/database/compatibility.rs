@@ -0,0 +1,157 @@
+//! Keeps an older version of the app, e.g. one rolled back to after a bad update, from using a database
+//! changed in ways it doesn't understand.
+//!
+//! Migrations older versions can't cope with raise `min_schema_version` in `schema_compatibility`. The
+//! database is checked before the migrations run: a version that doesn't know that schema opens it
+//! read-only. A version only missing harmless migrations uses it as usual, skipping the migrator, which
+//! refuses databases with migrations it doesn't know. A backup is made before new migrations are applied,
+//! by `migrator` right after.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{ConnectOptions, Connection, Pool, Sqlite, SqliteConnection};
+use crate::database::latest_migration;
+use crate::structs::schema_check::SchemaCheck;
+use crate::structs::version_mismatch::VersionMismatch;
+
+const KEPT_BACKUPS: usize = 3;
+
+/// Never fails: a database that can't be inspected is left to the migrator, as before this check existed.
+pub async fn check_schema(database_file: &Path, app_version: &str) -> SchemaCheck {
+    if !database_file.exists() {
+        return SchemaCheck::default();
+    }
+
+    match inspect(database_file, app_version).await {
+        Ok(check) => check,
+        Err(e) => {
+            eprintln!("Error checking the database schema: {}", e);
+            SchemaCheck::default()
+        }
+    }
+}
+
+/// Records this version as the minimum once it applied a breaking migration.
+pub async fn record_min_app_version(app_version: &str, pool: &Pool<Sqlite>) {
+    if let Err(e) = sqlx::query(include_str!("queries/set_min_app_version.sql"))
+        .bind(app_version)
+        .execute(pool).await {
+        eprintln!("Error recording the minimum app version: {}", e);
+    }
+}
+
+async fn inspect(database_file: &Path, app_version: &str) -> Result<SchemaCheck, String> {
+    let mut connection = SqliteConnectOptions::new()
+        .filename(database_file)
+        .read_only(true)
+        .connect().await
+        .map_err(|e| format!("Error opening database: {}", e))?;
+
+    let schema_version = match table_exists("_sqlx_migrations", &mut connection).await? {
+        true => sqlx::query_scalar::<_, i64>(include_str!("queries/get_schema_version.sql"))
+            .fetch_one(&mut connection).await
+            .map_err(|e| format!("Error executing query: {}", e))?,
+        false => 0,
+    };
+
+    let (min_schema_version, min_app_version) = match table_exists("schema_compatibility", &mut connection).await? {
+        true => sqlx::query_as::<_, (i64, Option<String>)>(include_str!("queries/get_schema_compatibility.sql"))
+            .fetch_optional(&mut connection).await
+            .map_err(|e| format!("Error executing query: {}", e))?
+            .unwrap_or((0, None)),
+        false => (0, None),
+    };
+
+    let supported_schema_version = latest_migration();
+    if min_schema_version > supported_schema_version {
+        eprintln!("Database schema {} requires a newer version than {}, opening it read-only", schema_version, app_version);
+        return Ok(SchemaCheck {
+            run_migrations: false,
+            mismatch: Some(VersionMismatch {
+                app_version: app_version.to_string(),
+                minimum_app_version: min_app_version,
+                schema_version,
+                supported_schema_version,
+                backup_path: latest_backup(database_file, supported_schema_version).map(|path| path.display().to_string()),
+            }),
+            failure: None,
+        });
+    }
+
+    if schema_version > 0 && schema_version < supported_schema_version {
+        if let Err(e) = backup(database_file, schema_version, &mut connection).await {
+            eprintln!("Error backing up the database before migrating it: {}", e);
+        }
+    }
+
+    if let Err(e) = connection.close().await {
+        eprintln!("Error closing the schema check connection: {}", e);
+    }
+
+    Ok(SchemaCheck {
+        run_migrations: schema_version <= supported_schema_version,
+        mismatch: None,
+        failure: None,
+    })
+}
+
+async fn table_exists(name: &str, connection: &mut SqliteConnection) -> Result<bool, String> {
+    sqlx::query_scalar::<_, bool>(include_str!("queries/table_exists.sql"))
+        .bind(name)
+        .fetch_one(connection).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// Copies the database as of `schema_version` next to it, keeping the latest few copies.
+async fn backup(database_file: &Path, schema_version: i64, connection: &mut SqliteConnection) -> Result<(), String> {
+    let directory = backups_directory(database_file);
+    fs::create_dir_all(&directory).map_err(|e| format!("Error creating backups directory: {}", e))?;
+
+    // VACUUM INTO refuses to overwrite, an older copy of the same schema is replaced
+    let path = directory.join(format!("alduin-schema-{}.db", schema_version));
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Error removing previous backup: {}", e))?;
+    }
+
+    sqlx::query(include_str!("queries/backup_database.sql"))
+        .bind(path.display().to_string())
+        .execute(connection).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    eprintln!("Backed up schema {} database to {:?}", schema_version, path);
+
+    for (_, old) in backups(database_file).into_iter().rev().skip(KEPT_BACKUPS) {
+        if let Err(e) = fs::remove_file(&old) {
+            eprintln!("Error removing old backup {:?}: {}", old, e);
+        }
+    }
+
+    Ok(())
+}
+
+fn latest_backup(database_file: &Path, max_schema_version: i64) -> Option<PathBuf> {
+    backups(database_file).into_iter()
+        .rev()
+        .find(|(version, _)| *version <= max_schema_version)
+        .map(|(_, path)| path)
+}
+
+/// Backups sorted by schema version.
+fn backups(database_file: &Path) -> Vec<(i64, PathBuf)> {
+    let Ok(entries) = fs::read_dir(backups_directory(database_file)) else { return vec![] };
+
+    let mut backups: Vec<(i64, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let version = name.strip_prefix("alduin-schema-")?.strip_suffix(".db")?.parse::<i64>().ok()?;
+            Some((version, entry.path()))
+        })
+        .collect();
+    backups.sort();
+    backups
+}
+
+fn backups_directory(database_file: &Path) -> PathBuf {
+    database_file.with_file_name("backups")
+}
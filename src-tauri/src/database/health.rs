@@ -0,0 +1,113 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use sqlx::pool::PoolConnectionMetadata;
+use sqlx::{Pool, Sqlite};
+
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Device and inode on unix, creation time elsewhere: either changes when the file is replaced
+type FileIdentity = (u64, u64);
+
+struct DatabaseFile {
+    path: Option<PathBuf>,
+    identity: Option<FileIdentity>,
+    replaced_at: Option<Instant>,
+}
+
+static DATABASE_FILE: Mutex<DatabaseFile> = Mutex::new(DatabaseFile { path: None, identity: None, replaced_at: None });
+static ACQUIRE_TIMEOUTS: AtomicU64 = AtomicU64::new(0);
+static FILE_REPLACEMENTS: AtomicU64 = AtomicU64::new(0);
+
+/// Remembers the identity of the database file the pool connects to.
+pub fn watch_file(path: &Path) {
+    let mut file = DATABASE_FILE.lock().unwrap();
+    file.identity = file_identity(path);
+    file.path = Some(path.to_path_buf());
+}
+
+/// Called by the pool before handing out a connection. Connections opened before the database file
+/// was replaced (restore, external tooling) still point to the old file and are discarded,
+/// so the pool rebuilds itself with connections to the new one.
+pub fn is_current(metadata: &PoolConnectionMetadata) -> bool {
+    let mut file = DATABASE_FILE.lock().unwrap();
+
+    // A missing file is most likely being replaced right now, we keep the old identity until it is back
+    if let Some(identity) = file.path.as_deref().and_then(file_identity) {
+        if file.identity.is_some_and(|known| known != identity) {
+            eprintln!("Database file was replaced, reopening connections");
+            FILE_REPLACEMENTS.fetch_add(1, Ordering::SeqCst);
+            file.replaced_at = Some(Instant::now());
+        }
+        file.identity = Some(identity);
+    }
+
+    match (file.replaced_at, Instant::now().checked_sub(metadata.age)) {
+        (Some(replaced_at), Some(opened_at)) => opened_at > replaced_at,
+        _ => true,
+    }
+}
+
+/// Acquires a connection every few minutes so a long idle period never leaves the pool without a
+/// working one, and counts the acquire timeouts for the diagnostics.
+pub async fn keep_alive(pool: Pool<Sqlite>) {
+    loop {
+        tokio::time::sleep(KEEP_ALIVE_INTERVAL).await;
+
+        match pool.acquire().await {
+            Ok(mut connection) => {
+                if let Err(e) = sqlx::query("SELECT 1").execute(&mut *connection).await {
+                    eprintln!("Database keep-alive failed: {}", e);
+                }
+            }
+            Err(sqlx::Error::PoolTimedOut) => {
+                ACQUIRE_TIMEOUTS.fetch_add(1, Ordering::SeqCst);
+                eprintln!("Database keep-alive timed out acquiring a connection");
+            }
+            Err(e) => eprintln!("Database keep-alive failed: {}", e),
+        }
+    }
+}
+
+/// Copies the write-ahead log back into the database without waiting for the readers, the frames they still
+/// use being left for the next checkpoint. Called once the syncs are done writing.
+pub async fn checkpoint(pool: &Pool<Sqlite>) {
+    if let Err(e) = sqlx::query(include_str!("queries/wal_checkpoint_passive.sql")).execute(pool).await {
+        eprintln!("Error checkpointing the database: {}", e);
+    }
+}
+
+/// Size of the database file and its write-ahead log, 0 before the pool connected.
+pub fn database_bytes() -> u64 {
+    let Some(path) = DATABASE_FILE.lock().unwrap().path.clone() else { return 0 };
+    let wal = PathBuf::from(format!("{}-wal", path.display()));
+
+    [path, wal].iter()
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+pub fn acquire_timeouts() -> u64 {
+    ACQUIRE_TIMEOUTS.load(Ordering::SeqCst)
+}
+
+pub fn file_replacements() -> u64 {
+    FILE_REPLACEMENTS.load(Ordering::SeqCst)
+}
+
+#[cfg(unix)]
+fn file_identity(path: &Path) -> Option<FileIdentity> {
+    use std::os::unix::fs::MetadataExt;
+
+    fs::metadata(path).ok().map(|metadata| (metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_identity(path: &Path) -> Option<FileIdentity> {
+    let created = fs::metadata(path).ok()?.created().ok()?
+        .duration_since(std::time::UNIX_EPOCH).ok()?;
+    Some((created.as_secs(), created.subsec_nanos() as u64))
+}
@@ -0,0 +1,137 @@
+//! Usage metrics about the app, for the insights page. They stay in the local database and are never
+//! sent anywhere.
+//!
+//! Every write to `local_metrics` goes through this file, so it lists all that is recorded: daily
+//! counters of the metrics in `LocalMetric`, without any article or feed identifier. Nothing is
+//! recorded unless the `localMetrics` preference is on, and turning it off wipes the table.
+
+use chrono::Months;
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::enums::local_metric::LocalMetric;
+use crate::structs::preferences::Preferences;
+use crate::structs::write_gate::WriteGate;
+use crate::utils::time_format::today;
+
+/// Adds to today's counter, the local date of `time_format`. Failures are only logged, metrics must never make a command fail.
+pub async fn record(app_handle: &AppHandle, metric: LocalMetric, amount: i64) {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    record_with(&Preferences::load(app_handle), &app_handle.state::<WriteGate>(), &pool, metric, amount).await;
+}
+
+async fn record_with(preferences: &Preferences, gate: &WriteGate, pool: &Pool<Sqlite>, metric: LocalMetric, amount: i64) {
+    if amount == 0 || !preferences.local_metrics {
+        return;
+    }
+    let Ok(_writing) = gate.enter() else { return };
+
+    if let Err(e) = sqlx::query(include_str!("queries/increment_local_metric.sql"))
+        .bind(today().format("%Y-%m-%d").to_string())
+        .bind(metric)
+        .bind(amount)
+        .execute(pool).await {
+        eprintln!("Error recording {} metric: {}", metric.as_str(), e);
+    }
+}
+
+/// Drops the days older than a year, or everything when the metrics are disabled. Run at startup.
+pub async fn tidy(app_handle: AppHandle) {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    tidy_with(&Preferences::load(&app_handle), &app_handle.state::<WriteGate>(), &pool).await;
+}
+
+async fn tidy_with(preferences: &Preferences, gate: &WriteGate, pool: &Pool<Sqlite>) {
+    let Ok(_writing) = gate.enter() else { return };
+
+    let result = if preferences.local_metrics {
+        let cutoff = today().checked_sub_months(Months::new(12)).unwrap_or_default();
+        sqlx::query(include_str!("queries/prune_local_metrics.sql"))
+            .bind(cutoff.format("%Y-%m-%d").to_string())
+            .execute(pool).await
+    } else {
+        sqlx::query(include_str!("queries/clear_local_metrics.sql")).execute(pool).await
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error tidying local metrics: {}", e);
+    }
+}
+
+pub async fn clear(pool: &Pool<Sqlite>) -> Result<(), String> {
+    sqlx::query(include_str!("queries/clear_local_metrics.sql"))
+        .execute(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::database::migrator::migrator;
+    use super::*;
+
+    const METRICS: [LocalMetric; 5] = [
+        LocalMetric::SyncsRun,
+        LocalMetric::ArticlesIngested,
+        LocalMetric::ArticlesRead,
+        LocalMetric::SessionSeconds,
+        LocalMetric::Searches,
+    ];
+
+    async fn fixture() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        pool
+    }
+
+    async fn recorded(pool: &Pool<Sqlite>) -> Vec<(String, i64)> {
+        sqlx::query_as("SELECT metric, value FROM local_metrics ORDER BY metric").fetch_all(pool).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn records_nothing_when_disabled() {
+        let pool = fixture().await;
+        let gate = WriteGate::default();
+
+        for metric in METRICS {
+            record_with(&Preferences::default(), &gate, &pool, metric, 3).await;
+        }
+        assert!(recorded(&pool).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn records_the_enabled_metrics() {
+        let pool = fixture().await;
+        let gate = WriteGate::default();
+        let enabled = Preferences { local_metrics: true, ..Preferences::default() };
+
+        record_with(&enabled, &gate, &pool, LocalMetric::Searches, 1).await;
+        record_with(&enabled, &gate, &pool, LocalMetric::Searches, 2).await;
+        record_with(&enabled, &gate, &pool, LocalMetric::ArticlesRead, 0).await;
+        // Frozen for a backup
+        gate.freeze(String::from("backup"));
+        record_with(&enabled, &gate, &pool, LocalMetric::SyncsRun, 1).await;
+        gate.thaw();
+
+        assert_eq!(recorded(&pool).await, [(String::from("searches"), 3)]);
+    }
+
+    #[tokio::test]
+    async fn disabling_wipes_the_metrics() {
+        let pool = fixture().await;
+        let gate = WriteGate::default();
+        let enabled = Preferences { local_metrics: true, ..Preferences::default() };
+        sqlx::query("INSERT INTO local_metrics (day, metric, value) VALUES ('2001-01-01', 'searches', 1)").execute(&pool).await.unwrap();
+        for metric in METRICS {
+            record_with(&enabled, &gate, &pool, metric, 1).await;
+        }
+
+        // The day older than a year goes
+        tidy_with(&enabled, &gate, &pool).await;
+        assert_eq!(recorded(&pool).await.len(), METRICS.len());
+
+        tidy_with(&Preferences::default(), &gate, &pool).await;
+        assert!(recorded(&pool).await.is_empty());
+    }
+}
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS feed_changes_feed;
+DROP TABLE IF EXISTS feed_changes;
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS feed_changes (
+    feed_identifier TEXT NOT NULL REFERENCES feeds(identifier) ON DELETE CASCADE,
+    field TEXT NOT NULL,
+    old_value TEXT DEFAULT NULL,
+    new_value TEXT DEFAULT NULL,
+    source TEXT NOT NULL,
+    changed_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS feed_changes_feed ON feed_changes(feed_identifier, changed_at);
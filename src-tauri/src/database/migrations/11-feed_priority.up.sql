@@ -0,0 +1 @@
+ALTER TABLE feeds ADD COLUMN priority TEXT NOT NULL DEFAULT 'normal';
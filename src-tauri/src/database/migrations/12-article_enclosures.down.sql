@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS article_transcripts;
+DROP TABLE IF EXISTS article_enclosures;
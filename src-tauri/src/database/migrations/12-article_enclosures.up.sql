@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS article_enclosures (
+    article_identifier TEXT NOT NULL REFERENCES articles(identifier) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    mime_type TEXT DEFAULT NULL,
+    role TEXT NOT NULL,
+    language TEXT DEFAULT NULL,
+    PRIMARY KEY (article_identifier, url)
+);
+
+CREATE TABLE IF NOT EXISTS article_transcripts (
+    article_identifier TEXT NOT NULL PRIMARY KEY REFERENCES articles(identifier) ON DELETE CASCADE,
+    format TEXT NOT NULL,
+    mime_type TEXT DEFAULT NULL,
+    segments TEXT DEFAULT NULL,
+    raw TEXT DEFAULT NULL,
+    fetched_at INTEGER NOT NULL
+);
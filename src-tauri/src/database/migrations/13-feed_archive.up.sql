@@ -0,0 +1 @@
+ALTER TABLE feeds ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,16 @@
+CREATE VIRTUAL TABLE IF NOT EXISTS article_search USING fts5(title, content, content='articles', content_rowid='rowid');
+
+INSERT INTO article_search(rowid, title, content) SELECT rowid, title, content FROM articles;
+
+CREATE TRIGGER IF NOT EXISTS article_search_insert AFTER INSERT ON articles BEGIN
+    INSERT INTO article_search(rowid, title, content) VALUES (new.rowid, new.title, new.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS article_search_delete AFTER DELETE ON articles BEGIN
+    INSERT INTO article_search(article_search, rowid, title, content) VALUES ('delete', old.rowid, old.title, old.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS article_search_update AFTER UPDATE OF title, content ON articles BEGIN
+    INSERT INTO article_search(article_search, rowid, title, content) VALUES ('delete', old.rowid, old.title, old.content);
+    INSERT INTO article_search(rowid, title, content) VALUES (new.rowid, new.title, new.content);
+END;
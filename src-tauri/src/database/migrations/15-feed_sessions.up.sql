@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS feed_sessions (
+    feed_identifier TEXT NOT NULL PRIMARY KEY REFERENCES feeds(identifier) ON DELETE CASCADE,
+    -- The values are in the keychain, see `secrets`
+    cookie_names TEXT NOT NULL DEFAULT '[]',
+    cookies_secret TEXT NOT NULL,
+    state TEXT NOT NULL DEFAULT 'new',
+    updated_at INTEGER NOT NULL
+);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS backfills (
+    name TEXT NOT NULL PRIMARY KEY,
+    last_rowid INTEGER NOT NULL DEFAULT 0,
+    done INTEGER NOT NULL DEFAULT 0,
+    updated_at INTEGER DEFAULT NULL
+);
+
+-- Articles stored before the dedup key existed still have none
+INSERT OR IGNORE INTO backfills (name) VALUES ('article_dedup_keys');
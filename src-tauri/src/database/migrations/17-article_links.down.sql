@@ -0,0 +1,2 @@
+DELETE FROM backfills WHERE name = 'article_links';
+DROP TABLE IF EXISTS article_links;
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS article_links (
+    article_identifier TEXT NOT NULL REFERENCES articles(identifier) ON DELETE CASCADE,
+    host TEXT NOT NULL,
+    path TEXT NOT NULL,
+    PRIMARY KEY (article_identifier, host, path)
+);
+
+CREATE INDEX IF NOT EXISTS article_links_host_path ON article_links(host, path);
+
+-- Links of the articles already stored
+INSERT OR IGNORE INTO backfills (name) VALUES ('article_links');
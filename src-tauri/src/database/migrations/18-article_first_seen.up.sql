@@ -0,0 +1,2 @@
+-- NULL for articles stored before, which fall back to their date
+ALTER TABLE articles ADD COLUMN first_seen INTEGER DEFAULT NULL;
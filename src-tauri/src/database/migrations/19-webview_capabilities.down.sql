@@ -0,0 +1 @@
+DROP TABLE IF EXISTS webview_capabilities;
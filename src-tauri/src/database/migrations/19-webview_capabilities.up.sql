@@ -0,0 +1,7 @@
+-- What the system webview reported it can decode, probed again after each update of the app
+CREATE TABLE IF NOT EXISTS webview_capabilities (
+    app_version TEXT PRIMARY KEY,
+    image_types TEXT NOT NULL,
+    audio_types TEXT NOT NULL,
+    probed_at INTEGER NOT NULL
+);
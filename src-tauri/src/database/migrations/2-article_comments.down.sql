@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS article_comments;
+DROP TABLE IF EXISTS watched_comments;
+ALTER TABLE articles DROP COLUMN comments_url;
@@ -0,0 +1,16 @@
+ALTER TABLE articles ADD COLUMN comments_url TEXT DEFAULT NULL;
+
+CREATE TABLE IF NOT EXISTS watched_comments (
+    article_identifier TEXT NOT NULL PRIMARY KEY REFERENCES articles(identifier) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    last_activity INTEGER NOT NULL,
+    last_updated INTEGER DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS article_comments (
+    identifier TEXT NOT NULL PRIMARY KEY,
+    article_identifier TEXT NOT NULL REFERENCES articles(identifier) ON DELETE CASCADE,
+    author TEXT DEFAULT NULL,
+    content TEXT NOT NULL,
+    date INTEGER NOT NULL
+);
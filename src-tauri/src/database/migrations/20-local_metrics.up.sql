@@ -0,0 +1,7 @@
+-- Daily counters about the app only, never about a given article or feed
+CREATE TABLE IF NOT EXISTS local_metrics (
+    day TEXT NOT NULL,
+    metric TEXT NOT NULL,
+    value INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, metric)
+);
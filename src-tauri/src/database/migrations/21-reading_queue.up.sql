@@ -0,0 +1,8 @@
+-- Articles to read next. Positions are fractional so moving one never renumbers the others
+CREATE TABLE IF NOT EXISTS reading_queue (
+    article_identifier TEXT PRIMARY KEY REFERENCES articles(identifier) ON DELETE CASCADE,
+    position REAL NOT NULL,
+    added_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS reading_queue_position ON reading_queue(position);
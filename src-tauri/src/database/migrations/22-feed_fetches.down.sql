@@ -0,0 +1,3 @@
+ALTER TABLE feeds DROP COLUMN unavailable_minutes;
+ALTER TABLE feeds DROP COLUMN unavailable_start;
+DROP TABLE IF EXISTS feed_fetches;
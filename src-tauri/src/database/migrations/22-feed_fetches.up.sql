@@ -0,0 +1,13 @@
+-- Outcome of the scheduled syncs of the last 30 days, with the local wall clock time to spot daily downtimes
+CREATE TABLE IF NOT EXISTS feed_fetches (
+    feed_identifier TEXT NOT NULL REFERENCES feeds(identifier) ON DELETE CASCADE,
+    fetched_at INTEGER NOT NULL,
+    local_date TEXT NOT NULL,
+    local_minute INTEGER NOT NULL,
+    success INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS feed_fetches_feed ON feed_fetches(feed_identifier, fetched_at);
+
+ALTER TABLE feeds ADD COLUMN unavailable_start INTEGER DEFAULT NULL;
+ALTER TABLE feeds ADD COLUMN unavailable_minutes INTEGER DEFAULT NULL;
@@ -0,0 +1,11 @@
+-- The oldest schema an app version must know to use this database. A migration older versions can't
+-- cope with, e.g. one dropping or repurposing a column, ends with:
+-- UPDATE schema_compatibility SET min_schema_version = <its version>, min_app_version = NULL;
+-- The app version is filled in at the next startup by the version that applied it.
+CREATE TABLE IF NOT EXISTS schema_compatibility (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    min_schema_version INTEGER NOT NULL DEFAULT 0,
+    min_app_version TEXT DEFAULT NULL
+);
+
+INSERT OR IGNORE INTO schema_compatibility (id) VALUES (1);
@@ -0,0 +1,4 @@
+-- Structured item elements feed-rs doesn't keep, and which of them the frontend shows for the feed
+ALTER TABLE feeds ADD COLUMN extras TEXT NOT NULL DEFAULT '{"namespaces":[],"displayed":[]}';
+
+ALTER TABLE articles ADD COLUMN extras TEXT NOT NULL DEFAULT '{}';
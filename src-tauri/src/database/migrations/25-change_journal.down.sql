@@ -0,0 +1,11 @@
+DROP TRIGGER IF EXISTS change_journal_queue_delete;
+DROP TRIGGER IF EXISTS change_journal_queue_update;
+DROP TRIGGER IF EXISTS change_journal_queue_insert;
+DROP TRIGGER IF EXISTS change_journal_feed_delete;
+DROP TRIGGER IF EXISTS change_journal_feed_update;
+DROP TRIGGER IF EXISTS change_journal_feed_insert;
+DROP TRIGGER IF EXISTS change_journal_article_delete;
+DROP TRIGGER IF EXISTS change_journal_article_update;
+DROP TRIGGER IF EXISTS change_journal_article_insert;
+DROP TABLE IF EXISTS change_acks;
+DROP TABLE IF EXISTS change_journal;
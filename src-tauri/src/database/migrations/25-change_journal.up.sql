@@ -0,0 +1,53 @@
+-- Every change to the rows the frontend shows, whoever made it, so a window that missed the events can
+-- catch up. The watermark of a window is the last change it applied
+CREATE TABLE IF NOT EXISTS change_journal (
+    watermark INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity TEXT NOT NULL,
+    identifier TEXT NOT NULL,
+    changed_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS change_journal_changed_at ON change_journal(changed_at);
+
+CREATE TABLE IF NOT EXISTS change_acks (
+    window_label TEXT PRIMARY KEY,
+    watermark INTEGER NOT NULL,
+    acked_at INTEGER NOT NULL
+);
+
+CREATE TRIGGER IF NOT EXISTS change_journal_article_insert AFTER INSERT ON articles BEGIN
+    INSERT INTO change_journal (entity, identifier, changed_at) VALUES ('article', new.identifier, unixepoch() * 1000);
+END;
+
+-- Backfilled columns like the dedup key aren't shown, and would flood the journal
+CREATE TRIGGER IF NOT EXISTS change_journal_article_update AFTER UPDATE OF title, content, date, read, image_url, score ON articles BEGIN
+    INSERT INTO change_journal (entity, identifier, changed_at) VALUES ('article', new.identifier, unixepoch() * 1000);
+END;
+
+CREATE TRIGGER IF NOT EXISTS change_journal_article_delete AFTER DELETE ON articles BEGIN
+    INSERT INTO change_journal (entity, identifier, changed_at) VALUES ('article', old.identifier, unixepoch() * 1000);
+END;
+
+CREATE TRIGGER IF NOT EXISTS change_journal_feed_insert AFTER INSERT ON feeds BEGIN
+    INSERT INTO change_journal (entity, identifier, changed_at) VALUES ('feed', new.identifier, unixepoch() * 1000);
+END;
+
+CREATE TRIGGER IF NOT EXISTS change_journal_feed_update AFTER UPDATE ON feeds BEGIN
+    INSERT INTO change_journal (entity, identifier, changed_at) VALUES ('feed', new.identifier, unixepoch() * 1000);
+END;
+
+CREATE TRIGGER IF NOT EXISTS change_journal_feed_delete AFTER DELETE ON feeds BEGIN
+    INSERT INTO change_journal (entity, identifier, changed_at) VALUES ('feed', old.identifier, unixepoch() * 1000);
+END;
+
+CREATE TRIGGER IF NOT EXISTS change_journal_queue_insert AFTER INSERT ON reading_queue BEGIN
+    INSERT INTO change_journal (entity, identifier, changed_at) VALUES ('reading_queue', new.article_identifier, unixepoch() * 1000);
+END;
+
+CREATE TRIGGER IF NOT EXISTS change_journal_queue_update AFTER UPDATE ON reading_queue BEGIN
+    INSERT INTO change_journal (entity, identifier, changed_at) VALUES ('reading_queue', new.article_identifier, unixepoch() * 1000);
+END;
+
+CREATE TRIGGER IF NOT EXISTS change_journal_queue_delete AFTER DELETE ON reading_queue BEGIN
+    INSERT INTO change_journal (entity, identifier, changed_at) VALUES ('reading_queue', old.article_identifier, unixepoch() * 1000);
+END;
@@ -0,0 +1,9 @@
+DROP TRIGGER IF EXISTS exchange_feed_delete;
+DROP TRIGGER IF EXISTS exchange_feed_move;
+DROP TRIGGER IF EXISTS exchange_feed_rename;
+DROP TRIGGER IF EXISTS exchange_feed_insert;
+DROP TRIGGER IF EXISTS exchange_article_arrival;
+DROP TRIGGER IF EXISTS exchange_article_read;
+DROP TABLE IF EXISTS exchange_peers;
+DROP TABLE IF EXISTS exchange_device;
+DROP TABLE IF EXISTS exchange_fields;
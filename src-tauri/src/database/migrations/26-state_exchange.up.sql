@@ -0,0 +1,96 @@
+-- Last known value of every field shared with the other devices, see database::state_exchange.
+-- Articles are keyed by feed URL and link, feeds by URL, as identifiers differ between devices
+CREATE TABLE IF NOT EXISTS exchange_fields (
+    entity TEXT NOT NULL,
+    key TEXT NOT NULL,
+    field TEXT NOT NULL,
+    -- NULL is a tombstone
+    value TEXT,
+    changed_at INTEGER NOT NULL,
+    -- NULL for local changes not exported yet
+    ulid TEXT,
+    -- The device of the change. Along with the time and the ULID, it orders the changes the same way on every device
+    origin TEXT NOT NULL,
+    PRIMARY KEY (entity, key, field)
+);
+
+CREATE INDEX IF NOT EXISTS exchange_fields_pending ON exchange_fields(ulid) WHERE ulid IS NULL;
+
+-- Only there once the exchange is enabled, the triggers record nothing before
+CREATE TABLE IF NOT EXISTS exchange_device (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    device_id TEXT NOT NULL
+);
+
+-- The files of other devices as last applied, so unchanged ones aren't read again
+CREATE TABLE IF NOT EXISTS exchange_peers (
+    file_name TEXT PRIMARY KEY,
+    modified_at INTEGER NOT NULL,
+    size INTEGER NOT NULL
+);
+
+-- A change equal to the known value is one applied from another device, and isn't recorded again.
+-- A local change always comes after the value it replaces, even when the clock of its device is late
+CREATE TRIGGER IF NOT EXISTS exchange_article_read AFTER UPDATE OF read ON articles
+WHEN old.read IS NOT new.read AND EXISTS (SELECT 1 FROM exchange_device) BEGIN
+    INSERT INTO exchange_fields (entity, key, field, value, changed_at, ulid, origin)
+    SELECT 'article', feeds.url || char(10) || COALESCE(new.link, new.title), 'read', new.read, unixepoch() * 1000, NULL, (SELECT device_id FROM exchange_device)
+    FROM feeds WHERE feeds.identifier = new.feed_identifier
+    ON CONFLICT (entity, key, field) DO UPDATE
+    SET value = excluded.value, changed_at = MAX(excluded.changed_at, exchange_fields.changed_at + 1), ulid = NULL, origin = excluded.origin
+    WHERE exchange_fields.value IS NOT excluded.value;
+END;
+
+-- Articles read on another device before being fetched here
+CREATE TRIGGER IF NOT EXISTS exchange_article_arrival AFTER INSERT ON articles
+WHEN EXISTS (SELECT 1 FROM exchange_device) BEGIN
+    UPDATE articles SET read = known.value
+    FROM (
+        SELECT exchange_fields.value FROM exchange_fields, feeds
+        WHERE feeds.identifier = new.feed_identifier
+          AND exchange_fields.entity = 'article'
+          AND exchange_fields.key = feeds.url || char(10) || COALESCE(new.link, new.title)
+          AND exchange_fields.field = 'read'
+    ) AS known
+    WHERE articles.identifier = new.identifier AND articles.read IS NOT known.value;
+END;
+
+CREATE TRIGGER IF NOT EXISTS exchange_feed_insert AFTER INSERT ON feeds
+WHEN EXISTS (SELECT 1 FROM exchange_device) BEGIN
+    INSERT INTO exchange_fields (entity, key, field, value, changed_at, ulid, origin)
+    VALUES ('feed', new.url, 'subscribed', '1', unixepoch() * 1000, NULL, (SELECT device_id FROM exchange_device)),
+           ('feed', new.url, 'display_name', new.display_name, unixepoch() * 1000, NULL, (SELECT device_id FROM exchange_device))
+    ON CONFLICT (entity, key, field) DO UPDATE
+    SET value = excluded.value, changed_at = MAX(excluded.changed_at, exchange_fields.changed_at + 1), ulid = NULL, origin = excluded.origin
+    WHERE exchange_fields.value IS NOT excluded.value;
+END;
+
+CREATE TRIGGER IF NOT EXISTS exchange_feed_rename AFTER UPDATE OF display_name ON feeds
+WHEN old.display_name IS NOT new.display_name AND EXISTS (SELECT 1 FROM exchange_device) BEGIN
+    INSERT INTO exchange_fields (entity, key, field, value, changed_at, ulid, origin)
+    VALUES ('feed', new.url, 'display_name', new.display_name, unixepoch() * 1000, NULL, (SELECT device_id FROM exchange_device))
+    ON CONFLICT (entity, key, field) DO UPDATE
+    SET value = excluded.value, changed_at = MAX(excluded.changed_at, exchange_fields.changed_at + 1), ulid = NULL, origin = excluded.origin
+    WHERE exchange_fields.value IS NOT excluded.value;
+END;
+
+-- Moving a feed is unsubscribing from the old URL and subscribing to the new one
+CREATE TRIGGER IF NOT EXISTS exchange_feed_move AFTER UPDATE OF url ON feeds
+WHEN old.url IS NOT new.url AND EXISTS (SELECT 1 FROM exchange_device) BEGIN
+    INSERT INTO exchange_fields (entity, key, field, value, changed_at, ulid, origin)
+    VALUES ('feed', old.url, 'subscribed', NULL, unixepoch() * 1000, NULL, (SELECT device_id FROM exchange_device)),
+           ('feed', new.url, 'subscribed', '1', unixepoch() * 1000, NULL, (SELECT device_id FROM exchange_device)),
+           ('feed', new.url, 'display_name', new.display_name, unixepoch() * 1000, NULL, (SELECT device_id FROM exchange_device))
+    ON CONFLICT (entity, key, field) DO UPDATE
+    SET value = excluded.value, changed_at = MAX(excluded.changed_at, exchange_fields.changed_at + 1), ulid = NULL, origin = excluded.origin
+    WHERE exchange_fields.value IS NOT excluded.value;
+END;
+
+CREATE TRIGGER IF NOT EXISTS exchange_feed_delete AFTER DELETE ON feeds
+WHEN EXISTS (SELECT 1 FROM exchange_device) BEGIN
+    INSERT INTO exchange_fields (entity, key, field, value, changed_at, ulid, origin)
+    VALUES ('feed', old.url, 'subscribed', NULL, unixepoch() * 1000, NULL, (SELECT device_id FROM exchange_device))
+    ON CONFLICT (entity, key, field) DO UPDATE
+    SET value = excluded.value, changed_at = MAX(excluded.changed_at, exchange_fields.changed_at + 1), ulid = NULL, origin = excluded.origin
+    WHERE exchange_fields.value IS NOT excluded.value;
+END;
@@ -0,0 +1,4 @@
+ALTER TABLE feeds DROP COLUMN html_url;
+ALTER TABLE feeds DROP COLUMN folder_identifier;
+DROP INDEX IF EXISTS folders_parent_name;
+DROP TABLE IF EXISTS folders;
@@ -0,0 +1,13 @@
+-- Folders of feeds, nested under their parent folder. Root folders have no parent
+CREATE TABLE IF NOT EXISTS folders (
+    identifier TEXT NOT NULL PRIMARY KEY,
+    name TEXT NOT NULL,
+    parent_identifier TEXT DEFAULT NULL REFERENCES folders(identifier) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS folders_parent_name ON folders(COALESCE(parent_identifier, ''), name);
+
+ALTER TABLE feeds ADD COLUMN folder_identifier TEXT DEFAULT NULL REFERENCES folders(identifier) ON DELETE SET NULL;
+
+-- The website of the feed, as given by OPML files
+ALTER TABLE feeds ADD COLUMN html_url TEXT DEFAULT NULL;
@@ -0,0 +1,2 @@
+-- 'auto' follows the address family of the preferences
+ALTER TABLE feeds ADD COLUMN address_family TEXT NOT NULL DEFAULT 'auto';
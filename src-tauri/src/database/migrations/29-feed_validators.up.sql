@@ -0,0 +1,3 @@
+-- Validators of the last fetched version of the feed, sent back to only download it again once it changed
+ALTER TABLE feeds ADD COLUMN etag TEXT DEFAULT NULL;
+ALTER TABLE feeds ADD COLUMN last_modified TEXT DEFAULT NULL;
@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS sync_queue (
+    feed_identifier TEXT NOT NULL PRIMARY KEY REFERENCES feeds(identifier) ON DELETE CASCADE,
+    position INTEGER NOT NULL
+);
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS articles_updated_since_read;
+ALTER TABLE feeds DROP COLUMN reread_on_update;
+ALTER TABLE articles DROP COLUMN reread_requested;
+ALTER TABLE articles DROP COLUMN content_updated_at;
+ALTER TABLE articles DROP COLUMN read_at;
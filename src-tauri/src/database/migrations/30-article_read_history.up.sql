@@ -0,0 +1,12 @@
+-- When the article was last read, kept when it is marked unread or updated afterwards
+ALTER TABLE articles ADD COLUMN read_at INTEGER DEFAULT NULL;
+-- When a sync last changed its title or content
+ALTER TABLE articles ADD COLUMN content_updated_at INTEGER DEFAULT NULL;
+-- Updated after being read in a feed asking for it, counted as unread until read again
+ALTER TABLE articles ADD COLUMN reread_requested INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE feeds ADD COLUMN reread_on_update INTEGER NOT NULL DEFAULT 0;
+
+-- When they were read is unknown, at least it was after they were first seen
+UPDATE articles SET read_at = COALESCE(first_seen, date) WHERE read = 1;
+
+CREATE INDEX IF NOT EXISTS articles_updated_since_read ON articles(feed_identifier) WHERE content_updated_at > read_at;
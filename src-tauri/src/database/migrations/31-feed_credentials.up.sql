@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS feed_credentials (
+    feed_identifier TEXT NOT NULL PRIMARY KEY REFERENCES feeds(identifier) ON DELETE CASCADE,
+    username TEXT,
+    -- The password and the values of the headers are in the keychain, see `secrets`
+    password_secret TEXT,
+    header_names TEXT NOT NULL DEFAULT '[]',
+    headers_secret TEXT,
+    updated_at INTEGER NOT NULL
+);
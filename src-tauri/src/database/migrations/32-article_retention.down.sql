@@ -0,0 +1,5 @@
+DROP TRIGGER IF EXISTS pruned_article_arrival;
+DROP INDEX IF EXISTS pruned_articles_feed;
+DROP TABLE IF EXISTS pruned_articles;
+ALTER TABLE feeds DROP COLUMN retention_max_articles;
+ALTER TABLE feeds DROP COLUMN retention_days;
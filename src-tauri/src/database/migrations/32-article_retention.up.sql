@@ -0,0 +1,18 @@
+-- NULL follows the preferences, 0 keeps every article
+ALTER TABLE feeds ADD COLUMN retention_days INTEGER DEFAULT NULL;
+ALTER TABLE feeds ADD COLUMN retention_max_articles INTEGER DEFAULT NULL;
+
+-- Articles deleted by a prune, so the next syncs don't bring them back as unread while the feed still carries them
+CREATE TABLE IF NOT EXISTS pruned_articles (
+    identifier TEXT NOT NULL PRIMARY KEY,
+    feed_identifier TEXT NOT NULL REFERENCES feeds(identifier) ON DELETE CASCADE,
+    dedup_key INTEGER,
+    pruned_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS pruned_articles_feed ON pruned_articles(feed_identifier);
+
+CREATE TRIGGER IF NOT EXISTS pruned_article_arrival BEFORE INSERT ON articles
+WHEN EXISTS (SELECT 1 FROM pruned_articles WHERE identifier = new.identifier) BEGIN
+    SELECT RAISE(IGNORE);
+END;
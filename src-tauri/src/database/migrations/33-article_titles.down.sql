@@ -0,0 +1,4 @@
+DELETE FROM backfills WHERE name = 'article_titles';
+UPDATE articles SET title = original_title WHERE original_title IS NOT NULL;
+ALTER TABLE feeds DROP COLUMN title_prefixes;
+ALTER TABLE articles DROP COLUMN original_title;
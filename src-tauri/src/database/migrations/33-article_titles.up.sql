@@ -0,0 +1,8 @@
+-- The title as the feed sent it, the title column holding the cleaned one. NULL for the articles
+-- stored before, until the article_titles backfill cleans them
+ALTER TABLE articles ADD COLUMN original_title TEXT DEFAULT NULL;
+-- JSON list of prefixes removed from the titles of the feed, like "[Sponsor]"
+ALTER TABLE feeds ADD COLUMN title_prefixes TEXT NOT NULL DEFAULT '[]';
+
+-- Cleans the titles already stored and recomputes their dedup keys from the cleaned titles
+INSERT OR IGNORE INTO backfills (name) VALUES ('article_titles');
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS feed_certificates (
+    feed_identifier TEXT NOT NULL PRIMARY KEY REFERENCES feeds(identifier) ON DELETE CASCADE,
+    client_certificate_path TEXT,
+    -- The passphrase of the client certificate is in the keychain, see `secrets`
+    client_certificate_secret TEXT,
+    ca_certificate_path TEXT,
+    updated_at INTEGER NOT NULL
+);
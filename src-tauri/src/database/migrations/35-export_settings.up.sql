@@ -0,0 +1,8 @@
+-- Where exports go when no path is given, the downloads folder while unset
+CREATE TABLE IF NOT EXISTS export_settings (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    default_directory TEXT DEFAULT NULL,
+    updated_at INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT OR IGNORE INTO export_settings (id) VALUES (1);
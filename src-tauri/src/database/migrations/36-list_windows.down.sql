@@ -0,0 +1,2 @@
+ALTER TABLE session_state DROP COLUMN list_filter;
+ALTER TABLE session_state DROP COLUMN list_title;
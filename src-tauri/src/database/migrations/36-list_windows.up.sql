@@ -0,0 +1,3 @@
+-- Set for the list windows, which are reopened with their filter by the next session
+ALTER TABLE session_state ADD COLUMN list_title TEXT DEFAULT NULL;
+ALTER TABLE session_state ADD COLUMN list_filter TEXT DEFAULT NULL;
@@ -0,0 +1,2 @@
+-- 'honest' sends the user agent of Alduin, 'browser' the one of a browser
+ALTER TABLE feeds ADD COLUMN user_agent TEXT NOT NULL DEFAULT 'honest';
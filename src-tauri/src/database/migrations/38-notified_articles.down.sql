@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS notified_articles_notified_at;
+DROP INDEX IF EXISTS notified_articles_feed_dedup_key;
+DROP TABLE IF EXISTS notified_articles;
@@ -0,0 +1,14 @@
+-- Articles already notified, so none is notified twice whatever restarts or goes unread in between
+CREATE TABLE IF NOT EXISTS notified_articles (
+    identifier TEXT NOT NULL PRIMARY KEY,
+    feed_identifier TEXT NOT NULL REFERENCES feeds(identifier) ON DELETE CASCADE,
+    dedup_key INTEGER,
+    -- As notified, for the history to outlive the article
+    title TEXT NOT NULL,
+    -- Last notified for an update, in a feed rereading its updated articles
+    updated INTEGER NOT NULL DEFAULT 0,
+    notified_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS notified_articles_feed_dedup_key ON notified_articles(feed_identifier, dedup_key);
+CREATE INDEX IF NOT EXISTS notified_articles_notified_at ON notified_articles(notified_at);
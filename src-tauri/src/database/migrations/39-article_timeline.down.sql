@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS articles_first_seen_timeline;
+DROP INDEX IF EXISTS articles_timeline;
@@ -0,0 +1,4 @@
+-- Scrolls the timeline from any position in either direction, the columns of the filters included so
+-- that pages of a filter excluding most articles are found in the index alone
+CREATE INDEX IF NOT EXISTS articles_timeline ON articles(date, identifier, read, reread_requested, score, feed_identifier);
+CREATE INDEX IF NOT EXISTS articles_first_seen_timeline ON articles(COALESCE(first_seen, date), date, identifier, read, reread_requested, score, feed_identifier);
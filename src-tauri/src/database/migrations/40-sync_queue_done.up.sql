@@ -0,0 +1,2 @@
+-- Set once the feed was synced, so a resumed run tells the feeds it reached apart from new ones
+ALTER TABLE sync_queue ADD COLUMN done INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,8 @@
+DROP TRIGGER IF EXISTS feed_sessions_deleted;
+DROP TRIGGER IF EXISTS feed_sessions_cookies_replaced;
+DROP TRIGGER IF EXISTS feed_certificates_deleted;
+DROP TRIGGER IF EXISTS feed_certificates_passphrase_replaced;
+DROP TRIGGER IF EXISTS feed_credentials_deleted;
+DROP TRIGGER IF EXISTS feed_credentials_headers_replaced;
+DROP TRIGGER IF EXISTS feed_credentials_password_replaced;
+DROP TABLE IF EXISTS forgotten_secrets;
@@ -0,0 +1,47 @@
+-- References of the secrets in the keychain no row uses anymore, removed from it by `secrets::forget_orphaned`.
+-- Filled by triggers, so feeds removed by the frontend or through a foreign key are covered too
+CREATE TABLE IF NOT EXISTS forgotten_secrets (
+    reference TEXT NOT NULL PRIMARY KEY
+);
+
+CREATE TRIGGER IF NOT EXISTS feed_credentials_password_replaced AFTER UPDATE OF password_secret ON feed_credentials
+WHEN old.password_secret IS NOT new.password_secret AND old.password_secret IS NOT NULL
+BEGIN
+    INSERT OR IGNORE INTO forgotten_secrets (reference) VALUES (old.password_secret);
+END;
+
+CREATE TRIGGER IF NOT EXISTS feed_credentials_headers_replaced AFTER UPDATE OF headers_secret ON feed_credentials
+WHEN old.headers_secret IS NOT new.headers_secret AND old.headers_secret IS NOT NULL
+BEGIN
+    INSERT OR IGNORE INTO forgotten_secrets (reference) VALUES (old.headers_secret);
+END;
+
+CREATE TRIGGER IF NOT EXISTS feed_credentials_deleted AFTER DELETE ON feed_credentials
+BEGIN
+    INSERT OR IGNORE INTO forgotten_secrets (reference)
+    SELECT reference FROM (SELECT old.password_secret AS reference UNION ALL SELECT old.headers_secret)
+    WHERE reference IS NOT NULL;
+END;
+
+CREATE TRIGGER IF NOT EXISTS feed_certificates_passphrase_replaced AFTER UPDATE OF client_certificate_secret ON feed_certificates
+WHEN old.client_certificate_secret IS NOT new.client_certificate_secret AND old.client_certificate_secret IS NOT NULL
+BEGIN
+    INSERT OR IGNORE INTO forgotten_secrets (reference) VALUES (old.client_certificate_secret);
+END;
+
+CREATE TRIGGER IF NOT EXISTS feed_certificates_deleted AFTER DELETE ON feed_certificates
+WHEN old.client_certificate_secret IS NOT NULL
+BEGIN
+    INSERT OR IGNORE INTO forgotten_secrets (reference) VALUES (old.client_certificate_secret);
+END;
+
+CREATE TRIGGER IF NOT EXISTS feed_sessions_cookies_replaced AFTER UPDATE OF cookies_secret ON feed_sessions
+WHEN old.cookies_secret IS NOT new.cookies_secret
+BEGIN
+    INSERT OR IGNORE INTO forgotten_secrets (reference) VALUES (old.cookies_secret);
+END;
+
+CREATE TRIGGER IF NOT EXISTS feed_sessions_deleted AFTER DELETE ON feed_sessions
+BEGIN
+    INSERT OR IGNORE INTO forgotten_secrets (reference) VALUES (old.cookies_secret);
+END;
@@ -0,0 +1,2 @@
+-- As told by the article, or else by its feed. Matched by the language conditions of the scoring rules
+ALTER TABLE articles ADD COLUMN language TEXT DEFAULT NULL;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS articles_feed_score;
+ALTER TABLE articles DROP COLUMN score;
+DROP TABLE IF EXISTS scoring_rules;
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS scoring_rules (
+    identifier TEXT NOT NULL PRIMARY KEY,
+    conditions TEXT NOT NULL,
+    weight INTEGER NOT NULL
+);
+
+ALTER TABLE articles ADD COLUMN score INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS articles_feed_score ON articles(feed_identifier, score);
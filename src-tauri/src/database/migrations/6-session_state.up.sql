@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS session_state (
+    window_label TEXT NOT NULL PRIMARY KEY,
+    feed_identifier TEXT DEFAULT NULL,
+    article_identifier TEXT DEFAULT NULL,
+    scroll_anchor TEXT DEFAULT NULL,
+    reader_scroll REAL DEFAULT NULL,
+    updated_at INTEGER NOT NULL
+);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS articles_feed_dedup_key;
+ALTER TABLE articles DROP COLUMN dedup_key;
+ALTER TABLE feeds DROP COLUMN dedup_strategy_locked;
+ALTER TABLE feeds DROP COLUMN dedup_strategy;
@@ -0,0 +1,6 @@
+ALTER TABLE feeds ADD COLUMN dedup_strategy TEXT NOT NULL DEFAULT 'guid';
+ALTER TABLE feeds ADD COLUMN dedup_strategy_locked INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE articles ADD COLUMN dedup_key INTEGER DEFAULT NULL;
+
+CREATE INDEX IF NOT EXISTS articles_feed_dedup_key ON articles(feed_identifier, dedup_key);
@@ -0,0 +1,2 @@
+ALTER TABLE articles DROP COLUMN post_processors;
+ALTER TABLE feeds DROP COLUMN post_processors;
@@ -0,0 +1,3 @@
+ALTER TABLE feeds ADD COLUMN post_processors TEXT NOT NULL DEFAULT '{}';
+
+ALTER TABLE articles ADD COLUMN post_processors TEXT NOT NULL DEFAULT '[]';
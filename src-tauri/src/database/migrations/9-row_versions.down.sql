@@ -0,0 +1,2 @@
+ALTER TABLE articles DROP COLUMN version;
+ALTER TABLE feeds DROP COLUMN version;
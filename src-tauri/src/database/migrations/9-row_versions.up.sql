@@ -0,0 +1,3 @@
+ALTER TABLE feeds ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE articles ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
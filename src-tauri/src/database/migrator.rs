@@ -0,0 +1,74 @@
+//! Owns the schema: the migrations of `load_migrations` are applied here, at startup, before the builder
+//! starts. The SQL plugin of the frontend is given none and the pools of the backend are opened after,
+//! so nothing else touches the database while it migrates.
+//!
+//! Each migration runs in a transaction with its bookkeeping, so a run dying midway leaves the database
+//! at the migration before, the next run applying the rest. Once done the schema version is checked
+//! against the one this version expects.
+
+use std::fs;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use sqlx::error::BoxDynError;
+use sqlx::migrate::{MigrateError, Migration, MigrationSource, Migrator};
+use sqlx::{ConnectOptions, Connection};
+use crate::database::{connect_options, latest_migration, load_migrations};
+use crate::enums::schema_failure::SchemaFailure;
+use crate::structs::schema_check::SchemaCheck;
+
+#[derive(Debug)]
+struct EmbeddedMigrations;
+
+impl MigrationSource<'static> for EmbeddedMigrations {
+    fn resolve(self) -> Pin<Box<dyn Future<Output = Result<Vec<Migration>, BoxDynError>> + Send + 'static>> {
+        Box::pin(async { Ok(load_migrations()) })
+    }
+}
+
+pub async fn migrator() -> Result<Migrator, String> {
+    Migrator::new(EmbeddedMigrations).await
+        .map_err(|e| format!("Error loading the migrations: {}", e))
+}
+
+/// Creates the database when missing and applies the migrations `check` allows, then makes sure the
+/// database reports the schema this version expects. Returns the schema version.
+pub async fn migrate(database_file: &Path, check: &SchemaCheck) -> Result<i64, SchemaFailure> {
+    // Only read by this version, which doesn't know its schema
+    if let Some(mismatch) = &check.mismatch {
+        return Ok(mismatch.schema_version);
+    }
+
+    if let Some(directory) = database_file.parent() {
+        fs::create_dir_all(directory).map_err(|e| SchemaFailure::Open { message: format!("Error creating {}: {}", directory.display(), e) })?;
+    }
+    let mut connection = connect_options(database_file, false)
+        .create_if_missing(true)
+        .connect().await
+        .map_err(|e| SchemaFailure::Open { message: e.to_string() })?;
+
+    if check.run_migrations {
+        let migrator = migrator().await.map_err(|message| SchemaFailure::Open { message })?;
+        migrator.run(&mut connection).await.map_err(|e| match e {
+            MigrateError::ExecuteMigration(e, version) => SchemaFailure::Migration { version, message: e.to_string() },
+            MigrateError::Dirty(version) => SchemaFailure::Interrupted { version },
+            MigrateError::VersionMismatch(version) | MigrateError::VersionMissing(version) => SchemaFailure::Modified { version },
+            e => SchemaFailure::Migration { version: 0, message: e.to_string() },
+        })?;
+    }
+
+    let applied = sqlx::query_scalar::<_, i64>(include_str!("queries/get_schema_version.sql"))
+        .fetch_one(&mut connection).await
+        .map_err(|e| SchemaFailure::Open { message: format!("Error executing query: {}", e) })?;
+    if let Err(e) = connection.close().await {
+        eprintln!("Error closing the migration connection: {}", e);
+    }
+
+    // Newer migrations the check deemed harmless are left for the version that applied them
+    let expected = latest_migration();
+    match check.run_migrations {
+        true if applied != expected => Err(SchemaFailure::VersionMismatch { applied, expected }),
+        false if applied < expected => Err(SchemaFailure::VersionMismatch { applied, expected }),
+        _ => Ok(applied),
+    }
+}
@@ -1,7 +1,25 @@
+pub mod health;
+pub mod backfill;
+pub mod local_metrics;
+pub mod compatibility;
+pub mod change_journal;
+pub mod state_exchange;
+pub mod article_writer;
+pub mod migrator;
+pub mod notification_ledger;
+
+use std::borrow::Cow;
+use std::path::Path;
+use std::time::Duration;
 use include_dir::{include_dir, Dir};
-use tauri_plugin_sql::{Migration, MigrationKind};
+use sqlx::migrate::{Migration, MigrationType};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
 use itertools::Itertools;
 
+/// Of the read-only pool, enough for the windows loading their lists at the same time.
+pub const READ_CONNECTIONS: u32 = 4;
+
+/// Every migration, up and down, sorted by version. Only `migrator` applies them.
 pub fn load_migrations() -> Vec<Migration> {
     static MIGRATIONS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/src/database/migrations");
 
@@ -16,13 +34,48 @@ pub fn load_migrations() -> Vec<Migration> {
             .flat_map(|part| part.split("-"))
             .next_tuple().unwrap();
 
-        migrations.push(Migration {
-            version: version.parse::<i64>().expect("version should be a valid integer"),
-            description,
-            sql,
-            kind: match kind { "up" => MigrationKind::Up, "down" => MigrationKind::Down, &_ => MigrationKind::Up},
-        });
+        migrations.push(Migration::new(
+            version.parse::<i64>().expect("version should be a valid integer"),
+            Cow::Borrowed(description),
+            match kind { "down" => MigrationType::ReversibleDown, &_ => MigrationType::ReversibleUp },
+            Cow::Borrowed(sql),
+            false,
+        ));
+    }
+
+    migrations.sort_by_key(|migration| (migration.version, migration.migration_type.is_down_migration()));
+    migrations
+}
+
+/// The last migration this version of the app knows.
+pub fn latest_migration() -> i64 {
+    load_migrations().iter().map(|migration| migration.version).max().unwrap_or(0)
+}
+
+/// WAL lets the reads go on while a sync writes. The log is only checkpointed at idle moments, see
+/// `health::checkpoint`, rather than by whichever commit fills it up. Read-only connections keep the
+/// journal mode the writing ones set.
+pub fn connect_options(path: &Path, read_only: bool) -> SqliteConnectOptions {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .read_only(read_only);
+    if read_only {
+        return options;
     }
 
-    return migrations;
+    options
+        .journal_mode(SqliteJournalMode::Wal)
+        .synchronous(SqliteSynchronous::Normal)
+        .pragma("wal_autocheckpoint", "0")
+}
+
+/// Connections are pinged before use and stale or pre-replacement ones reopened,
+/// so the first command after a long idle period never gets a closed connection.
+pub fn pool_options() -> SqlitePoolOptions {
+    SqlitePoolOptions::new()
+        .min_connections(1)
+        .test_before_acquire(true)
+        .idle_timeout(Duration::from_secs(10 * 60))
+        .acquire_timeout(Duration::from_secs(10))
+        .before_acquire(|_, metadata| Box::pin(async move { Ok(health::is_current(&metadata)) }))
 }
@@ -0,0 +1,348 @@
+//! Notifies the articles found by the background syncs, each once.
+//!
+//! Every article is recorded in `notified_articles` before its notification is shown, so restarting
+//! after a sync, marking it unread again or storing it again under another identifier with the same dedup
+//! key doesn't notify it again. Only the feeds rereading their updated articles notify them again, once per
+//! update. The records outlive the articles for the history of `get_recent_notifications`, and are dropped
+//! after a month, long after the day new articles can be notified within. The notifications themselves
+//! go through the `NotificationLimiter`, so a feed republishing its whole history shows one of them.
+
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::enums::notification_notice::NotificationNotice;
+use crate::structs::notification_candidate::NotificationCandidate;
+use crate::structs::notification_limiter::NotificationLimiter;
+use crate::structs::notified_article::NotifiedArticle;
+use crate::structs::preferences::Preferences;
+use crate::structs::write_gate::WriteGate;
+use crate::utils::notification::notify;
+
+/// Articles first seen earlier aren't notified, e.g. when the preference is turned on
+const NEW_ARTICLE_MS: i64 = 24 * 60 * 60 * 1000;
+const RETENTION_MS: i64 = 30 * 24 * 60 * 60 * 1000;
+/// Titles named by a notification about several articles, the others being counted
+const MAX_TITLES: usize = 3;
+
+/// Shows a notification per feed with articles not notified yet, when the `notifyNewArticles` preference
+/// is on, as far as the limiter lets them through. Failures are only logged, like those of the syncs
+/// running it.
+pub async fn notify_new_articles(app_handle: &AppHandle) {
+    let preferences = Preferences::load(app_handle);
+    if !preferences.notify_new_articles {
+        return;
+    }
+    let Ok(_writing) = app_handle.state::<WriteGate>().enter() else { return };
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let now = chrono::Utc::now().timestamp_millis();
+    match take_candidates(&pool, now).await {
+        Ok(candidates) => {
+            let window_ms = preferences.notification_window_minutes as i64 * 60 * 1000;
+            for notice in app_handle.state::<NotificationLimiter>().admit(candidates, window_ms, now) {
+                notify(app_handle, &notification_body(&notice));
+            }
+        }
+        Err(e) => eprintln!("Error recording notified articles: {}", e),
+    }
+}
+
+/// The articles to notify, recorded as notified at `now` in the same transaction.
+pub async fn take_candidates(pool: &Pool<Sqlite>, now: i64) -> Result<Vec<NotificationCandidate>, String> {
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+
+    let candidates = sqlx::query_as::<_, NotificationCandidate>(include_str!("queries/get_notification_candidates.sql"))
+        .bind(now - NEW_ARTICLE_MS)
+        .fetch_all(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    for candidate in &candidates {
+        sqlx::query(include_str!("queries/insert_notified_article.sql"))
+            .bind(&candidate.identifier)
+            .bind(&candidate.feed_identifier)
+            .bind(candidate.dedup_key)
+            .bind(&candidate.title)
+            .bind(candidate.updated)
+            .bind(now)
+            .execute(&mut *transaction).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+    }
+
+    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))?;
+
+    Ok(candidates)
+}
+
+pub async fn recent_notifications(limit: i64, pool: &Pool<Sqlite>) -> Result<Vec<NotifiedArticle>, String> {
+    sqlx::query_as::<_, NotifiedArticle>(include_str!("queries/get_recent_notifications.sql"))
+        .bind(limit)
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// Drops the records older than a month. Run at startup.
+pub async fn tidy(app_handle: AppHandle) {
+    let Ok(_writing) = app_handle.state::<WriteGate>().enter() else { return };
+
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    if let Err(e) = sqlx::query(include_str!("queries/prune_notified_articles.sql"))
+        .bind(RETENTION_MS)
+        .execute(&*pool).await {
+        eprintln!("Error pruning the notified articles: {}", e);
+    }
+}
+
+/// Names the feed and the titles of its most recent articles, the others being counted.
+fn notification_body(notice: &NotificationNotice) -> String {
+    let (feed_name, candidates) = match notice {
+        NotificationNotice::Summary { articles, feeds } => {
+            return format!("Lots of new activity: {} new or updated articles in {} feeds", articles, feeds);
+        }
+        NotificationNotice::Feed { feed_name, candidates, .. } => (feed_name, candidates),
+    };
+    let articles = notice.articles();
+
+    match candidates.as_slice() {
+        [] => return format!("{} more {} in {}", articles, if articles == 1 { "article" } else { "articles" }, feed_name),
+        [candidate] if articles == 1 => {
+            let kind = if candidate.updated { "Updated" } else { "New" };
+            return format!("{} in {}: {}", kind, feed_name, candidate.title);
+        }
+        _ => {}
+    }
+
+    let kind = match candidates.iter().filter(|candidate| candidate.updated).count() {
+        0 => "new articles",
+        updated if updated == candidates.len() => "updated articles",
+        _ => "new or updated articles",
+    };
+    let titles: Vec<&str> = candidates.iter().take(MAX_TITLES).map(|candidate| candidate.title.as_str()).collect();
+
+    match articles - titles.len() {
+        0 => format!("{} {} in {}: {}", articles, kind, feed_name, titles.join(", ")),
+        more => format!("{} {} in {}: {} and {} more", articles, kind, feed_name, titles.join(", "), more),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::Path;
+    use std::time::Duration;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::benchmark::random::SeededRandom;
+    use crate::commands::articles::mark_read;
+    use crate::commands::fetcher::prepare_article;
+    use crate::database::article_writer::insert_article;
+    use crate::database::connect_options;
+    use crate::database::migrator::migrator;
+    use crate::structs::article::Article;
+    use crate::utils::dedup::dedup_key;
+    use super::*;
+
+    const FEEDS: &str = "INSERT INTO feeds (identifier, display_name, url, interval, reread_on_update) VALUES
+        ('news', 'News', '', 60, 0), ('docs', 'Docs', '', 60, 1)";
+
+    /// Opened as at startup, the ledger kept in the file across restarts.
+    async fn open(path: &Path) -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new().max_connections(1)
+            .connect_with(connect_options(path, false).create_if_missing(true)).await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        pool
+    }
+
+    async fn restart(pool: Pool<Sqlite>, path: &Path) -> Pool<Sqlite> {
+        pool.close().await;
+        open(path).await
+    }
+
+    /// Stores the article as a sync does, a later write of the same guid updating its content.
+    async fn sync(feed: &str, guid: &str, title: &str, content: &str, pool: &Pool<Sqlite>) -> String {
+        let link = format!("https://{}.example/{}", feed, title.to_lowercase().replace(' ', "-"));
+        let article = Article {
+            id: guid.to_string(),
+            title: title.to_string(),
+            original_title: title.to_string(),
+            content: content.to_string(),
+            date: 0,
+            read: false,
+            image: None,
+            comments_url: None,
+            link: Some(link.clone()),
+            language: None,
+            enclosures: vec![],
+            post_processors: vec![],
+            extras: BTreeMap::new(),
+        };
+        let prepared = prepare_article(feed, article, dedup_key(Some(&link), title), &[], &[]);
+        let identifier = prepared.identifier.to_string();
+        insert_article(feed, prepared, &mut pool.acquire().await.unwrap()).await.unwrap();
+        identifier
+    }
+
+    /// The articles notified by a background sync, as (identifier, updated).
+    async fn notified(pool: &Pool<Sqlite>) -> Vec<(String, bool)> {
+        // Updates are told apart from notifications by milliseconds
+        tokio::time::sleep(Duration::from_millis(3)).await;
+        let now = chrono::Utc::now().timestamp_millis();
+        let notified = take_candidates(pool, now).await.unwrap()
+            .into_iter()
+            .map(|candidate| (candidate.identifier, candidate.updated))
+            .collect();
+        tokio::time::sleep(Duration::from_millis(3)).await;
+        notified
+    }
+
+    #[tokio::test]
+    async fn notifies_each_article_once_across_restarts() {
+        let path = std::env::temp_dir().join(format!("alduin-notification-ledger-{}.db", std::process::id()));
+        let mut pool = open(&path).await;
+        sqlx::query(FEEDS).execute(&pool).await.unwrap();
+
+        let first = sync("news", "1", "First", "", &pool).await;
+        let second = sync("news", "2", "Second", "", &pool).await;
+        let mut expected = vec![(first.clone(), false), (second.clone(), false)];
+        expected.sort();
+        let mut found = notified(&pool).await;
+        found.sort();
+        assert_eq!(found, expected);
+
+        // Restarted right after the sync, the next one finds the same articles again
+        pool = restart(pool, &path).await;
+        sync("news", "1", "First", "", &pool).await;
+        sync("news", "2", "Second", "", &pool).await;
+        let third = sync("news", "3", "Third", "", &pool).await;
+        assert_eq!(notified(&pool).await, [(third.clone(), false)]);
+        assert_eq!(notified(&pool).await, []);
+
+        // Read and marked unread again
+        mark_read(&first, true, &pool).await.unwrap();
+        mark_read(&first, false, &pool).await.unwrap();
+        pool = restart(pool, &path).await;
+        assert_eq!(notified(&pool).await, []);
+
+        // Stored again under another guid, with the same link and title
+        sync("news", "1-rotated", "First", "", &pool).await;
+        assert_eq!(notified(&pool).await, []);
+
+        // Edited, in a feed not rereading its updates
+        sync("news", "2", "Second", "Edited", &pool).await;
+        assert_eq!(notified(&pool).await, []);
+
+        // A feed rereading them notifies each update once
+        let doc = sync("docs", "1", "Guide", "", &pool).await;
+        assert_eq!(notified(&pool).await, [(doc.clone(), false)]);
+        sync("docs", "1", "Guide", "Edited", &pool).await;
+        pool = restart(pool, &path).await;
+        assert_eq!(notified(&pool).await, [(doc.clone(), true)]);
+        assert_eq!(notified(&pool).await, []);
+        mark_read(&doc, true, &pool).await.unwrap();
+        sync("docs", "1", "Guide", "Edited again", &pool).await;
+        assert_eq!(notified(&pool).await, [(doc.clone(), true)]);
+        pool = restart(pool, &path).await;
+        assert_eq!(notified(&pool).await, []);
+
+        let history: Vec<(String, bool)> = recent_notifications(10, &pool).await.unwrap()
+            .into_iter()
+            .map(|article| (article.article_identifier, article.updated))
+            .collect();
+        assert_eq!(history, [(doc, true), (third, false), (second, false), (first, false)]);
+
+        pool.close().await;
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[tokio::test]
+    async fn restarts_between_syncs_never_notify_twice() {
+        let path = std::env::temp_dir().join(format!("alduin-notification-restarts-{}.db", std::process::id()));
+
+        for seed in 0..10u32 {
+            let mut random = SeededRandom::new(seed);
+            let mut pool = open(&path).await;
+            sqlx::query(FEEDS).execute(&pool).await.unwrap();
+            // Of each article, the notifications and the content updates of the feed rereading them
+            let mut notifications: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+            let mut stored: BTreeMap<(&str, u64), String> = BTreeMap::new();
+            let mut updates: BTreeMap<(&str, u64), u64> = BTreeMap::new();
+
+            for _ in 0..40 {
+                match random.below(6) {
+                    0 => pool = restart(pool, &path).await,
+                    1 | 2 => {
+                        // A sync finds some articles seen before, and sometimes a new one
+                        let feed = if random.below(2) == 0 { "news" } else { "docs" };
+                        for guid in 0..random.between(1, 6) {
+                            let version = *updates.get(&(feed, guid)).unwrap_or(&0);
+                            let identifier = sync(feed, &guid.to_string(), &format!("Article {}", guid), &version.to_string(), &pool).await;
+                            stored.insert((feed, guid), identifier);
+                        }
+                    }
+                    3 if !stored.is_empty() => {
+                        let (feed, guid) = *stored.keys().nth(random.below(stored.len() as u64) as usize).unwrap();
+                        let version = updates.entry((feed, guid)).or_default();
+                        *version += 1;
+                        let identifier = sync(feed, &guid.to_string(), &format!("Article {}", guid), &version.to_string(), &pool).await;
+                        if feed == "docs" {
+                            notifications.entry(identifier).or_default().1 += 1;
+                        }
+                    }
+                    4 if !stored.is_empty() => {
+                        let identifier = stored.values().nth(random.below(stored.len() as u64) as usize).unwrap();
+                        mark_read(identifier, random.below(2) == 0, &pool).await.unwrap();
+                    }
+                    _ => {
+                        for (identifier, updated) in notified(&pool).await {
+                            let (count, _) = notifications.entry(identifier).or_default();
+                            *count += 1;
+                            assert!(updated || *count == 1, "seed {}", seed);
+                        }
+                    }
+                }
+            }
+
+            for (identifier, (count, updates)) in notifications {
+                // Notified when new, then at most once per update
+                assert!(count <= 1 + updates, "seed {}: {} notified {} times for {} updates", seed, identifier, count, updates);
+            }
+            pool.close().await;
+            for suffix in ["", "-wal", "-shm"] {
+                let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+            }
+        }
+    }
+
+    /// A feed notice with articles as (title, updated)
+    fn feed(articles: &[(&str, bool)], held_back: usize) -> NotificationNotice {
+        let candidates = articles.iter()
+            .map(|(title, updated)| NotificationCandidate {
+                identifier: title.to_string(),
+                feed_identifier: String::from("feed"),
+                dedup_key: None,
+                title: title.to_string(),
+                feed_name: String::from("Feed"),
+                updated: *updated,
+            })
+            .collect();
+        NotificationNotice::Feed { feed_name: String::from("Feed"), candidates, held_back }
+    }
+
+    #[test]
+    fn bodies() {
+        let cases = [
+            (feed(&[("One", false)], 0), "New in Feed: One"),
+            (feed(&[("One", true)], 0), "Updated in Feed: One"),
+            (feed(&[("One", false)], 4), "5 new articles in Feed: One and 4 more"),
+            (feed(&[("One", false), ("Two", false)], 0), "2 new articles in Feed: One, Two"),
+            (feed(&[("One", true), ("Two", true)], 0), "2 updated articles in Feed: One, Two"),
+            (feed(&[("One", false), ("Two", true), ("Three", false), ("Four", false)], 0), "4 new or updated articles in Feed: One, Two, Three and 1 more"),
+            (feed(&[], 1), "1 more article in Feed"),
+            (feed(&[], 12), "12 more articles in Feed"),
+            (NotificationNotice::Summary { articles: 500, feeds: 6 }, "Lots of new activity: 500 new or updated articles in 6 feeds"),
+        ];
+
+        for (notice, body) in cases {
+            assert_eq!(notification_body(&notice), body, "{:?}", notice);
+        }
+    }
+}
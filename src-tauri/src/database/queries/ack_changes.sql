@@ -0,0 +1,3 @@
+INSERT INTO change_acks (window_label, watermark, acked_at)
+VALUES (?1, ?2, unixepoch() * 1000)
+ON CONFLICT (window_label) DO UPDATE SET watermark = MAX(watermark, excluded.watermark), acked_at = excluded.acked_at
@@ -0,0 +1,3 @@
+UPDATE feeds
+SET display_name = ?1, version = version + 1
+WHERE url = ?2 AND display_name IS NOT ?1
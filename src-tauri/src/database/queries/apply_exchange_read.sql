@@ -0,0 +1,8 @@
+UPDATE articles
+SET read = ?1,
+    read_at = CASE WHEN ?1 THEN CAST(unixepoch('subsec') * 1000 AS INTEGER) ELSE read_at END,
+    reread_requested = CASE WHEN ?1 THEN 0 ELSE reread_requested END,
+    version = version + 1
+WHERE feed_identifier IN (SELECT identifier FROM feeds WHERE url = ?2)
+  AND COALESCE(link, title) = ?3
+  AND read IS NOT ?1
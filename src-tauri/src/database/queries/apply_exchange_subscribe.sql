@@ -0,0 +1,3 @@
+INSERT INTO feeds (identifier, display_name, url, interval)
+SELECT ?1, COALESCE((SELECT value FROM exchange_fields WHERE entity = 'feed' AND key = ?2 AND field = 'display_name'), ?2), ?2, 5
+WHERE NOT EXISTS (SELECT 1 FROM feeds WHERE url = ?2)
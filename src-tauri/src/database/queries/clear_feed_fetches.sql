@@ -0,0 +1,2 @@
+DELETE FROM feed_fetches
+WHERE feed_identifier = ?
@@ -0,0 +1 @@
+DELETE FROM local_metrics
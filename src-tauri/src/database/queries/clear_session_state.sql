@@ -0,0 +1 @@
+DELETE FROM session_state
@@ -0,0 +1 @@
+UPDATE sync_queue SET done = 1 WHERE feed_identifier = ?
@@ -0,0 +1,13 @@
+-- The articles get_articles returns for the same filter before the one with the sort key ?4, ?5, ?6,
+-- all of them when it is NULL
+SELECT COUNT(*)
+FROM articles
+WHERE (?1 IS NULL OR feed_identifier = ?1)
+  AND (?2 IS NULL OR score >= ?2)
+  AND (?4 IS NULL OR (CASE ?3 WHEN 'score' THEN score WHEN 'first_seen' THEN COALESCE(first_seen, date) ELSE 0 END, date, identifier) > (?4, ?5, ?6))
+  AND CASE ?7
+      WHEN 'unread' THEN read = 0 OR reread_requested = 1
+      WHEN 'read' THEN read = 1 AND reread_requested = 0
+      WHEN 'updated_since_read' THEN content_updated_at > read_at
+      ELSE 1
+  END
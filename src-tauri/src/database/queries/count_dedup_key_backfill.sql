@@ -0,0 +1,3 @@
+SELECT count(*)
+FROM articles
+WHERE rowid > ? AND dedup_key IS NULL
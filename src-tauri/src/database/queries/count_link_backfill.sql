@@ -0,0 +1,3 @@
+SELECT count(*)
+FROM articles
+WHERE rowid > ?
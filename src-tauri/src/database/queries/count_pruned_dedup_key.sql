@@ -0,0 +1,3 @@
+SELECT COUNT(*)
+FROM pruned_articles
+WHERE feed_identifier = ? AND dedup_key = ?
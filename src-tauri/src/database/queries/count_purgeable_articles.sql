@@ -0,0 +1,6 @@
+SELECT feed_identifier, COUNT(*) AS articles, COALESCE(SUM(length(content)), 0) AS content_bytes
+FROM articles
+WHERE read = 1
+  AND date < ?
+  AND NOT EXISTS (SELECT 1 FROM reading_queue WHERE article_identifier = articles.identifier)
+GROUP BY feed_identifier
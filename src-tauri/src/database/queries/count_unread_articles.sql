@@ -0,0 +1,5 @@
+SELECT feeds.identifier, COUNT(CASE WHEN articles.read = 0 OR articles.reread_requested = 1 THEN 1 END) AS unread
+FROM feeds
+LEFT JOIN articles ON feeds.identifier = articles.feed_identifier
+GROUP BY feeds.identifier
+ORDER BY feeds.rowid ASC
@@ -0,0 +1,5 @@
+-- Only reads the articles_updated_since_read partial index
+SELECT feed_identifier, COUNT(*) AS count
+FROM articles
+WHERE content_updated_at > read_at
+GROUP BY feed_identifier
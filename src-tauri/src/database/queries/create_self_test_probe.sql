@@ -0,0 +1 @@
+CREATE TEMP TABLE IF NOT EXISTS self_test_probe (written_at INTEGER NOT NULL)
@@ -0,0 +1 @@
+DELETE FROM article_comments WHERE article_identifier = ?
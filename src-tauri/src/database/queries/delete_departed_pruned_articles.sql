@@ -0,0 +1,5 @@
+-- The feed doesn't carry these pruned articles anymore, so it can't deliver them again
+DELETE FROM pruned_articles
+WHERE feed_identifier = ?1
+  AND identifier NOT IN (SELECT value FROM json_each(?2))
+  AND pruned_at < unixepoch() * 1000 - ?3
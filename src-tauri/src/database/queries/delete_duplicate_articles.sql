@@ -0,0 +1,8 @@
+-- Keeps the first copy that was inserted
+DELETE FROM articles
+WHERE feed_identifier = ?1
+  AND dedup_key IS NOT NULL
+  AND EXISTS (
+      SELECT 1 FROM articles AS other
+      WHERE other.feed_identifier = ?1 AND other.dedup_key = articles.dedup_key AND other.rowid < articles.rowid
+  )
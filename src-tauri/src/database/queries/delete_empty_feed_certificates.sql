@@ -0,0 +1,2 @@
+DELETE FROM feed_certificates
+WHERE feed_identifier = ? AND client_certificate_path IS NULL AND ca_certificate_path IS NULL
@@ -0,0 +1,2 @@
+DELETE FROM feed_credentials
+WHERE feed_identifier = ? AND username IS NULL AND header_names = '[]'
@@ -0,0 +1,2 @@
+DELETE FROM feeds
+WHERE identifier = ?
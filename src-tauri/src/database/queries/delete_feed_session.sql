@@ -0,0 +1 @@
+DELETE FROM feed_sessions WHERE feed_identifier = ?
@@ -0,0 +1 @@
+DELETE FROM forgotten_secrets WHERE reference = ?
@@ -0,0 +1 @@
+DELETE FROM session_state WHERE window_label = ? AND list_filter IS NOT NULL
@@ -0,0 +1,4 @@
+-- Queued since the candidates were listed
+DELETE FROM articles
+WHERE identifier IN (SELECT value FROM json_each(?))
+  AND NOT EXISTS (SELECT 1 FROM reading_queue WHERE reading_queue.article_identifier = articles.identifier)
@@ -0,0 +1 @@
+DELETE FROM scoring_rules WHERE identifier = ?
@@ -0,0 +1,2 @@
+DELETE FROM reading_queue
+WHERE article_identifier = ?
@@ -0,0 +1,2 @@
+DELETE FROM feed_changes
+WHERE changed_at < (unixepoch() - 365 * 24 * 60 * 60) * 1000
@@ -0,0 +1,2 @@
+DELETE FROM watched_comments
+WHERE last_activity < (unixepoch() - 30 * 24 * 60 * 60) * 1000
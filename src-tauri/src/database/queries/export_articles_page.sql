@@ -0,0 +1,5 @@
+SELECT rowid, identifier, feed_identifier, title, content, date, read, image_url, comments_url, link, score, first_seen
+FROM articles
+WHERE rowid > ?
+ORDER BY rowid
+LIMIT ?
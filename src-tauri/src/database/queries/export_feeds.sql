@@ -0,0 +1,4 @@
+SELECT identifier, display_name, url, interval, priority, archived, dedup_strategy, dedup_strategy_locked, folder_identifier,
+       html_url, address_family, user_agent, reread_on_update, retention_days, retention_max_articles, title_prefixes
+FROM feeds
+ORDER BY rowid
@@ -0,0 +1,3 @@
+SELECT article_identifier, position, added_at
+FROM reading_queue
+ORDER BY position
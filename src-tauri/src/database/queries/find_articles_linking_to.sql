@@ -0,0 +1,10 @@
+SELECT identifier, feed_identifier, title, content, date, read, image_url, score
+FROM articles
+WHERE identifier IN (
+    SELECT article_identifier
+    FROM article_links
+    WHERE (?2 IS NULL AND (host = ?1 OR host LIKE '%.' || ?1)) OR (host = ?1 AND path = ?2)
+)
+  AND (?3 IS NULL OR (date, identifier) < (?3, ?4))
+ORDER BY date DESC, identifier DESC
+LIMIT ?5
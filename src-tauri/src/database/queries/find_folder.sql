@@ -0,0 +1,3 @@
+SELECT identifier
+FROM folders
+WHERE name = ? AND parent_identifier IS ?
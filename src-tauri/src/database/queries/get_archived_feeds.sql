@@ -0,0 +1,3 @@
+SELECT identifier
+FROM feeds
+WHERE archived = 1
@@ -0,0 +1,4 @@
+SELECT identifier, article_identifier, author, content, date
+FROM article_comments
+WHERE article_identifier = ?
+ORDER BY date ASC
@@ -0,0 +1,3 @@
+SELECT version, length(content) AS total_size, substr(content, ?2 + 1, ?3) AS chunk
+FROM articles
+WHERE identifier = ?1
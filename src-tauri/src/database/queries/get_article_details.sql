@@ -0,0 +1,4 @@
+SELECT articles.extras, feeds.extras AS feed_extras
+FROM articles
+INNER JOIN feeds ON feeds.identifier = articles.feed_identifier
+WHERE articles.identifier = ?
@@ -0,0 +1,4 @@
+SELECT url, mime_type, role, language
+FROM article_enclosures
+WHERE article_identifier = ?
+ORDER BY rowid
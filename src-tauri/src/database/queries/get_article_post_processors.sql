@@ -0,0 +1,3 @@
+SELECT post_processors
+FROM articles
+WHERE identifier = ?
@@ -0,0 +1,3 @@
+SELECT format, mime_type, segments, raw
+FROM article_transcripts
+WHERE article_identifier = ?
@@ -0,0 +1 @@
+PRAGMA auto_vacuum
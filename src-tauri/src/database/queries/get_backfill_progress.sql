@@ -0,0 +1 @@
+SELECT last_rowid FROM backfills WHERE name = ?
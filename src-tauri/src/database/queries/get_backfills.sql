@@ -0,0 +1,3 @@
+SELECT name, last_rowid, done
+FROM backfills
+ORDER BY name ASC
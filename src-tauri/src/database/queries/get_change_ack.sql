@@ -0,0 +1,3 @@
+SELECT watermark
+FROM change_acks
+WHERE window_label = ?
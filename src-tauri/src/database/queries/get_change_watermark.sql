@@ -0,0 +1,2 @@
+-- The sequence survives the pruning, unlike MAX(watermark)
+SELECT COALESCE((SELECT seq FROM sqlite_sequence WHERE name = 'change_journal'), 0)
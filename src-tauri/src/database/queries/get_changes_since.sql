@@ -0,0 +1,6 @@
+-- Each changed row once, in the order of its last change
+SELECT entity, identifier
+FROM change_journal
+WHERE watermark > ?
+GROUP BY entity, identifier
+ORDER BY MAX(watermark)
@@ -0,0 +1 @@
+SELECT comments_url FROM articles WHERE identifier = ?
@@ -0,0 +1,5 @@
+SELECT identifier, title
+FROM articles
+WHERE feed_identifier = ? AND dedup_key = ? AND identifier != ?
+ORDER BY date DESC
+LIMIT ?
@@ -0,0 +1,5 @@
+SELECT rowid, link, COALESCE(original_title, title) AS title
+FROM articles
+WHERE rowid > ?1 AND dedup_key IS NULL
+ORDER BY rowid ASC
+LIMIT ?2
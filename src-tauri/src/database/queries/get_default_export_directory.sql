@@ -0,0 +1,3 @@
+SELECT default_directory
+FROM export_settings
+WHERE id = 1
@@ -0,0 +1,4 @@
+SELECT identifier, url
+FROM feeds
+WHERE priority = 'high' AND archived = 0 AND COALESCE(last_updated, 0) <= (unixepoch() - ? * 60) * 1000
+ORDER BY last_updated ASC
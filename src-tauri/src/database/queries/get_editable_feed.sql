@@ -0,0 +1,3 @@
+SELECT identifier, display_name, url, interval, priority, archived, address_family, user_agent, reread_on_update, retention_days, retention_max_articles, title_prefixes, version
+FROM feeds
+WHERE identifier = ?
@@ -0,0 +1,2 @@
+SELECT device_id
+FROM exchange_device
@@ -0,0 +1,3 @@
+SELECT modified_at, size
+FROM exchange_peers
+WHERE file_name = ?
@@ -0,0 +1,10 @@
+SELECT articles.identifier, articles.feed_identifier, articles.title, NULLIF(articles.original_title, articles.title) AS original_title,
+       articles.content, articles.date, articles.read, articles.image_url, articles.score, articles.first_seen,
+       COALESCE(articles.first_seen - articles.date > ?2, 0) AS backfilled,
+       articles.read_at, articles.content_updated_at, articles.reread_requested,
+       COALESCE(articles.content_updated_at > articles.read_at, 0) AS updated_since_read,
+       articles.dedup_key, articles.post_processors, articles.language,
+       feeds.display_name AS feed_name, feeds.dedup_strategy, feeds.archived AS feed_archived
+FROM articles
+LEFT JOIN feeds ON feeds.identifier = articles.feed_identifier
+WHERE articles.identifier = ?1
@@ -0,0 +1,3 @@
+SELECT client_certificate_path, client_certificate_secret, ca_certificate_path
+FROM feed_certificates
+WHERE feed_identifier = ?
@@ -0,0 +1,4 @@
+SELECT field, old_value, new_value, source, changed_at
+FROM feed_changes
+WHERE feed_identifier = ?
+ORDER BY changed_at DESC, rowid DESC
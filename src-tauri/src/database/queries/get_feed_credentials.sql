@@ -0,0 +1,3 @@
+SELECT username, password_secret, headers_secret
+FROM feed_credentials
+WHERE feed_identifier = ?
@@ -0,0 +1,3 @@
+SELECT dedup_strategy AS strategy, dedup_strategy_locked AS locked
+FROM feeds
+WHERE identifier = ?
@@ -0,0 +1,3 @@
+SELECT rowid, identifier, dedup_key, link, COALESCE(original_title, title) AS title
+FROM articles
+WHERE feed_identifier = ?
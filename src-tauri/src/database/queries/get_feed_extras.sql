@@ -0,0 +1,3 @@
+SELECT extras
+FROM feeds
+WHERE identifier = ?
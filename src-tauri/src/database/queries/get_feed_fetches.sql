@@ -0,0 +1,4 @@
+SELECT local_date, local_minute, success
+FROM feed_fetches
+WHERE feed_identifier = ?
+ORDER BY fetched_at
@@ -0,0 +1,15 @@
+-- The folder of every feed in one, or of the feed ?1 only, then the ancestors of the folder, nearest first
+WITH RECURSIVE chain(feed_identifier, folder_identifier, depth) AS (
+    SELECT identifier, folder_identifier, 0
+    FROM feeds
+    WHERE folder_identifier IS NOT NULL AND (?1 IS NULL OR identifier = ?1)
+    UNION ALL
+    SELECT chain.feed_identifier, folders.parent_identifier, chain.depth + 1
+    FROM chain
+    JOIN folders ON folders.identifier = chain.folder_identifier
+    -- Folders edited outside the app could form a cycle
+    WHERE folders.parent_identifier IS NOT NULL AND chain.depth < 32
+)
+SELECT feed_identifier, folder_identifier
+FROM chain
+ORDER BY feed_identifier, depth
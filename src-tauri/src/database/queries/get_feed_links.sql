@@ -0,0 +1,3 @@
+SELECT link
+FROM articles
+WHERE feed_identifier = ? AND link IS NOT NULL
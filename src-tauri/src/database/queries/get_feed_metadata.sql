@@ -0,0 +1,3 @@
+SELECT type, image_url, last_updated
+FROM feeds
+WHERE identifier = ?
@@ -0,0 +1,3 @@
+SELECT address_family, user_agent
+FROM feeds
+WHERE identifier = ?
@@ -0,0 +1,2 @@
+SELECT identifier, priority
+FROM feeds
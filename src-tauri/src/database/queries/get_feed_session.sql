@@ -0,0 +1,3 @@
+SELECT cookies_secret, state
+FROM feed_sessions
+WHERE feed_identifier = ?
@@ -0,0 +1,3 @@
+SELECT title_prefixes
+FROM feeds
+WHERE identifier = ?
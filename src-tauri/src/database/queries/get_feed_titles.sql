@@ -0,0 +1,3 @@
+SELECT rowid, title, COALESCE(original_title, title) AS original_title
+FROM articles
+WHERE feed_identifier = ?
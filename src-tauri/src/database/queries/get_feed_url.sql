@@ -0,0 +1,3 @@
+SELECT url
+FROM feeds
+WHERE identifier = ?
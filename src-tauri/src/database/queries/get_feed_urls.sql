@@ -0,0 +1,3 @@
+SELECT identifier, url
+FROM feeds
+ORDER BY rowid ASC
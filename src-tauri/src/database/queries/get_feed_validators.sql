@@ -0,0 +1,3 @@
+SELECT etag, last_modified
+FROM feeds
+WHERE identifier = ?
@@ -0,0 +1,3 @@
+SELECT identifier, name, parent_identifier
+FROM folders
+ORDER BY rowid
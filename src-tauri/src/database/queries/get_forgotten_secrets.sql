@@ -0,0 +1 @@
+SELECT reference FROM forgotten_secrets
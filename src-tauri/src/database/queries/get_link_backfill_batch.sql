@@ -0,0 +1,5 @@
+SELECT rowid, identifier, content, link
+FROM articles
+WHERE rowid > ?1
+ORDER BY rowid ASC
+LIMIT ?2
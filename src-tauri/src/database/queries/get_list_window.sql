@@ -0,0 +1,3 @@
+SELECT window_label AS label, list_title AS title, list_filter AS filter
+FROM session_state
+WHERE window_label = ? AND list_filter IS NOT NULL
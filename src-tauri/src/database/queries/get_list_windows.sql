@@ -0,0 +1,4 @@
+SELECT window_label AS label, list_title AS title, list_filter AS filter
+FROM session_state
+WHERE list_filter IS NOT NULL
+ORDER BY updated_at
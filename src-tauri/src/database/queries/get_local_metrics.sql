@@ -0,0 +1,4 @@
+SELECT day, metric, value
+FROM local_metrics
+WHERE day >= ?1 AND day <= ?2
+ORDER BY day, metric
@@ -0,0 +1,16 @@
+-- Every feed with its network settings, the names of its headers and cookies but never their values
+SELECT feeds.identifier AS feed_identifier,
+    feeds.display_name,
+    feeds.user_agent,
+    feeds.address_family,
+    COALESCE(feed_credentials.header_names, '[]') AS header_names,
+    feed_credentials.username IS NOT NULL AS basic_auth,
+    COALESCE(feed_sessions.cookie_names, '[]') AS cookie_names,
+    feed_certificates.client_certificate_path,
+    feed_certificates.client_certificate_secret IS NOT NULL AS client_certificate_passphrase,
+    feed_certificates.ca_certificate_path
+FROM feeds
+LEFT JOIN feed_credentials ON feed_credentials.feed_identifier = feeds.identifier
+LEFT JOIN feed_sessions ON feed_sessions.feed_identifier = feeds.identifier
+LEFT JOIN feed_certificates ON feed_certificates.feed_identifier = feeds.identifier
+ORDER BY feeds.display_name COLLATE NOCASE
@@ -0,0 +1,17 @@
+SELECT articles.identifier, articles.feed_identifier, articles.dedup_key, articles.title, feeds.display_name AS feed_name,
+       notified_articles.identifier IS NOT NULL AS updated
+FROM articles
+JOIN feeds ON feeds.identifier = articles.feed_identifier
+LEFT JOIN notified_articles ON notified_articles.identifier = articles.identifier
+WHERE feeds.archived = 0
+  AND (articles.read = 0 OR articles.reread_requested = 1)
+  AND (
+      (notified_articles.identifier IS NULL AND articles.first_seen >= ?1
+          -- Another copy of it, stored again under another identifier
+          AND NOT EXISTS (
+              SELECT 1 FROM notified_articles AS copy
+              WHERE copy.feed_identifier = articles.feed_identifier AND copy.dedup_key = articles.dedup_key
+          ))
+      OR (feeds.reread_on_update = 1 AND articles.content_updated_at > notified_articles.notified_at)
+  )
+ORDER BY COALESCE(articles.content_updated_at, articles.first_seen) DESC
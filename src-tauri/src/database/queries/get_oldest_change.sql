@@ -0,0 +1,2 @@
+SELECT MIN(watermark)
+FROM change_journal
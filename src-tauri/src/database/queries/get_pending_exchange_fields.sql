@@ -0,0 +1,4 @@
+SELECT entity, key, field, value, changed_at
+FROM exchange_fields
+WHERE ulid IS NULL
+ORDER BY changed_at ASC
@@ -0,0 +1,22 @@
+-- ?1 and ?2 are the preferences, NULL when off. Archived feeds keep their whole history
+WITH retention AS (
+    SELECT identifier AS feed_identifier,
+           CASE WHEN retention_days IS NULL THEN ?1 ELSE NULLIF(retention_days, 0) END AS max_days,
+           CASE WHEN retention_max_articles IS NULL THEN ?2 ELSE NULLIF(retention_max_articles, 0) END AS max_articles
+    FROM feeds
+    WHERE archived = 0
+),
+ranked AS (
+    SELECT articles.identifier, articles.feed_identifier,
+           -- Kept for its days after being published or first seen, whichever is later
+           MAX(articles.date, COALESCE(articles.first_seen, 0)) AS kept_since,
+           ROW_NUMBER() OVER (PARTITION BY articles.feed_identifier ORDER BY articles.date DESC, articles.rowid DESC) AS rank
+    FROM articles
+    JOIN retention ON retention.feed_identifier = articles.feed_identifier
+    WHERE retention.max_days IS NOT NULL OR retention.max_articles IS NOT NULL
+)
+SELECT ranked.identifier
+FROM ranked
+JOIN retention ON retention.feed_identifier = ranked.feed_identifier
+WHERE (ranked.kept_since < (unixepoch() - retention.max_days * 24 * 60 * 60) * 1000 OR ranked.rank > retention.max_articles)
+  AND NOT EXISTS (SELECT 1 FROM reading_queue WHERE reading_queue.article_identifier = ranked.identifier)
@@ -0,0 +1,3 @@
+SELECT dedup_key
+FROM pruned_articles
+WHERE feed_identifier = ? AND dedup_key IS NOT NULL
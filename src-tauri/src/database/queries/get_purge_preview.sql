@@ -0,0 +1,15 @@
+-- Per feed aggregates for `preview_purge`. ?1 is the JSON array of the articles a prune would delete, see
+-- get_prune_candidates.sql, and ?2 the lowest score of the unread articles counted, NULL for all
+SELECT feeds.identifier AS feed_identifier,
+       feeds.display_name,
+       COUNT(*) AS articles,
+       SUM(articles.read = 0 AND (?2 IS NULL OR articles.score >= ?2)) AS unread,
+       MIN(articles.date) AS oldest_date,
+       MAX(articles.date) AS newest_date,
+       SUM(length(CAST(articles.title AS BLOB)) + length(CAST(articles.content AS BLOB))) AS bytes
+FROM articles
+JOIN feeds ON feeds.identifier = articles.feed_identifier
+WHERE articles.identifier IN (SELECT value FROM json_each(?1))
+  AND NOT EXISTS (SELECT 1 FROM reading_queue WHERE reading_queue.article_identifier = articles.identifier)
+GROUP BY feeds.identifier
+ORDER BY articles DESC, feeds.display_name
@@ -0,0 +1,4 @@
+SELECT articles.identifier, articles.feed_identifier, articles.title, articles.content, articles.date, articles.read, articles.image_url, articles.score
+FROM reading_queue
+JOIN articles ON articles.identifier = reading_queue.article_identifier
+ORDER BY reading_queue.position
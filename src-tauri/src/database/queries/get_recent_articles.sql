@@ -0,0 +1,4 @@
+SELECT rowid, identifier, feed_identifier, title, content, language, score
+FROM articles
+ORDER BY date DESC
+LIMIT ?
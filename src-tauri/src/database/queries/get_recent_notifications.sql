@@ -0,0 +1,8 @@
+SELECT notified_articles.identifier AS article_identifier, notified_articles.feed_identifier, feeds.display_name AS feed_name,
+       notified_articles.title, notified_articles.updated, notified_articles.notified_at,
+       articles.identifier IS NOT NULL AS available
+FROM notified_articles
+JOIN feeds ON feeds.identifier = notified_articles.feed_identifier
+LEFT JOIN articles ON articles.identifier = notified_articles.identifier
+ORDER BY notified_articles.notified_at DESC
+LIMIT ?1
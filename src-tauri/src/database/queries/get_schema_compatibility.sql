@@ -0,0 +1,3 @@
+SELECT min_schema_version, min_app_version
+FROM schema_compatibility
+WHERE id = 1
@@ -0,0 +1,5 @@
+SELECT rowid, identifier, feed_identifier, title, content, language, score
+FROM articles
+WHERE rowid > ?
+ORDER BY rowid ASC
+LIMIT ?
@@ -0,0 +1,3 @@
+SELECT identifier, conditions, weight
+FROM scoring_rules
+ORDER BY rowid ASC
@@ -0,0 +1,10 @@
+-- References to feeds or articles deleted since are returned as NULL
+SELECT
+    (SELECT identifier FROM feeds WHERE identifier = session_state.feed_identifier) AS feed_identifier,
+    (SELECT identifier FROM articles WHERE identifier = session_state.article_identifier) AS article_identifier,
+    (SELECT identifier FROM articles WHERE identifier = session_state.scroll_anchor) AS scroll_anchor,
+    CASE
+        WHEN EXISTS (SELECT 1 FROM articles WHERE identifier = session_state.article_identifier) THEN reader_scroll
+    END AS reader_scroll
+FROM session_state
+WHERE window_label = ?
@@ -0,0 +1,32 @@
+-- Per feed aggregates for `project_storage`. ?1 is the JSON array of the articles the candidate retention
+-- prunes right away, see get_prune_candidates.sql, ?2 and ?3 the candidate retention, NULL when off, ?4
+-- the lowest score of the unread articles counted, NULL for all, and ?5 the start of the measured period,
+-- over which the articles are counted by publication date, as the first sync of a feed sees its backlog
+WITH sized AS (
+    SELECT articles.feed_identifier, articles.read, articles.read_at,
+           articles.date AS posted,
+           MAX(articles.date, COALESCE(articles.first_seen, 0)) AS kept_since,
+           (?4 IS NULL OR articles.score >= ?4) AS shown,
+           length(CAST(articles.title AS BLOB)) + length(CAST(articles.content AS BLOB)) AS bytes,
+           articles.identifier IN (SELECT value FROM json_each(?1)) AS pruned
+    FROM articles
+)
+SELECT feeds.identifier AS feed_identifier,
+       feeds.archived = 1 AS archived,
+       CASE WHEN feeds.retention_days IS NULL THEN ?2 ELSE NULLIF(feeds.retention_days, 0) END AS max_days,
+       CASE WHEN feeds.retention_max_articles IS NULL THEN ?3 ELSE NULLIF(feeds.retention_max_articles, 0) END AS max_articles,
+       COALESCE(SUM(NOT sized.pruned), 0) AS articles,
+       COALESCE(SUM(NOT sized.pruned AND sized.read = 0 AND sized.shown), 0) AS unread,
+       COALESCE(SUM(CASE WHEN NOT sized.pruned THEN sized.bytes END), 0) AS bytes,
+       MIN(CASE WHEN NOT sized.pruned THEN sized.kept_since END) AS oldest_kept_since,
+       MIN(sized.posted) AS first_posted,
+       COALESCE(SUM(sized.posted >= ?5), 0) AS recent_articles,
+       COALESCE(SUM(sized.posted >= ?5 AND sized.shown), 0) AS recent_shown,
+       COALESCE(SUM(sized.read_at >= ?5), 0) AS recent_read,
+       COALESCE(SUM(CASE WHEN sized.posted >= ?5 THEN sized.bytes END), 0) AS recent_bytes,
+       COALESCE(SUM(sized.pruned), 0) AS pruned_articles,
+       COALESCE(SUM(sized.pruned AND sized.read = 0 AND sized.shown), 0) AS pruned_unread,
+       COALESCE(SUM(CASE WHEN sized.pruned THEN sized.bytes END), 0) AS pruned_bytes
+FROM feeds
+LEFT JOIN sized ON sized.feed_identifier = feeds.identifier
+GROUP BY feeds.identifier
@@ -0,0 +1,3 @@
+SELECT identifier, feed_identifier, title, content, date, read, image_url, score
+FROM articles
+WHERE identifier = ?
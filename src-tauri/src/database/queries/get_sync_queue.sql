@@ -0,0 +1,3 @@
+SELECT feed_identifier, done
+FROM sync_queue
+ORDER BY position ASC
@@ -0,0 +1,17 @@
+SELECT identifier, feed_identifier, title, NULLIF(original_title, title) AS original_title, content, date, read, image_url, score, first_seen,
+       COALESCE(first_seen - date > ?6, 0) AS backfilled,
+       EXISTS (SELECT 1 FROM reading_queue WHERE article_identifier = articles.identifier) AS queued,
+       read_at, content_updated_at, reread_requested,
+       COALESCE(content_updated_at > read_at, 0) AS updated_since_read
+FROM articles
+WHERE (date, identifier) > (?3, ?4)
+  AND (?1 IS NULL OR feed_identifier = ?1)
+  AND (?2 IS NULL OR score >= ?2)
+  AND CASE ?7
+      WHEN 'unread' THEN read = 0 OR reread_requested = 1
+      WHEN 'read' THEN read = 1 AND reread_requested = 0
+      WHEN 'updated_since_read' THEN content_updated_at > read_at
+      ELSE 1
+  END
+ORDER BY date, identifier
+LIMIT ?5
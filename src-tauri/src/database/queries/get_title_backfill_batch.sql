@@ -0,0 +1,6 @@
+SELECT articles.rowid, articles.link, articles.title, COALESCE(feeds.title_prefixes, '[]') AS title_prefixes
+FROM articles
+LEFT JOIN feeds ON feeds.identifier = articles.feed_identifier
+WHERE articles.rowid > ?1 AND articles.original_title IS NULL
+ORDER BY articles.rowid ASC
+LIMIT ?2
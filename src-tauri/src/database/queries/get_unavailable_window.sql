@@ -0,0 +1,3 @@
+SELECT unavailable_start AS start_minute, unavailable_minutes AS minutes
+FROM feeds
+WHERE identifier = ? AND unavailable_start IS NOT NULL AND unavailable_minutes IS NOT NULL
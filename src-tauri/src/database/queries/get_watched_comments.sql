@@ -0,0 +1,3 @@
+SELECT article_identifier, url
+FROM watched_comments
+ORDER BY last_updated ASC
@@ -0,0 +1,3 @@
+SELECT image_types, audio_types, probed_at
+FROM webview_capabilities
+WHERE app_version = ?
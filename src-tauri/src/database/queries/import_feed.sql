@@ -0,0 +1,2 @@
+INSERT INTO feeds (identifier, display_name, url, interval, folder_identifier, html_url)
+VALUES (?, ?, ?, ?, ?, ?)
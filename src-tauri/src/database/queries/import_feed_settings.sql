@@ -0,0 +1,14 @@
+-- Settings of a feed just imported, from the `alduin:` attributes of its outline, NULL for the missing ones
+UPDATE feeds
+SET interval = COALESCE(?2, interval),
+    priority = COALESCE(?3, priority),
+    archived = COALESCE(?4, archived),
+    dedup_strategy = COALESCE(?5, dedup_strategy),
+    dedup_strategy_locked = COALESCE(?6, dedup_strategy_locked),
+    address_family = COALESCE(?7, address_family),
+    user_agent = COALESCE(?8, user_agent),
+    reread_on_update = COALESCE(?9, reread_on_update),
+    retention_days = COALESCE(?10, retention_days),
+    retention_max_articles = COALESCE(?11, retention_max_articles),
+    title_prefixes = COALESCE(?12, title_prefixes)
+WHERE identifier = ?1
@@ -0,0 +1,3 @@
+INSERT INTO local_metrics (day, metric, value)
+VALUES (?1, ?2, ?3)
+ON CONFLICT(day, metric) DO UPDATE SET value = value + excluded.value
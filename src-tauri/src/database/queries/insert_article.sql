@@ -1,2 +1,2 @@
-INSERT INTO articles (identifier, feed_identifier, title, content, date, image_url)
-VALUES (?, ?, ?, ?, ?, ?);
+INSERT INTO articles (identifier, feed_identifier, title, content, date, image_url, comments_url, link, score, dedup_key, post_processors, extras, original_title, language, first_seen)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, unixepoch() * 1000);
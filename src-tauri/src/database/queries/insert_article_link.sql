@@ -0,0 +1 @@
+INSERT OR IGNORE INTO article_links (article_identifier, host, path) VALUES (?, ?, ?)
@@ -0,0 +1,2 @@
+INSERT OR REPLACE INTO article_transcripts (article_identifier, format, mime_type, segments, raw, fetched_at)
+VALUES (?, ?, ?, ?, ?, unixepoch() * 1000)
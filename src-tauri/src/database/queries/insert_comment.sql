@@ -0,0 +1,2 @@
+INSERT INTO article_comments (identifier, article_identifier, author, content, date)
+VALUES (?, ?, ?, ?, ?);
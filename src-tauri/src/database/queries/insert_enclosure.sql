@@ -0,0 +1,2 @@
+INSERT OR IGNORE INTO article_enclosures (article_identifier, url, mime_type, role, language)
+VALUES (?, ?, ?, ?, ?)
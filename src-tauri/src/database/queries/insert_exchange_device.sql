@@ -0,0 +1,2 @@
+INSERT OR IGNORE INTO exchange_device (id, device_id)
+VALUES (1, ?)
@@ -0,0 +1,2 @@
+INSERT INTO feeds (identifier, display_name, url, type, interval, priority)
+VALUES (?, ?, ?, ?, ?, ?)
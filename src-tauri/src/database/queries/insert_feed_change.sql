@@ -0,0 +1,2 @@
+INSERT INTO feed_changes (feed_identifier, field, old_value, new_value, source, changed_at)
+VALUES (?, ?, ?, ?, ?, unixepoch() * 1000)
@@ -0,0 +1,2 @@
+INSERT INTO feed_fetches (feed_identifier, fetched_at, local_date, local_minute, success)
+VALUES (?, unixepoch() * 1000, ?, ?, ?)
@@ -0,0 +1,2 @@
+INSERT INTO folders (identifier, name, parent_identifier)
+VALUES (?, ?, ?)
@@ -0,0 +1,3 @@
+INSERT INTO notified_articles (identifier, feed_identifier, dedup_key, title, updated, notified_at)
+VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+ON CONFLICT(identifier) DO UPDATE SET title = excluded.title, updated = excluded.updated, notified_at = excluded.notified_at
@@ -0,0 +1,5 @@
+INSERT OR IGNORE INTO pruned_articles (identifier, feed_identifier, dedup_key, pruned_at)
+SELECT identifier, feed_identifier, dedup_key, unixepoch() * 1000
+FROM articles
+WHERE identifier IN (SELECT value FROM json_each(?))
+  AND NOT EXISTS (SELECT 1 FROM reading_queue WHERE reading_queue.article_identifier = articles.identifier)
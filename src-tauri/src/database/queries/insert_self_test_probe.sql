@@ -0,0 +1 @@
+INSERT INTO self_test_probe (written_at) VALUES (?1)
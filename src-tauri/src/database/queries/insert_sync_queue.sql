@@ -0,0 +1,2 @@
+INSERT INTO sync_queue (feed_identifier, position)
+VALUES (?, ?)
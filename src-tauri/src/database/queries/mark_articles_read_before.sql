@@ -0,0 +1,3 @@
+UPDATE articles
+SET read = 1, read_at = CAST(unixepoch('subsec') * 1000 AS INTEGER), reread_requested = 0
+WHERE (read = 0 OR reread_requested = 1) AND date < ?
@@ -0,0 +1,3 @@
+UPDATE exchange_fields
+SET ulid = ?
+WHERE entity = ? AND key = ? AND field = ? AND ulid IS NULL AND changed_at = ? AND value IS ?
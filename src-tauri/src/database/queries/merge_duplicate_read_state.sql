@@ -0,0 +1,9 @@
+UPDATE articles
+SET read = 1, read_at = CAST(unixepoch('subsec') * 1000 AS INTEGER)
+WHERE feed_identifier = ?1
+  AND dedup_key IS NOT NULL
+  AND read = 0
+  AND EXISTS (
+      SELECT 1 FROM articles AS other
+      WHERE other.feed_identifier = ?1 AND other.dedup_key = articles.dedup_key AND other.read = 1
+  )
@@ -0,0 +1,6 @@
+INSERT INTO exchange_fields (entity, key, field, value, changed_at, ulid, origin)
+VALUES (?, ?, ?, ?, ?, ?, ?)
+ON CONFLICT (entity, key, field) DO UPDATE
+SET value = excluded.value, changed_at = excluded.changed_at, ulid = excluded.ulid, origin = excluded.origin
+WHERE (excluded.changed_at, excluded.origin, excluded.ulid) > (exchange_fields.changed_at, exchange_fields.origin, COALESCE(exchange_fields.ulid, ''))
+RETURNING 1
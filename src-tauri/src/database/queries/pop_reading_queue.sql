@@ -0,0 +1,3 @@
+DELETE FROM reading_queue
+WHERE article_identifier = (SELECT article_identifier FROM reading_queue ORDER BY position LIMIT 1)
+RETURNING article_identifier
@@ -0,0 +1,2 @@
+DELETE FROM change_journal
+WHERE changed_at < unixepoch() * 1000 - ?
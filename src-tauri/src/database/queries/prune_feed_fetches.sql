@@ -0,0 +1,2 @@
+DELETE FROM feed_fetches
+WHERE feed_identifier = ? AND fetched_at < (unixepoch() - 30 * 24 * 60 * 60) * 1000
@@ -0,0 +1,2 @@
+DELETE FROM local_metrics
+WHERE day < ?1
@@ -0,0 +1,2 @@
+DELETE FROM notified_articles
+WHERE notified_at < unixepoch() * 1000 - ?
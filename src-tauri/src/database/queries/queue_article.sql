@@ -0,0 +1,3 @@
+INSERT INTO reading_queue (article_identifier, position, added_at)
+VALUES (?1, (SELECT COALESCE(MAX(position), 0) + 1 FROM reading_queue), unixepoch() * 1000)
+ON CONFLICT(article_identifier) DO NOTHING
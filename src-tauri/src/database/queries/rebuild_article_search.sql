@@ -0,0 +1,2 @@
+-- Reindexes every article under its current rowid
+INSERT INTO article_search(article_search) VALUES ('rebuild')
@@ -0,0 +1,13 @@
+-- Moves ?1 right after ?2, or first when ?2 is NULL, halfway between its new neighbours
+UPDATE reading_queue
+SET position = CASE
+    WHEN ?2 IS NULL THEN (SELECT COALESCE(MIN(position), 1) - 1 FROM reading_queue WHERE article_identifier != ?1)
+    ELSE COALESCE(
+        ((SELECT position FROM reading_queue WHERE article_identifier = ?2)
+            + (SELECT MIN(position) FROM reading_queue
+               WHERE position > (SELECT position FROM reading_queue WHERE article_identifier = ?2) AND article_identifier != ?1)) / 2,
+        (SELECT position FROM reading_queue WHERE article_identifier = ?2) + 1
+    )
+END
+WHERE article_identifier = ?1
+  AND (?2 IS NULL OR EXISTS (SELECT 1 FROM reading_queue WHERE article_identifier = ?2 AND article_identifier != ?1))
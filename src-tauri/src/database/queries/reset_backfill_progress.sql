@@ -0,0 +1,2 @@
+-- The backfills not done start over, their batches skipping the rows done already
+UPDATE backfills SET last_rowid = 0, updated_at = unixepoch() * 1000 WHERE done = 0
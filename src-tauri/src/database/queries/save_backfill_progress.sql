@@ -0,0 +1 @@
+UPDATE backfills SET last_rowid = ?1, done = ?2, updated_at = unixepoch() * 1000 WHERE name = ?3
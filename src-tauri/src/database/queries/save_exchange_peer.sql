@@ -0,0 +1,4 @@
+INSERT INTO exchange_peers (file_name, modified_at, size)
+VALUES (?, ?, ?)
+ON CONFLICT (file_name) DO UPDATE
+SET modified_at = excluded.modified_at, size = excluded.size
@@ -0,0 +1,6 @@
+INSERT INTO session_state (window_label, list_title, list_filter, updated_at)
+VALUES (?, ?, ?, unixepoch() * 1000)
+ON CONFLICT(window_label) DO UPDATE
+SET list_title = excluded.list_title,
+    list_filter = excluded.list_filter,
+    updated_at = excluded.updated_at
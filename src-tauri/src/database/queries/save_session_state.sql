@@ -0,0 +1,8 @@
+INSERT INTO session_state (window_label, feed_identifier, article_identifier, scroll_anchor, reader_scroll, updated_at)
+VALUES (?, ?, ?, ?, ?, unixepoch() * 1000)
+ON CONFLICT(window_label) DO UPDATE
+SET feed_identifier = excluded.feed_identifier,
+    article_identifier = excluded.article_identifier,
+    scroll_anchor = excluded.scroll_anchor,
+    reader_scroll = excluded.reader_scroll,
+    updated_at = excluded.updated_at
@@ -0,0 +1,3 @@
+INSERT INTO webview_capabilities (app_version, image_types, audio_types, probed_at)
+VALUES (?1, ?2, ?3, unixepoch() * 1000)
+ON CONFLICT(app_version) DO UPDATE SET image_types = excluded.image_types, audio_types = excluded.audio_types, probed_at = excluded.probed_at
@@ -0,0 +1,12 @@
+SELECT articles.identifier, articles.feed_identifier, articles.title, articles.content, articles.date, articles.read, articles.image_url, articles.score
+FROM articles
+JOIN feeds ON feeds.identifier = articles.feed_identifier
+WHERE (?1 IS NULL OR articles.rowid IN (SELECT rowid FROM article_search WHERE article_search MATCH ?1))
+  AND (?2 IS NULL OR lower(feeds.display_name) IN (SELECT value FROM json_each(?2)))
+  AND (?3 IS NULL OR lower(feeds.display_name) NOT IN (SELECT value FROM json_each(?3)))
+  AND (?4 IS NULL OR articles.read = ?4)
+  AND (?5 IS NULL OR articles.date < ?5)
+  AND (?6 IS NULL OR articles.date >= ?6)
+  AND (?7 IS NULL OR (articles.date, articles.identifier) < (?7, ?8))
+ORDER BY articles.date DESC, articles.identifier DESC
+LIMIT ?9
@@ -0,0 +1,9 @@
+INSERT OR IGNORE INTO exchange_fields (entity, key, field, value, changed_at, ulid, origin)
+SELECT 'feed', url, 'subscribed', '1', 0, NULL, ?1 FROM feeds WHERE archived = 0
+UNION ALL
+SELECT 'feed', url, 'display_name', display_name, 0, NULL, ?1 FROM feeds WHERE archived = 0
+UNION ALL
+SELECT 'article', feeds.url || char(10) || COALESCE(articles.link, articles.title), 'read', '1', 0, NULL, ?1
+FROM articles
+JOIN feeds ON feeds.identifier = articles.feed_identifier
+WHERE articles.read = 1
@@ -0,0 +1,7 @@
+-- Rows left behind by a write interrupted between its statements, each kept apart by a transaction now
+SELECT
+    (SELECT COUNT(*) FROM feed_credentials WHERE username IS NULL AND header_names = '[]'),
+    (SELECT COUNT(*) FROM feed_certificates WHERE client_certificate_path IS NULL AND ca_certificate_path IS NULL),
+    (SELECT COUNT(*) FROM feeds
+     WHERE unavailable_start IS NOT NULL
+       AND NOT EXISTS (SELECT 1 FROM feed_fetches WHERE feed_fetches.feed_identifier = feeds.identifier))
@@ -0,0 +1 @@
+SELECT COUNT(*) FROM (SELECT rowid FROM article_search WHERE article_search MATCH ?1 LIMIT 1)
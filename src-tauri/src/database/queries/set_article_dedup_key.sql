@@ -0,0 +1 @@
+UPDATE articles SET dedup_key = ? WHERE rowid = ?
@@ -0,0 +1,2 @@
+-- The dedup key is only kept for the articles without a usable link, which have none anyway
+UPDATE articles SET original_title = title, dedup_key = COALESCE(?1, dedup_key) WHERE rowid = ?2
@@ -0,0 +1,8 @@
+UPDATE articles
+SET read = ?1,
+    -- Marking it unread again keeps when it was last read
+    read_at = CASE WHEN ?1 THEN CAST(unixepoch('subsec') * 1000 AS INTEGER) ELSE read_at END,
+    reread_requested = CASE WHEN ?1 THEN 0 ELSE reread_requested END,
+    version = version + 1
+WHERE identifier = ?2
+RETURNING version, feed_identifier, score
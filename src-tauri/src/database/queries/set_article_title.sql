@@ -0,0 +1 @@
+UPDATE articles SET title = ?1, original_title = ?2 WHERE rowid = ?3
@@ -0,0 +1 @@
+UPDATE articles SET original_title = title, title = ?1, dedup_key = COALESCE(?2, dedup_key) WHERE rowid = ?3
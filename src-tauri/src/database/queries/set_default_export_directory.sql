@@ -0,0 +1,3 @@
+UPDATE export_settings
+SET default_directory = ?, updated_at = unixepoch() * 1000
+WHERE id = 1
@@ -0,0 +1,3 @@
+UPDATE feeds
+SET dedup_strategy = ?, dedup_strategy_locked = ?
+WHERE identifier = ?
@@ -0,0 +1,4 @@
+UPDATE feeds
+SET extras = ?, version = version + 1
+WHERE identifier = ?
+RETURNING version
@@ -0,0 +1,4 @@
+UPDATE feeds
+SET post_processors = json_set(post_processors, '$.' || ?, json(?)), version = version + 1
+WHERE identifier = ?
+RETURNING version
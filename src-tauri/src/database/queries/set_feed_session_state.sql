@@ -0,0 +1 @@
+UPDATE feed_sessions SET state = ?1, updated_at = unixepoch() * 1000 WHERE feed_identifier = ?2
@@ -0,0 +1,3 @@
+UPDATE schema_compatibility
+SET min_app_version = ?
+WHERE id = 1 AND min_app_version IS NULL
@@ -0,0 +1,3 @@
+UPDATE feeds
+SET unavailable_start = ?, unavailable_minutes = ?
+WHERE identifier = ?
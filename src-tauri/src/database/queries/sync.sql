@@ -1,3 +1,3 @@
 UPDATE feeds
-SET type = ?, last_updated = unixepoch() * 1000, image_url = ?
-WHERE identifier = ?
+SET type = ?1, last_updated = unixepoch() * 1000, image_url = ?2, etag = ?4, last_modified = ?5
+WHERE identifier = ?3
@@ -0,0 +1,5 @@
+UPDATE feeds
+SET last_updated = unixepoch() * 1000,
+    etag = COALESCE(?1, etag),
+    last_modified = COALESCE(?2, last_modified)
+WHERE identifier = ?3
@@ -0,0 +1,4 @@
+UPDATE watched_comments
+SET last_updated = unixepoch() * 1000,
+    last_activity = CASE WHEN ? THEN unixepoch() * 1000 ELSE last_activity END
+WHERE article_identifier = ?
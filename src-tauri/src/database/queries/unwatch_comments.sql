@@ -0,0 +1 @@
+DELETE FROM watched_comments WHERE article_identifier = ?
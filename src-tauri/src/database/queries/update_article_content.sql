@@ -0,0 +1,12 @@
+-- Read articles of the feeds set to reread updates are requested again, their read history kept
+UPDATE articles
+SET title = ?2,
+    content = ?3,
+    original_title = ?4,
+    content_updated_at = CAST(unixepoch('subsec') * 1000 AS INTEGER),
+    reread_requested = CASE
+        WHEN read = 1 AND (SELECT reread_on_update FROM feeds WHERE identifier = articles.feed_identifier) THEN 1
+        ELSE reread_requested
+    END,
+    version = version + 1
+WHERE identifier = ?1 AND (title != ?2 OR content != ?3)
@@ -0,0 +1 @@
+UPDATE articles SET score = ? WHERE identifier = ?
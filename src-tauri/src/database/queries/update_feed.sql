@@ -0,0 +1,18 @@
+UPDATE feeds
+SET display_name = COALESCE(?1, display_name),
+    url = COALESCE(?2, url),
+    interval = COALESCE(?3, interval),
+    priority = COALESCE(?6, priority),
+    archived = COALESCE(?7, archived),
+    address_family = COALESCE(?8, address_family),
+    reread_on_update = COALESCE(?9, reread_on_update),
+    retention_days = CASE WHEN ?10 IS NULL THEN retention_days WHEN ?10 < 0 THEN NULL ELSE ?10 END,
+    retention_max_articles = CASE WHEN ?11 IS NULL THEN retention_max_articles WHEN ?11 < 0 THEN NULL ELSE ?11 END,
+    title_prefixes = COALESCE(?12, title_prefixes),
+    user_agent = COALESCE(?13, user_agent),
+    -- The validators belong to the old URL
+    etag = CASE WHEN ?2 IS NOT NULL AND ?2 != url THEN NULL ELSE etag END,
+    last_modified = CASE WHEN ?2 IS NOT NULL AND ?2 != url THEN NULL ELSE last_modified END,
+    version = version + 1
+WHERE identifier = ?4 AND (?5 IS NULL OR version = ?5)
+RETURNING version
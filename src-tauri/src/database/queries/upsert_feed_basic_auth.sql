@@ -0,0 +1,3 @@
+INSERT INTO feed_credentials (feed_identifier, username, password_secret, updated_at)
+VALUES (?1, ?2, ?3, unixepoch() * 1000)
+ON CONFLICT(feed_identifier) DO UPDATE SET username = excluded.username, password_secret = excluded.password_secret, updated_at = excluded.updated_at
@@ -0,0 +1,3 @@
+INSERT INTO feed_certificates (feed_identifier, ca_certificate_path, updated_at)
+VALUES (?1, ?2, unixepoch() * 1000)
+ON CONFLICT(feed_identifier) DO UPDATE SET ca_certificate_path = excluded.ca_certificate_path, updated_at = excluded.updated_at
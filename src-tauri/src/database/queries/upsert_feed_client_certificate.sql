@@ -0,0 +1,3 @@
+INSERT INTO feed_certificates (feed_identifier, client_certificate_path, client_certificate_secret, updated_at)
+VALUES (?1, ?2, ?3, unixepoch() * 1000)
+ON CONFLICT(feed_identifier) DO UPDATE SET client_certificate_path = excluded.client_certificate_path, client_certificate_secret = excluded.client_certificate_secret, updated_at = excluded.updated_at
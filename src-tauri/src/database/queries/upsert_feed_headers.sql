@@ -0,0 +1,3 @@
+INSERT INTO feed_credentials (feed_identifier, header_names, headers_secret, updated_at)
+VALUES (?1, ?2, ?3, unixepoch() * 1000)
+ON CONFLICT(feed_identifier) DO UPDATE SET header_names = excluded.header_names, headers_secret = excluded.headers_secret, updated_at = excluded.updated_at
@@ -0,0 +1,3 @@
+INSERT INTO feed_sessions (feed_identifier, cookie_names, cookies_secret, state, updated_at)
+VALUES (?1, ?2, ?3, 'new', unixepoch() * 1000)
+ON CONFLICT(feed_identifier) DO UPDATE SET cookie_names = excluded.cookie_names, cookies_secret = excluded.cookies_secret, state = 'new', updated_at = excluded.updated_at
@@ -0,0 +1,4 @@
+INSERT INTO scoring_rules (identifier, conditions, weight)
+VALUES (?, ?, ?)
+ON CONFLICT(identifier) DO UPDATE
+SET conditions = excluded.conditions, weight = excluded.weight
@@ -0,0 +1,4 @@
+INSERT INTO watched_comments (article_identifier, url, last_activity)
+VALUES (?, ?, unixepoch() * 1000)
+ON CONFLICT(article_identifier) DO UPDATE
+SET url = excluded.url, last_activity = excluded.last_activity
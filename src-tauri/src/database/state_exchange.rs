@@ -0,0 +1,570 @@
+//! Syncs the read state and the subscriptions of several devices through a folder they share, e.g. one
+//! kept in sync by Syncthing or a cloud drive, without a server.
+//!
+//! Once enabled, triggers keep the last known value of every shared field in `exchange_fields`, keyed by
+//! feed URL and article link since identifiers differ between devices. Each device appends its own changes
+//! to `<device>.alduin-state` in the folder, one JSON entry per line after a header, and is the only one
+//! writing to that file. It reads the files of the other devices, keeping the latest change of each field:
+//! the one with the highest time, then device, then ULID, so every device picks the same one whatever
+//! order it reads the files in. Removed feeds are kept as tombstones, a field without a value.
+//!
+//! Files are read whole and re-read only when they changed, so a file being synced, whose last line can be
+//! cut, is read again once complete. Files that can't be read are skipped with a warning, as are the
+//! conflict copies sync tools make, and entries of fields this version doesn't know are stored but not
+//! applied. Each device compacts its own file once it grows, keeping the latest entry of each field.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+use sqlx::{Pool, Sqlite, SqliteConnection};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+use crate::database::change_journal;
+use crate::structs::exchange_entry::ExchangeEntry;
+use crate::structs::exchange_header::ExchangeHeader;
+use crate::structs::exchange_report::ExchangeReport;
+use crate::structs::preferences::Preferences;
+use crate::structs::write_gate::WriteGate;
+use crate::utils::ulid::{is_ulid, ulid};
+
+pub const FILE_EXTENSION: &str = "alduin-state";
+const FORMAT: u32 = 1;
+/// Lets the frontend load the database first, which applies the migration adding the exchange tables.
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+const EXCHANGE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// Below it, the file of the device isn't worth compacting.
+const COMPACT_AFTER_BYTES: u64 = 1024 * 1024;
+/// Devices offline for longer may keep feeds removed elsewhere.
+const TOMBSTONE_RETENTION_MS: i64 = 30 * 24 * 60 * 60 * 1000;
+
+/// The loop and the command share the files, so exchanges run one at a time.
+static RUNNING: Mutex<()> = Mutex::const_new(());
+
+/// Exchanges with the other devices after startup then periodically, while a folder is set.
+pub async fn run(app_handle: AppHandle) {
+    tokio::time::sleep(STARTUP_DELAY).await;
+
+    loop {
+        let enabled = !Preferences::load(&app_handle).state_exchange_folder.is_empty();
+        if enabled && app_handle.state::<WriteGate>().check().is_ok() {
+            if let Err(e) = exchange_now(&app_handle).await {
+                eprintln!("Error exchanging state: {}", e);
+            }
+        }
+
+        tokio::time::sleep(EXCHANGE_INTERVAL).await;
+    }
+}
+
+/// Exchanges with the folder of the preferences, then lets the windows catch up with the applied changes.
+pub async fn exchange_now(app_handle: &AppHandle) -> Result<ExchangeReport, String> {
+    let folder = Preferences::load(app_handle).state_exchange_folder;
+    if folder.is_empty() {
+        return Err(String::from("No state exchange folder is set"));
+    }
+
+    let _running = RUNNING.lock().await;
+    let writing = app_handle.state::<WriteGate>().enter()?;
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let report = exchange(Path::new(&folder), &pool).await?;
+    drop(writing);
+
+    if report.imported > 0 {
+        for window in app_handle.windows().into_values() {
+            change_journal::catch_up(window).await;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Exports the local changes to `folder` then imports the ones of the other devices.
+/// Enables the exchange on the first call, exporting every subscription and read article.
+pub async fn exchange(folder: &Path, pool: &Pool<Sqlite>) -> Result<ExchangeReport, String> {
+    // A missing folder is more likely an unmounted drive than one to create
+    if !folder.is_dir() {
+        return Err(format!("The state exchange folder {:?} doesn't exist", folder));
+    }
+
+    let device_id = enable(pool).await?;
+    let own_file = folder.join(format!("{}.{}", device_id, FILE_EXTENSION));
+
+    let exported = export(&own_file, &device_id, pool).await?;
+    let compacted = compact(&own_file, &device_id)?;
+    let (imported, skipped_files) = import(folder, &device_id, pool).await?;
+
+    Ok(ExchangeReport { device_id, exported, imported, compacted, skipped_files })
+}
+
+/// Returns the id of this device, creating it along with the initial state on the first call.
+/// The initial state is dated from the epoch, so any change made on another device wins over it.
+async fn enable(pool: &Pool<Sqlite>) -> Result<String, String> {
+    let device_id = sqlx::query_scalar::<_, String>(include_str!("queries/get_exchange_device.sql"))
+        .fetch_optional(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    if let Some(device_id) = device_id {
+        return Ok(device_id);
+    }
+
+    let device_id = ulid();
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+    sqlx::query(include_str!("queries/insert_exchange_device.sql"))
+        .bind(&device_id)
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    sqlx::query(include_str!("queries/seed_exchange_fields.sql"))
+        .bind(&device_id)
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))?;
+
+    Ok(device_id)
+}
+
+/// Appends the changes not exported yet. They are marked as exported once written, unless they changed
+/// again in the meantime, so a failed write exports them again next time.
+async fn export(own_file: &Path, device_id: &str, pool: &Pool<Sqlite>) -> Result<usize, String> {
+    let pending = sqlx::query_as::<_, (String, String, String, Option<String>, i64)>(include_str!("queries/get_pending_exchange_fields.sql"))
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    let entries: Vec<ExchangeEntry> = pending.into_iter()
+        .map(|(entity, key, field, value, at)| ExchangeEntry { id: ulid(), at, entity, key, field, value })
+        .collect();
+
+    let mut lines = String::new();
+    match File::open(own_file) {
+        Ok(mut file) if file.metadata().is_ok_and(|metadata| metadata.len() > 0) => {
+            // A line cut by a crash is ended, so it doesn't swallow the first new one
+            if !ends_with_newline(&mut file) {
+                lines.push('\n');
+            }
+        }
+        _ => lines.push_str(&header_line(device_id)?),
+    }
+    for entry in entries.iter() {
+        lines.push_str(&serde_json::to_string(entry).map_err(|e| format!("Error serializing entry: {}", e))?);
+        lines.push('\n');
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(own_file)
+        .map_err(|e| format!("Error opening {:?}: {}", own_file, e))?;
+    file.write_all(lines.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("Error writing {:?}: {}", own_file, e))?;
+
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+    for entry in entries.iter() {
+        sqlx::query(include_str!("queries/mark_exchange_field_exported.sql"))
+            .bind(&entry.id)
+            .bind(&entry.entity)
+            .bind(&entry.key)
+            .bind(&entry.field)
+            .bind(entry.at)
+            .bind(&entry.value)
+            .execute(&mut *transaction).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+    }
+    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))?;
+
+    Ok(entries.len())
+}
+
+/// Rewrites the file of this device with the latest entry of each field, once most of its entries are
+/// outdated. Old tombstones are dropped, the other devices had time to read them.
+fn compact(own_file: &Path, device_id: &str) -> Result<bool, String> {
+    let size = fs::metadata(own_file).map(|metadata| metadata.len()).unwrap_or_default();
+    if size < COMPACT_AFTER_BYTES {
+        return Ok(false);
+    }
+
+    let content = fs::read_to_string(own_file).map_err(|e| format!("Error reading {:?}: {}", own_file, e))?;
+    let entries = read_entries(&content, device_id)?;
+    let total = entries.len();
+
+    let mut latest: HashMap<(String, String, String), ExchangeEntry> = HashMap::new();
+    for entry in entries {
+        let field = (entry.entity.clone(), entry.key.clone(), entry.field.clone());
+        match latest.get(&field) {
+            Some(known) if (known.at, &known.id) >= (entry.at, &entry.id) => {}
+            _ => { latest.insert(field, entry); }
+        }
+    }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut kept: Vec<ExchangeEntry> = latest.into_values()
+        .filter(|entry| entry.value.is_some() || now - entry.at < TOMBSTONE_RETENTION_MS)
+        .collect();
+    if kept.len() * 2 > total {
+        return Ok(false);
+    }
+    kept.sort_by(|a, b| (a.at, &a.id).cmp(&(b.at, &b.id)));
+
+    let mut lines = header_line(device_id)?;
+    for entry in kept.iter() {
+        lines.push_str(&serde_json::to_string(entry).map_err(|e| format!("Error serializing entry: {}", e))?);
+        lines.push('\n');
+    }
+
+    // Renamed over the file so the sync tool never sees it half written
+    let partial_file = own_file.with_extension("partial");
+    fs::write(&partial_file, lines).map_err(|e| format!("Error writing {:?}: {}", partial_file, e))?;
+    fs::rename(&partial_file, own_file).map_err(|e| format!("Error moving {:?}: {}", partial_file, e))?;
+
+    Ok(true)
+}
+
+/// Reads the files of the other devices that changed since last time, each one in its own transaction.
+async fn import(folder: &Path, device_id: &str, pool: &Pool<Sqlite>) -> Result<(usize, Vec<String>), String> {
+    let files = fs::read_dir(folder).map_err(|e| format!("Error reading {:?}: {}", folder, e))?;
+    let mut imported = 0;
+    let mut skipped_files = vec![];
+
+    for file in files.flatten() {
+        let file_name = file.file_name().to_string_lossy().to_string();
+        // Conflict copies and partial files have other names
+        let Some(peer) = file_name.strip_suffix(&format!(".{}", FILE_EXTENSION)) else { continue };
+        if peer == device_id || !is_ulid(peer) {
+            continue;
+        }
+
+        let Ok(metadata) = file.metadata() else { continue };
+        let modified_at = metadata.modified().ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|modified| modified.as_millis() as i64)
+            .unwrap_or_default();
+        let size = metadata.len() as i64;
+
+        let known = sqlx::query_as::<_, (i64, i64)>(include_str!("queries/get_exchange_peer.sql"))
+            .bind(&file_name)
+            .fetch_optional(pool).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+        if known == Some((modified_at, size)) {
+            continue;
+        }
+
+        let entries = fs::read(file.path())
+            .map_err(|e| e.to_string())
+            .and_then(|content| String::from_utf8(content).map_err(|e| e.to_string()))
+            .and_then(|content| read_entries(&content, peer));
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("Skipping the state exchange file {}: {}", file_name, e);
+                skipped_files.push(file_name);
+                continue;
+            }
+        };
+
+        let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+        imported += merge(&entries, peer, &mut transaction).await?;
+        sqlx::query(include_str!("queries/save_exchange_peer.sql"))
+            .bind(&file_name)
+            .bind(modified_at)
+            .bind(size)
+            .execute(&mut *transaction).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+        transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))?;
+    }
+
+    Ok((imported, skipped_files))
+}
+
+/// Stores the entries newer than the known values, then applies the latest one of each field. Storing them
+/// all first lets a feed subscribed to get the name sent along with it, and applying only the latest keeps the
+/// triggers from taking an outdated value for a local change.
+async fn merge(entries: &[ExchangeEntry], peer: &str, connection: &mut SqliteConnection) -> Result<usize, String> {
+    let mut newer: HashMap<(&str, &str, &str), usize> = HashMap::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let stored = sqlx::query_scalar::<_, i64>(include_str!("queries/merge_exchange_field.sql"))
+            .bind(&entry.entity)
+            .bind(&entry.key)
+            .bind(&entry.field)
+            .bind(&entry.value)
+            .bind(entry.at)
+            .bind(&entry.id)
+            .bind(peer)
+            .fetch_optional(&mut *connection).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+        if stored.is_some() {
+            newer.insert((&entry.entity, &entry.key, &entry.field), index);
+        }
+    }
+
+    let mut latest: Vec<usize> = newer.into_values().collect();
+    latest.sort();
+    for index in latest.iter() {
+        apply(&entries[*index], connection).await?;
+    }
+
+    Ok(latest.len())
+}
+
+/// Applies a change to the feeds and articles. The triggers see the stored value, and don't export it back.
+async fn apply(entry: &ExchangeEntry, connection: &mut SqliteConnection) -> Result<(), String> {
+    let query = match (entry.entity.as_str(), entry.field.as_str(), entry.value.as_deref()) {
+        ("article", "read", Some(read @ ("0" | "1"))) => {
+            // Articles not fetched yet get the read state when they are, see the migration
+            let Some((feed_url, link)) = entry.key.split_once('\n') else { return Ok(()) };
+            let read: i64 = read.parse().unwrap_or_default();
+            sqlx::query(include_str!("queries/apply_exchange_read.sql"))
+                .bind(read)
+                .bind(feed_url.to_string())
+                .bind(link.to_string())
+        }
+        ("feed", "subscribed", Some(_)) => sqlx::query(include_str!("queries/apply_exchange_subscribe.sql"))
+            .bind(ulid())
+            .bind(entry.key.clone()),
+        // Archived feeds were archived to be kept
+        ("feed", "subscribed", None) => sqlx::query(include_str!("queries/apply_exchange_unsubscribe.sql"))
+            .bind(entry.key.clone()),
+        ("feed", "display_name", Some(display_name)) => sqlx::query(include_str!("queries/apply_exchange_display_name.sql"))
+            .bind(display_name.to_string())
+            .bind(entry.key.clone()),
+        _ => return Ok(()),
+    };
+
+    query.execute(&mut *connection).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(())
+}
+
+/// Parses a file written by `device`. The last line is left out unless complete, it may still be syncing.
+fn read_entries(content: &str, device: &str) -> Result<Vec<ExchangeEntry>, String> {
+    let mut lines: Vec<&str> = content.split('\n').collect();
+    // Empty when the file ends with a newline, cut otherwise
+    lines.pop();
+
+    let mut lines = lines.into_iter();
+    let header: ExchangeHeader = lines.next()
+        .and_then(|line| serde_json::from_str(line).ok())
+        .ok_or_else(|| String::from("missing or invalid header"))?;
+    if header.format != FORMAT {
+        return Err(format!("unsupported format {}", header.format));
+    }
+    if header.device != device {
+        return Err(format!("written by device {}", header.device));
+    }
+
+    let mut entries = vec![];
+    let mut invalid = 0;
+    for line in lines.filter(|line| !line.trim().is_empty()) {
+        match serde_json::from_str::<ExchangeEntry>(line) {
+            Ok(entry) => entries.push(entry),
+            Err(_) => invalid += 1,
+        }
+    }
+    if invalid > 0 {
+        eprintln!("Skipped {} invalid entries of the state exchange file of {}", invalid, header.device);
+    }
+
+    Ok(entries)
+}
+
+fn header_line(device_id: &str) -> Result<String, String> {
+    let header = ExchangeHeader { format: FORMAT, device: device_id.to_string() };
+    serde_json::to_string(&header)
+        .map(|header| format!("{}\n", header))
+        .map_err(|e| format!("Error serializing header: {}", e))
+}
+
+fn ends_with_newline(file: &mut File) -> bool {
+    let mut last = [0u8; 1];
+    file.seek(SeekFrom::End(-1)).and_then(|_| file.read_exact(&mut last)).is_ok() && last[0] == b'\n'
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::path::PathBuf;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::database::migrator::migrator;
+    use super::*;
+
+    const URLS: [&str; 3] = ["https://a.example/feed", "https://b.example/feed", "https://c.example/feed"];
+    const LINKS: u64 = 4;
+
+    /// xorshift64, so every failing seed can be replayed.
+    struct Random(u64);
+
+    impl Random {
+        fn below(&mut self, bound: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % bound
+        }
+    }
+
+    async fn device() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        pool
+    }
+
+    fn folder(name: &str) -> PathBuf {
+        let folder = std::env::temp_dir().join(format!("alduin-exchange-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    async fn subscribed(pool: &Pool<Sqlite>) -> Vec<(String, String)> {
+        sqlx::query_as("SELECT url, display_name FROM feeds ORDER BY url").fetch_all(pool).await.unwrap()
+    }
+
+    async fn read_state(pool: &Pool<Sqlite>) -> Vec<(String, String, bool)> {
+        sqlx::query_as("SELECT feeds.url, articles.link, articles.read FROM articles JOIN feeds ON feeds.identifier = articles.feed_identifier ORDER BY 1, 2")
+            .fetch_all(pool).await.unwrap()
+    }
+
+    async fn fields(pool: &Pool<Sqlite>) -> Vec<(String, String, String, Option<String>, i64, Option<String>, String)> {
+        sqlx::query_as("SELECT entity, key, field, value, changed_at, ulid, origin FROM exchange_fields ORDER BY entity, key, field")
+            .fetch_all(pool).await.unwrap()
+    }
+
+    async fn fetch(url: &str, link: u64, pool: &Pool<Sqlite>) {
+        sqlx::query("
+            INSERT INTO articles (identifier, feed_identifier, title, content, date, link)
+            SELECT ?1, identifier, 'article', '', 0, ?2 FROM feeds
+            WHERE url = ?3 AND NOT EXISTS (SELECT 1 FROM articles WHERE feed_identifier = feeds.identifier AND link = ?2)
+        ")
+            .bind(ulid())
+            .bind(format!("{}/{}", url, link))
+            .bind(url)
+            .execute(pool).await.unwrap();
+    }
+
+    /// A change made by the user on a device, or a file written or copied badly by the tool syncing the folder.
+    async fn act(random: &mut Random, folder: &Path, pool: &Pool<Sqlite>) {
+        let url = URLS[random.below(URLS.len() as u64) as usize];
+        match random.below(8) {
+            0 => {
+                sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval) SELECT ?, ?, ?, 5 WHERE NOT EXISTS (SELECT 1 FROM feeds WHERE url = ?3)")
+                    .bind(ulid()).bind(url).bind(url)
+                    .execute(pool).await.unwrap();
+            }
+            1 => { sqlx::query("DELETE FROM feeds WHERE url = ?").bind(url).execute(pool).await.unwrap(); }
+            2 => {
+                sqlx::query("UPDATE feeds SET display_name = ? WHERE url = ?")
+                    .bind(format!("name {}", random.below(3))).bind(url)
+                    .execute(pool).await.unwrap();
+            }
+            3 => fetch(url, random.below(LINKS), pool).await,
+            4 => {
+                sqlx::query("UPDATE articles SET read = NOT read WHERE link = ?")
+                    .bind(format!("{}/{}", url, random.below(LINKS)))
+                    .execute(pool).await.unwrap();
+            }
+            5 => { exchange(folder, pool).await.unwrap(); }
+            // A line cut by a crash in the middle of an export
+            6 => {
+                let device_id: Option<String> = sqlx::query_scalar(include_str!("queries/get_exchange_device.sql")).fetch_optional(pool).await.unwrap();
+                let own_file = device_id.and_then(|device_id| OpenOptions::new().append(true).open(folder.join(format!("{}.{}", device_id, FILE_EXTENSION))).ok());
+                if let Some(mut file) = own_file {
+                    file.write_all(br#"{"id":"01J"#).unwrap();
+                }
+            }
+            // A conflict copy, of a file that may be partial
+            _ => if let Some(file) = fs::read_dir(folder).unwrap().flatten().next() {
+                let content = fs::read(file.path()).unwrap();
+                let copy = format!("{}.sync-conflict-{}.{}", file.file_name().to_string_lossy(), random.below(100), FILE_EXTENSION);
+                fs::write(folder.join(copy), &content[..random.below(content.len() as u64 + 1) as usize]).unwrap();
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn three_devices_converge_whatever_the_interleaving() {
+        for seed in 1..=30u64 {
+            let folder = folder(&seed.to_string());
+            let mut random = Random(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+            let devices = [device().await, device().await, device().await];
+
+            for _ in 0..80 {
+                let device = &devices[random.below(3) as usize];
+                act(&mut random, &folder, device).await;
+            }
+
+            // Settled: every device exports then reads the others, twice as the first ones read stale files
+            for _ in 0..2 {
+                for device in devices.iter() {
+                    exchange(&folder, device).await.unwrap();
+                }
+            }
+            // Every device fetched every article, the late ones taking the read state exchanged before
+            for (url, _) in subscribed(&devices[0]).await {
+                for device in devices.iter() {
+                    for link in 0..LINKS {
+                        fetch(&url, link, device).await;
+                    }
+                }
+            }
+
+            for device in devices[1..].iter() {
+                assert_eq!(subscribed(device).await, subscribed(&devices[0]).await, "seed {}", seed);
+                assert_eq!(read_state(device).await, read_state(&devices[0]).await, "seed {}", seed);
+                assert_eq!(fields(device).await, fields(&devices[0]).await, "seed {}", seed);
+            }
+            fs::remove_dir_all(&folder).unwrap();
+        }
+    }
+
+    #[test]
+    fn reads_complete_lines_of_the_expected_device() {
+        let device = ulid();
+        let header = header_line(&device).unwrap();
+        let entry = r#"{"id":"01","at":1,"entity":"feed","key":"https://a.example/feed","field":"subscribed","value":"1"}"#;
+
+        let cases = [
+            (format!("{}{}\n", header, entry), Ok(1)),
+            // Still syncing
+            (format!("{}{}", header, entry), Ok(0)),
+            (format!("{}not json\n{}\n", header, entry), Ok(1)),
+            (format!("{}\n", entry), Err(String::from("missing or invalid header"))),
+            (format!("{}{}\n", header.replace("\"format\":1", "\"format\":2"), entry), Err(String::from("unsupported format 2"))),
+            (format!("{}{}\n", header_line("other").unwrap(), entry), Err(String::from("written by device other"))),
+        ];
+
+        for case in cases.iter() {
+            let entries = read_entries(&case.0, &device).map(|entries| entries.len());
+            assert_eq!(entries, case.1, "{:?}", case);
+        }
+    }
+
+    #[test]
+    fn compacts_to_the_latest_entry_of_each_field() {
+        let folder = folder("compact");
+        let device = ulid();
+        let own_file = folder.join(format!("{}.{}", device, FILE_EXTENSION));
+        let now = chrono::Utc::now().timestamp_millis();
+        let entry = |at: i64, key: &str, value: Option<&str>| ExchangeEntry {
+            id: format!("{:026}", at), at, entity: String::from("feed"), key: key.to_string(), field: String::from("display_name"), value: value.map(str::to_string),
+        };
+
+        let mut lines = header_line(&device).unwrap();
+        let renames = (0..20_000).map(|at| entry(now - 20_000 + at, "https://a.example/feed", Some("renamed again and again")));
+        let tombstones = [entry(0, "https://old.example/feed", None), entry(now, "https://b.example/feed", None)];
+        for entry in renames.chain(tombstones) {
+            lines.push_str(&serde_json::to_string(&entry).unwrap());
+            lines.push('\n');
+        }
+        fs::write(&own_file, lines).unwrap();
+
+        assert!(compact(&own_file, &device).unwrap());
+        let kept: BTreeSet<(String, i64)> = read_entries(&fs::read_to_string(&own_file).unwrap(), &device).unwrap()
+            .into_iter()
+            .map(|entry| (entry.key, entry.at))
+            .collect();
+        // The old tombstone is dropped, the recent one kept for the devices that didn't read it yet
+        assert_eq!(kept, BTreeSet::from([(String::from("https://a.example/feed"), now - 1), (String::from("https://b.example/feed"), now)]));
+        assert!(!compact(&own_file, &device).unwrap());
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -0,0 +1,40 @@
+use std::net::SocketAddr;
+use serde::{Deserialize, Serialize};
+
+/// Which addresses of a host feeds are fetched from, for hosts publishing addresses they don't answer on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum AddressFamily {
+    /// Any address, IPv6 and IPv4 ones raced as the system orders them. On a feed, follows the preference
+    #[default]
+    Auto,
+    Ipv4,
+    Ipv6,
+}
+
+impl AddressFamily {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AddressFamily::Auto => "auto",
+            AddressFamily::Ipv4 => "ipv4",
+            AddressFamily::Ipv6 => "ipv6",
+        }
+    }
+
+    /// The family of a feed, `default` being the one of the preferences.
+    pub fn or(self, default: AddressFamily) -> AddressFamily {
+        if self == AddressFamily::Auto { default } else { self }
+    }
+
+    /// Keeps the addresses of this family, in the order they were resolved in.
+    pub fn filter(&self, addresses: impl IntoIterator<Item = SocketAddr>) -> Vec<SocketAddr> {
+        addresses.into_iter()
+            .filter(|address| match self {
+                AddressFamily::Auto => true,
+                AddressFamily::Ipv4 => address.is_ipv4(),
+                AddressFamily::Ipv6 => address.is_ipv6(),
+            })
+            .collect()
+    }
+}
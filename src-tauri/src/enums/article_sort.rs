@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArticleSort {
+    /// By publication date
+    #[default]
+    Date,
+    Score,
+    /// By when Alduin first stored the article, so the archive of a new feed doesn't top the timeline
+    FirstSeen,
+}
+
+impl ArticleSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArticleSort::Date => "date",
+            ArticleSort::Score => "score",
+            ArticleSort::FirstSeen => "first_seen",
+        }
+    }
+}
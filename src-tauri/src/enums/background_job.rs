@@ -0,0 +1,25 @@
+use serde::Serialize;
+
+/// Work the app starts on its own, see `structs::activity_monitor`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackgroundJob {
+    /// The background sync and the priority pass
+    Sync,
+    Backfill,
+    /// The prune after a sync, the ones asked for in the preferences running right away
+    Prune,
+    /// Tidying the metrics, the change journal and the notification ledger
+    Maintenance,
+}
+
+impl BackgroundJob {
+    /// Whether the job waits for the user to be idle, rather than being safe to run while the app is used.
+    /// Idle-only jobs hold the write lock for long or rewrite many pages, which makes scrolling stutter.
+    pub fn idle_only(&self) -> bool {
+        match self {
+            BackgroundJob::Sync => false,
+            BackgroundJob::Backfill | BackgroundJob::Prune | BackgroundJob::Maintenance => true,
+        }
+    }
+}
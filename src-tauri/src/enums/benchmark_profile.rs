@@ -0,0 +1,56 @@
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+/// Size of the synthetic database a benchmark runs against.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BenchmarkProfile {
+    /// A few months of a typical subscription list
+    Small,
+    /// Years of a heavy user, where slow queries show
+    Large,
+}
+
+impl BenchmarkProfile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BenchmarkProfile::Small => "small",
+            BenchmarkProfile::Large => "large",
+        }
+    }
+
+    pub fn feeds(&self) -> usize {
+        match self {
+            BenchmarkProfile::Small => 25,
+            BenchmarkProfile::Large => 400,
+        }
+    }
+
+    pub fn articles(&self) -> usize {
+        match self {
+            BenchmarkProfile::Small => 5_000,
+            BenchmarkProfile::Large => 100_000,
+        }
+    }
+
+    /// The 95th percentile a page of the timeline must load within while a sync writes.
+    pub fn read_target_during_sync(&self) -> Duration {
+        match self {
+            BenchmarkProfile::Small => Duration::from_millis(100),
+            BenchmarkProfile::Large => Duration::from_millis(400),
+        }
+    }
+
+    /// The 95th percentile a page of the All Articles view must load within, anywhere in the history.
+    pub fn timeline_target(&self) -> Duration {
+        Duration::from_millis(20)
+    }
+
+    /// How far back the articles are spread.
+    pub fn history_days(&self) -> u64 {
+        match self {
+            BenchmarkProfile::Small => 90,
+            BenchmarkProfile::Large => 2 * 365,
+        }
+    }
+}
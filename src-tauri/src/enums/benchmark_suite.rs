@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// An operation timed by a benchmark. Suites run in this order whatever order they are asked in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BenchmarkSuite {
+    /// Walking the timeline and a feed page by page, in every sort
+    Pagination,
+    /// The feed list with its unread counts
+    UnreadCounts,
+    /// Full-text searches, from a single common word to phrases and exclusions
+    Search,
+    /// Storing fetched articles, half of them already known
+    SyncUpsert,
+    /// Counting the read articles a purge of old ones would delete
+    PurgePreview,
+    /// Scrolling the All Articles view both ways from dates across the history, all and unread only
+    Timeline,
+    /// Loading the timeline while a heavy sync writes, after the others as it adds articles
+    ReadsDuringSync,
+    /// Parsing and storing fetched feeds, each worker writing its own feed as sync did before the writer
+    SyncSequential,
+    /// The same feeds stored through the writer of the run, as sync does
+    SyncPipelined,
+}
+
+impl BenchmarkSuite {
+    pub const ALL: [BenchmarkSuite; 9] = [
+        BenchmarkSuite::Pagination,
+        BenchmarkSuite::UnreadCounts,
+        BenchmarkSuite::Search,
+        BenchmarkSuite::SyncUpsert,
+        BenchmarkSuite::PurgePreview,
+        BenchmarkSuite::Timeline,
+        BenchmarkSuite::ReadsDuringSync,
+        BenchmarkSuite::SyncSequential,
+        BenchmarkSuite::SyncPipelined,
+    ];
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// What a row of the change journal is about, its identifier being the one of the article or the feed.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum ChangeEntity {
+    Article,
+    Feed,
+    ReadingQueue,
+}
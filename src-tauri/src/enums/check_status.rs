@@ -0,0 +1,32 @@
+use serde::Serialize;
+
+/// Outcome of a self-test check, ordered from best to worst.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    /// Doesn't apply where the self-test ran, like the scheduler with `--self-test`. Left out of the verdict
+    Skip,
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckStatus::Skip => "skip",
+            CheckStatus::Pass => "pass",
+            CheckStatus::Warn => "warn",
+            CheckStatus::Fail => "fail",
+        }
+    }
+
+    /// The exit code of `--self-test`, as monitoring plugins use them: 1 on warnings, 2 on failures.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CheckStatus::Skip | CheckStatus::Pass => 0,
+            CheckStatus::Warn => 1,
+            CheckStatus::Fail => 2,
+        }
+    }
+}
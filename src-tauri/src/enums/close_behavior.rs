@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+/// What the close button of the main window does.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CloseBehavior {
+    /// Hides to the tray, unless the tray is known to be unavailable
+    #[default]
+    Auto,
+    Tray,
+    Quit,
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// How articles of a feed are recognized as already known.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum DedupStrategy {
+    #[default]
+    Guid,
+    /// For feeds whose GUIDs change over time, by normalized link and title
+    LinkTitle,
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum EnclosureRole {
+    /// The audio or video file of a podcast episode, or any other attached file
+    Media,
+    Transcript,
+    /// Podcasting 2.0 JSON chapters
+    Chapters,
+}
+
+impl EnclosureRole {
+    /// Role of a plain enclosure, subtitle files being attached as a second enclosure by some podcasts.
+    pub fn from_mime_type(mime_type: Option<&str>) -> Self {
+        match mime_type {
+            Some("text/vtt" | "text/srt" | "application/srt" | "application/x-subrip") => EnclosureRole::Transcript,
+            Some("application/json+chapters") => EnclosureRole::Chapters,
+            _ => EnclosureRole::Media,
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use serde::Serialize;
+
+/// What part of the decision trail of an article a factor of `explain_article` comes from.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExplanationKind {
+    Feed,
+    /// The feed, score and read state filters of the view
+    Filter,
+    Score,
+    ScoringRule,
+    Dedup,
+    ReadState,
+    PostProcessor,
+    /// Where the sort of the view puts it
+    Position,
+}
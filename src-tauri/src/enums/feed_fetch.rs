@@ -0,0 +1,8 @@
+use crate::structs::feed_validators::FeedValidators;
+
+#[derive(Debug)]
+pub enum FeedFetch {
+    Modified { content: String, validators: FeedValidators },
+    /// A 304, with the validators it repeated, if any
+    NotModified(FeedValidators),
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+/// Feeds are synced tier by tier in this order, high priority ones also by the priority pass.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum FeedPriority {
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+impl FeedPriority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedPriority::High => "high",
+            FeedPriority::Normal => "normal",
+            FeedPriority::Low => "low",
+        }
+    }
+}
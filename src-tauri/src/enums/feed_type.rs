@@ -19,3 +19,14 @@ impl From<feed_rs::model::FeedType> for FeedType {
         }
     }
 }
+
+impl FeedType {
+    /// As stored in `feeds.type`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedType::Atom => "atom",
+            FeedType::RSS => "rss",
+            FeedType::JSON => "json",
+        }
+    }
+}
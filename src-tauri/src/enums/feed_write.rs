@@ -0,0 +1,9 @@
+use crate::structs::feed_completion::FeedCompletion;
+use crate::structs::prepared_article::PreparedArticle;
+
+#[derive(Debug)]
+pub enum FeedWrite {
+    Articles(Vec<PreparedArticle>),
+    /// Last of a feed, without which its articles are rolled back
+    Finish(FeedCompletion),
+}
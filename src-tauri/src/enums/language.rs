@@ -0,0 +1,68 @@
+use chrono::Weekday;
+use serde::{Deserialize, Serialize};
+
+/// Variables checked in this order for the language of the system, as by `setlocale`.
+const LOCALE_VARIABLES: [&str; 3] = ["LC_ALL", "LC_TIME", "LANG"];
+
+/// The language dates are written in by `utils::time_format`. Unsupported ones fall back to English.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Language {
+    /// The language of the system, see `resolve`
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "fr")]
+    French,
+    #[serde(rename = "de")]
+    German,
+    #[serde(rename = "es")]
+    Spanish,
+}
+
+impl Language {
+    /// The language of a tag like `fr`, `de-CH` or `es_ES.UTF-8`, none when unsupported.
+    pub fn from_tag(tag: &str) -> Option<Language> {
+        let code = tag.split(['-', '_', '.', '@']).next().unwrap_or_default().to_ascii_lowercase();
+        match code.as_str() {
+            "en" => Some(Language::English),
+            "fr" => Some(Language::French),
+            "de" => Some(Language::German),
+            "es" => Some(Language::Spanish),
+            _ => None,
+        }
+    }
+
+    /// `Auto` replaced by the language of the system. Only Unix-like systems tell it through the environment,
+    /// others get English until the frontend saves the language of the webview.
+    pub fn resolve(self) -> Language {
+        if self != Language::Auto {
+            return self;
+        }
+
+        LOCALE_VARIABLES.iter()
+            .filter_map(|variable| std::env::var(variable).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Language::from_tag(&value))
+            .unwrap_or(Language::English)
+    }
+
+    /// The first day of the weeks of the statistics, Sunday as in the United States for English.
+    pub fn week_start(&self) -> Weekday {
+        match self {
+            Language::Auto => self.resolve().week_start(),
+            Language::English => Weekday::Sun,
+            Language::French | Language::German | Language::Spanish => Weekday::Mon,
+        }
+    }
+
+    /// Whether `count` takes the singular, French using it for 0 too.
+    pub fn is_singular(&self, count: i64) -> bool {
+        match self {
+            Language::Auto => self.resolve().is_singular(count),
+            Language::French => count == 0 || count == 1,
+            Language::English | Language::German | Language::Spanish => count == 1,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+/// Actions offered on a link right-clicked in an article.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkAction {
+    /// Finds the feeds of the linked page, or the linked feed itself
+    Subscribe,
+    CopyClean,
+}
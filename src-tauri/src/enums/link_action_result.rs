@@ -0,0 +1,9 @@
+use serde::Serialize;
+use crate::structs::feed_candidate::FeedCandidate;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LinkActionResult {
+    FeedCandidates { candidates: Vec<FeedCandidate> },
+    CleanUrl { url: String },
+}
@@ -0,0 +1,25 @@
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, Serialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum LocalMetric {
+    SyncsRun,
+    ArticlesIngested,
+    ArticlesRead,
+    /// Time the main window had the focus
+    SessionSeconds,
+    Searches,
+}
+
+impl LocalMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LocalMetric::SyncsRun => "syncs_run",
+            LocalMetric::ArticlesIngested => "articles_ingested",
+            LocalMetric::ArticlesRead => "articles_read",
+            LocalMetric::SessionSeconds => "session_seconds",
+            LocalMetric::Searches => "searches",
+        }
+    }
+}
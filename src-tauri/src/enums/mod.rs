@@ -1 +1,36 @@
 pub mod feed_type;
+pub mod scoring_condition;
+pub mod article_sort;
+pub mod dedup_strategy;
+pub mod feed_change_source;
+pub mod feed_priority;
+pub mod enclosure_role;
+pub mod transcript_format;
+pub mod search_term;
+pub mod tray_status;
+pub mod close_behavior;
+pub mod session_state;
+pub mod link_action;
+pub mod link_action_result;
+pub mod local_metric;
+pub mod export_format;
+pub mod window_placement;
+pub mod change_entity;
+pub mod benchmark_profile;
+pub mod benchmark_suite;
+pub mod power_source;
+pub mod address_family;
+pub mod feed_fetch;
+pub mod read_state;
+pub mod window_status;
+pub mod feed_write;
+pub mod explanation_kind;
+pub mod check_status;
+pub mod schema_failure;
+pub mod user_agent;
+pub mod override_kind;
+pub mod language;
+pub mod timestamp_style;
+pub mod page_direction;
+pub mod notification_notice;
+pub mod background_job;
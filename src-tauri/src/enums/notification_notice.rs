@@ -0,0 +1,20 @@
+use crate::structs::notification_candidate::NotificationCandidate;
+
+/// A notification let through by `NotificationLimiter`.
+#[derive(Debug)]
+pub enum NotificationNotice {
+    /// The new articles of a feed, with the ones held back since its last notification. No articles when
+    /// only held back ones came due
+    Feed { feed_name: String, candidates: Vec<NotificationCandidate>, held_back: usize },
+    /// Too many feeds notifying at once, shown as one
+    Summary { articles: usize, feeds: usize },
+}
+
+impl NotificationNotice {
+    pub fn articles(&self) -> usize {
+        match self {
+            NotificationNotice::Feed { candidates, held_back, .. } => candidates.len() + held_back,
+            NotificationNotice::Summary { articles, .. } => *articles,
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use serde::Serialize;
+
+/// A network setting a feed can have of its own, in place of the defaults every other feed is fetched with.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverrideKind {
+    UserAgent,
+    AddressFamily,
+    Headers,
+    BasicAuth,
+    Cookies,
+    ClientCertificate,
+    CaCertificate,
+}
+
+impl OverrideKind {
+    /// In the order of the audit. A setting added to the feeds must be added here, and described by
+    /// `FeedNetworkSettings::describe`, to be audited.
+    pub const ALL: [OverrideKind; 7] = [
+        OverrideKind::UserAgent,
+        OverrideKind::AddressFamily,
+        OverrideKind::Headers,
+        OverrideKind::BasicAuth,
+        OverrideKind::Cookies,
+        OverrideKind::ClientCertificate,
+        OverrideKind::CaCertificate,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OverrideKind::UserAgent => "user agent",
+            OverrideKind::AddressFamily => "address family",
+            OverrideKind::Headers => "headers",
+            OverrideKind::BasicAuth => "basic auth",
+            OverrideKind::Cookies => "cookies",
+            OverrideKind::ClientCertificate => "client certificate",
+            OverrideKind::CaCertificate => "CA certificate",
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// Which way `get_timeline` pages from its cursor, pages always listing the newest article first.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PageDirection {
+    /// Scrolling down, towards the oldest articles
+    #[default]
+    Older,
+    /// Scrolling up, towards the newest articles
+    Newer,
+}
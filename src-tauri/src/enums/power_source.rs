@@ -0,0 +1,11 @@
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    /// Not read yet, or no detection on this platform, treated as AC
+    #[default]
+    Unknown,
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadState {
+    /// Never read, marked unread again, or updated since read in a feed asking to reread updates
+    Unread,
+    Read,
+    /// Changed by a sync after it was last read, whether or not it was marked unread again since
+    UpdatedSinceRead,
+}
+
+impl ReadState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReadState::Unread => "unread",
+            ReadState::Read => "read",
+            ReadState::UpdatedSinceRead => "updated_since_read",
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use serde::Serialize;
+
+/// Why the database couldn't be brought to the schema of this version at startup. It is then opened
+/// read-only, so nothing writes to a schema half understood.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SchemaFailure {
+    /// The database file couldn't be created or opened
+    Open { message: String },
+    /// The migration failed and was rolled back, the ones before it staying applied
+    Migration { version: i64, message: String },
+    /// Recorded as not applied successfully, which a migration ended midway leaves behind
+    Interrupted { version: i64 },
+    /// Applied by a version of the app with other migrations under the same number
+    Modified { version: i64 },
+    /// The migrations ran, yet the database doesn't report the schema this version expects
+    VersionMismatch { applied: i64, expected: i64 },
+}
+
+impl SchemaFailure {
+    pub fn message(&self) -> String {
+        match self {
+            SchemaFailure::Open { message } => format!("The database couldn't be opened: {}", message),
+            SchemaFailure::Migration { version, message } => format!("Migration {} failed: {}", version, message),
+            SchemaFailure::Interrupted { version } => format!("Migration {} was interrupted by a previous run", version),
+            SchemaFailure::Modified { version } => format!("Migration {} was applied by another version of Alduin", version),
+            SchemaFailure::VersionMismatch { applied, expected } => format!("The database is at schema {} instead of {}", applied, expected),
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum ScoringCondition {
+    /// Case-insensitive match on the title
+    Keyword(String),
+    /// Match on the title or the content
+    Regex(String),
+    Feed(String),
+    /// A feed of the folder or of one of its subfolders
+    Folder(String),
+    /// A language tag, "en" also matching "en-GB"
+    Language(String),
+    MinWords(u32),
+    MaxWords(u32),
+}
@@ -0,0 +1,16 @@
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum SearchTerm {
+    /// A word or quoted phrase, looked up in the title and the content
+    Text(String),
+    Title(String),
+    /// Display name of the feed, case-insensitive
+    Feed(String),
+    Read(bool),
+    /// Published before the start of that local day, in milliseconds
+    Before(i64),
+    /// Published on that local day or later, in milliseconds
+    After(i64),
+}
@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum SessionState {
+    /// Cookies saved but not used by a successful sync yet
+    New,
+    Working,
+    /// Rejected after having worked, until new cookies are saved
+    Expired,
+}
@@ -0,0 +1,16 @@
+use serde::Deserialize;
+
+/// How `utils::time_format` writes a timestamp, in the local time zone.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampStyle {
+    /// Like "5 minutes ago" or "in 2 days"
+    Relative,
+    /// Like "Jan 31, 2024"
+    Date,
+    /// Like "Jan 31, 2024, 2:05 PM"
+    #[default]
+    DateTime,
+    /// Like "2:05 PM"
+    Time,
+}
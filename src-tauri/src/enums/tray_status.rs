@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrayStatus {
+    Available,
+    /// No host to show the tray icon, so a hidden window couldn't be brought back
+    Unavailable,
+    /// Not probed yet, or the probe couldn't run
+    #[default]
+    Unknown,
+}
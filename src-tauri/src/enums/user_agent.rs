@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+const PROJECT_URL: &str = "https://github.com/uniuuu/alduin";
+/// Firefox ESR on Windows, as common a browser as any and slow to change version
+const BROWSER_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:128.0) Gecko/20100101 Firefox/128.0";
+
+/// The user agent a feed is fetched with. Alduin tells what it is, unless the site of the feed refuses it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum UserAgent {
+    /// `Alduin/<version>` with the address of the project, for the site to know who to reach
+    #[default]
+    Honest,
+    /// The user agent of a browser, for the single feeds blocking Alduin's
+    Browser,
+}
+
+impl UserAgent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserAgent::Honest => "honest",
+            UserAgent::Browser => "browser",
+        }
+    }
+
+    pub fn header(&self, version: &str) -> String {
+        match self {
+            UserAgent::Honest => format!("Alduin/{} (+{})", version, PROJECT_URL),
+            UserAgent::Browser => String::from(BROWSER_USER_AGENT),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use serde::Deserialize;
+
+/// Where the main window shows up from the tray.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowPlacement {
+    /// Wherever it was last, as restored by the window state plugin
+    #[default]
+    Last,
+    Primary,
+    /// The monitor the tray icon was clicked on
+    Cursor,
+}
@@ -0,0 +1,13 @@
+use serde::Serialize;
+
+/// What `close_splashscreen` and `open_main_window` did, calling them again doing nothing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowStatus {
+    Opened,
+    AlreadyOpen,
+    /// The main window didn't exist, or was lost with its webview process, and was created again
+    Recreated,
+    Closed,
+    AlreadyClosed,
+}
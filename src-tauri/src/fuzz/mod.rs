@@ -0,0 +1,215 @@
+//! Fuzzes the parsers of untrusted input, behind the `fuzz` feature: `alduin --fuzz [target] [seconds]`,
+//! every target for a minute by default.
+//!
+//! The inputs of each target in `fuzz/regressions`, minimized from past crashes, are replayed first and
+//! must all pass. Inputs are then mutated from them and from the seeds in `fuzz/corpus`, blindly as the
+//! binary isn't instrumented for coverage. A panicking or slow input is saved in `fuzz/artifacts`, and every
+//! input is written there as `last` before it runs, for what aborts the process, like a stack overflow.
+//! Parsers run on a thread with the stack of an async worker, as in the app.
+//! The tests replay the seeds and the regressions of every target.
+
+pub mod mutator;
+pub mod targets;
+
+use std::any::Any;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use xxhash_rust::xxh32::xxh32;
+use crate::benchmark::random::SeededRandom;
+use crate::fuzz::mutator::mutate;
+use crate::fuzz::targets::{FuzzTarget, TARGETS};
+
+pub const FUZZ_ARG: &str = "--fuzz";
+const DEFAULT_SECONDS: u64 = 60;
+/// Parsers taking longer on a 64 KiB input are quadratic somewhere
+const SLOW_INPUT: Duration = Duration::from_secs(1);
+const WORKER_STACK_BYTES: usize = 2 * 1024 * 1024;
+/// Inputs kept to mutate further, so mutations pile up
+const MAX_CORPUS_INPUTS: usize = 2_000;
+
+static LAST_PANIC: Mutex<Option<String>> = Mutex::new(None);
+
+/// Runs the targets named after `--fuzz` and returns the exit code, 1 when an input crashed.
+pub fn run_from_args(args: &[String]) -> i32 {
+    let options: Vec<&String> = args.iter().skip_while(|arg| *arg != FUZZ_ARG).skip(1).collect();
+    let (name, seconds) = match options.first().map(|option| option.parse::<u64>()) {
+        Some(Ok(seconds)) => (None, seconds),
+        Some(Err(_)) => (options.first().map(|name| name.as_str()), options.get(1).and_then(|seconds| seconds.parse().ok()).unwrap_or(DEFAULT_SECONDS)),
+        None => (None, DEFAULT_SECONDS),
+    };
+
+    let targets: Vec<&'static FuzzTarget> = TARGETS.iter().filter(|target| name.is_none_or(|name| name == target.name)).collect();
+    if targets.is_empty() {
+        let names: Vec<&str> = TARGETS.iter().map(|target| target.name).collect();
+        eprintln!("Unknown fuzz target, expected one of {}", names.join(", "));
+        return 2;
+    }
+
+    panic::set_hook(Box::new(|info| {
+        *LAST_PANIC.lock().unwrap() = Some(info.to_string());
+    }));
+
+    let failed = thread::Builder::new()
+        .stack_size(WORKER_STACK_BYTES)
+        .spawn(move || targets.iter().filter(|target| !fuzz(target, Duration::from_secs(seconds))).count())
+        .and_then(|worker| worker.join().map_err(|_| std::io::Error::other("the fuzzer panicked")))
+        .unwrap_or_else(|e| {
+            eprintln!("Error running the fuzzer: {}", e);
+            1
+        });
+
+    if failed > 0 { 1 } else { 0 }
+}
+
+/// Parses one input like libFuzzer's `LLVMFuzzerTestOneInput`, for a coverage guided fuzzer to call.
+/// Returns the panic message when it panics.
+pub fn test_one_input(target: &FuzzTarget, data: &[u8]) -> Option<String> {
+    panic::catch_unwind(AssertUnwindSafe(|| (target.run)(data)))
+        .err()
+        .map(|payload| LAST_PANIC.lock().unwrap().take().unwrap_or_else(|| panic_message(payload)))
+}
+
+/// Whether the target passed its regressions and no input crashed or was slow within `duration`.
+fn fuzz(target: &FuzzTarget, duration: Duration) -> bool {
+    let seeds = read_inputs(&fuzz_dir().join("corpus").join(target.name));
+    let regressions = read_inputs(&fuzz_dir().join("regressions").join(target.name));
+    let artifacts = fuzz_dir().join("artifacts").join(target.name);
+    if let Err(e) = fs::create_dir_all(&artifacts) {
+        eprintln!("Error creating {}: {}", artifacts.display(), e);
+        return false;
+    }
+
+    let mut passed = true;
+    for (path, input) in &regressions {
+        if let Some(message) = test_one_input(target, input) {
+            eprintln!("{}: regression {} crashed again: {}", target.name, path.display(), message);
+            passed = false;
+        }
+    }
+
+    let mut corpus: Vec<Vec<u8>> = seeds.into_iter().chain(regressions).map(|(_, input)| input).collect();
+    if corpus.is_empty() {
+        corpus.push(vec![]);
+    }
+    let mut random = SeededRandom::new(xxh32(target.name.as_bytes(), 0));
+    let (mut inputs, mut crashes, mut slow) = (0, 0, 0);
+    let started = Instant::now();
+
+    while started.elapsed() < duration {
+        let base = &corpus[random.below(corpus.len() as u64) as usize];
+        let input = mutate(base, &corpus, target.dictionary, &mut random);
+        let _ = fs::write(artifacts.join("last"), &input);
+
+        let run_started = Instant::now();
+        let crash = test_one_input(target, &input);
+        let elapsed = run_started.elapsed();
+        inputs += 1;
+
+        let kind = match crash {
+            Some(message) => {
+                crashes += 1;
+                eprintln!("{}: crash, {}", target.name, message);
+                "crash"
+            }
+            None if elapsed > SLOW_INPUT => {
+                slow += 1;
+                eprintln!("{}: slow input, {} ms", target.name, elapsed.as_millis());
+                "slow"
+            }
+            None => {
+                if random.chance(0.01) && corpus.len() < MAX_CORPUS_INPUTS {
+                    corpus.push(input);
+                }
+                continue;
+            }
+        };
+        let artifact = artifacts.join(format!("{}-{:08x}", kind, xxh32(&input, 0)));
+        if let Err(e) = fs::write(&artifact, &input) {
+            eprintln!("Error writing {}: {}", artifact.display(), e);
+        }
+    }
+
+    let _ = fs::remove_file(artifacts.join("last"));
+    println!("{}: {} inputs in {} s, {} crashes, {} slow", target.name, inputs, duration.as_secs(), crashes, slow);
+    passed && crashes == 0 && slow == 0
+}
+
+fn fuzz_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz")
+}
+
+/// The files of `dir`, none when it doesn't exist.
+fn read_inputs(dir: &Path) -> Vec<(PathBuf, Vec<u8>)> {
+    let Ok(entries) = fs::read_dir(dir) else { return vec![] };
+    let mut inputs: Vec<(PathBuf, Vec<u8>)> = entries.flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| fs::read(&path).ok().map(|input| (path, input)))
+        .collect();
+    inputs.sort();
+    inputs
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    payload.downcast_ref::<&str>().map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| String::from("panicked"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input_dirs(kind: &str) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(fuzz_dir().join(kind)).unwrap().flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn keeps_seeds_and_regressions_for_every_target() {
+        let mut names: Vec<&str> = TARGETS.iter().map(|target| target.name).collect();
+        names.sort();
+
+        for kind in ["corpus", "regressions"] {
+            assert_eq!(input_dirs(kind), names, "{}", kind);
+            for target in &TARGETS {
+                assert!(!read_inputs(&fuzz_dir().join(kind).join(target.name)).is_empty(), "{} {}", kind, target.name);
+            }
+        }
+    }
+
+    #[test]
+    fn replays_the_corpus_and_the_regressions() {
+        let failures = thread::Builder::new()
+            .stack_size(WORKER_STACK_BYTES)
+            .spawn(|| {
+                let mut failures = vec![];
+                for target in &TARGETS {
+                    for kind in ["corpus", "regressions"] {
+                        for (path, input) in read_inputs(&fuzz_dir().join(kind).join(target.name)) {
+                            let started = Instant::now();
+                            if let Some(message) = test_one_input(target, &input) {
+                                failures.push(format!("{} crashed: {}", path.display(), message));
+                            } else if !cfg!(debug_assertions) && started.elapsed() > SLOW_INPUT {
+                                failures.push(format!("{} took {} ms", path.display(), started.elapsed().as_millis()));
+                            }
+                        }
+                    }
+                }
+                failures
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+
+        assert!(failures.is_empty(), "{:#?}", failures);
+    }
+}
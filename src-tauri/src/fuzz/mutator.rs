@@ -0,0 +1,59 @@
+use crate::benchmark::random::SeededRandom;
+
+/// Longer inputs only slow the runs down, the ceilings of the parsers are checked by their own inputs
+pub const MAX_INPUT_BYTES: usize = 64 * 1024;
+const INTERESTING_BYTES: [u8; 12] = [0, 0xff, 0x80, b'<', b'>', b'"', b'\'', b':', b'-', b'\n', b'9', b'&'];
+/// Repeated ranges build the deep nesting and long runs the parsers must stay linear on
+const MAX_REPEATS: u64 = 256;
+
+/// Applies a few random mutations to `input`, splicing from `corpus` and inserting from `dictionary`.
+pub fn mutate(input: &[u8], corpus: &[Vec<u8>], dictionary: &[&str], random: &mut SeededRandom) -> Vec<u8> {
+    let mut data = input.to_vec();
+
+    for _ in 0..random.between(1, 4) {
+        let position = random.below(data.len() as u64 + 1) as usize;
+        match random.below(8) {
+            0 if !data.is_empty() => {
+                let index = position.min(data.len() - 1);
+                data[index] ^= 1 << random.below(8);
+            }
+            1 if !data.is_empty() => {
+                let index = position.min(data.len() - 1);
+                data[index] = INTERESTING_BYTES[random.below(INTERESTING_BYTES.len() as u64) as usize];
+            }
+            2 if !dictionary.is_empty() => {
+                let token = dictionary[random.below(dictionary.len() as u64) as usize];
+                data.splice(position..position, token.bytes());
+            }
+            3 => {
+                let end = random.between(position as u64, data.len() as u64) as usize;
+                data.drain(position..end);
+            }
+            4 => {
+                let end = random.between(position as u64, (position + 64).min(data.len()) as u64) as usize;
+                let range = data[position..end].to_vec();
+                let repeats = random.skewed(MAX_REPEATS) as usize + 1;
+                data.splice(end..end, range.iter().copied().cycle().take(range.len() * repeats));
+            }
+            5 if !corpus.is_empty() => {
+                let other = &corpus[random.below(corpus.len() as u64) as usize];
+                let start = random.below(other.len() as u64 + 1) as usize;
+                let end = random.between(start as u64, other.len() as u64) as usize;
+                data.splice(position..position, other[start..end].iter().copied());
+            }
+            6 => data.truncate(position),
+            _ => {
+                let number = match random.below(4) {
+                    0 => i64::MAX.to_string(),
+                    1 => u64::MAX.to_string(),
+                    2 => String::from("-1"),
+                    _ => String::from("99999999999999999999999"),
+                };
+                data.splice(position..position, number.bytes());
+            }
+        }
+    }
+
+    data.truncate(MAX_INPUT_BYTES);
+    data
+}
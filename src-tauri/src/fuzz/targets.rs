@@ -0,0 +1,83 @@
+use url::Url;
+use crate::parsers::bookmarks::parse_bookmarks;
+use crate::parsers::feed_content::parse_feed;
+use crate::parsers::feed_links::feed_links;
+use crate::parsers::item_extensions::parse_item_extensions;
+use crate::parsers::outbound_links::outbound_links;
+use crate::parsers::transcript::parse_transcript;
+use crate::search::query::parse_query;
+use crate::structs::article::Article;
+use crate::utils::cookies::parse_cookie_export;
+use crate::utils::opml::parse_opml;
+
+pub struct FuzzTarget {
+    pub name: &'static str,
+    /// Parses one input like the parser's callers do, results ignored
+    pub run: fn(&[u8]),
+    /// Inserted by the mutations, closer to what the parser expects than random bytes
+    pub dictionary: &'static [&'static str],
+}
+
+const XML: [&str; 14] = ["<?xml version=\"1.0\"?>", "<rss>", "</rss>", "<channel>", "<item>", "</item>", "<![CDATA[", "]]>", "&amp;", "&#x", " xmlns:a=\"b\"", "<a:b>", "<entry>", "<content src=\"https://a\" type=\"text/html\"/>"];
+
+pub const TARGETS: [FuzzTarget; 9] = [
+    FuzzTarget {
+        name: "feed",
+        // Through `Article::from` too, which the sync runs on every entry
+        run: |data| {
+            if let Ok((feed, _, _)) = parse_feed(&String::from_utf8_lossy(data)) {
+                for entry in feed.entries {
+                    let _ = Article::from(entry);
+                }
+            }
+        },
+        dictionary: &XML,
+    },
+    FuzzTarget {
+        name: "item_extensions",
+        run: |data| { parse_item_extensions(data, &[String::from("media")]); },
+        dictionary: &["<item>", "</item>", "<entry>", "<enclosure url=\"a\" length=\"99999999999999999999\"/>", "<media:content url=\"a\"/>", "<wfw:commentRss>", "<comments>", "<georss:point>", "<category domain=\"a\">"],
+    },
+    FuzzTarget {
+        name: "opml",
+        run: |data| { let _ = parse_opml(data); },
+        dictionary: &["<opml>", "<body>", "</body>", "<outline text=\"a\">", "</outline>", "<outline xmlUrl=\"a\"/>", " category=\"a/b/c\"", "<?xml encoding=\"utf-16\"?>"],
+    },
+    FuzzTarget {
+        name: "bookmarks",
+        run: |data| { let _ = parse_bookmarks(&String::from_utf8_lossy(data)); },
+        dictionary: &["<DL>", "</DL>", "<DT>", "<H3>", "</H3>", "<A HREF=\"https://a\">", "</A>", "&amp;", "&#"],
+    },
+    FuzzTarget {
+        name: "cookies",
+        run: |data| { let _ = parse_cookie_export(&String::from_utf8_lossy(data), "example.com", 0); },
+        dictionary: &["curl ", "-H ", "'Cookie: a=b'", "-b ", "\"", "'", "\\\n", "\t", "#HttpOnly_", ".example.com\tTRUE\t/\tFALSE\t0\ta\tb\n"],
+    },
+    FuzzTarget {
+        name: "transcript",
+        run: |data| {
+            let content = String::from_utf8_lossy(data);
+            let _ = parse_transcript(&content, Some("text/vtt"), "a.vtt");
+            let _ = parse_transcript(&content, None, "a.srt");
+        },
+        dictionary: &["WEBVTT\n\n", " --> ", "00:00:01.000", "99999999999999999:59:59.999", "-1", "\n\n", "<v Speaker>", "NOTE", ","],
+    },
+    FuzzTarget {
+        name: "search_query",
+        run: |data| { let _ = parse_query(&String::from_utf8_lossy(data)); },
+        dictionary: &[" OR ", " AND ", "-", "\"", "title:", "feed:", "is:read", "before:2024-01-31", "after:", "tag:", "é"],
+    },
+    FuzzTarget {
+        name: "feed_links",
+        run: |data| {
+            let base = Url::parse("https://example.com/blog/").unwrap();
+            feed_links(&String::from_utf8_lossy(data), &base);
+        },
+        dictionary: &["<link rel=\"alternate\" type=\"application/rss+xml\" href=\"", "\">", "<link ", "href=", "type=application/feed+json", "&amp;", "//", "../"],
+    },
+    FuzzTarget {
+        name: "outbound_links",
+        run: |data| { outbound_links(&String::from_utf8_lossy(data), Some("https://example.com/a")); },
+        dictionary: &["<a href=\"", "\">", "<a ", "href='", "https://", "&amp;", "#", "?"],
+    },
+];
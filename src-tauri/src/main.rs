@@ -5,62 +5,228 @@ pub mod commands;
 pub mod structs;
 pub mod enums;
 pub mod database;
+pub mod parsers;
+pub mod scheduler;
+pub mod utils;
+pub mod scoring;
+pub mod post_processors;
+pub mod search;
+pub mod benchmark;
+pub mod secrets;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
 
-use std::fs;
-use std::time::Duration;
-use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
-use commands::fetcher::{sync, sync_all};
-use commands::splashscreen::{close_splashscreen, open_main_window};
+use commands::fetcher::{sync, sync_all, debug_fetch};
+use commands::opml::{import_opml, import_feeds, export_opml};
+use commands::bookmarks::{inspect_bookmarks_html, import_bookmarks_html, cancel_bookmarks_discovery};
+use commands::comments::{watch_comments, unwatch_comments, get_article_comments};
+use commands::feeds::{find_duplicate_feed, find_duplicate_feeds, get_editable_feed, update_feed, archive_feed, remove_feed};
+use commands::scoring::{get_scoring_rules, save_scoring_rule, delete_scoring_rule, preview_scoring};
+use commands::feed_changes::get_feed_changes;
+use commands::enclosures::{get_article_enclosures, get_article_transcript};
+use commands::articles::{get_articles, get_smart_counts, set_article_read, get_article_content, get_article_content_chunk};
+use commands::session::{save_session_state, get_session_state, clear_session_state};
+use commands::dedup::{get_dedup_strategy, set_dedup_strategy};
+use commands::maintenance::{freeze_writes, thaw_writes, get_pool_health, get_resource_stats, get_schema_info, get_command_timings, get_version_mismatch, get_schema_failure, get_preferences_upgrade};
+use commands::post_processors::{get_post_processors, set_post_processor, get_article_post_processors};
+use commands::search::{search_articles, explain_search};
+use commands::explain::explain_article;
+use commands::cookies::{update_feed_cookies, parse_feed_cookies};
+use commands::credentials::{set_feed_credentials, set_feed_headers};
+use commands::certificates::{set_feed_client_certificate, set_feed_ca_certificate};
+use commands::retention::{preview_purge, prune_articles, project_storage};
+use commands::links::{link_action, find_articles_linking_to};
+use commands::webview::{needs_webview_probe, report_webview_capabilities, get_webview_capabilities};
+use commands::metrics::{get_local_metrics, export_local_metrics, clear_local_metrics};
+use commands::reading_queue::{queue_article, dequeue_article, reorder_queue, get_queue, pop_next_in_queue};
+use commands::export::{export_everything, cancel_export, get_default_export_directory, set_default_export_directory};
+use commands::availability::{get_feed_health, clear_feed_unavailability};
+use commands::extras::{get_feed_extras, set_feed_extras, get_article_details};
+use commands::changes::{ack_changes, get_missed_changes};
+use commands::benchmark::run_benchmark;
+use commands::scheduler::{get_scheduler_state, report_activity, get_background_sync, set_sync_interval, pause_background_sync, resume_background_sync};
+use commands::state_exchange::exchange_state;
+use commands::quick_add::{add_feed_from_clipboard, take_add_feed_request};
+use commands::list_windows::{open_list_window, get_list_window, forget_list_window, is_list_window};
+use commands::overrides::get_override_audit;
+use commands::formatting::format_timestamp;
+use commands::notifications::get_recent_notifications;
+use commands::timeline::{get_timeline, jump_to_date};
+use commands::self_test::{run_self_test, run_self_test_from_tray, run_self_test_from_cli, SELF_TEST_ARG};
+use structs::write_gate::WriteGate;
+use structs::tray_monitor::TrayMonitor;
+use structs::focus_tracker::FocusTracker;
+use structs::power_monitor::PowerMonitor;
+use structs::activity_monitor::ActivityMonitor;
+use structs::background_sync::BackgroundSync;
+use structs::read_pool::ReadPool;
+use structs::power_policy::PowerPolicy;
+use structs::main_window::MainWindow;
+use structs::quick_add::QuickAdd;
+use structs::list_windows::ListWindows;
+use structs::notification_limiter::NotificationLimiter;
+use structs::preferences_cache::PreferencesCache;
+use enums::local_metric::LocalMetric;
+use commands::splashscreen::{self, close_splashscreen, open_main_window};
 use structs::single_instance_payload::SingleInstancePayload;
-use tauri::{generate_handler, generate_context, Manager, Builder, SystemTray, SystemTrayEvent, SystemTrayMenu, CustomMenuItem, AppHandle, Wry};
+use tauri::{generate_handler, generate_context, Manager, Builder, SystemTray, SystemTrayEvent, AppHandle, Wry, WindowEvent, RunEvent};
 use tauri::async_runtime::block_on;
 use tauri_plugin_autostart::MacosLauncher;
 use tauri_plugin_window_state::StateFlags;
-use crate::database::load_migrations;
+use crate::database::{backfill, change_journal, compatibility, health, local_metrics, migrator, state_exchange, READ_CONNECTIONS};
+use crate::scheduler::{background, idle, power, priority_pass};
+use crate::commands::autostart::{get_autostart_status, repair_autostart};
+use crate::structs::preferences::Preferences;
+use crate::structs::preferences_upgrade::PreferencesUpgrade;
+use crate::utils::preferences_schema::unchanged;
+use crate::structs::schema_check::SchemaCheck;
+use crate::utils::autostart::{launch_args, start_delay};
+use crate::utils::tray::{tray_menu, ADD_FROM_CLIPBOARD, RUN_DIAGNOSTICS};
+use crate::commands::tray::{close_main_window, get_tray_status, watch_tray};
 
 fn show_main_window(app: &AppHandle<Wry>) {
-    let window = app.get_window("main").unwrap();
-    window.show().unwrap();
+    if let Err(e) = splashscreen::show_main_window(app) {
+        eprintln!("{}", e);
+    }
 }
 
+/// List windows outlive the main window, except when quitting, which keeps them for the next session.
 fn fully_close_app(app: &AppHandle<Wry>) {
-    let window = app.get_window("main").unwrap();
-    window.close().unwrap();
+    if !app.state::<ListWindows>().is_empty() {
+        return app.exit(0);
+    }
+    match app.get_window("main") {
+        Some(window) => if let Err(e) = window.close() {
+            eprintln!("Error closing window: {}", e);
+            app.exit(0);
+        },
+        None => app.exit(0),
+    }
 }
 
 fn main() {
-    let quit = CustomMenuItem::new("quit".to_string(), "Quit");
-    let show = CustomMenuItem::new("show".to_string(), "Show Alduin");
+    #[cfg(feature = "fuzz")]
+    if std::env::args().any(|arg| arg == fuzz::FUZZ_ARG) {
+        std::process::exit(fuzz::run_from_args(&std::env::args().collect::<Vec<String>>()));
+    }
 
-    let tray_menu = SystemTrayMenu::new()
-        .add_item(show)
-        .add_item(quit);
     let system_tray = SystemTray::new()
-        .with_menu(tray_menu);
+        .with_menu(tray_menu(&PowerPolicy::default(), false));
+
+    let context = generate_context!();
+    // Before the builder, so neither the single instance plugin nor the migrations get in the way
+    if std::env::args().any(|arg| arg == SELF_TEST_ARG) {
+        std::process::exit(block_on(run_self_test_from_cli(context.config())));
+    }
+    // Upgraded before the store plugin of the frontend reads the file
+    let (preferences, preferences_upgrade) = tauri::api::path::app_data_dir(context.config())
+        .map(|dir| Preferences::load_upgrading(&dir))
+        .unwrap_or_else(|| (Preferences::default(), unchanged()));
+    // The plugin wants 'static arguments and keeps them for the whole run, so they are leaked once
+    let autostart_args: Vec<&'static str> = launch_args(&preferences).into_iter()
+        .map(|arg| &*Box::leak(arg.into_boxed_str()))
+        .collect();
 
     let mut flags = StateFlags::all();
     flags.remove(StateFlags::VISIBLE);
 
+    // Checked and migrated before anything else opens the database, the SQL plugin of the frontend included
+    let app_version = context.package_info().version.to_string();
+    let database_file = tauri::api::path::app_config_dir(context.config()).map(|dir| dir.join("alduin.db"));
+    let mut schema_check = database_file.as_ref()
+        .map(|file| block_on(compatibility::check_schema(file, &app_version)))
+        .unwrap_or_default();
+    if let Some(file) = &database_file {
+        if let Err(failure) = block_on(migrator::migrate(file, &schema_check)) {
+            eprintln!("{}, opening the database read-only", failure.message());
+            schema_check.failure = Some(failure);
+        }
+    }
+    let write_gate = match (&schema_check.mismatch, &schema_check.failure) {
+        (Some(mismatch), _) => WriteGate::read_only(format!("The database was upgraded by a newer version of Alduin than {}", mismatch.app_version)),
+        (None, Some(failure)) => WriteGate::read_only(failure.message()),
+        (None, None) => WriteGate::default(),
+    };
+
+    let handler = generate_handler![sync, sync_all, debug_fetch, import_opml, import_feeds, export_opml, inspect_bookmarks_html, import_bookmarks_html, cancel_bookmarks_discovery, close_splashscreen, open_main_window, watch_comments, unwatch_comments, get_article_comments, find_duplicate_feed, find_duplicate_feeds, get_scoring_rules, save_scoring_rule, delete_scoring_rule, preview_scoring, get_articles, get_smart_counts, save_session_state, get_session_state, clear_session_state, get_dedup_strategy, set_dedup_strategy, freeze_writes, thaw_writes, get_post_processors, set_post_processor, get_article_post_processors, get_pool_health, get_editable_feed, update_feed, set_article_read, get_feed_changes, get_article_enclosures, get_article_transcript, archive_feed, remove_feed, get_resource_stats, get_autostart_status, search_articles, explain_search, get_tray_status, close_main_window, update_feed_cookies, parse_feed_cookies, set_feed_credentials, set_feed_headers, set_feed_client_certificate, set_feed_ca_certificate, link_action, get_schema_info, prune_articles, find_articles_linking_to, needs_webview_probe, report_webview_capabilities, get_webview_capabilities, get_local_metrics, export_local_metrics, clear_local_metrics, get_article_content, get_article_content_chunk, queue_article, dequeue_article, reorder_queue, get_queue, pop_next_in_queue, export_everything, cancel_export, get_feed_health, clear_feed_unavailability, get_command_timings, get_version_mismatch, get_schema_failure, get_preferences_upgrade, get_feed_extras, set_feed_extras, get_article_details, ack_changes, get_missed_changes, run_benchmark, get_scheduler_state, report_activity, get_background_sync, set_sync_interval, pause_background_sync, resume_background_sync, exchange_state, take_add_feed_request, get_default_export_directory, set_default_export_directory, explain_article, run_self_test, open_list_window, get_list_window, project_storage, get_override_audit, format_timestamp, get_recent_notifications, get_timeline, jump_to_date, preview_purge];
+
     Builder::default()
         .plugin(tauri_plugin_window_state::Builder::default()
             .with_state_flags(flags)
             .build())
-        .plugin(tauri_plugin_sql::Builder::default().add_migrations("sqlite:alduin.db", load_migrations()).build())
+        .plugin(tauri_plugin_sql::Builder::default().build())
         .plugin(tauri_plugin_store::Builder::default().build())
-        .plugin(tauri_plugin_autostart::init(MacosLauncher::LaunchAgent, Some(vec!["--autostart"])))
+        .plugin(tauri_plugin_autostart::init(MacosLauncher::LaunchAgent, Some(autostart_args)))
         .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
             app.emit_all("single-instance", SingleInstancePayload { args: argv, cwd }).unwrap();
         }))
-        .invoke_handler(generate_handler![sync, sync_all, close_splashscreen, open_main_window])
+        .manage(write_gate)
+        .manage(schema_check)
+        .manage(preferences_upgrade)
+        .manage(TrayMonitor::default())
+        .manage(FocusTracker::default())
+        .manage(PowerMonitor::default())
+        .manage(ActivityMonitor::default())
+        .manage(BackgroundSync::new(preferences.background_sync_minutes))
+        .manage(MainWindow::default())
+        .manage(QuickAdd::default())
+        .manage(ListWindows::default())
+        .manage(NotificationLimiter::default())
+        .manage(PreferencesCache::default())
+        // Postpones the idle-only jobs, see `structs::activity_monitor`
+        .invoke_handler(move |invoke| {
+            idle::record_command(invoke.message.command(), &invoke.message.window().app_handle());
+            handler(invoke)
+        })
+        .on_window_event(|event| {
+            // Of any window, the list windows being used too
+            if let WindowEvent::Focused(_) = event.event() {
+                idle::record_activity(&event.window().app_handle());
+            }
+            // Events emitted while a window was reloading or not yet listening are caught up on
+            if let WindowEvent::Focused(true) = event.event() {
+                tauri::async_runtime::spawn(change_journal::catch_up(event.window().clone()));
+            }
+            if let WindowEvent::CloseRequested { .. } = event.event() {
+                if is_list_window(event.window().label()) {
+                    let label = event.window().label().to_string();
+                    tauri::async_runtime::spawn(forget_list_window(label, event.window().app_handle()));
+                }
+            }
+            if event.window().label() != "main" {
+                return;
+            }
+            let handle = event.window().app_handle();
+            match event.event() {
+                WindowEvent::Focused(true) => handle.state::<FocusTracker>().focus(),
+                WindowEvent::Focused(false) => if let Some(focused) = handle.state::<FocusTracker>().blur() {
+                    tauri::async_runtime::spawn(async move {
+                        local_metrics::record(&handle, LocalMetric::SessionSeconds, focused.as_secs() as i64).await;
+                    });
+                },
+                WindowEvent::CloseRequested { .. } => handle.state::<MainWindow>().expect_close(),
+                // Not asked to close, so its webview process most likely crashed
+                WindowEvent::Destroyed if handle.state::<MainWindow>().destroyed() => {
+                    eprintln!("The main window was destroyed without being closed, recreating it");
+                    tauri::async_runtime::spawn(async move { splashscreen::recreate_main_window(&handle) });
+                }
+                _ => {}
+            }
+        })
         .system_tray(system_tray)
         .on_system_tray_event(|app, event| match event {
             SystemTrayEvent::DoubleClick {
-                position: _,
+                position,
                 size: _,
                 ..
             } => {
+                app.state::<TrayMonitor>().record_click(position.x, position.y);
                 show_main_window(app)
             }
+            // Opens the menu, whose show item should use the monitor of the click too
+            SystemTrayEvent::RightClick { position, .. } => {
+                app.state::<TrayMonitor>().record_click(position.x, position.y);
+            }
             SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
                 "quit" => {
                     fully_close_app(app)
@@ -68,125 +234,102 @@ fn main() {
                 "show" => {
                     show_main_window(app)
                 }
+                "sync" => {
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(async move { background::sync_now(&app).await });
+                }
+                ADD_FROM_CLIPBOARD => {
+                    tauri::async_runtime::spawn(add_feed_from_clipboard(app.clone()));
+                }
+                RUN_DIAGNOSTICS => {
+                    tauri::async_runtime::spawn(run_self_test_from_tray(app.clone()));
+                }
                 _ => {}
             },
             _ => {}
         })
-        .setup(|app| {
+        .setup(move |app| {
              block_on(async move {
                  let handle = app.handle();
-                 
-                 eprintln!("=== ALDUIN DATABASE SETUP DEBUG ===");
-                 eprintln!("Starting database initialization...");
-
-                 // Use plugin's config directory to access same database file
-                 let app_dir = if let Some(native_dir) = handle.path_resolver().app_config_dir() {
-                     eprintln!("✅ Using Tauri app config directory: {:?}", native_dir);
-                     native_dir
-                 } else if let Ok(home) = std::env::var("HOME") {
-                     let fallback_dir = std::path::PathBuf::from(&home).join(".config/io.stouder.alduin");
-                     eprintln!("⚠️  Falling back to HOME/.config/io.stouder.alduin: {:?}", fallback_dir);
-                     fallback_dir
-                 } else {
-                     let emergency_dir = std::path::PathBuf::from("./data");
-                     eprintln!("🚨 Emergency fallback to ./data: {:?}", emergency_dir);
-                     emergency_dir
-                 };
-
-                 // Debug: Log path information  
-                 eprintln!("Plugin config directory: {:?}", app_dir);
-                 eprintln!("Directory exists: {}", app_dir.exists());
 
-                 // Connect to existing plugin database
-                 let sqlite_path = app_dir.join("alduin.db");
-                 
-                 eprintln!("Plugin database path: {:?}", sqlite_path);
-                 eprintln!("Database file exists: {}", sqlite_path.exists());
-                 
-                 if sqlite_path.exists() {
-                     if let Ok(metadata) = fs::metadata(&sqlite_path) {
-                         eprintln!("Database file size: {} bytes", metadata.len());
-                         eprintln!("Database file readonly: {}", metadata.permissions().readonly());
-                     }
-                 } else {
-                     eprintln!("⚠️  Plugin database not yet created, will retry connection...");
+                 if let Err(e) = repair_autostart(&handle) {
+                     eprintln!("Error repairing autostart: {}", e);
                  }
 
-                 // Create connection options without create_if_missing (plugin handles creation)
-                 let connect_options = SqliteConnectOptions::new()
-                     .filename(&sqlite_path);
+                 tauri::async_runtime::spawn(watch_tray(handle.clone()));
 
-                 // Wait for plugin initialization and database creation
-                 eprintln!("Waiting for plugin initialization and database creation...");
-                 tokio::time::sleep(Duration::from_millis(1000)).await;
+                 // Plugins are set up by now, single-instance included, so a second launch is still caught during the delay
+                 let args: Vec<String> = std::env::args().collect();
+                 if let Some(delay) = start_delay(&args) {
+                     eprintln!("Delaying startup by {}s", delay.as_secs());
+                     tokio::time::sleep(delay).await;
+                 }
                  
-                 // Verify plugin database exists before connecting
-                 if !sqlite_path.exists() {
-                     eprintln!("⚠️  Plugin database still not found, waiting longer...");
-                     tokio::time::sleep(Duration::from_millis(2000)).await;
-                     
-                     if !sqlite_path.exists() {
-                         eprintln!("❌ Plugin database not found after extended wait");
-                         eprintln!("❌ Expected location: {:?}", sqlite_path);
-                         panic!("Plugin database creation failed or path mismatch");
+                 // Migrated in main, before the SQL plugin could open it
+                 let Some(sqlite_path) = database_file else {
+                     panic!("No config directory to keep the database in");
+                 };
+                 let schema_check = handle.state::<SchemaCheck>();
+                 let connect_options = database::connect_options(&sqlite_path, schema_check.mismatch.is_some() || schema_check.failure.is_some());
+
+                 health::watch_file(&sqlite_path);
+
+                 let db = match database::pool_options().connect_with(connect_options).await {
+                     Ok(pool) => pool,
+                     Err(e) => panic!("Failed to connect to SQLite at {}: {}", sqlite_path.display(), e),
+                 };
+
+                 tauri::async_runtime::spawn(health::keep_alive(db.clone()));
+                 match (&schema_check.mismatch, &schema_check.failure) {
+                     (Some(mismatch), _) => if let Err(e) = handle.emit_all("startup:version-mismatch", mismatch) {
+                         eprintln!("Error emitting version mismatch: {}", e);
+                     },
+                     (None, Some(failure)) => if let Err(e) = handle.emit_all("startup:schema-failure", failure) {
+                         eprintln!("Error emitting schema failure: {}", e);
+                     },
+                     (None, None) => compatibility::record_min_app_version(&handle.package_info().version.to_string(), &db).await,
+                 }
+                 let preferences_upgrade = handle.state::<PreferencesUpgrade>();
+                 if preferences_upgrade.is_notable() {
+                     if let Err(e) = handle.emit_all("startup:preferences-reset", preferences_upgrade.inner().clone()) {
+                         eprintln!("Error emitting preferences upgrade: {}", e);
                      }
                  }
-
-                 // Connect to plugin database
-                 eprintln!("Connecting to plugin database...");
-                 let mut connection_attempts = 0;
-                 let max_attempts = 3;
-                 
-                 let db = loop {
-                     connection_attempts += 1;
-                     eprintln!("Plugin database connection attempt {}/{}", connection_attempts, max_attempts);
-                     
-                     match SqlitePool::connect_with(connect_options.clone()).await {
-                         Ok(pool) => {
-                             eprintln!("✅ Plugin database connection successful on attempt {}", connection_attempts);
-                             break pool;
-                         }
-                         Err(e) => {
-                             eprintln!("❌ SQLite connection failed on attempt {}: {}", connection_attempts, e);
-                             eprintln!("Connection options - filename: {:?}", sqlite_path);
-                             eprintln!("Database path: {:?}", sqlite_path);
-                             eprintln!("Directory exists: {}", app_dir.exists());
-                             eprintln!("Database file exists: {}", sqlite_path.exists());
-                             
-                             // Additional debugging for SQLite-specific errors
-                             match e {
-                                 sqlx::Error::Database(ref db_err) => {
-                                     eprintln!("Database error code: {:?}", db_err.code());
-                                     eprintln!("Database error message: {}", db_err.message());
-                                 }
-                                 _ => {
-                                     eprintln!("Non-database error: {:?}", e);
-                                 }
-                             }
-                             
-                             if connection_attempts >= max_attempts {
-                                 eprintln!("❌ All connection attempts failed. Final error: {}", e);
-                                 panic!("Failed to connect to SQLite after {} attempts: {}", max_attempts, e);
-                             }
-                             
-                             // Wait before retrying with exponential backoff
-                             let delay = Duration::from_millis(100 * connection_attempts as u64);
-                             eprintln!("Retrying in {}ms...", delay.as_millis());
-                             tokio::time::sleep(delay).await;
-                         }
+                 // Opened once the writing connections switched the database to WAL
+                 let read_pool = match database::pool_options().max_connections(READ_CONNECTIONS).connect_with(database::connect_options(&sqlite_path, true)).await {
+                     Ok(read_pool) => read_pool,
+                     Err(e) => {
+                         eprintln!("Error opening the read-only connections, reading through the others: {}", e);
+                         db.clone()
                      }
                  };
-
-                 eprintln!("✅ Plugin database connection established!");
-                 eprintln!("Registering unified database with app state...");
+                 app.manage(ReadPool::new(read_pool));
                  app.manage(db);
+                 if let Err(e) = handle.tray_handle().get_item(ADD_FROM_CLIPBOARD).set_enabled(true) {
+                     eprintln!("Error enabling tray item: {}", e);
+                 }
+                 tauri::async_runtime::spawn(priority_pass::run(handle.clone()));
+                 tauri::async_runtime::spawn(background::run(handle.clone()));
+                 tauri::async_runtime::spawn(backfill::run_backfills(handle.clone()));
+                 // Also runs the idle maintenance, once on AC
+                 tauri::async_runtime::spawn(power::watch_power(handle.clone()));
+                 tauri::async_runtime::spawn(state_exchange::run(handle.clone()));
+                 tauri::async_runtime::spawn(secrets::forget_orphaned_at_startup(handle.clone()));
                  eprintln!("✅ Unified database registered with app state");
                  eprintln!("=== UNIFIED DATABASE SETUP COMPLETE ===");
 
                  Ok(())
             })
         })
-        .run(generate_context!())
-        .expect("error while running tauri application");
+        .build(context)
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Closing the last window exits, which losing the main window shouldn't do
+            if let RunEvent::ExitRequested { api, .. } = event {
+                if app.state::<MainWindow>().is_recreating() {
+                    api.prevent_exit();
+                }
+            }
+        });
 }
 
@@ -0,0 +1,88 @@
+use std::sync::LazyLock;
+use quick_xml::escape::unescape;
+use regex::Regex;
+use crate::structs::bookmark::Bookmark;
+use crate::utils::input_limits::{too_large, MAX_BOOKMARKS_BYTES, MAX_FOLDER_DEPTH};
+
+/// The tags that matter in the Netscape bookmark format, which browsers export as unclosed HTML:
+/// `<H3>` names the folder of the next `<DL>` list, `<A>` is a bookmark.
+static TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)<(/?)(h3|a|dl)\b([^>]*)>").unwrap());
+static HREF: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)\bhref\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap()
+});
+static MARKUP: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<[^>]*>").unwrap());
+
+/// Reads the bookmarks of a browser export in document order, whatever their scheme. Bookmarks without
+/// a title are named after their URL. Folders nested deeper than `MAX_FOLDER_DEPTH` are flattened into
+/// their ancestor.
+pub fn parse_bookmarks(html: &str) -> Result<Vec<Bookmark>, String> {
+    if html.len() > MAX_BOOKMARKS_BYTES {
+        return Err(too_large("The bookmarks export", MAX_BOOKMARKS_BYTES));
+    }
+
+    let mut bookmarks = vec![];
+    // Whether each open list is a folder, and the names of the folders, the deeper ones left out
+    let mut stack: Vec<bool> = vec![];
+    let mut folders: Vec<String> = vec![];
+    let mut folder_name: Option<String> = None;
+    // The attributes of the open link, none for a heading, and where its text starts. Left unclosed
+    // before the next tag, it's dropped rather than named after the rest of the file.
+    let mut open: Option<(Option<&str>, usize)> = None;
+
+    for captures in TAG.captures_iter(html) {
+        let tag = captures.get(0).unwrap();
+        let closing = !captures[1].is_empty();
+        let name = captures[2].to_ascii_lowercase();
+
+        if let Some((attributes, start)) = open.take() {
+            let closed_by = if attributes.is_some() { "a" } else { "h3" };
+            if closing && name == closed_by {
+                let content = text(&html[start..tag.start()]);
+                match attributes {
+                    Some(attributes) => bookmarks.extend(bookmark(attributes, content, &folders)),
+                    None => folder_name = Some(content),
+                }
+                continue;
+            }
+        }
+
+        match (closing, name.as_str()) {
+            (false, "h3") => open = Some((None, tag.end())),
+            (false, "a") => open = Some((Some(captures.get(3).map_or("", |attributes| attributes.as_str())), tag.end())),
+            (false, _) => {
+                // The lists of the root have no heading before them
+                let folder = folder_name.take().filter(|_| folders.len() < MAX_FOLDER_DEPTH);
+                stack.push(folder.is_some());
+                folders.extend(folder);
+            }
+            (true, "dl") if stack.pop() == Some(true) => {
+                folders.pop();
+            }
+            _ => {}
+        }
+    }
+
+    Ok(bookmarks)
+}
+
+/// A bookmark of `<A>` attributes, none without a URL.
+fn bookmark(attributes: &str, title: String, folders: &[String]) -> Option<Bookmark> {
+    let href = HREF.captures(attributes)?;
+    let url = text(href.get(1).or(href.get(2)).or(href.get(3)).map_or("", |url| url.as_str()));
+    if url.is_empty() {
+        return None;
+    }
+
+    Some(Bookmark {
+        title: if title.is_empty() { url.clone() } else { title },
+        url,
+        folder: folders.to_vec(),
+    })
+}
+
+/// Without markup, entities decoded when they can be and whitespace collapsed.
+fn text(html: &str) -> String {
+    let stripped = MARKUP.replace_all(html, "");
+    let decoded = unescape(&stripped).map(|text| text.to_string()).unwrap_or_else(|_| stripped.to_string());
+    decoded.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
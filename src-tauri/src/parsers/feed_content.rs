@@ -0,0 +1,86 @@
+use std::sync::LazyLock;
+use feed_rs::model::Feed;
+use feed_rs::parser;
+use regex::Regex;
+use crate::utils::input_limits::{scanned, too_large, MAX_FEED_BYTES};
+
+/// How far the start of the feed is looked for, longer junk meaning it isn't a feed at all.
+const JUNK_WINDOW: usize = 64 * 1024;
+const FEED_ROOTS: [&str; 4] = ["<?xml", "<rss", "<feed", "<rdf:RDF"];
+
+static ENTITY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?:#[0-9]+|#[xX][0-9a-fA-F]+|[A-Za-z][A-Za-z0-9]*);").unwrap());
+
+/// Parses a feed, recovering from what broken servers commonly send: BOMs and blank lines, PHP warnings
+/// or an HTML doctype before the feed, and unescaped ampersands in URLs. Returns the feed along with the
+/// content actually parsed, and a warning for each recovery so chronically broken feeds can be spotted.
+pub fn parse_feed(content: &str) -> Result<(Feed, String, Vec<String>), String> {
+    if content.len() > MAX_FEED_BYTES {
+        return Err(too_large("The feed", MAX_FEED_BYTES));
+    }
+    let mut warnings = vec![];
+
+    let content = content.trim_start_matches(|c: char| c == '\u{feff}' || c.is_whitespace());
+    let start = feed_start(content);
+    if start > 0 {
+        let skipped: String = content[..start].chars().take(80).collect();
+        eprintln!("Skipped {} bytes before the feed: {:?}", start, skipped);
+        warnings.push(format!("Skipped {} bytes of junk before the feed", start));
+    }
+    let content = &content[start..];
+
+    // The parser silently drops the text of elements holding one, like links with query strings
+    let (content, count) = if content.starts_with('{') { (content.to_string(), 0) } else { escape_ampersands(content) };
+    if count > 0 {
+        warnings.push(format!("Escaped {} unescaped ampersands", count));
+    }
+
+    let feed = parser::parse(content.as_bytes()).map_err(|e| format!("Error parsing feed: {}", e))?;
+    Ok((feed, content, warnings))
+}
+
+/// Where the feed starts, 0 when nothing precedes it or its start can't be found.
+fn feed_start(content: &str) -> usize {
+    let looks_valid = content.starts_with('{')
+        || content.starts_with("<!--")
+        || FEED_ROOTS.iter().any(|root| content.starts_with(root))
+        || (content.get(..9).is_some_and(|start| start.eq_ignore_ascii_case("<!doctype"))
+            && !content.get(..14).is_some_and(|start| start.eq_ignore_ascii_case("<!doctype html")));
+    if looks_valid {
+        return 0;
+    }
+
+    let window = scanned(content, JUNK_WINDOW);
+
+    // The declaration comes first when there is one, a root tag may appear earlier within the junk
+    window.find("<?xml")
+        .or_else(|| FEED_ROOTS[1..].iter().filter_map(|root| window.find(root)).min())
+        .unwrap_or_default()
+}
+
+/// Escapes the ampersands which don't start an entity, leaving CDATA sections untouched.
+fn escape_ampersands(content: &str) -> (String, usize) {
+    let mut repaired = String::with_capacity(content.len());
+    let mut count = 0;
+    let mut rest = content;
+
+    while let Some(index) = rest.find(['&', '<']) {
+        repaired.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        if rest.starts_with("<![CDATA[") {
+            let end = rest.find("]]>").map_or(rest.len(), |end| end + 3);
+            repaired.push_str(&rest[..end]);
+            rest = &rest[end..];
+        } else if rest.starts_with('&') && !ENTITY.is_match(&rest[1..]) {
+            repaired.push_str("&amp;");
+            count += 1;
+            rest = &rest[1..];
+        } else {
+            repaired.push_str(&rest[..1]);
+            rest = &rest[1..];
+        }
+    }
+    repaired.push_str(rest);
+
+    (repaired, count)
+}
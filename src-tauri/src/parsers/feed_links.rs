@@ -0,0 +1,53 @@
+use std::sync::LazyLock;
+use regex::Regex;
+use url::Url;
+use crate::enums::feed_type::FeedType;
+use crate::structs::feed_candidate::FeedCandidate;
+use crate::utils::input_limits::{scanned, MAX_SCANNED_HTML_BYTES};
+
+/// Pages advertise one per format at most, a few more for their comments or categories
+const MAX_CANDIDATES: usize = 50;
+
+static LINK_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?is)<link\b[^>]*>").unwrap());
+static ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)([a-z-]+)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap()
+});
+
+/// Lists the feeds an HTML page advertises with `<link rel="alternate">`, resolved against `base`. Only the
+/// start of longer pages is scanned, where their head is.
+pub fn feed_links(html: &str, base: &Url) -> Vec<FeedCandidate> {
+    let mut candidates: Vec<FeedCandidate> = vec![];
+
+    for tag in LINK_TAG.find_iter(scanned(html, MAX_SCANNED_HTML_BYTES)) {
+        let attribute = |name: &str| ATTRIBUTE.captures_iter(tag.as_str())
+            .find(|captures| captures[1].eq_ignore_ascii_case(name))
+            .and_then(|captures| captures.get(2).or(captures.get(3)).or(captures.get(4)))
+            .map(|value| value.as_str().trim().replace("&amp;", "&"));
+
+        let is_alternate = attribute("rel")
+            .is_some_and(|rel| rel.split_whitespace().any(|rel| rel.eq_ignore_ascii_case("alternate")));
+        let feed_type = match attribute("type").map(|mime_type| mime_type.to_lowercase()).as_deref() {
+            Some("application/rss+xml" | "application/rdf+xml") => FeedType::RSS,
+            Some("application/atom+xml") => FeedType::Atom,
+            Some("application/feed+json") => FeedType::JSON,
+            _ => continue,
+        };
+        let Some(url) = attribute("href").and_then(|href| base.join(&href).ok()) else { continue };
+
+        if !is_alternate || candidates.iter().any(|candidate| candidate.url == url.as_str()) {
+            continue;
+        }
+
+        candidates.push(FeedCandidate {
+            url: url.to_string(),
+            title: attribute("title").filter(|title| !title.is_empty()),
+            feed_type: Some(feed_type),
+            existing_feed: None,
+        });
+        if candidates.len() == MAX_CANDIDATES {
+            break;
+        }
+    }
+
+    candidates
+}
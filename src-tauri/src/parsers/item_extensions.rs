@@ -0,0 +1,232 @@
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use crate::enums::enclosure_role::EnclosureRole;
+use crate::structs::article_extra::ArticleExtra;
+use crate::structs::enclosure::Enclosure;
+use crate::structs::item_extensions::ItemExtensions;
+
+/// Namespaces with structured item data and little noise, read for every feed. Others are opt-in per feed.
+const KNOWN_NAMESPACES: [&str; 4] = ["georss", "geo", "ev", "yweather"];
+const MAX_EXTRA_TEXT: usize = 500;
+/// Per item, extras beyond it are dropped
+const MAX_EXTRAS_SIZE: usize = 8 * 1024;
+
+/// Scans the raw document for the `<item>`/`<entry>` elements, in document order, so the
+/// result can be zipped with the entries returned by feed-rs.
+/// Extras are read from the children of the items in the known namespaces and the `namespaces` of the feed,
+/// and from the RSS categories with a domain, which feed-rs flattens: `<category domain="location">` is
+/// kept as "category:location".
+pub fn parse_item_extensions(content: &[u8], namespaces: &[String]) -> Vec<ItemExtensions> {
+    let mut reader = Reader::from_reader(content);
+    reader.trim_text(true);
+
+    let mut items: Vec<ItemExtensions> = vec![];
+    let mut current: Option<ItemExtensions> = None;
+    // In a wfw:commentRss, the feed of the comments. The RSS <comments> element links to a page instead
+    let mut in_comment_rss = false;
+    // Elements open in the current item, and the extra being read with the depth it was opened at
+    let mut depth = 0;
+    let mut extra: Option<(String, ArticleExtra, usize)> = None;
+    let mut buf = vec![];
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => match (e.local_name().as_ref(), e.name().prefix().is_none()) {
+                (b"item", _) | (b"entry", _) => {
+                    current = Some(ItemExtensions::default());
+                    depth = 0;
+                }
+                (local_name, unprefixed) if current.is_some() => {
+                    depth += 1;
+                    match local_name {
+                        b"commentRss" if !unprefixed => in_comment_rss = true,
+                        _ => push_enclosure(&mut current, &e, &reader),
+                    }
+                    if depth == 1 {
+                        extra = read_extra(&e, &reader, namespaces).map(|(key, value)| (key, value, depth));
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Empty(e)) => {
+                push_enclosure(&mut current, &e, &reader);
+                if depth == 0 {
+                    if let (Some(item), Some((key, value))) = (current.as_mut(), read_extra(&e, &reader, namespaces)) {
+                        push_extra(item, key, value);
+                    }
+                }
+            }
+            Ok(Event::Text(e)) => {
+                if let Ok(text) = e.unescape() {
+                    set_comment_rss(&mut current, in_comment_rss, text.trim());
+                    append_text(&mut extra, text.trim());
+                }
+            }
+            Ok(Event::CData(e)) => {
+                let text = String::from_utf8_lossy(&e);
+                set_comment_rss(&mut current, in_comment_rss, text.trim());
+                append_text(&mut extra, text.trim());
+            }
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"item" | b"entry" => {
+                    if let Some(item) = current.take() {
+                        items.push(item);
+                    }
+                }
+                _ => {
+                    in_comment_rss = false;
+                    if extra.as_ref().is_some_and(|(_, _, opened_at)| *opened_at == depth) {
+                        if let (Some(item), Some((key, value, _))) = (current.as_mut(), extra.take()) {
+                            push_extra(item, key, value);
+                        }
+                    }
+                    depth = depth.saturating_sub(1);
+                }
+            },
+            Ok(Event::Eof) => break,
+            // The document already went through feed-rs, anything we can't read here is simply ignored
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    items
+}
+
+fn set_comment_rss(current: &mut Option<ItemExtensions>, in_comment_rss: bool, text: &str) {
+    let Some(item) = current else { return };
+    if in_comment_rss && !text.is_empty() {
+        item.comments_url = Some(text.to_string());
+    }
+}
+
+fn read_extra(element: &BytesStart, reader: &Reader<&[u8]>, namespaces: &[String]) -> Option<(String, ArticleExtra)> {
+    let name = element.name();
+    let local_name = String::from_utf8_lossy(name.local_name().into_inner()).to_string();
+
+    let key = match name.prefix() {
+        Some(prefix) => {
+            let prefix = String::from_utf8_lossy(prefix.into_inner()).to_string();
+            if !KNOWN_NAMESPACES.contains(&prefix.as_str()) && !namespaces.contains(&prefix) {
+                return None;
+            }
+            format!("{}:{}", prefix, local_name)
+        }
+        None if local_name == "category" => format!("category:{}", attribute(element, reader, "domain")?),
+        None => return None,
+    };
+
+    let attributes = element.attributes()
+        .filter_map(|attribute| attribute.ok())
+        .filter(|attribute| !attribute.key.as_ref().starts_with(b"xmlns"))
+        .filter_map(|attribute| {
+            let value = attribute.decode_and_unescape_value(reader).ok()?.trim().to_string();
+            Some((String::from_utf8_lossy(attribute.key.as_ref()).to_string(), value))
+        })
+        .filter(|(_, value)| !value.is_empty())
+        .collect();
+
+    Some((key, ArticleExtra { text: None, attributes }))
+}
+
+fn append_text(extra: &mut Option<(String, ArticleExtra, usize)>, text: &str) {
+    let Some((_, value, _)) = extra else { return };
+    if text.is_empty() {
+        return;
+    }
+
+    value.text = Some(match value.text.take() {
+        Some(known) => format!("{} {}", known, text),
+        None => text.to_string(),
+    });
+}
+
+fn push_extra(item: &mut ItemExtensions, key: String, mut value: ArticleExtra) {
+    value.text = value.text.map(|text| text.chars().take(MAX_EXTRA_TEXT).collect());
+    if value.text.is_none() && value.attributes.is_empty() {
+        return;
+    }
+
+    let size = |key: &str, value: &ArticleExtra| key.len()
+        + value.text.as_ref().map_or(0, |text| text.len())
+        + value.attributes.iter().map(|(name, attribute)| name.len() + attribute.len()).sum::<usize>();
+    let known_size: usize = item.extras.iter()
+        .flat_map(|(key, values)| values.iter().map(move |value| size(key, value)))
+        .sum();
+    if known_size + size(&key, &value) > MAX_EXTRAS_SIZE {
+        return;
+    }
+
+    item.extras.entry(key).or_default().push(value);
+}
+
+fn push_enclosure(current: &mut Option<ItemExtensions>, element: &BytesStart, reader: &Reader<&[u8]>) {
+    let Some(item) = current else { return };
+    let Some(enclosure) = parse_enclosure(element, reader) else { return };
+
+    if !item.enclosures.iter().any(|known| known.url == enclosure.url) {
+        item.enclosures.push(enclosure);
+    }
+}
+
+/// Reads RSS `<enclosure>`, Atom `<link rel="enclosure">` and podcasting 2.0 `<podcast:transcript>`
+/// and `<podcast:chapters>` elements.
+fn parse_enclosure(element: &BytesStart, reader: &Reader<&[u8]>) -> Option<Enclosure> {
+    let name = element.name();
+    let prefix = name.prefix().map(|prefix| prefix.into_inner());
+    let mime_type = attribute(element, reader, "type");
+
+    let (url, role) = match (prefix, name.local_name().as_ref()) {
+        (None, b"enclosure") => (attribute(element, reader, "url")?, EnclosureRole::from_mime_type(mime_type.as_deref())),
+        (None, b"link") if attribute(element, reader, "rel").as_deref() == Some("enclosure") => {
+            (attribute(element, reader, "href")?, EnclosureRole::from_mime_type(mime_type.as_deref()))
+        }
+        (Some(b"podcast"), b"transcript") => (attribute(element, reader, "url")?, EnclosureRole::Transcript),
+        (Some(b"podcast"), b"chapters") => (attribute(element, reader, "url")?, EnclosureRole::Chapters),
+        _ => return None,
+    };
+
+    Some(Enclosure {
+        url,
+        mime_type,
+        role,
+        language: attribute(element, reader, "language"),
+        playable: None,
+    })
+}
+
+fn attribute(element: &BytesStart, reader: &Reader<&[u8]>, name: &str) -> Option<String> {
+    element.try_get_attribute(name).ok()??
+        .decode_and_unescape_value(reader).ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comments_urls(items: &str) -> Vec<Option<String>> {
+        let document = format!(r#"<rss version="2.0" xmlns:wfw="http://wellformedweb.org/CommentAPI/"><channel>{}</channel></rss>"#, items);
+        parse_item_extensions(document.as_bytes(), &[]).into_iter().map(|item| item.comments_url).collect()
+    }
+
+    #[test]
+    fn reads_the_comments_feed_only() {
+        let items = concat!(
+            "<item><comments>https://blog.example/post#comments</comments><wfw:commentRss>https://blog.example/post/feed</wfw:commentRss></item>",
+            "<item><wfw:commentRss><![CDATA[ https://blog.example/cdata/feed ]]></wfw:commentRss><comments>https://blog.example/cdata#comments</comments></item>",
+            // The page of the comments isn't a feed
+            "<item><comments>https://blog.example/page#comments</comments></item>",
+            "<item><wfw:commentRss></wfw:commentRss></item>",
+        );
+
+        assert_eq!(comments_urls(items), [
+            Some(String::from("https://blog.example/post/feed")),
+            Some(String::from("https://blog.example/cdata/feed")),
+            None,
+            None,
+        ]);
+    }
+}
@@ -0,0 +1,6 @@
+pub mod item_extensions;
+pub mod transcript;
+pub mod feed_links;
+pub mod outbound_links;
+pub mod feed_content;
+pub mod bookmarks;
@@ -0,0 +1,41 @@
+use std::sync::LazyLock;
+use regex::Regex;
+use url::Url;
+use crate::utils::input_limits::{scanned, MAX_SCANNED_HTML_BYTES};
+use crate::utils::url::link_key;
+
+/// Links kept per article, so a link directory doesn't fill the table.
+const MAX_LINKS: usize = 100;
+
+static ANCHOR_HREF: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)<a\b[^>]*?\bhref\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap()
+});
+
+/// Lists the distinct links of an article's content as `(host, path)`, relative ones resolved
+/// against the article link. Links back to the article itself are left out, and the links past the start
+/// of longer articles.
+pub fn outbound_links(content: &str, article_link: Option<&str>) -> Vec<(String, String)> {
+    let base = article_link.and_then(|link| Url::parse(link).ok());
+    let own_key = base.as_ref().and_then(link_key);
+    let mut links: Vec<(String, String)> = vec![];
+
+    for captures in ANCHOR_HREF.captures_iter(scanned(content, MAX_SCANNED_HTML_BYTES)) {
+        let Some(href) = captures.get(1).or(captures.get(2)).or(captures.get(3)) else { continue };
+        let href = href.as_str().trim().replace("&amp;", "&");
+
+        let url = match &base {
+            Some(base) => base.join(&href),
+            None => Url::parse(&href),
+        };
+        let Some(key) = url.ok().as_ref().and_then(link_key) else { continue };
+
+        if Some(&key) != own_key.as_ref() && !links.contains(&key) {
+            links.push(key);
+            if links.len() == MAX_LINKS {
+                break;
+            }
+        }
+    }
+
+    links
+}
@@ -0,0 +1,152 @@
+use std::sync::LazyLock;
+use regex::Regex;
+use sqlx::types::Json;
+use crate::enums::transcript_format::TranscriptFormat;
+use crate::structs::transcript::Transcript;
+use crate::structs::transcript_segment::TranscriptSegment;
+use crate::utils::input_limits::{too_large, MAX_TRANSCRIPT_BYTES};
+
+static TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+static VOICE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<v(?:\.[^\s>]*)?\s+([^>]+)>").unwrap());
+
+/// Guesses the format of a transcript before downloading it, from its declared type or file extension.
+pub fn detect_format(mime_type: Option<&str>, url: &str) -> TranscriptFormat {
+    let path = url.split(['?', '#']).next().unwrap_or_default().to_lowercase();
+
+    match mime_type {
+        Some("text/vtt") => TranscriptFormat::Vtt,
+        Some("text/srt" | "application/srt" | "application/x-subrip") => TranscriptFormat::Srt,
+        _ if path.ends_with(".vtt") => TranscriptFormat::Vtt,
+        _ if path.ends_with(".srt") => TranscriptFormat::Srt,
+        _ => TranscriptFormat::Raw,
+    }
+}
+
+/// Converts VTT and SRT cues to segments, keeping any other document raw.
+pub fn parse_transcript(content: &str, mime_type: Option<&str>, url: &str) -> Result<Transcript, String> {
+    if content.len() > MAX_TRANSCRIPT_BYTES {
+        return Err(too_large("The transcript", MAX_TRANSCRIPT_BYTES));
+    }
+
+    let format = match detect_format(mime_type, url) {
+        TranscriptFormat::Raw if content.trim_start().starts_with("WEBVTT") => TranscriptFormat::Vtt,
+        format => format,
+    };
+
+    let segments = match format {
+        TranscriptFormat::Vtt | TranscriptFormat::Srt => parse_cues(content),
+        TranscriptFormat::Raw => vec![],
+    };
+
+    // A subtitle file we got no cue from is most likely mislabeled, the raw document is all we can offer
+    if segments.is_empty() {
+        return Ok(Transcript {
+            format: TranscriptFormat::Raw,
+            mime_type: mime_type.map(str::to_string),
+            segments: None,
+            raw: Some(content.to_string()),
+        });
+    }
+
+    Ok(Transcript {
+        format,
+        mime_type: mime_type.map(str::to_string),
+        segments: Some(Json(segments)),
+        raw: None,
+    })
+}
+
+/// VTT and SRT cues are both blocks separated by blank lines, with a `start --> end` line followed by the text.
+/// Blocks without timing (VTT header, NOTE, STYLE) are skipped.
+fn parse_cues(content: &str) -> Vec<TranscriptSegment> {
+    let content = content.replace("\r\n", "\n");
+    let mut segments = vec![];
+
+    for block in content.split("\n\n") {
+        let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+        let Some(timing) = lines.next() else { continue };
+
+        let Some((start, end)) = timing.split_once("-->") else { continue };
+        let end = end.split_whitespace().next().unwrap_or_default();
+        let (Some(start_ms), Some(end_ms)) = (parse_timestamp(start.trim()), parse_timestamp(end)) else { continue };
+
+        let text = lines.collect::<Vec<_>>().join("\n");
+        let speaker = VOICE.captures(&text).map(|voice| voice[1].trim().to_string());
+        let text = TAG.replace_all(&text, "").trim().to_string();
+        if text.is_empty() {
+            continue;
+        }
+
+        segments.push(TranscriptSegment { start_ms, end_ms, speaker, text });
+    }
+
+    segments
+}
+
+/// Reads `HH:MM:SS.mmm`, `MM:SS.mmm` and the SRT `HH:MM:SS,mmm` variant, in milliseconds. The fraction is
+/// a decimal one, `.5` being 500 ms, with the digits past the millisecond dropped. Signs and timestamps
+/// past `i64` are refused.
+fn parse_timestamp(timestamp: &str) -> Option<i64> {
+    let (clock, fraction) = timestamp.split_once(['.', ',']).unwrap_or((timestamp, "0"));
+    let parts = clock.split(':')
+        .map(number)
+        .collect::<Option<Vec<i64>>>()?;
+
+    let seconds = match parts.as_slice() {
+        [hours, minutes, seconds] => hours.checked_mul(3600)?.checked_add(minutes.checked_mul(60)?)?.checked_add(*seconds)?,
+        [minutes, seconds] => minutes.checked_mul(60)?.checked_add(*seconds)?,
+        _ => return None,
+    };
+
+    seconds.checked_mul(1000)?.checked_add(milliseconds(fraction)?)
+}
+
+/// The first three digits of a fraction of a second, padded with zeros.
+fn milliseconds(fraction: &str) -> Option<i64> {
+    if fraction.is_empty() || !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    format!("{:0<3}", &fraction[..fraction.len().min(3)]).parse().ok()
+}
+
+/// Digits only, as `parse` also takes a sign.
+fn number(digits: &str) -> Option<i64> {
+    if !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps() {
+        let cases = [
+            ("00:00:01.000", Some(1_000)),
+            ("01:02:03.456", Some(3_723_456)),
+            ("01:02:03,456", Some(3_723_456)),
+            ("02:03.456", Some(123_456)),
+            ("00:00:07", Some(7_000)),
+            ("00:00:01.5", Some(1_500)),
+            ("00:00:01.05", Some(1_050)),
+            ("00:00:01.050", Some(1_050)),
+            ("00:00:01.0509", Some(1_050)),
+            ("00:00:01.000000000000000000000001", Some(1_000)),
+            ("00:00:01.", None),
+            ("00:00:01.5a", None),
+            ("00:00:01.-5", None),
+            ("-00:00:01.000", None),
+            ("00:+1:01.000", None),
+            ("1", None),
+            ("1:2:3:4", None),
+            ("", None),
+            ("9223372036854775807:00:00.000", None),
+        ];
+
+        for (timestamp, milliseconds) in cases {
+            assert_eq!(parse_timestamp(timestamp), milliseconds, "{:?}", timestamp);
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::sync::LazyLock;
+use regex::Regex;
+use crate::post_processors::PostProcessor;
+
+static AMP_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)<(/?)amp-(img|video|audio|iframe)\b").unwrap());
+// img is a void element, its AMP closing tag has no equivalent
+static AMP_IMG_END: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)</amp-img\s*>").unwrap());
+
+/// Unwraps AMP media elements into their standard HTML equivalent, which the webview can render.
+pub struct AmpElements;
+
+impl PostProcessor for AmpElements {
+    fn name(&self) -> &'static str {
+        "amp_elements"
+    }
+
+    fn process(&self, html: &str) -> Option<String> {
+        if !AMP_TAG.is_match(html) {
+            return None;
+        }
+
+        let without_img_ends = AMP_IMG_END.replace_all(html, "");
+        Some(AMP_TAG.replace_all(&without_img_ends, "<${1}${2}").into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unwraps_amp_media() {
+        let cases = [
+            (r#"<amp-img src="a.png" width="1"></amp-img>"#, Some(r#"<img src="a.png" width="1">"#)),
+            (r#"<AMP-IMG src="a.png"></AMP-IMG >"#, Some(r#"<IMG src="a.png">"#)),
+            (r#"<amp-video src="a.mp4"><source src="b.mp4"></amp-video>"#, Some(r#"<video src="a.mp4"><source src="b.mp4"></video>"#)),
+            (r#"<amp-audio src="a.mp3"></amp-audio>"#, Some(r#"<audio src="a.mp3"></audio>"#)),
+            (r#"<amp-iframe src="https://a.example/"></amp-iframe>"#, Some(r#"<iframe src="https://a.example/"></iframe>"#)),
+            // Not media, nothing to unwrap into
+            (r#"<amp-carousel><img src="a.png"></amp-carousel>"#, None),
+            (r#"<amp-imgs>"#, None),
+            (r#"<p>amp-img</p>"#, None),
+        ];
+
+        for (html, expected) in cases {
+            assert_eq!(AmpElements.process(html).as_deref(), expected, "{}", html);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::LazyLock;
+use regex::{Captures, NoExpand, Regex};
+use crate::post_processors::PostProcessor;
+
+static IMG_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(?i)<img\b[^>]*>"#).unwrap());
+static LAZY_SRC: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(?i)\sdata-(?:lazy-src|src|original)\s*=\s*("[^"]*"|'[^']*')"#).unwrap());
+static SRC: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(?i)\ssrc\s*=\s*("[^"]*"|'[^']*'|[^\s>]+)"#).unwrap());
+
+/// Replaces the placeholder `src` of lazy-loaded images with the real one from `data-src`.
+pub struct LazyImages;
+
+impl PostProcessor for LazyImages {
+    fn name(&self) -> &'static str {
+        "lazy_images"
+    }
+
+    fn process(&self, html: &str) -> Option<String> {
+        if !LAZY_SRC.is_match(html) {
+            return None;
+        }
+
+        let processed = IMG_TAG.replace_all(html, |tag: &Captures| {
+            let tag = &tag[0];
+            let Some(lazy) = LAZY_SRC.captures(tag) else { return tag.to_string() };
+
+            let without_src = SRC.replace(tag, "");
+            let real_src = format!(" src={}", &lazy[1]);
+            LAZY_SRC.replace(&without_src, NoExpand(&real_src)).into_owned()
+        });
+
+        Some(processed.into_owned()).filter(|processed| processed != html)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uses_the_real_sources() {
+        let cases = [
+            (r#"<img src="placeholder.gif" data-src="real.png">"#, Some(r#"<img src="real.png">"#)),
+            (r#"<img data-lazy-src='real.png' src=placeholder.gif alt="">"#, Some(r#"<img src='real.png' alt="">"#)),
+            (r#"<IMG DATA-ORIGINAL="real.png">"#, Some(r#"<IMG src="real.png">"#)),
+            (r#"<p>a</p><img data-src="1.png"><img src="2.png">"#, Some(r#"<p>a</p><img src="1.png"><img src="2.png">"#)),
+            // Already right, or not an image
+            (r#"<img src="real.png">"#, None),
+            (r#"<div data-src="real.png"></div>"#, None),
+            (r#"<img data-srcset="real.png 2x">"#, None),
+        ];
+
+        for (html, expected) in cases {
+            assert_eq!(LazyImages.process(html).as_deref(), expected, "{}", html);
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use feed_rs::model::Entry;
+
+pub mod lazy_images;
+pub mod amp_elements;
+pub mod post_footer;
+pub mod protocol_relative_urls;
+
+/// A named transform applied to article HTML before it is stored.
+/// Adding one means implementing this trait and listing it in `registry`.
+pub trait PostProcessor: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Returns the transformed HTML, or `None` when there was nothing to change.
+    fn process(&self, html: &str) -> Option<String>;
+}
+
+/// Every post-processor, in the order they run.
+pub fn registry() -> Vec<Box<dyn PostProcessor>> {
+    vec![
+        Box::new(amp_elements::AmpElements),
+        Box::new(lazy_images::LazyImages),
+        Box::new(protocol_relative_urls::ProtocolRelativeUrls),
+        Box::new(post_footer::PostFooter),
+    ]
+}
+
+pub struct Pipeline {
+    processors: Vec<Box<dyn PostProcessor>>,
+}
+
+impl Pipeline {
+    /// Builds the pipeline of a feed, post-processors being enabled unless its flags disable them.
+    pub fn for_flags(flags: &HashMap<String, bool>) -> Self {
+        Pipeline {
+            processors: registry().into_iter()
+                .filter(|processor| flags.get(processor.name()).copied().unwrap_or(true))
+                .collect(),
+        }
+    }
+
+    /// Returns the names of the post-processors that changed the HTML.
+    pub fn apply(&self, html: &mut String) -> Vec<String> {
+        let mut applied = vec![];
+
+        for processor in self.processors.iter() {
+            if let Some(processed) = processor.process(html) {
+                *html = processed;
+                applied.push(processor.name().to_string());
+            }
+        }
+
+        applied
+    }
+
+    /// Processes the raw HTML of an entry, before `Article::from` escapes it.
+    pub fn apply_entry(&self, entry: &mut Entry) -> Vec<String> {
+        let html = match (entry.content.as_mut(), entry.summary.as_mut()) {
+            (Some(content), _) => content.body.as_mut(),
+            (None, Some(summary)) => Some(&mut summary.content),
+            (None, None) => None,
+        };
+
+        match html {
+            Some(html) => self.apply(html),
+            None => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTML: &str = r#"<amp-img data-src="//cdn.example/a.png"></amp-img><p>The post A appeared first on B.</p>"#;
+
+    #[test]
+    fn runs_the_enabled_post_processors_in_order() {
+        let mut html = String::from(HTML);
+        let applied = Pipeline::for_flags(&HashMap::new()).apply(&mut html);
+
+        assert_eq!(html, r#"<img src="https://cdn.example/a.png">"#);
+        assert_eq!(applied, ["amp_elements", "lazy_images", "protocol_relative_urls", "post_footer"]);
+    }
+
+    #[test]
+    fn skips_the_disabled_post_processors() {
+        let flags = HashMap::from([(String::from("lazy_images"), false), (String::from("post_footer"), true)]);
+        let mut html = String::from(HTML);
+        let applied = Pipeline::for_flags(&flags).apply(&mut html);
+
+        assert_eq!(html, r#"<img data-src="https://cdn.example/a.png">"#);
+        assert_eq!(applied, ["amp_elements", "protocol_relative_urls", "post_footer"]);
+    }
+
+    #[test]
+    fn leaves_untouched_html_unnamed() {
+        let mut html = String::from("<p>Text</p>");
+        assert!(Pipeline::for_flags(&HashMap::new()).apply(&mut html).is_empty());
+        assert_eq!(html, "<p>Text</p>");
+    }
+}
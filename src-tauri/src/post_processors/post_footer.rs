@@ -0,0 +1,46 @@
+use std::sync::LazyLock;
+use regex::Regex;
+use crate::post_processors::PostProcessor;
+
+static FOOTER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)(?:<p[^>]*>\s*)?The post\s.{1,500}?\sappeared first on\s.{1,500}?\.(?:\s*</p>)?\s*$").unwrap()
+});
+
+/// Removes the "The post X appeared first on Y." footer added by WordPress plugins.
+pub struct PostFooter;
+
+impl PostProcessor for PostFooter {
+    fn name(&self) -> &'static str {
+        "post_footer"
+    }
+
+    fn process(&self, html: &str) -> Option<String> {
+        if !FOOTER.is_match(html) {
+            return None;
+        }
+
+        Some(FOOTER.replace(html, "").trim_end().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_the_footer() {
+        let cases = [
+            ("<p>Text</p>\n<p>The post <a href=\"https://a.example/p\">Title</a> appeared first on <a href=\"https://a.example/\">Blog</a>.</p>\n", Some("<p>Text</p>")),
+            ("Text The post Title appeared first on Blog.", Some("Text")),
+            ("<p>Text</p><p class=\"footer\">The post Title\nappeared first on Blog.</p>", Some("<p>Text</p>")),
+            // Only at the end of the article
+            ("<p>The post Title appeared first on Blog.</p><p>Text</p>", None),
+            ("<p>The post office appeared first on the map</p>", None),
+            ("<p>Text</p>", None),
+        ];
+
+        for (html, expected) in cases {
+            assert_eq!(PostFooter.process(html).as_deref(), expected, "{}", html);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::LazyLock;
+use regex::Regex;
+use crate::post_processors::PostProcessor;
+
+static RELATIVE_URL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(?i)\b(src|href|poster)\s*=\s*(["'])//"#).unwrap());
+
+/// Turns protocol-relative URLs into https ones, the webview has no page protocol to resolve them against.
+pub struct ProtocolRelativeUrls;
+
+impl PostProcessor for ProtocolRelativeUrls {
+    fn name(&self) -> &'static str {
+        "protocol_relative_urls"
+    }
+
+    fn process(&self, html: &str) -> Option<String> {
+        if !RELATIVE_URL.is_match(html) {
+            return None;
+        }
+
+        Some(RELATIVE_URL.replace_all(html, "${1}=${2}https://").into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uses_https() {
+        let cases = [
+            (r#"<img src="//cdn.example/a.png">"#, Some(r#"<img src="https://cdn.example/a.png">"#)),
+            (r#"<a HREF = '//a.example/'>a</a>"#, Some(r#"<a HREF='https://a.example/'>a</a>"#)),
+            (r#"<video poster="//cdn.example/a.png" src="//cdn.example/a.mp4">"#, Some(r#"<video poster="https://cdn.example/a.png" src="https://cdn.example/a.mp4">"#)),
+            // The sources lazy images load too
+            (r#"<img data-src="//cdn.example/a.png">"#, Some(r#"<img data-src="https://cdn.example/a.png">"#)),
+            // Already absolute, relative to the page, or not an attribute
+            (r#"<img src="http://cdn.example/a.png">"#, None),
+            (r#"<img src="/a.png">"#, None),
+            (r#"<p>//cdn.example/a.png</p>"#, None),
+        ];
+
+        for (html, expected) in cases {
+            assert_eq!(ProtocolRelativeUrls.process(html).as_deref(), expected, "{}", html);
+        }
+    }
+}
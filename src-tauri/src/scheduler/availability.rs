@@ -0,0 +1,216 @@
+use std::collections::HashSet;
+use chrono::{Local, Timelike};
+use sqlx::{Pool, Sqlite, SqliteExecutor};
+use tauri::{AppHandle, Manager};
+use crate::commands::fetcher::sync_feed;
+use crate::structs::fetch_outcome::FetchOutcome;
+use crate::structs::sync_outcome::SyncOutcome;
+use crate::structs::sync_request::SyncRequest;
+use crate::structs::sync_writer::SyncWriter;
+use crate::structs::unavailable_window::UnavailableWindow;
+
+const MAX_WINDOW_HOURS: u32 = 3;
+/// Failures on fewer days are a one-off outage rather than a daily pattern.
+const MIN_FAILURE_DAYS: usize = 3;
+const MIN_SUCCESSES_OUTSIDE: usize = 5;
+/// Failures caused by the app or the settings of the feed rather than the site.
+const UNCOUNTED_ERRORS: [&str; 6] = ["WritesFrozen:", "SessionExpired:", "AuthenticationFailed:", "CertificateInvalid:", "ClientCertificateExpired:", "ClientCertificateRejected:"];
+
+/// Syncs a feed for the scheduler, unless it is within its daily downtime. The outcome is recorded, and the
+/// downtime updated from it. Failures caused by the app itself, like frozen writes, expired cookies,
+/// rejected credentials or certificates, aren't.
+pub async fn scheduled_sync(request: SyncRequest, writer: SyncWriter, app_handle: AppHandle) -> Result<SyncOutcome, String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let identifier = request.identifier.clone();
+
+    let now = Local::now();
+    let minute = now.hour() * 60 + now.minute();
+    if load_unavailable_window(&identifier, &pool).await?.is_some_and(|window| window.contains(minute)) {
+        return Ok(SyncOutcome { warnings: vec![String::from("Skipped during its daily downtime")], ..SyncOutcome::default() });
+    }
+
+    let result = sync_feed(request, &writer, &app_handle).await;
+    let counted = match &result {
+        Ok(_) => true,
+        Err(e) => !UNCOUNTED_ERRORS.iter().any(|prefix| e.starts_with(prefix)),
+    };
+    if counted {
+        if let Err(e) = record_fetch(&identifier, &now.format("%Y-%m-%d").to_string(), minute, result.is_ok(), &pool).await {
+            eprintln!("Error recording fetch of feed {}: {}", identifier, e);
+        }
+    }
+
+    result
+}
+
+pub async fn load_unavailable_window(feed_id: &str, pool: &Pool<Sqlite>) -> Result<Option<UnavailableWindow>, String> {
+    sqlx::query_as::<_, UnavailableWindow>(include_str!("../database/queries/get_unavailable_window.sql"))
+        .bind(feed_id)
+        .fetch_optional(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+pub async fn load_fetches<'e, E: SqliteExecutor<'e>>(feed_id: &str, executor: E) -> Result<Vec<FetchOutcome>, String> {
+    sqlx::query_as::<_, FetchOutcome>(include_str!("../database/queries/get_feed_fetches.sql"))
+        .bind(feed_id)
+        .fetch_all(executor).await
+        .map_err(|e| format!("Error executing query: {}", e))
+}
+
+/// The window of the feed is always the one found in its fetches, as they are written together.
+pub async fn record_fetch(feed_id: &str, local_date: &str, minute: u32, success: bool, pool: &Pool<Sqlite>) -> Result<(), String> {
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+
+    sqlx::query(include_str!("../database/queries/insert_feed_fetch.sql"))
+        .bind(feed_id)
+        .bind(local_date)
+        .bind(minute)
+        .bind(success)
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    sqlx::query(include_str!("../database/queries/prune_feed_fetches.sql"))
+        .bind(feed_id)
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    let window = unavailable_window(&load_fetches(feed_id, &mut *transaction).await?);
+    sqlx::query(include_str!("../database/queries/set_unavailable_window.sql"))
+        .bind(window.map(|window| window.start_minute))
+        .bind(window.map(|window| window.minutes))
+        .bind(feed_id)
+        .execute(&mut *transaction).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))
+}
+
+/// Finds the shortest daily window, of up to three hours, holding at least 80% of the failures on several
+/// days, in which the feed fails more than it works, while it works at least 90% of the time outside.
+/// Times are local wall clock times, so a server restarting at 03:00 stays at 03:00 across DST changes.
+pub fn unavailable_window(fetches: &[FetchOutcome]) -> Option<UnavailableWindow> {
+    let failures = fetches.iter().filter(|fetch| !fetch.success).count();
+    let successes = fetches.len() - failures;
+
+    (1..=MAX_WINDOW_HOURS)
+        .flat_map(|hours| (0..24).map(move |start| UnavailableWindow { start_minute: start * 60, minutes: hours * 60 }))
+        .find(|window| {
+            let (inside, outside): (Vec<&FetchOutcome>, Vec<&FetchOutcome>) = fetches.iter()
+                .partition(|fetch| window.contains(fetch.local_minute));
+            let inside_failures: Vec<&&FetchOutcome> = inside.iter().filter(|fetch| !fetch.success).collect();
+            let failure_days: HashSet<&str> = inside_failures.iter().map(|fetch| fetch.local_date.as_str()).collect();
+            let outside_failures = outside.iter().filter(|fetch| !fetch.success).count();
+
+            failure_days.len() >= MIN_FAILURE_DAYS
+                && inside_failures.len() * 10 >= failures * 8
+                && inside_failures.len() * 2 > inside.len()
+                && successes - (inside.len() - inside_failures.len()) >= MIN_SUCCESSES_OUTSIDE
+                && outside_failures * 10 <= outside.len()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate, TimeZone, Utc};
+    use super::*;
+
+    const DAYS: i64 = 14;
+
+    /// xorshift64, so every failing seed can be replayed.
+    struct Random(u64);
+
+    impl Random {
+        fn chance(&mut self, per_mille: u64) -> bool {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % 1000 < per_mille
+        }
+    }
+
+    /// Fetches every 30 minutes, on a jittered clock, of a server failing `noise` per mille of the time, and
+    /// mostly failing when `down` at the UTC and local minutes of the day. Recorded at the local time of a user
+    /// `offset` hours ahead of UTC on each day.
+    fn fetches(seed: u64, noise: u64, offset: impl Fn(i64) -> i64, down: impl Fn(u32, u32) -> bool) -> Vec<FetchOutcome> {
+        let mut random = Random(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        let start = Utc.from_utc_datetime(&NaiveDate::from_ymd_opt(2026, 3, 22).unwrap().and_hms_opt(0, 0, 0).unwrap());
+
+        (0..DAYS * 48)
+            .map(|slot| {
+                let utc = start + Duration::minutes(slot * 30 + if random.chance(500) { 7 } else { 0 });
+                let day = (utc - start).num_days();
+                let local = utc + Duration::hours(offset(day));
+                let local_minute = local.hour() * 60 + local.minute();
+                // A few fetches getting through during the downtime
+                let success = if down(utc.hour() * 60 + utc.minute(), local_minute) { random.chance(100) } else { !random.chance(noise) };
+                FetchOutcome { local_date: local.format("%Y-%m-%d").to_string(), local_minute, success }
+            })
+            .collect()
+    }
+
+    fn window(start_minute: u32, minutes: u32) -> Option<UnavailableWindow> {
+        Some(UnavailableWindow { start_minute, minutes })
+    }
+
+    #[test]
+    fn finds_daily_downtimes_in_noisy_fetches() {
+        for seed in 1..=10 {
+            // 03:00 to 03:45 every night
+            let nightly = fetches(seed, 3, |_| 0, |utc, _| (180..225).contains(&utc));
+            assert_eq!(unavailable_window(&nightly), window(180, 60), "seed {}", seed);
+
+            // 23:20 to 00:40, across midnight
+            let midnight = fetches(seed, 3, |_| 0, |utc, _| !(40..1400).contains(&utc));
+            assert_eq!(unavailable_window(&midnight), window(23 * 60, 120), "seed {}", seed);
+
+            // Failing as often at other times, the downtime explains too few failures
+            let flaky = fetches(seed, 40, |_| 0, |utc, _| (180..225).contains(&utc));
+            assert_eq!(unavailable_window(&flaky), None, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn follows_the_local_clock() {
+        for seed in 1..=10 {
+            // A server down 01:00 to 01:45 UTC, seen from a timezone two hours ahead
+            let ahead = fetches(seed, 3, |_| 2, |utc, _| (60..105).contains(&utc));
+            assert_eq!(unavailable_window(&ahead), window(180, 60), "seed {}", seed);
+
+            // A server in Paris down 03:00 to 03:45 local time, seen from Paris, which moves to summer time on
+            // the 29th of March: the window stays where the user sees it
+            let dst = fetches(seed, 3, |day| if day < 7 { 1 } else { 2 }, |_, local| (180..225).contains(&local));
+            assert_eq!(unavailable_window(&dst), window(180, 60), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn ignores_outages_without_a_daily_pattern() {
+        for seed in 1..=10 {
+            let noise = fetches(seed, 30, |_| 0, |_, _| false);
+            assert_eq!(unavailable_window(&noise), None, "seed {}", seed);
+        }
+
+        // Down a whole morning once, then on two nights only
+        let mut fetches = fetches(1, 3, |_| 0, |_, _| false);
+        for fetch in fetches.iter_mut() {
+            let bad_morning = fetch.local_date == "2026-03-25" && fetch.local_minute < 12 * 60;
+            let two_nights = ["2026-03-27", "2026-03-28"].contains(&fetch.local_date.as_str()) && (180..225).contains(&fetch.local_minute);
+            if bad_morning || two_nights {
+                fetch.success = false;
+            }
+        }
+        assert_eq!(unavailable_window(&fetches), None);
+
+        assert_eq!(unavailable_window(&[]), None);
+    }
+
+    #[test]
+    fn windows_wrap_around_midnight() {
+        let late = UnavailableWindow { start_minute: 23 * 60, minutes: 120 };
+        let cases = [(22 * 60 + 59, false), (23 * 60, true), (0, true), (59, true), (60, false), (12 * 60, false)];
+
+        for (minute, contained) in cases {
+            assert_eq!(late.contains(minute), contained, "{}", minute);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::commands::fetcher::sync_all;
+use crate::database::change_journal::emit_article_change;
+use crate::database::notification_ledger::notify_new_articles;
+use crate::scheduler::limiter::full_pass_running;
+use crate::scheduler::power::power_policy;
+use crate::structs::background_sync::BackgroundSync;
+use crate::structs::background_sync_status::BackgroundSyncStatus;
+use crate::structs::sync_request::SyncRequest;
+use crate::structs::write_gate::WriteGate;
+
+/// Set from the check for a running pass until `sync_all` marks its own, so two runs can't both start.
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static ALIVE: AtomicBool = AtomicBool::new(false);
+
+/// Clears `ALIVE` when the loop stops, however it does.
+struct Alive;
+
+impl Drop for Alive {
+    fn drop(&mut self) {
+        ALIVE.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Syncs every feed each `backgroundSyncMinutes`, which the power policy stretches, so the articles stay
+/// fresh while the window is hidden in the tray. Nothing runs while paused. A changed interval applies to
+/// the current wait, counted from the last run.
+pub async fn run(app_handle: AppHandle) {
+    ALIVE.store(true, Ordering::SeqCst);
+    let _alive = Alive;
+    let mut last_run = Instant::now();
+
+    loop {
+        let background_sync = app_handle.state::<BackgroundSync>();
+        if background_sync.paused() {
+            background_sync.changed().await;
+            continue;
+        }
+
+        let interval = Duration::from_secs(background_sync.minutes() * power_policy(&app_handle).interval_factor * 60);
+        let elapsed = last_run.elapsed();
+        if elapsed < interval {
+            // Either the interval went by, or the settings changed and the wait is computed again
+            let _ = tokio::time::timeout(interval - elapsed, background_sync.changed()).await;
+            continue;
+        }
+
+        last_run = Instant::now();
+        sync_now(&app_handle).await;
+    }
+}
+
+/// Syncs every feed, notifies the new articles then emits `background-sync-finished` with the report. The run is skipped when
+/// another one is going, started by the frontend or not, or while writes are frozen.
+pub async fn sync_now(app_handle: &AppHandle) {
+    if app_handle.state::<WriteGate>().check().is_err() || full_pass_running() || RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let result = sync_every_feed(app_handle).await;
+    RUNNING.store(false, Ordering::SeqCst);
+
+    if let Err(e) = result {
+        eprintln!("Error running background sync: {}", e);
+    }
+}
+
+pub fn background_sync_status(app_handle: &AppHandle) -> BackgroundSyncStatus {
+    let background_sync = app_handle.state::<BackgroundSync>();
+
+    BackgroundSyncStatus {
+        minutes: background_sync.minutes(),
+        paused: background_sync.paused(),
+        running: RUNNING.load(Ordering::SeqCst) || full_pass_running(),
+        alive: ALIVE.load(Ordering::SeqCst),
+    }
+}
+
+async fn sync_every_feed(app_handle: &AppHandle) -> Result<(), String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let requests: Vec<SyncRequest> = sqlx::query_as::<_, (String, String)>(include_str!("../database/queries/get_feed_urls.sql"))
+        .fetch_all(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .into_iter()
+        .map(|(identifier, url)| SyncRequest { identifier, url })
+        .collect();
+
+    let report = sync_all(requests, app_handle.clone()).await?;
+    notify_new_articles(app_handle).await;
+
+    let updated = report.updated.clone();
+    emit_article_change(app_handle, "background-sync-finished", report, |filter| updated.iter().any(|feed| filter.may_show(feed, None))).await
+}
@@ -0,0 +1,29 @@
+use std::time::Duration;
+use chrono::Utc;
+use tauri::{AppHandle, Manager};
+use crate::enums::background_job::BackgroundJob;
+use crate::structs::activity_monitor::ActivityMonitor;
+
+const IDLE_RETRY: Duration = Duration::from_secs(30);
+
+/// Invoked by the frontend on its own, the periodic syncs of its clock telling nothing about the user.
+const UNATTENDED_COMMANDS: [&str; 1] = ["sync"];
+
+/// Called by the invoke handler before every command.
+pub fn record_command(command: &str, app_handle: &AppHandle) {
+    if !UNATTENDED_COMMANDS.contains(&command) {
+        record_activity(app_handle);
+    }
+}
+
+pub fn record_activity(app_handle: &AppHandle) {
+    app_handle.state::<ActivityMonitor>().record(Utc::now().timestamp_millis());
+}
+
+/// Waits for the user to be idle before the next step of an idle-only job, which is in the deferred
+/// queue meanwhile. Returns right away for the other jobs.
+pub async fn wait_for_idle(job: BackgroundJob, app_handle: &AppHandle) {
+    while !app_handle.state::<ActivityMonitor>().may_run(job, Utc::now().timestamp_millis()) {
+        tokio::time::sleep(IDLE_RETRY).await;
+    }
+}
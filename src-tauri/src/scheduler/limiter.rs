@@ -0,0 +1,39 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Concurrencies that don't divide it are approximated, see `acquire_sync_permit`.
+const MAX_CONCURRENT_SYNCS: usize = 16;
+
+/// Shared by every sync, whichever pass started it, so the total load on the network stays bounded.
+static SYNC_PERMITS: Semaphore = Semaphore::const_new(MAX_CONCURRENT_SYNCS);
+
+static FULL_PASSES: AtomicUsize = AtomicUsize::new(0);
+
+/// Marks a `sync_all` run as in progress until dropped.
+pub struct FullPass;
+
+impl FullPass {
+    pub fn start() -> Self {
+        FULL_PASSES.fetch_add(1, Ordering::SeqCst);
+        FullPass
+    }
+}
+
+impl Drop for FullPass {
+    fn drop(&mut self) {
+        FULL_PASSES.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Waits for a sync slot. Each sync takes a share of the permits matching `concurrency`, so a lower
+/// concurrency applies to the next syncs without resizing the semaphore under the running ones.
+pub async fn acquire_sync_permit(concurrency: usize) -> Result<SemaphorePermit<'static>, String> {
+    let permits = MAX_CONCURRENT_SYNCS / concurrency.clamp(1, MAX_CONCURRENT_SYNCS);
+
+    SYNC_PERMITS.acquire_many(permits as u32).await
+        .map_err(|e| format!("Error acquiring sync permit: {}", e))
+}
+
+pub fn full_pass_running() -> bool {
+    FULL_PASSES.load(Ordering::SeqCst) > 0
+}
@@ -0,0 +1,8 @@
+pub mod order;
+pub mod queue;
+pub mod limiter;
+pub mod priority_pass;
+pub mod availability;
+pub mod power;
+pub mod background;
+pub mod idle;
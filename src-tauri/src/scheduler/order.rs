@@ -0,0 +1,131 @@
+use std::collections::{BTreeMap, HashMap};
+use reqwest::Url;
+use crate::enums::feed_priority::FeedPriority;
+use crate::structs::sync_request::SyncRequest;
+
+/// Interleaves feeds by host so that one slow host can't serialize the start of a big sync,
+/// keeping the original order for feeds of the same host.
+pub fn order_sync_requests(requests: Vec<SyncRequest>) -> Vec<SyncRequest> {
+    let mut hosts: Vec<Vec<SyncRequest>> = vec![];
+    let mut host_indexes: HashMap<String, usize> = HashMap::new();
+
+    for request in requests {
+        let host = Url::parse(&request.url).ok()
+            .and_then(|url| url.host_str().map(|host| host.to_lowercase()))
+            .unwrap_or_default();
+
+        match host_indexes.get(&host) {
+            Some(&index) => hosts[index].push(request),
+            None => {
+                host_indexes.insert(host, hosts.len());
+                hosts.push(vec![request]);
+            }
+        }
+    }
+
+    let total = hosts.iter().map(Vec::len).sum();
+    let mut ordered = Vec::with_capacity(total);
+    let mut iterators: Vec<_> = hosts.into_iter().map(Vec::into_iter).collect();
+
+    while ordered.len() < total {
+        for iterator in iterators.iter_mut() {
+            if let Some(request) = iterator.next() {
+                ordered.push(request);
+            }
+        }
+    }
+
+    ordered
+}
+
+/// Orders feeds tier by tier, high priority first, each tier being interleaved by `order_sync_requests`.
+pub fn order_by_priority(requests: Vec<SyncRequest>, priorities: &HashMap<String, FeedPriority>) -> Vec<SyncRequest> {
+    let mut tiers: BTreeMap<FeedPriority, Vec<SyncRequest>> = BTreeMap::new();
+
+    for request in requests {
+        let priority = priorities.get(&request.identifier).copied().unwrap_or_default();
+        tiers.entry(priority).or_default().push(request);
+    }
+
+    tiers.into_values().flat_map(order_sync_requests).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// As (identifier, URL)
+    type Feeds<'a> = &'a [(&'a str, &'a str)];
+
+    fn requests(feeds: &[(&str, &str)]) -> Vec<SyncRequest> {
+        feeds.iter()
+            .map(|(identifier, url)| SyncRequest { identifier: identifier.to_string(), url: url.to_string() })
+            .collect()
+    }
+
+    fn identifiers(requests: Vec<SyncRequest>) -> Vec<String> {
+        requests.into_iter().map(|request| request.identifier).collect()
+    }
+
+    #[test]
+    fn spaces_out_the_feeds_of_a_host() {
+        let cases: [(Feeds, &[&str]); 4] = [
+            (
+                &[("a1", "https://a.example/1"), ("a2", "https://a.example/2"), ("a3", "https://a.example/3"), ("b1", "https://b.example/1"), ("c1", "https://c.example/1")],
+                &["a1", "b1", "c1", "a2", "a3"],
+            ),
+            (
+                &[("a1", "https://a.example/1"), ("b1", "https://b.example/1"), ("a2", "https://A.Example/2"), ("b2", "http://b.example:8080/2")],
+                &["a1", "b1", "a2", "b2"],
+            ),
+            // Invalid URLs are a host of their own
+            (
+                &[("x1", "not a url"), ("x2", ""), ("a1", "https://a.example/1")],
+                &["x1", "a1", "x2"],
+            ),
+            (&[], &[]),
+        ];
+
+        for (feeds, expected) in cases {
+            assert_eq!(identifiers(order_sync_requests(requests(feeds))), expected, "{:?}", feeds);
+        }
+    }
+
+    #[test]
+    fn keeps_the_order_of_a_single_host() {
+        let feeds = [("3", "https://a.example/3"), ("1", "https://a.example/1"), ("2", "https://a.example/2")];
+
+        assert_eq!(identifiers(order_sync_requests(requests(&feeds))), ["3", "1", "2"]);
+    }
+
+    #[test]
+    fn orders_by_priority_then_host() {
+        let feeds = [
+            ("low", "https://a.example/low"),
+            ("a1", "https://a.example/1"),
+            ("a2", "https://a.example/2"),
+            ("high-a", "https://a.example/high"),
+            ("b1", "https://b.example/1"),
+            ("high-b", "https://b.example/high"),
+            ("unknown", "https://c.example/unknown"),
+        ];
+        let priorities = HashMap::from([
+            (String::from("low"), FeedPriority::Low),
+            (String::from("high-a"), FeedPriority::High),
+            (String::from("high-b"), FeedPriority::High),
+            (String::from("a1"), FeedPriority::Normal),
+        ]);
+
+        // Feeds without a priority are normal ones, and the hosts are interleaved within each tier only
+        assert_eq!(identifiers(order_by_priority(requests(&feeds), &priorities)), ["high-a", "high-b", "a1", "b1", "unknown", "a2", "low"]);
+    }
+
+    #[test]
+    fn breaks_ties_by_the_given_order() {
+        let feeds = [("b", "https://b.example/"), ("a2", "https://a.example/2"), ("a1", "https://a.example/1")];
+
+        assert_eq!(identifiers(order_by_priority(requests(&feeds), &HashMap::new())), ["b", "a2", "a1"]);
+        let reversed: Vec<(&str, &str)> = feeds.iter().rev().copied().collect();
+        assert_eq!(identifiers(order_by_priority(requests(&reversed), &HashMap::new())), ["a1", "b", "a2"]);
+    }
+}
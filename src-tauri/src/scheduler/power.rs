@@ -0,0 +1,168 @@
+use std::time::Duration;
+use chrono::Utc;
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::database::{change_journal, local_metrics, notification_ledger};
+use crate::enums::background_job::BackgroundJob;
+use crate::scheduler::idle::wait_for_idle;
+use crate::scheduler::limiter::full_pass_running;
+use crate::structs::activity_monitor::ActivityMonitor;
+use crate::structs::power_monitor::PowerMonitor;
+use crate::structs::power_policy::PowerPolicy;
+use crate::structs::preferences::Preferences;
+use crate::structs::scheduler_state::SchedulerState;
+use crate::utils::power::read_power_status;
+use crate::utils::tray::tray_menu;
+
+const POWER_PROBE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The policy for the last known power status and the current preferences.
+pub fn power_policy(app_handle: &AppHandle) -> PowerPolicy {
+    Preferences::load(app_handle).power_policy(app_handle.state::<PowerMonitor>().status())
+}
+
+pub fn scheduler_state(app_handle: &AppHandle) -> SchedulerState {
+    SchedulerState {
+        power: app_handle.state::<PowerMonitor>().status(),
+        policy: power_policy(app_handle),
+        full_pass_running: full_pass_running(),
+        idle: app_handle.state::<ActivityMonitor>().status(Utc::now().timestamp_millis()),
+    }
+}
+
+/// Reads the power status right away then periodically, switching policies on the next read after
+/// being unplugged or plugged in. Emits `scheduler-policy-changed` and updates the tray on changes.
+/// The idle maintenance starts on the first read allowing it, so on battery it waits until plugged in, then
+/// for the user to be idle.
+pub async fn watch_power(app_handle: AppHandle) {
+    let mut watch = PowerWatch::default();
+
+    loop {
+        let status = tauri::async_runtime::spawn_blocking(read_power_status).await.unwrap_or_default();
+        app_handle.state::<PowerMonitor>().update(status);
+
+        let (changed, maintain) = watch.observe(power_policy(&app_handle));
+        if changed {
+            show_in_tray(&watch.policy, &app_handle);
+            if let Err(e) = app_handle.emit_all("scheduler-policy-changed", scheduler_state(&app_handle)) {
+                eprintln!("Error emitting scheduler policy: {}", e);
+            }
+        }
+
+        if maintain {
+            tauri::async_runtime::spawn(maintain_when_idle(app_handle.clone()));
+        }
+
+        tokio::time::sleep(POWER_PROBE_INTERVAL).await;
+    }
+}
+
+async fn maintain_when_idle(app_handle: AppHandle) {
+    wait_for_idle(BackgroundJob::Maintenance, &app_handle).await;
+    local_metrics::tidy(app_handle.clone()).await;
+    change_journal::tidy(app_handle.clone()).await;
+    notification_ledger::tidy(app_handle).await;
+}
+
+/// The policy of the last read, and whether the idle maintenance ran already.
+#[derive(Default)]
+struct PowerWatch {
+    policy: PowerPolicy,
+    maintained: bool,
+}
+
+impl PowerWatch {
+    /// Returns whether the policy changed, and whether the idle maintenance is due.
+    fn observe(&mut self, current: PowerPolicy) -> (bool, bool) {
+        let changed = current != self.policy;
+        self.policy = current;
+        let maintain = self.policy.idle_maintenance && !self.maintained;
+        self.maintained |= maintain;
+
+        (changed, maintain)
+    }
+}
+
+fn show_in_tray(policy: &PowerPolicy, app_handle: &AppHandle) {
+    let tray = app_handle.tray_handle();
+    let tooltip = match &policy.reason {
+        Some(reason) => format!("Alduin - syncing less often: {}", reason),
+        None => String::from("Alduin"),
+    };
+
+    if let Err(e) = tray.set_menu(tray_menu(policy, app_handle.try_state::<Pool<Sqlite>>().is_some())) {
+        eprintln!("Error updating tray menu: {}", e);
+    }
+    // Not supported on Linux, where the menu shows it
+    if let Err(e) = tray.set_tooltip(&tooltip) {
+        eprintln!("Error updating tray tooltip: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enums::power_source::PowerSource;
+    use crate::structs::power_status::PowerStatus;
+    use super::*;
+
+    fn status(source: PowerSource, battery_percent: Option<u8>) -> PowerStatus {
+        PowerStatus { source, battery_percent }
+    }
+
+    #[test]
+    fn follows_simulated_power_transitions() {
+        let preferences = Preferences::default();
+        let battery = |percent| status(PowerSource::Battery, Some(percent));
+        // The status, then the interval factor, whether the priority pass runs, the reason, whether the
+        // policy changed and whether the idle maintenance runs
+        let transitions = [
+            // Undetected, on the AC policy
+            (status(PowerSource::Unknown, None), 1, true, None, false, true),
+            (battery(80), 3, false, Some("on battery"), true, false),
+            (battery(21), 3, false, Some("on battery"), false, false),
+            (battery(20), 6, false, Some("battery low"), true, false),
+            (battery(5), 6, false, Some("battery low"), false, false),
+            (status(PowerSource::Ac, Some(6)), 1, true, None, true, false),
+            (status(PowerSource::Battery, None), 3, false, Some("on battery"), true, false),
+            (status(PowerSource::Unknown, None), 1, true, None, true, false),
+        ];
+
+        let mut watch = PowerWatch::default();
+        for (case, (status, factor, priority_pass, reason, changed, maintain)) in transitions.into_iter().enumerate() {
+            let policy = preferences.power_policy(status);
+            assert_eq!((policy.interval_factor, policy.priority_pass, policy.reason.as_deref()), (factor, priority_pass, reason), "{:?}", case);
+            // Optional work stops with the priority pass
+            assert_eq!((policy.load_images, policy.idle_maintenance), (priority_pass, priority_pass), "{:?}", case);
+            assert_eq!(watch.observe(policy), (changed, maintain), "{:?}", case);
+        }
+    }
+
+    #[test]
+    fn defers_the_maintenance_until_plugged_in() {
+        let preferences = Preferences::default();
+        let mut watch = PowerWatch::default();
+
+        // Started unplugged
+        assert_eq!(watch.observe(preferences.power_policy(status(PowerSource::Battery, Some(50)))), (true, false));
+        assert_eq!(watch.observe(preferences.power_policy(status(PowerSource::Battery, Some(49)))), (false, false));
+        assert_eq!(watch.observe(preferences.power_policy(status(PowerSource::Ac, Some(49)))), (true, true));
+        // Once per run
+        assert_eq!(watch.observe(preferences.power_policy(status(PowerSource::Battery, Some(49)))), (true, false));
+        assert_eq!(watch.observe(preferences.power_policy(status(PowerSource::Ac, Some(49)))), (true, false));
+    }
+
+    #[test]
+    fn applies_the_battery_preferences() {
+        let on_battery = status(PowerSource::Battery, Some(30));
+
+        let off = Preferences { battery_saver: false, ..Preferences::default() };
+        assert_eq!(off.power_policy(on_battery), PowerPolicy::default());
+
+        let low_at_30 = Preferences { battery_interval_factor: 4, low_battery_percent: 30, ..Preferences::default() };
+        assert_eq!(low_at_30.power_policy(on_battery).interval_factor, 8);
+
+        // A factor of 0 would sync continuously
+        let zero = Preferences { battery_interval_factor: 0, ..Preferences::default() };
+        assert_eq!(zero.power_policy(on_battery).interval_factor, 1);
+    }
+}
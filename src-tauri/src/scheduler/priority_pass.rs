@@ -0,0 +1,70 @@
+use std::time::Duration;
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::database::change_journal::emit_article_change;
+use crate::database::health;
+use crate::database::notification_ledger::notify_new_articles;
+use crate::scheduler::availability::scheduled_sync;
+use crate::scheduler::limiter::full_pass_running;
+use crate::scheduler::power::power_policy;
+use crate::structs::preferences::Preferences;
+use crate::structs::sync_outcome::SyncOutcome;
+use crate::structs::sync_progress::SyncProgress;
+use crate::structs::sync_request::SyncRequest;
+use crate::structs::sync_writer::SyncWriter;
+use crate::structs::write_gate::WriteGate;
+
+/// Syncs the high priority feeds not synced for `fastPassMinutes`, every `fastPassMinutes`.
+/// A pass is skipped while a full one is running, which already syncs those feeds first, and when the power
+/// policy disables it. Otherwise the policy stretches the wait.
+pub async fn run(app_handle: AppHandle) {
+    loop {
+        let minutes = Preferences::load(&app_handle).fast_pass_minutes.max(1);
+        tokio::time::sleep(Duration::from_secs(minutes * power_policy(&app_handle).interval_factor * 60)).await;
+
+        if full_pass_running() || !power_policy(&app_handle).priority_pass || app_handle.state::<WriteGate>().check().is_err() {
+            continue;
+        }
+
+        if let Err(e) = sync_due_feeds(minutes, &app_handle).await {
+            eprintln!("Error running priority pass: {}", e);
+        }
+        if !full_pass_running() && app_handle.state::<WriteGate>().check().is_ok() {
+            health::checkpoint(&app_handle.state::<Pool<Sqlite>>()).await;
+        }
+    }
+}
+
+async fn sync_due_feeds(minutes: u64, app_handle: &AppHandle) -> Result<(), String> {
+    let pool = app_handle.state::<Pool<Sqlite>>();
+    let due: Vec<(String, String)> = sqlx::query_as(include_str!("../database/queries/get_due_priority_feeds.sql"))
+        .bind(minutes as i64)
+        .fetch_all(&*pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    let total = due.len();
+    let writer = SyncWriter::start(pool.inner().clone(), app_handle.state::<WriteGate>().inner().clone());
+    for (index, (identifier, url)) in due.into_iter().enumerate() {
+        if full_pass_running() {
+            break;
+        }
+
+        let (outcome, error) = match scheduled_sync(SyncRequest { identifier: identifier.clone(), url }, writer.clone(), app_handle.clone()).await {
+            Ok(outcome) => (outcome, None),
+            Err(e) => {
+                eprintln!("Error syncing feed {}: {}", identifier, e);
+                (SyncOutcome::default(), Some(e))
+            }
+        };
+
+        let SyncOutcome { not_modified, warnings, .. } = outcome;
+        let changed = !not_modified && error.is_none();
+        let progress = SyncProgress { identifier: identifier.clone(), current: index + 1, total, eta_ms: 0, not_modified, warnings, error };
+        emit_article_change(app_handle, "sync-progress", progress, |filter| changed && filter.may_show(&identifier, None)).await?;
+    }
+
+    if total > 0 {
+        notify_new_articles(app_handle).await;
+    }
+    Ok(())
+}
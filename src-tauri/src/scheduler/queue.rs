@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use sqlx::{Pool, Sqlite};
+use crate::enums::feed_priority::FeedPriority;
+use crate::scheduler::order::order_by_priority;
+use crate::structs::sync_request::SyncRequest;
+
+/// Returns the feeds to sync for this run. When a previous run was interrupted, the feeds it didn't reach
+/// are synced in their queued order, followed by the requested feeds it didn't have, otherwise every feed is
+/// queued in the order given by `order_by_priority`. Archived feeds are left out.
+///
+/// Writes the queue, so the caller holds a `WriteGuard`.
+pub async fn resume_or_start(requests: Vec<SyncRequest>, pool: &Pool<Sqlite>) -> Result<Vec<SyncRequest>, String> {
+    let queued: Vec<(String, bool)> = sqlx::query_as(include_str!("../database/queries/get_sync_queue.sql"))
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    let archived: Vec<String> = sqlx::query_scalar(include_str!("../database/queries/get_archived_feeds.sql"))
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    let pending = |identifier: &str| queued.iter().position(|(queued, done)| !done && queued == identifier);
+    let (mut resumed, others): (Vec<SyncRequest>, Vec<SyncRequest>) = requests.into_iter()
+        .filter(|request| !archived.contains(&request.identifier))
+        .partition(|request| pending(&request.identifier).is_some());
+    resumed.sort_by_key(|request| pending(&request.identifier));
+
+    // Everything left in the queue may have been deleted since, start over in that case
+    let resuming = !resumed.is_empty();
+    let others = others.into_iter()
+        .filter(|request| !resuming || !queued.iter().any(|(queued, _)| queued == &request.identifier))
+        .collect();
+
+    let priorities: HashMap<String, FeedPriority> = sqlx::query_as(include_str!("../database/queries/get_feed_priorities.sql"))
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?
+        .into_iter()
+        .collect();
+
+    let added = order_by_priority(others, &priorities);
+
+    let mut transaction = pool.begin().await.map_err(|e| format!("Error starting transaction: {}", e))?;
+    let first_position = if resuming {
+        queued.len()
+    } else {
+        sqlx::query(include_str!("../database/queries/clear_sync_queue.sql"))
+            .execute(&mut *transaction).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+        0
+    };
+    for (position, request) in added.iter().enumerate() {
+        sqlx::query(include_str!("../database/queries/insert_sync_queue.sql"))
+            .bind(&request.identifier)
+            .bind((first_position + position) as i64)
+            .execute(&mut *transaction).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+    }
+    transaction.commit().await.map_err(|e| format!("Error committing transaction: {}", e))?;
+
+    resumed.extend(added);
+    Ok(resumed)
+}
+
+/// Kept in the queue until the next run starts over, so a resumed run knows the feed was reached. Writes
+/// the queue, so the caller holds a `WriteGuard`.
+pub async fn complete(identifier: &str, pool: &Pool<Sqlite>) -> Result<(), String> {
+    sqlx::query(include_str!("../database/queries/complete_sync_queue.sql"))
+        .bind(identifier)
+        .execute(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::database::migrator::migrator;
+    use super::*;
+
+    /// Feeds as (identifier, host, archived)
+    async fn fixture() -> Pool<Sqlite> {
+        let feeds = [
+            ("a1", "a.example", false),
+            ("a2", "a.example", false),
+            ("b1", "b.example", false),
+            ("c1", "c.example", false),
+            ("archived", "a.example", true),
+        ];
+
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+
+        for (identifier, host, archived) in feeds {
+            sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval, archived) VALUES (?, ?, ?, 60, ?)")
+                .bind(identifier).bind(identifier).bind(format!("https://{}/{}", host, identifier)).bind(archived)
+                .execute(&pool).await.unwrap();
+        }
+
+        pool
+    }
+
+    fn requests(identifiers: &[&str]) -> Vec<SyncRequest> {
+        identifiers.iter()
+            .map(|identifier| SyncRequest {
+                identifier: identifier.to_string(),
+                url: format!("https://{}.example/{}", &identifier[..1], identifier),
+            })
+            .collect()
+    }
+
+    async fn run(identifiers: &[&str], pool: &Pool<Sqlite>) -> Vec<String> {
+        resume_or_start(requests(identifiers), pool).await.unwrap().into_iter()
+            .map(|request| request.identifier)
+            .collect()
+    }
+
+    /// The feeds left to sync, in their order
+    async fn pending(pool: &Pool<Sqlite>) -> Vec<String> {
+        sqlx::query_as::<_, (String, bool)>(include_str!("../database/queries/get_sync_queue.sql"))
+            .fetch_all(pool).await.unwrap()
+            .into_iter()
+            .filter_map(|(identifier, done)| (!done).then_some(identifier))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn starts_interleaved_without_the_archived_feeds() {
+        let pool = fixture().await;
+
+        assert_eq!(run(&["a1", "a2", "archived", "b1"], &pool).await, ["a1", "b1", "a2"]);
+        assert_eq!(pending(&pool).await, ["a1", "b1", "a2"]);
+    }
+
+    #[tokio::test]
+    async fn resumes_where_the_run_stopped() {
+        let pool = fixture().await;
+        run(&["a1", "a2", "b1"], &pool).await;
+        complete("a1", &pool).await.unwrap();
+
+        assert_eq!(run(&["a1", "a2", "b1"], &pool).await, ["b1", "a2"]);
+        assert_eq!(pending(&pool).await, ["b1", "a2"]);
+    }
+
+    #[tokio::test]
+    async fn resumes_with_the_new_feeds_last() {
+        let pool = fixture().await;
+        run(&["a1", "a2", "b1"], &pool).await;
+        complete("a1", &pool).await.unwrap();
+
+        assert_eq!(run(&["c1", "a1", "a2", "b1"], &pool).await, ["b1", "a2", "c1"]);
+        assert_eq!(pending(&pool).await, ["b1", "a2", "c1"]);
+    }
+
+    #[tokio::test]
+    async fn starts_over_once_every_feed_was_reached() {
+        let pool = fixture().await;
+        run(&["a1", "b1"], &pool).await;
+        complete("a1", &pool).await.unwrap();
+        complete("b1", &pool).await.unwrap();
+
+        assert_eq!(run(&["a1", "b1", "c1"], &pool).await, ["a1", "b1", "c1"]);
+        assert_eq!(pending(&pool).await, ["a1", "b1", "c1"]);
+
+        // The queue left may also only have feeds not requested anymore
+        assert_eq!(run(&["a2"], &pool).await, ["a2"]);
+        assert_eq!(pending(&pool).await, ["a2"]);
+    }
+}
@@ -0,0 +1,287 @@
+use regex::{Regex, RegexBuilder};
+use crate::enums::scoring_condition::ScoringCondition;
+use crate::structs::condition_outcome::ConditionOutcome;
+use crate::structs::scoring_rule::ScoringRule;
+use crate::structs::scoring_subject::ScoringSubject;
+
+const MAX_EXCERPT_CHARS: usize = 60;
+
+enum CompiledCondition {
+    Keyword(String),
+    Regex(Regex),
+    Feed(String),
+    Folder(String),
+    Language(String),
+    MinWords(usize),
+    MaxWords(usize),
+}
+
+pub struct CompiledRule {
+    conditions: Vec<CompiledCondition>,
+    weight: i64,
+}
+
+impl CompiledRule {
+    /// Validates the rule and prepares it for evaluation, so regexes are only built once per batch.
+    pub fn compile(rule: &ScoringRule) -> Result<Self, String> {
+        if rule.conditions.is_empty() {
+            return Err(String::from("A scoring rule needs at least one condition"));
+        }
+        if rule.weight == 0 {
+            return Err(String::from("A scoring rule needs a non-zero weight"));
+        }
+
+        let conditions = rule.conditions.iter()
+            .map(|condition| match condition {
+                ScoringCondition::Keyword(keyword) if keyword.trim().is_empty() => Err(String::from("Keyword can't be empty")),
+                ScoringCondition::Keyword(keyword) => Ok(CompiledCondition::Keyword(keyword.trim().to_lowercase())),
+                ScoringCondition::Regex(pattern) => RegexBuilder::new(pattern)
+                    .case_insensitive(true)
+                    .size_limit(1 << 20)
+                    .build()
+                    .map(CompiledCondition::Regex)
+                    .map_err(|e| format!("Invalid regex: {}", e)),
+                ScoringCondition::Feed(identifier) if identifier.is_empty() => Err(String::from("Feed can't be empty")),
+                ScoringCondition::Feed(identifier) => Ok(CompiledCondition::Feed(identifier.clone())),
+                ScoringCondition::Folder(identifier) if identifier.is_empty() => Err(String::from("Folder can't be empty")),
+                ScoringCondition::Folder(identifier) => Ok(CompiledCondition::Folder(identifier.clone())),
+                ScoringCondition::Language(language) if language.trim().is_empty() => Err(String::from("Language can't be empty")),
+                ScoringCondition::Language(language) => Ok(CompiledCondition::Language(normalize_language(language))),
+                ScoringCondition::MinWords(words) => Ok(CompiledCondition::MinWords(*words as usize)),
+                ScoringCondition::MaxWords(words) => Ok(CompiledCondition::MaxWords(*words as usize)),
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(CompiledRule { conditions, weight: rule.weight })
+    }
+
+    pub fn weight(&self) -> i64 {
+        self.weight
+    }
+
+    pub fn matches(&self, subject: &ScoringSubject) -> bool {
+        // Only computed when a length condition needs it
+        let mut words: Option<usize> = None;
+        let mut word_count = || *words.get_or_insert_with(|| count_words(subject.content));
+
+        self.conditions.iter().all(|condition| condition.holds(subject, &mut word_count))
+    }
+
+    /// Every condition described along with whether it holds, the rule matching when they all do.
+    /// Unlike `matches`, the conditions after a failing one are still evaluated.
+    pub fn explain(&self, subject: &ScoringSubject) -> Vec<ConditionOutcome> {
+        let ScoringSubject { title, content, .. } = *subject;
+        let words = count_words(content);
+
+        self.conditions.iter()
+            .map(|condition| {
+                let matched = condition.holds(subject, &mut || words);
+                let description = match condition {
+                    CompiledCondition::Keyword(keyword) => format!("Title contains \"{}\"", keyword),
+                    CompiledCondition::Regex(regex) => match regex.find(title).or_else(|| regex.find(content)) {
+                        Some(found) => format!("Title or content matches /{}/, found \"{}\"", regex.as_str(), excerpt(found.as_str())),
+                        None => format!("Title or content matches /{}/", regex.as_str()),
+                    },
+                    CompiledCondition::Feed(identifier) => format!("From feed {}", identifier),
+                    CompiledCondition::Folder(identifier) => format!("From a feed in folder {}", identifier),
+                    CompiledCondition::Language(language) => format!("Written in {}, is {}", language, subject.language.unwrap_or("unknown")),
+                    CompiledCondition::MinWords(min) => format!("At least {} words, has {}", min, words),
+                    CompiledCondition::MaxWords(max) => format!("At most {} words, has {}", max, words),
+                };
+                ConditionOutcome { description, matched }
+            })
+            .collect()
+    }
+}
+
+impl CompiledCondition {
+    fn holds(&self, subject: &ScoringSubject, word_count: &mut impl FnMut() -> usize) -> bool {
+        match self {
+            CompiledCondition::Keyword(keyword) => subject.title.to_lowercase().contains(keyword),
+            CompiledCondition::Regex(regex) => regex.is_match(subject.title) || regex.is_match(subject.content),
+            CompiledCondition::Feed(identifier) => identifier == subject.feed_identifier,
+            CompiledCondition::Folder(identifier) => subject.folders.contains(identifier),
+            CompiledCondition::Language(language) => subject.language.is_some_and(|subject_language| {
+                let subject_language = normalize_language(subject_language);
+                subject_language == *language || subject_language.strip_prefix(language.as_str()).is_some_and(|rest| rest.starts_with('-'))
+            }),
+            CompiledCondition::MinWords(min) => word_count() >= *min,
+            CompiledCondition::MaxWords(max) => word_count() <= *max,
+        }
+    }
+}
+
+pub fn compile_rules(rules: &[ScoringRule]) -> Result<Vec<CompiledRule>, String> {
+    rules.iter().map(CompiledRule::compile).collect()
+}
+
+/// Sums the weights of the matching rules, the order of the rules doesn't matter.
+pub fn score(rules: &[CompiledRule], subject: &ScoringSubject) -> i64 {
+    rules.iter()
+        .filter(|rule| rule.matches(subject))
+        .map(CompiledRule::weight)
+        .sum()
+}
+
+/// Feeds write "en_US" or "EN-us" as well.
+fn normalize_language(language: &str) -> String {
+    language.trim().replace('_', "-").to_lowercase()
+}
+
+/// Counts the words of the content, ignoring markup.
+fn count_words(content: &str) -> usize {
+    let mut text = String::with_capacity(content.len());
+    let mut in_tag = false;
+
+    for c in content.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().count()
+}
+
+/// Regexes may match whole paragraphs, only their start is shown.
+fn excerpt(found: &str) -> String {
+    match found.char_indices().nth(MAX_EXCERPT_CHARS) {
+        Some((end, _)) => format!("{}…", &found[..end]),
+        None => found.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::types::Json;
+    use super::*;
+
+    const FOLDERS: [&str; 2] = ["rust", "programming"];
+
+    fn rule(conditions: Vec<ScoringCondition>, weight: i64) -> ScoringRule {
+        ScoringRule { identifier: String::from("rule"), conditions: Json(conditions), weight }
+    }
+
+    fn compiled(condition: ScoringCondition) -> CompiledRule {
+        CompiledRule::compile(&rule(vec![condition], 1)).unwrap()
+    }
+
+    fn subject<'a>(folders: &'a [String], language: Option<&'a str>) -> ScoringSubject<'a> {
+        ScoringSubject {
+            feed_identifier: "feed",
+            folders,
+            language,
+            title: "Rust 2.0 released",
+            content: "<p>The <b>new</b> edition</p>",
+        }
+    }
+
+    #[test]
+    fn conditions() {
+        let folders: Vec<String> = FOLDERS.iter().map(|folder| folder.to_string()).collect();
+        let cases = [
+            (ScoringCondition::Keyword(String::from(" RUST ")), true),
+            (ScoringCondition::Keyword(String::from("python")), false),
+            (ScoringCondition::Regex(String::from(r"\bedition\b")), true),
+            (ScoringCondition::Regex(String::from("^released")), false),
+            (ScoringCondition::Feed(String::from("feed")), true),
+            (ScoringCondition::Feed(String::from("other")), false),
+            (ScoringCondition::Folder(String::from("rust")), true),
+            // Subfolders are part of their parent
+            (ScoringCondition::Folder(String::from("programming")), true),
+            (ScoringCondition::Folder(String::from("cooking")), false),
+            (ScoringCondition::MinWords(3), true),
+            (ScoringCondition::MinWords(4), false),
+            (ScoringCondition::MaxWords(3), true),
+            (ScoringCondition::MaxWords(2), false),
+        ];
+
+        for (condition, expected) in cases {
+            assert_eq!(compiled(condition.clone()).matches(&subject(&folders, None)), expected, "{:?}", condition);
+        }
+        assert!(!compiled(ScoringCondition::Folder(String::from("rust"))).matches(&subject(&[], None)));
+    }
+
+    #[test]
+    fn language_conditions() {
+        let cases = [
+            ("en", Some("en"), true),
+            ("en", Some("en-GB"), true),
+            ("EN", Some("en_us"), true),
+            ("en-gb", Some("en-GB"), true),
+            ("en", Some("eng"), false),
+            ("en-GB", Some("en"), false),
+            ("pt-BR", Some("pt-PT"), false),
+            ("en", None, false),
+        ];
+
+        for (language, article_language, expected) in cases {
+            let rule = compiled(ScoringCondition::Language(String::from(language)));
+            assert_eq!(rule.matches(&subject(&[], article_language)), expected, "{} against {:?}", language, article_language);
+        }
+    }
+
+    #[test]
+    fn invalid_rules() {
+        let cases = [
+            (rule(vec![], 1), "A scoring rule needs at least one condition"),
+            (rule(vec![ScoringCondition::MinWords(1)], 0), "A scoring rule needs a non-zero weight"),
+            (rule(vec![ScoringCondition::Keyword(String::from("  "))], 1), "Keyword can't be empty"),
+            (rule(vec![ScoringCondition::Feed(String::new())], 1), "Feed can't be empty"),
+            (rule(vec![ScoringCondition::Folder(String::new())], 1), "Folder can't be empty"),
+            (rule(vec![ScoringCondition::Language(String::from(" "))], 1), "Language can't be empty"),
+        ];
+
+        for (rule, expected) in cases {
+            assert_eq!(CompiledRule::compile(&rule).err().as_deref(), Some(expected));
+        }
+        let invalid_regex = CompiledRule::compile(&rule(vec![ScoringCondition::Regex(String::from("("))], 1));
+        assert!(invalid_regex.err().is_some_and(|e| e.starts_with("Invalid regex: ")));
+    }
+
+    #[test]
+    fn sums_the_matching_rules_in_any_order() {
+        let rules = [
+            rule(vec![ScoringCondition::Keyword(String::from("rust"))], 5),
+            rule(vec![ScoringCondition::Feed(String::from("feed")), ScoringCondition::MaxWords(200)], -3),
+            rule(vec![ScoringCondition::Keyword(String::from("python"))], 10),
+            rule(vec![ScoringCondition::Language(String::from("en"))], 2),
+        ];
+        let subject = subject(&[], Some("en-US"));
+
+        let forward = compile_rules(&rules).unwrap();
+        let mut reversed = rules.to_vec();
+        reversed.reverse();
+        let reversed = compile_rules(&reversed).unwrap();
+
+        assert_eq!(score(&forward, &subject), 4);
+        assert_eq!(score(&reversed, &subject), 4);
+        // The same article always gets the same score
+        assert_eq!(score(&forward, &subject), score(&forward, &subject));
+        assert_eq!(score(&[], &subject), 0);
+    }
+
+    #[test]
+    fn explains_every_condition() {
+        let rule = CompiledRule::compile(&rule(vec![
+            ScoringCondition::Keyword(String::from("python")),
+            ScoringCondition::Language(String::from("fr")),
+            ScoringCondition::MinWords(2),
+        ], 1)).unwrap();
+
+        let outcomes: Vec<(String, bool)> = rule.explain(&subject(&[], None)).into_iter()
+            .map(|outcome| (outcome.description, outcome.matched))
+            .collect();
+
+        assert_eq!(outcomes, [
+            (String::from("Title contains \"python\""), false),
+            (String::from("Written in fr, is unknown"), false),
+            (String::from("At least 2 words, has 3"), true),
+        ]);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::enums::search_term::SearchTerm;
+use crate::structs::search_error::SearchError;
+use crate::structs::search_filters::SearchFilters;
+use crate::structs::search_query::SearchQuery;
+
+/// Compiles a parsed query into the FTS expression and SQL filters of the search query.
+pub fn compile_filters(query: &str, parsed: &SearchQuery) -> Result<SearchFilters, SearchError> {
+    let mut filters = SearchFilters::default();
+    let mut included: Vec<String> = vec![];
+    let mut excluded: Vec<String> = vec![];
+    let mut first_excluded: Option<(usize, usize)> = None;
+
+    for clause in parsed.clauses.iter() {
+        let error = |message: &str| SearchError::new(query, clause.start, clause.end, message);
+
+        let texts: Option<Vec<String>> = clause.terms.iter().map(match_expression).collect();
+        if let Some(texts) = texts {
+            let expression = if texts.len() > 1 { format!("({})", texts.join(" OR ")) } else { texts.join("") };
+            if clause.negated {
+                excluded.push(expression);
+                first_excluded.get_or_insert((clause.start, clause.end));
+            } else {
+                included.push(expression);
+            }
+            continue;
+        }
+
+        let feeds: Option<Vec<String>> = clause.terms.iter()
+            .map(|term| match term {
+                SearchTerm::Feed(name) => Some(name.to_lowercase()),
+                _ => None,
+            })
+            .collect();
+        if let Some(feeds) = feeds {
+            if clause.negated {
+                filters.excluded_feeds.extend(feeds);
+            } else if filters.feeds.is_some() {
+                return Err(error("An article belongs to a single feed, use OR to search several"));
+            } else {
+                filters.feeds = Some(feeds);
+            }
+            continue;
+        }
+
+        let [term] = clause.terms.as_slice() else {
+            return Err(error("OR can only combine words, phrases or feeds"));
+        };
+
+        match (term, clause.negated) {
+            (SearchTerm::Read(read), negated) => {
+                let read = *read != negated;
+                if filters.read.is_some_and(|known| known != read) {
+                    return Err(error("Articles can't be both read and unread"));
+                }
+                filters.read = Some(read);
+            }
+            (SearchTerm::Before(date), false) | (SearchTerm::After(date), true) => {
+                filters.before = Some(filters.before.map_or(*date, |before| before.min(*date)));
+            }
+            (SearchTerm::After(date), false) | (SearchTerm::Before(date), true) => {
+                filters.after = Some(filters.after.map_or(*date, |after| after.max(*date)));
+            }
+            _ => return Err(error("This term can't be used here")),
+        }
+    }
+
+    if let (true, Some((start, end))) = (included.is_empty(), first_excluded) {
+        return Err(SearchError::new(query, start, end, "Excluding words needs at least one word to search for"));
+    }
+    if !included.is_empty() {
+        let mut expression = included.join(" AND ");
+        for exclusion in excluded {
+            expression = format!("{} NOT {}", expression, exclusion);
+        }
+        filters.text = Some(expression);
+    }
+
+    Ok(filters)
+}
+
+/// Every user-provided text is an FTS5 string, so no input can be read as FTS syntax.
+fn match_expression(term: &SearchTerm) -> Option<String> {
+    let quote = |text: &str| format!("\"{}\"", text.replace('"', "\"\""));
+
+    match term {
+        SearchTerm::Text(text) => Some(quote(text)),
+        SearchTerm::Title(text) => Some(format!("title : {}", quote(text))),
+        _ => None,
+    }
+}
@@ -0,0 +1,276 @@
+use chrono::NaiveDate;
+use crate::enums::search_term::SearchTerm;
+use crate::structs::search_clause::SearchClause;
+use crate::structs::search_error::SearchError;
+use crate::structs::search_query::SearchQuery;
+use crate::utils::input_limits::{too_large, MAX_SEARCH_QUERY_BYTES};
+use crate::utils::time_format::start_of_day;
+
+struct Token<'a> {
+    negated: bool,
+    field: Option<&'a str>,
+    value: &'a str,
+    quoted: bool,
+    /// The token without its `-`, for unknown fields which are searched as text
+    raw: &'a str,
+    start: usize,
+    end: usize,
+}
+
+impl Token<'_> {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        !self.negated && !self.quoted && self.field.is_none() && self.value == keyword
+    }
+}
+
+/// Parses a search query: words and quoted phrases, `title:`, `feed:`, `is:read`/`is:unread`,
+/// `before:`/`after:` dates as `YYYY-MM-DD`, `-` to exclude a term and `OR` between terms.
+/// Terms are implicitly joined by AND, OR binding tighter.
+pub fn parse_query(query: &str) -> Result<SearchQuery, SearchError> {
+    if query.len() > MAX_SEARCH_QUERY_BYTES {
+        return Err(SearchError::new(query, 0, 0, &too_large("The search", MAX_SEARCH_QUERY_BYTES)));
+    }
+    let mut clauses: Vec<SearchClause> = vec![];
+    let mut pending_or: Option<(usize, usize)> = None;
+
+    for token in tokenize(query)? {
+        if token.is_keyword("AND") {
+            continue;
+        }
+
+        if token.is_keyword("OR") {
+            let joinable = pending_or.is_none() && clauses.last().is_some_and(|clause| !clause.negated);
+            if !joinable {
+                return Err(SearchError::new(query, token.start, token.end, "OR needs a term that isn't excluded on each side"));
+            }
+            pending_or = Some((token.start, token.end));
+            continue;
+        }
+
+        let term = parse_term(query, &token)?;
+
+        match (pending_or.take(), clauses.last_mut()) {
+            (Some(_), Some(clause)) => {
+                if token.negated {
+                    return Err(SearchError::new(query, token.start, token.end, "Excluded terms can't be combined with OR"));
+                }
+                clause.terms.push(term);
+                clause.end = token.end;
+            }
+            _ => clauses.push(SearchClause {
+                negated: token.negated,
+                terms: vec![term],
+                start: token.start,
+                end: token.end,
+            }),
+        }
+    }
+
+    if let Some((start, end)) = pending_or {
+        return Err(SearchError::new(query, start, end, "OR needs a term that isn't excluded on each side"));
+    }
+    if clauses.is_empty() {
+        return Err(SearchError::new(query, 0, query.len(), "Type something to search for"));
+    }
+
+    Ok(SearchQuery { clauses })
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token<'_>>, SearchError> {
+    let mut tokens = vec![];
+    let mut index = 0;
+
+    while let Some(character) = query[index..].chars().next() {
+        if character.is_whitespace() {
+            index += character.len_utf8();
+            continue;
+        }
+
+        let start = index;
+        let negated = character == '-';
+        if negated {
+            index += 1;
+        }
+        let raw_start = index;
+
+        let rest = &query[index..];
+        let prefix_length = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        let field = if prefix_length > 0 && rest[prefix_length..].starts_with(':') {
+            index += prefix_length + 1;
+            Some(&rest[..prefix_length])
+        } else {
+            None
+        };
+
+        let quoted = query[index..].starts_with('"');
+        let value = if quoted {
+            let Some(length) = query[index + 1..].find('"') else {
+                return Err(SearchError::new(query, index, query.len(), "Unbalanced quote, a phrase needs a closing \""));
+            };
+            let value = &query[index + 1..index + 1 + length];
+            index += length + 2;
+            value
+        } else {
+            let length = query[index..].find(char::is_whitespace).unwrap_or(query.len() - index);
+            let value = &query[index..index + length];
+            if let Some(position) = value.find('"') {
+                let quote = index + position;
+                return Err(SearchError::new(query, quote, quote + 1, "Unexpected quote, quotes go around a whole phrase"));
+            }
+            index += length;
+            value
+        };
+
+        tokens.push(Token { negated, field, value, quoted, raw: &query[raw_start..index], start, end: index });
+    }
+
+    Ok(tokens)
+}
+
+fn parse_term(query: &str, token: &Token) -> Result<SearchTerm, SearchError> {
+    let error = |message: &str| SearchError::new(query, token.start, token.end, message);
+    let value = token.value.trim();
+
+    let Some(field) = token.field.map(str::to_lowercase) else {
+        if value.is_empty() {
+            return Err(error(if token.quoted { "Empty phrase" } else { "Nothing to exclude after -" }));
+        }
+        return Ok(SearchTerm::Text(value.to_string()));
+    };
+
+    let known = matches!(field.as_str(), "title" | "feed" | "is" | "tag" | "before" | "after");
+    if !known {
+        // Most likely not meant as a filter, like a URL
+        return Ok(SearchTerm::Text(token.raw.replace('"', "")));
+    }
+    if value.is_empty() {
+        return Err(error(&format!("Missing value after {}:", field)));
+    }
+
+    match field.as_str() {
+        "title" => Ok(SearchTerm::Title(value.to_string())),
+        "feed" => Ok(SearchTerm::Feed(value.to_string())),
+        "is" => match value.to_lowercase().as_str() {
+            "unread" => Ok(SearchTerm::Read(false)),
+            "read" => Ok(SearchTerm::Read(true)),
+            "starred" => Err(error("Starred articles aren't supported yet")),
+            _ => Err(error("Expected is:unread or is:read")),
+        },
+        "tag" => Err(error("Tags aren't supported yet")),
+        _ => {
+            let day = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map_err(|_| error("Expected a date like 2024-01-31"))?;
+            let start = start_of_day(day);
+
+            Ok(if field == "before" { SearchTerm::Before(start) } else { SearchTerm::After(start) })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::search::filters::compile_filters;
+    use super::*;
+
+    /// Each clause as (negated, terms)
+    fn clauses(query: &str) -> Vec<(bool, Vec<SearchTerm>)> {
+        let parsed = parse_query(query).unwrap_or_else(|e| panic!("{:?} failed: {}", query, e.message));
+        parsed.clauses.into_iter().map(|clause| (clause.negated, clause.terms)).collect()
+    }
+
+    fn text(text: &str) -> SearchTerm {
+        SearchTerm::Text(text.to_string())
+    }
+
+    #[test]
+    fn phrases() {
+        let cases = [
+            ("\"rust release\"", vec![(false, vec![text("rust release")])]),
+            ("\"  padded  \"", vec![(false, vec![text("padded")])]),
+            ("-\"release notes\" rust", vec![(true, vec![text("release notes")]), (false, vec![text("rust")])]),
+            ("title:\"this week\"", vec![(false, vec![SearchTerm::Title(String::from("this week"))])]),
+            ("\"a\" OR \"b c\"", vec![(false, vec![text("a"), text("b c")])]),
+            ("\"OR\" \"AND\"", vec![(false, vec![text("OR")]), (false, vec![text("AND")])]),
+            ("\"émoji 🦀\"", vec![(false, vec![text("émoji 🦀")])]),
+            ("rust AND sqlite", vec![(false, vec![text("rust")]), (false, vec![text("sqlite")])]),
+            ("feed:News is:unread", vec![(false, vec![SearchTerm::Feed(String::from("News"))]), (false, vec![SearchTerm::Read(false)])]),
+        ];
+
+        for (query, expected) in cases {
+            assert_eq!(clauses(query), expected, "{:?}", query);
+        }
+    }
+
+    /// As (query, message, token)
+    #[test]
+    fn errors() {
+        let cases = [
+            ("\"rust release", "Unbalanced quote, a phrase needs a closing \"", "\"rust release"),
+            ("rust \"", "Unbalanced quote, a phrase needs a closing \"", "\""),
+            ("title:\"open", "Unbalanced quote, a phrase needs a closing \"", "\"open"),
+            ("rel\"ease", "Unexpected quote, quotes go around a whole phrase", "\""),
+            ("\"\"", "Empty phrase", "\"\""),
+            ("\"   \"", "Empty phrase", "\"   \""),
+            ("-", "Nothing to exclude after -", "-"),
+            ("OR rust", "OR needs a term that isn't excluded on each side", "OR"),
+            ("rust OR", "OR needs a term that isn't excluded on each side", "OR"),
+            ("-rust OR go", "OR needs a term that isn't excluded on each side", "OR"),
+            ("title:", "Missing value after title:", "title:"),
+            ("is:maybe", "Expected is:unread or is:read", "is:maybe"),
+            ("before:31-01-2024", "Expected a date like 2024-01-31", "before:31-01-2024"),
+        ];
+
+        for (query, message, token) in cases {
+            let error = parse_query(query).expect_err(query);
+            assert_eq!((error.message.as_str(), error.token.as_str()), (message, token), "{:?}", query);
+        }
+    }
+
+    #[test]
+    fn error_offsets_are_utf16() {
+        let error = parse_query("🦀 \"open").unwrap_err();
+
+        assert_eq!((error.start, error.end), (3, 8));
+    }
+
+    #[test]
+    fn empty_input() {
+        for query in ["", " ", "\t\n ", "AND", "AND AND"] {
+            let error = parse_query(query).expect_err(query);
+            assert_eq!(error.message, "Type something to search for", "{:?}", query);
+        }
+    }
+
+    #[test]
+    fn too_large_input() {
+        let query = "a".repeat(MAX_SEARCH_QUERY_BYTES + 1);
+
+        assert!(parse_query(&query).is_err());
+        assert!(parse_query(&query[1..]).is_ok());
+    }
+
+    /// FTS5 syntax in the query is searched for as text, as (query, FTS expression)
+    #[test]
+    fn fts_special_characters() {
+        let cases = [
+            ("c++", "\"c++\""),
+            ("wild*", "\"wild*\""),
+            ("^start", "\"^start\""),
+            ("NEAR(a b)", "\"NEAR(a\" AND \"b)\""),
+            ("\"NOT this\"", "\"NOT this\""),
+            ("a:b", "\"a:b\""),
+            ("https://example.com/a?b=c", "\"https://example.com/a?b=c\""),
+            ("content:secret", "\"content:secret\""),
+            ("ti:\"quoted\"", "\"ti:quoted\""),
+            ("(a)", "\"(a)\""),
+            ("{title}", "\"{title}\""),
+            ("title:\"it's\" --", "title : \"it's\" NOT \"-\""),
+        ];
+
+        for (query, expression) in cases {
+            let parsed = parse_query(query).unwrap_or_else(|e| panic!("{:?} failed: {}", query, e.message));
+            let filters = compile_filters(query, &parsed).unwrap_or_else(|e| panic!("{:?} failed: {}", query, e.message));
+            assert_eq!(filters.text.as_deref(), Some(expression), "{:?}", query);
+        }
+    }
+}
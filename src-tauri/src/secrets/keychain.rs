@@ -0,0 +1,53 @@
+//! The keychain of the OS: the Keychain on macOS, the Credential Manager on Windows and the Secret Service
+//! on Linux. The tests keep the secrets in memory instead.
+
+#[cfg(not(test))]
+use keyring::{Entry, Error};
+
+#[cfg(not(test))]
+const SERVICE: &str = "alduin";
+
+#[cfg(not(test))]
+pub fn set(reference: &str, secret: &str) -> Result<(), String> {
+    Entry::new(SERVICE, reference)
+        .and_then(|entry| entry.set_password(secret))
+        .map_err(|e| format!("Error storing the secret in the keychain: {}", e))
+}
+
+#[cfg(not(test))]
+pub fn get(reference: &str) -> Result<Option<String>, String> {
+    match Entry::new(SERVICE, reference).and_then(|entry| entry.get_password()) {
+        Ok(secret) => Ok(Some(secret)),
+        Err(Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Error reading the secret from the keychain: {}", e)),
+    }
+}
+
+/// Succeeds when the keychain doesn't have it.
+#[cfg(not(test))]
+pub fn delete(reference: &str) -> Result<(), String> {
+    match Entry::new(SERVICE, reference).and_then(|entry| entry.delete_credential()) {
+        Ok(()) | Err(Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Error removing the secret from the keychain: {}", e)),
+    }
+}
+
+#[cfg(test)]
+static SECRETS: std::sync::Mutex<std::collections::BTreeMap<String, String>> = std::sync::Mutex::new(std::collections::BTreeMap::new());
+
+#[cfg(test)]
+pub fn set(reference: &str, secret: &str) -> Result<(), String> {
+    SECRETS.lock().unwrap().insert(reference.to_string(), secret.to_string());
+    Ok(())
+}
+
+#[cfg(test)]
+pub fn get(reference: &str) -> Result<Option<String>, String> {
+    Ok(SECRETS.lock().unwrap().get(reference).cloned())
+}
+
+#[cfg(test)]
+pub fn delete(reference: &str) -> Result<(), String> {
+    SECRETS.lock().unwrap().remove(reference);
+    Ok(())
+}
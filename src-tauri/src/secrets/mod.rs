@@ -0,0 +1,156 @@
+//! Passwords, header values and other secrets of the feeds, kept in the keychain of the OS rather than in
+//! the database. The database only holds a reference to each, a ULID from `new_reference`.
+//!
+//! A secret is stored under a new reference each time it changes. The triggers of `forgotten_secrets` list
+//! the references of the rows replaced or deleted since, whoever deleted them, and `forget_orphaned` removes
+//! their secrets from the keychain.
+
+mod keychain;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use crate::structs::write_gate::WriteGate;
+use crate::utils::ulid::ulid;
+
+pub fn new_reference() -> String {
+    ulid()
+}
+
+/// Returns the new reference the secret is stored under.
+pub async fn store(secret: String) -> Result<String, String> {
+    let reference = new_reference();
+    let stored = reference.clone();
+    tauri::async_runtime::spawn_blocking(move || keychain::set(&stored, &secret)).await
+        .map_err(|e| format!("Error storing the secret: {}", e))??;
+
+    Ok(reference)
+}
+
+pub async fn store_json<T: Serialize>(secret: &T) -> Result<String, String> {
+    let secret = serde_json::to_string(secret).map_err(|e| format!("Error serializing the secret: {}", e))?;
+    store(secret).await
+}
+
+/// None when the keychain doesn't have it anymore, e.g. removed by the user.
+pub async fn load(reference: String) -> Result<Option<String>, String> {
+    tauri::async_runtime::spawn_blocking(move || keychain::get(&reference)).await
+        .map_err(|e| format!("Error reading the secret: {}", e))?
+}
+
+pub async fn load_json<T: DeserializeOwned>(reference: String) -> Result<Option<T>, String> {
+    load(reference).await?
+        .map(|secret| serde_json::from_str(&secret).map_err(|e| format!("Error reading the secret: {}", e)))
+        .transpose()
+}
+
+/// Forgets the secret stored for a write when the write failed, no row referring to it then.
+pub async fn forget_if_unsaved<T>(reference: Option<String>, saved: Result<T, String>) -> Result<T, String> {
+    if let (Some(reference), Err(_)) = (reference, &saved) {
+        forget(reference).await;
+    }
+    saved
+}
+
+pub async fn forget(reference: String) {
+    let forgotten = tauri::async_runtime::spawn_blocking(move || keychain::delete(&reference)).await
+        .map_err(|e| e.to_string());
+    if let Err(e) = forgotten.and_then(|deleted| deleted) {
+        eprintln!("Error forgetting a secret: {}", e);
+    }
+}
+
+/// Removes the secrets no row references anymore from the keychain. The ones it can't remove are tried
+/// again next time. Writes to the database, so the caller holds a `WriteGuard`.
+pub async fn forget_orphaned(pool: &Pool<Sqlite>) -> Result<(), String> {
+    let references: Vec<String> = sqlx::query_scalar(include_str!("../database/queries/get_forgotten_secrets.sql"))
+        .fetch_all(pool).await
+        .map_err(|e| format!("Error executing query: {}", e))?;
+
+    for reference in references {
+        let deleting = reference.clone();
+        let deleted = tauri::async_runtime::spawn_blocking(move || keychain::delete(&deleting)).await
+            .map_err(|e| format!("Error forgetting a secret: {}", e))?;
+        if let Err(e) = deleted {
+            eprintln!("{}", e);
+            continue;
+        }
+
+        sqlx::query(include_str!("../database/queries/delete_forgotten_secret.sql"))
+            .bind(&reference)
+            .execute(pool).await
+            .map_err(|e| format!("Error executing query: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Forgets the secrets of the rows removed while the backend wasn't looking, e.g. feeds deleted by the frontend.
+pub async fn forget_orphaned_at_startup(app_handle: AppHandle) {
+    let Ok(_writing) = app_handle.state::<WriteGate>().enter() else { return };
+    if let Err(e) = forget_orphaned(&app_handle.state::<Pool<Sqlite>>()).await {
+        eprintln!("Error forgetting the orphaned secrets: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+    use crate::database::migrator::migrator;
+    use super::*;
+
+    #[tokio::test]
+    async fn stores_under_new_references() {
+        let first = store(String::from("secret")).await.unwrap();
+        let second = store(String::from("secret")).await.unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(load(first.clone()).await.unwrap().as_deref(), Some("secret"));
+
+        forget(first.clone()).await;
+        assert_eq!(load(first).await.unwrap(), None);
+        assert_eq!(load(second).await.unwrap().as_deref(), Some("secret"));
+    }
+
+    #[tokio::test]
+    async fn round_trips_json() {
+        let reference = store_json(&vec!["a", "b"]).await.unwrap();
+
+        assert_eq!(load_json::<Vec<String>>(reference).await.unwrap(), Some(vec![String::from("a"), String::from("b")]));
+    }
+
+    #[tokio::test]
+    async fn forgets_the_secrets_of_replaced_and_deleted_rows() {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        migrator().await.unwrap().run(&pool).await.unwrap();
+        sqlx::query("INSERT INTO feeds (identifier, display_name, url, interval) VALUES ('feed', 'feed', '', 60)")
+            .execute(&pool).await.unwrap();
+
+        let replaced = store(String::from("old")).await.unwrap();
+        let current = store(String::from("new")).await.unwrap();
+        sqlx::query("INSERT INTO feed_credentials (feed_identifier, username, password_secret, updated_at) VALUES ('feed', 'user', ?, 0)")
+            .bind(&replaced)
+            .execute(&pool).await.unwrap();
+        sqlx::query("UPDATE feed_credentials SET password_secret = ?")
+            .bind(&current)
+            .execute(&pool).await.unwrap();
+
+        forget_orphaned(&pool).await.unwrap();
+        assert_eq!(load(replaced).await.unwrap(), None);
+        assert_eq!(load(current.clone()).await.unwrap().as_deref(), Some("new"));
+
+        let cookies = store(String::from("[]")).await.unwrap();
+        sqlx::query("INSERT INTO feed_sessions (feed_identifier, cookies_secret, updated_at) VALUES ('feed', ?, 0)")
+            .bind(&cookies)
+            .execute(&pool).await.unwrap();
+
+        // Removing the feed deletes its credentials and session through the foreign keys
+        sqlx::query("DELETE FROM feeds").execute(&pool).await.unwrap();
+        forget_orphaned(&pool).await.unwrap();
+        assert_eq!(load(current).await.unwrap(), None);
+        assert_eq!(load(cookies).await.unwrap(), None);
+        let left: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM forgotten_secrets").fetch_one(&pool).await.unwrap();
+        assert_eq!(left, 0);
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+use std::time::Duration;
+use chrono::Utc;
+use crate::enums::background_job::BackgroundJob;
+use crate::structs::idle_status::IdleStatus;
+
+/// The user is idle after this long without a command, a heartbeat of the frontend or a focus change.
+pub const IDLE_AFTER: Duration = Duration::from_secs(5 * 60);
+
+struct Activity {
+    last_activity_at: i64,
+    deferred: BTreeSet<BackgroundJob>,
+}
+
+/// When the user last did something in the app, and the idle-only jobs waiting for them to stop.
+///
+/// Idle-only jobs ask `may_run` before each step they checkpoint, and pause at the next one once the
+/// user is back. Timestamps are in milliseconds, taken by the callers.
+pub struct ActivityMonitor {
+    activity: Mutex<Activity>,
+}
+
+impl Default for ActivityMonitor {
+    /// Launching the app is the first activity.
+    fn default() -> Self {
+        Self::new(Utc::now().timestamp_millis())
+    }
+}
+
+impl ActivityMonitor {
+    pub fn new(now: i64) -> Self {
+        ActivityMonitor { activity: Mutex::new(Activity { last_activity_at: now, deferred: BTreeSet::new() }) }
+    }
+
+    pub fn record(&self, now: i64) {
+        let mut activity = self.activity.lock().unwrap();
+        // Reported late by the frontend, or the clock went back
+        activity.last_activity_at = activity.last_activity_at.max(now);
+    }
+
+    /// Queues a job that runs once, returning false when it is queued already so it isn't started twice.
+    pub fn defer(&self, job: BackgroundJob) -> bool {
+        self.activity.lock().unwrap().deferred.insert(job)
+    }
+
+    /// Whether the job may run its next step now. Idle-only jobs stay queued until the user is idle, and
+    /// leave the queue then.
+    pub fn may_run(&self, job: BackgroundJob, now: i64) -> bool {
+        if !job.idle_only() {
+            return true;
+        }
+
+        let mut activity = self.activity.lock().unwrap();
+        let idle = is_idle(activity.last_activity_at, now);
+        if idle {
+            activity.deferred.remove(&job);
+        } else {
+            activity.deferred.insert(job);
+        }
+        idle
+    }
+
+    pub fn status(&self, now: i64) -> IdleStatus {
+        let activity = self.activity.lock().unwrap();
+        IdleStatus {
+            idle: is_idle(activity.last_activity_at, now),
+            last_activity_at: activity.last_activity_at,
+            deferred: activity.deferred.iter().copied().collect(),
+        }
+    }
+}
+
+fn is_idle(last_activity_at: i64, now: i64) -> bool {
+    now - last_activity_at >= IDLE_AFTER.as_millis() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: i64 = 1000;
+
+    #[derive(Debug)]
+    enum Event {
+        /// Done at this second, later than the current one when reported late
+        Activity(i64),
+        Idle(bool),
+    }
+
+    #[test]
+    fn detects_idle_along_simulated_activity() {
+        // Seconds since the launch
+        let timeline = [
+            (0, Event::Idle(false)),
+            (299, Event::Idle(false)),
+            (300, Event::Idle(true)),
+            (400, Event::Activity(400)),
+            (401, Event::Idle(false)),
+            (699, Event::Idle(false)),
+            (700, Event::Idle(true)),
+            // A heartbeat arriving late still counts from when it was sent
+            (800, Event::Activity(650)),
+            (800, Event::Idle(false)),
+            (949, Event::Idle(false)),
+            (950, Event::Idle(true)),
+            // Older than the last activity
+            (1000, Event::Activity(100)),
+            (1000, Event::Idle(true)),
+        ];
+
+        let monitor = ActivityMonitor::new(0);
+        for (now, event) in timeline.iter() {
+            match event {
+                Event::Activity(at) => monitor.record(at * SECOND),
+                Event::Idle(idle) => assert_eq!(monitor.status(now * SECOND).idle, *idle, "{:?} at {}", event, now),
+            }
+        }
+        assert_eq!(monitor.status(1000 * SECOND).last_activity_at, 650 * SECOND);
+    }
+
+    #[test]
+    fn pauses_idle_only_jobs_while_the_user_is_active() {
+        const STEPS: usize = 12;
+        let activity = [200, 1000, 1010, 1500];
+
+        let monitor = ActivityMonitor::new(0);
+        let mut backfill_steps = vec![];
+        let mut sync_steps = 0;
+        // Ticking every minute, the job checkpointing each step so it goes on from there when resumed
+        for now in (0..=3000).step_by(60) {
+            for at in activity.iter().filter(|at| (now - 59..=now).contains(*at)) {
+                monitor.record(at * SECOND);
+            }
+
+            let done = backfill_steps.len() == STEPS;
+            if !done && monitor.may_run(BackgroundJob::Backfill, now * SECOND) {
+                backfill_steps.push(now);
+            }
+            if monitor.may_run(BackgroundJob::Sync, now * SECOND) {
+                sync_steps += 1;
+            }
+
+            let waiting = !done && backfill_steps.last() != Some(&now);
+            let deferred = monitor.status(now * SECOND).deferred;
+            assert_eq!(deferred, if waiting { vec![BackgroundJob::Backfill] } else { vec![] }, "at {}", now);
+        }
+
+        // Five minutes after each burst, pausing at the next step once the user is back
+        assert_eq!(backfill_steps, [540, 600, 660, 720, 780, 840, 900, 960, 1320, 1380, 1440, 1800]);
+        assert_eq!(sync_steps, 3000 / 60 + 1);
+    }
+
+    #[test]
+    fn queues_one_shot_jobs_once() {
+        let monitor = ActivityMonitor::new(0);
+
+        assert!(monitor.defer(BackgroundJob::Maintenance));
+        assert!(monitor.defer(BackgroundJob::Prune));
+        // A second sync finishing before the user is idle
+        assert!(!monitor.defer(BackgroundJob::Prune));
+        assert!(!monitor.may_run(BackgroundJob::Prune, 60 * SECOND));
+        assert_eq!(monitor.status(60 * SECOND).deferred, [BackgroundJob::Prune, BackgroundJob::Maintenance]);
+
+        assert!(monitor.may_run(BackgroundJob::Prune, 300 * SECOND));
+        assert_eq!(monitor.status(300 * SECOND).deferred, [BackgroundJob::Maintenance]);
+        // Running now, so the next sync queues another one
+        assert!(monitor.defer(BackgroundJob::Prune));
+    }
+}
@@ -0,0 +1,10 @@
+use serde::Serialize;
+use crate::structs::feed_candidate::FeedCandidate;
+
+/// The URL taken from the clipboard by the tray, for the add feed dialog to pick among its feeds.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddFeedRequest {
+    pub url: String,
+    pub candidates: Vec<FeedCandidate>,
+}
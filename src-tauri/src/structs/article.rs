@@ -1,74 +1,142 @@
+use std::collections::BTreeMap;
 use chrono::Utc;
-use feed_rs::model::Entry;
+use feed_rs::model::{Content, Entry};
 use serde::Serialize;
+use crate::enums::enclosure_role::EnclosureRole;
+use crate::structs::article_extra::ArticleExtra;
+use crate::structs::enclosure::Enclosure;
 use crate::structs::image::Image;
 
 #[derive(Debug, Serialize)]
 pub struct Article {
     pub id: String,
+    /// Cleaned by the sync, see `utils::title`
     pub title: String,
+    pub original_title: String,
     pub content: String,
     pub date: i64,
     pub read: bool,
-    pub image: Option<Image>
+    pub image: Option<Image>,
+    pub comments_url: Option<String>,
+    pub link: Option<String>,
+    /// Of the entry, the sync falling back to the one of the feed
+    pub language: Option<String>,
+    pub enclosures: Vec<Enclosure>,
+    /// Post-processors that changed the content, set by the sync
+    pub post_processors: Vec<String>,
+    /// Read from the raw document, like the enclosures
+    pub extras: BTreeMap<String, Vec<ArticleExtra>>,
 }
 
 impl From<Entry> for Article {
     fn from(entry: Entry) -> Self {
         let id = entry.id;
+        // Atom exposes the comments feed as a "replies" link (RFC 4685), RSS ones are read from the raw document.
+        // Some blogs add one to their comments page, typed text/html
+        let comments_url = entry.links.iter()
+            .find(|l| l.rel.as_deref() == Some("replies") && l.media_type.as_deref().is_none_or(is_feed_type))
+            .map(|l| l.href.clone());
+        let link = entry.links.iter()
+            .find(|l| l.rel.is_none() || l.rel.as_deref() == Some("alternate"))
+            .or_else(|| entry.links.first())
+            .map(|l| l.href.clone());
+        // Replaced by the ones read from the raw document for XML feeds, feed-rs skips the podcast namespace
+        let enclosures = entry.media.iter()
+            .flat_map(|m| m.content.iter())
+            .filter_map(|c| {
+                let mime_type = c.content_type.as_ref().map(|t| t.essence_str().to_string());
+                Some(Enclosure {
+                    url: c.url.as_ref()?.to_string(),
+                    role: EnclosureRole::from_mime_type(mime_type.as_deref()),
+                    mime_type,
+                    language: None,
+                    playable: None,
+                })
+            })
+            .collect();
         let title = match entry.title {
             Some(t) => t.content,
             None => String::from("No title found, please report this issue."),
         };
         let content = match entry.content {
-            Some(c) => c.body.unwrap()
-                .replace('&', "&amp;")
-                .replace('<', "&lt;")
-                .replace('>', "&gt;")
-                .replace('\n', "<br>\n")
-                .replace('\t', "&nbsp;&nbsp;&nbsp;&nbsp;"),
+            Some(Content { body: Some(body), .. }) if !body.is_empty() => plain_text_html(&body),
+            // Out-of-line Atom content only links to it with `src`, feed-rs leaving the body empty or out
+            Some(content) => entry.summary
+                .map(|s| plain_text_html(&s.content))
+                .or_else(|| content.src.map(|src| plain_text_html(&src.href)))
+                .unwrap_or_default(),
             None => match entry.summary {
-                Some(s) => {
-                    // Convert plain text to HTML preserving formatting
-                    s.content
-                        .replace('&', "&amp;")
-                        .replace('<', "&lt;")
-                        .replace('>', "&gt;")
-                        .replace('\n', "<br>\n")
-                        .replace('\t', "&nbsp;&nbsp;&nbsp;&nbsp;")
-                },
+                // Convert plain text to HTML preserving formatting
+                Some(s) => plain_text_html(&s.content),
                 None => String::from("No content found, please report this issue."),
             },
         };
 
-
         let date = entry.published
-            .unwrap_or_else(|| entry.updated.unwrap_or_else(|| Utc::now()))
+            .unwrap_or_else(|| entry.updated.unwrap_or_else(Utc::now))
             .timestamp_millis();
 
-        let image = if entry.media.is_empty() {
-            None
-        } else {
-            let media = entry.media.iter().filter(|m| !m.thumbnails.is_empty()).next();
-            match media {
-                Some(m) => {
-                    let thumbnail = m.thumbnails.iter().next();
-                    match thumbnail {
-                        Some(t) => Some(Image::from(t.image.clone())),
-                        None => None
-                    }
-                },
-                None => None
-            }
-        };
+        let image = entry.media.iter()
+            .find(|m| !m.thumbnails.is_empty())
+            .and_then(|m| m.thumbnails.first())
+            .map(|t| Image::from(t.image.clone()));
 
         Article {
             id,
+            original_title: title.clone(),
             title,
             content,
             date,
             read: false,
             image,
+            comments_url,
+            link,
+            language: entry.language,
+            enclosures,
+            post_processors: vec![],
+            extras: BTreeMap::new(),
         }
     }
 }
+
+fn is_feed_type(media_type: &str) -> bool {
+    let essence = media_type.split(';').next().unwrap_or_default().trim().to_lowercase();
+    matches!(essence.as_str(), "application/atom+xml" | "application/rss+xml" | "application/rdf+xml" | "application/feed+json" | "application/xml" | "text/xml")
+}
+
+fn plain_text_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\n', "<br>\n")
+        .replace('\t', "&nbsp;&nbsp;&nbsp;&nbsp;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_comments_feed_from_the_replies_links() {
+        let document = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>Blog</title><id>blog</id><updated>2024-01-01T00:00:00Z</updated>
+            <entry><id>typed</id><title>Typed</title><updated>2024-01-01T00:00:00Z</updated>
+                <link rel="replies" type="text/html" href="https://blog.example/typed#comments"/>
+                <link rel="replies" type="application/atom+xml" href="https://blog.example/typed/comments.xml"/>
+            </entry>
+            <entry><id>untyped</id><title>Untyped</title><updated>2024-01-01T00:00:00Z</updated>
+                <link rel="replies" href="https://blog.example/untyped/comments.xml"/>
+            </entry>
+            <entry><id>page</id><title>Page</title><updated>2024-01-01T00:00:00Z</updated>
+                <link rel="replies" type="text/html" href="https://blog.example/page#comments"/>
+            </entry>
+        </feed>"#;
+        let feed = feed_rs::parser::parse(document.as_bytes()).unwrap();
+
+        let comments_urls: Vec<Option<String>> = feed.entries.into_iter().map(|entry| Article::from(entry).comments_url).collect();
+        assert_eq!(comments_urls, [
+            Some(String::from("https://blog.example/typed/comments.xml")),
+            Some(String::from("https://blog.example/untyped/comments.xml")),
+            None,
+        ]);
+    }
+}
@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+/// A part of the content of an article, long contents being sent in several chunks.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArticleContent {
+    pub chunk: String,
+    /// In characters, like the chunk sizes
+    pub total_size: i64,
+    /// For `get_article_content_chunk`, None once the whole content was sent
+    pub next_token: Option<String>,
+}
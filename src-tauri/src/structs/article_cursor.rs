@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use crate::enums::article_sort::ArticleSort;
+use crate::structs::stored_article::StoredArticle;
+
+/// The last article of a page, the next page starting right after it. The position is compared rather
+/// than looked up, so the next page is still right when that article was deleted meanwhile.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArticleCursor {
+    pub date: i64,
+    pub identifier: String,
+    /// Only needed when sorting by score
+    pub score: Option<i64>,
+    pub first_seen: Option<i64>,
+}
+
+impl ArticleCursor {
+    pub fn after(article: &StoredArticle) -> Self {
+        ArticleCursor {
+            date: article.date,
+            identifier: article.identifier.clone(),
+            score: Some(article.score),
+            first_seen: article.first_seen,
+        }
+    }
+
+    /// The value the articles are sorted by before their date.
+    pub fn sort_key(&self, sort: ArticleSort) -> i64 {
+        match sort {
+            ArticleSort::Date => 0,
+            ArticleSort::Score => self.score.unwrap_or_default(),
+            ArticleSort::FirstSeen => self.first_seen.unwrap_or(self.date),
+        }
+    }
+}
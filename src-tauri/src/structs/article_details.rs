@@ -0,0 +1,11 @@
+use std::collections::BTreeMap;
+use serde::Serialize;
+use crate::structs::article_extra::ArticleExtra;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArticleDetails {
+    pub extras: BTreeMap<String, Vec<ArticleExtra>>,
+    /// The extras the feed is configured to show and the article has, in the configured order
+    pub displayed_extras: Vec<String>,
+}
@@ -0,0 +1,11 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+/// The text and attributes of an item element feed-rs doesn't keep, like `<geo:lat>` or `<yweather:condition>`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArticleExtra {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
+}
@@ -0,0 +1,16 @@
+use crate::enums::article_sort::ArticleSort;
+use crate::enums::read_state::ReadState;
+use crate::structs::article_cursor::ArticleCursor;
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ArticleFilter {
+    pub feed_identifier: Option<String>,
+    pub sort: ArticleSort,
+    pub min_score: Option<i64>,
+    /// All the articles when left out
+    pub read_state: Option<ReadState>,
+    pub cursor: Option<ArticleCursor>,
+    /// Capped to MAX_PAGE_SIZE, which is also the default
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,23 @@
+use serde::Serialize;
+use crate::structs::article_cursor::ArticleCursor;
+use crate::structs::stored_article::StoredArticle;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArticlePage {
+    pub articles: Vec<StoredArticle>,
+    /// The page size actually used, which may be lower than requested
+    pub limit: i64,
+    /// None on the last page
+    pub next_cursor: Option<ArticleCursor>,
+}
+
+impl ArticlePage {
+    pub fn new(articles: Vec<StoredArticle>, limit: i64) -> Self {
+        let next_cursor = (articles.len() as i64 >= limit)
+            .then(|| articles.last().map(ArticleCursor::after))
+            .flatten();
+
+        ArticlePage { articles, limit, next_cursor }
+    }
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// What the login item was registered with, the plugin can't read it back from the OS.
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutostartRegistration {
+    pub path: String,
+    pub args: Vec<String>,
+}
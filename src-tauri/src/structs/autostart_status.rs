@@ -0,0 +1,11 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutostartStatus {
+    pub enabled: bool,
+    /// The executable the login item should launch
+    pub derived_path: String,
+    pub path_matches: bool,
+    pub args_match: bool,
+}
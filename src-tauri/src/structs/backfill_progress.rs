@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackfillProgress {
+    pub name: String,
+    pub done: bool,
+    /// Rows left to process, unknown once done or for a backfill this version doesn't know
+    pub remaining: Option<i64>,
+}
@@ -0,0 +1,42 @@
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+struct Settings {
+    minutes: u64,
+    paused: bool,
+}
+
+/// Settings of the background sync, which the commands change while it waits.
+pub struct BackgroundSync {
+    settings: Mutex<Settings>,
+    /// Wakes the background sync up so the new settings apply to its current wait
+    changed: Notify,
+}
+
+impl BackgroundSync {
+    pub fn new(minutes: u64) -> Self {
+        Self { settings: Mutex::new(Settings { minutes: minutes.max(1), paused: false }), changed: Notify::new() }
+    }
+
+    pub fn minutes(&self) -> u64 {
+        self.settings.lock().unwrap().minutes
+    }
+
+    pub fn paused(&self) -> bool {
+        self.settings.lock().unwrap().paused
+    }
+
+    pub fn set_minutes(&self, minutes: u64) {
+        self.settings.lock().unwrap().minutes = minutes.max(1);
+        self.changed.notify_one();
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.settings.lock().unwrap().paused = paused;
+        self.changed.notify_one();
+    }
+
+    pub async fn changed(&self) {
+        self.changed.notified().await;
+    }
+}
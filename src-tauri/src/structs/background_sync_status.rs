@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackgroundSyncStatus {
+    pub minutes: u64,
+    pub paused: bool,
+    /// A background run or a `sync_all` from the frontend
+    pub running: bool,
+    /// The background loop is started and didn't stop since, which would take a panic
+    pub alive: bool,
+}
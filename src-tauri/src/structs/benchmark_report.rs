@@ -0,0 +1,20 @@
+use serde::Serialize;
+use crate::enums::benchmark_profile::BenchmarkProfile;
+use crate::structs::benchmark_result::BenchmarkResult;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkReport {
+    pub app_version: String,
+    pub profile: BenchmarkProfile,
+    pub seed: u32,
+    pub feeds: usize,
+    pub articles: usize,
+    /// Size of the generated database, FTS index included
+    pub database_bytes: u64,
+    pub generation_ms: f64,
+    pub ran_at: i64,
+    pub results: Vec<BenchmarkResult>,
+    /// Where the report was written for comparing versions, None when that failed
+    pub report_path: Option<String>,
+}
@@ -0,0 +1,46 @@
+use std::time::Duration;
+use serde::Serialize;
+use crate::enums::benchmark_suite::BenchmarkSuite;
+use crate::utils::command_timing::{milliseconds, percentile};
+
+/// Timings of a suite, each sample being one run of the operation it measures.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkResult {
+    pub suite: BenchmarkSuite,
+    pub samples: usize,
+    /// Rows read or written over every sample, so a faster run finding less stands out
+    pub rows: i64,
+    pub total_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+    /// Only for the suites with a target, see `with_target`
+    pub target_p95_ms: Option<f64>,
+    pub within_target: Option<bool>,
+}
+
+impl BenchmarkResult {
+    pub fn new(suite: BenchmarkSuite, rows: i64, mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+
+        BenchmarkResult {
+            suite,
+            samples: samples.len(),
+            rows,
+            total_ms: milliseconds(samples.iter().sum()),
+            p50_ms: milliseconds(percentile(&samples, 0.5)),
+            p95_ms: milliseconds(percentile(&samples, 0.95)),
+            max_ms: milliseconds(samples.last().copied().unwrap_or_default()),
+            target_p95_ms: None,
+            within_target: None,
+        }
+    }
+
+    pub fn with_target(mut self, target_p95: Duration) -> Self {
+        let target_p95_ms = milliseconds(target_p95);
+        self.within_target = Some(self.p95_ms <= target_p95_ms);
+        self.target_p95_ms = Some(target_p95_ms);
+        self
+    }
+}
@@ -0,0 +1,11 @@
+use serde::Serialize;
+
+/// A link of a browser bookmarks export.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bookmark {
+    pub title: String,
+    pub url: String,
+    /// Names of the folders it's in, from the root
+    pub folder: Vec<String>,
+}
@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookmarkFolder {
+    /// Names of the folder and its parents, from the root. Empty for the bookmarks outside any folder
+    pub path: Vec<String>,
+    /// Web bookmarks directly in this folder
+    pub bookmarks: usize,
+}
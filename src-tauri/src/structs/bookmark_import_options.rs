@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BookmarkImportOptions {
+    /// As returned by `inspect_bookmarks_html`, subfolders included. Every bookmark when empty
+    pub folders: Vec<Vec<String>>,
+    /// Sites to fetch at most, up to the default limit
+    pub max_requests: Option<usize>,
+}
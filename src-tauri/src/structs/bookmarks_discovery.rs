@@ -0,0 +1,19 @@
+use serde::Serialize;
+use crate::structs::bookmark::Bookmark;
+use crate::structs::discovered_folder::DiscoveredFolder;
+use crate::structs::undiscovered_site::UndiscoveredSite;
+
+/// The feeds found on bookmarked sites, for the user to pick the ones to import with `import_feeds`.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookmarksDiscovery {
+    /// In the order of the bookmarks
+    pub folders: Vec<DiscoveredFolder>,
+    pub without_feed: Vec<UndiscoveredSite>,
+    /// Bookmarks of feeds or sites already subscribed to, never fetched, and sites whose feeds all are
+    pub already_subscribed: Vec<Bookmark>,
+    /// Left out by the request limit or the cancellation
+    pub not_checked: Vec<Bookmark>,
+    pub requests: usize,
+    pub cancelled: bool,
+}
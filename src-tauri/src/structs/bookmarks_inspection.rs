@@ -0,0 +1,14 @@
+use serde::Serialize;
+use crate::structs::bookmark_folder::BookmarkFolder;
+
+/// What a bookmarks export holds, for choosing the folders to look for feeds in.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookmarksInspection {
+    /// In document order
+    pub folders: Vec<BookmarkFolder>,
+    /// Bookmarks to HTTP or HTTPS URLs
+    pub bookmarks: usize,
+    /// Bookmarks to other URLs, like `javascript:` bookmarklets, which are left out
+    pub ignored: usize,
+}
@@ -0,0 +1,11 @@
+use serde::Serialize;
+
+/// What a certificate file holds, for the user to tell whether it is the expected one.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub valid_from: i64,
+    pub expires_at: i64,
+}
@@ -0,0 +1,19 @@
+use std::collections::BTreeMap;
+use serde::Serialize;
+
+/// Timings of a command since startup, percentiles covering its most recent calls.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandTiming {
+    pub command: String,
+    pub count: u64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+    /// Calls per result: "ok", the kind of a typed error like "WritesFrozen", or "error"
+    pub outcomes: BTreeMap<String, u64>,
+    /// Calls that ended during a `sync_all` run, the percentiles covering the most recent ones
+    pub count_during_sync: u64,
+    pub p50_during_sync_ms: f64,
+    pub p95_during_sync_ms: f64,
+}
@@ -0,0 +1,28 @@
+use feed_rs::model::Entry;
+use serde::Serialize;
+use crate::structs::article::Article;
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub identifier: String,
+    pub article_identifier: String,
+    pub author: Option<String>,
+    pub content: String,
+    pub date: i64,
+}
+
+impl Comment {
+    pub fn from_entry(article_identifier: &str, entry: Entry) -> Self {
+        let author = entry.authors.first().map(|a| a.name.clone());
+        let article = Article::from(entry);
+
+        Comment {
+            identifier: article.id,
+            article_identifier: article_identifier.to_string(),
+            author,
+            content: article.content,
+            date: article.date,
+        }
+    }
+}
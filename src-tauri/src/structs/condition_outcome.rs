@@ -0,0 +1,9 @@
+use serde::Serialize;
+
+/// A condition of a scoring rule checked against an article, see `CompiledRule::explain`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionOutcome {
+    pub description: String,
+    pub matched: bool,
+}
@@ -0,0 +1,8 @@
+#[derive(Debug, sqlx::FromRow)]
+pub struct DedupCandidate {
+    pub rowid: i64,
+    pub identifier: String,
+    pub dedup_key: Option<u32>,
+    pub link: Option<String>,
+    pub title: String,
+}
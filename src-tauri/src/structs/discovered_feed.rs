@@ -0,0 +1,14 @@
+use serde::Serialize;
+use crate::enums::feed_type::FeedType;
+
+/// A feed found on a bookmarked site, not subscribed to yet.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveredFeed {
+    pub url: String,
+    /// The title the site gives the feed, the bookmark title otherwise
+    pub title: String,
+    pub feed_type: Option<FeedType>,
+    /// The bookmark it was found from
+    pub site_url: String,
+}
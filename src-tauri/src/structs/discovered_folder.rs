@@ -0,0 +1,10 @@
+use serde::Serialize;
+use crate::structs::discovered_feed::DiscoveredFeed;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveredFolder {
+    /// The bookmark folder, from the root
+    pub folder: Vec<String>,
+    pub feeds: Vec<DiscoveredFeed>,
+}
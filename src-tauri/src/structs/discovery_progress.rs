@@ -0,0 +1,8 @@
+use serde::Serialize;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryProgress {
+    pub sites_done: usize,
+    pub sites_total: usize,
+}
@@ -0,0 +1,6 @@
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateFeeds {
+    pub canonical_url: String,
+    pub identifiers: Vec<String>,
+}
@@ -0,0 +1,24 @@
+use serde::Serialize;
+use sqlx::types::Json;
+use crate::enums::address_family::AddressFamily;
+use crate::enums::feed_priority::FeedPriority;
+use crate::enums::user_agent::UserAgent;
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct EditableFeed {
+    pub identifier: String,
+    pub display_name: String,
+    pub url: String,
+    pub interval: i64,
+    pub priority: FeedPriority,
+    pub archived: bool,
+    pub address_family: AddressFamily,
+    pub user_agent: UserAgent,
+    pub reread_on_update: bool,
+    /// None when following the preferences
+    pub retention_days: Option<i64>,
+    pub retention_max_articles: Option<i64>,
+    pub title_prefixes: Json<Vec<String>>,
+    pub version: i64,
+}
@@ -0,0 +1,14 @@
+use serde::Serialize;
+use crate::enums::enclosure_role::EnclosureRole;
+
+#[derive(Clone, Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Enclosure {
+    pub url: String,
+    pub mime_type: Option<String>,
+    pub role: EnclosureRole,
+    pub language: Option<String>,
+    /// Set by get_article_enclosures once the webview has been probed
+    #[sqlx(default)]
+    pub playable: Option<bool>,
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// A change of a field, one per line of a state exchange file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExchangeEntry {
+    /// ULID of the entry, ordering the changes of a device made in the same millisecond
+    pub id: String,
+    /// When the field changed, in ms
+    pub at: i64,
+    pub entity: String,
+    pub key: String,
+    pub field: String,
+    /// None for a deletion
+    pub value: Option<String>,
+}
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+/// First line of a state exchange file.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExchangeHeader {
+    pub format: u32,
+    pub device: String,
+}
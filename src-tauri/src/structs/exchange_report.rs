@@ -0,0 +1,14 @@
+use serde::Serialize;
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeReport {
+    pub device_id: String,
+    /// Local changes appended to the file of this device
+    pub exported: usize,
+    /// Changes of other devices newer than the known ones
+    pub imported: usize,
+    pub compacted: bool,
+    /// Files of other devices left out because they couldn't be read
+    pub skipped_files: Vec<String>,
+}
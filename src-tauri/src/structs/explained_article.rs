@@ -0,0 +1,17 @@
+use sqlx::types::Json;
+use crate::enums::dedup_strategy::DedupStrategy;
+use crate::structs::stored_article::StoredArticle;
+
+/// An article along with what decided how it is shown, for `explain_article`.
+#[derive(Debug, sqlx::FromRow)]
+pub struct ExplainedArticle {
+    #[sqlx(flatten)]
+    pub article: StoredArticle,
+    pub dedup_key: Option<i64>,
+    pub post_processors: Json<Vec<String>>,
+    pub language: Option<String>,
+    /// The feed ones are none when the feed was removed meanwhile
+    pub feed_name: Option<String>,
+    pub dedup_strategy: Option<DedupStrategy>,
+    pub feed_archived: Option<bool>,
+}
@@ -0,0 +1,36 @@
+use serde::Serialize;
+use crate::enums::explanation_kind::ExplanationKind;
+
+/// One reason an article is shown, hidden or ordered as it is, see `explain_article`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplanationFactor {
+    pub kind: ExplanationKind,
+    pub label: String,
+    pub details: Vec<String>,
+    /// False for the rules and filters checked that don't concern the article, listed so it's clear why not
+    pub applies: bool,
+    /// Keeps the article out of the view explained
+    pub hides: bool,
+}
+
+impl ExplanationFactor {
+    pub fn new(kind: ExplanationKind, label: String) -> Self {
+        ExplanationFactor { kind, label, details: vec![], applies: true, hides: false }
+    }
+
+    pub fn with_details(mut self, details: Vec<String>) -> Self {
+        self.details = details;
+        self
+    }
+
+    pub fn with_applies(mut self, applies: bool) -> Self {
+        self.applies = applies;
+        self
+    }
+
+    pub fn with_hides(mut self, hides: bool) -> Self {
+        self.hides = hides;
+        self
+    }
+}
@@ -0,0 +1,13 @@
+use serde::Serialize;
+
+/// First file of an export, telling what produced it.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportManifest {
+    pub app_version: String,
+    pub schema_version: i64,
+    pub exported_at: i64,
+    pub feeds: usize,
+    pub articles: i64,
+    pub files: Vec<String>,
+}
@@ -0,0 +1,8 @@
+use serde::Serialize;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportProgress {
+    pub articles_done: i64,
+    pub articles_total: i64,
+}
@@ -0,0 +1,10 @@
+use serde::Serialize;
+use crate::structs::export_manifest::ExportManifest;
+
+/// The result of `export_everything`, the path being left out of the manifest written in the archive.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedArchive {
+    pub path: String,
+    pub manifest: ExportManifest,
+}
@@ -0,0 +1,20 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedArticle {
+    /// Only used to page through the articles
+    #[serde(skip)]
+    pub rowid: i64,
+    pub identifier: String,
+    pub feed_identifier: Option<String>,
+    pub title: String,
+    pub content: String,
+    pub date: i64,
+    pub read: bool,
+    pub image_url: Option<String>,
+    pub comments_url: Option<String>,
+    pub link: Option<String>,
+    pub score: i64,
+    pub first_seen: Option<i64>,
+}
@@ -0,0 +1,25 @@
+use serde::Serialize;
+
+/// A feed written to OPML, its settings as stored.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedFeed {
+    pub identifier: String,
+    pub display_name: String,
+    pub url: String,
+    pub interval: i64,
+    pub priority: String,
+    pub archived: bool,
+    pub dedup_strategy: String,
+    pub dedup_strategy_locked: bool,
+    pub folder_identifier: Option<String>,
+    pub html_url: Option<String>,
+    pub address_family: String,
+    pub user_agent: String,
+    pub reread_on_update: bool,
+    /// None when following the preferences
+    pub retention_days: Option<i64>,
+    pub retention_max_articles: Option<i64>,
+    /// JSON array
+    pub title_prefixes: String,
+}
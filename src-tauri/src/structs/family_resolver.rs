@@ -0,0 +1,115 @@
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use crate::enums::address_family::AddressFamily;
+
+/// Resolves hosts with another resolver and keeps the addresses of one family, which the client
+/// then tries in that order.
+pub struct FamilyResolver {
+    family: AddressFamily,
+    lookup: Arc<dyn Resolve>,
+    /// Every address resolved, kept or not, in resolution order
+    resolved: Arc<Mutex<Vec<SocketAddr>>>,
+}
+
+impl FamilyResolver {
+    pub fn new(family: AddressFamily, lookup: Arc<dyn Resolve>) -> Self {
+        FamilyResolver { family, lookup, resolved: Arc::default() }
+    }
+
+    pub fn family(&self) -> AddressFamily {
+        self.family
+    }
+
+    pub fn resolved(&self) -> Vec<SocketAddr> {
+        self.resolved.lock().unwrap().clone()
+    }
+}
+
+impl Resolve for FamilyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let family = self.family;
+        let host = name.as_str().to_string();
+        let lookup = self.lookup.resolve(name);
+        let resolved = self.resolved.clone();
+
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = lookup.await?.collect();
+            resolved.lock().unwrap().extend(&addresses);
+
+            let kept = family.filter(addresses);
+            if kept.is_empty() {
+                let family = match family {
+                    AddressFamily::Auto => "",
+                    AddressFamily::Ipv4 => "IPv4 ",
+                    AddressFamily::Ipv6 => "IPv6 ",
+                };
+                return Err(format!("{} has no {}address", host, family).into());
+            }
+            Ok(Box::new(kept.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use super::*;
+
+    /// Resolves every host to the same addresses, IPv6 ones first like most systems order them.
+    struct MockResolver(Vec<SocketAddr>);
+
+    impl Resolve for MockResolver {
+        fn resolve(&self, _: Name) -> Resolving {
+            let addresses = self.0.clone();
+            Box::pin(async move { Ok(Box::new(addresses.into_iter()) as Addrs) })
+        }
+    }
+
+    fn addresses(addresses: &[&str]) -> Vec<SocketAddr> {
+        addresses.iter().map(|address| address.parse().unwrap()).collect()
+    }
+
+    async fn resolve(family: AddressFamily, mock: &[&str]) -> (Result<Vec<SocketAddr>, String>, Vec<SocketAddr>) {
+        let resolver = FamilyResolver::new(family, Arc::new(MockResolver(addresses(mock))));
+        let kept = resolver.resolve(Name::from_str("feeds.example").unwrap()).await
+            .map(|addresses| addresses.collect())
+            .map_err(|e| e.to_string());
+        (kept, resolver.resolved())
+    }
+
+    #[tokio::test]
+    async fn keeps_the_addresses_of_the_family() {
+        let mixed = ["[2001:db8::1]:0", "192.0.2.1:0", "[2001:db8::2]:0", "192.0.2.2:0"];
+        let cases = [
+            (AddressFamily::Auto, &mixed[..], Ok(addresses(&mixed))),
+            (AddressFamily::Ipv4, &mixed[..], Ok(addresses(&["192.0.2.1:0", "192.0.2.2:0"]))),
+            (AddressFamily::Ipv6, &mixed[..], Ok(addresses(&["[2001:db8::1]:0", "[2001:db8::2]:0"]))),
+            (AddressFamily::Ipv4, &mixed[..1], Err(String::from("feeds.example has no IPv4 address"))),
+            (AddressFamily::Ipv6, &mixed[1..2], Err(String::from("feeds.example has no IPv6 address"))),
+            (AddressFamily::Auto, &[][..], Err(String::from("feeds.example has no address"))),
+        ];
+
+        for case in cases.iter() {
+            let (kept, resolved) = resolve(case.0, case.1).await;
+            assert_eq!(kept, case.2, "{:?}", case);
+            // Kept or not, for debug_fetch
+            assert_eq!(resolved, addresses(case.1), "{:?}", case);
+        }
+    }
+
+    #[test]
+    fn feeds_follow_the_preference_on_auto() {
+        let cases = [
+            (AddressFamily::Auto, AddressFamily::Auto, AddressFamily::Auto),
+            (AddressFamily::Auto, AddressFamily::Ipv4, AddressFamily::Ipv4),
+            (AddressFamily::Ipv6, AddressFamily::Ipv4, AddressFamily::Ipv6),
+            (AddressFamily::Ipv4, AddressFamily::Auto, AddressFamily::Ipv4),
+        ];
+
+        for case in cases.iter() {
+            assert_eq!(case.0.or(case.1), case.2, "{:?}", case);
+        }
+    }
+}
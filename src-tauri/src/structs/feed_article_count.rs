@@ -0,0 +1,6 @@
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedArticleCount {
+    pub feed_identifier: String,
+    pub count: i64,
+}
@@ -0,0 +1,12 @@
+use serde::Serialize;
+use crate::enums::feed_type::FeedType;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedCandidate {
+    pub url: String,
+    pub title: Option<String>,
+    pub feed_type: Option<FeedType>,
+    /// Identifier of the feed already subscribed to this URL, host variants included
+    pub existing_feed: Option<String>,
+}
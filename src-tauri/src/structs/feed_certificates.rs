@@ -0,0 +1,8 @@
+/// Files of the client certificate and extra CA of a feed, read on each sync. The passphrase of the client
+/// certificate is in the keychain, see `secrets`.
+#[derive(Debug, sqlx::FromRow)]
+pub struct FeedCertificates {
+    pub client_certificate_path: Option<String>,
+    pub client_certificate_secret: Option<String>,
+    pub ca_certificate_path: Option<String>,
+}
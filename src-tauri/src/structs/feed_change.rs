@@ -0,0 +1,12 @@
+use serde::Serialize;
+use crate::enums::feed_change_source::FeedChangeSource;
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedChange {
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub source: FeedChangeSource,
+    pub changed_at: i64,
+}
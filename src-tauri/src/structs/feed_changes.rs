@@ -0,0 +1,24 @@
+use crate::enums::address_family::AddressFamily;
+use crate::enums::feed_priority::FeedPriority;
+use crate::enums::user_agent::UserAgent;
+
+/// Fields of a feed to update, the ones left out keep their current value.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FeedChanges {
+    pub display_name: Option<String>,
+    pub url: Option<String>,
+    pub interval: Option<i64>,
+    pub priority: Option<FeedPriority>,
+    /// Archived feeds are never synced again and keep their whole history
+    pub archived: Option<bool>,
+    pub address_family: Option<AddressFamily>,
+    pub user_agent: Option<UserAgent>,
+    /// Read articles it updates are counted as unread until read again
+    pub reread_on_update: Option<bool>,
+    /// Override the preferences of `prune_articles`, 0 keeping every article and -1 following the preferences again
+    pub retention_days: Option<i64>,
+    pub retention_max_articles: Option<i64>,
+    /// Removed from the titles of its articles, the ones already stored included
+    pub title_prefixes: Option<Vec<String>>,
+}
@@ -0,0 +1,16 @@
+use crate::structs::feed_validators::FeedValidators;
+
+/// What a sync updates on the feed itself, written with its last articles so the validators are only
+/// saved once the articles they stand for are.
+#[derive(Debug)]
+pub struct FeedCompletion {
+    pub feed_type: &'static str,
+    pub image_url: Option<String>,
+    pub validators: FeedValidators,
+    /// The cookies of the feed worked, after being new or expired
+    pub session_working: bool,
+    /// Identifiers of every article of the fetch, the ones skipped as duplicates included
+    pub fetched: Vec<String>,
+    /// None on the first sync, which only discovers the metadata
+    pub previous: Option<(Option<String>, Option<String>)>,
+}
@@ -0,0 +1,41 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::RequestBuilder;
+
+/// Basic auth and extra headers of a private feed, sent to the host of the feed only. The password and the
+/// values of the headers come from the keychain, see `secrets`.
+pub struct FeedCredentials {
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub headers: BTreeMap<String, String>,
+}
+
+impl FeedCredentials {
+    /// Adds the credentials to the request. An `Authorization` header among the extra headers replaces the Basic auth.
+    pub fn apply(&self, mut request: RequestBuilder) -> RequestBuilder {
+        let has_authorization = self.headers.keys().any(|name| name.eq_ignore_ascii_case(AUTHORIZATION.as_str()));
+        if let (Some(username), false) = (&self.username, has_authorization) {
+            request = request.basic_auth(username, self.password.as_ref());
+        }
+
+        for (name, value) in self.headers.iter() {
+            // Validated when saved
+            let (Ok(name), Ok(mut value)) = (HeaderName::try_from(name.as_str()), HeaderValue::try_from(value.as_str())) else { continue };
+            value.set_sensitive(true);
+            request = request.header(name, value);
+        }
+        request
+    }
+}
+
+// The values are secrets, only which ones are set is shown
+impl fmt::Debug for FeedCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FeedCredentials")
+            .field("username", &self.username.as_ref().map(|_| "redacted"))
+            .field("password", &self.password.as_ref().map(|_| "redacted"))
+            .field("headers", &self.headers.keys().collect::<Vec<&String>>())
+            .finish()
+    }
+}
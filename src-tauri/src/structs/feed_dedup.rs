@@ -0,0 +1,10 @@
+use serde::Serialize;
+use crate::enums::dedup_strategy::DedupStrategy;
+
+#[derive(Debug, Default, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedDedup {
+    pub strategy: DedupStrategy,
+    /// Whether the strategy was set manually, disabling the automatic switch
+    pub locked: bool,
+}
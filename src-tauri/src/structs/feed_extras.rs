@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedExtras {
+    /// Prefixes read on top of the known ones, e.g. "media" for the avatars of GitHub release feeds
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+    /// Extras shown as metadata chips, by name like "georss:point" or "category:location"
+    #[serde(default)]
+    pub displayed: Vec<String>,
+}
@@ -0,0 +1,12 @@
+use serde::Serialize;
+use crate::structs::unavailable_window::UnavailableWindow;
+
+/// Scheduled syncs of the last 30 days.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedHealth {
+    pub successes: usize,
+    pub failures: usize,
+    /// Set when the failures happen at the same time every day, the feed being skipped then
+    pub unavailable_window: Option<UnavailableWindow>,
+}
@@ -0,0 +1,10 @@
+use crate::enums::address_family::AddressFamily;
+use crate::enums::user_agent::UserAgent;
+
+/// How a feed is fetched, its secrets aside.
+#[derive(Clone, Copy, Debug, Default, sqlx::FromRow)]
+pub struct FeedNetwork {
+    /// Auto following the preferences
+    pub address_family: AddressFamily,
+    pub user_agent: UserAgent,
+}
@@ -0,0 +1,55 @@
+use sqlx::types::Json;
+use crate::enums::address_family::AddressFamily;
+use crate::enums::override_kind::OverrideKind;
+use crate::enums::user_agent::UserAgent;
+use crate::structs::network_override::NetworkOverride;
+
+/// Masks the values of headers and cookies
+const MASK: &str = "********";
+
+/// The network settings of a feed, with the names of its headers and cookies but none of their values.
+#[derive(Debug, sqlx::FromRow)]
+pub struct FeedNetworkSettings {
+    pub feed_identifier: String,
+    pub display_name: String,
+    pub user_agent: UserAgent,
+    pub address_family: AddressFamily,
+    pub header_names: Json<Vec<String>>,
+    pub basic_auth: bool,
+    pub cookie_names: Json<Vec<String>>,
+    pub client_certificate_path: Option<String>,
+    /// Whether the client certificate has a passphrase
+    pub client_certificate_passphrase: bool,
+    pub ca_certificate_path: Option<String>,
+}
+
+impl FeedNetworkSettings {
+    /// The settings differing from the defaults, none when the feed follows all of them.
+    pub fn overrides(&self) -> Vec<NetworkOverride> {
+        OverrideKind::ALL.into_iter()
+            .filter_map(|kind| self.describe(kind).map(|detail| NetworkOverride { kind, detail }))
+            .collect()
+    }
+
+    /// What the feed uses in place of the default, when it has its own.
+    fn describe(&self, kind: OverrideKind) -> Option<String> {
+        match kind {
+            OverrideKind::UserAgent => (self.user_agent != UserAgent::Honest).then(|| String::from(self.user_agent.as_str())),
+            OverrideKind::AddressFamily => (self.address_family != AddressFamily::Auto).then(|| format!("{} only", self.address_family.as_str())),
+            OverrideKind::Headers => masked(&self.header_names, ": "),
+            OverrideKind::BasicAuth => self.basic_auth.then(|| format!("username and password {}", MASK)),
+            OverrideKind::Cookies => masked(&self.cookie_names, "="),
+            OverrideKind::ClientCertificate => self.client_certificate_path.as_ref().map(|path| {
+                if self.client_certificate_passphrase { format!("{}, passphrase {}", path, MASK) } else { path.clone() }
+            }),
+            OverrideKind::CaCertificate => self.ca_certificate_path.clone(),
+        }
+    }
+}
+
+fn masked(names: &[String], separator: &str) -> Option<String> {
+    if names.is_empty() {
+        return None;
+    }
+    Some(names.iter().map(|name| format!("{}{}{}", name, separator, MASK)).collect::<Vec<String>>().join(", "))
+}
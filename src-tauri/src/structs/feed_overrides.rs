@@ -0,0 +1,11 @@
+use serde::Serialize;
+use crate::structs::network_override::NetworkOverride;
+
+/// A feed fetched other than with the defaults, for the override audit.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedOverrides {
+    pub feed_identifier: String,
+    pub display_name: String,
+    pub overrides: Vec<NetworkOverride>,
+}
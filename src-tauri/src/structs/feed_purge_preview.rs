@@ -0,0 +1,16 @@
+use serde::Serialize;
+
+/// The articles of a feed a prune would delete, dates being in milliseconds.
+#[derive(Clone, Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedPurgePreview {
+    pub feed_identifier: String,
+    pub display_name: String,
+    pub articles: i64,
+    /// Of the articles passing the filter
+    pub unread: i64,
+    pub oldest_date: i64,
+    pub newest_date: i64,
+    /// Of the title and content, without the indexes
+    pub bytes: i64,
+}
@@ -0,0 +1,9 @@
+use crate::enums::session_state::SessionState;
+use crate::structs::feed_cookie::FeedCookie;
+
+/// Cookies of a members-only feed, sent to the host of the feed only.
+#[derive(Debug)]
+pub struct FeedSession {
+    pub cookies: Vec<FeedCookie>,
+    pub state: SessionState,
+}
@@ -0,0 +1,23 @@
+/// What `project_storage` knows about a feed, after the articles the candidate retention prunes right away.
+/// Dates are in milliseconds, counts of the articles published during the measured period in `recent_*`.
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct FeedStorageStats {
+    pub feed_identifier: String,
+    pub archived: bool,
+    pub max_days: Option<i64>,
+    pub max_articles: Option<i64>,
+    pub articles: i64,
+    /// Of the articles passing the filter
+    pub unread: i64,
+    /// Of the title and content, without the indexes
+    pub bytes: i64,
+    pub oldest_kept_since: Option<i64>,
+    pub first_posted: Option<i64>,
+    pub recent_articles: i64,
+    pub recent_shown: i64,
+    pub recent_read: i64,
+    pub recent_bytes: i64,
+    pub pruned_articles: i64,
+    pub pruned_unread: i64,
+    pub pruned_bytes: i64,
+}
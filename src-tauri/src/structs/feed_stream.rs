@@ -0,0 +1,10 @@
+use tokio::sync::{mpsc, oneshot};
+use crate::enums::feed_write::FeedWrite;
+
+/// The articles of a feed on their way to the writer of the run, which answers with the number of
+/// articles it stored once they are committed.
+pub struct FeedStream {
+    pub feed_identifier: String,
+    pub writes: mpsc::Receiver<FeedWrite>,
+    pub done: oneshot::Sender<Result<i64, String>>,
+}
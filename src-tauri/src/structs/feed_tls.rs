@@ -0,0 +1,30 @@
+use reqwest::{Certificate, ClientBuilder, Identity};
+use crate::structs::certificate_info::CertificateInfo;
+
+/// The client certificate and extra CA of a feed, read from their files. Only the client fetching from
+/// the origin of the feed gets them, so neither is used with another host.
+#[derive(Default)]
+pub struct FeedTls {
+    pub identity: Option<(Identity, CertificateInfo)>,
+    pub ca: Option<(Certificate, CertificateInfo)>,
+}
+
+impl FeedTls {
+    pub fn apply(&self, mut builder: ClientBuilder) -> ClientBuilder {
+        if let Some((identity, _)) = &self.identity {
+            builder = builder.identity(identity.clone());
+        }
+        if let Some((ca, _)) = &self.ca {
+            builder = builder.add_root_certificate(ca.clone());
+        }
+        builder
+    }
+
+    pub fn client_certificate(&self) -> Option<&CertificateInfo> {
+        self.identity.as_ref().map(|(_, info)| info)
+    }
+
+    pub fn ca_certificate(&self) -> Option<&CertificateInfo> {
+        self.ca.as_ref().map(|(_, info)| info)
+    }
+}
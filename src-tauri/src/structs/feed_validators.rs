@@ -0,0 +1,32 @@
+use reqwest::header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::RequestBuilder;
+
+/// The `ETag` and `Last-Modified` headers of a feed response, as received. Weak ETags are kept as such,
+/// servers compare them weakly.
+#[derive(Clone, Debug, Default, sqlx::FromRow)]
+pub struct FeedValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl FeedValidators {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| headers.get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+
+        FeedValidators { etag: header(ETAG), last_modified: header(LAST_MODIFIED) }
+    }
+
+    /// Makes the request conditional, or leaves it as is without validators.
+    pub fn apply(&self, mut request: RequestBuilder) -> RequestBuilder {
+        if let Some(etag) = &self.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        request
+    }
+}
@@ -0,0 +1,24 @@
+use serde::Serialize;
+use crate::enums::address_family::AddressFamily;
+use crate::enums::user_agent::UserAgent;
+use crate::structs::certificate_info::CertificateInfo;
+
+/// How a fetch of a feed went, down to the addresses it was fetched from.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchDebug {
+    pub url: String,
+    /// The family of the feed, or of the preferences when the feed follows them
+    pub address_family: AddressFamily,
+    pub user_agent: UserAgent,
+    /// In resolution order, redirects included
+    pub resolved_addresses: Vec<String>,
+    /// The resolved addresses of the family, the ones the connections were attempted to
+    pub tried_addresses: Vec<String>,
+    pub connected_address: Option<String>,
+    pub status: Option<u16>,
+    pub elapsed_ms: u64,
+    pub error: Option<String>,
+    pub client_certificate: Option<CertificateInfo>,
+    pub ca_certificate: Option<CertificateInfo>,
+}
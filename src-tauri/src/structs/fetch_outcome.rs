@@ -0,0 +1,8 @@
+/// A scheduled sync, at the local time it happened.
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct FetchOutcome {
+    /// As YYYY-MM-DD
+    pub local_date: String,
+    pub local_minute: u32,
+    pub success: bool,
+}
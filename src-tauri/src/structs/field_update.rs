@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+/// Sent to every window after an edit so they refresh only the changed fields of the row.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldUpdate {
+    pub identifier: String,
+    pub fields: Vec<&'static str>,
+    pub version: i64,
+}
@@ -0,0 +1,19 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// When the main window got the focus, to measure the time spent in the app.
+#[derive(Default)]
+pub struct FocusTracker {
+    focused_since: Mutex<Option<Instant>>,
+}
+
+impl FocusTracker {
+    pub fn focus(&self) {
+        self.focused_since.lock().unwrap().get_or_insert_with(Instant::now);
+    }
+
+    /// Returns how long the window had the focus, None when it didn't have it.
+    pub fn blur(&self) -> Option<Duration> {
+        self.focused_since.lock().unwrap().take().map(|since| since.elapsed())
+    }
+}
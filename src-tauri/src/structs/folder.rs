@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Folder {
+    pub identifier: String,
+    pub name: String,
+    /// None for root folders
+    pub parent_identifier: Option<String>,
+}
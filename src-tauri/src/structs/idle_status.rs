@@ -0,0 +1,12 @@
+use serde::Serialize;
+use crate::enums::background_job::BackgroundJob;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleStatus {
+    pub idle: bool,
+    /// Of the last command, heartbeat or focus change
+    pub last_activity_at: i64,
+    /// The idle-only jobs waiting for the user to be idle
+    pub deferred: Vec<BackgroundJob>,
+}
@@ -0,0 +1,12 @@
+use std::collections::BTreeMap;
+use crate::structs::article_extra::ArticleExtra;
+use crate::structs::enclosure::Enclosure;
+
+/// Item-level elements that feed-rs does not keep in its model.
+#[derive(Debug, Default)]
+pub struct ItemExtensions {
+    pub comments_url: Option<String>,
+    /// Every enclosure, podcasting 2.0 transcripts and chapters included
+    pub enclosures: Vec<Enclosure>,
+    pub extras: BTreeMap<String, Vec<ArticleExtra>>,
+}
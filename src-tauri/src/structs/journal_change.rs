@@ -0,0 +1,9 @@
+use serde::Serialize;
+use crate::enums::change_entity::ChangeEntity;
+
+#[derive(Clone, Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalChange {
+    pub entity: ChangeEntity,
+    pub identifier: String,
+}
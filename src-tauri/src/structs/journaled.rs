@@ -0,0 +1,13 @@
+use serde::Serialize;
+
+/// The payload of an event about changed data, with the journal watermark the window acknowledges once
+/// it applied it.
+#[derive(Clone, Debug, Serialize)]
+pub struct Journaled<T: Serialize> {
+    #[serde(flatten)]
+    pub payload: T,
+    pub watermark: i64,
+    /// The list windows the event was sent to, for the events about articles
+    #[serde(rename = "listWindows", skip_serializing_if = "Option::is_none")]
+    pub list_windows: Option<Vec<String>>,
+}
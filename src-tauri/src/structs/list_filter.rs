@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use crate::enums::article_sort::ArticleSort;
+use crate::enums::read_state::ReadState;
+
+/// The articles a list window shows, as the `ArticleFilter` it pages through them with.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ListFilter {
+    pub feed_identifier: Option<String>,
+    pub sort: ArticleSort,
+    pub min_score: Option<i64>,
+    /// All the articles when left out
+    pub read_state: Option<ReadState>,
+}
+
+impl ListFilter {
+    /// Whether a change to an article of the feed may show in the list, the score being unknown for a whole
+    /// sync. Read state changes always may, as they move articles in or out of the list.
+    pub fn may_show(&self, feed_identifier: &str, score: Option<i64>) -> bool {
+        self.feed_identifier.as_deref().is_none_or(|identifier| identifier == feed_identifier)
+            && self.min_score.zip(score).is_none_or(|(min_score, score)| score >= min_score)
+    }
+}
@@ -0,0 +1,11 @@
+use serde::Serialize;
+use sqlx::types::Json;
+use crate::structs::list_filter::ListFilter;
+
+#[derive(Clone, Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWindow {
+    pub label: String,
+    pub title: String,
+    pub filter: Json<ListFilter>,
+}
@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use crate::structs::list_filter::ListFilter;
+
+/// The filters of the open list windows, so events about changed articles only wake the list windows
+/// they may show in.
+#[derive(Default)]
+pub struct ListWindows {
+    filters: Mutex<HashMap<String, ListFilter>>,
+    restored: AtomicBool,
+}
+
+impl ListWindows {
+    pub fn subscribe(&self, label: &str, filter: ListFilter) {
+        self.filters.lock().unwrap().insert(label.to_string(), filter);
+    }
+
+    /// Returns whether the window was a list window.
+    pub fn unsubscribe(&self, label: &str) -> bool {
+        self.filters.lock().unwrap().remove(label).is_some()
+    }
+
+    pub fn contains(&self, label: &str) -> bool {
+        self.filters.lock().unwrap().contains_key(label)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.lock().unwrap().is_empty()
+    }
+
+    /// The list windows whose filter `shows` accepts.
+    pub fn showing(&self, shows: impl Fn(&ListFilter) -> bool) -> Vec<String> {
+        let mut labels: Vec<String> = self.filters.lock().unwrap().iter()
+            .filter(|(_, filter)| shows(filter))
+            .map(|(label, _)| label.clone())
+            .collect();
+        labels.sort();
+        labels
+    }
+
+    /// Returns true the first time only, the list windows of the last session being reopened once.
+    pub fn start_restore(&self) -> bool {
+        !self.restored.swap(true, Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,11 @@
+use serde::Serialize;
+use crate::enums::local_metric::LocalMetric;
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalMetricDay {
+    /// Local date, as YYYY-MM-DD
+    pub day: String,
+    pub metric: LocalMetric,
+    pub value: i64,
+}
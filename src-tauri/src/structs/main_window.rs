@@ -0,0 +1,55 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+/// Keeps track of the splashscreen and the main window, so the main window is recreated when it goes
+/// away without being asked to close, like when its webview process crashes.
+#[derive(Default)]
+pub struct MainWindow {
+    /// Held while the windows are shown, closed or created, so concurrent calls see each other's changes
+    lock: Mutex<()>,
+    splashscreen_closed: AtomicBool,
+    closing: AtomicBool,
+    /// From the unexpected destruction of the window until the new one is created
+    recreating: AtomicBool,
+    /// Created again and not shown yet
+    recreated: AtomicBool,
+}
+
+impl MainWindow {
+    pub fn lock(&self) -> MutexGuard<'_, ()> {
+        self.lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns true the first time only.
+    pub fn close_splashscreen(&self) -> bool {
+        !self.splashscreen_closed.swap(true, Ordering::SeqCst)
+    }
+
+    pub fn expect_close(&self) {
+        self.closing.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns true when the window wasn't asked to close, the app then waiting for its recreation
+    /// rather than exiting with its last window.
+    pub fn destroyed(&self) -> bool {
+        let unexpected = !self.closing.swap(false, Ordering::SeqCst);
+        if unexpected {
+            self.recreating.store(true, Ordering::SeqCst);
+        }
+        unexpected
+    }
+
+    pub fn is_recreating(&self) -> bool {
+        self.recreating.load(Ordering::SeqCst)
+    }
+
+    pub fn finish_recreating(&self, created: bool) {
+        self.recreated.store(created, Ordering::SeqCst);
+        self.recreating.store(false, Ordering::SeqCst);
+    }
+
+    /// Whether the window being shown is a recreated one, which only the first show reports.
+    pub fn take_recreated(&self) -> bool {
+        self.recreated.swap(false, Ordering::SeqCst)
+    }
+}
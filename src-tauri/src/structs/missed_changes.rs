@@ -0,0 +1,15 @@
+use serde::Serialize;
+use crate::structs::journal_change::JournalChange;
+
+/// The changes a window didn't acknowledge. Once there are too many, or they were pruned from the journal,
+/// only `full_refresh` is set and the window reloads everything.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissedChanges {
+    /// The last watermark acknowledged by the window
+    pub from: i64,
+    /// To acknowledge once the changes are applied
+    pub watermark: i64,
+    pub full_refresh: bool,
+    pub changes: Vec<JournalChange>,
+}
@@ -4,3 +4,144 @@ pub mod sync_request;
 pub mod image;
 pub mod single_instance_payload;
 pub mod app;
+pub mod item_extensions;
+pub mod comment;
+pub mod sync_progress;
+pub mod duplicate_feeds;
+pub mod scoring_rule;
+pub mod scorable_article;
+pub mod scoring_preview;
+pub mod article_filter;
+pub mod stored_article;
+pub mod session_state;
+pub mod feed_dedup;
+pub mod dedup_candidate;
+pub mod write_gate;
+pub mod write_guard;
+pub mod write_freeze_payload;
+pub mod post_processor_setting;
+pub mod pool_health;
+pub mod feed_changes;
+pub mod editable_feed;
+pub mod field_update;
+pub mod feed_change;
+pub mod preferences;
+pub mod enclosure;
+pub mod transcript_segment;
+pub mod transcript;
+pub mod resource_stats;
+pub mod autostart_registration;
+pub mod autostart_status;
+pub mod search_clause;
+pub mod search_query;
+pub mod search_error;
+pub mod search_filters;
+pub mod tray_monitor;
+pub mod tray_report;
+pub mod feed_cookie;
+pub mod feed_session;
+pub mod feed_candidate;
+pub mod backfill_progress;
+pub mod schema_info;
+pub mod article_cursor;
+pub mod webview_capabilities;
+pub mod article_page;
+pub mod local_metric_day;
+pub mod focus_tracker;
+pub mod article_content;
+pub mod screen_rect;
+pub mod exported_feed;
+pub mod exported_article;
+pub mod queued_article;
+pub mod export_manifest;
+pub mod export_progress;
+pub mod fetch_outcome;
+pub mod unavailable_window;
+pub mod feed_health;
+pub mod command_timing;
+pub mod version_mismatch;
+pub mod schema_check;
+pub mod article_extra;
+pub mod feed_extras;
+pub mod article_details;
+pub mod journal_change;
+pub mod missed_changes;
+pub mod journaled;
+pub mod benchmark_result;
+pub mod benchmark_report;
+pub mod power_status;
+pub mod power_policy;
+pub mod power_monitor;
+pub mod scheduler_state;
+pub mod exchange_header;
+pub mod exchange_entry;
+pub mod exchange_report;
+pub mod folder;
+pub mod opml_outline;
+pub mod opml_failure;
+pub mod opml_import;
+pub mod system_resolver;
+pub mod family_resolver;
+pub mod fetch_debug;
+pub mod sync_failure;
+pub mod sync_report;
+pub mod feed_validators;
+pub mod sync_outcome;
+pub mod bookmark;
+pub mod bookmark_folder;
+pub mod bookmarks_inspection;
+pub mod bookmark_import_options;
+pub mod discovered_feed;
+pub mod discovered_folder;
+pub mod undiscovered_site;
+pub mod bookmarks_discovery;
+pub mod discovery_progress;
+pub mod feed_article_count;
+pub mod smart_counts;
+pub mod background_sync;
+pub mod background_sync_status;
+pub mod read_pool;
+pub mod feed_credentials;
+pub mod prune_report;
+pub mod main_window;
+pub mod certificate_info;
+pub mod feed_certificates;
+pub mod feed_tls;
+pub mod prepared_article;
+pub mod feed_completion;
+pub mod feed_stream;
+pub mod sync_writer;
+pub mod add_feed_request;
+pub mod quick_add;
+pub mod exported_archive;
+pub mod condition_outcome;
+pub mod explanation_factor;
+pub mod explained_article;
+pub mod self_test_check;
+pub mod self_test_report;
+pub mod self_test_environment;
+pub mod list_filter;
+pub mod list_window;
+pub mod list_windows;
+pub mod storage_policy;
+pub mod feed_storage_stats;
+pub mod storage_projection_point;
+pub mod storage_projection;
+pub mod feed_network;
+pub mod network_override;
+pub mod feed_overrides;
+pub mod feed_network_settings;
+pub mod notification_candidate;
+pub mod notified_article;
+pub mod preference_reset;
+pub mod preferences_upgrade;
+pub mod timeline_page;
+pub mod feed_purge_preview;
+pub mod purge_preview;
+pub mod notification_limiter;
+pub mod opml_feed_settings;
+pub mod opml_export_options;
+pub mod preferences_cache;
+pub mod scoring_subject;
+pub mod activity_monitor;
+pub mod idle_status;
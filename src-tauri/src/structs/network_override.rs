@@ -0,0 +1,10 @@
+use serde::Serialize;
+use crate::enums::override_kind::OverrideKind;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkOverride {
+    pub kind: OverrideKind,
+    /// What the feed uses instead, secret values masked
+    pub detail: String,
+}
@@ -0,0 +1,11 @@
+/// An article to notify, from the last syncs.
+#[derive(Debug, sqlx::FromRow)]
+pub struct NotificationCandidate {
+    pub identifier: String,
+    pub feed_identifier: String,
+    pub dedup_key: Option<i64>,
+    pub title: String,
+    pub feed_name: String,
+    /// Notified before, and updated since in a feed rereading its updated articles
+    pub updated: bool,
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use crate::enums::notification_notice::NotificationNotice;
+use crate::structs::notification_candidate::NotificationCandidate;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+const MINUTE_MS: i64 = 60 * 1000;
+/// Feeds notifying within a minute above which a single summary is shown instead
+pub const MAX_FEEDS_PER_MINUTE: usize = 5;
+
+#[derive(Default)]
+struct LimiterState {
+    /// Days since the epoch, in UTC
+    day: i64,
+    notified_at: HashMap<String, i64>,
+    /// Articles of each feed taken during its window, with the feed name
+    held_back: HashMap<String, (String, usize)>,
+    /// Feed notifications shown in the last minute
+    shown_at: Vec<i64>,
+    summary_at: Option<i64>,
+}
+
+/// Shows at most one notification per feed per window, the articles taken in between being counted in
+/// the next one, and a single summary when more than `MAX_FEEDS_PER_MINUTE` feeds notify within a minute,
+/// the feeds then waiting for the minute to end. Kept for the session, and forgotten at midnight UTC.
+/// Times are in milliseconds, and increase from a call to the next.
+#[derive(Default)]
+pub struct NotificationLimiter {
+    state: Mutex<LimiterState>,
+}
+
+impl NotificationLimiter {
+    /// The notifications to show at `now` for `candidates`, in their order, and for the articles held back
+    /// whose feed window ended since.
+    pub fn admit(&self, candidates: Vec<NotificationCandidate>, window_ms: i64, now: i64) -> Vec<NotificationNotice> {
+        let mut state = self.state.lock().unwrap();
+        let day = now.div_euclid(DAY_MS);
+        if state.day != day {
+            *state = LimiterState { day, ..LimiterState::default() };
+        }
+
+        let mut feeds: Vec<(String, String, Vec<NotificationCandidate>)> = vec![];
+        for candidate in candidates {
+            match feeds.iter_mut().find(|(identifier, ..)| *identifier == candidate.feed_identifier) {
+                Some((_, _, feed_candidates)) => feed_candidates.push(candidate),
+                None => feeds.push((candidate.feed_identifier.clone(), candidate.feed_name.clone(), vec![candidate])),
+            }
+        }
+        let mut due: Vec<(String, String, Vec<NotificationCandidate>)> = state.held_back.iter()
+            .filter(|(identifier, _)| !feeds.iter().any(|(feed, ..)| feed == *identifier))
+            .map(|(identifier, (name, _))| (identifier.clone(), name.clone(), vec![]))
+            .collect();
+        due.sort_by(|a, b| a.0.cmp(&b.0));
+        feeds.extend(due);
+
+        let summarizing = state.summary_at.is_some_and(|at| now - at < MINUTE_MS);
+        let mut notices = vec![];
+        for (identifier, name, feed_candidates) in feeds {
+            let window_ended = state.notified_at.get(&identifier).is_none_or(|at| now - at >= window_ms);
+            if window_ended && !summarizing {
+                let held_back = state.held_back.remove(&identifier).map_or(0, |(_, count)| count);
+                notices.push(NotificationNotice::Feed { feed_name: name, candidates: feed_candidates, held_back });
+                state.notified_at.insert(identifier, now);
+            } else if !feed_candidates.is_empty() {
+                state.held_back.entry(identifier).or_insert((name, 0)).1 += feed_candidates.len();
+            }
+        }
+
+        state.shown_at.retain(|at| now - at < MINUTE_MS);
+        if state.shown_at.len() + notices.len() > MAX_FEEDS_PER_MINUTE {
+            state.summary_at = Some(now);
+            let articles = notices.iter().map(NotificationNotice::articles).sum();
+            return vec![NotificationNotice::Summary { articles, feeds: notices.len() }];
+        }
+        state.shown_at.extend(notices.iter().map(|_| now));
+
+        notices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW_MS: i64 = 15 * MINUTE_MS;
+    /// Away from midnight
+    const START: i64 = 20_000 * DAY_MS + 8 * 60 * MINUTE_MS;
+
+    /// Articles of each feed, in order
+    fn candidates(feeds: &[(&str, usize)]) -> Vec<NotificationCandidate> {
+        feeds.iter()
+            .flat_map(|(feed, articles)| (0..*articles).map(move |index| NotificationCandidate {
+                identifier: format!("{}-{}", feed, index),
+                feed_identifier: feed.to_string(),
+                dedup_key: None,
+                title: format!("Article {} of {}", index, feed),
+                feed_name: feed.to_uppercase(),
+                updated: false,
+            }))
+            .collect()
+    }
+
+    /// As "FEED new+held back" and "summary articles/feeds"
+    fn describe(notices: &[NotificationNotice]) -> Vec<String> {
+        notices.iter()
+            .map(|notice| match notice {
+                NotificationNotice::Feed { feed_name, candidates, held_back } => format!("{} {}+{}", feed_name, candidates.len(), held_back),
+                NotificationNotice::Summary { articles, feeds } => format!("summary {}/{}", articles, feeds),
+            })
+            .collect()
+    }
+
+    /// A call as (ms after the start, articles of each feed, notices)
+    type Step<'a> = (i64, &'a [(&'a str, usize)], &'a [&'a str]);
+
+    fn run(steps: &[Step]) {
+        let limiter = NotificationLimiter::default();
+
+        for (offset, feeds, expected) in steps {
+            let notices = limiter.admit(candidates(feeds), WINDOW_MS, START + offset);
+            assert_eq!(describe(&notices), *expected, "at {} ms with {:?}", offset, feeds);
+        }
+    }
+
+    #[test]
+    fn one_notification_per_feed_per_window() {
+        run(&[
+            (0, &[("a", 2)], &["A 2+0"]),
+            (MINUTE_MS, &[("a", 3)], &[]),
+            (5 * MINUTE_MS, &[("a", 500)], &[]),
+            (WINDOW_MS - 1, &[("a", 1)], &[]),
+            (WINDOW_MS, &[("a", 1)], &["A 1+504"]),
+            (WINDOW_MS + MINUTE_MS, &[("a", 1)], &[]),
+        ]);
+    }
+
+    #[test]
+    fn feeds_have_their_own_window() {
+        run(&[
+            (0, &[("a", 1), ("b", 1)], &["A 1+0", "B 1+0"]),
+            (MINUTE_MS, &[("a", 1), ("c", 2)], &["C 2+0"]),
+            (WINDOW_MS, &[("b", 1), ("a", 4)], &["B 1+0", "A 4+1"]),
+        ]);
+    }
+
+    #[test]
+    fn held_back_articles_come_due_alone() {
+        run(&[
+            (0, &[("a", 1), ("b", 1)], &["A 1+0", "B 1+0"]),
+            (MINUTE_MS, &[("b", 3), ("a", 2)], &[]),
+            (10 * MINUTE_MS, &[], &[]),
+            (WINDOW_MS, &[], &["A 0+2", "B 0+3"]),
+            (2 * WINDOW_MS, &[], &[]),
+        ]);
+    }
+
+    #[test]
+    fn too_many_feeds_show_a_summary() {
+        run(&[
+            (0, &[("a", 1), ("b", 2), ("c", 1), ("d", 1), ("e", 1), ("f", 10)], &["summary 16/6"]),
+            // Waiting for the minute to end, whatever their window
+            (30_000, &[("g", 1), ("a", 1)], &[]),
+            (MINUTE_MS, &[("h", 1)], &["H 1+0", "G 0+1"]),
+            (WINDOW_MS, &[], &["A 0+1"]),
+        ]);
+    }
+
+    #[test]
+    fn feeds_notifying_within_a_minute_add_up() {
+        run(&[
+            (0, &[("a", 1), ("b", 1), ("c", 1)], &["A 1+0", "B 1+0", "C 1+0"]),
+            (30_000, &[("d", 1), ("e", 1)], &["D 1+0", "E 1+0"]),
+            (45_000, &[("f", 2)], &["summary 2/1"]),
+            // The feeds shown a minute ago no longer count
+            (3 * MINUTE_MS, &[("g", 1), ("h", 1), ("i", 1), ("j", 1), ("k", 1)], &["G 1+0", "H 1+0", "I 1+0", "J 1+0", "K 1+0"]),
+        ]);
+    }
+
+    #[test]
+    fn resets_daily() {
+        let midnight = (START / DAY_MS + 1) * DAY_MS - START;
+
+        run(&[
+            (0, &[("a", 1)], &["A 1+0"]),
+            (midnight - MINUTE_MS, &[("a", 1)], &["A 1+0"]),
+            (midnight - 1, &[("a", 2)], &[]),
+            // Forgetting the window and the articles held back
+            (midnight, &[("a", 1)], &["A 1+0"]),
+            (midnight + WINDOW_MS, &[], &[]),
+        ]);
+    }
+}
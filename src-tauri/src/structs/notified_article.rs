@@ -0,0 +1,17 @@
+use serde::Serialize;
+
+/// An article of the notification history, most recent first.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifiedArticle {
+    pub article_identifier: String,
+    pub feed_identifier: String,
+    pub feed_name: String,
+    /// As notified
+    pub title: String,
+    /// Notified for an update rather than as new
+    pub updated: bool,
+    pub notified_at: i64,
+    /// False once the article was pruned or deleted
+    pub available: bool,
+}
@@ -0,0 +1,26 @@
+use serde::Deserialize;
+
+/// What `export_opml` writes, every feed with its folders and settings by default.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OpmlExportOptions {
+    /// The feeds are all at the root otherwise
+    pub folders: bool,
+    /// Plain OPML 2.0 for other readers, without the `alduin:` namespace and the settings of the feeds
+    pub interchange: bool,
+    /// Only the feeds of these folders, their subfolders included, and the feeds of `feed_identifiers`.
+    /// Every feed when both are None
+    pub folder_identifiers: Option<Vec<String>>,
+    pub feed_identifiers: Option<Vec<String>>,
+}
+
+impl Default for OpmlExportOptions {
+    fn default() -> Self {
+        OpmlExportOptions {
+            folders: true,
+            interchange: false,
+            folder_identifiers: None,
+            feed_identifiers: None,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpmlFailure {
+    pub title: String,
+    pub xml_url: String,
+    pub error: String,
+}
@@ -0,0 +1,24 @@
+use serde::Deserialize;
+use crate::enums::address_family::AddressFamily;
+use crate::enums::dedup_strategy::DedupStrategy;
+use crate::enums::feed_priority::FeedPriority;
+use crate::enums::user_agent::UserAgent;
+
+/// The settings of a feed read from the `alduin:` attributes of its outline, None for the missing or
+/// invalid ones, which keep the default of a new feed.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OpmlFeedSettings {
+    pub interval: Option<i64>,
+    pub priority: Option<FeedPriority>,
+    pub archived: Option<bool>,
+    pub dedup_strategy: Option<DedupStrategy>,
+    pub dedup_strategy_locked: Option<bool>,
+    pub address_family: Option<AddressFamily>,
+    pub user_agent: Option<UserAgent>,
+    pub reread_on_update: Option<bool>,
+    /// 0 turning the retention off for the feed, like in the feed editor
+    pub retention_days: Option<i64>,
+    pub retention_max_articles: Option<i64>,
+    pub title_prefixes: Option<Vec<String>>,
+}
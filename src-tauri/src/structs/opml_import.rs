@@ -0,0 +1,13 @@
+use serde::Serialize;
+use crate::structs::opml_failure::OpmlFailure;
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpmlImport {
+    pub added: usize,
+    /// Feeds already subscribed to, under this URL or a variant of it
+    pub skipped: usize,
+    pub failed: usize,
+    pub folders_added: usize,
+    pub failures: Vec<OpmlFailure>,
+}
@@ -0,0 +1,15 @@
+use crate::structs::opml_feed_settings::OpmlFeedSettings;
+
+/// An `<outline>` of an OPML file, or a feed picked for `import_feeds`. The ones without a feed URL are folders.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpmlOutline {
+    pub title: String,
+    pub xml_url: Option<String>,
+    pub html_url: Option<String>,
+    /// Names of the folders it's in, from the root
+    pub folder: Vec<String>,
+    /// None when the outline has no `alduin:` attributes
+    #[serde(default)]
+    pub settings: Option<OpmlFeedSettings>,
+}
@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolHealth {
+    pub size: u32,
+    pub idle: usize,
+    /// Acquire timeouts observed by the keep-alive since startup
+    pub acquire_timeouts: u64,
+    /// Times the database file was replaced underneath the app since startup
+    pub file_replacements: u64,
+}
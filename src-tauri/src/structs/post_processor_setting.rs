@@ -0,0 +1,8 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostProcessorSetting {
+    pub name: String,
+    pub enabled: bool,
+}
@@ -0,0 +1,18 @@
+use std::sync::Mutex;
+use crate::structs::power_status::PowerStatus;
+
+/// Last known power status, kept up to date by the power watch.
+#[derive(Default)]
+pub struct PowerMonitor {
+    status: Mutex<PowerStatus>,
+}
+
+impl PowerMonitor {
+    pub fn status(&self) -> PowerStatus {
+        *self.status.lock().unwrap()
+    }
+
+    pub fn update(&self, status: PowerStatus) {
+        *self.status.lock().unwrap() = status;
+    }
+}
@@ -0,0 +1,28 @@
+use serde::Serialize;
+
+/// How much the scheduler does on the current power source, the default being the AC policy.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerPolicy {
+    /// Feed intervals and the priority pass wait this many times longer
+    pub interval_factor: u64,
+    pub priority_pass: bool,
+    /// Feed icons and article thumbnails are loaded
+    pub load_images: bool,
+    /// Work that can wait for idle time, like the backfills and pruning the journals
+    pub idle_maintenance: bool,
+    /// Why the scheduler does less, e.g. "on battery". None on the AC policy
+    pub reason: Option<String>,
+}
+
+impl Default for PowerPolicy {
+    fn default() -> Self {
+        PowerPolicy {
+            interval_factor: 1,
+            priority_pass: true,
+            load_images: true,
+            idle_maintenance: true,
+            reason: None,
+        }
+    }
+}
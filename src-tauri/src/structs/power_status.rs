@@ -0,0 +1,10 @@
+use serde::Serialize;
+use crate::enums::power_source::PowerSource;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerStatus {
+    pub source: PowerSource,
+    /// None without a battery, or when its charge can't be read
+    pub battery_percent: Option<u8>,
+}
@@ -0,0 +1,11 @@
+use serde::Serialize;
+
+/// A preference of the file that didn't fit `Preferences`, back to its default.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferenceReset {
+    pub key: String,
+    /// As found in the file, in JSON
+    pub value: String,
+    pub reason: String,
+}
@@ -0,0 +1,177 @@
+use std::fs;
+use std::path::Path;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
+use crate::enums::address_family::AddressFamily;
+use crate::enums::close_behavior::CloseBehavior;
+use crate::enums::language::Language;
+use crate::enums::power_source::PowerSource;
+use crate::enums::window_placement::WindowPlacement;
+use crate::structs::power_policy::PowerPolicy;
+use crate::structs::power_status::PowerStatus;
+use crate::structs::preferences_cache::PreferencesCache;
+use crate::structs::preferences_upgrade::PreferencesUpgrade;
+use crate::utils::preferences_schema::{unchanged, upgrade, versioned};
+
+pub const FILE_NAME: &str = "preferences.dat";
+
+/// Preferences the backend needs, as saved by the frontend in the store.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Preferences {
+    pub fast_pass_minutes: u64,
+    /// Between two syncs of every feed run by the backend, see `scheduler::background`
+    pub background_sync_minutes: u64,
+    /// Tightens the resource knobs below, for constrained devices
+    pub low_resource_mode: bool,
+    /// Feeds fetched at the same time, see `sync_concurrency`
+    pub concurrent_syncs: usize,
+    pub auto_start: bool,
+    /// Passed as `--autostart-delay` when launched at login, to wait out the login network storm
+    pub autostart_delay_seconds: u64,
+    pub close_behavior: CloseBehavior,
+    /// Opt-in, see `database::local_metrics`
+    pub local_metrics: bool,
+    /// Longer articles are sent to the frontend in chunks of this many characters
+    pub content_chunk_size: i64,
+    /// Removes articles from the reading queue once read
+    pub dequeue_read: bool,
+    pub show_window_on: WindowPlacement,
+    /// Syncs less often and skips optional work on battery, see `power_policy`
+    pub battery_saver: bool,
+    pub battery_interval_factor: u64,
+    /// Below this charge, the interval factor doubles
+    pub low_battery_percent: u8,
+    /// Folder shared with other devices, see `database::state_exchange`. Empty when off
+    pub state_exchange_folder: String,
+    /// Of the feeds following the preferences
+    pub address_family: AddressFamily,
+    /// Articles kept this many days by `prune_articles`, 0 keeping them forever. Feeds can override it
+    pub retention_days: u32,
+    /// Most recent articles kept per feed, 0 for no limit. Feeds can override it
+    pub retention_max_articles: u32,
+    /// Prunes after `sync_all`, once the user is idle
+    pub prune_after_sync: bool,
+    /// Of the dates written by the backend, see `utils::time_format`
+    pub language: Language,
+    /// Notifies the articles found by the background syncs, see `database::notification_ledger`
+    pub notify_new_articles: bool,
+    /// At most one notification per feed within this many minutes, see `NotificationLimiter`
+    pub notification_window_minutes: u64,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Preferences {
+            fast_pass_minutes: 5,
+            background_sync_minutes: 30,
+            low_resource_mode: false,
+            concurrent_syncs: 8,
+            auto_start: true,
+            autostart_delay_seconds: 0,
+            close_behavior: CloseBehavior::Auto,
+            local_metrics: false,
+            content_chunk_size: 256 * 1024,
+            dequeue_read: false,
+            show_window_on: WindowPlacement::Last,
+            battery_saver: true,
+            battery_interval_factor: 3,
+            low_battery_percent: 20,
+            state_exchange_folder: String::new(),
+            address_family: AddressFamily::Auto,
+            retention_days: 0,
+            retention_max_articles: 0,
+            prune_after_sync: false,
+            language: Language::Auto,
+            notify_new_articles: false,
+            notification_window_minutes: 15,
+        }
+    }
+}
+
+impl Preferences {
+    /// Falls back to the defaults for missing preferences, or when the store can't be read. Kept in
+    /// `PreferencesCache` until the store file changes.
+    pub fn load(app_handle: &AppHandle) -> Self {
+        app_handle.path_resolver().app_data_dir()
+            .map(|dir| app_handle.state::<PreferencesCache>().load(&dir))
+            .unwrap_or_default()
+    }
+
+    /// Same as `load`, for when the app isn't built yet. Invalid preferences fall back to their default
+    /// alone, see `utils::preferences_schema`.
+    pub fn load_from(data_dir: &Path) -> Self {
+        fs::read_to_string(data_dir.join(FILE_NAME)).ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .map(|file| Preferences::from_object(upgrade(file).0))
+            .unwrap_or_default()
+    }
+
+    /// Loads the preferences at startup, before the store plugin reads the file, and writes the file back
+    /// at the current version when it was upgraded or had invalid preferences, unless written by a newer one.
+    pub fn load_upgrading(data_dir: &Path) -> (Self, PreferencesUpgrade) {
+        let path = data_dir.join(FILE_NAME);
+        let file = match fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str(&content) {
+                Ok(file) => file,
+                Err(e) => {
+                    eprintln!("Error parsing {}, using the default preferences: {}", path.display(), e);
+                    return (Preferences::default(), unchanged());
+                }
+            },
+            // Not saved yet
+            Err(_) => return (Preferences::default(), unchanged()),
+        };
+
+        let (object, upgrade) = upgrade(file);
+        for reset in &upgrade.resets {
+            eprintln!("Reset preference {} from {}: {}", reset.key, reset.value, reset.reason);
+        }
+        if upgrade.read_only {
+            eprintln!("The preferences were saved by a newer version of Alduin ({}), they won't be saved", upgrade.from_version);
+        } else if upgrade.from_version != upgrade.version || !upgrade.resets.is_empty() {
+            let partial = data_dir.join(format!("{}.partial", FILE_NAME));
+            let written = serde_json::to_vec(&versioned(object.clone()))
+                .map_err(|e| e.to_string())
+                .and_then(|content| fs::write(&partial, content).map_err(|e| e.to_string()))
+                .and_then(|_| fs::rename(&partial, &path).map_err(|e| e.to_string()));
+            if let Err(e) = written {
+                eprintln!("Error writing the upgraded preferences to {}: {}", path.display(), e);
+            }
+        }
+
+        (Preferences::from_object(object), upgrade)
+    }
+
+    /// Every preference of `object` having been checked alone, only a conflict between them can fail.
+    fn from_object(object: Map<String, Value>) -> Self {
+        serde_json::from_value(Value::Object(object)).unwrap_or_default()
+    }
+
+    /// How many feeds can be fetched at the same time, up to the limit of `scheduler::limiter`.
+    pub fn sync_concurrency(&self) -> usize {
+        if self.low_resource_mode { 2 } else { self.concurrent_syncs.max(1) }
+    }
+
+    /// How many articles the background rescoring loads and updates per transaction.
+    pub fn recompute_batch_size(&self) -> i64 {
+        if self.low_resource_mode { 100 } else { 500 }
+    }
+
+    /// What the scheduler does on this power status. Platforms without detection get the AC policy.
+    pub fn power_policy(&self, status: PowerStatus) -> PowerPolicy {
+        if !self.battery_saver || status.source != PowerSource::Battery {
+            return PowerPolicy::default();
+        }
+
+        let low = status.battery_percent.is_some_and(|percent| percent <= self.low_battery_percent);
+        PowerPolicy {
+            interval_factor: self.battery_interval_factor.max(1) * if low { 2 } else { 1 },
+            priority_pass: false,
+            load_images: false,
+            idle_maintenance: false,
+            reason: Some(String::from(if low { "battery low" } else { "on battery" })),
+        }
+    }
+}
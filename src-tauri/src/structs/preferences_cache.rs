@@ -0,0 +1,62 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::SystemTime;
+use crate::structs::preferences::{Preferences, FILE_NAME};
+
+/// The size and modification time of the store file, None while it isn't saved.
+type FileStamp = Option<(u64, SystemTime)>;
+
+/// The preferences last loaded from the store, read and upgraded again only once the frontend saved it.
+#[derive(Default)]
+pub struct PreferencesCache {
+    loaded: Mutex<Option<(FileStamp, Preferences)>>,
+}
+
+impl PreferencesCache {
+    /// Stamped before reading, a save racing the read is reloaded on the next call.
+    pub fn load(&self, data_dir: &Path) -> Preferences {
+        let stamp = fs::metadata(data_dir.join(FILE_NAME)).ok()
+            .and_then(|metadata| Some((metadata.len(), metadata.modified().ok()?)));
+
+        let mut loaded = self.loaded.lock().unwrap();
+        match loaded.as_ref() {
+            Some((loaded_stamp, preferences)) if *loaded_stamp == stamp => preferences.clone(),
+            _ => loaded.insert((stamp, Preferences::load_from(data_dir))).1.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::time::Duration;
+
+    #[test]
+    fn reloads_once_the_file_changes() {
+        let dir = std::env::temp_dir().join(format!("alduin-preferences-cache-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(FILE_NAME);
+        let _ = fs::remove_file(&path);
+        let cache = PreferencesCache::default();
+
+        assert_eq!(cache.load(&dir).retention_days, 0);
+
+        fs::write(&path, r#"{"retentionDays":30}"#).unwrap();
+        assert_eq!(cache.load(&dir).retention_days, 30);
+
+        // Same size and modification time, taken as the same file
+        let modified = fs::metadata(&path).unwrap().modified().unwrap();
+        fs::write(&path, r#"{"retentionDays":40}"#).unwrap();
+        File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+        assert_eq!(cache.load(&dir).retention_days, 30);
+
+        File::options().write(true).open(&path).unwrap().set_modified(modified + Duration::from_secs(1)).unwrap();
+        assert_eq!(cache.load(&dir).retention_days, 40);
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(cache.load(&dir).retention_days, 0);
+        fs::remove_dir(&dir).unwrap();
+    }
+}
@@ -0,0 +1,21 @@
+use serde::Serialize;
+use crate::structs::preference_reset::PreferenceReset;
+
+/// How the preferences file was brought to the current version at startup, see `utils::preferences_schema`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferencesUpgrade {
+    /// Of the file as found, 0 for files written before the versions
+    pub from_version: u64,
+    pub version: u64,
+    /// Written by a newer version of the app, so never written back
+    pub read_only: bool,
+    pub resets: Vec<PreferenceReset>,
+}
+
+impl PreferencesUpgrade {
+    /// Whether the user should hear about it: preferences were lost, or can't be saved.
+    pub fn is_notable(&self) -> bool {
+        self.read_only || !self.resets.is_empty()
+    }
+}
@@ -0,0 +1,12 @@
+use crate::structs::article::Article;
+
+/// An article parsed and scored by a sync, ready for the writer of the run to store.
+#[derive(Debug)]
+pub struct PreparedArticle {
+    pub identifier: u32,
+    pub article: Article,
+    pub score: i64,
+    pub dedup_key: Option<u32>,
+    /// Host and path of the links in its content
+    pub links: Vec<(String, String)>,
+}
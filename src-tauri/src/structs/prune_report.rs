@@ -0,0 +1,13 @@
+use serde::Serialize;
+
+/// Outcome of `prune_articles`, sizes being the database file and its write-ahead log.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneReport {
+    pub articles_deleted: u64,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub bytes_reclaimed: u64,
+    /// False when the freed pages are only reused by the next writes, see `commands::retention`
+    pub vacuumed: bool,
+}
@@ -0,0 +1,12 @@
+use serde::Serialize;
+use crate::structs::feed_purge_preview::FeedPurgePreview;
+
+/// Outcome of `preview_purge`, the totals of `feeds`, which has the feeds losing articles only.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgePreview {
+    pub articles: i64,
+    pub unread: i64,
+    pub bytes: i64,
+    pub feeds: Vec<FeedPurgePreview>,
+}
@@ -0,0 +1,9 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedArticle {
+    pub article_identifier: String,
+    pub position: f64,
+    pub added_at: i64,
+}
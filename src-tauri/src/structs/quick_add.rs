@@ -0,0 +1,31 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use crate::structs::add_feed_request::AddFeedRequest;
+
+/// Adds from the tray one at a time, and the add feed dialog they asked for until the main window takes it,
+/// as a window just shown or recreated may not be listening yet.
+#[derive(Default)]
+pub struct QuickAdd {
+    running: AtomicBool,
+    pending: Mutex<Option<AddFeedRequest>>,
+}
+
+impl QuickAdd {
+    /// False when an add is already running.
+    pub fn start(&self) -> bool {
+        !self.running.swap(true, Ordering::SeqCst)
+    }
+
+    pub fn finish(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    /// Replaces the request not taken yet, the user having copied another URL since.
+    pub fn request(&self, request: AddFeedRequest) {
+        *self.pending.lock().unwrap() = Some(request);
+    }
+
+    pub fn take(&self) -> Option<AddFeedRequest> {
+        self.pending.lock().unwrap().take()
+    }
+}
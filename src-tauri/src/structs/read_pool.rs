@@ -0,0 +1,20 @@
+use std::ops::Deref;
+use sqlx::{Pool, Sqlite};
+
+/// Read-only connections for the commands only querying the database, so they never wait for a
+/// connection held by a sync. Every write goes through the `Pool<Sqlite>` state.
+pub struct ReadPool(Pool<Sqlite>);
+
+impl ReadPool {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self(pool)
+    }
+}
+
+impl Deref for ReadPool {
+    type Target = Pool<Sqlite>;
+
+    fn deref(&self) -> &Pool<Sqlite> {
+        &self.0
+    }
+}
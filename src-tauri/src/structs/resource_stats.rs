@@ -0,0 +1,11 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceStats {
+    pub low_resource_mode: bool,
+    pub sync_concurrency: usize,
+    pub recompute_batch_size: i64,
+    /// Unknown outside Linux
+    pub resident_memory_bytes: Option<u64>,
+}
@@ -0,0 +1,13 @@
+use serde::Serialize;
+use crate::structs::idle_status::IdleStatus;
+use crate::structs::power_policy::PowerPolicy;
+use crate::structs::power_status::PowerStatus;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchedulerState {
+    pub power: PowerStatus,
+    pub policy: PowerPolicy,
+    pub full_pass_running: bool,
+    pub idle: IdleStatus,
+}
@@ -0,0 +1,22 @@
+use crate::enums::schema_failure::SchemaFailure;
+use crate::structs::version_mismatch::VersionMismatch;
+
+/// What the startup check found about the database before the migrations ran, and how they went.
+#[derive(Clone, Debug)]
+pub struct SchemaCheck {
+    /// False when the database has migrations this version doesn't know, which the migrator would refuse
+    pub run_migrations: bool,
+    pub mismatch: Option<VersionMismatch>,
+    /// Set when the database couldn't be migrated to the schema of this version
+    pub failure: Option<SchemaFailure>,
+}
+
+impl Default for SchemaCheck {
+    fn default() -> Self {
+        Self {
+            run_migrations: true,
+            mismatch: None,
+            failure: None,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use serde::Serialize;
+use crate::structs::backfill_progress::BackfillProgress;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaInfo {
+    /// Last migration applied
+    pub version: i64,
+    pub backfills: Vec<BackfillProgress>,
+}
@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+use crate::structs::scoring_subject::ScoringSubject;
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct ScorableArticle {
+    pub rowid: i64,
+    pub identifier: String,
+    pub feed_identifier: String,
+    pub title: String,
+    pub content: String,
+    pub language: Option<String>,
+    pub score: i64,
+}
+
+impl ScorableArticle {
+    /// With the folders of its feed, as loaded by `load_feed_folders`.
+    pub fn subject<'a>(&'a self, folders: &'a HashMap<String, Vec<String>>) -> ScoringSubject<'a> {
+        ScoringSubject {
+            feed_identifier: &self.feed_identifier,
+            folders: folders.get(&self.feed_identifier).map(Vec::as_slice).unwrap_or_default(),
+            language: self.language.as_deref(),
+            title: &self.title,
+            content: &self.content,
+        }
+    }
+}
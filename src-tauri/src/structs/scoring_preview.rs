@@ -0,0 +1,9 @@
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoringPreview {
+    pub identifier: String,
+    pub feed_identifier: String,
+    pub title: String,
+    pub current_score: i64,
+    pub new_score: i64,
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use crate::enums::scoring_condition::ScoringCondition;
+
+/// A rule adding `weight` to the score of the articles matching all of its conditions.
+#[derive(Clone, Debug, Deserialize, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoringRule {
+    pub identifier: String,
+    pub conditions: Json<Vec<ScoringCondition>>,
+    pub weight: i64,
+}
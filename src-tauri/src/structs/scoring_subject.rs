@@ -0,0 +1,10 @@
+/// What the conditions of the scoring rules are evaluated against, the stored article or the one being synced.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ScoringSubject<'a> {
+    pub feed_identifier: &'a str,
+    /// The folder of the feed then its ancestors, see `load_feed_folders`
+    pub folders: &'a [String],
+    pub language: Option<&'a str>,
+    pub title: &'a str,
+    pub content: &'a str,
+}
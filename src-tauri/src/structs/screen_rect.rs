@@ -0,0 +1,15 @@
+/// A window or monitor area, in physical pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScreenRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ScreenRect {
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x as f64 && x < self.x as f64 + self.width as f64
+            && y >= self.y as f64 && y < self.y as f64 + self.height as f64
+    }
+}
@@ -0,0 +1,15 @@
+use serde::Serialize;
+use crate::enums::search_term::SearchTerm;
+
+/// Terms joined by OR, the clauses of a query being joined by AND.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchClause {
+    pub negated: bool,
+    pub terms: Vec<SearchTerm>,
+    /// Byte offsets in the query, to point at the clause when it can't be compiled
+    #[serde(skip)]
+    pub start: usize,
+    #[serde(skip)]
+    pub end: usize,
+}
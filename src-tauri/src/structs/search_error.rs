@@ -0,0 +1,24 @@
+use serde::Serialize;
+
+/// Points at the part of a search query that can't be understood.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchError {
+    pub message: String,
+    pub token: String,
+    /// UTF-16 offsets, as string indexes are counted in the frontend
+    pub start: usize,
+    pub end: usize,
+}
+
+impl SearchError {
+    /// Takes byte offsets in `query`.
+    pub fn new(query: &str, start: usize, end: usize, message: &str) -> Self {
+        SearchError {
+            message: message.to_string(),
+            token: query[start..end].to_string(),
+            start: query[..start].encode_utf16().count(),
+            end: query[..end].encode_utf16().count(),
+        }
+    }
+}
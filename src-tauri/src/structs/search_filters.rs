@@ -0,0 +1,11 @@
+/// A search query compiled into the parameters of the search SQL query.
+#[derive(Debug, Default)]
+pub struct SearchFilters {
+    /// FTS5 MATCH expression, every user-provided text being quoted
+    pub text: Option<String>,
+    pub feeds: Option<Vec<String>>,
+    pub excluded_feeds: Vec<String>,
+    pub read: Option<bool>,
+    pub before: Option<i64>,
+    pub after: Option<i64>,
+}
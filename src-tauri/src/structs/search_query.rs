@@ -0,0 +1,8 @@
+use serde::Serialize;
+use crate::structs::search_clause::SearchClause;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    pub clauses: Vec<SearchClause>,
+}
@@ -0,0 +1,12 @@
+use serde::Serialize;
+use crate::enums::check_status::CheckStatus;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestCheck {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    /// One line, shown as is
+    pub detail: String,
+    pub duration_ms: u64,
+}
@@ -0,0 +1,20 @@
+use std::path::PathBuf;
+use sqlx::{Pool, Sqlite};
+use crate::enums::tray_status::TrayStatus;
+use crate::structs::background_sync_status::BackgroundSyncStatus;
+
+/// What the self-test checks, taken from the running app or opened just for `--self-test`.
+pub struct SelfTestEnvironment {
+    /// Why it couldn't be opened otherwise
+    pub pool: Result<Pool<Sqlite>, String>,
+    /// Why writes are refused, when they are
+    pub read_only: Option<String>,
+    /// Whether the migrations of this version should be applied by now, false before the app started
+    pub migrated: bool,
+    /// Where the database and the preferences are written
+    pub data_dirs: Vec<PathBuf>,
+    /// Probed again by the self-test when unknown
+    pub tray: TrayStatus,
+    /// None without the running app
+    pub scheduler: Option<BackgroundSyncStatus>,
+}
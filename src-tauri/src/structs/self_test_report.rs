@@ -0,0 +1,35 @@
+use serde::Serialize;
+use crate::enums::check_status::CheckStatus;
+use crate::structs::self_test_check::SelfTestCheck;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestReport {
+    /// The worst status among the checks
+    pub verdict: CheckStatus,
+    pub checks: Vec<SelfTestCheck>,
+    pub duration_ms: u64,
+}
+
+impl SelfTestReport {
+    pub fn new(checks: Vec<SelfTestCheck>, duration_ms: u64) -> Self {
+        let verdict = checks.iter().map(|check| check.status).max().unwrap_or(CheckStatus::Pass).max(CheckStatus::Pass);
+        SelfTestReport { verdict, checks, duration_ms }
+    }
+
+    /// One line for a notification, naming the failed checks first.
+    pub fn summary(&self) -> String {
+        let named = |status: CheckStatus| self.checks.iter()
+            .filter(|check| check.status == status)
+            .map(|check| check.name)
+            .collect::<Vec<&str>>();
+        let (failed, warned) = (named(CheckStatus::Fail), named(CheckStatus::Warn));
+
+        match (failed.is_empty(), warned.is_empty()) {
+            (true, true) => format!("Diagnostics passed, {} checks", self.checks.len()),
+            (true, false) => format!("Diagnostics passed with warnings: {}", warned.join(", ")),
+            (false, true) => format!("Diagnostics failed: {}", failed.join(", ")),
+            (false, false) => format!("Diagnostics failed: {}, with warnings: {}", failed.join(", "), warned.join(", ")),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionState {
+    pub feed_identifier: Option<String>,
+    pub article_identifier: Option<String>,
+    /// Identifier of the article at the top of the list viewport
+    pub scroll_anchor: Option<String>,
+    /// Scroll position of the reader, between 0 and 1
+    pub reader_scroll: Option<f64>,
+}
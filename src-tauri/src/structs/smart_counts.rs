@@ -0,0 +1,10 @@
+use crate::structs::feed_article_count::FeedArticleCount;
+
+/// Counts of the sidebar entries that aren't feeds or folders.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartCounts {
+    pub updated_since_read: i64,
+    /// Only the feeds having some
+    pub updated_since_read_by_feed: Vec<FeedArticleCount>,
+}
@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+/// A retention and filter to project with `project_storage` or `preview_purge` before saving them. Like in
+/// the preferences, 0 turns a retention limit off, and the feeds with their own retention keep it.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StoragePolicy {
+    pub retention_days: u32,
+    pub retention_max_articles: u32,
+    /// Unread articles scored below it aren't counted, like in a view filtered by score
+    pub min_score: Option<i64>,
+}
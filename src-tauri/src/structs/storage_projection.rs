@@ -0,0 +1,17 @@
+use serde::Serialize;
+use crate::structs::storage_projection_point::StorageProjectionPoint;
+
+/// Outcome of `project_storage`. Every number is an estimate, extrapolated from the last `measured_days`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageProjection {
+    pub measured_days: u32,
+    pub horizon_days: u32,
+    pub database_bytes: u64,
+    /// Deleted by the first prune with the candidate retention
+    pub pruned_articles: u64,
+    pub pruned_unread: u64,
+    pub reclaimed_bytes: u64,
+    /// One per month, starting with today after that first prune
+    pub months: Vec<StorageProjectionPoint>,
+}
@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+/// The estimated state of the database `day` days from now, the first month of `project_storage` onwards.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageProjectionPoint {
+    pub month: u32,
+    pub day: u32,
+    pub articles: u64,
+    pub unread: u64,
+    pub database_bytes: u64,
+}
@@ -0,0 +1,36 @@
+/// An article as stored in the database, as opposed to `Article` which comes straight from a feed.
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredArticle {
+    pub identifier: String,
+    pub feed_identifier: String,
+    pub title: String,
+    /// As the feed sent it when it differs from the title, only selected by get_articles
+    #[sqlx(default)]
+    pub original_title: Option<String>,
+    pub content: String,
+    pub date: i64,
+    pub read: bool,
+    pub image_url: Option<String>,
+    pub score: i64,
+    /// Only selected by get_articles
+    #[sqlx(default)]
+    pub first_seen: Option<i64>,
+    /// Published long before it was first seen, i.e. from the archive of a newly added feed
+    #[sqlx(default)]
+    pub backfilled: bool,
+    /// In the reading queue, only selected by get_articles
+    #[sqlx(default)]
+    pub queued: bool,
+    /// When it was last read, kept once marked unread again. Only selected by get_articles
+    #[sqlx(default)]
+    pub read_at: Option<i64>,
+    /// When a sync last changed its title or content, only selected by get_articles
+    #[sqlx(default)]
+    pub content_updated_at: Option<i64>,
+    /// Updated since read in a feed asking to reread updates, so counted as unread
+    #[sqlx(default)]
+    pub reread_requested: bool,
+    #[sqlx(default)]
+    pub updated_since_read: bool,
+}
@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncOutcome {
+    /// Set when the server answered that the feed didn't change, nothing being parsed then
+    pub not_modified: bool,
+    /// Stored for the first time
+    pub new_articles: i64,
+    /// About the feed, they don't prevent the sync
+    pub warnings: Vec<String>,
+}
@@ -0,0 +1,12 @@
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncProgress {
+    pub identifier: String,
+    pub current: usize,
+    pub total: usize,
+    pub eta_ms: u64,
+    pub not_modified: bool,
+    pub warnings: Vec<String>,
+    /// Set when the feed failed to sync
+    pub error: Option<String>,
+}
@@ -0,0 +1,14 @@
+use serde::Serialize;
+use crate::structs::sync_failure::SyncFailure;
+
+/// Outcome of a `sync_all` run, each feed having succeeded or failed on its own.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    pub updated: Vec<String>,
+    /// Answered with a 304
+    pub not_modified: Vec<String>,
+    pub failed: Vec<SyncFailure>,
+    /// Over all the feeds
+    pub new_articles: i64,
+}
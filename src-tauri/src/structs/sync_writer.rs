@@ -0,0 +1,36 @@
+use sqlx::{Pool, Sqlite};
+use tokio::sync::{mpsc, oneshot};
+use crate::database::article_writer;
+use crate::enums::feed_write::FeedWrite;
+use crate::structs::feed_stream::FeedStream;
+use crate::structs::write_gate::WriteGate;
+
+/// Batches a feed holds in the channel to the writer, so a fast parse doesn't pile up articles in memory.
+const QUEUED_BATCHES: usize = 4;
+
+/// The writer of a sync run, storing the parsed articles of its feeds while the next ones are parsed.
+/// Feeds are written one after the other, each in its own transaction, in the order they were started.
+/// The writer stops once every clone of this handle is dropped.
+#[derive(Clone)]
+pub struct SyncWriter {
+    streams: mpsc::UnboundedSender<FeedStream>,
+}
+
+impl SyncWriter {
+    /// Every feed is written through `gate`, the managed one of the app.
+    pub fn start(pool: Pool<Sqlite>, gate: WriteGate) -> Self {
+        let (streams, receiver) = mpsc::unbounded_channel();
+        tauri::async_runtime::spawn(article_writer::run(receiver, pool, gate));
+        SyncWriter { streams }
+    }
+
+    /// Queues a feed for writing. Its articles are stored once `FeedWrite::Finish` is sent, and rolled
+    /// back if the sender is dropped before.
+    pub fn feed(&self, feed_identifier: &str) -> (mpsc::Sender<FeedWrite>, oneshot::Receiver<Result<i64, String>>) {
+        let (sender, writes) = mpsc::channel(QUEUED_BATCHES);
+        let (done, result) = oneshot::channel();
+        // A stopped writer drops `done`, which the sync sees as a failure
+        let _ = self.streams.send(FeedStream { feed_identifier: feed_identifier.to_string(), writes, done });
+        (sender, result)
+    }
+}
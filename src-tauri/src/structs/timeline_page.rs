@@ -0,0 +1,17 @@
+use serde::Serialize;
+use crate::structs::article_cursor::ArticleCursor;
+use crate::structs::stored_article::StoredArticle;
+
+/// A page of `get_timeline`, newest article first whichever way it was loaded. The frontend only keeps a few
+/// pages around the position, the cursors loading the next ones from the database again in either direction.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelinePage {
+    pub articles: Vec<StoredArticle>,
+    /// The page size actually used, which may be lower than requested
+    pub limit: i64,
+    /// The oldest article of the page, none when the page reaches the oldest matching one
+    pub older_cursor: Option<ArticleCursor>,
+    /// The newest article of the page, none when the page reaches the newest matching one
+    pub newer_cursor: Option<ArticleCursor>,
+}
@@ -0,0 +1,14 @@
+use serde::Serialize;
+use sqlx::types::Json;
+use crate::enums::transcript_format::TranscriptFormat;
+use crate::structs::transcript_segment::TranscriptSegment;
+
+/// A downloaded transcript, as timed segments for VTT and SRT, as the raw document otherwise.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Transcript {
+    pub format: TranscriptFormat,
+    pub mime_type: Option<String>,
+    pub segments: Option<Json<Vec<TranscriptSegment>>>,
+    pub raw: Option<String>,
+}
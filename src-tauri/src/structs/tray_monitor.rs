@@ -0,0 +1,31 @@
+use std::sync::Mutex;
+use crate::enums::tray_status::TrayStatus;
+
+/// Last known status of the tray, kept up to date by the tray watch, and where its icon was last clicked.
+#[derive(Default)]
+pub struct TrayMonitor {
+    status: Mutex<TrayStatus>,
+    last_click: Mutex<Option<(f64, f64)>>,
+}
+
+impl TrayMonitor {
+    pub fn status(&self) -> TrayStatus {
+        *self.status.lock().unwrap()
+    }
+
+    /// Returns the previous status when it changed.
+    pub fn update(&self, status: TrayStatus) -> Option<TrayStatus> {
+        let mut current = self.status.lock().unwrap();
+        let previous = std::mem::replace(&mut *current, status);
+        (previous != status).then_some(previous)
+    }
+
+    pub fn record_click(&self, x: f64, y: f64) {
+        *self.last_click.lock().unwrap() = Some((x, y));
+    }
+
+    /// None on platforms without tray click events, like Linux.
+    pub fn last_click(&self) -> Option<(f64, f64)> {
+        *self.last_click.lock().unwrap()
+    }
+}
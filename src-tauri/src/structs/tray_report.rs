@@ -0,0 +1,10 @@
+use serde::Serialize;
+use crate::enums::tray_status::TrayStatus;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayReport {
+    pub status: TrayStatus,
+    /// Whether the close button hides the window, given the status and the close behavior preference
+    pub close_to_tray: bool,
+}
@@ -0,0 +1,16 @@
+use serde::Serialize;
+
+/// The daily time range a feed is known to be down, in local minutes since midnight.
+/// It may wrap around midnight.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct UnavailableWindow {
+    pub start_minute: u32,
+    pub minutes: u32,
+}
+
+impl UnavailableWindow {
+    pub fn contains(&self, minute: u32) -> bool {
+        (minute + 24 * 60 - self.start_minute) % (24 * 60) < self.minutes
+    }
+}
@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+/// A bookmarked site no feed was found on.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndiscoveredSite {
+    pub title: String,
+    pub url: String,
+    pub folder: Vec<String>,
+    /// Set when the site couldn't be fetched, rather than advertising no feed
+    pub error: Option<String>,
+}
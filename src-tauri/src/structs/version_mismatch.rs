@@ -0,0 +1,14 @@
+use serde::Serialize;
+
+/// The database was upgraded by a newer version of the app, with changes this one can't cope with.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionMismatch {
+    pub app_version: String,
+    /// None until the version that upgraded the database started once after the upgrade
+    pub minimum_app_version: Option<String>,
+    pub schema_version: i64,
+    pub supported_schema_version: i64,
+    /// The latest backup made before an upgrade this version can open, to restore instead of staying read-only
+    pub backup_path: Option<String>,
+}
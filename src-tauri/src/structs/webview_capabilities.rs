@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+
+/// Image and audio MIME types the system webview can decode, as reported by the frontend.
+#[derive(Clone, Debug, Deserialize, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct WebviewCapabilities {
+    pub image_types: Json<Vec<String>>,
+    pub audio_types: Json<Vec<String>>,
+    #[serde(default)]
+    pub probed_at: i64,
+}
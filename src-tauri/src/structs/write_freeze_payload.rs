@@ -0,0 +1,6 @@
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteFreezePayload {
+    pub frozen: bool,
+    pub reason: Option<String>,
+}
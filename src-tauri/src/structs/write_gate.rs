@@ -0,0 +1,108 @@
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::Notify;
+use crate::structs::write_guard::WriteGuard;
+
+#[derive(Default)]
+struct GateState {
+    reason: Option<String>,
+    generation: u64,
+}
+
+/// Lets the user pause every backend write to the database, e.g. while an external tool backs up the data dir.
+/// Clones share the same gate, for the tasks writing outside of a command like the article writer.
+#[derive(Clone, Default)]
+pub struct WriteGate {
+    state: Arc<Mutex<GateState>>,
+    /// Writes running, each holding a `WriteGuard`
+    writers: Arc<AtomicUsize>,
+    idle: Arc<Notify>,
+    thawed: Arc<Notify>,
+    /// Set for the whole run when the database can't be written by this version of the app, unlike freezes
+    read_only: Option<String>,
+}
+
+impl WriteGate {
+    pub fn read_only(reason: String) -> Self {
+        Self { read_only: Some(reason), ..Self::default() }
+    }
+
+    /// Returns the generation of this freeze, so an auto-thaw only ends the freeze that scheduled it.
+    /// The writes already running go on, `wait_for_writers` tells when they are done.
+    pub fn freeze(&self, reason: String) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        state.generation += 1;
+        state.reason = Some(reason);
+        state.generation
+    }
+
+    /// Returns whether writes were frozen.
+    pub fn thaw(&self) -> bool {
+        let thawed = self.state.lock().unwrap().reason.take().is_some();
+        self.thawed.notify_waiters();
+        thawed
+    }
+
+    pub fn thaw_generation(&self, generation: u64) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let thawed = state.generation == generation && state.reason.take().is_some();
+        if thawed {
+            self.thawed.notify_waiters();
+        }
+        thawed
+    }
+
+    /// Whether writes may start, for the tasks deciding whether to run at all. What writes holds `enter`
+    /// instead, so a freeze waits for it.
+    pub fn check(&self) -> Result<(), String> {
+        if let Some(reason) = &self.read_only {
+            return Err(format!("WritesFrozen: {}", reason));
+        }
+
+        match &self.state.lock().unwrap().reason {
+            Some(reason) => Err(format!("WritesFrozen: {}", reason)),
+            None => Ok(()),
+        }
+    }
+
+    /// To be held by every write to the database from before its first statement until it commits.
+    pub fn enter(&self) -> Result<WriteGuard, String> {
+        if let Some(reason) = &self.read_only {
+            return Err(format!("WritesFrozen: {}", reason));
+        }
+
+        // Counted under the lock, so a freeze either refuses the write or waits for it
+        let state = self.state.lock().unwrap();
+        if let Some(reason) = &state.reason {
+            return Err(format!("WritesFrozen: {}", reason));
+        }
+        self.writers.fetch_add(1, Ordering::SeqCst);
+        Ok(WriteGuard::new(self.writers.clone(), self.idle.clone()))
+    }
+
+    /// Same as `enter`, waiting for a freeze to end instead of failing, for the background work that can
+    /// resume later. Only fails when the database is read-only.
+    pub async fn enter_when_thawed(&self) -> Result<WriteGuard, String> {
+        loop {
+            // Created before entering, so a thaw in between still wakes it
+            let thawed = self.thawed.notified();
+            match self.enter() {
+                Ok(guard) => return Ok(guard),
+                Err(e) if self.read_only.is_some() => return Err(e),
+                Err(_) => thawed.await,
+            }
+        }
+    }
+
+    /// Waits until every write that entered the gate is done.
+    pub async fn wait_for_writers(&self) {
+        loop {
+            // Created before the count is read, so the last writer leaving in between still wakes it
+            let idle = self.idle.notified();
+            if self.writers.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::Notify;
+
+/// A write running through the `WriteGate`, which freezes wait for until it is dropped.
+#[must_use = "the write is only waited for while the guard is held"]
+pub struct WriteGuard {
+    writers: Arc<AtomicUsize>,
+    idle: Arc<Notify>,
+}
+
+impl WriteGuard {
+    pub fn new(writers: Arc<AtomicUsize>, idle: Arc<Notify>) -> Self {
+        WriteGuard { writers, idle }
+    }
+}
+
+impl Drop for WriteGuard {
+    fn drop(&mut self) {
+        if self.writers.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.idle.notify_waiters();
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use crate::structs::autostart_registration::AutostartRegistration;
+use crate::structs::preferences::Preferences;
+
+const DELAY_ARG: &str = "--autostart-delay=";
+
+/// Arguments the login item launches the app with.
+pub fn launch_args(preferences: &Preferences) -> Vec<String> {
+    let mut args = vec![String::from("--autostart")];
+    if preferences.autostart_delay_seconds > 0 {
+        args.push(format!("{}{}", DELAY_ARG, preferences.autostart_delay_seconds));
+    }
+    args
+}
+
+/// Whether the login item must be registered again. Registrations that can't be read are stale, the login
+/// item may have been made by a version that didn't record it.
+pub fn is_stale(registered: Option<&AutostartRegistration>, expected: &AutostartRegistration) -> bool {
+    registered != Some(expected)
+}
+
+pub fn load_registration(path: &Path) -> Option<AutostartRegistration> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn save_registration(path: &Path, registration: &AutostartRegistration) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Error saving autostart: {}", e))?;
+    }
+    let content = serde_json::to_string(registration).map_err(|e| format!("Error serializing autostart: {}", e))?;
+    fs::write(path, content).map_err(|e| format!("Error saving autostart: {}", e))
+}
+
+/// Reads `--autostart-delay=<seconds>`, only honored along with `--autostart`.
+pub fn start_delay(args: &[String]) -> Option<Duration> {
+    if !args.iter().any(|arg| arg == "--autostart" || arg == "-a") {
+        return None;
+    }
+
+    args.iter()
+        .find_map(|arg| arg.strip_prefix(DELAY_ARG))
+        .and_then(|seconds| seconds.parse::<u64>().ok())
+        .filter(|seconds| *seconds > 0)
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registration(path: &str, args: &[&str]) -> AutostartRegistration {
+        AutostartRegistration { path: path.to_string(), args: args.iter().map(|arg| arg.to_string()).collect() }
+    }
+
+    #[test]
+    fn launch_args_carry_the_delay() {
+        let preferences = Preferences { autostart_delay_seconds: 30, ..Preferences::default() };
+        assert_eq!(launch_args(&Preferences::default()), ["--autostart"]);
+        assert_eq!(launch_args(&preferences), ["--autostart", "--autostart-delay=30"]);
+    }
+
+    #[test]
+    fn detects_stale_registrations() {
+        let expected = registration("/opt/alduin/2.0/alduin", &["--autostart", "--autostart-delay=30"]);
+        let cases = [
+            (None, true),
+            (Some(registration("/opt/alduin/2.0/alduin", &["--autostart", "--autostart-delay=30"])), false),
+            // Moved by an update
+            (Some(registration("/opt/alduin/1.9/alduin", &["--autostart", "--autostart-delay=30"])), true),
+            // Registered before the delay preference changed
+            (Some(registration("/opt/alduin/2.0/alduin", &["--autostart"])), true),
+            (Some(registration("/opt/alduin/2.0/alduin", &["--autostart-delay=30", "--autostart"])), true),
+        ];
+
+        for (registered, stale) in cases {
+            assert_eq!(is_stale(registered.as_ref(), &expected), stale, "{:?}", registered);
+        }
+    }
+
+    #[test]
+    fn repairs_the_recorded_registration() {
+        let dir = std::env::temp_dir().join(format!("alduin-autostart-{}", std::process::id()));
+        let path = dir.join("data").join("autostart.json");
+        let expected = registration("/opt/alduin/2.0/alduin", &["--autostart"]);
+
+        // Never recorded, then left by the previous version, then unreadable
+        assert!(is_stale(load_registration(&path).as_ref(), &expected));
+        save_registration(&path, &registration("/opt/alduin/1.9/alduin", &["--autostart"])).unwrap();
+        assert!(is_stale(load_registration(&path).as_ref(), &expected));
+        fs::write(&path, "{\"path\": ").unwrap();
+        assert!(is_stale(load_registration(&path).as_ref(), &expected));
+
+        save_registration(&path, &expected).unwrap();
+        assert!(!is_stale(load_registration(&path).as_ref(), &expected));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn delays_only_autostart_launches() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>();
+        let cases = [
+            (args(&["alduin", "--autostart", "--autostart-delay=30"]), Some(Duration::from_secs(30))),
+            (args(&["alduin", "-a", "--autostart-delay=5"]), Some(Duration::from_secs(5))),
+            (args(&["alduin", "--autostart-delay=30"]), None),
+            (args(&["alduin", "--autostart", "--autostart-delay=0"]), None),
+            (args(&["alduin", "--autostart", "--autostart-delay=soon"]), None),
+            (args(&["alduin", "--autostart"]), None),
+        ];
+
+        for (args, delay) in cases {
+            assert_eq!(start_delay(&args), delay, "{:?}", args);
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! Cancels a future at one of its await points, for the tests of the writes that must stay whole when the
+//! task running them stops midway.
+
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::task::Poll;
+
+/// Polls `future` until it's done or was left pending `polls` times, then drops it at the await point it
+/// was pending at. None when it was dropped.
+pub async fn cancel_after<F: Future>(polls: u64, future: F) -> Option<F::Output> {
+    let mut future = pin!(future);
+    let mut pending = 0;
+
+    poll_fn(|context| match future.as_mut().poll(context) {
+        Poll::Ready(output) => Poll::Ready(Some(output)),
+        Poll::Pending if pending >= polls => Poll::Ready(None),
+        Poll::Pending => {
+            pending += 1;
+            Poll::Pending
+        }
+    }).await
+}
+
+/// How many times `future` was left pending before it was done, its await points crossed.
+pub async fn count_pending<F: Future>(future: F) -> (u64, F::Output) {
+    let mut future = pin!(future);
+    let mut pending = 0;
+
+    poll_fn(|context| match future.as_mut().poll(context) {
+        Poll::Ready(output) => Poll::Ready((pending, output)),
+        Poll::Pending => {
+            pending += 1;
+            Poll::Pending
+        }
+    }).await
+}
@@ -0,0 +1,119 @@
+use crate::structs::webview_capabilities::WebviewCapabilities;
+
+/// Feeds often use legacy or generic MIME types, the extension being more telling for the latter.
+const EXTENSION_TYPES: [(&str, &str); 12] = [
+    ("mp3", "audio/mpeg"),
+    ("m4a", "audio/mp4"),
+    ("m4b", "audio/mp4"),
+    ("aac", "audio/aac"),
+    ("ogg", "audio/ogg"),
+    ("oga", "audio/ogg"),
+    ("opus", "audio/opus"),
+    ("flac", "audio/flac"),
+    ("wav", "audio/wav"),
+    ("weba", "audio/webm"),
+    ("webp", "image/webp"),
+    ("avif", "image/avif"),
+];
+
+/// Whether the webview can play or display an enclosure, None when it can't be told from its type or extension.
+pub fn is_playable(capabilities: &WebviewCapabilities, mime_type: Option<&str>, url: &str) -> Option<bool> {
+    let mime_type = mime_type.map(normalize_mime_type)
+        .filter(|mime_type| mime_type != "application/octet-stream")
+        .or_else(|| extension_type(url).map(String::from))?;
+
+    if mime_type.starts_with("audio/") {
+        Some(capabilities.audio_types.contains(&mime_type))
+    } else if mime_type.starts_with("image/") {
+        Some(capabilities.image_types.contains(&mime_type))
+    } else {
+        None
+    }
+}
+
+/// Lowercases the type and drops its parameters, mapping the common aliases to the type probed for.
+fn normalize_mime_type(mime_type: &str) -> String {
+    let essence = mime_type.split(';').next().unwrap_or_default().trim().to_lowercase();
+
+    match essence.as_str() {
+        "audio/mp3" | "audio/x-mp3" | "audio/mpeg3" | "audio/x-mpeg" => String::from("audio/mpeg"),
+        "audio/x-m4a" | "audio/m4a" | "audio/x-m4b" => String::from("audio/mp4"),
+        "audio/x-wav" | "audio/wave" | "audio/vnd.wave" => String::from("audio/wav"),
+        "audio/x-flac" => String::from("audio/flac"),
+        "audio/x-aac" => String::from("audio/aac"),
+        "audio/vorbis" | "application/ogg" => String::from("audio/ogg"),
+        "image/jpg" | "image/pjpeg" => String::from("image/jpeg"),
+        _ => essence,
+    }
+}
+
+fn extension_type(url: &str) -> Option<&'static str> {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let extension = path.rsplit_once('.')?.1.to_lowercase();
+
+    EXTENSION_TYPES.iter()
+        .find(|(candidate, _)| *candidate == extension)
+        .map(|(_, mime_type)| *mime_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::types::Json;
+    use super::*;
+
+    fn profile(image_types: &[&str], audio_types: &[&str]) -> WebviewCapabilities {
+        WebviewCapabilities {
+            image_types: Json(image_types.iter().map(|mime_type| mime_type.to_string()).collect()),
+            audio_types: Json(audio_types.iter().map(|mime_type| mime_type.to_string()).collect()),
+            probed_at: 0,
+        }
+    }
+
+    #[test]
+    fn adapts_to_each_platform() {
+        // WebView2, an older WebKitGTK and WKWebView, as their probes report them
+        let webview2 = profile(
+            &["image/jpeg", "image/png", "image/gif", "image/webp", "image/avif"],
+            &["audio/mpeg", "audio/mp4", "audio/aac", "audio/ogg", "audio/opus", "audio/flac", "audio/wav", "audio/webm"],
+        );
+        let webkitgtk = profile(
+            &["image/jpeg", "image/png", "image/gif"],
+            &["audio/mpeg", "audio/ogg", "audio/opus", "audio/flac", "audio/wav"],
+        );
+        let wkwebview = profile(
+            &["image/jpeg", "image/png", "image/gif", "image/webp"],
+            &["audio/mpeg", "audio/mp4", "audio/aac", "audio/flac", "audio/wav"],
+        );
+
+        // As (MIME type, URL, playable by WebView2, WebKitGTK and WKWebView)
+        let cases = [
+            (Some("audio/mpeg"), "https://a.example/1.mp3", [Some(true), Some(true), Some(true)]),
+            (Some("audio/x-m4a"), "https://a.example/1", [Some(true), Some(false), Some(true)]),
+            (Some("application/ogg"), "https://a.example/1", [Some(true), Some(true), Some(false)]),
+            (Some("audio/opus; codecs=opus"), "https://a.example/1.opus", [Some(true), Some(true), Some(false)]),
+            (Some("image/webp"), "https://a.example/1.webp", [Some(true), Some(false), Some(true)]),
+            (Some("image/avif"), "https://a.example/1.avif", [Some(true), Some(false), Some(false)]),
+            (Some("IMAGE/JPG"), "https://a.example/1", [Some(true), Some(true), Some(true)]),
+            // Told by the extension
+            (None, "https://a.example/episode.M4B?token=1#t=10", [Some(true), Some(false), Some(true)]),
+            (Some("application/octet-stream"), "https://a.example/1.weba", [Some(true), Some(false), Some(false)]),
+            // Can't be told, or not media
+            (None, "https://a.example/episode", [None, None, None]),
+            (Some("application/octet-stream"), "https://a.example/1.bin", [None, None, None]),
+            (Some("video/mp4"), "https://a.example/1.mp4", [None, None, None]),
+            (Some("application/pdf"), "https://a.example/1.pdf", [None, None, None]),
+        ];
+
+        for (mime_type, url, playable) in cases {
+            let adapted = [&webview2, &webkitgtk, &wkwebview].map(|capabilities| is_playable(capabilities, mime_type, url));
+            assert_eq!(adapted, playable, "{:?} {}", mime_type, url);
+        }
+    }
+
+    #[test]
+    fn unprobed_types_are_unplayable() {
+        let nothing = profile(&[], &[]);
+        assert_eq!(is_playable(&nothing, Some("audio/mpeg"), ""), Some(false));
+        assert_eq!(is_playable(&nothing, Some("image/png"), ""), Some(false));
+    }
+}
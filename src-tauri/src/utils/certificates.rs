@@ -0,0 +1,195 @@
+use std::error::Error;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::fs;
+use chrono::{DateTime, Utc};
+use p12_keystore::{KeyStore, KeyStoreEntry};
+use reqwest::{Certificate, Identity};
+use x509_parser::certificate::X509Certificate;
+use x509_parser::pem::parse_x509_pem;
+use x509_parser::prelude::FromDer;
+use crate::structs::certificate_info::CertificateInfo;
+use crate::structs::feed_tls::FeedTls;
+
+/// Alerts of a server rejecting the client certificate, or asking for one, during the handshake.
+const REJECTION_ALERTS: [&str; 7] = ["alert bad certificate", "alert certificate required", "alert unknown ca", "alert certificate expired", "alert certificate revoked", "alert certificate unknown", "alert access denied"];
+/// A TLS 1.3 server checks the client certificate once the handshake is over, the client only seeing the
+/// connection close before its request is answered.
+const CLOSED_AFTER_HANDSHAKE: [&str; 2] = ["channel closed", "connection closed before message completed"];
+/// How the TLS backends of each platform say they don't trust the certificate of the server.
+const UNTRUSTED_SERVER: [&str; 6] = ["certificate verify failed", "self signed certificate", "self-signed certificate", "unable to get local issuer certificate", "not trusted", "untrustedroot"];
+
+/// Reads a PKCS#12 file holding the client certificate and its private key. Decoded here rather than by
+/// the TLS backend, which only tells whether it could load it and refuses the legacy encryptions of older
+/// exports on some platforms.
+pub fn read_client_certificate(path: &str, passphrase: &str) -> Result<(Identity, CertificateInfo), String> {
+    let der = fs::read(path).map_err(|e| format!("CertificateInvalid: error reading {}: {}", path, e))?;
+    let store = KeyStore::from_pkcs12(&der, passphrase)
+        .map_err(|_| format!("CertificateInvalid: {} isn't a PKCS#12 file, or its passphrase is wrong", path))?;
+    let chain = store.entries()
+        .find_map(|(_, entry)| match entry {
+            KeyStoreEntry::PrivateKeyChain(chain) => Some(chain),
+            _ => None,
+        })
+        .filter(|chain| !chain.chain().is_empty())
+        .ok_or_else(|| format!("CertificateInvalid: {} holds no certificate with its private key", path))?;
+    let info = certificate_info(chain.chain()[0].as_der(), path)?;
+
+    let certificates: String = chain.chain().iter().map(|certificate| pem("CERTIFICATE", certificate.as_der())).collect();
+    let identity = Identity::from_pkcs8_pem(certificates.as_bytes(), pem("PRIVATE KEY", chain.key()).as_bytes())
+        .map_err(|e| format!("CertificateInvalid: {}: {}", path, e))?;
+    Ok((identity, info))
+}
+
+/// Reads a CA certificate, PEM or DER encoded.
+pub fn read_ca_certificate(path: &str) -> Result<(Certificate, CertificateInfo), String> {
+    let content = fs::read(path).map_err(|e| format!("CertificateInvalid: error reading {}: {}", path, e))?;
+    let der = match parse_x509_pem(&content) {
+        Ok((_, pem)) if pem.label == "CERTIFICATE" => pem.contents,
+        _ => content,
+    };
+    let info = certificate_info(&der, path)
+        .map_err(|_| format!("CertificateInvalid: {} isn't a PEM or DER certificate", path))?;
+
+    let ca = Certificate::from_der(&der).map_err(|e| format!("CertificateInvalid: {}: {}", path, e))?;
+    Ok((ca, info))
+}
+
+/// Checked before connecting, the error of a server refusing an expired certificate rarely says so.
+pub fn expired_error(tls: &FeedTls) -> Option<String> {
+    tls.client_certificate()
+        .filter(|info| info.expires_at < Utc::now().timestamp_millis())
+        .map(|info| format!("ClientCertificateExpired: the client certificate {} expired on {}", info.subject, date(info.expires_at)))
+}
+
+/// The certificate errors of a failed request, told apart by the messages of the TLS backend. None for
+/// other errors, or certificate errors this doesn't recognize.
+pub fn certificate_error(e: &reqwest::Error, tls: Option<&FeedTls>) -> Option<String> {
+    let mut messages: Vec<String> = vec![];
+    let mut source: Option<&dyn Error> = Some(e);
+    while let Some(error) = source {
+        messages.push(error.to_string().to_lowercase());
+        source = error.source();
+    }
+    let chain = messages.join(" ");
+    // The first message is the one of reqwest, repeating the URL
+    let detail = messages.get(1).or(messages.last())
+        .map(|message| message.trim_start_matches("error trying to connect: ").to_string())
+        .unwrap_or_default();
+    let client_certificate = tls.and_then(FeedTls::client_certificate);
+
+    // Without a certificate, a failed handshake or a closed connection is as likely to come from anything else
+    let rejected = REJECTION_ALERTS.iter().any(|alert| chain.contains(alert))
+        || (client_certificate.is_some() && chain.contains("alert handshake failure"))
+        || (client_certificate.is_some() && CLOSED_AFTER_HANDSHAKE.iter().any(|message| chain.contains(message)));
+    if rejected {
+        return Some(match client_certificate {
+            Some(info) => format!("ClientCertificateRejected: the site rejected the client certificate {}, valid until {} ({})", info.subject, date(info.expires_at), detail),
+            None => format!("ClientCertificateRejected: the site asks for a client certificate, set one in the settings of the feed ({})", detail),
+        });
+    }
+
+    if UNTRUSTED_SERVER.iter().any(|message| chain.contains(message)) {
+        return Some(match tls.and_then(FeedTls::ca_certificate) {
+            Some(ca) => format!("ServerCertificateUntrusted: the certificate of the site is signed neither by {} nor by a trusted authority ({})", ca.subject, detail),
+            None => format!("ServerCertificateUntrusted: the certificate of the site isn't signed by a trusted authority, its CA can be added in the settings of the feed ({})", detail),
+        });
+    }
+
+    None
+}
+
+/// Names like `O=Homelab, CN=alduin`.
+fn certificate_info(der: &[u8], path: &str) -> Result<CertificateInfo, String> {
+    let (_, certificate) = X509Certificate::from_der(der)
+        .map_err(|e| format!("CertificateInvalid: {}: {}", path, e))?;
+    let validity = certificate.validity();
+
+    Ok(CertificateInfo {
+        subject: certificate.subject().to_string(),
+        issuer: certificate.issuer().to_string(),
+        valid_from: validity.not_before.timestamp() * 1000,
+        expires_at: validity.not_after.timestamp() * 1000,
+    })
+}
+
+/// Wrapped at 64 characters, like the files of OpenSSL.
+fn pem(label: &str, der: &[u8]) -> String {
+    let encoded = STANDARD.encode(der);
+    let lines: Vec<&str> = encoded.as_bytes().chunks(64)
+        .filter_map(|line| std::str::from_utf8(line).ok())
+        .collect();
+    format!("-----BEGIN {}-----\n{}\n-----END {}-----\n", label, lines.join("\n"), label)
+}
+
+fn date(timestamp: i64) -> String {
+    DateTime::from_timestamp_millis(timestamp)
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use super::*;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tls/");
+
+    fn fixture(name: &str) -> String {
+        format!("{}{}", FIXTURES, name)
+    }
+
+    #[test]
+    fn reads_client_certificates() {
+        // The legacy one is encrypted with RC2 and 3DES, like older exports
+        for name in ["client.p12", "client-legacy.p12"] {
+            let (_, info) = read_client_certificate(&fixture(name), "alduin").unwrap();
+
+            assert_eq!(info.subject, "O=Homelab, CN=alduin", "{}", name);
+            assert_eq!(info.issuer, "O=Alduin Tests, CN=Alduin Test CA", "{}", name);
+            assert!(info.valid_from < info.expires_at, "{}", name);
+            assert!(date(info.expires_at).starts_with("2126-"), "{}", name);
+        }
+    }
+
+    #[test]
+    fn invalid_client_certificates() {
+        let cases = [
+            ("client.p12", "wrong", "isn't a PKCS#12 file, or its passphrase is wrong"),
+            ("ca.pem", "alduin", "isn't a PKCS#12 file, or its passphrase is wrong"),
+            ("missing.p12", "alduin", "error reading"),
+        ];
+
+        for (name, passphrase, expected) in cases {
+            let error = read_client_certificate(&fixture(name), passphrase).err().unwrap();
+            assert!(error.starts_with("CertificateInvalid: "), "{}: {}", name, error);
+            assert!(error.contains(expected), "{}: {}", name, error);
+        }
+    }
+
+    #[test]
+    fn reads_ca_certificates() {
+        let (_, pem) = parse_x509_pem(&fs::read(fixture("ca.pem")).unwrap()).unwrap();
+        let der_path: PathBuf = std::env::temp_dir().join(format!("alduin-ca-{}.der", std::process::id()));
+        fs::write(&der_path, &pem.contents).unwrap();
+
+        for path in [fixture("ca.pem"), der_path.display().to_string()] {
+            let (_, info) = read_ca_certificate(&path).unwrap();
+            assert_eq!(info.subject, "O=Alduin Tests, CN=Alduin Test CA", "{}", path);
+            assert_eq!(info.issuer, info.subject, "{}", path);
+        }
+        fs::remove_file(der_path).unwrap();
+
+        let error = read_ca_certificate(&fixture("server.key")).err().unwrap();
+        assert!(error.contains("isn't a PEM or DER certificate"), "{}", error);
+    }
+
+    #[test]
+    fn expired_client_certificates() {
+        let tls = |name: &str| FeedTls { identity: Some(read_client_certificate(&fixture(name), "alduin").unwrap()), ca: None };
+
+        assert_eq!(expired_error(&tls("client.p12")), None);
+        assert_eq!(expired_error(&tls("expired.p12")).as_deref(), Some("ClientCertificateExpired: the client certificate O=Homelab, CN=alduin expired on 2021-01-01"));
+        assert_eq!(expired_error(&FeedTls::default()), None);
+    }
+}
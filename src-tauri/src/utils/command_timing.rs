@@ -0,0 +1,170 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::scheduler::limiter::full_pass_running;
+use crate::structs::command_timing::CommandTiming;
+
+/// Calls kept per command for the percentiles.
+const RECENT_CALLS: usize = 512;
+const SLOW_COMMAND: Duration = Duration::from_millis(500);
+
+#[derive(Default)]
+struct Samples {
+    count: u64,
+    max: Duration,
+    recent: VecDeque<Duration>,
+    outcomes: BTreeMap<String, u64>,
+    /// The calls that ended while `sync_all` ran, to spot the ones waiting on its writes
+    count_during_sync: u64,
+    recent_during_sync: VecDeque<Duration>,
+}
+
+static TIMINGS: Mutex<BTreeMap<&'static str, Samples>> = Mutex::new(BTreeMap::new());
+
+/// A running command, entered by the commands declared with `alduin_macros::command`.
+pub struct CommandSpan {
+    command: &'static str,
+    /// Length of the text and list parameters, never their contents
+    sizes: Vec<(&'static str, Option<usize>)>,
+    started_at: Instant,
+}
+
+impl CommandSpan {
+    pub fn enter(command: &'static str, sizes: Vec<(&'static str, Option<usize>)>) -> Self {
+        Self { command, sizes, started_at: Instant::now() }
+    }
+
+    pub fn exit(self, result: &impl CommandOutcome) {
+        let elapsed = self.started_at.elapsed();
+        let outcome = result.outcome();
+
+        if elapsed >= SLOW_COMMAND {
+            eprintln!("{}", self.slow_call(elapsed, &outcome));
+        }
+
+        let mut timings = TIMINGS.lock().unwrap();
+        let samples = timings.entry(self.command).or_default();
+        samples.count += 1;
+        samples.max = samples.max.max(elapsed);
+        if samples.recent.len() == RECENT_CALLS {
+            samples.recent.pop_front();
+        }
+        samples.recent.push_back(elapsed);
+        *samples.outcomes.entry(outcome).or_default() += 1;
+
+        if full_pass_running() {
+            samples.count_during_sync += 1;
+            if samples.recent_during_sync.len() == RECENT_CALLS {
+                samples.recent_during_sync.pop_front();
+            }
+            samples.recent_during_sync.push_back(elapsed);
+        }
+    }
+
+    fn slow_call(&self, elapsed: Duration, outcome: &str) -> String {
+        let sizes = self.sizes.iter()
+            .map(|(name, size)| match size {
+                Some(size) => format!("{}: {}", name, size),
+                None => format!("{}: none", name),
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!("Slow command {} took {} ms ({}) [{}]", self.command, elapsed.as_millis(), outcome, sizes)
+    }
+}
+
+pub trait CommandOutcome {
+    fn outcome(&self) -> String;
+}
+
+impl CommandOutcome for () {
+    fn outcome(&self) -> String {
+        String::from("ok")
+    }
+}
+
+impl<T> CommandOutcome for Result<T, String> {
+    /// Typed errors start with their kind, like "WritesFrozen: ...", other errors are only counted as such.
+    fn outcome(&self) -> String {
+        match self {
+            Ok(_) => String::from("ok"),
+            Err(e) => match e.split_once(':') {
+                Some((kind, _)) if !kind.is_empty() && kind.chars().all(|c| c.is_ascii_alphanumeric()) => kind.to_string(),
+                _ => String::from("error"),
+            },
+        }
+    }
+}
+
+pub fn command_timings() -> Vec<CommandTiming> {
+    TIMINGS.lock().unwrap().iter()
+        .map(|(command, samples)| {
+            let mut recent: Vec<Duration> = samples.recent.iter().copied().collect();
+            recent.sort();
+            let mut recent_during_sync: Vec<Duration> = samples.recent_during_sync.iter().copied().collect();
+            recent_during_sync.sort();
+
+            CommandTiming {
+                command: command.to_string(),
+                count: samples.count,
+                p50_ms: milliseconds(percentile(&recent, 0.5)),
+                p95_ms: milliseconds(percentile(&recent, 0.95)),
+                max_ms: milliseconds(samples.max),
+                outcomes: samples.outcomes.clone(),
+                count_during_sync: samples.count_during_sync,
+                p50_during_sync_ms: milliseconds(percentile(&recent_during_sync, 0.5)),
+                p95_during_sync_ms: milliseconds(percentile(&recent_during_sync, 0.95)),
+            }
+        })
+        .collect()
+}
+
+pub fn percentile(sorted: &[Duration], rank: f64) -> Duration {
+    match sorted.len() {
+        0 => Duration::ZERO,
+        len => sorted[((len - 1) as f64 * rank).round() as usize],
+    }
+}
+
+pub fn milliseconds(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(command: &str) -> CommandTiming {
+        command_timings().into_iter().find(|timing| timing.command == command).unwrap()
+    }
+
+    #[test]
+    fn records_the_calls_and_their_outcomes() {
+        for result in [Ok(()), Ok(()), Err(String::from("WritesFrozen: backing up")), Err(String::from("Error executing query: locked"))] {
+            let span = CommandSpan::enter("timed_test_command", vec![]);
+            span.exit(&result);
+        }
+
+        let timing = timing("timed_test_command");
+        assert_eq!(timing.count, 4);
+        assert_eq!(timing.outcomes, BTreeMap::from([
+            (String::from("WritesFrozen"), 1),
+            (String::from("error"), 1),
+            (String::from("ok"), 2),
+        ]));
+        assert!(timing.p50_ms <= timing.p95_ms && timing.p95_ms <= timing.max_ms);
+    }
+
+    #[test]
+    fn logs_the_sizes_of_the_parameters_only() {
+        let password = String::from("hunter2");
+        let cookies: Option<Vec<String>> = None;
+        // As declared by `alduin_macros::command`
+        let span = CommandSpan::enter("sign_in", vec![("password", Some(password.len())), ("cookies", cookies.as_ref().map(|value| value.len()))]);
+
+        let logged = span.slow_call(Duration::from_millis(750), "ok");
+
+        assert_eq!(logged, "Slow command sign_in took 750 ms (ok) [password: 7, cookies: none]");
+        assert!(!logged.contains(&password));
+    }
+}
@@ -0,0 +1,135 @@
+use crate::structs::feed_cookie::FeedCookie;
+use crate::utils::input_limits::{too_large, MAX_COOKIE_EXPORT_BYTES};
+
+/// Names and values end up in a Cookie header as is, so anything that could break out of it is refused.
+pub fn validate_cookie(cookie: &FeedCookie) -> Result<(), String> {
+    let valid_name = !cookie.name.is_empty()
+        && cookie.name.chars().all(|c| c.is_ascii_graphic() && !"()<>@,;:\\\"/[]?={}".contains(c));
+    let valid_value = cookie.value.chars().all(|c| c.is_ascii_graphic() && !";,\\\"".contains(c));
+
+    if valid_name && valid_value {
+        Ok(())
+    } else {
+        Err(format!("Invalid cookie: {}", cookie.name))
+    }
+}
+
+pub fn cookie_header(cookies: &[FeedCookie]) -> String {
+    cookies.iter()
+        .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+        .collect::<Vec<String>>()
+        .join("; ")
+}
+
+/// Extracts the cookies for `host` from a pasted "Copy as cURL" command or a Netscape cookies.txt export.
+/// `now` is in seconds, to skip the cookies of the export that already expired.
+pub fn parse_cookie_export(input: &str, host: &str, now: i64) -> Result<Vec<FeedCookie>, String> {
+    if input.len() > MAX_COOKIE_EXPORT_BYTES {
+        return Err(too_large("The cookie export", MAX_COOKIE_EXPORT_BYTES));
+    }
+    let cookies = if input.trim_start().starts_with("curl ") {
+        parse_curl(input)?
+    } else {
+        parse_cookies_txt(input, host, now)
+    };
+
+    if cookies.is_empty() {
+        return Err(format!("No cookies found for {}", host));
+    }
+    Ok(cookies)
+}
+
+fn parse_curl(command: &str) -> Result<Vec<FeedCookie>, String> {
+    let words = shell_words(command)?;
+    let mut cookies = vec![];
+
+    for (option, value) in words.iter().zip(words.iter().skip(1)) {
+        let header = match option.as_str() {
+            "-b" | "--cookie" => Some(value.as_str()),
+            "-H" | "--header" => value.split_once(':')
+                .filter(|(name, _)| name.trim().eq_ignore_ascii_case("cookie"))
+                .map(|(_, header)| header),
+            _ => None,
+        };
+
+        // A value without "=" is a cookie jar file name for curl
+        for pair in header.into_iter().flat_map(|header| header.split(';')) {
+            if let Some((name, value)) = pair.split_once('=') {
+                cookies.push(FeedCookie { name: name.trim().to_string(), value: value.trim().to_string() });
+            }
+        }
+    }
+
+    Ok(cookies)
+}
+
+/// Splits the command like a POSIX shell would, quotes and line continuations included.
+fn shell_words(command: &str) -> Result<Vec<String>, String> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut characters = command.chars();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '\'' => {
+                let current = word.get_or_insert_with(String::new);
+                loop {
+                    match characters.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err(String::from("Unbalanced quote in the cURL command")),
+                    }
+                }
+            }
+            '"' => {
+                let current = word.get_or_insert_with(String::new);
+                loop {
+                    match characters.next() {
+                        Some('"') => break,
+                        Some('\\') => current.extend(characters.next()),
+                        Some(c) => current.push(c),
+                        None => return Err(String::from("Unbalanced quote in the cURL command")),
+                    }
+                }
+            }
+            '\\' => match characters.next() {
+                Some('\n') | None => {}
+                Some(c) => word.get_or_insert_with(String::new).push(c),
+            },
+            c if c.is_whitespace() => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+
+    Ok(words)
+}
+
+fn parse_cookies_txt(content: &str, host: &str, now: i64) -> Vec<FeedCookie> {
+    content.lines()
+        .filter_map(|line| {
+            // Browsers export HTTP-only cookies as comments
+            let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+            if line.starts_with('#') {
+                return None;
+            }
+
+            let [domain, subdomains, _path, _secure, expires, name, value] = line.split('\t').collect::<Vec<&str>>()[..] else {
+                return None;
+            };
+            let expired = expires.parse::<i64>().is_ok_and(|expires| expires != 0 && expires < now);
+            let domain = domain.trim_start_matches('.').to_lowercase();
+            let matches = host == domain || (subdomains == "TRUE" && host.ends_with(&format!(".{}", domain)));
+
+            (matches && !expired).then(|| FeedCookie { name: name.to_string(), value: value.trim_end().to_string() })
+        })
+        .collect()
+}
+
+/// Members-only sites usually answer an expired session with their login form instead of the feed.
+pub fn is_login_page(content: &str) -> bool {
+    let start: String = content.trim_start().chars().take(15).collect::<String>().to_lowercase();
+    let is_html = start.starts_with("<!doctype html") || start.starts_with("<html");
+
+    is_html && content.to_lowercase().replace(['"', '\''], "").contains("type=password")
+}
@@ -0,0 +1,26 @@
+use xxhash_rust::xxh32::xxh32;
+use crate::utils::title::clean_title;
+use crate::utils::url::canonical_url;
+
+/// Below this many items a fetch says too little about a feed's GUIDs.
+const MIN_ITEMS_FOR_SWITCH: usize = 3;
+
+/// Identifies an article by its link and original title, insensitive to host variants, case, spacing,
+/// markup and entity encoding. Articles without a usable link can't be identified this way.
+pub fn dedup_key(link: Option<&str>, title: &str) -> Option<u32> {
+    let link = canonical_url(link?)?;
+    let title = clean_title(title).to_lowercase();
+
+    Some(xxh32(format!("{}\n{}", link, title).as_bytes(), 42))
+}
+
+/// The format a feed was served in until this sync, when it changed. Content negotiation gone wrong serves
+/// the same items in another format, under other GUIDs. The first sync only discovers the format.
+pub fn changed_format(previous: Option<&str>, current: &str, first_sync: bool) -> Option<String> {
+    previous.filter(|previous| !first_sync && *previous != current).map(String::from)
+}
+
+/// A feed rotates its GUIDs when most items of a fetch are known by link and title but not by GUID.
+pub fn has_unstable_guids(rotated: usize, total: usize) -> bool {
+    total >= MIN_ITEMS_FOR_SWITCH && rotated * 2 >= total
+}
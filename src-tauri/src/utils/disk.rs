@@ -0,0 +1,41 @@
+use std::path::Path;
+
+/// Bytes available to this user on the disk holding `path`, none when it can't be told.
+#[cfg(unix)]
+pub fn free_space(path: &Path) -> Option<u64> {
+    let output = std::process::Command::new("df")
+        .arg("-Pk")
+        .arg(path)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+
+    // Filesystem, 1024-blocks, Used, Available, Capacity, Mounted on
+    String::from_utf8_lossy(&output.stdout).lines()
+        .nth(1)
+        .and_then(|line| line.split_whitespace().nth(3))
+        .and_then(|available| available.parse::<u64>().ok())
+        .map(|kilobytes| kilobytes * 1024)
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetDiskFreeSpaceExW(directory: *const u16, available: *mut u64, total: *mut u64, free: *mut u64) -> i32;
+}
+
+#[cfg(target_os = "windows")]
+pub fn free_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+
+    let directory: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut available = 0;
+    // SAFETY: the path is NUL-terminated and the counts outlive the call, the last two may be null
+    let succeeded = unsafe { GetDiskFreeSpaceExW(directory.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut()) };
+    (succeeded != 0).then_some(available)
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+pub fn free_space(_path: &Path) -> Option<u64> {
+    None
+}
@@ -0,0 +1,312 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::NaiveDate;
+
+/// Default names of the exported files, the `-{n}` suffix only being added when the name is taken.
+const NAME_TEMPLATE: &str = "{kind}-{date}";
+/// Most file systems allow 255 bytes, this leaves room for the `.partial` suffix of an archive being written.
+const MAX_FILE_NAME_BYTES: usize = 240;
+/// Extensions longer than this are cut with the rest of the name rather than kept whole.
+const MAX_EXTENSION_BYTES: usize = 16;
+const MAX_COLLISION_SUFFIX: u32 = 9999;
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// The name of an export made on `date`, like `alduin-feeds-2024-03-01.opml`.
+pub fn default_file_name(kind: &str, extension: &str, date: NaiveDate) -> String {
+    let stem = NAME_TEMPLATE
+        .replace("{kind}", kind)
+        .replace("{date}", &date.format("%Y-%m-%d").to_string());
+    sanitize_file_name(&format!("{}.{}", stem, extension))
+}
+
+/// The name, made valid on every platform since exports move between machines: the characters Windows
+/// forbids replaced by `_`, its reserved device names like `CON` or `lpt1.txt` suffixed with `_`, trailing
+/// dots and spaces removed and the name cut to `MAX_FILE_NAME_BYTES`, keeping its extension.
+pub fn sanitize_file_name(name: &str) -> String {
+    let replaced: String = name.chars()
+        .map(|c| if c.is_control() || matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') { '_' } else { c })
+        .collect();
+    let trimmed = replaced.trim().trim_end_matches(['.', ' ']);
+    let name = if trimmed.is_empty() { "export" } else { trimmed };
+
+    // Windows ignores what follows the first dot, and the spaces before it, when checking for device names
+    let (base, rest) = name.split_at(name.find('.').unwrap_or(name.len()));
+    let name = if RESERVED_NAMES.iter().any(|reserved| base.trim_end().eq_ignore_ascii_case(reserved)) {
+        format!("{}_{}", base.trim_end(), rest)
+    } else {
+        name.to_string()
+    };
+
+    let (stem, extension) = split_extension(&name);
+    fit_file_name(stem, "", extension)
+}
+
+/// Where to write an export: the `requested` path, inside `directory` when relative, with `default_name`
+/// when it is a folder or missing. Its name is sanitized and its missing folders created. Unless
+/// `overwrite`, a taken name gets the first free suffix, `-1`, `-2` and so on.
+pub fn resolve_export_path(requested: Option<&str>, directory: &Path, default_name: &str, overwrite: bool) -> Result<PathBuf, String> {
+    let requested = requested.map(str::trim).filter(|requested| !requested.is_empty());
+    let path = match requested {
+        Some(requested) => {
+            let path = directory.join(requested);
+            if requested.ends_with(['/', '\\']) || io_path(&path).is_dir() {
+                path.join(default_name)
+            } else {
+                let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                path.with_file_name(sanitize_file_name(&name))
+            }
+        }
+        None => directory.join(default_name),
+    };
+
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(io_path(parent)).map_err(|e| format!("Error creating the folder {}: {}", parent.display(), e))?;
+    }
+
+    if overwrite {
+        if io_path(&path).is_dir() {
+            return Err(format!("{} is a folder, it can't be overwritten", path.display()));
+        }
+        return Ok(path);
+    }
+    free_path(&path)
+}
+
+/// The path to open the file with. Windows needs the verbatim prefix past `MAX_PATH`, whatever its settings.
+#[cfg(target_os = "windows")]
+pub fn io_path(path: &Path) -> PathBuf {
+    const MAX_PATH: usize = 259;
+
+    let text = path.to_string_lossy();
+    if text.len() <= MAX_PATH || text.starts_with(r"\\?\") || !path.is_absolute() {
+        return path.to_path_buf();
+    }
+    // Verbatim paths are used as they are, without the usual separator conversion
+    let text = text.replace('/', "\\");
+    match text.strip_prefix(r"\\") {
+        Some(share) => PathBuf::from(format!(r"\\?\UNC\{}", share)),
+        None => PathBuf::from(format!(r"\\?\{}", text)),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn io_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+/// The path, or the first one suffixed with a number that nothing exists at, broken links included.
+fn free_path(path: &Path) -> Result<PathBuf, String> {
+    if fs::symlink_metadata(io_path(path)).is_err() {
+        return Ok(path.to_path_buf());
+    }
+
+    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let (stem, extension) = split_extension(&name);
+    for n in 1..=MAX_COLLISION_SUFFIX {
+        let candidate = path.with_file_name(fit_file_name(stem, &format!("-{}", n), extension));
+        if fs::symlink_metadata(io_path(&candidate)).is_err() {
+            return Ok(candidate);
+        }
+    }
+
+    Err(format!("Error choosing a name for the export, {} and its numbered copies all exist", path.display()))
+}
+
+/// The stem and the extension with its dot, none for names starting with their only dot like `.opml`.
+fn split_extension(name: &str) -> (&str, &str) {
+    match name.rfind('.') {
+        Some(dot) if dot > 0 && name.len() - dot <= MAX_EXTENSION_BYTES => (&name[..dot], &name[dot..]),
+        _ => (name, ""),
+    }
+}
+
+/// The stem is cut at a character boundary so the name fits, the suffix and extension being kept.
+fn fit_file_name(stem: &str, suffix: &str, extension: &str) -> String {
+    let available = MAX_FILE_NAME_BYTES.saturating_sub(suffix.len() + extension.len());
+    let mut end = stem.len().min(available);
+    while !stem.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    let stem = stem[..end].trim_end_matches(['.', ' ']);
+    let stem = if stem.is_empty() { "export" } else { stem };
+    format!("{}{}{}", stem, suffix, extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temporary_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("alduin-export-path-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn names_exports_by_kind_and_date() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let cases = [
+            ("alduin-feeds", "opml", "alduin-feeds-2024-03-01.opml"),
+            ("alduin-export", "zip", "alduin-export-2024-03-01.zip"),
+            ("metrics/local", "json", "metrics_local-2024-03-01.json"),
+            ("CON", "txt", "CON-2024-03-01.txt"),
+        ];
+
+        for case in cases.iter() {
+            assert_eq!(default_file_name(case.0, case.1, date), case.2, "{:?}", case);
+        }
+    }
+
+    #[test]
+    fn sanitizes_file_names() {
+        let cases = [
+            ("feeds.opml", String::from("feeds.opml")),
+            (r#"a<b>c:d"e/f\g|h?i*j.txt"#, String::from("a_b_c_d_e_f_g_h_i_j.txt")),
+            ("tab\there\u{7}.txt", String::from("tab_here_.txt")),
+            ("  name.txt  ", String::from("name.txt")),
+            ("name...", String::from("name")),
+            ("name. . ", String::from("name")),
+            ("", String::from("export")),
+            ("...", String::from("export")),
+            ("   ", String::from("export")),
+            ("CON .txt", String::from("CON_.txt")),
+            ("CONSOLE.txt", String::from("CONSOLE.txt")),
+            ("COM10", String::from("COM10")),
+            ("xcon", String::from("xcon")),
+            // A hidden file, its only dot starting its name
+            (".opml", String::from(".opml")),
+            ("éñ✓.opml", String::from("éñ✓.opml")),
+            // Too long to be an extension, cut with the rest
+            (&*format!("archive.{}", "x".repeat(300)), format!("archive.{}", "x".repeat(232))),
+            (&*format!("{}.opml", "a".repeat(300)), format!("{}.opml", "a".repeat(235))),
+            (&*format!("{}.zip", "é".repeat(200)), format!("{}.zip", "é".repeat(118))),
+            (&*format!("a{}.zip", "é".repeat(200)), format!("a{}.zip", "é".repeat(117))),
+            // The cut leaving dots at the end of the stem
+            (&*format!("{}{}.txt", "a".repeat(230), ".".repeat(20)), format!("{}.txt", "a".repeat(230))),
+        ];
+
+        for case in cases.iter() {
+            let sanitized = sanitize_file_name(case.0);
+            assert_eq!(sanitized, case.1, "{:?}", case);
+            assert!(sanitized.len() <= MAX_FILE_NAME_BYTES, "{:?}", case);
+            // Sanitizing is stable
+            assert_eq!(sanitize_file_name(&sanitized), sanitized, "{:?}", case);
+        }
+    }
+
+    #[test]
+    fn suffixes_every_reserved_name() {
+        for reserved in RESERVED_NAMES {
+            for name in [reserved.to_string(), reserved.to_lowercase(), format!("{}{}", &reserved[..1], reserved[1..].to_lowercase())] {
+                for rest in ["", ".txt", ".tar.gz"] {
+                    let file_name = format!("{}{}", name, rest);
+                    assert_eq!(sanitize_file_name(&file_name), format!("{}_{}", name, rest), "{}", file_name);
+                }
+                // Only the whole name before the first dot is reserved
+                let longer = format!("{}x.txt", name);
+                assert_eq!(sanitize_file_name(&longer), longer);
+            }
+        }
+    }
+
+    #[test]
+    fn resolves_the_requested_path() {
+        let directory = temporary_directory("requested");
+        fs::create_dir_all(directory.join("existing")).unwrap();
+        let absolute = directory.join("absolute.opml");
+        let cases = [
+            (None, directory.join("default.opml")),
+            (Some("   "), directory.join("default.opml")),
+            (Some("named.opml"), directory.join("named.opml")),
+            (Some("missing/folders/named.opml"), directory.join("missing/folders/named.opml")),
+            (Some("folder/"), directory.join("folder/default.opml")),
+            (Some("existing"), directory.join("existing/default.opml")),
+            (Some("bad:name?.opml"), directory.join("bad_name_.opml")),
+            (Some("sub/CON.opml"), directory.join("sub/CON_.opml")),
+            (Some(absolute.to_str().unwrap()), absolute.clone()),
+        ];
+
+        for case in cases.iter() {
+            let resolved = resolve_export_path(case.0, &directory, "default.opml", false);
+            assert_eq!(resolved.as_ref(), Ok(&case.1), "{:?}", case);
+            assert!(case.1.parent().unwrap().is_dir(), "{:?}", case);
+        }
+
+        // A file where a folder is needed
+        fs::write(directory.join("blocker"), "").unwrap();
+        let error = resolve_export_path(Some("blocker/named.opml"), &directory, "default.opml", false).unwrap_err();
+        assert!(error.starts_with("Error creating the folder"), "{}", error);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn never_overwrites_unless_asked() {
+        let directory = temporary_directory("collisions");
+        let resolve = |overwrite| resolve_export_path(None, &directory, "feeds.opml", overwrite).unwrap();
+
+        let mut taken = vec![];
+        for expected in ["feeds.opml", "feeds-1.opml", "feeds-2.opml"] {
+            let path = resolve(false);
+            assert_eq!(path, directory.join(expected));
+            fs::write(&path, "exported").unwrap();
+            taken.push(path);
+        }
+        // Folders and broken links take names too
+        fs::create_dir(directory.join("feeds-3.opml")).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(directory.join("gone"), directory.join("feeds-4.opml")).unwrap();
+        #[cfg(unix)]
+        assert_eq!(resolve(false), directory.join("feeds-5.opml"));
+        // A gap is filled first
+        fs::remove_file(&taken[1]).unwrap();
+        assert_eq!(resolve(false), directory.join("feeds-1.opml"));
+
+        assert_eq!(resolve(true), directory.join("feeds.opml"));
+        for path in taken.iter().filter(|path| path.exists()) {
+            assert_eq!(fs::read_to_string(path).unwrap(), "exported");
+        }
+        // Requested, a folder is exported into, so only a default name can point at one
+        let error = resolve_export_path(None, &directory, "feeds-3.opml", true).unwrap_err();
+        assert!(error.ends_with("is a folder, it can't be overwritten"), "{}", error);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn keeps_suffixed_long_names_within_the_limit() {
+        let directory = temporary_directory("long");
+        let name = sanitize_file_name(&format!("{}.opml", "é".repeat(200)));
+        fs::write(directory.join(&name), "").unwrap();
+
+        let mut previous = name.clone();
+        for n in 1..=12 {
+            let path = resolve_export_path(None, &directory, &name, false).unwrap();
+            let file_name = path.file_name().unwrap().to_str().unwrap().to_string();
+            assert!(file_name.len() <= MAX_FILE_NAME_BYTES, "{}", file_name);
+            assert!(file_name.ends_with(&format!("-{}.opml", n)), "{}", file_name);
+            assert_ne!(file_name, previous);
+            fs::write(&path, "").unwrap();
+            previous = file_name;
+        }
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn fails_once_every_suffix_is_taken() {
+        let directory = temporary_directory("exhausted");
+        fs::write(directory.join("feeds.opml"), "").unwrap();
+        for n in 1..=MAX_COLLISION_SUFFIX {
+            fs::write(directory.join(format!("feeds-{}.opml", n)), "").unwrap();
+        }
+
+        let error = resolve_export_path(None, &directory, "feeds.opml", false).unwrap_err();
+        assert!(error.starts_with("Error choosing a name for the export"), "{}", error);
+        assert_eq!(resolve_export_path(None, &directory, "feeds.opml", true), Ok(directory.join("feeds.opml")));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
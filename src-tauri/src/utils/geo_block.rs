@@ -0,0 +1,39 @@
+use std::sync::LazyLock;
+use regex::Regex;
+use reqwest::StatusCode;
+
+static TITLE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap());
+
+/// Wordings of the pages CDNs and publishers answer with when blocking a country.
+const GEO_BLOCK_PHRASES: [&str; 6] = [
+    "not available in your country",
+    "not available in your region",
+    "not available in your location",
+    "unavailable in your country",
+    "unavailable for legal reasons",
+    "geo-restricted",
+];
+
+/// Returns the error of a response refused because of where it was requested from, if it was.
+/// Only failed responses are considered, a feed may well mention these phrases in an article.
+pub fn geo_block_error(status: StatusCode, body: &str) -> Option<String> {
+    if status.is_success() || status.is_redirection() {
+        return None;
+    }
+
+    let lowercase_body = body.to_lowercase();
+    let blocked = status == StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS
+        || GEO_BLOCK_PHRASES.iter().any(|phrase| lowercase_body.contains(phrase));
+    if !blocked {
+        return None;
+    }
+
+    let explanation = TITLE.captures(body)
+        .map(|captures| captures[1].split_whitespace().collect::<Vec<&str>>().join(" "))
+        .filter(|title| !title.is_empty());
+
+    Some(match explanation {
+        Some(explanation) => format!("GeoBlocked: not available from this location ({})", explanation),
+        None => String::from("GeoBlocked: not available from this location"),
+    })
+}
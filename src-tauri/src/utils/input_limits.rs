@@ -0,0 +1,43 @@
+//! Ceilings on the untrusted input of the parsers, so a hostile feed or file is refused before it fills the
+//! memory. Responses and files are checked as they are read, the parsers check again at their entry point.
+
+use std::fs;
+
+/// Of a response, feeds, pages and transcripts alike
+pub const MAX_RESPONSE_BYTES: usize = 32 * 1024 * 1024;
+pub const MAX_FEED_BYTES: usize = MAX_RESPONSE_BYTES;
+pub const MAX_TRANSCRIPT_BYTES: usize = 8 * 1024 * 1024;
+pub const MAX_OPML_BYTES: usize = 16 * 1024 * 1024;
+/// Browsers export the favicon of every bookmark inline
+pub const MAX_BOOKMARKS_BYTES: usize = 64 * 1024 * 1024;
+pub const MAX_COOKIE_EXPORT_BYTES: usize = 1024 * 1024;
+/// Of a page or an article scanned for links, the rest being ignored
+pub const MAX_SCANNED_HTML_BYTES: usize = 2 * 1024 * 1024;
+pub const MAX_SEARCH_QUERY_BYTES: usize = 2 * 1024;
+/// Folders nested deeper in an import are flattened into their ancestor at this depth
+pub const MAX_FOLDER_DEPTH: usize = 16;
+
+pub fn too_large(what: &str, limit: usize) -> String {
+    match limit {
+        limit if limit >= 1024 * 1024 => format!("{} is larger than {} MB", what, limit / 1024 / 1024),
+        limit => format!("{} is larger than {} KB", what, limit / 1024),
+    }
+}
+
+/// Reads a file picked by the user, refused without reading it when larger than `limit`.
+pub fn read_file(path: &str, limit: usize) -> Result<Vec<u8>, String> {
+    let metadata = fs::metadata(path).map_err(|e| format!("Error reading {}: {}", path, e))?;
+    if metadata.len() > limit as u64 {
+        return Err(too_large(path, limit));
+    }
+
+    fs::read(path).map_err(|e| format!("Error reading {}: {}", path, e))
+}
+
+/// The start of `content` scanned when it's longer than `limit`, cut on a character boundary.
+pub fn scanned(content: &str, limit: usize) -> &str {
+    let end = (0..=limit.min(content.len())).rev()
+        .find(|index| content.is_char_boundary(*index))
+        .unwrap_or_default();
+    &content[..end]
+}
@@ -0,0 +1,25 @@
+pub mod url;
+pub mod dedup;
+pub mod memory;
+pub mod autostart;
+pub mod tray;
+pub mod cookies;
+pub mod geo_block;
+pub mod capabilities;
+pub mod pagination;
+pub mod window_placement;
+pub mod opml;
+pub mod command_timing;
+pub mod power;
+pub mod ulid;
+pub mod title;
+pub mod certificates;
+pub mod notification;
+pub mod export_path;
+pub mod disk;
+pub mod storage_projection;
+pub mod input_limits;
+pub mod time_format;
+pub mod preferences_schema;
+#[cfg(test)]
+pub mod cancellation;
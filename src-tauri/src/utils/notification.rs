@@ -0,0 +1,38 @@
+use tauri::api::notification::Notification;
+use tauri::AppHandle;
+
+/// Shows a native notification, for what happens while the main window may be hidden. Failures are
+/// only logged, some desktops having no notification daemon.
+pub fn notify(app_handle: &AppHandle, body: &str) {
+    let shown = Notification::new(&app_handle.config().tauri.bundle.identifier)
+        .title("Alduin")
+        .body(body)
+        .show();
+    if let Err(e) = shown {
+        eprintln!("Error showing notification \"{}\": {}", body, e);
+    }
+}
+
+/// Whether a notification daemon owns its name on the session bus, none when the bus can't be asked.
+#[cfg(target_os = "linux")]
+pub fn probe_notification_daemon() -> Option<bool> {
+    let output = std::process::Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus.NameHasOwner",
+            "string:org.freedesktop.Notifications",
+        ])
+        .output()
+        .ok()?;
+
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).contains("boolean true"))
+}
+
+/// Notifications are part of the system elsewhere.
+#[cfg(not(target_os = "linux"))]
+pub fn probe_notification_daemon() -> Option<bool> {
+    Some(true)
+}
@@ -0,0 +1,433 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use encoding_rs::{Encoding, UTF_8};
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use regex::bytes::Regex;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use crate::structs::exported_feed::ExportedFeed;
+use crate::structs::folder::Folder;
+use crate::structs::opml_export_options::OpmlExportOptions;
+use crate::structs::opml_feed_settings::OpmlFeedSettings;
+use crate::structs::opml_outline::OpmlOutline;
+use crate::utils::input_limits::{too_large, MAX_FOLDER_DEPTH, MAX_OPML_BYTES};
+use crate::utils::title::clean_title;
+
+/// Prefix of the attributes holding the settings of a feed
+const NAMESPACE_PREFIX: &str = "alduin";
+
+/// OPML 2.0 with the folders as nested outlines and the settings of each feed as `alduin:` attributes,
+/// which other readers ignore, unless `options` leaves them out. Feeds and folders whose folder is unknown
+/// are written at the root. With a selection, only the folders leading to a selected feed are written.
+pub fn feeds_opml(feeds: &[ExportedFeed], folders: &[Folder], options: &OpmlExportOptions, exported_at: &str) -> String {
+    let parents: HashMap<&str, Option<&str>> = folders.iter()
+        .map(|folder| (folder.identifier.as_str(), folder.parent_identifier.as_deref()))
+        .collect();
+
+    let selecting = options.folder_identifiers.is_some() || options.feed_identifiers.is_some();
+    let feeds: Vec<&ExportedFeed> = feeds.iter()
+        .filter(|feed| !selecting
+            || options.feed_identifiers.iter().flatten().any(|identifier| *identifier == feed.identifier)
+            || ancestors(feed.folder_identifier.as_deref(), &parents).iter().any(|folder| options.folder_identifiers.iter().flatten().any(|identifier| identifier == folder)))
+        .collect();
+    let written: HashSet<&str> = feeds.iter().flat_map(|feed| ancestors(feed.folder_identifier.as_deref(), &parents)).collect();
+    let folders: Vec<&Folder> = folders.iter()
+        .filter(|folder| options.folders && (!selecting || written.contains(folder.identifier.as_str())))
+        .collect();
+
+    let known: Vec<&str> = folders.iter().map(|folder| folder.identifier.as_str()).collect();
+
+    let mut feeds_in: HashMap<Option<&str>, Vec<&ExportedFeed>> = HashMap::new();
+    for feed in feeds {
+        feeds_in.entry(known_folder(&feed.folder_identifier, &known)).or_default().push(feed);
+    }
+    let mut folders_in: HashMap<Option<&str>, Vec<&Folder>> = HashMap::new();
+    for folder in folders.iter().copied() {
+        folders_in.entry(known_folder(&folder.parent_identifier, &known)).or_default().push(folder);
+    }
+
+    let mut outlines = String::new();
+    write_outlines(None, 2, &feeds_in, &folders_in, !options.interchange, &mut outlines);
+
+    let namespace = if options.interchange { String::new() } else { format!(" xmlns:{}=\"https://alduin.stouder.io/opml\"", NAMESPACE_PREFIX) };
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\"{}>\n  <head>\n    <title>Alduin subscriptions</title>\n    <dateCreated>{}</dateCreated>\n  </head>\n  <body>\n{}  </body>\n</opml>\n",
+        namespace, exported_at, outlines,
+    )
+}
+
+/// `folder` and the folders it's in, up to the root.
+fn ancestors<'a>(folder: Option<&'a str>, parents: &HashMap<&'a str, Option<&'a str>>) -> Vec<&'a str> {
+    let mut ancestors = vec![];
+    let mut next = folder;
+    while let Some(identifier) = next.filter(|identifier| !ancestors.contains(identifier)) {
+        ancestors.push(identifier);
+        next = parents.get(identifier).copied().flatten();
+    }
+    ancestors
+}
+
+fn known_folder<'a>(identifier: &'a Option<String>, known: &[&str]) -> Option<&'a str> {
+    identifier.as_deref().filter(|identifier| known.contains(identifier))
+}
+
+fn write_outlines(folder: Option<&str>, depth: usize, feeds_in: &HashMap<Option<&str>, Vec<&ExportedFeed>>, folders_in: &HashMap<Option<&str>, Vec<&Folder>>, settings: bool, outlines: &mut String) {
+    let indent = "  ".repeat(depth);
+
+    for child in folders_in.get(&folder).into_iter().flatten() {
+        let name = escape(&child.name);
+        let has_children = feeds_in.contains_key(&Some(child.identifier.as_str()))
+            || folders_in.contains_key(&Some(child.identifier.as_str()));
+        if has_children {
+            outlines.push_str(&format!("{}<outline text=\"{name}\" title=\"{name}\">\n", indent));
+            write_outlines(Some(&child.identifier), depth + 1, feeds_in, folders_in, settings, outlines);
+            outlines.push_str(&format!("{}</outline>\n", indent));
+        } else {
+            outlines.push_str(&format!("{}<outline text=\"{name}\" title=\"{name}\"/>\n", indent));
+        }
+    }
+
+    for feed in feeds_in.get(&folder).into_iter().flatten() {
+        let html_url = feed.html_url.as_deref()
+            .map(|html_url| format!(" htmlUrl=\"{}\"", escape(html_url)))
+            .unwrap_or_default();
+        let settings = if settings { settings_attributes(feed) } else { String::new() };
+        outlines.push_str(&format!(
+            "{indent}<outline type=\"rss\" text=\"{name}\" title=\"{name}\" xmlUrl=\"{url}\"{html_url}{settings}/>\n",
+            name = escape(&feed.display_name),
+            url = escape(&feed.url),
+        ));
+    }
+}
+
+/// The attributes read back by `read_settings`, the retention only when the feed has its own.
+fn settings_attributes(feed: &ExportedFeed) -> String {
+    let interval = feed.interval.to_string();
+    let archived = feed.archived.to_string();
+    let locked = feed.dedup_strategy_locked.to_string();
+    let reread = feed.reread_on_update.to_string();
+    let retention_days = feed.retention_days.map(|days| days.to_string());
+    let retention_max_articles = feed.retention_max_articles.map(|articles| articles.to_string());
+
+    let attributes = [
+        ("identifier", Some(feed.identifier.as_str())),
+        ("interval", Some(interval.as_str())),
+        ("priority", Some(feed.priority.as_str())),
+        ("archived", Some(archived.as_str())),
+        ("dedupStrategy", Some(feed.dedup_strategy.as_str())),
+        ("dedupStrategyLocked", Some(locked.as_str())),
+        ("addressFamily", Some(feed.address_family.as_str())),
+        ("userAgent", Some(feed.user_agent.as_str())),
+        ("rereadOnUpdate", Some(reread.as_str())),
+        ("retentionDays", retention_days.as_deref()),
+        ("retentionMaxArticles", retention_max_articles.as_deref()),
+        ("titlePrefixes", Some(feed.title_prefixes.as_str()).filter(|prefixes| *prefixes != "[]")),
+    ];
+
+    attributes.iter()
+        .filter_map(|(name, value)| value.map(|value| format!(" {}:{}=\"{}\"", NAMESPACE_PREFIX, name, escape(value))))
+        .collect()
+}
+
+/// Reads the outlines of an OPML file in document order, nested ones after their folder.
+/// Outlines without `xmlUrl` are folders. Outlines at the root can also be put in a folder by their
+/// `category` attribute, whose first slash-separated path is used as the folder. Folders nested deeper than
+/// `MAX_FOLDER_DEPTH` are flattened into their ancestor.
+pub fn parse_opml(content: &[u8]) -> Result<Vec<OpmlOutline>, String> {
+    if content.len() > MAX_OPML_BYTES {
+        return Err(too_large("The OPML file", MAX_OPML_BYTES));
+    }
+    let text = decode(content);
+    let mut reader = Reader::from_str(&text);
+    reader.trim_text(true);
+
+    let mut outlines = vec![];
+    // Whether each open outline is a folder, and the names of the folders, the deeper ones left out
+    let mut stack: Vec<bool> = vec![];
+    let mut folders: Vec<String> = vec![];
+    let mut is_opml = false;
+    let mut in_body = false;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                b"opml" => is_opml = true,
+                b"body" => in_body = true,
+                b"outline" if in_body => {
+                    let outline = read_outline(&e, &reader, &folders, stack.is_empty());
+                    match outline {
+                        Some(outline) if outline.xml_url.is_none() && folders.len() < MAX_FOLDER_DEPTH => {
+                            stack.push(true);
+                            folders.push(outline.title.clone());
+                            outlines.push(outline);
+                        }
+                        // Deeper folders are left out, their outlines going to the deepest folder kept
+                        Some(outline) if outline.xml_url.is_none() => stack.push(false),
+                        outline => {
+                            stack.push(false);
+                            outlines.extend(outline);
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Empty(e)) if in_body && e.local_name().as_ref() == b"outline" => {
+                outlines.extend(read_outline(&e, &reader, &folders, stack.is_empty())
+                    .filter(|outline| outline.xml_url.is_some() || folders.len() < MAX_FOLDER_DEPTH));
+            }
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"outline" if stack.pop() == Some(true) => { folders.pop(); }
+                b"body" => in_body = false,
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => return Err(format!("Invalid OPML at position {}: {}", reader.buffer_position(), e)),
+        }
+    }
+
+    if !is_opml {
+        return Err(String::from("This file is not an OPML file"));
+    }
+
+    Ok(outlines)
+}
+
+/// Folders and feeds need a name, taken from `title`, then `text`, then the feed URL.
+fn read_outline(element: &BytesStart, reader: &Reader<&[u8]>, folders: &[String], at_root: bool) -> Option<OpmlOutline> {
+    let attribute = |name: &str| element.attributes().flatten()
+        .find(|attribute| attribute.key.local_name().as_ref().eq_ignore_ascii_case(name.as_bytes()))
+        .and_then(|attribute| attribute.decode_and_unescape_value(reader).ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+
+    let xml_url = attribute("xmlUrl");
+    let title = attribute("title").or_else(|| attribute("text")).map(|title| clean_title(&title)).filter(|title| !title.is_empty())
+        .or_else(|| xml_url.clone())?;
+
+    let mut folder = folders.to_vec();
+    if at_root {
+        if let Some(category) = attribute("category") {
+            folder = category.split(',').next().unwrap_or_default()
+                .split('/')
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .take(MAX_FOLDER_DEPTH)
+                .collect();
+        }
+    }
+
+    let settings = xml_url.is_some().then(|| read_settings(element, reader)).flatten();
+    Some(OpmlOutline { title, xml_url, html_url: attribute("htmlUrl"), folder, settings })
+}
+
+/// The settings of the `alduin:` attributes of a feed outline, None without any. Invalid values are left out.
+fn read_settings(element: &BytesStart, reader: &Reader<&[u8]>) -> Option<OpmlFeedSettings> {
+    let setting = |name: &str| element.attributes().flatten()
+        .find(|attribute| attribute.key.prefix().is_some_and(|prefix| prefix.as_ref() == NAMESPACE_PREFIX.as_bytes())
+            && attribute.key.local_name().as_ref() == name.as_bytes())
+        .and_then(|attribute| attribute.decode_and_unescape_value(reader).ok())
+        .map(|value| value.trim().to_string());
+
+    let settings = OpmlFeedSettings {
+        interval: setting("interval").and_then(|interval| interval.parse().ok()).filter(|interval| *interval > 0),
+        priority: setting("priority").and_then(enum_variant),
+        archived: setting("archived").and_then(|archived| archived.parse().ok()),
+        dedup_strategy: setting("dedupStrategy").and_then(enum_variant),
+        dedup_strategy_locked: setting("dedupStrategyLocked").and_then(|locked| locked.parse().ok()),
+        address_family: setting("addressFamily").and_then(enum_variant),
+        user_agent: setting("userAgent").and_then(enum_variant),
+        reread_on_update: setting("rereadOnUpdate").and_then(|reread| reread.parse().ok()),
+        retention_days: setting("retentionDays").and_then(|days| days.parse().ok()).filter(|days| *days >= 0),
+        retention_max_articles: setting("retentionMaxArticles").and_then(|articles| articles.parse().ok()).filter(|articles| *articles >= 0),
+        title_prefixes: setting("titlePrefixes").and_then(|prefixes| serde_json::from_str(&prefixes).ok()),
+    };
+
+    (settings != OpmlFeedSettings::default()).then_some(settings)
+}
+
+/// An enum saved as its serialized name.
+fn enum_variant<T: DeserializeOwned>(value: String) -> Option<T> {
+    serde_json::from_value(Value::String(value)).ok()
+}
+
+/// Decodes the file as its byte order mark or XML declaration says, UTF-8 otherwise.
+/// Invalid sequences are replaced rather than failing the whole import.
+fn decode(content: &[u8]) -> Cow<'_, str> {
+    let declaration = Regex::new(r#"^\s*<\?xml[^>]*?encoding\s*=\s*["']([A-Za-z0-9._:-]+)["']"#).unwrap();
+    let head = &content[..content.len().min(1024)];
+    let encoding = declaration.captures(head)
+        .and_then(|captures| Encoding::for_label(&captures[1]))
+        .unwrap_or(UTF_8);
+
+    let (text, _, _) = encoding.decode(content);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use quick_xml::events::Event;
+    use crate::enums::address_family::AddressFamily;
+    use crate::enums::dedup_strategy::DedupStrategy;
+    use crate::enums::feed_priority::FeedPriority;
+    use crate::enums::user_agent::UserAgent;
+    use super::*;
+
+    fn feed(identifier: &str, folder: Option<&str>) -> ExportedFeed {
+        ExportedFeed {
+            identifier: identifier.to_string(),
+            display_name: format!("Feed {}", identifier),
+            url: format!("https://example.com/{}.xml", identifier),
+            interval: 5,
+            priority: String::from("normal"),
+            archived: false,
+            dedup_strategy: String::from("guid"),
+            dedup_strategy_locked: false,
+            folder_identifier: folder.map(str::to_string),
+            html_url: None,
+            address_family: String::from("auto"),
+            user_agent: String::from("honest"),
+            reread_on_update: false,
+            retention_days: None,
+            retention_max_articles: None,
+            title_prefixes: String::from("[]"),
+        }
+    }
+
+    fn folder(identifier: &str, parent: Option<&str>) -> Folder {
+        Folder { identifier: identifier.to_string(), name: format!("Folder {}", identifier), parent_identifier: parent.map(str::to_string) }
+    }
+
+    /// Feeds as (title, folder path), sorted
+    fn exported(feeds: &[ExportedFeed], folders: &[Folder], options: &OpmlExportOptions) -> Vec<(String, Vec<String>)> {
+        let mut feeds: Vec<(String, Vec<String>)> = parse_opml(feeds_opml(feeds, folders, options, "now").as_bytes()).unwrap().into_iter()
+            .filter(|outline| outline.xml_url.is_some())
+            .map(|outline| (outline.title, outline.folder))
+            .collect();
+        feeds.sort();
+        feeds
+    }
+
+    #[test]
+    fn round_trip_keeps_every_setting() {
+        let tuned = ExportedFeed {
+            display_name: String::from("Tuned & \"quoted\""),
+            html_url: Some(String::from("https://example.com/?a=1&b=2")),
+            interval: 90,
+            priority: String::from("high"),
+            archived: true,
+            dedup_strategy: String::from("link_title"),
+            dedup_strategy_locked: true,
+            address_family: String::from("ipv6"),
+            user_agent: String::from("browser"),
+            reread_on_update: true,
+            retention_days: Some(0),
+            retention_max_articles: Some(200),
+            title_prefixes: String::from(r#"["[Sponsored] ","Ad, \"big\" <one>: "]"#),
+            ..feed("tuned", Some("child"))
+        };
+        let feeds = [tuned, feed("plain", None)];
+        let folders = [folder("root", None), folder("child", Some("root"))];
+
+        let outlines = parse_opml(feeds_opml(&feeds, &folders, &OpmlExportOptions::default(), "now").as_bytes()).unwrap();
+
+        let [root, child, tuned, plain] = outlines.as_slice() else { panic!("{:?}", outlines) };
+        assert_eq!((root.title.as_str(), root.xml_url.as_deref(), &root.folder), ("Folder root", None, &vec![]));
+        assert_eq!(child.folder, vec!["Folder root"]);
+        assert_eq!(tuned.title, "Tuned & \"quoted\"");
+        assert_eq!(tuned.folder, vec!["Folder root", "Folder child"]);
+        assert_eq!(tuned.html_url.as_deref(), Some("https://example.com/?a=1&b=2"));
+        assert_eq!(tuned.settings, Some(OpmlFeedSettings {
+            interval: Some(90),
+            priority: Some(FeedPriority::High),
+            archived: Some(true),
+            dedup_strategy: Some(DedupStrategy::LinkTitle),
+            dedup_strategy_locked: Some(true),
+            address_family: Some(AddressFamily::Ipv6),
+            user_agent: Some(UserAgent::Browser),
+            reread_on_update: Some(true),
+            retention_days: Some(0),
+            retention_max_articles: Some(200),
+            title_prefixes: Some(vec![String::from("[Sponsored] "), String::from("Ad, \"big\" <one>: ")]),
+        }));
+        // Following the preferences
+        let plain_settings = plain.settings.as_ref().unwrap();
+        assert_eq!((plain_settings.retention_days, plain_settings.retention_max_articles, &plain_settings.title_prefixes), (None, None, &None));
+    }
+
+    #[test]
+    fn interchange_is_plain_opml() {
+        let feeds = [ExportedFeed { html_url: Some(String::from("https://example.com")), priority: String::from("high"), ..feed("a", Some("folder")) }];
+        let folders = [folder("folder", None)];
+        let options = OpmlExportOptions { interchange: true, ..OpmlExportOptions::default() };
+
+        let content = feeds_opml(&feeds, &folders, &options, "now");
+
+        assert!(!content.contains("alduin:") && !content.contains("xmlns"), "{}", content);
+        let mut reader = Reader::from_str(&content);
+        loop {
+            match reader.read_event().unwrap() {
+                Event::Start(element) | Event::Empty(element) => {
+                    for attribute in element.attributes() {
+                        let attribute = attribute.unwrap();
+                        let name = String::from_utf8_lossy(attribute.key.as_ref()).to_string();
+                        assert!(["version", "text", "title", "type", "xmlUrl", "htmlUrl"].contains(&name.as_str()), "{}", name);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        let outlines = parse_opml(content.as_bytes()).unwrap();
+        assert_eq!(outlines.len(), 2);
+        assert!(outlines.iter().all(|outline| outline.settings.is_none()));
+    }
+
+    #[test]
+    fn selections() {
+        let feeds = [feed("top", Some("a")), feed("nested", Some("b")), feed("other", Some("c")), feed("loose", None)];
+        let folders = [folder("a", None), folder("b", Some("a")), folder("c", None), folder("empty", None)];
+        let path = |names: &[&str]| names.iter().map(|name| format!("Folder {}", name)).collect::<Vec<String>>();
+        let select = |folders: Option<&[&str]>, feeds: Option<&[&str]>| OpmlExportOptions {
+            folder_identifiers: folders.map(|folders| folders.iter().map(|folder| folder.to_string()).collect()),
+            feed_identifiers: feeds.map(|feeds| feeds.iter().map(|feed| feed.to_string()).collect()),
+            ..OpmlExportOptions::default()
+        };
+
+        let cases = [
+            (OpmlExportOptions::default(), vec![("top", path(&["a"])), ("nested", path(&["a", "b"])), ("other", path(&["c"])), ("loose", vec![])]),
+            (OpmlExportOptions { folders: false, ..OpmlExportOptions::default() }, vec![("top", vec![]), ("nested", vec![]), ("other", vec![]), ("loose", vec![])]),
+            (select(Some(&["a"]), None), vec![("top", path(&["a"])), ("nested", path(&["a", "b"]))]),
+            (select(Some(&["b"]), None), vec![("nested", path(&["a", "b"]))]),
+            (select(Some(&["b"]), Some(&["loose"])), vec![("nested", path(&["a", "b"])), ("loose", vec![])]),
+            (select(None, Some(&["other"])), vec![("other", path(&["c"]))]),
+            (select(Some(&["empty"]), None), vec![]),
+        ];
+
+        for (options, expected) in cases {
+            let mut expected: Vec<(String, Vec<String>)> = expected.into_iter().map(|(feed, path)| (format!("Feed {}", feed), path)).collect();
+            expected.sort();
+            assert_eq!(exported(&feeds, &folders, &options), expected, "{:?}", options);
+        }
+
+        // Only the folders leading to a selected feed
+        let content = feeds_opml(&feeds, &folders, &select(Some(&["b"]), None), "now");
+        assert!(!content.contains("Folder c") && !content.contains("Folder empty"), "{}", content);
+    }
+
+    #[test]
+    fn invalid_settings_are_left_out() {
+        let content = br#"<opml version="2.0" xmlns:alduin="https://alduin.stouder.io/opml"><body>
+            <outline text="Odd" xmlUrl="https://example.com/odd.xml" alduin:interval="-5" alduin:priority="urgent" alduin:archived="yes" alduin:retentionDays="-1" alduin:titlePrefixes="[1" alduin:userAgent="browser"/>
+            <outline text="Invalid" xmlUrl="https://example.com/invalid.xml" alduin:interval="often"/>
+            <outline text="Other" xmlUrl="https://example.com/other.xml" interval="30" priority="high"/>
+        </body></opml>"#;
+
+        let outlines = parse_opml(content).unwrap();
+
+        let settings: Vec<Option<OpmlFeedSettings>> = outlines.into_iter().map(|outline| outline.settings).collect();
+        assert_eq!(settings, vec![Some(OpmlFeedSettings { user_agent: Some(UserAgent::Browser), ..OpmlFeedSettings::default() }), None, None]);
+    }
+}
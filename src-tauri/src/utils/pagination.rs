@@ -0,0 +1,12 @@
+/// Larger pages stall the IPC serialization, the frontend has to load more instead.
+pub const MAX_PAGE_SIZE: i64 = 500;
+
+/// The page size to use for a request. Capped requests are logged so the caller asking for too much can be found.
+pub fn page_limit(command: &str, requested: Option<i64>, default: i64) -> i64 {
+    let limit = requested.unwrap_or(default);
+    if limit > MAX_PAGE_SIZE {
+        eprintln!("{} asked for {} articles, capped to {}", command, limit, MAX_PAGE_SIZE);
+    }
+
+    limit.clamp(1, MAX_PAGE_SIZE)
+}
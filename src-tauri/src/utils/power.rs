@@ -0,0 +1,103 @@
+use crate::enums::power_source::PowerSource;
+use crate::structs::power_status::PowerStatus;
+
+/// Reads the power supplies the kernel exposes. Peripheral batteries, like the one of a wireless mouse,
+/// are left out. Machines without a battery are on AC.
+#[cfg(target_os = "linux")]
+pub fn read_power_status() -> PowerStatus {
+    let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else {
+        return PowerStatus::default();
+    };
+
+    let read = |path: &std::path::Path, name: &str| std::fs::read_to_string(path.join(name))
+        .map(|value| value.trim().to_string())
+        .unwrap_or_default();
+    let mut on_line = false;
+    let mut discharging = false;
+    let mut battery_percent = None;
+
+    for supply in supplies.flatten() {
+        let path = supply.path();
+        match read(&path, "type").as_str() {
+            "Mains" | "USB" => on_line |= read(&path, "online") == "1",
+            "Battery" if read(&path, "scope") != "Device" => {
+                discharging |= read(&path, "status") == "Discharging";
+                battery_percent = battery_percent.or_else(|| read(&path, "capacity").parse().ok());
+            }
+            _ => {}
+        }
+    }
+
+    PowerStatus {
+        source: if discharging && !on_line { PowerSource::Battery } else { PowerSource::Ac },
+        battery_percent,
+    }
+}
+
+/// Parses `pmset -g batt`, which starts with "Now drawing from 'Battery Power'" and lists the charge
+/// of the internal battery as "85%;".
+#[cfg(target_os = "macos")]
+pub fn read_power_status() -> PowerStatus {
+    let Ok(output) = std::process::Command::new("pmset").args(["-g", "batt"]).output() else {
+        return PowerStatus::default();
+    };
+    let output = String::from_utf8_lossy(&output.stdout);
+
+    let source = if output.contains("'Battery Power'") {
+        PowerSource::Battery
+    } else if output.contains("'AC Power'") {
+        PowerSource::Ac
+    } else {
+        PowerSource::Unknown
+    };
+    let battery_percent = output.lines()
+        .find(|line| line.contains("InternalBattery"))
+        .and_then(|line| line.split_whitespace().find_map(|word| word.strip_suffix("%;")))
+        .and_then(|percent| percent.parse().ok());
+
+    PowerStatus { source, battery_percent }
+}
+
+#[cfg(target_os = "windows")]
+#[repr(C)]
+#[derive(Default)]
+struct SystemPowerStatus {
+    ac_line_status: u8,
+    battery_flag: u8,
+    battery_life_percent: u8,
+    system_status_flag: u8,
+    battery_life_time: u32,
+    battery_full_life_time: u32,
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+}
+
+/// Asks GetSystemPowerStatus, where 255 means unknown for both the line status and the charge.
+#[cfg(target_os = "windows")]
+pub fn read_power_status() -> PowerStatus {
+    let mut status = SystemPowerStatus::default();
+    // SAFETY: the struct matches SYSTEM_POWER_STATUS and outlives the call
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return PowerStatus::default();
+    }
+
+    const NO_BATTERY: u8 = 128;
+    PowerStatus {
+        source: match status.ac_line_status {
+            0 => PowerSource::Battery,
+            1 => PowerSource::Ac,
+            _ => PowerSource::Unknown,
+        },
+        battery_percent: (status.battery_flag & NO_BATTERY == 0 && status.battery_life_percent <= 100)
+            .then_some(status.battery_life_percent),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn read_power_status() -> PowerStatus {
+    PowerStatus::default()
+}
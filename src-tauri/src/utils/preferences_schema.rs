@@ -0,0 +1,224 @@
+//! Versions of the preferences file, the `preferences.dat` of the store plugin written by the frontend.
+//!
+//! The version is saved under `schemaVersion`, files without it being version 0. On load, the steps from
+//! the version of the file to `PREFERENCES_VERSION` run in order, each a pure function of the preferences
+//! object. Every preference is then checked alone against `Preferences`, so an invalid one only resets
+//! itself to its default. Files of a newer version skip the steps, keeping what this version understands.
+
+use serde_json::{Map, Number, Value};
+use crate::structs::preference_reset::PreferenceReset;
+use crate::structs::preferences::Preferences;
+use crate::structs::preferences_upgrade::PreferencesUpgrade;
+
+pub const PREFERENCES_VERSION: u64 = 1;
+pub const VERSION_KEY: &str = "schemaVersion";
+
+/// `STEPS[n]` turns a version n object into a version n + 1 one. New steps go last, and never change once
+/// released, files of every version having to go through them
+const STEPS: [fn(&mut Map<String, Value>); PREFERENCES_VERSION as usize] = [coerce_input_strings];
+
+/// The preferences saved as true or false by version 0, the frontend included
+const VERSION_0_BOOLEANS: [&str; 12] = [
+    "darkMode", "showFeedIcons", "showArticleThumbnails", "autoStart", "startMinimized", "hideArchivedUnread",
+    "lowResourceMode", "localMetrics", "dequeueRead", "batterySaver", "pruneAfterSync", "notifyNewArticles",
+];
+/// The preferences saved as integers by version 0
+const VERSION_0_INTEGERS: [&str; 9] = [
+    "fastPassMinutes", "backgroundSyncMinutes", "concurrentSyncs", "autostartDelaySeconds", "contentChunkSize",
+    "batteryIntervalFactor", "lowBatteryPercent", "retentionDays", "retentionMaxArticles",
+];
+
+/// The object of a preferences file brought to the current version, without the preferences failing
+/// `Preferences`, and what was done.
+pub fn upgrade(file: Value) -> (Map<String, Value>, PreferencesUpgrade) {
+    let mut object = match file {
+        Value::Object(object) => object,
+        // Nothing could be kept
+        _ => Map::new(),
+    };
+    let from_version = object.remove(VERSION_KEY).and_then(|version| version.as_u64()).unwrap_or(0);
+
+    if from_version <= PREFERENCES_VERSION {
+        for step in &STEPS[from_version as usize..] {
+            step(&mut object);
+        }
+    }
+
+    let mut resets = vec![];
+    object.retain(|key, value| {
+        let alone = Value::Object(Map::from_iter([(key.clone(), value.clone())]));
+        match serde_json::from_value::<Preferences>(alone) {
+            Ok(_) => true,
+            Err(e) => {
+                resets.push(PreferenceReset { key: key.clone(), value: value.to_string(), reason: e.to_string() });
+                false
+            }
+        }
+    });
+
+    let upgrade = PreferencesUpgrade { from_version, version: PREFERENCES_VERSION, read_only: from_version > PREFERENCES_VERSION, resets };
+    (object, upgrade)
+}
+
+/// For a file left as it is, missing or unreadable.
+pub fn unchanged() -> PreferencesUpgrade {
+    PreferencesUpgrade { from_version: PREFERENCES_VERSION, version: PREFERENCES_VERSION, read_only: false, resets: vec![] }
+}
+
+/// The object as saved at the current version.
+pub fn versioned(mut object: Map<String, Value>) -> Value {
+    object.insert(String::from(VERSION_KEY), Value::from(PREFERENCES_VERSION));
+    Value::Object(object)
+}
+
+/// Version 0 settings forms could save the text of their inputs, like "true" or "30", instead of the
+/// boolean or number it stands for.
+fn coerce_input_strings(object: &mut Map<String, Value>) {
+    for (key, value) in object.iter_mut() {
+        let Value::String(text) = value else { continue };
+        let text = text.trim();
+
+        let coerced = if VERSION_0_BOOLEANS.contains(&key.as_str()) {
+            match text.to_ascii_lowercase().as_str() {
+                "true" => Some(Value::Bool(true)),
+                "false" => Some(Value::Bool(false)),
+                _ => None,
+            }
+        } else if VERSION_0_INTEGERS.contains(&key.as_str()) {
+            text.parse::<i64>().ok().map(|number| Value::Number(Number::from(number)))
+        } else {
+            None
+        };
+
+        if let Some(coerced) = coerced {
+            *value = coerced;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(object) => object,
+            _ => panic!("not an object: {}", value),
+        }
+    }
+
+    fn reset_keys(upgrade: &PreferencesUpgrade) -> Vec<&str> {
+        upgrade.resets.iter().map(|reset| reset.key.as_str()).collect()
+    }
+
+    #[test]
+    fn every_version_has_a_step() {
+        assert_eq!(STEPS.len() as u64, PREFERENCES_VERSION);
+    }
+
+    /// `STEPS[n]` applied alone, for every version, as (from version, before, after)
+    #[test]
+    fn steps() {
+        let cases = [
+            (0, json!({ "darkMode": "true", "autoStart": " FALSE ", "lowResourceMode": false }), json!({ "darkMode": true, "autoStart": false, "lowResourceMode": false })),
+            (0, json!({ "retentionDays": "30", "concurrentSyncs": " 4 ", "contentChunkSize": "-1" }), json!({ "retentionDays": 30, "concurrentSyncs": 4, "contentChunkSize": -1 })),
+            // Left for the check to reset
+            (0, json!({ "darkMode": "yes", "retentionDays": "30 days", "fastPassMinutes": "" }), json!({ "darkMode": "yes", "retentionDays": "30 days", "fastPassMinutes": "" })),
+            // Only the known booleans and integers, strings being the type of the others
+            (0, json!({ "stateExchangeFolder": "30", "closeBehavior": "true", "theme": "false" }), json!({ "stateExchangeFolder": "30", "closeBehavior": "true", "theme": "false" })),
+            (0, json!({}), json!({})),
+        ];
+
+        for (version, before, after) in cases {
+            let mut upgraded = object(before.clone());
+            STEPS[version](&mut upgraded);
+            assert_eq!(Value::Object(upgraded), after, "step from version {} of {}", version, before);
+        }
+    }
+
+    /// Files of every version up to the current one, as (file, kept, reset keys)
+    #[test]
+    fn upgrades_from_every_version() {
+        let cases = [
+            (json!({ "darkMode": "true", "retentionDays": "30" }), json!({ "darkMode": true, "retentionDays": 30 }), vec![]),
+            (json!({ "schemaVersion": 0, "lowBatteryPercent": "15" }), json!({ "lowBatteryPercent": 15 }), vec![]),
+            // Version 1 files were written by frontends saving the right types, so their strings stay strings
+            (json!({ "schemaVersion": 1, "retentionDays": 30, "pruneAfterSync": "true" }), json!({ "retentionDays": 30 }), vec!["pruneAfterSync"]),
+        ];
+
+        for (file, kept, resets) in cases {
+            let from_version = file.get(VERSION_KEY).and_then(Value::as_u64).unwrap_or(0);
+            let (upgraded, upgrade) = upgrade(file.clone());
+            assert_eq!(Value::Object(upgraded), kept, "kept from {}", file);
+            assert_eq!(reset_keys(&upgrade), resets, "reset from {}", file);
+            assert_eq!(upgrade.from_version, from_version);
+            assert_eq!(upgrade.version, PREFERENCES_VERSION);
+            assert!(!upgrade.read_only);
+        }
+    }
+
+    #[test]
+    fn future_version_is_read_only() {
+        let file = json!({ "schemaVersion": PREFERENCES_VERSION + 1, "retentionDays": 30, "autoStart": "true", "newPreference": { "a": 1 } });
+
+        let (upgraded, upgrade) = upgrade(file);
+
+        // No step ran, what this version can't read is only left out
+        assert_eq!(Value::Object(upgraded), json!({ "retentionDays": 30, "newPreference": { "a": 1 } }));
+        assert_eq!(reset_keys(&upgrade), vec!["autoStart"]);
+        assert_eq!(upgrade.from_version, PREFERENCES_VERSION + 1);
+        assert!(upgrade.read_only);
+        assert!(upgrade.is_notable());
+    }
+
+    #[test]
+    fn resets_only_the_invalid_preferences() {
+        let file = json!({
+            "schemaVersion": PREFERENCES_VERSION,
+            "retentionDays": 30,
+            "retentionMaxArticles": -5,
+            "lowBatteryPercent": 300,
+            "closeBehavior": "explode",
+            "language": "fr",
+            "stateExchangeFolder": 42,
+            "notifyNewArticles": true,
+            "darkMode": true,
+        });
+
+        let (upgraded, upgrade) = upgrade(file);
+
+        assert_eq!(Value::Object(upgraded.clone()), json!({ "retentionDays": 30, "language": "fr", "notifyNewArticles": true, "darkMode": true }));
+        let mut resets = reset_keys(&upgrade);
+        resets.sort();
+        assert_eq!(resets, vec!["closeBehavior", "lowBatteryPercent", "retentionMaxArticles", "stateExchangeFolder"]);
+        assert!(upgrade.resets.iter().all(|reset| !reset.reason.is_empty()));
+        assert_eq!(upgrade.resets.iter().find(|reset| reset.key == "lowBatteryPercent").map(|reset| reset.value.as_str()), Some("300"));
+        assert!(upgrade.is_notable());
+
+        // What was kept reads as a whole
+        let preferences: Preferences = serde_json::from_value(Value::Object(upgraded)).unwrap();
+        assert_eq!(preferences.retention_days, 30);
+        assert!(preferences.notify_new_articles);
+    }
+
+    #[test]
+    fn unreadable_files_keep_nothing() {
+        for file in [json!(null), json!([1, 2]), json!("preferences"), json!(3)] {
+            let (upgraded, upgrade) = upgrade(file.clone());
+            assert!(upgraded.is_empty(), "kept from {}", file);
+            assert_eq!(upgrade.from_version, 0);
+            assert!(!upgrade.is_notable());
+        }
+    }
+
+    #[test]
+    fn saves_the_current_version() {
+        let saved = versioned(object(json!({ "retentionDays": 30 })));
+
+        assert_eq!(saved, json!({ "schemaVersion": PREFERENCES_VERSION, "retentionDays": 30 }));
+        let (upgraded, upgrade) = upgrade(saved);
+        assert_eq!(Value::Object(upgraded), json!({ "retentionDays": 30 }));
+        assert!(!upgrade.is_notable());
+    }
+}
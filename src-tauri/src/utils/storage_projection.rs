@@ -0,0 +1,241 @@
+//! Projects the database and the unread articles over the coming months, from the aggregates of
+//! `get_storage_stats.sql`. Assumes every feed keeps posting articles of the same size at the pace measured
+//! over the last `MEASURED_DAYS`, the user keeps reading the same share of them, and the retention is
+//! applied as often as needed. The existing articles of a feed are taken as spread evenly since its oldest
+//! one, the indexes and the other tables as growing with the articles.
+
+use crate::structs::feed_storage_stats::FeedStorageStats;
+use crate::structs::storage_projection::StorageProjection;
+use crate::structs::storage_projection_point::StorageProjectionPoint;
+
+pub const MEASURED_DAYS: u32 = 30;
+pub const DAYS_PER_MONTH: u32 = 30;
+pub const MAX_HORIZON_DAYS: u32 = 2 * 365;
+const DAY_MS: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+/// Of a database without articles yet, mostly the search index
+const DEFAULT_OVERHEAD: f64 = 2.0;
+
+struct FeedTrend {
+    articles: f64,
+    unread: f64,
+    bytes: f64,
+    /// Since the oldest article, at least a day
+    kept_days: f64,
+    posted_per_day: f64,
+    /// Posted articles left unread and passing the filter
+    unread_per_day: f64,
+    article_bytes: f64,
+    max_days: Option<f64>,
+    max_articles: Option<f64>,
+}
+
+impl FeedTrend {
+    fn new(stats: &FeedStorageStats, now: i64) -> Self {
+        let days_since = |since: Option<i64>| since.map_or(0.0, |since| ((now - since) as f64 / DAY_MS).max(0.0));
+        // A feed added during the period posted over fewer days
+        let measured_days = days_since(stats.first_posted).clamp(1.0, MEASURED_DAYS as f64);
+        let recent = stats.recent_articles as f64;
+
+        let (posted_per_day, unread_per_day, article_bytes) = if stats.archived || stats.recent_articles == 0 {
+            (0.0, 0.0, 0.0)
+        } else {
+            let posted_per_day = recent / measured_days;
+            let read_share = (stats.recent_read as f64 / recent).min(1.0);
+            let shown_share = stats.recent_shown as f64 / recent;
+            (posted_per_day, posted_per_day * shown_share * (1.0 - read_share), stats.recent_bytes as f64 / recent)
+        };
+
+        FeedTrend {
+            articles: stats.articles as f64,
+            unread: stats.unread as f64,
+            bytes: stats.bytes as f64,
+            kept_days: days_since(stats.oldest_kept_since).max(1.0),
+            posted_per_day,
+            unread_per_day,
+            article_bytes,
+            max_days: stats.max_days.map(|days| days as f64),
+            max_articles: stats.max_articles.map(|articles| articles as f64),
+        }
+    }
+
+    /// Articles, unread articles and bytes `day` days from now.
+    fn at(&self, day: f64) -> (f64, f64, f64) {
+        let (kept_share, posted_days) = match self.max_days {
+            Some(max_days) => (((max_days - day) / self.kept_days).clamp(0.0, 1.0), day.min(max_days)),
+            None => (1.0, day),
+        };
+        let articles = self.articles * kept_share + self.posted_per_day * posted_days;
+        let unread = self.unread * kept_share + self.unread_per_day * posted_days;
+        let bytes = self.bytes * kept_share + self.article_bytes * self.posted_per_day * posted_days;
+
+        // Past the most recent articles kept, as many read and unread ones go
+        let capped_share = self.max_articles
+            .filter(|max_articles| articles > *max_articles)
+            .map_or(1.0, |max_articles| max_articles / articles);
+        (articles * capped_share, unread * capped_share, bytes * capped_share)
+    }
+}
+
+/// Projects `stats` month by month up to `horizon_days`, capped to `MAX_HORIZON_DAYS`. `database_bytes`
+/// is the size of the database today, before the first prune.
+pub fn project(stats: &[FeedStorageStats], database_bytes: u64, now: i64, horizon_days: u32) -> StorageProjection {
+    let horizon_days = horizon_days.clamp(1, MAX_HORIZON_DAYS);
+    let trends: Vec<FeedTrend> = stats.iter().map(|stats| FeedTrend::new(stats, now)).collect();
+
+    let article_bytes: i64 = stats.iter().map(|stats| stats.bytes + stats.pruned_bytes).sum();
+    let (baseline, overhead) = match article_bytes {
+        0 => (database_bytes as f64, DEFAULT_OVERHEAD),
+        _ => (0.0, (database_bytes as f64 / article_bytes as f64).max(1.0)),
+    };
+
+    let months = (0..=horizon_days.div_ceil(DAYS_PER_MONTH))
+        .map(|month| {
+            let day = (month * DAYS_PER_MONTH).min(horizon_days);
+            let (articles, unread, bytes) = trends.iter()
+                .map(|trend| trend.at(day as f64))
+                .fold((0.0, 0.0, 0.0), |total, feed| (total.0 + feed.0, total.1 + feed.1, total.2 + feed.2));
+
+            StorageProjectionPoint {
+                month,
+                day,
+                articles: articles.round() as u64,
+                unread: unread.round() as u64,
+                database_bytes: (baseline + bytes * overhead).round() as u64,
+            }
+        })
+        .collect();
+
+    let pruned_bytes: i64 = stats.iter().map(|stats| stats.pruned_bytes).sum();
+    StorageProjection {
+        measured_days: MEASURED_DAYS,
+        horizon_days,
+        database_bytes,
+        pruned_articles: stats.iter().map(|stats| stats.pruned_articles as u64).sum(),
+        pruned_unread: stats.iter().map(|stats| stats.pruned_unread as u64).sum(),
+        reclaimed_bytes: ((pruned_bytes as f64 * overhead).round() as u64).min(database_bytes),
+        months,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000_000;
+
+    fn days_ago(days: i64) -> Option<i64> {
+        Some(NOW - days * DAY_MS as i64)
+    }
+
+    /// 100 articles of 1000 bytes kept over 30 days, 10 a day posted lately and half of them read.
+    fn feed() -> FeedStorageStats {
+        FeedStorageStats {
+            feed_identifier: String::from("feed"),
+            archived: false,
+            max_days: None,
+            max_articles: None,
+            articles: 100,
+            unread: 40,
+            bytes: 100_000,
+            oldest_kept_since: days_ago(30),
+            first_posted: days_ago(90),
+            recent_articles: 300,
+            recent_shown: 300,
+            recent_read: 150,
+            recent_bytes: 300_000,
+            pruned_articles: 0,
+            pruned_unread: 0,
+            pruned_bytes: 0,
+        }
+    }
+
+    fn series(projection: &StorageProjection) -> Vec<(u32, u64, u64)> {
+        projection.months.iter().map(|point| (point.day, point.articles, point.unread)).collect()
+    }
+
+    #[test]
+    fn projects_the_articles_month_by_month() {
+        let cases = [
+            ("steady", feed(), vec![(0, 100, 40), (30, 400, 190), (60, 700, 340), (90, 1000, 490)]),
+            ("archived", FeedStorageStats { archived: true, ..feed() }, vec![(0, 100, 40), (30, 100, 40), (60, 100, 40), (90, 100, 40)]),
+            // The existing articles go as they age past the retention, while the new ones are kept that long
+            ("max days", FeedStorageStats { max_days: Some(30), ..feed() }, vec![(0, 100, 40), (30, 300, 150), (60, 300, 150), (90, 300, 150)]),
+            ("max articles", FeedStorageStats { max_articles: Some(250), ..feed() }, vec![(0, 100, 40), (30, 250, 119), (60, 250, 121), (90, 250, 123)]),
+            // Added 10 days ago, its 300 articles were posted over these days only
+            ("new", FeedStorageStats { first_posted: days_ago(10), ..feed() }, vec![(0, 100, 40), (30, 1000, 490), (60, 1900, 940), (90, 2800, 1390)]),
+            // Only the articles passing the filter count as unread
+            ("filtered", FeedStorageStats { recent_shown: 150, ..feed() }, vec![(0, 100, 40), (30, 400, 115), (60, 700, 190), (90, 1000, 265)]),
+            ("silent", FeedStorageStats { recent_articles: 0, recent_shown: 0, recent_read: 0, recent_bytes: 0, ..feed() }, vec![(0, 100, 40), (30, 100, 40), (60, 100, 40), (90, 100, 40)]),
+        ];
+
+        for (name, stats, expected) in cases {
+            let projection = project(&[stats], 300_000, NOW, 90);
+            assert_eq!(series(&projection), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn sums_every_feed() {
+        let other = FeedStorageStats { feed_identifier: String::from("other"), ..feed() };
+
+        let projection = project(&[feed(), other], 600_000, NOW, 60);
+        assert_eq!(series(&projection), [(0, 200, 80), (30, 800, 380), (60, 1400, 680)]);
+    }
+
+    #[test]
+    fn ends_the_series_at_the_horizon() {
+        let cases = [
+            (0, vec![0, 1]),
+            (1, vec![0, 1]),
+            (30, vec![0, 30]),
+            (45, vec![0, 30, 45]),
+            (90, vec![0, 30, 60, 90]),
+        ];
+
+        for (horizon_days, expected) in cases {
+            let projection = project(&[feed()], 300_000, NOW, horizon_days);
+            let days: Vec<u32> = projection.months.iter().map(|point| point.day).collect();
+            assert_eq!(days, expected, "{:?}", horizon_days);
+            assert_eq!(projection.horizon_days, *expected.last().unwrap(), "{:?}", horizon_days);
+        }
+
+        let projection = project(&[feed()], 300_000, NOW, u32::MAX);
+        assert_eq!(projection.horizon_days, MAX_HORIZON_DAYS);
+        assert_eq!(projection.months.last().map(|point| point.day), Some(MAX_HORIZON_DAYS));
+        assert_eq!(projection.months.len(), MAX_HORIZON_DAYS.div_ceil(DAYS_PER_MONTH) as usize + 1);
+    }
+
+    #[test]
+    fn grows_the_database_with_its_overhead() {
+        // 200 kB of articles in a 600 kB database, so 3 bytes on disk per byte of article
+        let stats = FeedStorageStats { pruned_articles: 100, pruned_unread: 10, pruned_bytes: 100_000, ..feed() };
+
+        let projection = project(&[stats], 600_000, NOW, 30);
+        let sizes: Vec<u64> = projection.months.iter().map(|point| point.database_bytes).collect();
+        assert_eq!(sizes, [300_000, 1_200_000]);
+        assert_eq!((projection.pruned_articles, projection.pruned_unread, projection.reclaimed_bytes), (100, 10, 300_000));
+    }
+
+    #[test]
+    fn grows_an_empty_database_from_its_size() {
+        let stats = FeedStorageStats { articles: 0, unread: 0, bytes: 0, oldest_kept_since: None, ..feed() };
+
+        let projection = project(&[stats], 50_000, NOW, 30);
+        let sizes: Vec<u64> = projection.months.iter().map(|point| point.database_bytes).collect();
+        // The 300 kB posted this month, twice that with the default overhead
+        assert_eq!(sizes, [50_000, 650_000]);
+        assert_eq!(projection.reclaimed_bytes, 0);
+
+        let projection = project(&[], 50_000, NOW, 30);
+        assert_eq!(series(&projection), [(0, 0, 0), (30, 0, 0)]);
+        assert_eq!(projection.months.last().map(|point| point.database_bytes), Some(50_000));
+    }
+
+    #[test]
+    fn never_reclaims_more_than_the_database() {
+        let stats = FeedStorageStats { articles: 0, unread: 0, bytes: 0, pruned_articles: 100, pruned_bytes: 100_000, ..feed() };
+
+        let projection = project(&[stats], 40_000, NOW, 30);
+        assert_eq!(projection.reclaimed_bytes, 40_000);
+    }
+}
@@ -0,0 +1,346 @@
+//! Dates and times as shown to the user, in the language of the preferences and the time zone of the
+//! system, daylight saving time included.
+//!
+//! What groups by day or week, like the `before:` and `after:` search filters, the local metrics and the
+//! names of the exports, goes through `today`, `start_of_day` and `week_start`, for "today" to mean the
+//! same everywhere. Machine-readable timestamps, like those of the OPML and archive exports, stay in UTC.
+
+use std::fmt::Display;
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, TimeZone};
+use crate::enums::language::Language;
+use crate::enums::timestamp_style::TimestampStyle;
+
+/// Closer than this to now is "just now"
+const NOW_SECONDS: i64 = 45;
+const MINUTE_SECONDS: i64 = 60;
+const HOUR_SECONDS: i64 = 60 * MINUTE_SECONDS;
+const DAY_SECONDS: i64 = 24 * HOUR_SECONDS;
+const FRENCH_MONTHS: [&str; 12] = ["janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.", "déc."];
+const SPANISH_MONTHS: [&str; 12] = ["ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic"];
+
+#[derive(Clone, Copy)]
+enum Unit {
+    Minute,
+    Hour,
+    Day,
+    Month,
+    Year,
+}
+
+/// The timestamp in milliseconds written in `style`, empty when out of range.
+pub fn format_timestamp(timestamp_ms: i64, style: TimestampStyle, language: Language) -> String {
+    let Some(time) = DateTime::from_timestamp_millis(timestamp_ms) else {
+        return String::new();
+    };
+
+    format_in(&time.with_timezone(&Local), &Local::now(), style, language.resolve())
+}
+
+/// `time` written in `style` as of `now`, both in the time zone they are shown in.
+fn format_in<Tz: TimeZone>(time: &DateTime<Tz>, now: &DateTime<Tz>, style: TimestampStyle, language: Language) -> String
+where
+    Tz::Offset: Display,
+{
+    match style {
+        TimestampStyle::Relative => relative(time, now, language),
+        TimestampStyle::Date => date(time, language),
+        TimestampStyle::DateTime => format!("{}, {}", date(time, language), clock(time, language)),
+        TimestampStyle::Time => clock(time, language),
+    }
+}
+
+/// The local date of now.
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// When `day` starts in the local time zone, in milliseconds. That is at the end of the gap on the days
+/// daylight saving time starts at midnight, as in some zones of South America.
+pub fn start_of_day(day: NaiveDate) -> i64 {
+    start_of_day_in(day, &Local)
+}
+
+fn start_of_day_in<Tz: TimeZone>(day: NaiveDate, zone: &Tz) -> i64 {
+    (0..24)
+        .find_map(|hour| zone.from_local_datetime(&day.and_hms_opt(hour, 0, 0)?).earliest())
+        .map(|time| time.timestamp_millis())
+        .unwrap_or_else(|| day.and_time(NaiveTime::MIN).and_utc().timestamp_millis())
+}
+
+/// The first day of the week of `day`, for the statistics grouped by week.
+pub fn week_start(day: NaiveDate, language: Language) -> NaiveDate {
+    day.week(language.week_start()).first_day()
+}
+
+/// Days are counted between local dates rather than by 24 hours, so the evening before is "1 day ago"
+/// whatever the time and daylight saving time changes in between.
+fn relative<Tz: TimeZone>(time: &DateTime<Tz>, now: &DateTime<Tz>, language: Language) -> String {
+    let seconds = (now.timestamp_millis() - time.timestamp_millis()) / 1000;
+    let past = seconds >= 0;
+    let seconds = seconds.abs();
+    let days = (now.date_naive() - time.date_naive()).num_days().abs().max(1);
+
+    let (count, unit) = match seconds {
+        seconds if seconds < NOW_SECONDS => return String::from(match language {
+            Language::French => "à l'instant",
+            Language::German => "gerade eben",
+            Language::Spanish => "ahora mismo",
+            Language::Auto | Language::English => "just now",
+        }),
+        seconds if seconds < 45 * MINUTE_SECONDS => (((seconds + MINUTE_SECONDS / 2) / MINUTE_SECONDS).max(1), Unit::Minute),
+        seconds if seconds < DAY_SECONDS => (((seconds + HOUR_SECONDS / 2) / HOUR_SECONDS).clamp(1, 23), Unit::Hour),
+        _ if days < 30 => (days, Unit::Day),
+        _ if days < 365 => (days / 30, Unit::Month),
+        _ => (days / 365, Unit::Year),
+    };
+
+    let amount = format!("{} {}", count, unit_name(unit, language.is_singular(count), language));
+    match (language, past) {
+        (Language::French, true) => format!("il y a {}", amount),
+        (Language::French, false) => format!("dans {}", amount),
+        (Language::German, true) => format!("vor {}", amount),
+        (Language::German, false) => format!("in {}", amount),
+        (Language::Spanish, true) => format!("hace {}", amount),
+        (Language::Spanish, false) => format!("dentro de {}", amount),
+        (Language::Auto | Language::English, true) => format!("{} ago", amount),
+        (Language::Auto | Language::English, false) => format!("in {}", amount),
+    }
+}
+
+/// German takes the dative after "vor" and "in", the same for both.
+fn unit_name(unit: Unit, singular: bool, language: Language) -> &'static str {
+    let (one, other) = match (language, unit) {
+        (Language::French, Unit::Minute) => ("minute", "minutes"),
+        (Language::French, Unit::Hour) => ("heure", "heures"),
+        (Language::French, Unit::Day) => ("jour", "jours"),
+        (Language::French, Unit::Month) => ("mois", "mois"),
+        (Language::French, Unit::Year) => ("an", "ans"),
+        (Language::German, Unit::Minute) => ("Minute", "Minuten"),
+        (Language::German, Unit::Hour) => ("Stunde", "Stunden"),
+        (Language::German, Unit::Day) => ("Tag", "Tagen"),
+        (Language::German, Unit::Month) => ("Monat", "Monaten"),
+        (Language::German, Unit::Year) => ("Jahr", "Jahren"),
+        (Language::Spanish, Unit::Minute) => ("minuto", "minutos"),
+        (Language::Spanish, Unit::Hour) => ("hora", "horas"),
+        (Language::Spanish, Unit::Day) => ("día", "días"),
+        (Language::Spanish, Unit::Month) => ("mes", "meses"),
+        (Language::Spanish, Unit::Year) => ("año", "años"),
+        (Language::Auto | Language::English, Unit::Minute) => ("minute", "minutes"),
+        (Language::Auto | Language::English, Unit::Hour) => ("hour", "hours"),
+        (Language::Auto | Language::English, Unit::Day) => ("day", "days"),
+        (Language::Auto | Language::English, Unit::Month) => ("month", "months"),
+        (Language::Auto | Language::English, Unit::Year) => ("year", "years"),
+    };
+    if singular { one } else { other }
+}
+
+fn date<Tz: TimeZone>(time: &DateTime<Tz>, language: Language) -> String
+where
+    Tz::Offset: Display,
+{
+    let month = time.month0() as usize;
+    match language {
+        Language::French => format!("{} {} {}", time.day(), FRENCH_MONTHS[month], time.year()),
+        Language::German => time.format("%d.%m.%Y").to_string(),
+        Language::Spanish => format!("{} {} {}", time.day(), SPANISH_MONTHS[month], time.year()),
+        Language::Auto | Language::English => time.format("%b %-d, %Y").to_string(),
+    }
+}
+
+fn clock<Tz: TimeZone>(time: &DateTime<Tz>, language: Language) -> String
+where
+    Tz::Offset: Display,
+{
+    match language {
+        Language::Auto | Language::English => time.format("%-I:%M %p").to_string(),
+        Language::French | Language::German | Language::Spanish => time.format("%H:%M").to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt;
+    use chrono::{FixedOffset, LocalResult, NaiveDateTime, Offset};
+    use super::*;
+
+    const LANGUAGES: [Language; 4] = [Language::English, Language::French, Language::German, Language::Spanish];
+
+    /// Time zones changing to summer time on the dates of the tests only, for the snapshots not to depend
+    /// on the zone of the machine.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Zone {
+        /// Europe/Paris in 2024, changing at 01:00 UTC
+        Paris,
+        /// America/Sao_Paulo in 2018-19, its summer time starting at midnight
+        SaoPaulo,
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    struct ZoneOffset {
+        zone: Zone,
+        offset: FixedOffset,
+    }
+
+    impl Offset for ZoneOffset {
+        fn fix(&self) -> FixedOffset {
+            self.offset
+        }
+    }
+
+    impl fmt::Display for ZoneOffset {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            self.offset.fmt(f)
+        }
+    }
+
+    impl Zone {
+        /// The standard and summer offsets in hours, and when summer time starts and ends in UTC.
+        fn rules(&self) -> (i32, i32, &'static str, &'static str) {
+            match self {
+                Zone::Paris => (1, 2, "2024-03-31T01:00:00", "2024-10-27T01:00:00"),
+                Zone::SaoPaulo => (-3, -2, "2018-11-04T03:00:00", "2019-02-17T02:00:00"),
+            }
+        }
+
+        fn offset(&self, hours: i32) -> ZoneOffset {
+            ZoneOffset { zone: *self, offset: FixedOffset::east_opt(hours * 3600).unwrap() }
+        }
+
+        fn local(&self, local: &str) -> DateTime<Zone> {
+            let local = NaiveDateTime::parse_from_str(local, "%Y-%m-%d %H:%M:%S").unwrap();
+            self.from_local_datetime(&local).earliest().unwrap()
+        }
+    }
+
+    impl TimeZone for Zone {
+        type Offset = ZoneOffset;
+
+        fn from_offset(offset: &ZoneOffset) -> Self {
+            offset.zone
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<ZoneOffset> {
+            self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+        }
+
+        /// Ambiguous in the hour repeated when summer time ends, none in the one skipped when it starts.
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<ZoneOffset> {
+            let (standard, summer, _, _) = self.rules();
+            let valid: Vec<ZoneOffset> = [summer, standard].into_iter()
+                .map(|hours| self.offset(hours))
+                .filter(|offset| self.offset_from_utc_datetime(&(*local - offset.offset)).offset == offset.offset)
+                .collect();
+            match valid[..] {
+                [] => LocalResult::None,
+                [offset] => LocalResult::Single(offset),
+                [earliest, latest, ..] => LocalResult::Ambiguous(earliest, latest),
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> ZoneOffset {
+            self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> ZoneOffset {
+            let (standard, summer, starts, ends) = self.rules();
+            let summer_time = (starts.parse::<NaiveDateTime>().unwrap()..ends.parse().unwrap()).contains(utc);
+            self.offset(if summer_time { summer } else { standard })
+        }
+    }
+
+    fn formatted(time: &DateTime<Zone>, now: &DateTime<Zone>, style: TimestampStyle) -> Vec<String> {
+        LANGUAGES.iter().map(|language| format_in(time, now, style, *language)).collect()
+    }
+
+    #[test]
+    fn writes_dates_and_times_in_each_language() {
+        let now = Zone::Paris.local("2024-08-01 12:00:00");
+        let cases = [
+            ("2024-01-31 14:05:00", TimestampStyle::Date, ["Jan 31, 2024", "31 janv. 2024", "31.01.2024", "31 ene 2024"]),
+            ("2024-01-31 14:05:00", TimestampStyle::DateTime, ["Jan 31, 2024, 2:05 PM", "31 janv. 2024, 14:05", "31.01.2024, 14:05", "31 ene 2024, 14:05"]),
+            ("2024-01-31 14:05:00", TimestampStyle::Time, ["2:05 PM", "14:05", "14:05", "14:05"]),
+            // In summer time
+            ("2024-07-04 09:30:00", TimestampStyle::DateTime, ["Jul 4, 2024, 9:30 AM", "4 juil. 2024, 09:30", "04.07.2024, 09:30", "4 jul 2024, 09:30"]),
+            ("2024-02-29 00:00:00", TimestampStyle::DateTime, ["Feb 29, 2024, 12:00 AM", "29 févr. 2024, 00:00", "29.02.2024, 00:00", "29 feb 2024, 00:00"]),
+            ("2024-09-15 23:59:00", TimestampStyle::Date, ["Sep 15, 2024", "15 sept. 2024", "15.09.2024", "15 sept 2024"]),
+        ];
+
+        for (local, style, expected) in cases {
+            assert_eq!(formatted(&Zone::Paris.local(local), &now, style), expected, "{:?}", (local, style));
+        }
+    }
+
+    #[test]
+    fn counts_the_time_elapsed_across_daylight_saving_time() {
+        let cases = [
+            // Summer time started at 02:00, the night being an hour shorter
+            ("2024-03-31 12:00:00", "2024-03-31 11:59:30", ["just now", "à l'instant", "gerade eben", "ahora mismo"]),
+            ("2024-03-31 12:00:00", "2024-03-31 11:59:00", ["1 minute ago", "il y a 1 minute", "vor 1 Minute", "hace 1 minuto"]),
+            ("2024-03-31 12:00:00", "2024-03-31 11:55:00", ["5 minutes ago", "il y a 5 minutes", "vor 5 Minuten", "hace 5 minutos"]),
+            ("2024-03-31 12:00:00", "2024-03-31 01:00:00", ["10 hours ago", "il y a 10 heures", "vor 10 Stunden", "hace 10 horas"]),
+            ("2024-03-31 12:00:00", "2024-03-30 23:00:00", ["12 hours ago", "il y a 12 heures", "vor 12 Stunden", "hace 12 horas"]),
+            ("2024-03-31 12:00:00", "2024-03-30 08:00:00", ["1 day ago", "il y a 1 jour", "vor 1 Tag", "hace 1 día"]),
+            // Summer time ended at 03:00, the night being an hour longer
+            ("2024-10-27 12:00:00", "2024-10-27 01:00:00", ["12 hours ago", "il y a 12 heures", "vor 12 Stunden", "hace 12 horas"]),
+            ("2024-10-27 12:00:00", "2024-10-26 13:00:00", ["1 day ago", "il y a 1 jour", "vor 1 Tag", "hace 1 día"]),
+            ("2024-10-27 12:00:00", "2024-10-22 20:00:00", ["5 days ago", "il y a 5 jours", "vor 5 Tagen", "hace 5 días"]),
+            ("2024-10-27 12:00:00", "2024-10-29 08:00:00", ["in 2 days", "dans 2 jours", "in 2 Tagen", "dentro de 2 días"]),
+            ("2024-10-27 12:00:00", "2024-10-27 12:20:00", ["in 20 minutes", "dans 20 minutes", "in 20 Minuten", "dentro de 20 minutos"]),
+            ("2024-10-27 12:00:00", "2024-08-20 12:00:00", ["2 months ago", "il y a 2 mois", "vor 2 Monaten", "hace 2 meses"]),
+            ("2024-10-27 12:00:00", "2024-09-20 12:00:00", ["1 month ago", "il y a 1 mois", "vor 1 Monat", "hace 1 mes"]),
+            ("2024-10-27 12:00:00", "2023-10-01 12:00:00", ["1 year ago", "il y a 1 an", "vor 1 Jahr", "hace 1 año"]),
+            ("2024-10-27 12:00:00", "2022-10-01 12:00:00", ["2 years ago", "il y a 2 ans", "vor 2 Jahren", "hace 2 años"]),
+        ];
+
+        for (now, time, expected) in cases {
+            let (now, time) = (Zone::Paris.local(now), Zone::Paris.local(time));
+            assert_eq!(formatted(&time, &now, TimestampStyle::Relative), expected, "{:?}", (now, time));
+        }
+    }
+
+    #[test]
+    fn starts_the_days_when_they_start_locally() {
+        let hour_ms = 3600 * 1000;
+        let utc = |utc: &str| utc.parse::<NaiveDateTime>().unwrap().and_utc().timestamp_millis();
+        let cases = [
+            (Zone::Paris, "2024-01-31", "2024-01-30T23:00:00", 24),
+            (Zone::Paris, "2024-03-31", "2024-03-30T23:00:00", 23),
+            (Zone::Paris, "2024-07-04", "2024-07-03T22:00:00", 24),
+            (Zone::Paris, "2024-10-27", "2024-10-26T22:00:00", 25),
+            // Midnight is skipped, the day starting at 01:00
+            (Zone::SaoPaulo, "2018-11-04", "2018-11-04T03:00:00", 23),
+            (Zone::SaoPaulo, "2019-02-16", "2019-02-16T02:00:00", 25),
+            (Zone::SaoPaulo, "2019-02-17", "2019-02-17T03:00:00", 24),
+        ];
+
+        for (zone, day, start, hours) in cases {
+            let day: NaiveDate = day.parse().unwrap();
+            let next = day.succ_opt().unwrap();
+            assert_eq!(start_of_day_in(day, &zone), utc(start), "{:?}", (zone, day));
+            assert_eq!(start_of_day_in(next, &zone) - start_of_day_in(day, &zone), hours * hour_ms, "{:?}", (zone, day));
+        }
+    }
+
+    #[test]
+    fn starts_the_weeks_as_in_each_language() {
+        let cases = [
+            // A Wednesday, a Sunday and a Monday
+            ("2024-01-31", ["2024-01-28", "2024-01-29", "2024-01-29", "2024-01-29"]),
+            ("2024-02-04", ["2024-02-04", "2024-01-29", "2024-01-29", "2024-01-29"]),
+            ("2024-04-01", ["2024-03-31", "2024-04-01", "2024-04-01", "2024-04-01"]),
+            // Across new year
+            ("2025-01-01", ["2024-12-29", "2024-12-30", "2024-12-30", "2024-12-30"]),
+        ];
+
+        for (day, expected) in cases {
+            let day: NaiveDate = day.parse().unwrap();
+            let starts: Vec<String> = LANGUAGES.iter().map(|language| week_start(day, *language).to_string()).collect();
+            assert_eq!(starts, expected, "{:?}", day);
+        }
+    }
+
+    #[test]
+    fn writes_nothing_out_of_range() {
+        assert_eq!(format_timestamp(i64::MAX, TimestampStyle::DateTime, Language::English), "");
+    }
+}
@@ -0,0 +1,112 @@
+use std::sync::LazyLock;
+use encoding_rs::WINDOWS_1252;
+use quick_xml::escape::unescape;
+use regex::{Captures, Regex};
+
+/// Longer titles are cut for display, the whole one being kept as the original title.
+pub const MAX_TITLE_CHARS: usize = 300;
+/// Entities encoded several times over, like `&amp;amp;#8217;`, are decoded this many times at most.
+const MAX_DECODING_PASSES: usize = 4;
+
+static ENTITY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"&(#[0-9]{1,7}|#[xX][0-9a-fA-F]{1,6}|[A-Za-z][A-Za-z0-9]{1,31});").unwrap());
+static CDATA: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<!\[CDATA\[(.*?)\]\]>").unwrap());
+// Only actual HTML tags, so titles like "Vec<T> explained" keep their angle brackets
+static BLOCK_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)</?(br|p|div|li|h[1-6])(\s[^<>]*)?/?>").unwrap());
+static INLINE_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)</?(a|abbr|b|big|cite|code|del|em|font|i|img|ins|kbd|mark|q|s|small|span|strike|strong|sub|sup|time|tt|u)(\s[^<>]*)?/?>").unwrap());
+
+/// The title as text: tags stripped, entities decoded however many times they were encoded, and
+/// whitespace collapsed. Dedup keys are computed from it, so differently encoded copies of a title match.
+pub fn clean_title(raw: &str) -> String {
+    let mut title = CDATA.replace_all(raw, "$1").into_owned();
+    for _ in 0..MAX_DECODING_PASSES {
+        let decoded = decode_entities(&title);
+        if decoded == title {
+            break;
+        }
+        title = decoded;
+    }
+
+    let title = BLOCK_TAG.replace_all(&title, " ");
+    let title = INLINE_TAG.replace_all(&title, "");
+
+    title.chars()
+        // Zero-width characters and soft hyphens, invisible but breaking sorting and matching
+        .filter(|c| !matches!(c, '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}' | '\u{00AD}'))
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// The title shown and searched: cleaned, without the prefixes of its feed, then cut to `MAX_TITLE_CHARS`.
+/// Prefixes match regardless of case, along with the separator following them. A title made only of
+/// a prefix keeps it.
+pub fn display_title(raw: &str, prefixes: &[String]) -> String {
+    let cleaned = clean_title(raw);
+
+    let mut title = cleaned.as_str();
+    while let Some(rest) = prefixes.iter()
+        .map(|prefix| prefix.trim())
+        .filter(|prefix| !prefix.is_empty())
+        .find_map(|prefix| strip_prefix_ignore_case(title, prefix))
+    {
+        title = rest.trim_start_matches(|c: char| c.is_whitespace() || matches!(c, ':' | '|' | '-' | '–' | '—'));
+    }
+    if title.is_empty() {
+        title = cleaned.as_str();
+    }
+
+    truncate(title, MAX_TITLE_CHARS)
+}
+
+/// A prefix ending with a letter or digit only matches a whole word, "Ad" leaving "Adobe" alone.
+fn strip_prefix_ignore_case<'a>(title: &'a str, prefix: &str) -> Option<&'a str> {
+    let end = title.char_indices().nth(prefix.chars().count()).map_or(title.len(), |(index, _)| index);
+    if title[..end].to_lowercase() != prefix.to_lowercase() {
+        return None;
+    }
+
+    let rest = &title[end..];
+    let splits_word = prefix.ends_with(char::is_alphanumeric) && rest.starts_with(char::is_alphanumeric);
+    (!splits_word).then_some(rest)
+}
+
+/// Cut at the last space before the limit when there is one not too far back, the ellipsis included in the limit.
+fn truncate(title: &str, max_chars: usize) -> String {
+    if title.chars().count() <= max_chars {
+        return title.to_string();
+    }
+
+    let end = title.char_indices().nth(max_chars - 1).map_or(title.len(), |(index, _)| index);
+    let cut = &title[..end];
+    let cut = match cut.rfind(' ') {
+        Some(space) if cut[space..].chars().count() < max_chars / 5 => &cut[..space],
+        _ => cut,
+    };
+    format!("{}…", cut.trim_end())
+}
+
+/// Leaves the unknown entities and stray ampersands as they are, unlike an XML unescape.
+fn decode_entities(text: &str) -> String {
+    ENTITY.replace_all(text, |captures: &Captures| {
+        let entity = &captures[1];
+        let decoded = match entity.strip_prefix('#') {
+            Some(number) => match number.strip_prefix(['x', 'X']) {
+                Some(hexadecimal) => u32::from_str_radix(hexadecimal, 16).ok(),
+                None => number.parse::<u32>().ok(),
+            }.and_then(decode_code_point),
+            None => unescape(&captures[0]).ok().map(|decoded| decoded.into_owned()),
+        };
+        decoded.unwrap_or_else(|| captures[0].to_string())
+    }).into_owned()
+}
+
+/// Code points 128 to 159 are control characters, feeds using them mean the Windows-1252 punctuation.
+fn decode_code_point(code_point: u32) -> Option<String> {
+    match code_point {
+        0 => None,
+        128..=159 => Some(WINDOWS_1252.decode_without_bom_handling(&[code_point as u8]).0.into_owned()),
+        _ => char::from_u32(code_point).map(String::from),
+    }
+}
@@ -0,0 +1,141 @@
+use tauri::{CustomMenuItem, SystemTrayMenu};
+use crate::enums::close_behavior::CloseBehavior;
+use crate::enums::tray_status::TrayStatus;
+use crate::structs::power_policy::PowerPolicy;
+
+pub const ADD_FROM_CLIPBOARD: &str = "add_from_clipboard";
+pub const RUN_DIAGNOSTICS: &str = "run_diagnostics";
+
+/// Starts with why syncing is reduced when it is, e.g. on battery. Adding from the clipboard is disabled
+/// until the database is ready.
+pub fn tray_menu(policy: &PowerPolicy, database_ready: bool) -> SystemTrayMenu {
+    let mut menu = SystemTrayMenu::new();
+    if let Some(reason) = &policy.reason {
+        menu = menu.add_item(CustomMenuItem::new("power".to_string(), format!("Syncing less often: {}", reason)).disabled());
+    }
+
+    let mut add_from_clipboard = CustomMenuItem::new(ADD_FROM_CLIPBOARD.to_string(), "Add feed from clipboard");
+    if !database_ready {
+        add_from_clipboard = add_from_clipboard.disabled();
+    }
+
+    menu
+        .add_item(CustomMenuItem::new("sync".to_string(), "Sync now"))
+        .add_item(add_from_clipboard)
+        .add_item(CustomMenuItem::new("show".to_string(), "Show Alduin"))
+        .add_item(CustomMenuItem::new(RUN_DIAGNOSTICS.to_string(), "Run diagnostics"))
+        .add_item(CustomMenuItem::new("quit".to_string(), "Quit"))
+}
+
+/// Whether closing the main window hides it to the tray instead of quitting.
+pub fn close_to_tray(behavior: CloseBehavior, status: TrayStatus) -> bool {
+    match behavior {
+        CloseBehavior::Tray => true,
+        CloseBehavior::Quit => false,
+        // An unknown status keeps the usual behavior, the probe can't run on every setup
+        CloseBehavior::Auto => status != TrayStatus::Unavailable,
+    }
+}
+
+/// The event telling the windows the tray status changed, none when it is unknown again.
+pub fn status_event(status: TrayStatus) -> Option<&'static str> {
+    match status {
+        TrayStatus::Available => Some("tray:available"),
+        TrayStatus::Unavailable => Some("tray:unavailable"),
+        TrayStatus::Unknown => None,
+    }
+}
+
+/// The tray icon is created without error even when no StatusNotifier host is there to show it,
+/// so the host is looked up on the session bus.
+/// Setups only providing a legacy XEmbed tray are reported unavailable, hence the close behavior preference.
+#[cfg(target_os = "linux")]
+pub fn probe_tray_host() -> TrayStatus {
+    let output = std::process::Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.kde.StatusNotifierWatcher",
+            "/StatusNotifierWatcher",
+            "org.freedesktop.DBus.Properties.Get",
+            "string:org.kde.StatusNotifierWatcher",
+            "string:IsStatusNotifierHostRegistered",
+        ])
+        .output();
+
+    match output {
+        Ok(output) => host_status(output.status.success(), &String::from_utf8_lossy(&output.stdout)),
+        Err(_) => TrayStatus::Unknown,
+    }
+}
+
+/// Reads the reply of the StatusNotifierWatcher, a failure meaning there is no watcher on the bus.
+#[cfg(any(target_os = "linux", test))]
+fn host_status(success: bool, reply: &str) -> TrayStatus {
+    if success && reply.contains("boolean true") {
+        TrayStatus::Available
+    } else {
+        TrayStatus::Unavailable
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn probe_tray_host() -> TrayStatus {
+    TrayStatus::Available
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::structs::tray_monitor::TrayMonitor;
+    use super::*;
+
+    #[test]
+    fn reads_the_watcher_reply() {
+        let cases = [
+            (true, "method return time=1 sender=:1.2 -> destination=:1.9 serial=4 reply_serial=2\n   variant       boolean true\n", TrayStatus::Available),
+            (true, "   variant       boolean false\n", TrayStatus::Unavailable),
+            // org.freedesktop.DBus.Error.ServiceUnknown
+            (false, "", TrayStatus::Unavailable),
+        ];
+
+        for (success, reply, status) in cases {
+            assert_eq!(host_status(success, reply), status, "{}", reply);
+        }
+    }
+
+    #[test]
+    fn follows_the_probes() {
+        use TrayStatus::*;
+
+        let monitor = TrayMonitor::default();
+        assert!(close_to_tray(CloseBehavior::Auto, monitor.status()));
+
+        // The host shows up late in the session, goes away with a crashed panel and comes back
+        let probes = [
+            (Unknown, None, true),
+            (Unavailable, Some((Unknown, Some("tray:unavailable"))), false),
+            (Unavailable, None, false),
+            (Available, Some((Unavailable, Some("tray:available"))), true),
+            (Available, None, true),
+            (Unavailable, Some((Available, Some("tray:unavailable"))), false),
+            // dbus-send went missing, the last status isn't worth keeping
+            (Unknown, Some((Unavailable, None)), true),
+            (Available, Some((Unknown, Some("tray:available"))), true),
+        ];
+
+        for (probed, change, hides) in probes {
+            let previous = monitor.update(probed);
+            assert_eq!(previous.map(|previous| (previous, status_event(probed))), change, "{:?}", probed);
+            assert_eq!(monitor.status(), probed);
+            assert_eq!(close_to_tray(CloseBehavior::Auto, monitor.status()), hides, "{:?}", probed);
+        }
+    }
+
+    #[test]
+    fn close_behavior_overrides_the_status() {
+        for status in [TrayStatus::Available, TrayStatus::Unavailable, TrayStatus::Unknown] {
+            assert!(close_to_tray(CloseBehavior::Tray, status));
+            assert!(!close_to_tray(CloseBehavior::Quit, status));
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const RANDOM_BITS: u32 = 80;
+
+/// Time and random part of the last ULID, to keep the ones of the same millisecond in order.
+static LAST: Mutex<(u64, u128)> = Mutex::new((0, 0));
+
+/// A ULID: 26 characters sorting like the millisecond they were made at, then like their random part.
+/// The ones made in the same millisecond, or while the clock went back, increment the previous one,
+/// so they sort in the order they were made.
+pub fn ulid() -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default();
+
+    let mut last = LAST.lock().unwrap();
+    let (time, random) = if now > last.0 {
+        (now, random_bits())
+    } else {
+        (last.0, (last.1 + 1) & ((1 << RANDOM_BITS) - 1))
+    };
+    *last = (time, random);
+
+    encode(((time as u128) << RANDOM_BITS) | random)
+}
+
+/// Seeded by the OS through the hasher keys, which is enough to tell devices apart.
+fn random_bits() -> u128 {
+    let mut bits = 0u128;
+    for _ in 0..2 {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(bits);
+        bits = (bits << 64) | hasher.finish() as u128;
+    }
+    bits & ((1 << RANDOM_BITS) - 1)
+}
+
+fn encode(value: u128) -> String {
+    (0..26).rev()
+        .map(|position| CROCKFORD[((value >> (position * 5)) & 0x1f) as usize] as char)
+        .collect()
+}
+
+/// Whether `text` is a ULID as made by `ulid`.
+pub fn is_ulid(text: &str) -> bool {
+    text.len() == 26 && text.bytes().all(|byte| CROCKFORD.contains(&byte))
+}
@@ -0,0 +1,140 @@
+use url::Url;
+
+/// Reduces a URL to the parts identifying the resource across host variants: the host is
+/// lowercased, punycode-encoded and stripped of `www.`, while the scheme and default ports are dropped.
+pub fn canonical_url(url: &str) -> Option<String> {
+    let parsed = Url::parse(url.trim()).ok()?;
+    let host = parsed.host_str()?;
+    let host = host.strip_prefix("www.").unwrap_or(host);
+
+    let mut canonical = match parsed.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    };
+    canonical.push_str(parsed.path().trim_end_matches('/'));
+    if let Some(query) = parsed.query() {
+        canonical.push('?');
+        canonical.push_str(query);
+    }
+
+    Some(canonical)
+}
+
+/// Query parameters only telling where a visit comes from, along with every `utm_*` one.
+const TRACKING_PARAMETERS: [&str; 13] = [
+    "fbclid", "gclid", "dclid", "msclkid", "yclid", "igshid", "mc_cid", "mc_eid", "_hsenc", "_hsmi", "mkt_tok", "ref_src", "spm",
+];
+
+/// Drops the tracking parameters of a URL, leaving the URL untouched when it has none.
+pub fn clean_url(url: &str) -> Option<String> {
+    let mut parsed = Url::parse(url.trim()).ok()?;
+
+    let pairs: Vec<(String, String)> = parsed.query_pairs().into_owned().collect();
+    let kept: Vec<&(String, String)> = pairs.iter()
+        .filter(|(name, _)| !name.starts_with("utm_") && !TRACKING_PARAMETERS.contains(&name.as_str()))
+        .collect();
+
+    if kept.len() < pairs.len() {
+        if kept.is_empty() {
+            parsed.set_query(None);
+        } else {
+            parsed.query_pairs_mut().clear().extend_pairs(kept);
+        }
+    }
+
+    Some(parsed.to_string())
+}
+
+/// Reduces a link to what article_links stores: the host without `www.` and the path without its
+/// trailing slash, the query being dropped to bound the storage.
+pub fn link_key(url: &Url) -> Option<(String, String)> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+
+    let host = url.host_str()?.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host).to_string();
+    let path = url.path().trim_end_matches('/').to_string();
+
+    Some((host, path))
+}
+
+/// The web address a text is, like one copied from the address bar. Addresses without a scheme, like
+/// `example.com/feed`, are taken as HTTPS when their host has a dot.
+pub fn web_url(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() || text.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let url = match Url::parse(text) {
+        Ok(url) => url,
+        Err(url::ParseError::RelativeUrlWithoutBase) => Url::parse(&format!("https://{}", text)).ok()
+            .filter(|url| url.host_str().is_some_and(|host| host.contains('.')))?,
+        Err(_) => return None,
+    };
+    matches!(url.scheme(), "http" | "https").then(|| url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_urls() {
+        let cases = [
+            ("https://example.com/feed/", Some("example.com/feed")),
+            ("http://www.example.com/feed", Some("example.com/feed")),
+            ("  https://WWW.Example.COM/Feed  ", Some("example.com/Feed")),
+            ("https://example.com:443/feed", Some("example.com/feed")),
+            ("https://example.com:8443/feed", Some("example.com:8443/feed")),
+            ("https://example.com/feed?format=rss#top", Some("example.com/feed?format=rss")),
+            ("https://www.bücher.example/", Some("xn--bcher-kva.example")),
+            ("https://xn--bcher-kva.example", Some("xn--bcher-kva.example")),
+            ("https://BÜCHER.example/feed", Some("xn--bcher-kva.example/feed")),
+            // Only a leading www. is a host variant
+            ("https://blog.www.example.com/", Some("blog.www.example.com")),
+            ("mailto:feed@example.com", None),
+            ("not a url", None),
+        ];
+
+        for (url, expected) in cases {
+            assert_eq!(canonical_url(url).as_deref(), expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn link_keys() {
+        let cases = [
+            ("https://www.example.com/post/", Some(("example.com", "/post"))),
+            ("http://Example.COM/Post?utm_source=feed#comments", Some(("example.com", "/Post"))),
+            ("https://www.Bücher.example/", Some(("xn--bcher-kva.example", ""))),
+            ("ftp://example.com/file", None),
+            ("mailto:someone@example.com", None),
+        ];
+
+        for (url, expected) in cases {
+            let expected = expected.map(|(host, path)| (host.to_string(), path.to_string()));
+            assert_eq!(link_key(&Url::parse(url).unwrap()), expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn clean_urls() {
+        let cases = [
+            ("https://example.com/post?id=1&utm_source=feed&utm_medium=rss&fbclid=abc", Some("https://example.com/post?id=1")),
+            ("https://example.com/post?utm_source=feed&gclid=1&mc_eid=2", Some("https://example.com/post")),
+            ("https://example.com/post?utm_campaign=x#comments", Some("https://example.com/post#comments")),
+            ("https://example.com/post?b=2&ref=home&a=1", Some("https://example.com/post?b=2&ref=home&a=1")),
+            ("https://example.com/post#utm_source=feed", Some("https://example.com/post#utm_source=feed")),
+            ("https://example.com/#section", Some("https://example.com/#section")),
+            ("https://example.com/post?", Some("https://example.com/post?")),
+            ("#section", None),
+            ("", None),
+        ];
+
+        for (url, expected) in cases {
+            assert_eq!(clean_url(url).as_deref(), expected, "{}", url);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use tauri::{AppHandle, Manager, Monitor, PhysicalPosition, Window, Wry};
+use tauri_plugin_window_state::{AppHandleExt, StateFlags};
+use crate::enums::window_placement::WindowPlacement;
+use crate::structs::preferences::Preferences;
+use crate::structs::screen_rect::ScreenRect;
+use crate::structs::tray_monitor::TrayMonitor;
+
+/// Moves the main window to the monitor picked by the `showWindowOn` preference before it is shown.
+/// The new position is saved right away so the window state plugin doesn't restore the old one later.
+pub fn move_to_preferred_monitor(app_handle: &AppHandle<Wry>, window: &Window<Wry>) -> tauri::Result<()> {
+    let target = match Preferences::load(app_handle).show_window_on {
+        WindowPlacement::Last => return Ok(()),
+        WindowPlacement::Primary => window.primary_monitor()?,
+        WindowPlacement::Cursor => match app_handle.state::<TrayMonitor>().last_click() {
+            Some((x, y)) => {
+                let mut monitors = window.available_monitors()?;
+                let rects: Vec<ScreenRect> = monitors.iter().map(screen_rect).collect();
+                monitor_at(&rects, x, y).map(|index| monitors.swap_remove(index))
+            }
+            None => None,
+        },
+    };
+    let (Some(target), Some(current)) = (target, window.current_monitor()?) else { return Ok(()) };
+
+    if screen_rect(&target) == screen_rect(&current) {
+        return Ok(());
+    }
+
+    let position = window.outer_position()?;
+    let size = window.outer_size()?;
+    let window_rect = ScreenRect { x: position.x, y: position.y, width: size.width, height: size.height };
+
+    let placed = center_on(window_rect, current.scale_factor(), screen_rect(&target), target.scale_factor());
+    window.set_position(PhysicalPosition { x: placed.x, y: placed.y })?;
+    app_handle.save_window_state(StateFlags::POSITION)
+}
+
+/// Centers a window on a monitor, its size following the scale of the monitor as the OS resizes
+/// windows moved to a monitor with another DPI. Never leaves a part of the window off the monitor.
+pub fn center_on(window: ScreenRect, window_scale: f64, monitor: ScreenRect, monitor_scale: f64) -> ScreenRect {
+    let ratio = monitor_scale / window_scale;
+    let width = ((window.width as f64 * ratio).round() as u32).min(monitor.width);
+    let height = ((window.height as f64 * ratio).round() as u32).min(monitor.height);
+
+    ScreenRect {
+        x: monitor.x + ((monitor.width - width) / 2) as i32,
+        y: monitor.y + ((monitor.height - height) / 2) as i32,
+        width,
+        height,
+    }
+}
+
+/// The index of the monitor containing a point, e.g. where the tray icon was clicked. None in the gaps of
+/// layouts whose monitors don't touch.
+pub fn monitor_at(monitors: &[ScreenRect], x: f64, y: f64) -> Option<usize> {
+    monitors.iter().position(|monitor| monitor.contains(x, y))
+}
+
+fn screen_rect(monitor: &Monitor) -> ScreenRect {
+    ScreenRect {
+        x: monitor.position().x,
+        y: monitor.position().y,
+        width: monitor.size().width,
+        height: monitor.size().height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> ScreenRect {
+        ScreenRect { x, y, width, height }
+    }
+
+    #[test]
+    fn centers_across_scales() {
+        let window = rect(100, 100, 1200, 800);
+        // As (window scale, monitor, monitor scale, placed)
+        let cases = [
+            (1.0, rect(1920, 0, 1920, 1080), 1.0, rect(2280, 140, 1200, 800)),
+            // A 4K monitor at 200% left of the primary one
+            (1.0, rect(-3840, 0, 3840, 2160), 2.0, rect(-3120, 280, 2400, 1600)),
+            (2.0, rect(0, 0, 1920, 1080), 1.0, rect(660, 340, 600, 400)),
+            (1.0, rect(0, -1440, 2560, 1440), 1.25, rect(530, -1220, 1500, 1000)),
+            (1.5, rect(0, 1080, 1366, 768), 1.0, rect(283, 1197, 800, 533)),
+            // Too large for a small monitor, capped to it
+            (1.0, rect(1920, 0, 1024, 600), 1.0, rect(1920, 0, 1024, 600)),
+            (1.0, rect(1920, 0, 1600, 900), 1.5, rect(1920, 0, 1600, 900)),
+        ];
+
+        for (window_scale, monitor, monitor_scale, placed) in cases {
+            let centered = center_on(window, window_scale, monitor, monitor_scale);
+            assert_eq!(centered, placed, "{:?} at {}", monitor, monitor_scale);
+            assert!(centered.x >= monitor.x && centered.y >= monitor.y);
+            assert!(centered.x + centered.width as i32 <= monitor.x + monitor.width as i32);
+            assert!(centered.y + centered.height as i32 <= monitor.y + monitor.height as i32);
+        }
+    }
+
+    #[test]
+    fn finds_the_clicked_monitor() {
+        // A 4K monitor left of the primary one, a laptop below it, and one above with a gap
+        let layout = [
+            rect(0, 0, 1920, 1080),
+            rect(-3840, -540, 3840, 2160),
+            rect(277, 1080, 1366, 768),
+            rect(0, -1500, 2560, 1440),
+        ];
+        let cases = [
+            ((960.0, 1060.0), Some(0)),
+            ((0.0, 0.0), Some(0)),
+            ((-0.5, 0.0), Some(1)),
+            ((-3840.0, 1619.0), Some(1)),
+            ((1919.9, 1079.9), Some(0)),
+            ((1920.0, 500.0), None),
+            ((1000.0, 1080.0), Some(2)),
+            ((100.0, 1200.0), None),
+            ((2000.0, -100.0), Some(3)),
+            // Between the primary and the one above
+            ((500.0, -30.0), None),
+        ];
+
+        for ((x, y), monitor) in cases {
+            assert_eq!(monitor_at(&layout, x, y), monitor, "{} {}", x, y);
+        }
+    }
+}
@@ -7,9 +7,13 @@ import ThemeManager from './components/ThemeManager';
 import { DispatchProvider } from './components/context/DispatchContext';
 import { ViewProvider } from './components/context/ViewContext';
 import FooterBar from './components/layout/FooterBar';
+import ListWindowPanel from './components/layout/ListWindowPanel';
 import WindowBar from './components/layout/WindowBar';
+import ActivityReporter from './components/utils/ActivityReporter';
+import ChangeManager from './components/utils/ChangeManager';
 import Clock from './components/utils/Clock';
 import LoadManager from './components/utils/LoadManager';
+import ListWindowService from './services/ListWindowService';
 import { initialViewState, viewReducer } from './state/view/ViewReducer';
 
 const queryClient = new QueryClient();
@@ -29,13 +33,26 @@ function App() {
       <QueryClientProvider client={queryClient}>
         <ViewProvider value={viewState}>
           <DispatchProvider value={dispatchers}>
-            <Clock />
-            <LoadManager />
+            <ChangeManager />
+            <ActivityReporter />
+            {/* Syncing and startup are left to the main window */}
+            {!ListWindowService.isListWindow && (
+              <>
+                <Clock />
+                <LoadManager />
+              </>
+            )}
             <ThemeManager>
               <div className="flex flex-col h-screen text-neutral-800 dark:text-zinc-400 overflow-hidden">
                 <WindowBar />
-                <CenterPanel />
-                <FooterBar />
+                {ListWindowService.isListWindow ? (
+                  <ListWindowPanel />
+                ) : (
+                  <>
+                    <CenterPanel />
+                    <FooterBar />
+                  </>
+                )}
               </div>
             </ThemeManager>
           </DispatchProvider>
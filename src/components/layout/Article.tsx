@@ -1,13 +1,16 @@
 import { useMutation, useQueryClient } from '@tanstack/react-query';
 import clsx from 'clsx';
-import { memo, useCallback } from 'react';
+import { MouseEvent, memo, useCallback } from 'react';
 import { useLocation, useNavigate } from 'react-router-dom';
 
+import useModal from '../../hooks/useModal';
 import usePreferences from '../../hooks/usePreferences';
+import useSchedulerState from '../../hooks/useSchedulerState';
 import useViewDispatch from '../../hooks/useViewDispatch';
 import ArticleService, { IArticle } from '../../services/ArticleService';
 import { SET_ACTIVE_ARTICLE } from '../../state/view/ViewActionType';
 import QueryKey from '../../utils/QueryKey';
+import { ExplainArticleState } from '../modal/ExplainArticleModal';
 
 interface ArticleProps extends IArticle {
   active: boolean;
@@ -24,8 +27,11 @@ function Article({
 }: ArticleProps) {
   const navigate = useNavigate();
   const location = useLocation();
-  const { showArticleThumbnails } = usePreferences();
+  const { showArticleThumbnails, timeline } = usePreferences();
+  const { policy } = useSchedulerState();
   const viewDispatch = useViewDispatch();
+  const { open: openExplanation } =
+    useModal<ExplainArticleState>('explainArticle');
 
   const queryClient = useQueryClient();
 
@@ -47,6 +53,18 @@ function Article({
     readMutation.mutate(identifier);
   }, [identifier, location.pathname, navigate, readMutation, viewDispatch]);
 
+  // Explained as the list shows it, the articles of its feed by the timeline sort
+  const explainArticle = useCallback(
+    (event: MouseEvent) => {
+      event.preventDefault();
+      openExplanation({
+        identifier,
+        contextFilter: { feedIdentifier, sort: timeline },
+      });
+    },
+    [feedIdentifier, identifier, openExplanation, timeline],
+  );
+
   return (
     <div
       className={clsx(
@@ -58,6 +76,7 @@ function Article({
           'bg-neutral-100 dark:bg-neutral-600 border-neutral-100 dark:border-neutral-600 pl-6',
       )}
       onClick={selectArticle}
+      onContextMenu={explainArticle}
     >
       <div className="flex items-center text-black dark:text-white transition-all duration-300 hover:pl-6 h-12">
         {/* Hacky way to preserve emojis */}
@@ -65,7 +84,7 @@ function Article({
       </div>
 
       <div className="flex items-center text-orange-400 text-xl flex gap-4 flex-nowrap">
-        {showArticleThumbnails && imageUrl !== null && (
+        {showArticleThumbnails && policy.loadImages && imageUrl !== null && (
           <img src={imageUrl} className="w-12 h-12 object-cover rounded-full" />
         )}
         {new Date(date).toLocaleDateString()}
@@ -1,6 +1,7 @@
-import { memo, useEffect, useRef } from 'react';
+import { memo, useEffect, useRef, useState } from 'react';
 
 import useActiveArticle from '../../hooks/useActiveArticle';
+import ArticleService, { IArticleExtra } from '../../services/ArticleService';
 import SanitizeHTML from '../utils/SanitizeHTML';
 
 const NoArticle = () => (
@@ -9,11 +10,30 @@ const NoArticle = () => (
   </div>
 );
 
+// The text when there is one, e.g. a job location, otherwise the attributes, e.g. a weather condition
+function extraLabel({ text, attributes }: IArticleExtra) {
+  return text ?? Object.values(attributes ?? {}).join(' ');
+}
+
 function ArticleFull() {
   const article = useActiveArticle();
 
   const containerRef = useRef<HTMLDivElement>(null);
 
+  const [chips, setChips] = useState<string[]>([]);
+  useEffect(() => {
+    setChips([]);
+    if (!article) return;
+
+    ArticleService.getArticleDetails(article.identifier)
+      .then(({ extras, displayedExtras }) =>
+        setChips(
+          displayedExtras.flatMap((key) => extras[key]?.map(extraLabel) ?? []),
+        ),
+      )
+      .catch((error) => console.error(error));
+  }, [article]);
+
   useEffect(() => {
     containerRef.current?.scrollTo({ top: 0, behavior: 'smooth' });
   }, [article]);
@@ -25,11 +45,28 @@ function ArticleFull() {
       ) : (
         <div className="w-full h-full p-8 text-black dark:text-white overflow-y-auto max-h-full">
           <div className="flex text-2xl justify-between items-center font-bold">
-            <div className="text-black dark:text-white">{article.title}</div>
+            <div
+              className="text-black dark:text-white"
+              title={article.originalTitle ?? undefined}
+            >
+              {article.title}
+            </div>
             <div className="text-orange-400 text-xl">
               {new Date(article.date).toLocaleDateString()}
             </div>
           </div>
+          {chips.length > 0 && (
+            <div className="flex flex-wrap gap-2 pt-3">
+              {chips.map((chip, index) => (
+                <div
+                  key={index}
+                  className="px-2 rounded-full bg-slate-300 dark:bg-zinc-700 text-sm"
+                >
+                  {chip}
+                </div>
+              ))}
+            </div>
+          )}
           <div className="text-justify py-3 overflow-y-auto">
             <SanitizeHTML html={article.content} />
           </div>
@@ -2,15 +2,17 @@ import { useSortable } from '@dnd-kit/sortable';
 import { CSS } from '@dnd-kit/utilities';
 import clsx from 'clsx';
 import { memo, useCallback, useMemo, MouseEvent } from 'react';
-import { FaEdit } from 'react-icons/fa';
+import { FaArchive, FaEdit, FaStar } from 'react-icons/fa';
 import { RiDraggable } from 'react-icons/ri';
 import { useNavigate } from 'react-router-dom';
 
 import useEditMode from '../../hooks/useEditMode';
 import useModal from '../../hooks/useModal';
+import usePreferences from '../../hooks/usePreferences';
 import useView from '../../hooks/useView';
 import useViewDispatch from '../../hooks/useViewDispatch';
 import { IFeed } from '../../services/FeedService';
+import ListWindowService from '../../services/ListWindowService';
 import { SET_ACTIVE_FEED } from '../../state/view/ViewActionType';
 import Button from '../form/Button';
 import { ModalFormContent } from '../modal/AddFeedModal';
@@ -20,7 +22,18 @@ import FeedIcon from './FeedIcon';
 interface FeedProps extends IFeed {}
 
 function Feed(props: FeedProps) {
-  const { identifier, displayName, url, interval, unread, rowid } = props;
+  const {
+    identifier,
+    displayName,
+    url,
+    interval,
+    priority,
+    archived,
+    unread,
+    rowid,
+  } = props;
+  const { hideArchivedUnread } = usePreferences();
+  const shownUnread = archived && hideArchivedUnread ? 0 : unread;
 
   const view = useView();
   const viewDispatch = useViewDispatch();
@@ -34,6 +47,19 @@ function Feed(props: FeedProps) {
   }, [identifier, navigate, viewDispatch]);
   const { isEditing } = useEditMode();
 
+  // A middle click lists its unread articles in a window of their own
+  const openUnreadList = useCallback(
+    (event: MouseEvent) => {
+      if (event.button !== 1) return;
+      event.preventDefault();
+      ListWindowService.openListWindow(
+        { feedIdentifier: identifier, readState: 'unread' },
+        `Unread in ${displayName}`,
+      ).catch(console.error);
+    },
+    [displayName, identifier],
+  );
+
   const { open } = useModal<ModalFormContent>('addFeed');
 
   const editFeed = useCallback(
@@ -72,8 +98,10 @@ function Feed(props: FeedProps) {
       className={clsx(
         'flex justify-between align-middle px-3 py-3 hover:bg-slate-300 hover:dark:bg-zinc-700 gap-4 cursor-pointer select-none',
         active && 'bg-slate-300 dark:bg-zinc-700 text-black dark:text-white',
+        archived && 'opacity-60',
       )}
       onClick={selectFeed}
+      onAuxClick={openUnreadList}
       style={style}
       ref={setNodeRef}
     >
@@ -92,10 +120,16 @@ function Feed(props: FeedProps) {
           <FeedIcon feed={props} />
 
           <div>{displayName}</div>
+
+          {archived && <FaArchive className="text-base" title="Archived" />}
+
+          {priority === 'high' && (
+            <FaStar className="text-base text-orange-400" title="High priority" />
+          )}
         </div>
-        {unread > 0 && (
+        {shownUnread > 0 && (
           <div className="flex justify-center items-center px-2 bg-orange-400 rounded-full text-black dark:text-white">
-            {unread}
+            {shownUnread}
           </div>
         )}
       </div>
@@ -4,6 +4,7 @@ import { FaAtom, FaQuestion, FaRss } from 'react-icons/fa';
 import { VscJson } from 'react-icons/vsc';
 
 import usePreferences from '../../hooks/usePreferences';
+import useSchedulerState from '../../hooks/useSchedulerState';
 import { IFeed } from '../../services/FeedService';
 
 interface FeedIconProps {
@@ -21,8 +22,9 @@ const ImageIcon = styled.img`
 function FeedIcon({ feed }: FeedIconProps) {
   const { type, imageUrl } = feed;
   const { showFeedIcons } = usePreferences();
+  const { policy } = useSchedulerState();
 
-  if (showFeedIcons && imageUrl !== null) {
+  if (showFeedIcons && policy.loadImages && imageUrl !== null) {
     return <ImageIcon src={imageUrl} />;
   }
 
@@ -0,0 +1,65 @@
+import { useMutation, useQuery } from '@tanstack/react-query';
+import { appWindow } from '@tauri-apps/api/window';
+import clsx from 'clsx';
+import { memo, useEffect } from 'react';
+import { FaSpinner } from 'react-icons/fa';
+
+import ArticleService from '../../services/ArticleService';
+import ListWindowService from '../../services/ListWindowService';
+import QueryKey from '../../utils/QueryKey';
+
+// The articles of the filter of this window, refreshed by the ChangeManager like the main list
+function ListWindowPanel() {
+  const { data: listWindow, error } = useQuery(
+    QueryKey.listWindow(),
+    ListWindowService.getListWindow,
+  );
+
+  const { data: page } = useQuery(
+    QueryKey.articles(appWindow.label),
+    () => ArticleService.getArticlePage(listWindow?.filter ?? {}),
+    { enabled: !!listWindow },
+  );
+
+  // Marked read through the backend, which tells the main window and the other lists
+  const readMutation = useMutation(ArticleService.readArticle);
+
+  useEffect(() => {
+    if (listWindow) {
+      appWindow.setTitle(`${listWindow.title} - Alduin`).catch(console.error);
+    }
+  }, [listWindow]);
+
+  if (error || listWindow === null) {
+    return (
+      <p className="p-3 text-red-500">
+        {error ? String(error) : 'This list was closed in another session'}
+      </p>
+    );
+  }
+  if (!listWindow || !page) {
+    return <FaSpinner className="m-3 animate-spin" />;
+  }
+
+  return (
+    <div className="flex flex-col h-full overflow-y-scroll bg-neutral-50 dark:bg-neutral-700">
+      <div className="p-3 font-semibold">{listWindow.title}</div>
+      {page.articles.map((article) => (
+        <div
+          key={article.identifier}
+          className={clsx(
+            'p-3 font-bold border-l-[3px] cursor-pointer hover:bg-neutral-100 hover:dark:bg-neutral-600',
+            article.read
+              ? 'border-neutral-50 dark:border-neutral-700'
+              : 'border-orange-400',
+          )}
+          onClick={() => readMutation.mutate(article.identifier)}
+        >
+          {article.title}
+        </div>
+      ))}
+    </div>
+  );
+}
+
+export default memo(ListWindowPanel);
@@ -1,3 +1,4 @@
+import { invoke } from '@tauri-apps/api';
 import { appWindow } from '@tauri-apps/api/window';
 import clsx from 'clsx';
 import { memo, useCallback } from 'react';
@@ -11,6 +12,7 @@ import { useLocation, useNavigate } from 'react-router-dom';
 
 import useView from '../../hooks/useView';
 import useViewDispatch from '../../hooks/useViewDispatch';
+import ListWindowService from '../../services/ListWindowService';
 import {
   SET_ACTIVE_ARTICLE,
   SET_ACTIVE_FEED,
@@ -71,7 +73,12 @@ function WindowBar() {
           className="mr-2"
         />
         <IconButton
-          onClick={() => appWindow.hide()}
+          onClick={() =>
+            // A list window closed by the user isn't reopened by the next session
+            ListWindowService.isListWindow
+              ? appWindow.close()
+              : invoke('close_main_window')
+          }
           Icon={FaWindowClose}
           className="mr-2"
         />
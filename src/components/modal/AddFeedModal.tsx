@@ -1,5 +1,6 @@
 import * as Form from '@radix-ui/react-form';
 import { useMutation, useQueryClient } from '@tanstack/react-query';
+import { listen } from '@tauri-apps/api/event';
 import clsx from 'clsx';
 import {
   FormEvent,
@@ -12,7 +13,10 @@ import {
 
 import useModal from '../../hooks/useModal';
 import useViewDispatch from '../../hooks/useViewDispatch';
-import FeedService from '../../services/FeedService';
+import FeedService, {
+  IAddFeedRequest,
+  IFeedCandidate,
+} from '../../services/FeedService';
 import {
   CLOSE_MODAL,
   SET_ACTIVE_ARTICLE,
@@ -44,23 +48,60 @@ export interface ModalFormContent {
 
 const modalIdentifier = 'addFeed';
 
+function candidateLabel({ title, url, existingFeed }: IFeedCandidate) {
+  return `${title || url}${existingFeed ? ' (subscribed)' : ''}`;
+}
+
 function AddFeedModal() {
   const viewDispatch = useViewDispatch();
 
-  const { isOpen, state, isStateEmpty } =
-    useModal<ModalFormContent>(modalIdentifier);
+  const {
+    open: openModal,
+    isOpen,
+    state,
+    isStateEmpty,
+  } = useModal<ModalFormContent>(modalIdentifier);
   const isEditing = useMemo(() => !isStateEmpty, [isStateEmpty]);
 
+  // Asked by the tray for a URL offering several feeds, the first one not subscribed to is picked
+  const [request, setRequest] = useState<IAddFeedRequest | null>(null);
+  const requested = useMemo(
+    () =>
+      request?.candidates.find(({ existingFeed }) => !existingFeed) ??
+      request?.candidates[0],
+    [request],
+  );
+
+  useEffect(() => {
+    function take() {
+      FeedService.takeAddFeedRequest()
+        .then((taken) => {
+          if (!taken) return;
+          setRequest(taken);
+          openModal();
+        })
+        .catch(console.error);
+    }
+
+    const unlisten = listen('tray:add-feed-requested', take);
+    // Also asked on load, the tray may have shown a window not listening yet
+    unlisten.then(take).catch(console.error);
+
+    return () => {
+      unlisten.then((stop) => stop()).catch(console.error);
+    };
+  }, [openModal]);
+
   const defaultForm = useMemo(
     () =>
       isEditing
         ? state
         : ({
-            displayName: '',
-            url: '',
+            displayName: requested?.title?.slice(0, 50) ?? '',
+            url: requested?.url ?? '',
             interval: Number.parseInt(intervalOptions[1].value, 10),
           } as ModalFormContent),
-    [isEditing, state],
+    [isEditing, requested, state],
   );
 
   const [form, setForm] = useState<ModalFormContent>(defaultForm);
@@ -92,6 +133,7 @@ function AddFeedModal() {
       type: CLOSE_MODAL,
       payload: { identifier: modalIdentifier },
     });
+    setRequest(null);
 
     setForm({
       identifier: '',
@@ -123,6 +165,29 @@ function AddFeedModal() {
   return (
     <Modal open={isOpen} identifier={modalIdentifier} title={title}>
       <Form.Root onSubmit={handleSubmit}>
+        {!isEditing && request && request.candidates.length > 1 && (
+          <Select
+            name="candidate"
+            label={`Feeds found at ${request.url}`}
+            placeholder="Select a feed"
+            options={request.candidates.map((candidate) => ({
+              label: candidateLabel(candidate),
+              value: candidate.url,
+            }))}
+            value={form.url}
+            onChange={(url) => {
+              const candidate = request.candidates.find(
+                (found) => found.url === url,
+              );
+              setForm({
+                ...form,
+                url,
+                displayName: candidate?.title?.slice(0, 50) ?? form.displayName,
+              });
+            }}
+          />
+        )}
+
         <Field
           type="text"
           required
@@ -0,0 +1,71 @@
+import { useQuery } from '@tanstack/react-query';
+import clsx from 'clsx';
+import { memo } from 'react';
+import { FaSpinner } from 'react-icons/fa';
+
+import useModal from '../../hooks/useModal';
+import ArticleService, {
+  IArticleFilter,
+} from '../../services/ArticleService';
+import QueryKey from '../../utils/QueryKey';
+
+import Modal from './Modal';
+
+const modalIdentifier = 'explainArticle';
+
+export interface ExplainArticleState {
+  identifier: string;
+  contextFilter?: IArticleFilter;
+}
+
+function ExplainArticleModal() {
+  const { isOpen, state, isStateEmpty } =
+    useModal<ExplainArticleState>(modalIdentifier);
+
+  const identifier = isStateEmpty ? null : state.identifier;
+  const { data: factors, error } = useQuery(
+    QueryKey.explanation(identifier, state?.contextFilter?.sort),
+    () => ArticleService.explainArticle(state.identifier, state.contextFilter),
+    // Rules and read states change, the explanation is made again each time
+    { enabled: isOpen && identifier !== null, cacheTime: 0 },
+  );
+
+  return (
+    <Modal
+      identifier={modalIdentifier}
+      open={isOpen}
+      title="Why am I seeing this?"
+    >
+      {error ? (
+        <p className="text-red-500">{String(error)}</p>
+      ) : !factors ? (
+        <FaSpinner className="animate-spin" />
+      ) : (
+        <ul className="flex flex-col gap-3 overflow-y-auto">
+          {factors.map((factor, index) => (
+            <li
+              key={index}
+              className={clsx(
+                'flex flex-col gap-1',
+                !factor.applies && 'text-gray-500 dark:text-gray-400',
+              )}
+            >
+              <span
+                className={clsx('font-semibold', factor.hides && 'text-red-500')}
+              >
+                {factor.label}
+              </span>
+              {factor.details.map((detail, detailIndex) => (
+                <span key={detailIndex} className="text-sm pl-3">
+                  {detail}
+                </span>
+              ))}
+            </li>
+          ))}
+        </ul>
+      )}
+    </Modal>
+  );
+}
+
+export default memo(ExplainArticleModal);
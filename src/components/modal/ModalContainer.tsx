@@ -2,6 +2,7 @@ import { memo } from 'react';
 
 import AboutModal from './AboutModal';
 import AddFeedModal from './AddFeedModal';
+import ExplainArticleModal from './ExplainArticleModal';
 import PreferenceModal from './PreferenceModal';
 
 function ModalContainer() {
@@ -10,6 +11,7 @@ function ModalContainer() {
       <AddFeedModal />
       <PreferenceModal />
       <AboutModal />
+      <ExplainArticleModal />
     </>
   );
 }
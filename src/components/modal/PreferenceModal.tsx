@@ -1,5 +1,5 @@
 import * as Form from '@radix-ui/react-form';
-import { useMutation, useQueryClient } from '@tanstack/react-query';
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
 import clsx from 'clsx';
 import {
   FormEvent,
@@ -13,17 +13,70 @@ import {
 import useModal from '../../hooks/useModal';
 import usePreferences from '../../hooks/usePreferences';
 import useViewDispatch from '../../hooks/useViewDispatch';
+import ArticleService from '../../services/ArticleService';
 import PreferencesService, {
+  AddressFamily,
+  CloseBehavior,
   IPreferences,
+  Timeline,
+  WindowPlacement,
 } from '../../services/PreferencesService';
 import { CLOSE_MODAL } from '../../state/view/ViewActionType';
 import QueryKey from '../../utils/QueryKey';
 import Button from '../form/Button';
+import Field from '../form/Field';
+import Select from '../form/Select';
 import Switch from '../form/Switch';
 
 import Modal from './Modal';
 
 const modalIdentifier = 'preference';
+const projectionDays = 90;
+
+const closeBehaviorOptions = [
+  { label: 'Hide to tray when available', value: 'auto' },
+  { label: 'Always hide to tray', value: 'tray' },
+  { label: 'Quit', value: 'quit' },
+];
+
+const timelineOptions = [
+  { label: 'Publication date', value: 'date' },
+  { label: 'First seen', value: 'first_seen' },
+];
+
+const windowPlacementOptions = [
+  { label: 'Where it was last', value: 'last' },
+  { label: 'Primary monitor', value: 'primary' },
+  { label: 'Monitor of the tray click', value: 'cursor' },
+];
+
+const addressFamilyOptions = [
+  { label: 'IPv6 or IPv4', value: 'auto' },
+  { label: 'IPv4 only', value: 'ipv4' },
+  { label: 'IPv6 only', value: 'ipv6' },
+];
+
+const backgroundSyncOptions = [
+  { label: '15 minutes', value: '15' },
+  { label: '30 minutes', value: '30' },
+  { label: 'Hour', value: '60' },
+  { label: '2 hours', value: '120' },
+];
+
+const retentionDaysOptions = [
+  { label: 'Forever', value: '0' },
+  { label: '30 days', value: '30' },
+  { label: '90 days', value: '90' },
+  { label: '6 months', value: '180' },
+  { label: 'A year', value: '365' },
+];
+
+const retentionMaxArticlesOptions = [
+  { label: 'No limit', value: '0' },
+  { label: '100 articles', value: '100' },
+  { label: '500 articles', value: '500' },
+  { label: '1000 articles', value: '1000' },
+];
 
 function PreferenceModal() {
   const preference = usePreferences();
@@ -55,6 +108,24 @@ function PreferenceModal() {
     onSuccess: () => queryClient.invalidateQueries(QueryKey.preferences()),
   });
 
+  const pruneMutation = useMutation(ArticleService.pruneArticles);
+  const pruneReport = pruneMutation.data;
+
+  // Of the retention being edited, before it's saved
+  const { data: projection } = useQuery(
+    QueryKey.storageProjection(form.retentionDays, form.retentionMaxArticles),
+    () =>
+      ArticleService.projectStorage(
+        {
+          retentionDays: form.retentionDays,
+          retentionMaxArticles: form.retentionMaxArticles,
+        },
+        projectionDays,
+      ),
+    { enabled: isOpen },
+  );
+  const projected = projection?.months[projection.months.length - 1];
+
   const handleSubmit = useCallback(
     (event: FormEvent) => {
       event.preventDefault();
@@ -89,6 +160,123 @@ function PreferenceModal() {
             setForm({ ...form, showArticleThumbnails })
           }
         />
+        <Switch
+          label="Low resource mode"
+          name="lowResourceMode"
+          value={form.lowResourceMode}
+          onChange={(lowResourceMode) => setForm({ ...form, lowResourceMode })}
+        />
+        <Switch
+          label="Sync less often on battery"
+          name="batterySaver"
+          value={form.batterySaver}
+          onChange={(batterySaver) => setForm({ ...form, batterySaver })}
+        />
+        <Switch
+          label="Keep local usage statistics"
+          name="localMetrics"
+          value={form.localMetrics}
+          onChange={(localMetrics) => setForm({ ...form, localMetrics })}
+        />
+        <Switch
+          label="Remove read articles from the reading queue"
+          name="dequeueRead"
+          value={form.dequeueRead}
+          onChange={(dequeueRead) => setForm({ ...form, dequeueRead })}
+        />
+        <Select
+          label="Closing the window"
+          name="closeBehavior"
+          placeholder="Hide to tray when available"
+          options={closeBehaviorOptions}
+          value={form.closeBehavior}
+          onChange={(closeBehavior) =>
+            setForm({ ...form, closeBehavior: closeBehavior as CloseBehavior })
+          }
+        />
+        <Select
+          label="Sort articles by"
+          name="timeline"
+          placeholder="Publication date"
+          options={timelineOptions}
+          value={form.timeline}
+          onChange={(timeline) =>
+            setForm({ ...form, timeline: timeline as Timeline })
+          }
+        />
+        <Select
+          label="Show the window on"
+          name="showWindowOn"
+          placeholder="Where it was last"
+          options={windowPlacementOptions}
+          value={form.showWindowOn}
+          onChange={(showWindowOn) =>
+            setForm({ ...form, showWindowOn: showWindowOn as WindowPlacement })
+          }
+        />
+        <Select
+          label="Fetch feeds over"
+          name="addressFamily"
+          placeholder="IPv6 or IPv4"
+          options={addressFamilyOptions}
+          value={form.addressFamily}
+          onChange={(addressFamily) =>
+            setForm({ ...form, addressFamily: addressFamily as AddressFamily })
+          }
+        />
+        <Select
+          label="Sync in the background every"
+          name="backgroundSyncMinutes"
+          placeholder="30 minutes"
+          options={backgroundSyncOptions}
+          value={String(form.backgroundSyncMinutes)}
+          onChange={(minutes) =>
+            setForm({ ...form, backgroundSyncMinutes: Number(minutes) })
+          }
+        />
+        <Select
+          label="Keep articles"
+          name="retentionDays"
+          placeholder="Forever"
+          options={retentionDaysOptions}
+          value={String(form.retentionDays)}
+          onChange={(days) => setForm({ ...form, retentionDays: Number(days) })}
+        />
+        <Select
+          label="Keep per feed at most"
+          name="retentionMaxArticles"
+          placeholder="No limit"
+          options={retentionMaxArticlesOptions}
+          value={String(form.retentionMaxArticles)}
+          onChange={(articles) =>
+            setForm({ ...form, retentionMaxArticles: Number(articles) })
+          }
+        />
+        {projection && projected && (
+          <p className="text-sm text-neutral-500">
+            Estimate in {projection.horizonDays} days:{' '}
+            {(projected.databaseBytes / 1024 / 1024).toFixed(0)} MB,{' '}
+            {projected.articles} articles, {projected.unread} unread
+            {projection.prunedArticles > 0 &&
+              `, ${projection.prunedArticles} articles deleted right away`}
+          </p>
+        )}
+        <Switch
+          label="Delete older articles after each sync"
+          name="pruneAfterSync"
+          value={form.pruneAfterSync}
+          onChange={(pruneAfterSync) => setForm({ ...form, pruneAfterSync })}
+        />
+        <Field
+          type="text"
+          label="Share read state through folder"
+          name="stateExchangeFolder"
+          messages={{}}
+          value={form.stateExchangeFolder}
+          onChange={(event) =>
+            setForm({ ...form, stateExchangeFolder: event.target.value })
+          }
+        />
         <div className="flex flex-row gap-8">
           <Switch
             label="Launch at startup"
@@ -118,7 +306,22 @@ function PreferenceModal() {
           <Form.Submit asChild>
             <Button variant="primary">Save</Button>
           </Form.Submit>
+          {/* Follows the saved preferences, not the ones being edited */}
+          <Button
+            type="button"
+            variant="danger"
+            disabled={pruneMutation.isLoading}
+            onClick={() => pruneMutation.mutate()}
+          >
+            Delete older articles now
+          </Button>
         </div>
+        {pruneReport && (
+          <p className="mt-2 text-sm">
+            {pruneReport.articlesDeleted} articles deleted,{' '}
+            {(pruneReport.bytesReclaimed / 1024 / 1024).toFixed(1)} MB freed
+          </p>
+        )}
       </Form.Root>
     </Modal>
   );
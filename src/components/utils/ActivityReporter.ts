@@ -0,0 +1,32 @@
+import { memo, useEffect } from 'react';
+
+import SchedulerService from '../../services/SchedulerService';
+
+// Well under the five minutes after which the backend deems the user idle
+const heartbeat = 1000 * 60;
+const inputEvents = ['pointerdown', 'pointermove', 'keydown', 'wheel'];
+
+function ActivityReporter() {
+  useEffect(() => {
+    let lastReported = 0;
+    const report = () => {
+      if (Date.now() - lastReported < heartbeat) return;
+      lastReported = Date.now();
+      SchedulerService.reportActivity().catch(console.error);
+    };
+
+    for (const event of inputEvents) {
+      window.addEventListener(event, report, { passive: true });
+    }
+
+    return () => {
+      for (const event of inputEvents) {
+        window.removeEventListener(event, report);
+      }
+    };
+  }, []);
+
+  return null;
+}
+
+export default memo(ActivityReporter);
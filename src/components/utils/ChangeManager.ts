@@ -0,0 +1,80 @@
+import { useQueryClient } from '@tanstack/react-query';
+import { listen } from '@tauri-apps/api/event';
+import { memo, useEffect } from 'react';
+
+import ChangeService, {
+  ChangeEntity,
+  IJournaled,
+  IMissedChanges,
+} from '../../services/ChangeService';
+import QueryKey from '../../utils/QueryKey';
+
+// The queries showing each kind of changed row, unread counts included
+const entityQueries: Record<ChangeEntity, string[][]> = {
+  article: [['articles'], ['article'], QueryKey.feeds()],
+  feed: [QueryKey.feeds()],
+  reading_queue: [['articles'], ['article']],
+};
+
+const journaledEvents: Record<string, ChangeEntity[]> = {
+  'article-updated': ['article'],
+  'articles-pruned': ['article', 'feed'],
+  'background-sync-finished': ['article', 'feed'],
+  'feed-updated': ['feed'],
+  'feeds-imported': ['feed'],
+  'reading-queue-changed': ['reading_queue'],
+  'scores-updated': ['article'],
+  'sync-progress': ['article', 'feed'],
+};
+
+function ChangeManager() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    async function refresh(entities: ChangeEntity[], watermark: number) {
+      const queries = entities.flatMap((entity) => entityQueries[entity]);
+      await Promise.all(
+        queries.map((queryKey) => queryClient.invalidateQueries(queryKey)),
+      );
+      await ChangeService.ackChanges(watermark);
+    }
+
+    async function applyMissed(missed: IMissedChanges) {
+      if (missed.fullRefresh) {
+        await queryClient.invalidateQueries();
+        await ChangeService.ackChanges(missed.watermark);
+        return;
+      }
+
+      const entities = new Set(missed.changes.map(({ entity }) => entity));
+      await refresh([...entities], missed.watermark);
+    }
+
+    const unlisteners = [
+      listen<IMissedChanges>('changes-missed', ({ payload }) =>
+        applyMissed(payload).catch(console.error),
+      ),
+      ...Object.entries(journaledEvents).map(([event, entities]) =>
+        listen<IJournaled>(event, ({ payload }) =>
+          refresh(entities, payload.watermark).catch(console.error),
+        ),
+      ),
+    ];
+
+    // Only listening from now on, what happened before (e.g. during a reload) is pulled
+    Promise.all(unlisteners)
+      .then(() => ChangeService.getMissedChanges())
+      .then(applyMissed)
+      .catch(console.error);
+
+    return () => {
+      unlisteners.forEach((unlisten) =>
+        unlisten.then((stop) => stop()).catch(console.error),
+      );
+    };
+  }, [queryClient]);
+
+  return null;
+}
+
+export default memo(ChangeManager);
@@ -1,18 +1,38 @@
-import { memo } from 'react';
+import { useQueryClient } from '@tanstack/react-query';
+import { listen } from '@tauri-apps/api/event';
+import { memo, useEffect } from 'react';
 import { useInterval } from 'react-use';
 
 import useFeeds from '../../hooks/useFeeds';
+import useSchedulerState from '../../hooks/useSchedulerState';
 import useSync from '../../hooks/useSync';
+import { ISchedulerState } from '../../services/SchedulerService';
+import QueryKey from '../../utils/QueryKey';
 
 const clockTick = 1000 * 30;
 
 function Clock() {
   const { feeds } = useFeeds();
   const { sync } = useSync();
+  const { policy } = useSchedulerState();
+
+  const queryClient = useQueryClient();
+  useEffect(() => {
+    // Sent when unplugged or plugged in
+    const unlisten = listen<ISchedulerState>(
+      'scheduler-policy-changed',
+      ({ payload }) => queryClient.setQueryData(QueryKey.scheduler(), payload),
+    );
+
+    return () => {
+      unlisten.then((stop) => stop()).catch(console.error);
+    };
+  }, [queryClient]);
 
   useInterval(() => {
     for (const { identifier, interval, lastUpdated, url } of feeds) {
-      if (Date.now() - (lastUpdated ?? 0) < interval * 60 * 1000) continue;
+      const due = interval * policy.intervalFactor * 60 * 1000;
+      if (Date.now() - (lastUpdated ?? 0) < due) continue;
       sync([{ identifier, url }]);
     }
   }, clockTick);
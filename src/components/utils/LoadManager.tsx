@@ -4,6 +4,7 @@ import { memo, useEffect } from 'react';
 import { useToggle } from 'react-use';
 
 import usePreferences from '../../hooks/usePreferences';
+import WebviewService from '../../services/WebviewService';
 
 import AutostartManager from './AutostartManager';
 
@@ -29,6 +30,10 @@ function LoadManager() {
     bootedRoutine().catch(console.error);
   }, [autostartLoaded, startMinimized]);
 
+  useEffect(() => {
+    WebviewService.probeCapabilities().catch(console.error);
+  }, []);
+
   return (
     <AutostartManager
       loaded={autostartLoaded}
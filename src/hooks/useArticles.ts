@@ -4,17 +4,19 @@ import ArticleService from '../services/ArticleService';
 import QueryKey from '../utils/QueryKey';
 
 import useActiveFeed from './useActiveFeed';
+import usePreferences from './usePreferences';
 
 export default function useArticles() {
   const activeFeed = useActiveFeed();
+  const { timeline } = usePreferences();
 
   const {
     data: articles,
     isLoading,
     isError,
   } = useQuery(
-    QueryKey.articles(activeFeed?.identifier ?? null),
-    () => ArticleService.getArticles(activeFeed?.identifier),
+    QueryKey.articles(activeFeed?.identifier ?? null, timeline),
+    () => ArticleService.getArticles(activeFeed?.identifier, timeline),
     { initialData: [] },
   );
 
@@ -0,0 +1,15 @@
+import { useQuery } from '@tanstack/react-query';
+
+import SchedulerService from '../services/SchedulerService';
+import QueryKey from '../utils/QueryKey';
+
+export default function useSchedulerState() {
+  const { data } = useQuery(
+    QueryKey.scheduler(),
+    SchedulerService.getSchedulerState,
+    {
+      initialData: SchedulerService.defaultSchedulerState,
+    },
+  );
+  return data;
+}
@@ -1,30 +1,173 @@
+import { invoke } from '@tauri-apps/api';
+
 import camelize from '../utils/camelize';
 
+import { Timeline } from './PreferencesService';
 import database from './database/sqlite';
 
 export interface IArticle {
   identifier: string;
   feedIdentifier: string;
   title: string;
+  // As the feed sent it, before its markup and prefixes were removed
+  originalTitle: string | null;
   content: string;
   date: Date;
   read: boolean;
   imageUrl: string;
+  firstSeen: number | null;
+  backfilled: boolean;
+  queued: boolean;
+  readAt: number | null;
+  contentUpdatedAt: number | null;
+  rereadRequested: boolean;
+}
+
+export interface IFeedArticleCount {
+  feedIdentifier: string;
+  count: number;
+}
+
+export interface ISmartCounts {
+  updatedSinceRead: number;
+  updatedSinceReadByFeed: IFeedArticleCount[];
+}
+
+export interface IPruneReport {
+  articlesDeleted: number;
+  bytesBefore: number;
+  bytesAfter: number;
+  bytesReclaimed: number;
+  vacuumed: boolean;
+}
+
+export interface IStoragePolicy {
+  retentionDays: number;
+  retentionMaxArticles: number;
+  minScore?: number;
+}
+
+export interface IStorageProjectionPoint {
+  month: number;
+  day: number;
+  articles: number;
+  unread: number;
+  databaseBytes: number;
+}
+
+// Estimates, extrapolated from the articles of the last measuredDays
+export interface IStorageProjection {
+  measuredDays: number;
+  horizonDays: number;
+  databaseBytes: number;
+  prunedArticles: number;
+  prunedUnread: number;
+  reclaimedBytes: number;
+  months: IStorageProjectionPoint[];
+}
+
+// Dates in milliseconds
+export interface IFeedPurgePreview {
+  feedIdentifier: string;
+  displayName: string;
+  articles: number;
+  unread: number;
+  oldestDate: number;
+  newestDate: number;
+  bytes: number;
+}
+
+export interface IPurgePreview {
+  articles: number;
+  unread: number;
+  bytes: number;
+  feeds: IFeedPurgePreview[];
 }
 
-async function getArticles(identifier: string | undefined) {
+export interface IArticleExtra {
+  text?: string;
+  attributes?: Record<string, string>;
+}
+
+export interface IArticleDetails {
+  extras: Record<string, IArticleExtra[]>;
+  displayedExtras: string[];
+}
+
+export type ExplanationKind =
+  | 'feed'
+  | 'filter'
+  | 'score'
+  | 'scoring_rule'
+  | 'dedup'
+  | 'read_state'
+  | 'post_processor'
+  | 'position';
+
+export interface IExplanationFactor {
+  kind: ExplanationKind;
+  label: string;
+  details: string[];
+  // False for the rules and filters checked that don't concern the article
+  applies: boolean;
+  hides: boolean;
+}
+
+export interface IArticleFilter {
+  feedIdentifier?: string;
+  sort?: 'date' | 'score' | 'first_seen';
+  minScore?: number;
+  readState?: 'unread' | 'read' | 'updated_since_read';
+}
+
+export interface INotifiedArticle {
+  articleIdentifier: string;
+  feedIdentifier: string;
+  feedName: string;
+  title: string;
+  // Notified for an update rather than as new
+  updated: boolean;
+  notifiedAt: number;
+  // False once the article was pruned or deleted
+  available: boolean;
+}
+
+export interface IArticlePage {
+  articles: IArticle[];
+  limit: number;
+  // Null on the last page
+  nextCursor: unknown | null;
+}
+
+export interface ITimelinePage {
+  articles: IArticle[];
+  limit: number;
+  // Null when the page reaches the oldest matching article
+  olderCursor: unknown | null;
+  // Null when the page reaches the newest matching article
+  newerCursor: unknown | null;
+}
+
+// Articles published this long before being first seen come from the archive of a newly added feed
+const BACKFILLED_AGE_MS = 7 * 24 * 60 * 60 * 1000;
+
+async function getArticles(
+  identifier: string | undefined,
+  timeline: Timeline = 'date',
+) {
   if (!identifier) return [] as IArticle[];
   console.log('getArticles');
 
   const db = await database();
   const snaked = await db.select<unknown[]>(
     `
-    SELECT *
+    SELECT *, COALESCE(first_seen - date > ?, 0) AS backfilled,
+      EXISTS (SELECT 1 FROM reading_queue WHERE article_identifier = articles.identifier) AS queued
     FROM articles
     WHERE feed_identifier = ?
-    ORDER BY date DESC
+    ORDER BY CASE ? WHEN 'first_seen' THEN COALESCE(first_seen, date) ELSE 0 END DESC, date DESC
     `,
-    [identifier],
+    [BACKFILLED_AGE_MS, identifier, timeline],
   );
 
   if (!snaked) return [] as IArticle[];
@@ -32,6 +175,31 @@ async function getArticles(identifier: string | undefined) {
   return camelize<IArticle[]>(snaked);
 }
 
+async function getArticlePage(filter: IArticleFilter) {
+  console.log('getArticlePage');
+
+  return invoke<IArticlePage>('get_articles', { filter });
+}
+
+// Newest article first whichever way the page was loaded. Without a cursor, starts from the newest
+// article when paging older and from the oldest when paging newer
+async function getTimeline(
+  filter: IArticleFilter,
+  cursor: unknown | null = null,
+  direction: 'older' | 'newer' = 'older',
+) {
+  console.log('getTimeline');
+
+  return invoke<ITimelinePage>('get_timeline', { filter, cursor, direction });
+}
+
+// The cursor of the older page starting with the article closest to the date, null for the top
+async function jumpToDate(filter: IArticleFilter, date: Date) {
+  console.log('jumpToDate');
+
+  return invoke<unknown | null>('jump_to_date', { filter, date: date.getTime() });
+}
+
 async function getArticle(identifier: string | null) {
   if (!identifier) return null as IArticle | null;
   console.log('getArticle');
@@ -48,24 +216,80 @@ async function getArticle(identifier: string | null) {
   return camelize<IArticle>(snaked[0]);
 }
 
+async function getArticleDetails(identifier: string) {
+  console.log('getArticleDetails');
+
+  return invoke<IArticleDetails>('get_article_details', {
+    articleId: identifier,
+  });
+}
+
+async function explainArticle(
+  identifier: string,
+  contextFilter?: IArticleFilter,
+) {
+  console.log('explainArticle');
+
+  return invoke<IExplanationFactor[]>('explain_article', {
+    articleId: identifier,
+    contextFilter,
+  });
+}
+
 async function readArticle(identifier: string) {
   console.log('readArticle');
 
-  const db = await database();
-  await db.execute(
-    `
-    UPDATE articles
-    SET read = 1
-    WHERE identifier = ?
-    `,
-    [identifier],
-  );
+  // Through the backend, which honours frozen writes and notifies the other windows
+  await invoke('set_article_read', { articleId: identifier, read: true });
+}
+
+async function getSmartCounts() {
+  console.log('getSmartCounts');
+
+  return invoke<ISmartCounts>('get_smart_counts');
+}
+
+async function pruneArticles() {
+  console.log('pruneArticles');
+
+  return invoke<IPruneReport>('prune_articles');
+}
+
+async function projectStorage(policy: IStoragePolicy, horizonDays: number) {
+  console.log('projectStorage');
+
+  return invoke<IStorageProjection>('project_storage', {
+    policy,
+    horizonDays,
+  });
+}
+
+async function previewPurge(policy: IStoragePolicy) {
+  console.log('previewPurge');
+
+  return invoke<IPurgePreview>('preview_purge', { policy });
+}
+
+async function getRecentNotifications(limit?: number) {
+  console.log('getRecentNotifications');
+
+  return invoke<INotifiedArticle[]>('get_recent_notifications', { limit });
 }
 
 const ArticleService = {
   getArticles,
+  getArticlePage,
+  getTimeline,
+  jumpToDate,
   getArticle,
+  getArticleDetails,
+  explainArticle,
   readArticle,
+  getSmartCounts,
+  pruneArticles,
+  projectStorage,
+  previewPurge,
+  getRecentNotifications,
 };
 
 export default ArticleService;
@@ -0,0 +1,36 @@
+import { invoke } from '@tauri-apps/api';
+
+export type ChangeEntity = 'article' | 'feed' | 'reading_queue';
+
+export interface IJournalChange {
+  entity: ChangeEntity;
+  identifier: string;
+}
+
+export interface IMissedChanges {
+  from: number;
+  watermark: number;
+  fullRefresh: boolean;
+  changes: IJournalChange[];
+}
+
+// Every event about changed data carries it
+export interface IJournaled {
+  watermark: number;
+}
+
+function getMissedChanges() {
+  return invoke<IMissedChanges>('get_missed_changes');
+}
+
+/** Tells the backend this window applied every change up to the watermark. */
+function ackChanges(watermark: number) {
+  return invoke('ack_changes', { watermark });
+}
+
+const ChangeService = {
+  getMissedChanges,
+  ackChanges,
+};
+
+export default ChangeService;
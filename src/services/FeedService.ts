@@ -1,7 +1,9 @@
 import { v4 as uuid } from '@lukeed/uuid';
+import { invoke } from '@tauri-apps/api';
 
 import camelize from '../utils/camelize';
 
+import { AddressFamily } from './PreferencesService';
 import database from './database/sqlite';
 
 export interface IInitialFeed {
@@ -11,10 +13,133 @@ export interface IInitialFeed {
   interval: number;
 }
 
+export type FeedPriority = 'high' | 'normal' | 'low';
+
+// 'browser' only for the feeds blocking the user agent of Alduin
+export type UserAgent = 'honest' | 'browser';
+
+export interface IOpmlFailure {
+  title: string;
+  xmlUrl: string;
+  error: string;
+}
+
+export interface IOpmlImport {
+  added: number;
+  skipped: number;
+  failed: number;
+  foldersAdded: number;
+  failures: IOpmlFailure[];
+}
+
+// Every feed with its folders and settings by default
+export interface IOpmlExportOptions {
+  folders?: boolean;
+  // Plain OPML for other readers, without the Alduin settings of the feeds
+  interchange?: boolean;
+  folderIdentifiers?: string[];
+  feedIdentifiers?: string[];
+}
+
+export interface IFeedOutline {
+  title: string;
+  xmlUrl: string;
+  htmlUrl: string | null;
+  folder: string[];
+}
+
+export interface IBookmark {
+  title: string;
+  url: string;
+  folder: string[];
+}
+
+export interface IBookmarksInspection {
+  folders: { path: string[]; bookmarks: number }[];
+  bookmarks: number;
+  ignored: number;
+}
+
+export interface IBookmarkImportOptions {
+  folders?: string[][];
+  maxRequests?: number;
+}
+
+export interface IDiscoveredFeed {
+  url: string;
+  title: string;
+  feedType: string | null;
+  siteUrl: string;
+}
+
+export interface IBookmarksDiscovery {
+  folders: { folder: string[]; feeds: IDiscoveredFeed[] }[];
+  withoutFeed: (IBookmark & { error: string | null })[];
+  alreadySubscribed: IBookmark[];
+  notChecked: IBookmark[];
+  requests: number;
+  cancelled: boolean;
+}
+
+export interface IFeedCandidate {
+  url: string;
+  title: string | null;
+  feedType: string | null;
+  existingFeed: string | null;
+}
+
+export interface IAddFeedRequest {
+  url: string;
+  candidates: IFeedCandidate[];
+}
+
+export interface ICertificateInfo {
+  subject: string;
+  issuer: string;
+  validFrom: number;
+  expiresAt: number;
+}
+
+export interface IFetchDebug {
+  url: string;
+  addressFamily: AddressFamily;
+  userAgent: UserAgent;
+  resolvedAddresses: string[];
+  triedAddresses: string[];
+  connectedAddress: string | null;
+  status: number | null;
+  elapsedMs: number;
+  error: string | null;
+  clientCertificate: ICertificateInfo | null;
+  caCertificate: ICertificateInfo | null;
+}
+
+export type OverrideKind =
+  | 'user_agent'
+  | 'address_family'
+  | 'headers'
+  | 'basic_auth'
+  | 'cookies'
+  | 'client_certificate'
+  | 'ca_certificate';
+
+export interface IFeedOverrides {
+  feedIdentifier: string;
+  displayName: string;
+  // Secret values masked
+  overrides: { kind: OverrideKind; detail: string }[];
+}
+
 export interface IFeed extends IInitialFeed {
   type: string | null;
   lastUpdated: number | null;
   imageUrl: string | null;
+  priority: FeedPriority;
+  archived: boolean;
+  rereadOnUpdate: boolean;
+  // null follows the preferences, 0 keeps every article
+  retentionDays: number | null;
+  retentionMaxArticles: number | null;
   unread: number;
   rowid: number;
 }
@@ -31,14 +156,24 @@ async function getFeeds() {
       feeds.interval as interval,
       feeds.last_updated as last_updated,
       feeds.image_url as image_url,
+      feeds.priority as priority,
+      feeds.archived as archived,
+      feeds.reread_on_update as reread_on_update,
+      feeds.retention_days as retention_days,
+      feeds.retention_max_articles as retention_max_articles,
       feeds.rowid as rowid,
-      COUNT(CASE WHEN articles.read = 0 THEN 1 END) as unread
+      COUNT(CASE WHEN articles.read = 0 OR articles.reread_requested = 1 THEN 1 END) as unread
     FROM feeds
     LEFT JOIN articles ON feeds.identifier = articles.feed_identifier
     GROUP BY feeds.identifier
     ORDER BY feeds.rowid ASC
   `);
-  return camelize<IFeed[]>(snaked);
+  // SQLite returns the flags as 0 or 1
+  return camelize<IFeed[]>(snaked).map((feed) => ({
+    ...feed,
+    archived: Boolean(feed.archived),
+    rereadOnUpdate: Boolean(feed.rereadOnUpdate),
+  }));
 }
 
 async function addFeed({ displayName, url, interval }: IInitialFeed) {
@@ -63,6 +198,11 @@ async function addFeed({ displayName, url, interval }: IInitialFeed) {
     type: null,
     lastUpdated: 0,
     imageUrl: null,
+    priority: 'normal',
+    archived: false,
+    rereadOnUpdate: false,
+    retentionDays: null,
+    retentionMaxArticles: null,
     unread: 0,
   });
 }
@@ -80,24 +220,29 @@ async function updateFeed({
     INSERT INTO feeds (identifier, display_name, url, interval)
     VALUES (?, ?, ?, ?)
     ON CONFLICT(identifier) DO UPDATE
-    SET display_name = ?, url = ?, interval = ?
+    SET display_name = ?, url = ?, interval = ?,
+      etag = CASE WHEN url = ? THEN etag END,
+      last_modified = CASE WHEN url = ? THEN last_modified END
     RETURNING *
     `,
-    [identifier, displayName, url, interval, displayName, url, interval],
+    [
+      identifier,
+      displayName,
+      url,
+      interval,
+      displayName,
+      url,
+      interval,
+      url,
+      url,
+    ],
   );
   return camelize<IFeed>(snaked[0]);
 }
 
-async function deleteFeed(identifier: string) {
+async function deleteFeed(identifier: string, force = false) {
   console.log('deleteFeed');
-  const db = await database();
-  await db.execute(
-    `
-    DELETE FROM feeds
-    WHERE identifier = ?
-    `,
-    [identifier],
-  );
+  await invoke('remove_feed', { feedId: identifier, force });
 }
 
 async function reorderFeed({ from, to }: { from: number; to: number }) {
@@ -117,12 +262,77 @@ async function reorderFeed({ from, to }: { from: number; to: number }) {
   );
 }
 
+async function importOpml(path: string) {
+  console.log('importOpml');
+  return invoke<IOpmlImport>('import_opml', { path });
+}
+
+// Resolves to the path written, under another name when taken unless overwrite
+async function exportOpml(
+  path?: string,
+  overwrite?: boolean,
+  options?: IOpmlExportOptions,
+) {
+  console.log('exportOpml');
+  return invoke<string>('export_opml', { path, overwrite, options });
+}
+
+async function importFeeds(feeds: IFeedOutline[]) {
+  console.log('importFeeds');
+  return invoke<IOpmlImport>('import_feeds', { feeds });
+}
+
+async function inspectBookmarksHtml(path: string) {
+  console.log('inspectBookmarksHtml');
+  return invoke<IBookmarksInspection>('inspect_bookmarks_html', { path });
+}
+
+async function importBookmarksHtml(
+  path: string,
+  options: IBookmarkImportOptions = {},
+) {
+  console.log('importBookmarksHtml');
+  return invoke<IBookmarksDiscovery>('import_bookmarks_html', {
+    path,
+    options,
+  });
+}
+
+async function cancelBookmarksDiscovery() {
+  console.log('cancelBookmarksDiscovery');
+  await invoke('cancel_bookmarks_discovery');
+}
+
+async function debugFetch(identifier: string) {
+  console.log('debugFetch');
+  return invoke<IFetchDebug>('debug_fetch', { feedId: identifier });
+}
+
+async function getOverrideAudit() {
+  console.log('getOverrideAudit');
+  return invoke<IFeedOverrides[]>('get_override_audit');
+}
+
+async function takeAddFeedRequest() {
+  console.log('takeAddFeedRequest');
+  return invoke<IAddFeedRequest | null>('take_add_feed_request');
+}
+
 const FeedService = {
   getFeeds,
   addFeed,
   updateFeed,
   deleteFeed,
   reorderFeed,
+  importOpml,
+  exportOpml,
+  importFeeds,
+  inspectBookmarksHtml,
+  importBookmarksHtml,
+  cancelBookmarksDiscovery,
+  debugFetch,
+  getOverrideAudit,
+  takeAddFeedRequest,
 };
 
 export default FeedService;
@@ -0,0 +1,34 @@
+import { invoke } from '@tauri-apps/api';
+import { appWindow } from '@tauri-apps/api/window';
+
+import { IArticleFilter } from './ArticleService';
+
+export interface IListWindow {
+  label: string;
+  title: string;
+  filter: IArticleFilter;
+}
+
+// Set by the backend when creating them, the other windows being the main one and the splashscreen
+const isListWindow = appWindow.label.startsWith('list-');
+
+function openListWindow(filter: IArticleFilter, title: string) {
+  console.log('openListWindow');
+
+  return invoke<string>('open_list_window', { filter, title });
+}
+
+/** The title and filter of this window, null when it isn't a list window. */
+function getListWindow() {
+  console.log('getListWindow');
+
+  return invoke<IListWindow | null>('get_list_window');
+}
+
+const ListWindowService = {
+  isListWindow,
+  openListWindow,
+  getListWindow,
+};
+
+export default ListWindowService;
@@ -1,11 +1,60 @@
+import { invoke } from '@tauri-apps/api';
+
 import { preferences as preferencesStore } from './database/store';
 
+export type CloseBehavior = 'auto' | 'tray' | 'quit';
+export type Timeline = 'date' | 'first_seen';
+export type WindowPlacement = 'last' | 'primary' | 'cursor';
+export type AddressFamily = 'auto' | 'ipv4' | 'ipv6';
+export type Language = 'auto' | 'en' | 'fr' | 'de' | 'es';
+export type TimestampStyle = 'relative' | 'date' | 'date_time' | 'time';
+
+// Of the preferences file, older ones being upgraded by the backend at startup
+const PREFERENCES_VERSION = 1;
+
+export interface IPreferenceReset {
+  key: string;
+  // As found in the file, in JSON
+  value: string;
+  reason: string;
+}
+
+export interface IPreferencesUpgrade {
+  fromVersion: number;
+  version: number;
+  // Saved by a newer version of Alduin, so never written back
+  readOnly: boolean;
+  resets: IPreferenceReset[];
+}
+
 export interface IPreferences {
   darkMode: boolean;
   showFeedIcons: boolean;
   showArticleThumbnails: boolean;
   autoStart: boolean;
   startMinimized: boolean;
+  fastPassMinutes: number;
+  backgroundSyncMinutes: number;
+  hideArchivedUnread: boolean;
+  lowResourceMode: boolean;
+  concurrentSyncs: number;
+  autostartDelaySeconds: number;
+  closeBehavior: CloseBehavior;
+  timeline: Timeline;
+  localMetrics: boolean;
+  dequeueRead: boolean;
+  showWindowOn: WindowPlacement;
+  batterySaver: boolean;
+  batteryIntervalFactor: number;
+  lowBatteryPercent: number;
+  stateExchangeFolder: string;
+  addressFamily: AddressFamily;
+  retentionDays: number;
+  retentionMaxArticles: number;
+  pruneAfterSync: boolean;
+  language: Language;
+  notifyNewArticles: boolean;
+  notificationWindowMinutes: number;
 }
 
 const defaultPreferences: IPreferences = {
@@ -14,6 +63,28 @@ const defaultPreferences: IPreferences = {
   showArticleThumbnails: false,
   autoStart: true,
   startMinimized: false,
+  fastPassMinutes: 5,
+  backgroundSyncMinutes: 30,
+  hideArchivedUnread: false,
+  lowResourceMode: false,
+  concurrentSyncs: 8,
+  autostartDelaySeconds: 0,
+  closeBehavior: 'auto',
+  timeline: 'date',
+  localMetrics: false,
+  dequeueRead: false,
+  showWindowOn: 'last',
+  batterySaver: true,
+  batteryIntervalFactor: 3,
+  lowBatteryPercent: 20,
+  stateExchangeFolder: '',
+  addressFamily: 'auto',
+  retentionDays: 0,
+  retentionMaxArticles: 0,
+  pruneAfterSync: false,
+  language: 'auto',
+  notifyNewArticles: false,
+  notificationWindowMinutes: 15,
 };
 
 async function getPreferences() {
@@ -28,17 +99,60 @@ async function getPreferences() {
 async function setPreferences(preferences: IPreferences) {
   console.log('setPreferences');
 
+  const upgrade = await getPreferencesUpgrade();
+  if (upgrade.readOnly) {
+    throw new Error(
+      `The preferences were saved by a newer version of Alduin (version ${upgrade.fromVersion}), they can't be changed`,
+    );
+  }
+
   await preferencesStore.clear();
   for (const [key, value] of Object.entries(preferences)) {
     await preferencesStore.set(key, value);
   }
+  await preferencesStore.set('schemaVersion', PREFERENCES_VERSION);
 
   await preferencesStore.save();
+
+  // The backend read it at launch, the new interval applies to the current wait
+  await invoke('set_sync_interval', {
+    minutes: preferences.backgroundSyncMinutes,
+  });
+
+  // Turning the metrics off also forgets what was recorded
+  if (!preferences.localMetrics) {
+    await invoke('clear_local_metrics');
+  }
+}
+
+// Also emitted at startup as startup:preferences-reset when preferences were reset or can't be saved
+async function getPreferencesUpgrade() {
+  return invoke<IPreferencesUpgrade>('get_preferences_upgrade');
+}
+
+async function getDefaultExportDirectory() {
+  console.log('getDefaultExportDirectory');
+  return invoke<string>('get_default_export_directory');
+}
+
+// Without a path, exports go back to the downloads folder
+async function setDefaultExportDirectory(path?: string) {
+  console.log('setDefaultExportDirectory');
+  return invoke<string>('set_default_export_directory', { path });
+}
+
+// In the language of the preferences, like the dates the backend writes
+async function formatTimestamp(timestamp: number, style: TimestampStyle) {
+  return invoke<string>('format_timestamp', { timestamp, style });
 }
 
 const PreferencesService = {
   getPreferences,
   setPreferences,
+  getPreferencesUpgrade,
+  getDefaultExportDirectory,
+  setDefaultExportDirectory,
+  formatTimestamp,
   defaultPreferences,
 };
 
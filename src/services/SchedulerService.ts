@@ -0,0 +1,61 @@
+import { invoke } from '@tauri-apps/api';
+
+export type PowerSource = 'ac' | 'battery' | 'unknown';
+
+export interface IPowerPolicy {
+  intervalFactor: number;
+  priorityPass: boolean;
+  loadImages: boolean;
+  idleMaintenance: boolean;
+  // Why the scheduler does less, null on AC
+  reason: string | null;
+}
+
+export type BackgroundJob = 'sync' | 'backfill' | 'prune' | 'maintenance';
+
+export interface IIdleStatus {
+  idle: boolean;
+  lastActivityAt: number;
+  // The idle-only jobs waiting for the user to be idle
+  deferred: BackgroundJob[];
+}
+
+export interface ISchedulerState {
+  power: {
+    source: PowerSource;
+    batteryPercent: number | null;
+  };
+  policy: IPowerPolicy;
+  fullPassRunning: boolean;
+  idle: IIdleStatus;
+}
+
+const defaultSchedulerState: ISchedulerState = {
+  power: { source: 'unknown', batteryPercent: null },
+  policy: {
+    intervalFactor: 1,
+    priorityPass: true,
+    loadImages: true,
+    idleMaintenance: true,
+    reason: null,
+  },
+  fullPassRunning: false,
+  idle: { idle: false, lastActivityAt: 0, deferred: [] },
+};
+
+function getSchedulerState() {
+  return invoke<ISchedulerState>('get_scheduler_state');
+}
+
+// Throttled by the caller, see ActivityReporter
+function reportActivity() {
+  return invoke<void>('report_activity');
+}
+
+const SchedulerService = {
+  getSchedulerState,
+  reportActivity,
+  defaultSchedulerState,
+};
+
+export default SchedulerService;
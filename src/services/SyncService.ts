@@ -5,13 +5,51 @@ interface SyncRequest {
   url: string;
 }
 
+export interface ISyncFailure {
+  identifier: string;
+  error: string;
+}
+
+export interface ISyncReport {
+  updated: string[];
+  notModified: string[];
+  failed: ISyncFailure[];
+  newArticles: number;
+}
+
+export interface IBackgroundSyncStatus {
+  minutes: number;
+  paused: boolean;
+  running: boolean;
+  // The background loop is started and didn't stop since
+  alive: boolean;
+}
+
 function sync(syncRequest: SyncRequest[]) {
   console.log('sync');
-  return invoke('sync_all', { syncRequest });
+  return invoke<ISyncReport>('sync_all', { syncRequest });
+}
+
+function getBackgroundSync() {
+  console.log('getBackgroundSync');
+  return invoke<IBackgroundSyncStatus>('get_background_sync');
+}
+
+function pauseBackgroundSync() {
+  console.log('pauseBackgroundSync');
+  return invoke<IBackgroundSyncStatus>('pause_background_sync');
+}
+
+function resumeBackgroundSync() {
+  console.log('resumeBackgroundSync');
+  return invoke<IBackgroundSyncStatus>('resume_background_sync');
 }
 
 const SyncService = {
   sync,
+  getBackgroundSync,
+  pauseBackgroundSync,
+  resumeBackgroundSync,
 };
 
 export default SyncService;
@@ -0,0 +1,63 @@
+import { invoke } from '@tauri-apps/api';
+
+// Decoded by every webview Alduin runs in
+const baseImageTypes = ['image/jpeg', 'image/png', 'image/gif', 'image/svg+xml'];
+
+// 1x1 samples of the formats older WebKitGTK and WebView2 builds may lack
+const imageSamples: Record<string, string> = {
+  'image/webp':
+    'data:image/webp;base64,UklGRiIAAABXRUJQVlA4IBYAAAAwAQCdASoBAAEADsD+JaQAA3AAAAAA',
+  'image/avif':
+    'data:image/avif;base64,AAAAIGZ0eXBhdmlmAAAAAGF2aWZtaWYxbWlhZk1BMUIAAADybWV0YQAAAAAAAAAoaGRscgAAAAAAAAAAcGljdAAAAAAAAAAAAAAAAGxpYmF2aWYAAAAADnBpdG0AAAAAAAEAAAAeaWxvYwAAAABEAAABAAEAAAABAAABGgAAAB0AAAAoaWluZgAAAAAAAQAAABppbmZlAgAAAAABAABhdjAxQ29sb3IAAAAAamlwcnAAAABLaXBjbwAAABRpc3BlAAAAAAAAAAIAAAACAAAAEHBpeGkAAAAAAwgICAAAAAxhdjFDgQ0MAAAAABNjb2xybmNseAACAAIAAYAAAAAXaXBtYQAAAAAAAAABAAEEAQKDBAAAACVtZGF0EgAKCBgANogQEAwgMg8f8D///8WfhwB8+ErK42A=',
+};
+
+// The type reported, and the one asked to the media element when it differs
+const audioTypes: [string, string][] = [
+  ['audio/mpeg', 'audio/mpeg'],
+  ['audio/mp4', 'audio/mp4; codecs="mp4a.40.2"'],
+  ['audio/aac', 'audio/aac'],
+  ['audio/ogg', 'audio/ogg; codecs="vorbis"'],
+  ['audio/opus', 'audio/ogg; codecs="opus"'],
+  ['audio/flac', 'audio/flac'],
+  ['audio/wav', 'audio/wav'],
+  ['audio/webm', 'audio/webm'],
+];
+
+function decodes(sample: string) {
+  return new Promise<boolean>((resolve) => {
+    const image = new Image();
+    image.onload = () => resolve(image.width > 0);
+    image.onerror = () => resolve(false);
+    image.src = sample;
+  });
+}
+
+async function probe() {
+  const decoded = await Promise.all(
+    Object.entries(imageSamples).map(async ([type, sample]) =>
+      (await decodes(sample)) ? [type] : [],
+    ),
+  );
+
+  const audio = new Audio();
+  return {
+    imageTypes: [...baseImageTypes, ...decoded.flat()],
+    audioTypes: audioTypes
+      .filter(([, asked]) => audio.canPlayType(asked) !== '')
+      .map(([type]) => type),
+  };
+}
+
+/** Reports what the webview can decode, once per app version. */
+async function probeCapabilities() {
+  if (!(await invoke<boolean>('needs_webview_probe'))) return;
+  console.log('probeCapabilities');
+
+  await invoke('report_webview_capabilities', { capabilities: await probe() });
+}
+
+const WebviewService = {
+  probeCapabilities,
+};
+
+export default WebviewService;
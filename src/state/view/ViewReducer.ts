@@ -25,7 +25,11 @@ import {
   SetActiveFeedAction,
 } from './actions/ModalActions';
 
-export type ModalName = 'addFeed' | 'preference' | 'about';
+export type ModalName =
+  | 'addFeed'
+  | 'preference'
+  | 'about'
+  | 'explainArticle';
 
 interface ModalState {
   open: boolean;
@@ -54,6 +58,10 @@ export const initialViewState: ViewState = {
       open: false,
       state: null,
     },
+    explainArticle: {
+      open: false,
+      state: null,
+    },
   },
   activeFeed: null,
   activeArticle: null,
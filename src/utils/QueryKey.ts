@@ -2,23 +2,46 @@ function feeds() {
   return ['feeds'];
 }
 
-function articles(identifier: string | null) {
-  return ['articles', identifier];
+function articles(identifier: string | null, timeline?: string) {
+  return timeline ? ['articles', identifier, timeline] : ['articles', identifier];
 }
 
 function article(identifier: string | null) {
   return ['article', identifier];
 }
 
+function explanation(identifier: string | null, sort?: string) {
+  return ['explanation', identifier, sort];
+}
+
+function listWindow() {
+  return ['listWindow'];
+}
+
+function storageProjection(
+  retentionDays: number,
+  retentionMaxArticles: number,
+) {
+  return ['storageProjection', retentionDays, retentionMaxArticles];
+}
+
 function preferences() {
   return ['preferences'];
 }
 
+function scheduler() {
+  return ['scheduler'];
+}
+
 const QueryKey = {
   feeds,
   articles,
   article,
+  explanation,
+  listWindow,
+  storageProjection,
   preferences,
+  scheduler,
 };
 
 export default QueryKey;